@@ -68,6 +68,8 @@ pub enum SinkType {
     Push,
     #[strum(serialize = "merge")]
     Merge,
+    #[strum(serialize = "snapshot_file")]
+    SnapshotFile,
 }
 
 #[derive(EnumString, IntoStaticStr, Clone, Display)]
@@ -109,6 +111,48 @@ pub enum ConflictPolicyEnum {
     Interrupt,
 }
 
+// how to handle shadow tables created by online ddl tools (gh-ost / pt-online-schema-change)
+#[derive(Clone, Debug, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum OnlineDdlPolicyEnum {
+    // replicate shadow tables as normal tables
+    #[default]
+    #[strum(serialize = "none")]
+    None,
+    // ignore shadow tables, apply alters of ghost tables to the original tables at cut-over
+    #[strum(serialize = "map")]
+    Map,
+    // ignore shadow tables
+    #[strum(serialize = "drop")]
+    Drop,
+}
+
+// how to coordinate ddl of sharded source tables routed into the same target table
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum ShardDdlModeEnum {
+    // replicate ddl of each shard as is
+    #[default]
+    #[strum(serialize = "none")]
+    None,
+    // apply the first ddl, swallow identical ddls of other shards
+    #[strum(serialize = "optimistic")]
+    Optimistic,
+    // apply a ddl after all shards executed it, hold shards executed it until then
+    #[strum(serialize = "pessimistic")]
+    Pessimistic,
+}
+
+// how user lua code of the processor is run
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum LuaModeEnum {
+    // the code is executed for each row with row variables as globals, in a new lua state for each batch
+    #[default]
+    #[strum(serialize = "legacy")]
+    Legacy,
+    // the code is executed once at start, process_row / process_ddl are called in a long-lived lua state
+    #[strum(serialize = "hooks")]
+    Hooks,
+}
+
 #[derive(Display, EnumString, IntoStaticStr, PartialEq)]
 pub enum MetaCenterType {
     #[strum(serialize = "basic")]
@@ -1,5 +1,5 @@
 use super::{
-    config_enums::{DbType, ExtractType},
+    config_enums::{DbType, ExtractType, OnlineDdlPolicyEnum},
     s3_config::S3Config,
 };
 
@@ -15,6 +15,12 @@ pub enum ExtractorConfig {
         schema: String,
     },
 
+    MongoStruct {
+        url: String,
+        app_name: String,
+        db: String,
+    },
+
     MysqlSnapshot {
         url: String,
         db: String,
@@ -37,6 +43,7 @@ pub enum ExtractorConfig {
         heartbeat_tb: String,
         start_time_utc: String,
         end_time_utc: String,
+        online_ddl_policy: OnlineDdlPolicyEnum,
     },
 
     MysqlCheck {
@@ -78,6 +85,8 @@ pub enum ExtractorConfig {
         app_name: String,
         db: String,
         tb: String,
+        parallel_size: usize,
+        batch_size: usize,
     },
 
     MongoCdc {
@@ -101,6 +110,7 @@ pub enum ExtractorConfig {
     RedisSnapshot {
         url: String,
         repl_port: u64,
+        is_cluster: bool,
     },
 
     RedisCdc {
@@ -112,6 +122,7 @@ pub enum ExtractorConfig {
         heartbeat_interval_secs: u64,
         heartbeat_key: String,
         now_db_id: i64,
+        is_cluster: bool,
     },
 
     RedisSnapshotAndCdc {
@@ -121,6 +132,7 @@ pub enum ExtractorConfig {
         keepalive_interval_secs: u64,
         heartbeat_interval_secs: u64,
         heartbeat_key: String,
+        is_cluster: bool,
     },
 
     RedisSnapshotFile {
@@ -137,6 +149,12 @@ pub enum ExtractorConfig {
         url: String,
     },
 
+    RedisCheck {
+        url: String,
+        check_log_dir: String,
+        batch_size: usize,
+    },
+
     Kafka {
         url: String,
         group: String,
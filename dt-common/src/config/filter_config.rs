@@ -9,5 +9,7 @@ pub struct FilterConfig {
     pub do_structures: String,
     pub do_ddls: String,
     pub ignore_cmds: String,
+    pub do_keys: String,
+    pub ignore_keys: String,
     pub where_conditions: String,
 }
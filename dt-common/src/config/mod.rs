@@ -5,13 +5,16 @@ pub mod extractor_config;
 pub mod filter_config;
 pub mod ini_loader;
 pub mod meta_center_config;
+pub mod mongo_mapping_config;
 pub mod monitor_config;
 pub mod parallelizer_config;
 pub mod pipeline_config;
 pub mod processor_config;
+pub mod redis_mapping_config;
 pub mod resumer_config;
 pub mod router_config;
 pub mod runtime_config;
 pub mod s3_config;
 pub mod sinker_config;
 pub mod task_config;
+pub mod transform_config;
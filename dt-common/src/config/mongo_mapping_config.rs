@@ -0,0 +1,9 @@
+/// flatten mongo documents into rows of relational / analytical targets,
+/// e.g. collection_map=json:[{"db":"app","tb":"users","col_map":{"profile.name":"name"},"child_tb_map":{"orders":"user_orders"}}]
+#[derive(Clone, Default)]
+pub struct MongoMappingConfig {
+    pub flatten_depth: usize,
+    pub separator: String,
+    pub sample_size: usize,
+    pub collection_map: String,
+}
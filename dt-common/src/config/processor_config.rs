@@ -1,5 +1,13 @@
+use super::config_enums::LuaModeEnum;
+
 #[derive(Clone)]
 pub struct ProcessorConfig {
     pub lua_code_file: String,
     pub lua_code: String,
+    pub lua_mode: LuaModeEnum,
+    pub lua_lookup_files: String,
+    pub wasm_module_file: String,
+    // fuel for each call into the wasm module
+    pub wasm_fuel: u64,
+    pub wasm_max_memory_mb: u64,
 }
@@ -0,0 +1,8 @@
+/// map redis keys to rows, each config is a list of `key_pattern:schema.tb`,
+/// e.g. hash_map="user:*":app.users,"order:*":app.orders
+#[derive(Clone, Default)]
+pub struct RedisMappingConfig {
+    pub string_map: String,
+    pub hash_map: String,
+    pub zset_map: String,
+}
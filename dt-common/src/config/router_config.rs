@@ -1,3 +1,5 @@
+use super::config_enums::ShardDdlModeEnum;
+
 #[derive(Clone)]
 pub enum RouterConfig {
     Rdb {
@@ -5,5 +7,11 @@ pub enum RouterConfig {
         tb_map: String,
         col_map: String,
         topic_map: String,
+        key_prefix_map: String,
+        tb_pattern_map: String,
+        shard_ddl_mode: ShardDdlModeEnum,
+        // pessimistic mode only, 0 means no limit
+        shard_ddl_max_held_items: usize,
+        shard_ddl_max_held_secs: u64,
     },
 }
@@ -11,18 +11,22 @@ pub enum SinkerConfig {
         url: String,
         batch_size: usize,
         replace: bool,
+        partial_upsert: bool,
     },
 
     Pg {
         url: String,
         batch_size: usize,
         replace: bool,
+        partial_upsert: bool,
+        ddl_conflict_policy: ConflictPolicyEnum,
     },
 
     Mongo {
         url: String,
         app_name: String,
         batch_size: usize,
+        ddl_conflict_policy: ConflictPolicyEnum,
     },
 
     MysqlCheck {
@@ -54,6 +58,12 @@ pub enum SinkerConfig {
         conflict_policy: ConflictPolicyEnum,
     },
 
+    MongoStruct {
+        url: String,
+        app_name: String,
+        conflict_policy: ConflictPolicyEnum,
+    },
+
     Kafka {
         url: String,
         batch_size: usize,
@@ -67,6 +77,21 @@ pub enum SinkerConfig {
         batch_size: usize,
         method: String,
         is_cluster: bool,
+        chunk_bytes: usize,
+        chunk_size: usize,
+    },
+
+    RedisCheck {
+        url: String,
+        batch_size: usize,
+        check_log_dir: String,
+        is_cluster: bool,
+        ttl_tolerance_secs: u64,
+    },
+
+    RedisSnapshotFile {
+        file_path: String,
+        rdb_version: u32,
     },
 
     RedisStatistic {
@@ -74,6 +99,10 @@ pub enum SinkerConfig {
         data_size_threshold: usize,
         freq_threshold: i64,
         statistic_log_dir: String,
+        key_prefix_delimiters: String,
+        key_prefix_depth: usize,
+        top_n: usize,
+        report_format: String,
     },
 
     StarRocks {
@@ -81,6 +110,8 @@ pub enum SinkerConfig {
         batch_size: usize,
         stream_load_url: String,
         hard_delete: bool,
+        auto_evolve: bool,
+        ddl_conflict_policy: ConflictPolicyEnum,
     },
 
     DorisStruct {
@@ -92,6 +123,8 @@ pub enum SinkerConfig {
         url: String,
         batch_size: usize,
         stream_load_url: String,
+        auto_evolve: bool,
+        ddl_conflict_policy: ConflictPolicyEnum,
     },
 
     StarRocksStruct {
@@ -102,6 +135,8 @@ pub enum SinkerConfig {
     ClickHouse {
         url: String,
         batch_size: usize,
+        auto_evolve: bool,
+        ddl_conflict_policy: ConflictPolicyEnum,
     },
 
     ClickhouseStruct {
@@ -9,22 +9,25 @@ use crate::error::Error;
 
 use super::{
     config_enums::{
-        ConflictPolicyEnum, DbType, ExtractType, MetaCenterType, ParallelType, PipelineType,
-        SinkType,
+        ConflictPolicyEnum, DbType, ExtractType, LuaModeEnum, MetaCenterType, OnlineDdlPolicyEnum,
+        ParallelType, PipelineType, ShardDdlModeEnum, SinkType,
     },
     data_marker_config::DataMarkerConfig,
     extractor_config::{BasicExtractorConfig, ExtractorConfig},
     filter_config::FilterConfig,
     ini_loader::IniLoader,
     meta_center_config::MetaCenterConfig,
+    mongo_mapping_config::MongoMappingConfig,
     parallelizer_config::ParallelizerConfig,
     pipeline_config::PipelineConfig,
     processor_config::ProcessorConfig,
+    redis_mapping_config::RedisMappingConfig,
     resumer_config::ResumerConfig,
     router_config::RouterConfig,
     runtime_config::RuntimeConfig,
     s3_config::S3Config,
     sinker_config::{BasicSinkerConfig, SinkerConfig},
+    transform_config::TransformConfig,
 };
 
 #[derive(Clone)]
@@ -42,6 +45,9 @@ pub struct TaskConfig {
     pub meta_center: Option<MetaCenterConfig>,
     pub data_marker: Option<DataMarkerConfig>,
     pub processor: Option<ProcessorConfig>,
+    pub redis_mapping: Option<RedisMappingConfig>,
+    pub mongo_mapping: Option<MongoMappingConfig>,
+    pub transform: Option<TransformConfig>,
 }
 
 // sections
@@ -55,6 +61,9 @@ const ROUTER: &str = "router";
 const RESUMER: &str = "resumer";
 const DATA_MARKER: &str = "data_marker";
 const PROCESSOR: &str = "processor";
+const REDIS_MAPPING: &str = "redis_mapping";
+const MONGO_MAPPING: &str = "mongo_mapping";
+const TRANSFORM: &str = "transform";
 const META_CENTER: &str = "metacenter";
 // keys
 const CHECK_LOG_DIR: &str = "check_log_dir";
@@ -70,7 +79,9 @@ const REVERSE: &str = "reverse";
 const REPL_PORT: &str = "repl_port";
 const PARALLEL_SIZE: &str = "parallel_size";
 const DDL_CONFLICT_POLICY: &str = "ddl_conflict_policy";
+const AUTO_EVOLVE: &str = "auto_evolve";
 const REPLACE: &str = "replace";
+const PARTIAL_UPSERT: &str = "partial_upsert";
 // default values
 const APE_DTS: &str = "APE_DTS";
 const ASTRISK: &str = "*";
@@ -84,6 +95,8 @@ impl TaskConfig {
         let resumer = Self::load_resumer_config(&loader, &runtime)?;
         let (extractor_basic, extractor) = Self::load_extractor_config(&loader, &pipeline)?;
         let (sinker_basic, sinker) = Self::load_sinker_config(&loader)?;
+        let mongo_mapping = Self::load_mongo_mapping_config(&loader, &sinker)?;
+        let processor = Self::load_processor_config(&loader, &extractor_basic)?;
         Ok(Self {
             extractor_basic,
             extractor,
@@ -93,10 +106,13 @@ impl TaskConfig {
             sinker,
             runtime,
             filter: Self::load_filter_config(&loader)?,
-            router: Self::load_router_config(&loader)?,
+            router: Self::load_router_config(&loader, &extractor)?,
             resumer,
             data_marker: Self::load_data_marker_config(&loader)?,
-            processor: Self::load_processor_config(&loader)?,
+            processor,
+            redis_mapping: Self::load_redis_mapping_config(&loader),
+            mongo_mapping,
+            transform: Self::load_transform_config(&loader),
             meta_center: Self::load_meta_center_config(&loader)?,
         })
     }
@@ -156,6 +172,11 @@ impl TaskConfig {
                     heartbeat_tb,
                     start_time_utc: loader.get_optional(EXTRACTOR, "start_time_utc"),
                     end_time_utc: loader.get_optional(EXTRACTOR, "end_time_utc"),
+                    online_ddl_policy: loader.get_with_default(
+                        EXTRACTOR,
+                        "online_ddl_policy",
+                        OnlineDdlPolicyEnum::None,
+                    ),
                 },
 
                 ExtractType::CheckLog => ExtractorConfig::MysqlCheck {
@@ -238,6 +259,8 @@ impl TaskConfig {
                         app_name,
                         db: String::new(),
                         tb: String::new(),
+                        parallel_size: loader.get_with_default(EXTRACTOR, PARALLEL_SIZE, 1),
+                        batch_size,
                     },
 
                     ExtractType::Cdc => ExtractorConfig::MongoCdc {
@@ -257,6 +280,12 @@ impl TaskConfig {
                         batch_size: loader.get_with_default(EXTRACTOR, BATCH_SIZE, 200),
                     },
 
+                    ExtractType::Struct => ExtractorConfig::MongoStruct {
+                        url,
+                        app_name,
+                        db: String::new(),
+                    },
+
                     _ => bail! { not_supported_err },
                 }
             }
@@ -264,7 +293,11 @@ impl TaskConfig {
             DbType::Redis => match extract_type {
                 ExtractType::Snapshot => {
                     let repl_port = loader.get_with_default(EXTRACTOR, REPL_PORT, 10008);
-                    ExtractorConfig::RedisSnapshot { url, repl_port }
+                    ExtractorConfig::RedisSnapshot {
+                        url,
+                        repl_port,
+                        is_cluster: loader.get_optional(EXTRACTOR, "is_cluster"),
+                    }
                 }
 
                 ExtractType::SnapshotFile => ExtractorConfig::RedisSnapshotFile {
@@ -273,7 +306,7 @@ impl TaskConfig {
 
                 ExtractType::Scan => ExtractorConfig::RedisScan {
                     url,
-                    statistic_type: loader.get_required(EXTRACTOR, "statistic_type"),
+                    statistic_type: loader.get_optional(EXTRACTOR, "statistic_type"),
                     scan_count: loader.get_with_default(EXTRACTOR, "scan_count", 1000),
                 },
 
@@ -288,6 +321,7 @@ impl TaskConfig {
                         heartbeat_interval_secs,
                         heartbeat_key: loader.get_optional(EXTRACTOR, "heartbeat_key"),
                         now_db_id: loader.get_optional(EXTRACTOR, "now_db_id"),
+                        is_cluster: loader.get_optional(EXTRACTOR, "is_cluster"),
                     }
                 }
 
@@ -300,11 +334,18 @@ impl TaskConfig {
                         keepalive_interval_secs,
                         heartbeat_interval_secs,
                         heartbeat_key: loader.get_optional(EXTRACTOR, "heartbeat_key"),
+                        is_cluster: loader.get_optional(EXTRACTOR, "is_cluster"),
                     }
                 }
 
                 ExtractType::Reshard => ExtractorConfig::RedisReshard { url },
 
+                ExtractType::CheckLog => ExtractorConfig::RedisCheck {
+                    url,
+                    check_log_dir: loader.get_required(EXTRACTOR, CHECK_LOG_DIR),
+                    batch_size: loader.get_with_default(EXTRACTOR, BATCH_SIZE, 200),
+                },
+
                 _ => bail! { not_supported_err },
             },
 
@@ -355,6 +396,7 @@ impl TaskConfig {
                     url,
                     batch_size,
                     replace: loader.get_with_default(SINKER, REPLACE, true),
+                    partial_upsert: loader.get_with_default(SINKER, PARTIAL_UPSERT, false),
                 },
 
                 SinkType::Check => SinkerConfig::MysqlCheck {
@@ -380,6 +422,12 @@ impl TaskConfig {
                     url,
                     batch_size,
                     replace: loader.get_with_default(SINKER, REPLACE, true),
+                    partial_upsert: loader.get_with_default(SINKER, PARTIAL_UPSERT, false),
+                    ddl_conflict_policy: loader.get_with_default(
+                        SINKER,
+                        DDL_CONFLICT_POLICY,
+                        ConflictPolicyEnum::Interrupt,
+                    ),
                 },
 
                 SinkType::Check => SinkerConfig::PgCheck {
@@ -408,6 +456,11 @@ impl TaskConfig {
                         url,
                         app_name,
                         batch_size,
+                        ddl_conflict_policy: loader.get_with_default(
+                            SINKER,
+                            DDL_CONFLICT_POLICY,
+                            ConflictPolicyEnum::Interrupt,
+                        ),
                     },
 
                     SinkType::Check => SinkerConfig::MongoCheck {
@@ -417,6 +470,12 @@ impl TaskConfig {
                         check_log_dir: loader.get_optional(SINKER, CHECK_LOG_DIR),
                     },
 
+                    SinkType::Struct => SinkerConfig::MongoStruct {
+                        url,
+                        app_name,
+                        conflict_policy,
+                    },
+
                     _ => bail! { not_supported_err },
                 }
             }
@@ -435,6 +494,21 @@ impl TaskConfig {
                     batch_size,
                     method: loader.get_optional(SINKER, "method"),
                     is_cluster: loader.get_optional(SINKER, "is_cluster"),
+                    chunk_bytes: loader.get_optional(SINKER, "chunk_bytes"),
+                    chunk_size: loader.get_with_default(SINKER, "chunk_size", 1000),
+                },
+
+                SinkType::Check => SinkerConfig::RedisCheck {
+                    url,
+                    batch_size,
+                    check_log_dir: loader.get_optional(SINKER, CHECK_LOG_DIR),
+                    is_cluster: loader.get_optional(SINKER, "is_cluster"),
+                    ttl_tolerance_secs: loader.get_with_default(SINKER, "ttl_tolerance_secs", 10),
+                },
+
+                SinkType::SnapshotFile => SinkerConfig::RedisSnapshotFile {
+                    file_path: loader.get_required(SINKER, "file_path"),
+                    rdb_version: loader.get_with_default(SINKER, "rdb_version", 10),
                 },
 
                 SinkType::Statistic => SinkerConfig::RedisStatistic {
@@ -442,6 +516,14 @@ impl TaskConfig {
                     data_size_threshold: loader.get_optional(SINKER, "data_size_threshold"),
                     freq_threshold: loader.get_optional(SINKER, "freq_threshold"),
                     statistic_log_dir: loader.get_optional(SINKER, "statistic_log_dir"),
+                    key_prefix_delimiters: loader.get_with_default(
+                        SINKER,
+                        "key_prefix_delimiters",
+                        ":".to_string(),
+                    ),
+                    key_prefix_depth: loader.get_with_default(SINKER, "key_prefix_depth", 1),
+                    top_n: loader.get_with_default(SINKER, "top_n", 100),
+                    report_format: loader.get_optional(SINKER, "report_format"),
                 },
 
                 _ => bail! { not_supported_err },
@@ -453,6 +535,12 @@ impl TaskConfig {
                     batch_size,
                     stream_load_url: loader.get_optional(SINKER, "stream_load_url"),
                     hard_delete: loader.get_optional(SINKER, "hard_delete"),
+                    auto_evolve: loader.get_optional(SINKER, AUTO_EVOLVE),
+                    ddl_conflict_policy: loader.get_with_default(
+                        SINKER,
+                        DDL_CONFLICT_POLICY,
+                        ConflictPolicyEnum::Interrupt,
+                    ),
                 },
 
                 SinkType::Struct => SinkerConfig::StarRocksStruct {
@@ -468,6 +556,12 @@ impl TaskConfig {
                     url,
                     batch_size,
                     stream_load_url: loader.get_optional(SINKER, "stream_load_url"),
+                    auto_evolve: loader.get_optional(SINKER, AUTO_EVOLVE),
+                    ddl_conflict_policy: loader.get_with_default(
+                        SINKER,
+                        DDL_CONFLICT_POLICY,
+                        ConflictPolicyEnum::Interrupt,
+                    ),
                 },
 
                 SinkType::Struct => SinkerConfig::DorisStruct {
@@ -479,7 +573,16 @@ impl TaskConfig {
             },
 
             DbType::ClickHouse => match sink_type {
-                SinkType::Write => SinkerConfig::ClickHouse { url, batch_size },
+                SinkType::Write => SinkerConfig::ClickHouse {
+                    url,
+                    batch_size,
+                    auto_evolve: loader.get_optional(SINKER, AUTO_EVOLVE),
+                    ddl_conflict_policy: loader.get_with_default(
+                        SINKER,
+                        DDL_CONFLICT_POLICY,
+                        ConflictPolicyEnum::Interrupt,
+                    ),
+                },
 
                 SinkType::Struct => SinkerConfig::ClickhouseStruct {
                     url,
@@ -600,16 +703,45 @@ impl TaskConfig {
             do_ddls: loader.get_optional(FILTER, "do_ddls"),
             do_structures: loader.get_with_default(FILTER, "do_structures", ASTRISK.to_string()),
             ignore_cmds: loader.get_optional(FILTER, "ignore_cmds"),
+            do_keys: loader.get_optional(FILTER, "do_keys"),
+            ignore_keys: loader.get_optional(FILTER, "ignore_keys"),
             where_conditions: loader.get_optional(FILTER, "where_conditions"),
         })
     }
 
-    fn load_router_config(loader: &IniLoader) -> anyhow::Result<RouterConfig> {
+    fn load_router_config(
+        loader: &IniLoader,
+        extractor: &ExtractorConfig,
+    ) -> anyhow::Result<RouterConfig> {
+        let shard_ddl_mode =
+            loader.get_with_default(ROUTER, "shard_ddl_mode", ShardDdlModeEnum::None);
+        // ddls of shards are only extracted by mysql / pg cdc
+        if shard_ddl_mode != ShardDdlModeEnum::None
+            && !matches!(
+                extractor,
+                ExtractorConfig::MysqlCdc { .. } | ExtractorConfig::PgCdc { .. }
+            )
+        {
+            bail! {Error::ConfigError(format!(
+                "shard_ddl_mode: {} is only supported for mysql / pg cdc tasks",
+                shard_ddl_mode
+            ))}
+        }
+
         Ok(RouterConfig::Rdb {
             schema_map: loader.get_optional(ROUTER, "db_map"),
             tb_map: loader.get_optional(ROUTER, "tb_map"),
             col_map: loader.get_optional(ROUTER, "col_map"),
             topic_map: loader.get_optional(ROUTER, "topic_map"),
+            key_prefix_map: loader.get_optional(ROUTER, "key_prefix_map"),
+            tb_pattern_map: loader.get_optional(ROUTER, "tb_pattern_map"),
+            shard_ddl_mode,
+            shard_ddl_max_held_items: loader.get_with_default(
+                ROUTER,
+                "shard_ddl_max_held_items",
+                100000,
+            ),
+            shard_ddl_max_held_secs: loader.get_with_default(ROUTER, "shard_ddl_max_held_secs", 0),
         })
     }
 
@@ -642,7 +774,10 @@ impl TaskConfig {
         }))
     }
 
-    fn load_processor_config(loader: &IniLoader) -> anyhow::Result<Option<ProcessorConfig>> {
+    fn load_processor_config(
+        loader: &IniLoader,
+        extractor_basic: &BasicExtractorConfig,
+    ) -> anyhow::Result<Option<ProcessorConfig>> {
         if !loader.ini.sections().contains(&PROCESSOR.to_string()) {
             return Ok(None);
         }
@@ -656,12 +791,83 @@ impl TaskConfig {
                 .expect("failed to read lua code file");
         }
 
+        // rows are passed to wasm modules as json of column values, mongo documents are not supported
+        let wasm_module_file: String = loader.get_optional(PROCESSOR, "wasm_module_file");
+        if !wasm_module_file.is_empty()
+            && !matches!(extractor_basic.db_type, DbType::Mysql | DbType::Pg)
+        {
+            bail! {Error::ConfigError(format!(
+                "wasm_module_file is only supported for mysql / pg sources, extractor db_type: {}",
+                extractor_basic.db_type
+            ))}
+        }
+
         Ok(Some(ProcessorConfig {
             lua_code_file,
             lua_code,
+            lua_mode: loader.get_with_default(PROCESSOR, "lua_mode", LuaModeEnum::Legacy),
+            lua_lookup_files: loader.get_optional(PROCESSOR, "lua_lookup_files"),
+            wasm_module_file,
+            wasm_fuel: loader.get_with_default(PROCESSOR, "wasm_fuel", 10_000_000_000),
+            wasm_max_memory_mb: loader.get_with_default(PROCESSOR, "wasm_max_memory_mb", 256),
         }))
     }
 
+    fn load_redis_mapping_config(loader: &IniLoader) -> Option<RedisMappingConfig> {
+        if !loader.ini.sections().contains(&REDIS_MAPPING.to_string()) {
+            return None;
+        }
+
+        Some(RedisMappingConfig {
+            string_map: loader.get_optional(REDIS_MAPPING, "string_map"),
+            hash_map: loader.get_optional(REDIS_MAPPING, "hash_map"),
+            zset_map: loader.get_optional(REDIS_MAPPING, "zset_map"),
+        })
+    }
+
+    fn load_mongo_mapping_config(
+        loader: &IniLoader,
+        sinker: &SinkerConfig,
+    ) -> anyhow::Result<Option<MongoMappingConfig>> {
+        if !loader.ini.sections().contains(&MONGO_MAPPING.to_string()) {
+            return Ok(None);
+        }
+
+        let config = MongoMappingConfig {
+            flatten_depth: loader.get_with_default(MONGO_MAPPING, "flatten_depth", 1),
+            separator: loader.get_with_default(MONGO_MAPPING, "separator", "_".to_string()),
+            sample_size: loader.get_with_default(MONGO_MAPPING, "sample_size", 1000),
+            collection_map: loader.get_optional(MONGO_MAPPING, "collection_map"),
+        };
+
+        // documents are written as full rows, fields removed in mongo should be set to NULL
+        if let SinkerConfig::Mysql {
+            partial_upsert: true,
+            ..
+        }
+        | SinkerConfig::Pg {
+            partial_upsert: true,
+            ..
+        } = sinker
+        {
+            bail! {Error::ConfigError(
+                "partial_upsert in [sinker] is not supported with [mongo_mapping]".into()
+            )}
+        }
+        Ok(Some(config))
+    }
+
+    fn load_transform_config(loader: &IniLoader) -> Option<TransformConfig> {
+        if !loader.ini.sections().contains(&TRANSFORM.to_string()) {
+            return None;
+        }
+
+        Some(TransformConfig {
+            rules: loader.get_optional(TRANSFORM, "rules"),
+            hmac_key: loader.get_optional(TRANSFORM, "hmac_key"),
+        })
+    }
+
     fn load_meta_center_config(loader: &IniLoader) -> anyhow::Result<Option<MetaCenterConfig>> {
         let mut config = MetaCenterConfig::Basic;
         let db_type: DbType = loader.get_required(EXTRACTOR, DB_TYPE);
@@ -0,0 +1,7 @@
+/// built-in column transforms applied to rows before sinking,
+/// e.g. rules=json:[{"db":"app","tb":"users","col":"email","type":"hmac"}]
+#[derive(Clone, Default)]
+pub struct TransformConfig {
+    pub rules: String,
+    pub hmac_key: String,
+}
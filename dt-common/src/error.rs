@@ -53,6 +53,9 @@ pub enum Error {
     #[error("struct error: {0}")]
     StructError(String),
 
+    #[error("ddl translate error: {0}")]
+    DdlTranslateError(String),
+
     #[error("avro encode error: {0}")]
     AvroEncodeError(#[from] apache_avro::Error),
 
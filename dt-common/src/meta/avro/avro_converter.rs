@@ -33,6 +33,7 @@ const DDL: &str = "ddl";
 const DB_TYPE: &str = "db_type";
 const DDL_TYPE: &str = "ddl_type";
 const QUERY: &str = "query";
+const ALTER_OPERATIONS: &str = "alter_operations";
 const SCHEMA: &str = "schema";
 const TB: &str = "tb";
 const FIELDS: &str = "fields";
@@ -165,6 +166,13 @@ impl AvroConverter {
             ColValue::String(ddl_data.ddl_type.to_string()),
         );
         col_values.insert(QUERY.into(), ColValue::String(ddl_data.query));
+        if !ddl_data.alter_operations.is_empty() {
+            // typed operations for consumers to evolve schemas without parsing the query
+            col_values.insert(
+                ALTER_OPERATIONS.into(),
+                ColValue::String(serde_json::to_string(&ddl_data.alter_operations)?),
+            );
+        }
 
         let (avro_values, _) = Self::col_values_to_avro(&Some(col_values));
         let extra = Value::Union(1, Box::new(avro_values));
@@ -210,12 +218,19 @@ impl AvroConverter {
             let db_type = get_extra_string(&extra, DB_TYPE);
             let ddl_type = get_extra_string(&extra, DDL_TYPE);
             let query = get_extra_string(&extra, QUERY);
+            let alter_operations = get_extra_string(&extra, ALTER_OPERATIONS);
+            let alter_operations = if alter_operations.is_empty() {
+                Vec::new()
+            } else {
+                serde_json::from_str(&alter_operations)?
+            };
             Ok(DtData::Ddl {
                 ddl_data: DdlData {
                     default_schema: schema,
                     query,
                     db_type: DbType::from_str(&db_type)?,
                     ddl_type: DdlType::from_str(&ddl_type)?,
+                    alter_operations,
                     ..Default::default()
                 },
             })
@@ -374,6 +389,7 @@ impl AvroConverter {
 mod tests {
 
     use super::*;
+    use crate::meta::ddl_meta::ddl_definition::{AlterTableOperation, ColumnDefinition};
 
     const STRING_COL: &str = "string_col";
     const LONG_COL: &str = "long_col";
@@ -433,6 +449,30 @@ mod tests {
             ..Default::default()
         };
         validate_ddl_data(&mut avro_converter, &ddl_data).await;
+
+        let ddl_data = DdlData {
+            default_schema: "db1".to_string(),
+            query: "alter table a add column f_1 int not null, drop column f_2;".to_string(),
+            ddl_type: DdlType::AlterTable,
+            db_type: DbType::Mysql,
+            alter_operations: vec![
+                AlterTableOperation::AddColumn {
+                    column: ColumnDefinition {
+                        column_name: "f_1".into(),
+                        column_type: "int".into(),
+                        data_type: "int".into(),
+                        nullable: Some(false),
+                        ..Default::default()
+                    },
+                    position: None,
+                },
+                AlterTableOperation::DropColumn {
+                    column_name: "f_2".into(),
+                },
+            ],
+            ..Default::default()
+        };
+        validate_ddl_data(&mut avro_converter, &ddl_data).await;
     }
 
     async fn validate_row_data(avro_converter: &mut AvroConverter, row_data: &RowData) {
@@ -0,0 +1,511 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use chrono::{Datelike, Duration, Months, NaiveDateTime, NaiveTime, Timelike};
+
+use super::condition_value::ConditionValue;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateUnit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DateUnit {
+    pub fn parse(str: &str) -> Option<Self> {
+        let unit = match str.to_lowercase().as_str() {
+            "year" | "years" => Self::Year,
+            "month" | "months" => Self::Month,
+            "week" | "weeks" => Self::Week,
+            "day" | "days" => Self::Day,
+            "hour" | "hours" => Self::Hour,
+            "minute" | "minutes" => Self::Minute,
+            "second" | "seconds" => Self::Second,
+            _ => return None,
+        };
+        Some(unit)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Column(String),
+    Literal(ConditionValue),
+    Now,
+    CurrentDate,
+    Date(Box<Operand>),
+    Extract(DateUnit, Box<Operand>),
+    // DATE_ADD / DATE_SUB / +- INTERVAL
+    DateAdd(Box<Operand>, i64, DateUnit),
+    Arithmetic(Box<Operand>, ArithOp, Box<Operand>),
+    Function(ScalarFunc, Vec<Operand>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScalarFunc {
+    Concat,
+    Coalesce,
+    Upper,
+    Lower,
+    Trim,
+    Length,
+    Substring,
+}
+
+impl ScalarFunc {
+    pub fn parse(str: &str) -> Option<Self> {
+        let func = match str.to_uppercase().as_str() {
+            "CONCAT" => Self::Concat,
+            "COALESCE" | "IFNULL" => Self::Coalesce,
+            "UPPER" => Self::Upper,
+            "LOWER" => Self::Lower,
+            "TRIM" => Self::Trim,
+            "LENGTH" | "CHAR_LENGTH" => Self::Length,
+            "SUBSTRING" | "SUBSTR" => Self::Substring,
+            _ => return None,
+        };
+        Some(func)
+    }
+
+    /// the (min, max) count of arguments
+    pub fn get_arg_count_range(&self) -> (usize, usize) {
+        match self {
+            Self::Concat | Self::Coalesce => (1, usize::MAX),
+            Self::Upper | Self::Lower | Self::Trim | Self::Length => (1, 1),
+            Self::Substring => (2, 3),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionExpr {
+    And(Box<ConditionExpr>, Box<ConditionExpr>),
+    Or(Box<ConditionExpr>, Box<ConditionExpr>),
+    Not(Box<ConditionExpr>),
+    Compare(Operand, CompareOp, Operand),
+    In {
+        operand: Operand,
+        list: Vec<Operand>,
+        negated: bool,
+    },
+    Like {
+        operand: Operand,
+        pattern: Operand,
+        negated: bool,
+    },
+    IsNull {
+        operand: Operand,
+        negated: bool,
+    },
+    Between {
+        operand: Operand,
+        low: Operand,
+        high: Operand,
+        negated: bool,
+    },
+    // a value used as a condition, e.g. WHERE is_active
+    Value(Operand),
+}
+
+pub trait ColumnResolver {
+    fn resolve(&self, col: &str) -> ConditionValue;
+}
+
+pub struct EvalContext {
+    pub now: NaiveDateTime,
+    // string comparisons of mysql are case insensitive by default collations
+    pub case_insensitive: bool,
+}
+
+impl ConditionExpr {
+    /// evaluated by three-valued logic, None means unknown (NULL), which is not matched
+    pub fn eval(&self, resolver: &dyn ColumnResolver, ctx: &EvalContext) -> Option<bool> {
+        match self {
+            Self::And(left, right) => match (left.eval(resolver, ctx), right.eval(resolver, ctx)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+
+            Self::Or(left, right) => match (left.eval(resolver, ctx), right.eval(resolver, ctx)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+
+            Self::Not(expr) => expr.eval(resolver, ctx).map(|v| !v),
+
+            Self::Compare(left, op, right) => {
+                let ordering = left
+                    .eval(resolver, ctx)
+                    .compare(&right.eval(resolver, ctx), ctx.case_insensitive)?;
+                let result = match op {
+                    CompareOp::Eq => ordering == Ordering::Equal,
+                    CompareOp::Ne => ordering != Ordering::Equal,
+                    CompareOp::Lt => ordering == Ordering::Less,
+                    CompareOp::Le => ordering != Ordering::Greater,
+                    CompareOp::Gt => ordering == Ordering::Greater,
+                    CompareOp::Ge => ordering != Ordering::Less,
+                };
+                Some(result)
+            }
+
+            Self::In {
+                operand,
+                list,
+                negated,
+            } => {
+                let value = operand.eval(resolver, ctx);
+                let mut result = Some(false);
+                for item in list {
+                    match value.compare(&item.eval(resolver, ctx), ctx.case_insensitive) {
+                        Some(Ordering::Equal) => {
+                            result = Some(true);
+                            break;
+                        }
+                        Some(_) => {}
+                        None => result = None,
+                    }
+                }
+                result.map(|v| v != *negated)
+            }
+
+            Self::Like {
+                operand,
+                pattern,
+                negated,
+            } => {
+                let value = operand.eval(resolver, ctx).to_like_string()?;
+                let pattern = pattern.eval(resolver, ctx).to_like_string()?;
+                let matched = if ctx.case_insensitive {
+                    Self::like_match(&value.to_lowercase(), &pattern.to_lowercase())
+                } else {
+                    Self::like_match(&value, &pattern)
+                };
+                Some(matched != *negated)
+            }
+
+            Self::IsNull { operand, negated } => {
+                Some(operand.eval(resolver, ctx).is_null() != *negated)
+            }
+
+            Self::Between {
+                operand,
+                low,
+                high,
+                negated,
+            } => {
+                let value = operand.eval(resolver, ctx);
+                let ge_low = value
+                    .compare(&low.eval(resolver, ctx), ctx.case_insensitive)
+                    .map(|i| i != Ordering::Less);
+                let le_high = value
+                    .compare(&high.eval(resolver, ctx), ctx.case_insensitive)
+                    .map(|i| i != Ordering::Greater);
+                let result = match (ge_low, le_high) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                };
+                result.map(|v| v != *negated)
+            }
+
+            Self::Value(operand) => operand.eval(resolver, ctx).to_bool(),
+        }
+    }
+
+    /// columns referenced by the condition
+    pub fn get_cols(&self) -> HashSet<String> {
+        let mut cols = HashSet::new();
+        self.collect_cols(&mut cols);
+        cols
+    }
+
+    fn collect_cols(&self, cols: &mut HashSet<String>) {
+        match self {
+            Self::And(left, right) | Self::Or(left, right) => {
+                left.collect_cols(cols);
+                right.collect_cols(cols);
+            }
+            Self::Not(expr) => expr.collect_cols(cols),
+            Self::Compare(left, _, right) => {
+                left.collect_cols(cols);
+                right.collect_cols(cols);
+            }
+            Self::In { operand, list, .. } => {
+                operand.collect_cols(cols);
+                list.iter().for_each(|i| i.collect_cols(cols));
+            }
+            Self::Like {
+                operand, pattern, ..
+            } => {
+                operand.collect_cols(cols);
+                pattern.collect_cols(cols);
+            }
+            Self::IsNull { operand, .. } | Self::Value(operand) => operand.collect_cols(cols),
+            Self::Between {
+                operand, low, high, ..
+            } => {
+                operand.collect_cols(cols);
+                low.collect_cols(cols);
+                high.collect_cols(cols);
+            }
+        }
+    }
+
+    /// % matches any chars, _ matches a single char, \ escapes the next char
+    fn like_match(value: &str, pattern: &str) -> bool {
+        let value: Vec<char> = value.chars().collect();
+        let pattern: Vec<char> = pattern.chars().collect();
+        // (value index, pattern index) to restart from when the last % should match one more char
+        let (mut v, mut p) = (0, 0);
+        let mut backtrack: Option<(usize, usize)> = None;
+        while v < value.len() {
+            match pattern.get(p) {
+                Some('%') => {
+                    p += 1;
+                    backtrack = Some((v, p));
+                    continue;
+                }
+                Some('_') => {
+                    v += 1;
+                    p += 1;
+                    continue;
+                }
+                Some('\\') if p + 1 < pattern.len() && pattern[p + 1] == value[v] => {
+                    v += 1;
+                    p += 2;
+                    continue;
+                }
+                // the escaped char is not matched
+                Some('\\') if p + 1 < pattern.len() => {}
+                Some(c) if *c == value[v] => {
+                    v += 1;
+                    p += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            match backtrack {
+                Some((last_v, last_p)) => {
+                    v = last_v + 1;
+                    p = last_p;
+                    backtrack = Some((v, p));
+                }
+                None => return false,
+            }
+        }
+        pattern[p..].iter().all(|c| *c == '%')
+    }
+}
+
+impl Operand {
+    pub fn eval(&self, resolver: &dyn ColumnResolver, ctx: &EvalContext) -> ConditionValue {
+        let value = match self {
+            Self::Column(col) => Some(resolver.resolve(col)),
+            Self::Literal(value) => Some(value.clone()),
+            Self::Now => Some(ConditionValue::DateTime(ctx.now)),
+            Self::CurrentDate => Some(ConditionValue::DateTime(
+                ctx.now.date().and_time(NaiveTime::MIN),
+            )),
+            Self::Date(operand) => operand
+                .eval(resolver, ctx)
+                .to_datetime()
+                .map(|v| ConditionValue::DateTime(v.date().and_time(NaiveTime::MIN))),
+            Self::Extract(unit, operand) => operand
+                .eval(resolver, ctx)
+                .to_datetime()
+                .map(|v| ConditionValue::Int(Self::extract(unit, &v) as i128)),
+            Self::DateAdd(operand, n, unit) => operand
+                .eval(resolver, ctx)
+                .to_datetime()
+                .and_then(|v| Self::date_add(&v, *n, unit))
+                .map(ConditionValue::DateTime),
+            Self::Arithmetic(left, op, right) => {
+                Self::arithmetic(&left.eval(resolver, ctx), op, &right.eval(resolver, ctx))
+            }
+            Self::Function(func, args) => {
+                let args: Vec<ConditionValue> =
+                    args.iter().map(|i| i.eval(resolver, ctx)).collect();
+                Self::call(func, &args)
+            }
+        };
+        value.unwrap_or(ConditionValue::Null)
+    }
+
+    /// columns referenced by the operand
+    pub fn get_cols(&self) -> HashSet<String> {
+        let mut cols = HashSet::new();
+        self.collect_cols(&mut cols);
+        cols
+    }
+
+    fn collect_cols(&self, cols: &mut HashSet<String>) {
+        match self {
+            Self::Column(col) => {
+                cols.insert(col.clone());
+            }
+            Self::Date(operand) | Self::Extract(_, operand) | Self::DateAdd(operand, _, _) => {
+                operand.collect_cols(cols)
+            }
+            Self::Arithmetic(left, _, right) => {
+                left.collect_cols(cols);
+                right.collect_cols(cols);
+            }
+            Self::Function(_, args) => args.iter().for_each(|i| i.collect_cols(cols)),
+            Self::Literal(_) | Self::Now | Self::CurrentDate => {}
+        }
+    }
+
+    /// None if either side is NULL or not a number, integers overflowed are computed as floats
+    fn arithmetic(
+        left: &ConditionValue,
+        op: &ArithOp,
+        right: &ConditionValue,
+    ) -> Option<ConditionValue> {
+        let (left, right) = (left.to_number()?, right.to_number()?);
+        if let (ConditionValue::Int(l), ConditionValue::Int(r)) = (&left, &right) {
+            let result = match op {
+                ArithOp::Add => l.checked_add(*r),
+                ArithOp::Sub => l.checked_sub(*r),
+                ArithOp::Mul => l.checked_mul(*r),
+                ArithOp::Div => None,
+            };
+            if let Some(v) = result {
+                return Some(ConditionValue::Int(v));
+            }
+        }
+
+        let (l, r) = (left.to_f64()?, right.to_f64()?);
+        let result = match op {
+            ArithOp::Add => l + r,
+            ArithOp::Sub => l - r,
+            ArithOp::Mul => l * r,
+            ArithOp::Div if r == 0.0 => return None,
+            ArithOp::Div => l / r,
+        };
+        Some(ConditionValue::Float(result))
+    }
+
+    // the result is NULL if any argument is NULL, except COALESCE
+    fn call(func: &ScalarFunc, args: &[ConditionValue]) -> Option<ConditionValue> {
+        let value = match func {
+            ScalarFunc::Coalesce => return args.iter().find(|i| !i.is_null()).cloned(),
+            ScalarFunc::Concat => {
+                let mut result = String::new();
+                for arg in args {
+                    result.push_str(&arg.to_like_string()?);
+                }
+                ConditionValue::String(result)
+            }
+            ScalarFunc::Upper => ConditionValue::String(args[0].to_like_string()?.to_uppercase()),
+            ScalarFunc::Lower => ConditionValue::String(args[0].to_like_string()?.to_lowercase()),
+            ScalarFunc::Trim => ConditionValue::String(args[0].to_like_string()?.trim().into()),
+            ScalarFunc::Length => {
+                ConditionValue::Int(args[0].to_like_string()?.chars().count() as i128)
+            }
+            ScalarFunc::Substring => {
+                // 1-based start, a negative start counts from the end
+                let chars: Vec<char> = args[0].to_like_string()?.chars().collect();
+                let start = match args[1].to_number()? {
+                    ConditionValue::Int(v) => v,
+                    _ => return None,
+                };
+                let start = match start {
+                    0 => return Some(ConditionValue::String(String::new())),
+                    v if v > 0 => usize::try_from(v - 1).unwrap_or(usize::MAX),
+                    v => chars
+                        .len()
+                        .saturating_sub(usize::try_from(v.unsigned_abs()).unwrap_or(usize::MAX)),
+                };
+                let len = match args.get(2) {
+                    Some(len) => match len.to_number()? {
+                        ConditionValue::Int(v) => usize::try_from(v.max(0)).unwrap_or(usize::MAX),
+                        _ => return None,
+                    },
+                    None => chars.len(),
+                };
+                ConditionValue::String(chars.iter().skip(start).take(len).collect())
+            }
+        };
+        Some(value)
+    }
+
+    fn extract(unit: &DateUnit, datetime: &NaiveDateTime) -> u32 {
+        match unit {
+            DateUnit::Year => datetime.year() as u32,
+            DateUnit::Month => datetime.month(),
+            DateUnit::Week => datetime.iso_week().week(),
+            DateUnit::Day => datetime.day(),
+            DateUnit::Hour => datetime.hour(),
+            DateUnit::Minute => datetime.minute(),
+            DateUnit::Second => datetime.second(),
+        }
+    }
+
+    fn date_add(datetime: &NaiveDateTime, n: i64, unit: &DateUnit) -> Option<NaiveDateTime> {
+        let months = match unit {
+            DateUnit::Year => n.checked_mul(12)?,
+            DateUnit::Month => n,
+            DateUnit::Week => return datetime.checked_add_signed(Duration::try_weeks(n)?),
+            DateUnit::Day => return datetime.checked_add_signed(Duration::try_days(n)?),
+            DateUnit::Hour => return datetime.checked_add_signed(Duration::try_hours(n)?),
+            DateUnit::Minute => return datetime.checked_add_signed(Duration::try_minutes(n)?),
+            DateUnit::Second => return datetime.checked_add_signed(Duration::try_seconds(n)?),
+        };
+
+        let abs_months = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+        if months >= 0 {
+            datetime.checked_add_months(abs_months)
+        } else {
+            datetime.checked_sub_months(abs_months)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConditionExpr;
+
+    #[test]
+    fn test_like_match() {
+        assert!(ConditionExpr::like_match("abc", "abc"));
+        assert!(ConditionExpr::like_match("abc", "a%"));
+        assert!(ConditionExpr::like_match("abc", "%c"));
+        assert!(ConditionExpr::like_match("abc", "%b%"));
+        assert!(ConditionExpr::like_match("abc", "a_c"));
+        assert!(ConditionExpr::like_match("abc", "%%"));
+        assert!(ConditionExpr::like_match("", "%"));
+        assert!(ConditionExpr::like_match("aXbXc", "a%b%c"));
+        assert!(ConditionExpr::like_match("a_c", "a\\_c"));
+        assert!(ConditionExpr::like_match("50%", "50\\%"));
+        assert!(!ConditionExpr::like_match("abc", "a\\_c"));
+        assert!(!ConditionExpr::like_match("500", "50\\%"));
+        assert!(!ConditionExpr::like_match("abc", "ab"));
+        assert!(!ConditionExpr::like_match("abc", "_"));
+        assert!(!ConditionExpr::like_match("", "_"));
+    }
+}
@@ -0,0 +1,727 @@
+use anyhow::bail;
+
+use crate::{config::config_enums::DbType, error::Error};
+
+use super::{
+    condition_expr::{ArithOp, CompareOp, ConditionExpr, DateUnit, Operand, ScalarFunc},
+    condition_value::ConditionValue,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    Number(String),
+    String(String),
+    Op(String),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+/// parses the sql-like conditions of where_conditions into expressions which can be
+/// evaluated over rows, only a subset of sql is supported:
+/// comparisons, [NOT] IN, [NOT] LIKE, IS [NOT] NULL, [NOT] BETWEEN, AND / OR / NOT,
+/// date functions: NOW(), CURRENT_TIMESTAMP, CURRENT_DATE, DATE(), YEAR(), MONTH(), DAY(),
+/// HOUR(), MINUTE(), SECOND(), EXTRACT(<unit> FROM <value>), DATE_ADD / DATE_SUB and +/- INTERVAL,
+/// arithmetic (+ - * /) and CONCAT(), COALESCE(), UPPER(), LOWER(), TRIM(), LENGTH(), SUBSTRING()
+pub struct ConditionParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl ConditionParser {
+    pub fn parse(condition: &str, db_type: &DbType) -> anyhow::Result<ConditionExpr> {
+        let mut parser = Self {
+            tokens: Self::tokenize(condition, db_type)?,
+            pos: 0,
+        };
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return parser.error("unexpected token");
+        }
+        Ok(expr)
+    }
+
+    /// parses a value expression, e.g. CONCAT(first_name, ' ', last_name) for computed columns
+    pub fn parse_operand_expr(expr: &str, db_type: &DbType) -> anyhow::Result<Operand> {
+        let mut parser = Self {
+            tokens: Self::tokenize(expr, db_type)?,
+            pos: 0,
+        };
+        let operand = parser.parse_operand()?;
+        if parser.pos < parser.tokens.len() {
+            return parser.error("unexpected token");
+        }
+        Ok(operand)
+    }
+
+    fn tokenize(condition: &str, db_type: &DbType) -> anyhow::Result<Vec<Token>> {
+        let chars: Vec<char> = condition.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                _ if c.is_whitespace() => i += 1,
+                '(' => {
+                    tokens.push(Token::LeftParen);
+                    i += 1;
+                }
+                ')' => {
+                    tokens.push(Token::RightParen);
+                    i += 1;
+                }
+                ',' => {
+                    tokens.push(Token::Comma);
+                    i += 1;
+                }
+
+                '\'' | '"' | '`' => {
+                    let (value, end) = Self::read_quoted(&chars, i)?;
+                    // "" quotes strings in mysql and identifiers in others
+                    let token = if c == '\'' || (c == '"' && *db_type == DbType::Mysql) {
+                        Token::String(value)
+                    } else {
+                        Token::QuotedIdent(value)
+                    };
+                    tokens.push(token);
+                    i = end;
+                }
+
+                '=' | '!' | '<' | '>' | '+' | '-' | '*' | '/' => {
+                    let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+                    let op = if ["!=", "<>", "<=", ">="].contains(&two.as_str()) {
+                        two
+                    } else if c == '!' {
+                        bail! {Error::ConfigError(format!(
+                            "invalid condition: [{}], unexpected char: !",
+                            condition
+                        ))}
+                    } else {
+                        c.to_string()
+                    };
+                    i += op.len();
+                    tokens.push(Token::Op(op));
+                }
+
+                _ if c.is_ascii_digit() || (c == '.' && Self::is_digit_at(&chars, i + 1)) => {
+                    let start = i;
+                    while i < chars.len()
+                        && (chars[i].is_ascii_digit()
+                            || chars[i] == '.'
+                            || ((chars[i] == 'e' || chars[i] == 'E')
+                                && (Self::is_digit_at(&chars, i + 1)
+                                    || (matches!(chars.get(i + 1), Some('+') | Some('-'))
+                                        && Self::is_digit_at(&chars, i + 2)))))
+                    {
+                        if chars[i] == 'e' || chars[i] == 'E' {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                    tokens.push(Token::Number(chars[start..i].iter().collect()));
+                }
+
+                _ if c.is_alphanumeric() || c == '_' || c == '$' => {
+                    let start = i;
+                    while i < chars.len()
+                        && (chars[i].is_alphanumeric()
+                            || chars[i] == '_'
+                            || chars[i] == '$'
+                            || chars[i] == '.')
+                    {
+                        i += 1;
+                    }
+                    tokens.push(Token::Ident(chars[start..i].iter().collect()));
+                }
+
+                _ => bail! {Error::ConfigError(format!(
+                    "invalid condition: [{}], unexpected char: {}",
+                    condition, c
+                ))},
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn is_digit_at(chars: &[char], i: usize) -> bool {
+        chars.get(i).is_some_and(|c| c.is_ascii_digit())
+    }
+
+    // a doubled quote char inside is an escaped quote char
+    fn read_quoted(chars: &[char], start: usize) -> anyhow::Result<(String, usize)> {
+        let quote = chars[start];
+        let mut value = String::new();
+        let mut i = start + 1;
+        while i < chars.len() {
+            if chars[i] == quote {
+                if chars.get(i + 1) == Some(&quote) {
+                    value.push(quote);
+                    i += 2;
+                    continue;
+                }
+                return Ok((value, i + 1));
+            }
+            value.push(chars[i]);
+            i += 1;
+        }
+        bail! {Error::ConfigError(format!(
+            "invalid condition, unclosed quote: {}",
+            chars.iter().collect::<String>()
+        ))}
+    }
+
+    fn parse_or(&mut self) -> anyhow::Result<ConditionExpr> {
+        let mut expr = self.parse_and()?;
+        while self.next_keyword_is("OR") {
+            self.pos += 1;
+            expr = ConditionExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> anyhow::Result<ConditionExpr> {
+        let mut expr = self.parse_not()?;
+        while self.next_keyword_is("AND") {
+            self.pos += 1;
+            expr = ConditionExpr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> anyhow::Result<ConditionExpr> {
+        if self.next_keyword_is("NOT") {
+            self.pos += 1;
+            return Ok(ConditionExpr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> anyhow::Result<ConditionExpr> {
+        // parentheses group conditions, parenthesized operands are not supported
+        if self.peek() == Some(&Token::LeftParen) {
+            self.pos += 1;
+            let expr = self.parse_or()?;
+            self.expect(Token::RightParen)?;
+            return Ok(expr);
+        }
+
+        let operand = self.parse_operand()?;
+        if let Some(Token::Op(op)) = self.peek() {
+            let op = match op.as_str() {
+                "=" => CompareOp::Eq,
+                "!=" | "<>" => CompareOp::Ne,
+                "<" => CompareOp::Lt,
+                "<=" => CompareOp::Le,
+                ">" => CompareOp::Gt,
+                ">=" => CompareOp::Ge,
+                _ => return self.error("unexpected operator"),
+            };
+            self.pos += 1;
+            let right = self.parse_operand()?;
+            return Ok(ConditionExpr::Compare(operand, op, right));
+        }
+
+        if self.next_keyword_is("IS") {
+            self.pos += 1;
+            let negated = self.consume_keyword("NOT");
+            if !self.consume_keyword("NULL") {
+                return self.error("expect NULL");
+            }
+            return Ok(ConditionExpr::IsNull { operand, negated });
+        }
+
+        let negated = self.consume_keyword("NOT");
+        if self.consume_keyword("IN") {
+            self.expect(Token::LeftParen)?;
+            let mut list = vec![self.parse_operand()?];
+            while self.peek() == Some(&Token::Comma) {
+                self.pos += 1;
+                list.push(self.parse_operand()?);
+            }
+            self.expect(Token::RightParen)?;
+            return Ok(ConditionExpr::In {
+                operand,
+                list,
+                negated,
+            });
+        }
+
+        if self.consume_keyword("LIKE") {
+            let pattern = self.parse_operand()?;
+            return Ok(ConditionExpr::Like {
+                operand,
+                pattern,
+                negated,
+            });
+        }
+
+        if self.consume_keyword("BETWEEN") {
+            let low = self.parse_operand()?;
+            if !self.consume_keyword("AND") {
+                return self.error("expect AND of BETWEEN");
+            }
+            let high = self.parse_operand()?;
+            return Ok(ConditionExpr::Between {
+                operand,
+                low,
+                high,
+                negated,
+            });
+        }
+
+        if negated {
+            return self.error("expect IN, LIKE or BETWEEN after NOT");
+        }
+        Ok(ConditionExpr::Value(operand))
+    }
+
+    fn parse_operand(&mut self) -> anyhow::Result<Operand> {
+        let mut operand = self.parse_term()?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let (sign, op) = match op.as_str() {
+                "+" => (1, ArithOp::Add),
+                "-" => (-1, ArithOp::Sub),
+                _ => break,
+            };
+            self.pos += 1;
+            // <datetime> +/- INTERVAL <n> <unit>
+            if self.consume_keyword("INTERVAL") {
+                let (n, unit) = self.parse_interval()?;
+                operand = Operand::DateAdd(Box::new(operand), sign * n, unit);
+                continue;
+            }
+            operand = Operand::Arithmetic(Box::new(operand), op, Box::new(self.parse_term()?));
+        }
+        Ok(operand)
+    }
+
+    fn parse_term(&mut self) -> anyhow::Result<Operand> {
+        let mut operand = self.parse_primary()?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = match op.as_str() {
+                "*" => ArithOp::Mul,
+                "/" => ArithOp::Div,
+                _ => break,
+            };
+            self.pos += 1;
+            operand = Operand::Arithmetic(Box::new(operand), op, Box::new(self.parse_primary()?));
+        }
+        Ok(operand)
+    }
+
+    fn parse_primary(&mut self) -> anyhow::Result<Operand> {
+        let token = match self.next() {
+            Some(token) => token,
+            None => return self.error("unexpected end"),
+        };
+
+        match token {
+            Token::Number(v) => self.parse_number(&v, 1),
+            Token::Op(op) if op == "-" => match self.next() {
+                Some(Token::Number(v)) => self.parse_number(&v, -1),
+                _ => self.error("expect number after -"),
+            },
+            Token::String(v) => Ok(Operand::Literal(ConditionValue::String(v))),
+            Token::QuotedIdent(v) => Ok(Operand::Column(v)),
+            Token::Ident(v) => self.parse_ident(v),
+            Token::LeftParen => {
+                let operand = self.parse_operand()?;
+                self.expect(Token::RightParen)?;
+                Ok(operand)
+            }
+            _ => {
+                self.pos -= 1;
+                self.error("unexpected token")
+            }
+        }
+    }
+
+    fn parse_ident(&mut self, ident: String) -> anyhow::Result<Operand> {
+        let upper = ident.to_uppercase();
+        match upper.as_str() {
+            "NULL" => return Ok(Operand::Literal(ConditionValue::Null)),
+            "TRUE" => return Ok(Operand::Literal(ConditionValue::Bool(true))),
+            "FALSE" => return Ok(Operand::Literal(ConditionValue::Bool(false))),
+            "CURRENT_TIMESTAMP" | "CURRENT_DATE" | "LOCALTIMESTAMP" => {
+                // parentheses are optional
+                if self.peek() == Some(&Token::LeftParen) {
+                    self.pos += 1;
+                    self.expect(Token::RightParen)?;
+                }
+                if upper == "CURRENT_DATE" {
+                    return Ok(Operand::CurrentDate);
+                }
+                return Ok(Operand::Now);
+            }
+            _ => {}
+        }
+
+        if self.peek() != Some(&Token::LeftParen) {
+            return Ok(Operand::Column(ident));
+        }
+        self.pos += 1;
+
+        let operand = match upper.as_str() {
+            "NOW" => Operand::Now,
+            "CURDATE" => Operand::CurrentDate,
+            "DATE" => Operand::Date(Box::new(self.parse_operand()?)),
+            "YEAR" | "MONTH" | "DAY" | "HOUR" | "MINUTE" | "SECOND" => {
+                let unit = DateUnit::parse(&upper).unwrap();
+                Operand::Extract(unit, Box::new(self.parse_operand()?))
+            }
+            "EXTRACT" => {
+                let unit = self.parse_unit()?;
+                if !self.consume_keyword("FROM") {
+                    return self.error("expect FROM of EXTRACT");
+                }
+                Operand::Extract(unit, Box::new(self.parse_operand()?))
+            }
+            "DATE_ADD" | "DATE_SUB" => {
+                let operand = self.parse_operand()?;
+                self.expect(Token::Comma)?;
+                if !self.consume_keyword("INTERVAL") {
+                    return self.error("expect INTERVAL");
+                }
+                let (n, unit) = self.parse_interval()?;
+                let n = if upper == "DATE_SUB" { -n } else { n };
+                Operand::DateAdd(Box::new(operand), n, unit)
+            }
+            _ => match ScalarFunc::parse(&upper) {
+                Some(func) => return self.parse_function_args(func),
+                None => {
+                    self.pos -= 2;
+                    return self.error("unsupported function");
+                }
+            },
+        };
+        self.expect(Token::RightParen)?;
+        Ok(operand)
+    }
+
+    fn parse_function_args(&mut self, func: ScalarFunc) -> anyhow::Result<Operand> {
+        let mut args = vec![self.parse_operand()?];
+        while self.peek() == Some(&Token::Comma) {
+            self.pos += 1;
+            args.push(self.parse_operand()?);
+        }
+        self.expect(Token::RightParen)?;
+
+        let (min, max) = func.get_arg_count_range();
+        if args.len() < min || args.len() > max {
+            return self.error(&format!("wrong argument count of {:?}", func));
+        }
+        Ok(Operand::Function(func, args))
+    }
+
+    // mysql: INTERVAL 7 DAY, postgres: INTERVAL '7 days' / INTERVAL '7' DAY
+    fn parse_interval(&mut self) -> anyhow::Result<(i64, DateUnit)> {
+        let (n, unit) = match self.next() {
+            Some(Token::Number(v)) => (v, None),
+            Some(Token::Op(op)) if op == "-" => match self.next() {
+                Some(Token::Number(v)) => (format!("-{}", v), None),
+                _ => return self.error("expect interval value"),
+            },
+            Some(Token::String(v)) => {
+                let mut tokens = v.split_whitespace();
+                let n = tokens.next().unwrap_or_default().to_string();
+                let unit = tokens.next().and_then(DateUnit::parse);
+                (n, unit)
+            }
+            _ => return self.error("expect interval value"),
+        };
+
+        let n: i64 = match n.parse() {
+            Ok(n) => n,
+            Err(_) => return self.error("interval value should be an integer"),
+        };
+        let unit = match unit {
+            Some(unit) => unit,
+            None => self.parse_unit()?,
+        };
+        Ok((n, unit))
+    }
+
+    fn parse_unit(&mut self) -> anyhow::Result<DateUnit> {
+        if let Some(Token::Ident(v)) = self.peek() {
+            if let Some(unit) = DateUnit::parse(v) {
+                self.pos += 1;
+                return Ok(unit);
+            }
+        }
+        self.error("expect date unit")
+    }
+
+    fn parse_number(&self, str: &str, sign: i128) -> anyhow::Result<Operand> {
+        match ConditionValue::parse_number(str) {
+            Some(ConditionValue::Int(v)) => Ok(Operand::Literal(ConditionValue::Int(sign * v))),
+            Some(ConditionValue::Float(v)) => {
+                Ok(Operand::Literal(ConditionValue::Float(sign as f64 * v)))
+            }
+            _ => self.error("invalid number"),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn next_keyword_is(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(v)) if v.eq_ignore_ascii_case(keyword))
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if self.next_keyword_is(keyword) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, token: Token) -> anyhow::Result<()> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            return Ok(());
+        }
+        self.error(&format!("expect {:?}", token))
+    }
+
+    fn error<T>(&self, msg: &str) -> anyhow::Result<T> {
+        bail! {Error::ConfigError(format!(
+            "invalid condition, {} at token {}: {:?}",
+            msg,
+            self.pos,
+            self.tokens.get(self.pos)
+        ))}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDateTime;
+
+    use super::ConditionParser;
+    use crate::{
+        config::config_enums::DbType,
+        meta::condition::{
+            condition_expr::{ColumnResolver, EvalContext},
+            condition_value::ConditionValue,
+        },
+    };
+
+    struct MockRow(HashMap<String, ConditionValue>);
+
+    impl ColumnResolver for MockRow {
+        fn resolve(&self, col: &str) -> ConditionValue {
+            self.0.get(col).cloned().unwrap_or(ConditionValue::Null)
+        }
+    }
+
+    fn eval(condition: &str, db_type: &DbType, row: &MockRow) -> Option<bool> {
+        let ctx = EvalContext {
+            now: NaiveDateTime::parse_from_str("2024-03-15 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+            case_insensitive: *db_type == DbType::Mysql,
+        };
+        ConditionParser::parse(condition, db_type)
+            .unwrap()
+            .eval(row, &ctx)
+    }
+
+    fn mock_row() -> MockRow {
+        let mut values = HashMap::new();
+        values.insert("id".to_string(), ConditionValue::Int(10));
+        values.insert("tenant_id".to_string(), ConditionValue::Int(42));
+        values.insert("price".to_string(), ConditionValue::Float(9.5));
+        values.insert("name".to_string(), ConditionValue::String("Alice".into()));
+        values.insert("remark".to_string(), ConditionValue::Null);
+        values.insert(
+            "created_at".to_string(),
+            ConditionValue::DateTime(
+                NaiveDateTime::parse_from_str("2024-03-10 08:30:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+            ),
+        );
+        MockRow(values)
+    }
+
+    #[test]
+    fn test_compare_and_logic() {
+        let row = mock_row();
+        let db_type = DbType::Pg;
+        assert_eq!(eval("tenant_id = 42", &db_type, &row), Some(true));
+        assert_eq!(eval("f_0 > 1 AND f_1 < 9", &db_type, &row), None);
+        assert_eq!(eval("id > 1 and price < 9", &db_type, &row), Some(false));
+        assert_eq!(eval("id > 1 or price < 9", &db_type, &row), Some(true));
+        assert_eq!(
+            eval("NOT (id > 1 AND price >= 9.5)", &db_type, &row),
+            Some(false)
+        );
+        assert_eq!(
+            eval("id <> 10 OR (price = 9.5)", &db_type, &row),
+            Some(true)
+        );
+        assert_eq!(eval("id != -10", &db_type, &row), Some(true));
+        assert_eq!(eval("\"tenant_id\" = '42'", &db_type, &row), Some(true));
+        assert_eq!(eval("`id` >= 1e1", &DbType::Mysql, &row), Some(true));
+        // NULL is unknown
+        assert_eq!(eval("remark = 'x'", &db_type, &row), None);
+        assert_eq!(eval("remark = 'x' OR id = 10", &db_type, &row), Some(true));
+        assert_eq!(eval("remark = 'x' AND id = 10", &db_type, &row), None);
+        assert_eq!(eval("remark = 'x' AND id = 1", &db_type, &row), Some(false));
+    }
+
+    #[test]
+    fn test_in_like_null_between() {
+        let row = mock_row();
+        let db_type = DbType::Pg;
+        assert_eq!(eval("tenant_id IN (1, 42)", &db_type, &row), Some(true));
+        assert_eq!(eval("tenant_id NOT IN (1, 2)", &db_type, &row), Some(true));
+        assert_eq!(eval("tenant_id IN (1, NULL)", &db_type, &row), None);
+        assert_eq!(eval("name LIKE 'Al%'", &db_type, &row), Some(true));
+        assert_eq!(eval("name LIKE 'al%'", &db_type, &row), Some(false));
+        assert_eq!(eval("name LIKE 'al%'", &DbType::Mysql, &row), Some(true));
+        assert_eq!(eval("name = \"alice\"", &DbType::Mysql, &row), Some(true));
+        assert_eq!(eval("name NOT LIKE '_lice'", &db_type, &row), Some(false));
+        assert_eq!(eval("remark IS NULL", &db_type, &row), Some(true));
+        assert_eq!(eval("remark IS NOT NULL", &db_type, &row), Some(false));
+        assert_eq!(eval("id BETWEEN 1 AND 10", &db_type, &row), Some(true));
+        assert_eq!(
+            eval("id NOT BETWEEN 1 AND 10 AND id = 10", &db_type, &row),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_date_functions() {
+        let row = mock_row();
+        let db_type = DbType::Mysql;
+        assert_eq!(
+            eval("created_at >= '2024-03-01'", &db_type, &row),
+            Some(true)
+        );
+        assert_eq!(
+            eval("DATE(created_at) = '2024-03-10'", &db_type, &row),
+            Some(true)
+        );
+        assert_eq!(
+            eval(
+                "YEAR(created_at) = 2024 AND MONTH(created_at) = 3",
+                &db_type,
+                &row
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            eval("EXTRACT(DAY FROM created_at) = 10", &db_type, &row),
+            Some(true)
+        );
+        assert_eq!(
+            eval("created_at > NOW() - INTERVAL 7 DAY", &db_type, &row),
+            Some(true)
+        );
+        assert_eq!(
+            eval(
+                "created_at > DATE_SUB(NOW(), INTERVAL 3 DAY)",
+                &db_type,
+                &row
+            ),
+            Some(false)
+        );
+        assert_eq!(
+            eval(
+                "created_at < DATE_ADD(CURRENT_DATE, INTERVAL 1 MONTH)",
+                &db_type,
+                &row
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            eval("created_at > now() - interval '1 month'", &DbType::Pg, &row),
+            Some(true)
+        );
+        assert_eq!(
+            eval(
+                "created_at < current_date - interval '4' day",
+                &DbType::Pg,
+                &row
+            ),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_value_expr() {
+        let row = mock_row();
+        let ctx = EvalContext {
+            now: NaiveDateTime::parse_from_str("2024-03-15 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+            case_insensitive: false,
+        };
+        let eval_value = |expr: &str| {
+            ConditionParser::parse_operand_expr(expr, &DbType::Pg)
+                .unwrap()
+                .eval(&row, &ctx)
+        };
+
+        assert_eq!(eval_value("id + tenant_id * 2"), ConditionValue::Int(94));
+        assert_eq!(eval_value("(id + 2) * 3"), ConditionValue::Int(36));
+        assert_eq!(eval_value("price / 2 - 1"), ConditionValue::Float(3.75));
+        assert_eq!(eval_value("id / 0"), ConditionValue::Null);
+        assert_eq!(eval_value("remark + 1"), ConditionValue::Null);
+        assert_eq!(
+            eval_value("CONCAT(UPPER(name), '-', id)"),
+            ConditionValue::String("ALICE-10".into())
+        );
+        assert_eq!(eval_value("CONCAT(name, remark)"), ConditionValue::Null);
+        assert_eq!(
+            eval_value("COALESCE(remark, LOWER(name))"),
+            ConditionValue::String("alice".into())
+        );
+        assert_eq!(
+            eval_value("SUBSTRING(name, 2, 3)"),
+            ConditionValue::String("lic".into())
+        );
+        assert_eq!(
+            eval_value("substr(name, -2)"),
+            ConditionValue::String("ce".into())
+        );
+        assert_eq!(eval_value("LENGTH(TRIM(' ab '))"), ConditionValue::Int(2));
+        assert_eq!(
+            eval_value("DATE(created_at + INTERVAL 1 DAY)"),
+            ConditionValue::DateTime(ConditionValue::parse_datetime("2024-03-11").unwrap())
+        );
+
+        // arithmetic in conditions
+        assert_eq!(
+            eval("id * 2 > tenant_id - 30", &DbType::Pg, &row),
+            Some(true)
+        );
+
+        assert!(ConditionParser::parse_operand_expr("UPPER(name, id)", &DbType::Pg).is_err());
+        assert!(ConditionParser::parse_operand_expr("id id", &DbType::Pg).is_err());
+        assert!(ConditionParser::parse_operand_expr("(id + 1", &DbType::Pg).is_err());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let db_type = DbType::Mysql;
+        assert!(ConditionParser::parse("id = (select 1)", &db_type).is_err());
+        assert!(ConditionParser::parse("id = 1 AND", &db_type).is_err());
+        assert!(ConditionParser::parse("id = 'abc", &db_type).is_err());
+        assert!(ConditionParser::parse("md5(id) = 'x'", &db_type).is_err());
+        assert!(ConditionParser::parse("id = 1 id", &db_type).is_err());
+
+        let expr =
+            ConditionParser::parse("a = 1 AND (b IN (1, 2) OR c LIKE 'x%')", &db_type).unwrap();
+        let mut cols: Vec<String> = expr.get_cols().into_iter().collect();
+        cols.sort();
+        assert_eq!(cols, vec!["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use mongodb::bson::Bson;
+
+use crate::meta::col_value::ColValue;
+
+const DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"];
+const DATETIME_TZ_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%dT%H:%M:%S%.f%#z"];
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// value of a column, literal or function in a condition
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionValue {
+    Null,
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    String(String),
+    DateTime(NaiveDateTime),
+}
+
+impl ConditionValue {
+    pub fn from_col_value(col_value: &ColValue) -> Self {
+        match col_value {
+            ColValue::None => Self::Null,
+            ColValue::Bool(v) => Self::Bool(*v),
+            ColValue::Tiny(v) => Self::Int(*v as i128),
+            ColValue::UnsignedTiny(v) => Self::Int(*v as i128),
+            ColValue::Short(v) => Self::Int(*v as i128),
+            ColValue::UnsignedShort(v) => Self::Int(*v as i128),
+            ColValue::Long(v) => Self::Int(*v as i128),
+            ColValue::UnsignedLong(v) => Self::Int(*v as i128),
+            ColValue::LongLong(v) => Self::Int(*v as i128),
+            ColValue::UnsignedLongLong(v) => Self::Int(*v as i128),
+            ColValue::Year(v) => Self::Int(*v as i128),
+            ColValue::Bit(v) | ColValue::Set(v) => Self::Int(*v as i128),
+            ColValue::Enum(v) => Self::Int(*v as i128),
+            ColValue::Float(v) => Self::Float(*v as f64),
+            ColValue::Double(v) => Self::Float(*v),
+            ColValue::Decimal(v) => Self::parse_number(v).unwrap_or(Self::String(v.clone())),
+            ColValue::Date(v) | ColValue::DateTime(v) | ColValue::Timestamp(v) => {
+                match Self::parse_datetime(v) {
+                    Some(datetime) => Self::DateTime(datetime),
+                    None => Self::String(v.clone()),
+                }
+            }
+            ColValue::Time(v)
+            | ColValue::String(v)
+            | ColValue::Set2(v)
+            | ColValue::Enum2(v)
+            | ColValue::Json2(v) => Self::String(v.clone()),
+            ColValue::RawString(v) | ColValue::Blob(v) | ColValue::Json(v) => {
+                Self::String(String::from_utf8_lossy(v).to_string())
+            }
+            ColValue::Json3(v) => Self::String(v.to_string()),
+            ColValue::MongoDoc(v) => Self::String(v.to_string()),
+        }
+    }
+
+    pub fn from_bson(bson: &Bson) -> Self {
+        match bson {
+            Bson::Null | Bson::Undefined => Self::Null,
+            Bson::Boolean(v) => Self::Bool(*v),
+            Bson::Int32(v) => Self::Int(*v as i128),
+            Bson::Int64(v) => Self::Int(*v as i128),
+            Bson::Double(v) => Self::Float(*v),
+            Bson::Decimal128(v) => {
+                let v = v.to_string();
+                Self::parse_number(&v).unwrap_or(Self::String(v))
+            }
+            Bson::String(v) | Bson::Symbol(v) => Self::String(v.clone()),
+            Bson::ObjectId(v) => Self::String(v.to_hex()),
+            Bson::DateTime(v) => match DateTime::from_timestamp_millis(v.timestamp_millis()) {
+                Some(datetime) => Self::DateTime(datetime.naive_utc()),
+                None => Self::Null,
+            },
+            _ => Self::String(bson.clone().into_relaxed_extjson().to_string()),
+        }
+    }
+
+    pub fn parse_number(str: &str) -> Option<Self> {
+        let str = str.trim();
+        if let Ok(v) = str.parse::<i128>() {
+            return Some(Self::Int(v));
+        }
+        str.parse::<f64>().ok().map(Self::Float)
+    }
+
+    pub fn parse_datetime(str: &str) -> Option<NaiveDateTime> {
+        let str = str.trim();
+        for format in DATETIME_FORMATS {
+            if let Ok(datetime) = NaiveDateTime::parse_from_str(str, format) {
+                return Some(datetime);
+            }
+        }
+        // timestamp with time zone, e.g. 2024-01-01 08:00:00+08 from postgres
+        for format in DATETIME_TZ_FORMATS {
+            if let Ok(datetime) = DateTime::parse_from_str(str, format) {
+                return Some(datetime.naive_utc());
+            }
+        }
+        NaiveDate::parse_from_str(str, DATE_FORMAT)
+            .ok()
+            .map(|date| date.and_time(NaiveTime::MIN))
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    pub fn to_datetime(&self) -> Option<NaiveDateTime> {
+        match self {
+            Self::DateTime(v) => Some(*v),
+            Self::String(v) => Self::parse_datetime(v),
+            _ => None,
+        }
+    }
+
+    /// the string used by LIKE, None for NULL
+    pub fn to_like_string(&self) -> Option<String> {
+        match self {
+            Self::Null => None,
+            Self::Bool(v) => Some((*v as i32).to_string()),
+            Self::Int(v) => Some(v.to_string()),
+            Self::Float(v) => Some(v.to_string()),
+            Self::String(v) => Some(v.clone()),
+            Self::DateTime(v) => Some(v.format("%Y-%m-%d %H:%M:%S%.f").to_string()),
+        }
+    }
+
+    /// the truth value of a value used as a condition, None for NULL
+    pub fn to_bool(&self) -> Option<bool> {
+        match self {
+            Self::Null => None,
+            Self::Bool(v) => Some(*v),
+            Self::Int(v) => Some(*v != 0),
+            Self::Float(v) => Some(*v != 0.0),
+            Self::String(v) => match Self::parse_number(v) {
+                Some(number) => number.to_bool(),
+                None => Some(false),
+            },
+            Self::DateTime(_) => Some(true),
+        }
+    }
+
+    /// None if either side is NULL or they are not comparable
+    pub fn compare(&self, other: &Self, case_insensitive: bool) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Null, _) | (_, Self::Null) => None,
+
+            (Self::DateTime(_), _) | (_, Self::DateTime(_)) => {
+                match (self.to_datetime(), other.to_datetime()) {
+                    (Some(left), Some(right)) => Some(left.cmp(&right)),
+                    _ => None,
+                }
+            }
+
+            (Self::String(left), Self::String(right)) => {
+                if case_insensitive {
+                    Some(left.to_lowercase().cmp(&right.to_lowercase()))
+                } else {
+                    Some(left.cmp(right))
+                }
+            }
+
+            (Self::String(v), _) => Self::parse_number(v)?.compare(other, case_insensitive),
+            (_, Self::String(v)) => self.compare(&Self::parse_number(v)?, case_insensitive),
+
+            (Self::Int(left), Self::Int(right)) => Some(left.cmp(right)),
+            (Self::Bool(_), _) | (_, Self::Bool(_)) => self
+                .to_number()?
+                .compare(&other.to_number()?, case_insensitive),
+            _ => self.to_f64()?.partial_cmp(&other.to_f64()?),
+        }
+    }
+
+    /// None if the value is NULL or not a number
+    pub fn to_number(&self) -> Option<Self> {
+        match self {
+            Self::Bool(v) => Some(Self::Int(*v as i128)),
+            Self::Int(_) | Self::Float(_) => Some(self.clone()),
+            Self::String(v) => Self::parse_number(v),
+            _ => None,
+        }
+    }
+
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use mongodb::bson::{oid::ObjectId, Bson, DateTime};
+
+    use super::ConditionValue;
+    use crate::meta::col_value::ColValue;
+
+    #[test]
+    fn test_from_col_value() {
+        assert_eq!(
+            ConditionValue::from_col_value(&ColValue::UnsignedLongLong(u64::MAX)),
+            ConditionValue::Int(u64::MAX as i128)
+        );
+        assert_eq!(
+            ConditionValue::from_col_value(&ColValue::Decimal("1.50".into())),
+            ConditionValue::Float(1.5)
+        );
+        assert_eq!(
+            ConditionValue::from_col_value(&ColValue::Timestamp("2024-01-01 08:00:00+08".into())),
+            ConditionValue::DateTime(ConditionValue::parse_datetime("2024-01-01").unwrap())
+        );
+        assert_eq!(
+            ConditionValue::from_col_value(&ColValue::None),
+            ConditionValue::Null
+        );
+    }
+
+    #[test]
+    fn test_from_bson() {
+        let oid = ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap();
+        assert_eq!(
+            ConditionValue::from_bson(&Bson::ObjectId(oid)),
+            ConditionValue::String("65a1b2c3d4e5f60718293a4b".into())
+        );
+        assert_eq!(
+            ConditionValue::from_bson(&Bson::DateTime(DateTime::from_millis(1704067200000))),
+            ConditionValue::DateTime(ConditionValue::parse_datetime("2024-01-01").unwrap())
+        );
+        assert_eq!(
+            ConditionValue::from_bson(&Bson::Int32(3)),
+            ConditionValue::Int(3)
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let int = ConditionValue::Int(10);
+        assert_eq!(
+            int.compare(&ConditionValue::Float(9.5), false),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            int.compare(&ConditionValue::String("10".into()), false),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            int.compare(&ConditionValue::String("abc".into()), false),
+            None
+        );
+        assert_eq!(int.compare(&ConditionValue::Null, false), None);
+        assert_eq!(
+            ConditionValue::Bool(true).compare(&ConditionValue::Int(1), false),
+            Some(Ordering::Equal)
+        );
+
+        let a = ConditionValue::String("abc".into());
+        let b = ConditionValue::String("ABC".into());
+        assert_eq!(a.compare(&b, true), Some(Ordering::Equal));
+        assert_eq!(a.compare(&b, false), Some(Ordering::Greater));
+
+        let datetime = ConditionValue::from_col_value(&ColValue::DateTime(
+            "2024-01-01 10:00:00.000000".into(),
+        ));
+        assert_eq!(
+            datetime.compare(&ConditionValue::String("2024-01-01".into()), false),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            datetime.compare(&ConditionValue::String("2024-01-01T10:00:00".into()), false),
+            Some(Ordering::Equal)
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod condition_expr;
+pub mod condition_parser;
+pub mod condition_value;
@@ -3,7 +3,7 @@ use serde_json::json;
 
 use crate::config::config_enums::DbType;
 
-use super::{ddl_statement::DdlStatement, ddl_type::DdlType};
+use super::{ddl_definition::AlterTableOperation, ddl_statement::DdlStatement, ddl_type::DdlType};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct DdlData {
@@ -12,6 +12,10 @@ pub struct DdlData {
     pub ddl_type: DdlType,
     pub db_type: DbType,
     pub statement: DdlStatement,
+    // parsed from ALTER TABLE statements, empty if the ddl is not ALTER TABLE
+    // or the alter specifications can not be parsed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alter_operations: Vec<AlterTableOperation>,
 }
 
 impl std::fmt::Display for DdlData {
@@ -50,6 +54,7 @@ impl DdlData {
                 ddl_type: self.ddl_type.clone(),
                 db_type: self.db_type.clone(),
                 statement,
+                alter_operations: self.alter_operations.clone(),
             });
         }
         res
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString, IntoStaticStr};
+
+use crate::meta::mysql::mysql_col_type::MysqlColType;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ColumnDefinition {
+    pub column_name: String,
+    // raw type: int(11) unsigned, varchar(255), decimal(10,2)
+    pub column_type: String,
+    // lower case type name without args: int, varchar, decimal
+    pub data_type: String,
+    // raw args of type: ["10", "2"] for decimal(10,2), ["'a'", "'b'"] for enum('a','b')
+    pub type_args: Vec<String>,
+    pub unsigned: bool,
+    // None if not specified
+    pub nullable: Option<bool>,
+    // raw sql of default value: 'abc', 1, CURRENT_TIMESTAMP, (rand())
+    pub default_value: Option<String>,
+    pub auto_increment: bool,
+    pub primary_key: bool,
+    pub unique_key: bool,
+    pub comment: Option<String>,
+    pub character_set: Option<String>,
+    pub collation: Option<String>,
+    // raw sql of ON UPDATE, mysql only
+    pub on_update: Option<String>,
+    // generated expression
+    pub generated: Option<String>,
+    pub generated_stored: bool,
+}
+
+impl ColumnDefinition {
+    pub fn is_nullable(&self) -> bool {
+        // in mysql, primary key cols are implicitly NOT NULL
+        self.nullable.unwrap_or(!self.primary_key)
+    }
+
+    /// args of type as numbers, e.g. [10, 2] for decimal(10,2)
+    pub fn get_num_args(&self) -> Vec<u64> {
+        self.type_args
+            .iter()
+            .filter_map(|i| i.trim().parse().ok())
+            .collect()
+    }
+
+    /// items of enum / set without quotes
+    pub fn get_str_args(&self) -> Vec<String> {
+        self.type_args
+            .iter()
+            .map(|i| {
+                i.trim()
+                    .trim_start_matches('\'')
+                    .trim_end_matches('\'')
+                    .replace("''", "'")
+            })
+            .collect()
+    }
+
+    /// convert mysql column definition to the same col type as fetched from information_schema
+    pub fn to_mysql_col_type(&self) -> MysqlColType {
+        let unsigned = self.unsigned;
+        let is_nullable = self.is_nullable();
+        let num_args = self.get_num_args();
+        let arg = |n: usize, default: u64| num_args.get(n).cloned().unwrap_or(default);
+        let charset = self.character_set.clone().unwrap_or_default();
+
+        match self.data_type.as_str() {
+            "tinyint" => MysqlColType::TinyInt { unsigned },
+            "smallint" => MysqlColType::SmallInt { unsigned },
+            "mediumint" => MysqlColType::MediumInt { unsigned },
+            "int" => MysqlColType::Int { unsigned },
+            "bigint" => MysqlColType::BigInt { unsigned },
+            "float" => MysqlColType::Float,
+            "double" => MysqlColType::Double,
+            "decimal" => MysqlColType::Decimal {
+                precision: arg(0, 10) as u32,
+                scale: arg(1, 0) as u32,
+            },
+            "time" => MysqlColType::Time {
+                precision: arg(0, 0) as u32,
+            },
+            "date" => MysqlColType::Date { is_nullable },
+            "datetime" => MysqlColType::DateTime {
+                precision: arg(0, 0) as u32,
+                is_nullable,
+            },
+            "timestamp" => MysqlColType::Timestamp {
+                precision: arg(0, 0) as u32,
+                timezone_offset: 0,
+                is_nullable,
+            },
+            "year" => MysqlColType::Year,
+            "char" => MysqlColType::Char {
+                length: arg(0, 1),
+                charset,
+            },
+            "varchar" => MysqlColType::Varchar {
+                length: arg(0, 0),
+                charset,
+            },
+            "tinytext" => MysqlColType::TinyText {
+                length: 255,
+                charset,
+            },
+            "text" => MysqlColType::Text {
+                length: 65535,
+                charset,
+            },
+            "mediumtext" => MysqlColType::MediumText {
+                length: 16777215,
+                charset,
+            },
+            "longtext" => MysqlColType::LongText {
+                length: 4294967295,
+                charset,
+            },
+            "binary" => MysqlColType::Binary {
+                length: arg(0, 1) as u8,
+            },
+            "varbinary" => MysqlColType::VarBinary {
+                length: arg(0, 0) as u16,
+            },
+            "tinyblob" => MysqlColType::TinyBlob,
+            "blob" => MysqlColType::Blob,
+            "mediumblob" => MysqlColType::MediumBlob,
+            "longblob" => MysqlColType::LongBlob,
+            "bit" => MysqlColType::Bit,
+            "json" => MysqlColType::Json,
+            "enum" => MysqlColType::Enum {
+                items: self.get_str_args(),
+            },
+            "set" => {
+                let mut items = HashMap::new();
+                let mut key = 1;
+                for item in self.get_str_args() {
+                    items.insert(key, item);
+                    key <<= 1;
+                }
+                MysqlColType::Set { items }
+            }
+            _ => MysqlColType::Unknown,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, Display, EnumString, IntoStaticStr,
+)]
+pub enum IndexDefinitionKind {
+    #[strum(serialize = "primary")]
+    Primary,
+    #[strum(serialize = "unique")]
+    Unique,
+    #[default]
+    #[strum(serialize = "normal")]
+    Normal,
+    #[strum(serialize = "fulltext")]
+    FullText,
+    #[strum(serialize = "spatial")]
+    Spatial,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct IndexDefinition {
+    pub index_name: Option<String>,
+    pub index_kind: IndexDefinitionKind,
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ConstraintDefinition {
+    ForeignKey {
+        name: Option<String>,
+        columns: Vec<String>,
+        ref_schema: String,
+        ref_tb: String,
+        ref_columns: Vec<String>,
+        // raw sql: ON DELETE CASCADE ON UPDATE SET NULL
+        options: String,
+    },
+    Check {
+        name: Option<String>,
+        // raw sql of the check expression
+        expression: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct TableDefinition {
+    pub columns: Vec<ColumnDefinition>,
+    pub indexes: Vec<IndexDefinition>,
+    pub constraints: Vec<ConstraintDefinition>,
+    pub comment: Option<String>,
+}
+
+impl TableDefinition {
+    /// primary key cols, defined either in column definition or in index definition
+    pub fn get_primary_key(&self) -> Vec<String> {
+        for index in self.indexes.iter() {
+            if index.index_kind == IndexDefinitionKind::Primary {
+                return index.columns.clone();
+            }
+        }
+        self.columns
+            .iter()
+            .filter(|i| i.primary_key)
+            .map(|i| i.column_name.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ColumnPosition {
+    First,
+    After(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AlterTableOperation {
+    AddColumn {
+        column: ColumnDefinition,
+        position: Option<ColumnPosition>,
+    },
+    DropColumn {
+        column_name: String,
+    },
+    // mysql: MODIFY COLUMN, the full column definition is replaced
+    ModifyColumn {
+        column: ColumnDefinition,
+        position: Option<ColumnPosition>,
+    },
+    // mysql: CHANGE COLUMN, rename + modify
+    ChangeColumn {
+        old_column_name: String,
+        column: ColumnDefinition,
+        position: Option<ColumnPosition>,
+    },
+    RenameColumn {
+        old_column_name: String,
+        new_column_name: String,
+    },
+    // pg: ALTER COLUMN ... TYPE, only the name and type fields of column are set
+    AlterColumnType {
+        column: ColumnDefinition,
+    },
+    // pg: ALTER COLUMN ... SET NOT NULL
+    SetColumnNotNull {
+        column_name: String,
+    },
+    // pg: ALTER COLUMN ... DROP NOT NULL
+    DropColumnNotNull {
+        column_name: String,
+    },
+    SetColumnDefault {
+        column_name: String,
+        default_value: String,
+    },
+    DropColumnDefault {
+        column_name: String,
+    },
+    AddIndex {
+        index: IndexDefinition,
+    },
+    DropIndex {
+        index_name: String,
+    },
+    RenameIndex {
+        old_index_name: String,
+        new_index_name: String,
+    },
+    DropPrimaryKey,
+    AddConstraint {
+        constraint: ConstraintDefinition,
+    },
+    DropConstraint {
+        constraint_name: String,
+    },
+    RenameConstraint {
+        old_constraint_name: String,
+        new_constraint_name: String,
+    },
+    SetTableComment {
+        comment: String,
+    },
+    // operations which do not affect the columns, kept as raw sql:
+    // mysql: ENGINE=InnoDB, AUTO_INCREMENT=100, pg: OWNER TO user_1, ALTER COLUMN ... SET STATISTICS 100
+    TableOption {
+        option: String,
+    },
+}
+
+impl AlterTableOperation {
+    /// rename the columns referred by the operation, col_map: HashMap<src_col, dst_col>
+    pub fn route_cols(&mut self, col_map: &HashMap<String, String>) {
+        let route = |col: &mut String| {
+            if let Some(dst_col) = col_map.get(col.as_str()) {
+                *col = dst_col.clone();
+            }
+        };
+        let route_position = |position: &mut Option<ColumnPosition>| {
+            if let Some(ColumnPosition::After(col)) = position {
+                route(col);
+            }
+        };
+
+        match self {
+            Self::AddColumn { column, position } | Self::ModifyColumn { column, position } => {
+                route(&mut column.column_name);
+                route_position(position);
+            }
+            Self::ChangeColumn {
+                old_column_name,
+                column,
+                position,
+            } => {
+                route(old_column_name);
+                route(&mut column.column_name);
+                route_position(position);
+            }
+            Self::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                route(old_column_name);
+                route(new_column_name);
+            }
+            Self::AlterColumnType { column } => route(&mut column.column_name),
+            Self::DropColumn { column_name }
+            | Self::SetColumnNotNull { column_name }
+            | Self::DropColumnNotNull { column_name }
+            | Self::SetColumnDefault { column_name, .. }
+            | Self::DropColumnDefault { column_name } => route(column_name),
+            Self::AddIndex { index } => index.columns.iter_mut().for_each(route),
+            Self::AddConstraint {
+                constraint: ConstraintDefinition::ForeignKey { columns, .. },
+            } => columns.iter_mut().for_each(route),
+            _ => {}
+        }
+    }
+}
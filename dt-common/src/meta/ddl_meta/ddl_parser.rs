@@ -1,4 +1,4 @@
-use crate::{config::config_enums::DbType, error::Error, utils::sql_util::SqlUtil};
+use crate::{config::config_enums::DbType, error::Error, log_warn, utils::sql_util::SqlUtil};
 use anyhow::bail;
 use nom::{
     branch::alt,
@@ -21,6 +21,7 @@ use std::{
 
 use super::{
     ddl_data::DdlData,
+    ddl_definition::AlterTableOperation,
     ddl_statement::{
         AlterSchemaStatement, DropMultiTableStatement, DropSchemaStatement,
         MysqlAlterTableRenameStatement, MysqlAlterTableStatement, MysqlCreateIndexStatement,
@@ -31,6 +32,8 @@ use super::{
     },
     ddl_type::DdlType,
     keywords::keyword_a_to_c,
+    mysql_definition_parser::MysqlDefinitionParser,
+    pg_definition_parser::PgDefinitionParser,
 };
 use super::{ddl_statement::AlterDatabaseStatement, keywords::keyword_o_to_s};
 use super::{ddl_statement::CreateDatabaseStatement, keywords::keyword_c_to_e};
@@ -55,6 +58,7 @@ impl DdlParser {
         match self.sql_query(input) {
             Ok((_, mut ddl)) => {
                 ddl.db_type = self.db_type.clone();
+                ddl.alter_operations = Self::parse_alter_operations(&ddl.statement);
                 Ok(ddl)
             }
             Err(err) => {
@@ -69,6 +73,32 @@ impl DdlParser {
         }
     }
 
+    /// best effort, the ddl can still be replayed by raw sql if alter specifications not parsed
+    fn parse_alter_operations(statement: &DdlStatement) -> Vec<AlterTableOperation> {
+        let (unparsed, res) = match statement {
+            DdlStatement::MysqlAlterTable(s) => (
+                &s.unparsed,
+                MysqlDefinitionParser::parse_alter_table(&s.unparsed),
+            ),
+            DdlStatement::PgAlterTable(s) => (
+                &s.unparsed,
+                PgDefinitionParser::parse_alter_table(&s.unparsed),
+            ),
+            _ => return Vec::new(),
+        };
+        match res {
+            Ok(operations) => operations,
+            Err(error) => {
+                log_warn!(
+                    "failed to parse alter specifications: {}, error: {}",
+                    unparsed,
+                    error
+                );
+                Vec::new()
+            }
+        }
+    }
+
     fn remove_comments(sql: &str) -> Cow<str> {
         // "create /*some comments,*/table/*some comments*/ `aaa`.`bbb`"
         let regex = Regex::new(r"(/\*([^*]|\*+[^*/*])*\*+/)|(--[^\n]*\n)").unwrap();
@@ -1156,6 +1186,35 @@ mod test_mysql {
         }
     }
 
+    #[test]
+    fn test_alter_table_operations_mysql() {
+        let sql = "alter table aaa.bbb add column f_1 int not null default 0 after id, drop column f_2, modify f_3 varchar(10), rename column f_4 to f_5, add index idx_1 (f_1)";
+        let parser = DdlParser::new(DbType::Mysql);
+        let r = parser.parse(sql).unwrap();
+        assert_eq!(r.alter_operations.len(), 5);
+        assert!(matches!(
+            r.alter_operations[0],
+            AlterTableOperation::AddColumn { .. }
+        ));
+        assert_eq!(
+            r.alter_operations[1],
+            AlterTableOperation::DropColumn {
+                column_name: "f_2".into()
+            }
+        );
+        assert!(matches!(
+            r.alter_operations[2],
+            AlterTableOperation::ModifyColumn { .. }
+        ));
+        assert!(matches!(
+            r.alter_operations[4],
+            AlterTableOperation::AddIndex { .. }
+        ));
+
+        let r = parser.parse("create table aaa.bbb (id int)").unwrap();
+        assert!(r.alter_operations.is_empty());
+    }
+
     #[test]
     fn test_alter_table_with_schema_mysql() {
         let sqls = [
@@ -1600,9 +1659,39 @@ mod test_mysql {
 mod test_pg {
     use crate::{
         config::config_enums::DbType,
-        meta::ddl_meta::{ddl_parser::DdlParser, ddl_type::DdlType},
+        meta::ddl_meta::{
+            ddl_definition::AlterTableOperation, ddl_parser::DdlParser, ddl_type::DdlType,
+        },
     };
 
+    #[test]
+    fn test_alter_table_operations_pg() {
+        let sql = r#"alter table if exists only aaa."bbb" add column f_1 int not null default 0, alter column f_2 type bigint, alter column f_3 set not null, drop constraint pk_1"#;
+        let parser = DdlParser::new(DbType::Pg);
+        let r = parser.parse(sql).unwrap();
+        assert_eq!(r.alter_operations.len(), 4);
+        assert!(matches!(
+            r.alter_operations[0],
+            AlterTableOperation::AddColumn { .. }
+        ));
+        assert!(matches!(
+            r.alter_operations[1],
+            AlterTableOperation::AlterColumnType { .. }
+        ));
+        assert_eq!(
+            r.alter_operations[2],
+            AlterTableOperation::SetColumnNotNull {
+                column_name: "f_3".into()
+            }
+        );
+        assert_eq!(
+            r.alter_operations[3],
+            AlterTableOperation::DropConstraint {
+                constraint_name: "pk_1".into()
+            }
+        );
+    }
+
     #[test]
     fn test_create_table_multi_lines_pg() {
         let sqls = [r#"CREATE TABLE -- some comments
@@ -1,6 +1,10 @@
+use anyhow::bail;
+use mongodb::bson::{doc, Bson, Document};
 use serde::{Deserialize, Serialize};
 
-use crate::{config::config_enums::DbType, utils::sql_util::SqlUtil};
+use crate::{config::config_enums::DbType, error::Error, utils::sql_util::SqlUtil};
+
+const MONGO_ADMIN_DB: &str = "admin";
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub enum DdlStatement {
@@ -34,6 +38,10 @@ pub enum DdlStatement {
     RenameTable(RenameTableStatement),
     PgDropIndex(PgDropIndexStatement),
 
+    MongoCreate(MongoCreateStatement),
+    MongoCreateIndexes(MongoCreateIndexesStatement),
+    MongoDropIndexes(MongoDropIndexesStatement),
+
     #[default]
     Unknown,
 }
@@ -113,6 +121,10 @@ impl DdlStatement {
             DdlStatement::PgAlterTableRename(s) => (s.schema.clone(), s.tb.clone()),
             DdlStatement::PgAlterTableSetSchema(s) => (s.schema.clone(), s.tb.clone()),
 
+            DdlStatement::MongoCreate(s) => (s.db.clone(), s.tb.clone()),
+            DdlStatement::MongoCreateIndexes(s) => (s.db.clone(), s.tb.clone()),
+            DdlStatement::MongoDropIndexes(s) => (s.db.clone(), s.tb.clone()),
+
             DdlStatement::PgDropIndex(_)
             | DdlStatement::PgDropMultiIndex(_)
             | DdlStatement::DropMultiTable(_)
@@ -260,6 +272,19 @@ impl DdlStatement {
                 s.tb = dst_tb;
             }
 
+            DdlStatement::MongoCreate(s) => {
+                s.db = dst_schema;
+                s.tb = dst_tb;
+            }
+            DdlStatement::MongoCreateIndexes(s) => {
+                s.db = dst_schema;
+                s.tb = dst_tb;
+            }
+            DdlStatement::MongoDropIndexes(s) => {
+                s.db = dst_schema;
+                s.tb = dst_tb;
+            }
+
             // not supported
             DdlStatement::RenameTable(_)
             | DdlStatement::MysqlAlterTableRename(_)
@@ -471,8 +496,40 @@ pub struct PgDropIndexStatement {
     pub unparsed: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MongoCreateStatement {
+    pub db: String,
+    pub tb: String,
+    // collection options in canonical extended json, e.g. capped, validator, viewOn
+    pub options: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MongoCreateIndexesStatement {
+    pub db: String,
+    pub tb: String,
+    // index specs in canonical extended json
+    pub indexes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MongoDropIndexesStatement {
+    pub db: String,
+    pub tb: String,
+    pub index_name: String,
+}
+
 impl DdlStatement {
     pub fn to_sql(&self, db_type: &DbType) -> String {
+        if *db_type == DbType::Mongo {
+            return match self.to_mongo_command() {
+                Ok(Some((_, command))) => {
+                    Bson::Document(command).into_relaxed_extjson().to_string()
+                }
+                _ => String::new(),
+            };
+        }
+
         match self {
             DdlStatement::CreateDatabase(s) => {
                 let mut sql = "CREATE DATABASE".to_string();
@@ -697,6 +754,63 @@ impl DdlStatement {
     }
 }
 
+impl DdlStatement {
+    /// the command to be executed in target mongo and the db it should be executed in,
+    /// None if the ddl is not supported by mongo
+    pub fn to_mongo_command(&self) -> anyhow::Result<Option<(String, Document)>> {
+        let res = match self {
+            DdlStatement::DropDatabase(s) => (s.db.clone(), doc! { "dropDatabase": 1 }),
+
+            DdlStatement::DropTable(s) => (s.schema.clone(), doc! { "drop": &s.tb }),
+
+            // renameCollection must be executed in admin, the rename succeeded in source means
+            // the target namespace was either absent or dropped there, so drop it in target too
+            DdlStatement::RenameTable(s) => (
+                MONGO_ADMIN_DB.to_string(),
+                doc! {
+                    "renameCollection": format!("{}.{}", s.schema, s.tb),
+                    "to": format!("{}.{}", s.new_schema, s.new_tb),
+                    "dropTarget": true,
+                },
+            ),
+
+            DdlStatement::MongoCreate(s) => {
+                let mut command = doc! { "create": &s.tb };
+                command.extend(parse_extjson_doc(&s.options)?);
+                (s.db.clone(), command)
+            }
+
+            DdlStatement::MongoCreateIndexes(s) => {
+                let mut indexes = Vec::new();
+                for index in s.indexes.iter() {
+                    indexes.push(Bson::Document(parse_extjson_doc(index)?));
+                }
+                let command = doc! { "createIndexes": &s.tb, "indexes": indexes };
+                (s.db.clone(), command)
+            }
+
+            DdlStatement::MongoDropIndexes(s) => (
+                s.db.clone(),
+                doc! { "dropIndexes": &s.tb, "index": &s.index_name },
+            ),
+
+            _ => return Ok(None),
+        };
+        Ok(Some(res))
+    }
+}
+
+fn parse_extjson_doc(extjson: &str) -> anyhow::Result<Document> {
+    if extjson.is_empty() {
+        return Ok(Document::new());
+    }
+    let value: serde_json::Value = serde_json::from_str(extjson)?;
+    match Bson::try_from(value)? {
+        Bson::Document(doc) => Ok(doc),
+        other => bail! {Error::Unexpected(format!("not a mongo document: {}", other))},
+    }
+}
+
 impl DropMultiTableStatement {
     pub fn to_sql(&self, db_type: &DbType) -> String {
         let mut sql = "DROP TABLE".to_string();
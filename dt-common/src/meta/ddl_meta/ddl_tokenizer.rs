@@ -0,0 +1,347 @@
+use anyhow::bail;
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    // keywords, unquoted identifiers, numbers
+    Word,
+    // `abc` in mysql, "abc" in pg
+    QuotedIdentifier,
+    // 'abc', _utf8mb4'abc', b'01', x'ff'
+    StringLiteral,
+    // ( ) , ; . =
+    Symbol,
+    // any other operator chars: + - * / < > ...
+    Operator,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    // byte offsets of the token in the origin sql
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+
+    pub fn is_symbol(&self, symbol: char) -> bool {
+        self.kind == TokenKind::Symbol && self.text.len() == 1 && self.text.starts_with(symbol)
+    }
+
+    /// identifier without quotes
+    pub fn to_identifier(&self) -> String {
+        match self.kind {
+            TokenKind::QuotedIdentifier => {
+                let quote = &self.text[0..1];
+                let inner = &self.text[1..self.text.len() - 1];
+                inner.replace(&format!("{}{}", quote, quote), quote)
+            }
+            _ => self.text.clone(),
+        }
+    }
+
+    /// content of string literal without quotes and charset introducer
+    pub fn to_unquoted_string(&self) -> String {
+        if self.kind != TokenKind::StringLiteral {
+            return self.text.clone();
+        }
+        let start = self.text.find('\'').unwrap_or(0);
+        let inner = &self.text[start + 1..self.text.len() - 1];
+        inner.replace("''", "'").replace("\\'", "'")
+    }
+}
+
+/// A lightweight tokenizer for the parts of ddl (column definitions, alter specifications)
+/// which are kept as unparsed text by DdlParser.
+pub struct DdlTokenizer {}
+
+impl DdlTokenizer {
+    pub fn tokenize(sql: &str, identifier_quote: char) -> anyhow::Result<Vec<Token>> {
+        let bytes = sql.as_bytes();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let c = bytes[i];
+            if c.is_ascii_whitespace() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            let kind = if c == b'\'' {
+                i = Self::skip_quoted(bytes, i, b'\'', true)?;
+                TokenKind::StringLiteral
+            } else if c == identifier_quote as u8 {
+                i = Self::skip_quoted(bytes, i, c, false)?;
+                TokenKind::QuotedIdentifier
+            } else if Self::is_word_char(c) {
+                while i < bytes.len() && Self::is_word_char(bytes[i]) {
+                    i += 1;
+                }
+                // charset introducer / bit / hex literals: _utf8mb4'abc', b'01', x'ff'
+                if i < bytes.len() && bytes[i] == b'\'' {
+                    i = Self::skip_quoted(bytes, i, b'\'', true)?;
+                    TokenKind::StringLiteral
+                } else {
+                    TokenKind::Word
+                }
+            } else if matches!(c, b'(' | b')' | b',' | b';' | b'.' | b'=') {
+                i += 1;
+                TokenKind::Symbol
+            } else {
+                i += 1;
+                TokenKind::Operator
+            };
+
+            tokens.push(Token {
+                kind,
+                text: sql[start..i].to_string(),
+                start,
+                end: i,
+            });
+        }
+        Ok(tokens)
+    }
+
+    fn skip_quoted(
+        bytes: &[u8],
+        start: usize,
+        quote: u8,
+        backslash: bool,
+    ) -> anyhow::Result<usize> {
+        let mut i = start + 1;
+        while i < bytes.len() {
+            if backslash && bytes[i] == b'\\' {
+                i += 2;
+                continue;
+            }
+            if bytes[i] == quote {
+                // doubled quotes: 'it''s', `a``b`
+                if i + 1 < bytes.len() && bytes[i + 1] == quote {
+                    i += 2;
+                    continue;
+                }
+                return Ok(i + 1);
+            }
+            i += 1;
+        }
+        bail! {Error::Unexpected(format!(
+            "unclosed quote: {}, sql: {}",
+            quote as char,
+            String::from_utf8_lossy(bytes)
+        ))}
+    }
+
+    #[inline]
+    fn is_word_char(c: u8) -> bool {
+        c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || c >= 0x80
+    }
+}
+
+/// A cursor over tokens
+pub struct TokenStream<'a> {
+    pub sql: &'a str,
+    pub tokens: Vec<Token>,
+    pub pos: usize,
+}
+
+impl<'a> TokenStream<'a> {
+    pub fn new(sql: &'a str, identifier_quote: char) -> anyhow::Result<Self> {
+        Ok(Self {
+            sql,
+            tokens: DdlTokenizer::tokenize(sql, identifier_quote)?,
+            pos: 0,
+        })
+    }
+
+    pub fn is_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    pub fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n)
+    }
+
+    pub fn next_token(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    pub fn peek_word(&self, word: &str) -> bool {
+        self.peek().is_some_and(|t| t.is_word(word))
+    }
+
+    pub fn peek_words(&self, words: &[&str]) -> bool {
+        words
+            .iter()
+            .enumerate()
+            .all(|(n, word)| self.peek_nth(n).is_some_and(|t| t.is_word(word)))
+    }
+
+    pub fn peek_symbol(&self, symbol: char) -> bool {
+        self.peek().is_some_and(|t| t.is_symbol(symbol))
+    }
+
+    /// consume the word if matched
+    pub fn eat_word(&mut self, word: &str) -> bool {
+        if self.peek_word(word) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    /// consume the words if all matched
+    pub fn eat_words(&mut self, words: &[&str]) -> bool {
+        if self.peek_words(words) {
+            self.pos += words.len();
+            return true;
+        }
+        false
+    }
+
+    pub fn eat_symbol(&mut self, symbol: char) -> bool {
+        if self.peek_symbol(symbol) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    pub fn expect_identifier(&mut self) -> anyhow::Result<String> {
+        match self.peek() {
+            Some(t) if matches!(t.kind, TokenKind::Word | TokenKind::QuotedIdentifier) => {
+                let identifier = t.to_identifier();
+                self.pos += 1;
+                Ok(identifier)
+            }
+            _ => bail! {self.unexpected("expect identifier")},
+        }
+    }
+
+    pub fn expect_string(&mut self) -> anyhow::Result<String> {
+        match self.peek() {
+            Some(t) if t.kind == TokenKind::StringLiteral => {
+                let str = t.to_unquoted_string();
+                self.pos += 1;
+                Ok(str)
+            }
+            _ => bail! {self.unexpected("expect string")},
+        }
+    }
+
+    /// consume a parenthesized group and return the raw text inside the parentheses
+    pub fn expect_group(&mut self) -> anyhow::Result<String> {
+        let (start, end) = self.skip_group()?;
+        Ok(self.sql[start..end].trim().to_string())
+    }
+
+    /// consume a parenthesized group and return the byte range inside the parentheses
+    pub fn skip_group(&mut self) -> anyhow::Result<(usize, usize)> {
+        let open = match self.peek() {
+            Some(t) if t.is_symbol('(') => t.end,
+            _ => bail! {self.unexpected("expect: (")},
+        };
+        self.pos += 1;
+        let mut depth = 1;
+        while let Some(t) = self.next_token() {
+            if t.is_symbol('(') {
+                depth += 1;
+            } else if t.is_symbol(')') {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok((open, t.start));
+                }
+            }
+        }
+        bail! {self.unexpected("unclosed (")}
+    }
+
+    /// split a parenthesized list by top level commas, return raw text of each item
+    pub fn expect_list(&mut self) -> anyhow::Result<Vec<String>> {
+        let (start, end) = self.skip_group()?;
+        Ok(Self::split_by_comma(&self.sql[start..end]))
+    }
+
+    /// consume tokens until top level comma / end, return the raw text
+    pub fn take_until_comma(&mut self) -> String {
+        let start = match self.peek() {
+            Some(t) => t.start,
+            None => return String::new(),
+        };
+        let mut end = start;
+        while let Some(t) = self.peek() {
+            if t.is_symbol(',') {
+                break;
+            }
+            if t.is_symbol('(') {
+                match self.skip_group() {
+                    Ok((_, group_end)) => end = group_end + 1,
+                    Err(_) => break,
+                }
+                continue;
+            }
+            end = t.end;
+            self.pos += 1;
+        }
+        self.sql[start..end].trim().to_string()
+    }
+
+    pub fn split_by_comma(sql: &str) -> Vec<String> {
+        let mut items = Vec::new();
+        let (mut depth, mut start) = (0, 0);
+        let (mut in_str, mut quote) = (false, '\0');
+        let mut prev = '\0';
+        for (i, c) in sql.char_indices() {
+            if in_str {
+                if c == quote && prev != '\\' {
+                    in_str = false;
+                }
+            } else {
+                match c {
+                    '\'' | '`' | '"' => {
+                        in_str = true;
+                        quote = c;
+                    }
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    ',' if depth == 0 => {
+                        items.push(sql[start..i].trim().to_string());
+                        start = i + 1;
+                    }
+                    _ => {}
+                }
+            }
+            prev = c;
+        }
+        let last = sql[start..].trim();
+        if !last.is_empty() {
+            items.push(last.to_string());
+        }
+        items
+    }
+
+    pub fn unexpected(&self, message: &str) -> Error {
+        let near = match self.peek() {
+            Some(t) => &self.sql[t.start..],
+            None => "end of sql",
+        };
+        Error::Unexpected(format!(
+            "failed to parse ddl definition: {}, near: {}, sql: {}",
+            message, near, self.sql
+        ))
+    }
+}
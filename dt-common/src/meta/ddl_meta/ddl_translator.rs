@@ -0,0 +1,1618 @@
+use std::cmp::Ordering;
+
+use anyhow::bail;
+
+use crate::{
+    config::config_enums::DbType, error::Error, log_warn,
+    meta::mysql::mysql_col_type::MysqlColType, utils::sql_util::SqlUtil,
+};
+
+use super::{
+    ddl_data::DdlData,
+    ddl_definition::{
+        AlterTableOperation, ColumnDefinition, ColumnPosition, ConstraintDefinition,
+        IndexDefinition, IndexDefinitionKind,
+    },
+    ddl_statement::DdlStatement,
+    ddl_tokenizer::{TokenKind, TokenStream},
+    mysql_definition_parser::MysqlDefinitionParser,
+};
+
+const PG_IDENTITY: &str = "GENERATED BY DEFAULT AS IDENTITY";
+// longer identifiers are truncated by pg
+const PG_MAX_IDENTIFIER_LEN: usize = 63;
+
+/// Translate ddl parsed from source into the dialect of target.
+///
+/// Supported:
+///     mysql -> pg: ddl sqls
+///     mysql -> starrocks / doris / clickhouse: ddl sqls changing databases, tables and columns
+pub struct DdlTranslator {}
+
+impl DdlTranslator {
+    /// Returns the sqls to be executed in target, an empty result means the ddl
+    /// has no effect in target (e.g. ALTER TABLE ... ENGINE=InnoDB).
+    /// Returns Error::DdlTranslateError if the ddl can not be translated.
+    pub fn translate(ddl_data: &DdlData, dst_db_type: &DbType) -> anyhow::Result<Vec<String>> {
+        if ddl_data.db_type == *dst_db_type {
+            return Ok(vec![ddl_data.to_sql()]);
+        }
+
+        match (&ddl_data.db_type, dst_db_type) {
+            (DbType::Mysql, DbType::Pg) => MysqlToPgTranslator::new(ddl_data).translate(),
+            (DbType::Mysql, DbType::StarRocks | DbType::Doris | DbType::ClickHouse) => {
+                MysqlToOlapTranslator::new(ddl_data, dst_db_type).translate()
+            }
+            _ => bail! {Error::DdlTranslateError(format!(
+                "translating ddl from {} to {} is not supported, ddl: {}",
+                ddl_data.db_type, dst_db_type, ddl_data.query
+            ))},
+        }
+    }
+
+    pub fn mysql_to_starrocks_col_type(col_type: &MysqlColType, dst_db_type: &DbType) -> String {
+        let is_starrocks = *dst_db_type == DbType::StarRocks;
+        match col_type {
+            MysqlColType::TinyInt { unsigned: false } => "TINYINT".into(),
+            MysqlColType::TinyInt { unsigned: true } => "SMALLINT".into(),
+            MysqlColType::SmallInt { unsigned: false } => "SMALLINT".into(),
+            MysqlColType::SmallInt { unsigned: true } => "INT".into(),
+            MysqlColType::MediumInt { unsigned: false } => "INT".into(),
+            MysqlColType::MediumInt { unsigned: true } => "BIGINT".into(),
+            MysqlColType::Int { unsigned: false } => "INT".into(),
+            MysqlColType::Int { unsigned: true } => "BIGINT".into(),
+            MysqlColType::BigInt { unsigned: false } => "BIGINT".into(),
+            MysqlColType::BigInt { unsigned: true } => "LARGEINT".into(),
+
+            MysqlColType::Float => "FLOAT".into(),
+            MysqlColType::Double => "DOUBLE".into(),
+            MysqlColType::Decimal { precision, scale } => {
+                // https://docs.starrocks.io/docs/sql-reference/data-types/numeric/DECIMAL/
+                if *precision <= 38 {
+                    format!("DECIMAL({},{})", precision, scale)
+                } else {
+                    "STRING".into()
+                }
+            }
+
+            MysqlColType::Time { .. } => "VARCHAR(255)".into(),
+            MysqlColType::Year => "INT".into(),
+
+            // https://github.com/apache/doris-flink-connector/blob/master/flink-doris-connector/src/main/java/org/apache/doris/flink/tools/cdc/mysql/MysqlType.java
+            MysqlColType::Date { .. } => "DATE".into(),
+            MysqlColType::DateTime { precision, .. }
+            | MysqlColType::Timestamp { precision, .. } => {
+                if is_starrocks {
+                    "DATETIME".into()
+                } else {
+                    format!("DATETIME({})", precision)
+                }
+            }
+
+            MysqlColType::Char { length: v, .. } => format!("CHAR({})", v),
+            MysqlColType::Varchar { length: v, .. } => format!("VARCHAR({})", v),
+
+            MysqlColType::TinyText { .. }
+            | MysqlColType::MediumText { .. }
+            | MysqlColType::Text { .. }
+            | MysqlColType::LongText { .. } => "STRING".into(),
+
+            MysqlColType::Binary { .. }
+            | MysqlColType::VarBinary { .. }
+            | MysqlColType::TinyBlob
+            | MysqlColType::MediumBlob
+            | MysqlColType::Blob
+            | MysqlColType::LongBlob => {
+                if is_starrocks {
+                    "VARBINARY".into()
+                } else {
+                    "STRING".into()
+                }
+            }
+
+            MysqlColType::Bit => "BIGINT".into(),
+            MysqlColType::Set { .. } => "VARCHAR(255)".into(),
+            MysqlColType::Enum { .. } => "VARCHAR(255)".into(),
+            MysqlColType::Json => "JSON".into(),
+            MysqlColType::Unknown => "STRING".into(),
+        }
+    }
+
+    pub fn mysql_to_clickhouse_col_type(col_type: &MysqlColType) -> String {
+        match col_type {
+            MysqlColType::TinyInt { unsigned: false } => "Int8".into(),
+            MysqlColType::TinyInt { unsigned: true } => "UInt8".into(),
+            MysqlColType::SmallInt { unsigned: false } => "Int16".into(),
+            MysqlColType::SmallInt { unsigned: true } => "UInt16".into(),
+            MysqlColType::MediumInt { unsigned: false } => "Int32".into(),
+            MysqlColType::MediumInt { unsigned: true } => "UInt32".into(),
+            MysqlColType::Int { unsigned: false } => "Int32".into(),
+            MysqlColType::Int { unsigned: true } => "UInt32".into(),
+            MysqlColType::BigInt { unsigned: false } => "Int64".into(),
+            MysqlColType::BigInt { unsigned: true } => "UInt64".into(),
+
+            MysqlColType::Float => "Float32".into(),
+            MysqlColType::Double => "Float64".into(),
+            MysqlColType::Decimal { precision, scale } => {
+                format!("Decimal({},{})", precision, scale)
+            }
+
+            MysqlColType::Time { .. } => "String".into(),
+            MysqlColType::Date { .. } => "Date32".into(),
+            MysqlColType::DateTime { .. } => "DateTime64(6)".into(),
+            MysqlColType::Timestamp { .. } => "DateTime64(6)".into(),
+            MysqlColType::Year => "Int32".into(),
+
+            MysqlColType::Char { .. }
+            | MysqlColType::Varchar { .. }
+            | MysqlColType::TinyText { .. }
+            | MysqlColType::MediumText { .. }
+            | MysqlColType::Text { .. }
+            | MysqlColType::LongText { .. } => "String".into(),
+
+            MysqlColType::Binary { .. }
+            | MysqlColType::VarBinary { .. }
+            | MysqlColType::TinyBlob
+            | MysqlColType::MediumBlob
+            | MysqlColType::Blob
+            | MysqlColType::LongBlob => "String".into(),
+
+            MysqlColType::Bit => "UInt64".into(),
+            MysqlColType::Set { .. } => "String".into(),
+            MysqlColType::Enum { .. } => "String".into(),
+            MysqlColType::Json => "String".into(),
+            MysqlColType::Unknown => "String".into(),
+        }
+    }
+
+    /// Compare column types of starrocks / doris / clickhouse, e.g. Less for
+    /// BIGINT -> INT, VARCHAR(255) -> VARCHAR(64), Nullable(Int64) -> Nullable(Int32).
+    /// Returns None if the types are not comparable, e.g. INT -> VARCHAR(64).
+    pub fn compare_olap_col_type(old_type: &str, new_type: &str) -> Option<Ordering> {
+        let (old_family, old_ranks) = Self::get_olap_col_type_ranks(old_type)?;
+        let (new_family, new_ranks) = Self::get_olap_col_type_ranks(new_type)?;
+        if old_family != new_family {
+            return None;
+        }
+
+        let mut res = Ordering::Equal;
+        for (old, new) in old_ranks.iter().zip(new_ranks.iter()) {
+            match new.cmp(old) {
+                Ordering::Less => return Some(Ordering::Less),
+                Ordering::Greater => res = Ordering::Greater,
+                Ordering::Equal => {}
+            }
+        }
+        Some(res)
+    }
+
+    /// (family, ranks), a type is wider than another of the same family if all ranks are not less
+    fn get_olap_col_type_ranks(col_type: &str) -> Option<(&'static str, Vec<u64>)> {
+        let mut col_type = col_type.trim().to_lowercase();
+        // clickhouse: Nullable(Int32), LowCardinality(String)
+        for wrapper in ["nullable(", "lowcardinality("] {
+            if col_type.starts_with(wrapper) && col_type.ends_with(')') {
+                col_type = col_type[wrapper.len()..col_type.len() - 1].to_string();
+            }
+        }
+
+        let (name, args) = match col_type.split_once('(') {
+            Some((name, args)) => (
+                name.trim(),
+                args.trim_end_matches(')')
+                    .split(',')
+                    .filter_map(|i| i.trim().parse::<u64>().ok())
+                    .collect::<Vec<u64>>(),
+            ),
+            None => (col_type.as_str(), Vec::new()),
+        };
+        let arg = |n: usize, default: u64| args.get(n).cloned().unwrap_or(default);
+
+        let res = match name {
+            // display width of int types is ignored, e.g. int(11)
+            "tinyint" | "int8" | "uint8" => ("int", vec![1]),
+            "smallint" | "int16" | "uint16" => ("int", vec![2]),
+            "int" | "integer" | "int32" | "uint32" => ("int", vec![3]),
+            "bigint" | "int64" | "uint64" => ("int", vec![4]),
+            "largeint" | "int128" | "uint128" => ("int", vec![5]),
+            "int256" | "uint256" => ("int", vec![6]),
+            "float" | "float32" => ("float", vec![1]),
+            "double" | "float64" => ("float", vec![2]),
+            // ranks: integer digits, scale
+            "decimal" | "decimalv3" => {
+                let (precision, scale) = (arg(0, 10), arg(1, 0));
+                ("decimal", vec![precision.saturating_sub(scale), scale])
+            }
+            "char" | "varchar" | "fixedstring" => ("string", vec![arg(0, 1)]),
+            "string" | "text" => ("string", vec![u64::MAX]),
+            "datetime" | "datetime64" => ("datetime", vec![arg(0, 0)]),
+            _ => return None,
+        };
+        Some(res)
+    }
+
+    /// https://www.postgresql.org/docs/current/datatype.html
+    pub fn mysql_to_pg_col_type(column: &ColumnDefinition) -> anyhow::Result<String> {
+        let args = column.get_num_args();
+        let unsigned = column.unsigned;
+        let pg_type = match column.data_type.as_str() {
+            "tinyint" => "smallint".into(),
+            "smallint" if unsigned => "integer".into(),
+            "smallint" => "smallint".into(),
+            "mediumint" => "integer".into(),
+            "int" if unsigned => "bigint".into(),
+            "int" => "integer".into(),
+            // identity cols must be smallint / integer / bigint
+            "bigint" if unsigned && !column.auto_increment => "numeric(20)".into(),
+            "bigint" => "bigint".into(),
+
+            "float" => "real".into(),
+            "double" => "double precision".into(),
+            "decimal" => match args.as_slice() {
+                [precision, scale] => format!("numeric({},{})", precision, scale),
+                [precision] => format!("numeric({})", precision),
+                _ => "numeric(10,0)".into(),
+            },
+
+            "char" => format!("char({})", args.first().unwrap_or(&1)),
+            "varchar" => match args.first() {
+                Some(length) => format!("varchar({})", length),
+                None => "varchar".into(),
+            },
+            "tinytext" | "text" | "mediumtext" | "longtext" => "text".into(),
+
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => {
+                "bytea".into()
+            }
+
+            "date" => "date".into(),
+            "time" => format!("time({})", args.first().unwrap_or(&0)),
+            "datetime" | "timestamp" => format!("timestamp({})", args.first().unwrap_or(&0)),
+            "year" => "smallint".into(),
+
+            "bit" => format!("bit({})", args.first().unwrap_or(&1)),
+            "json" => "json".into(),
+            "enum" | "set" => "varchar(255)".into(),
+
+            _ => bail! {Error::DdlTranslateError(format!(
+                "unsupported mysql type: {}, column: {}",
+                column.column_type, column.column_name
+            ))},
+        };
+        Ok(pg_type)
+    }
+}
+
+struct MysqlToPgTranslator<'a> {
+    ddl_data: &'a DdlData,
+}
+
+impl<'a> MysqlToPgTranslator<'a> {
+    fn new(ddl_data: &'a DdlData) -> Self {
+        Self { ddl_data }
+    }
+
+    fn translate(&self) -> anyhow::Result<Vec<String>> {
+        let mut sqls = Vec::new();
+        match &self.ddl_data.statement {
+            DdlStatement::CreateDatabase(s) => {
+                let if_not_exists = if s.if_not_exists {
+                    " IF NOT EXISTS"
+                } else {
+                    ""
+                };
+                sqls.push(format!(
+                    "CREATE SCHEMA{} {}",
+                    if_not_exists,
+                    Self::escape(&s.db)
+                ));
+            }
+
+            DdlStatement::DropDatabase(s) => {
+                let if_exists = if s.if_exists { " IF EXISTS" } else { "" };
+                sqls.push(format!(
+                    "DROP SCHEMA{} {} CASCADE",
+                    if_exists,
+                    Self::escape(&s.db)
+                ));
+            }
+
+            // charset / collation of database has no equivalent in pg schema
+            DdlStatement::AlterDatabase(_) => {}
+
+            DdlStatement::MysqlCreateTable(s) => {
+                let tb = self.get_tb(&s.db, &s.tb);
+                sqls.extend(self.translate_create_table(
+                    &tb,
+                    &s.tb,
+                    s.if_not_exists,
+                    &s.unparsed,
+                )?);
+            }
+
+            DdlStatement::MysqlAlterTable(s) => {
+                let tb = self.get_tb(&s.db, &s.tb);
+                let operations = if self.ddl_data.alter_operations.is_empty() {
+                    // parse again to get the error
+                    MysqlDefinitionParser::parse_alter_table(&s.unparsed)?
+                } else {
+                    self.ddl_data.alter_operations.clone()
+                };
+                for operation in operations {
+                    sqls.extend(self.translate_alter_operation(&tb, &s.tb, operation)?);
+                }
+            }
+
+            DdlStatement::MysqlAlterTableRename(s) => {
+                if !s.unparsed.trim().is_empty() {
+                    bail! {self.untranslatable("ALTER TABLE ... RENAME combined with other operations")}
+                }
+                sqls.extend(self.translate_rename_table(&s.db, &s.tb, &s.new_db, &s.new_tb));
+            }
+
+            DdlStatement::RenameTable(s) => {
+                sqls.extend(self.translate_rename_table(
+                    &s.schema,
+                    &s.tb,
+                    &s.new_schema,
+                    &s.new_tb,
+                ));
+            }
+
+            DdlStatement::RenameMultiTable(s) => {
+                for (i, (schema, tb)) in s.schema_tbs.iter().enumerate() {
+                    let (new_schema, new_tb) = &s.new_schema_tbs[i];
+                    sqls.extend(self.translate_rename_table(schema, tb, new_schema, new_tb));
+                }
+            }
+
+            DdlStatement::DropTable(s) => {
+                let if_exists = if s.if_exists { " IF EXISTS" } else { "" };
+                sqls.push(format!(
+                    "DROP TABLE{} {}",
+                    if_exists,
+                    self.get_tb(&s.schema, &s.tb)
+                ));
+            }
+
+            DdlStatement::DropMultiTable(s) => {
+                let if_exists = if s.if_exists { " IF EXISTS" } else { "" };
+                let tbs: Vec<String> = s
+                    .schema_tbs
+                    .iter()
+                    .map(|(schema, tb)| self.get_tb(schema, tb))
+                    .collect();
+                sqls.push(format!("DROP TABLE{} {}", if_exists, tbs.join(", ")));
+            }
+
+            DdlStatement::MysqlTruncateTable(s) => {
+                sqls.push(format!("TRUNCATE TABLE {}", self.get_tb(&s.db, &s.tb)));
+            }
+
+            DdlStatement::MysqlCreateIndex(s) => {
+                let index = MysqlDefinitionParser::parse_create_index(
+                    &s.index_kind,
+                    &s.index_name,
+                    &s.unparsed,
+                )?;
+                let tb = self.get_tb(&s.db, &s.tb);
+                sqls.push(self.translate_add_index(&tb, &s.tb, &index)?);
+            }
+
+            DdlStatement::MysqlDropIndex(s) => {
+                let tb = self.get_tb(&s.db, &s.tb);
+                if s.index_name.eq_ignore_ascii_case("primary") {
+                    sqls.push(Self::drop_primary_key_sql(&tb, &s.tb));
+                } else {
+                    sqls.push(self.get_index_sql(
+                        &s.db,
+                        &s.tb,
+                        &s.index_name,
+                        "DROP INDEX %I.%I",
+                        &[],
+                    ));
+                }
+            }
+
+            _ => bail! {self.untranslatable("unsupported ddl type")},
+        }
+        Ok(sqls)
+    }
+
+    fn translate_create_table(
+        &self,
+        tb: &str,
+        tb_name: &str,
+        if_not_exists: bool,
+        unparsed: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let definition = MysqlDefinitionParser::parse_create_table(unparsed)?;
+        let primary_key = definition.get_primary_key();
+
+        let mut items = Vec::new();
+        let mut post_sqls = Vec::new();
+        for column in definition.columns.iter() {
+            items.push(self.translate_column(column, false)?);
+            post_sqls.extend(self.translate_column_extra(tb, tb_name, column));
+        }
+
+        if !primary_key.is_empty() {
+            items.push(format!("PRIMARY KEY ({})", Self::escape_cols(&primary_key)));
+        }
+
+        for index in definition.indexes.iter() {
+            if index.index_kind != IndexDefinitionKind::Primary {
+                post_sqls.push(self.translate_add_index(tb, tb_name, index)?);
+            }
+        }
+
+        for constraint in definition.constraints.iter() {
+            items.push(self.translate_constraint(constraint)?);
+        }
+
+        if let Some(comment) = &definition.comment {
+            post_sqls.push(format!(
+                "COMMENT ON TABLE {} IS {}",
+                tb,
+                Self::quote_str(comment)
+            ));
+        }
+
+        let if_not_exists = if if_not_exists { " IF NOT EXISTS" } else { "" };
+        let mut sqls = vec![format!(
+            "CREATE TABLE{} {} ({})",
+            if_not_exists,
+            tb,
+            items.join(", ")
+        )];
+        sqls.extend(post_sqls);
+        Ok(sqls)
+    }
+
+    fn translate_alter_operation(
+        &self,
+        tb: &str,
+        tb_name: &str,
+        operation: AlterTableOperation,
+    ) -> anyhow::Result<Vec<String>> {
+        let alter_table = format!("ALTER TABLE {}", tb);
+        let mut sqls = Vec::new();
+        warn_column_position(self.ddl_data, &operation, "pg");
+        match operation {
+            AlterTableOperation::AddColumn { column, .. } => {
+                sqls.push(format!(
+                    "{} ADD COLUMN {}",
+                    alter_table,
+                    self.translate_column(&column, true)?
+                ));
+                sqls.extend(self.translate_column_extra(tb, tb_name, &column));
+            }
+
+            AlterTableOperation::DropColumn { column_name } => {
+                sqls.push(format!(
+                    "{} DROP COLUMN {}",
+                    alter_table,
+                    Self::escape(&column_name)
+                ));
+            }
+
+            AlterTableOperation::ModifyColumn { column, .. } => {
+                sqls.extend(self.translate_modify_column(tb, tb_name, &column)?);
+            }
+
+            AlterTableOperation::ChangeColumn {
+                old_column_name,
+                column,
+                ..
+            } => {
+                if old_column_name != column.column_name {
+                    sqls.push(format!(
+                        "{} RENAME COLUMN {} TO {}",
+                        alter_table,
+                        Self::escape(&old_column_name),
+                        Self::escape(&column.column_name)
+                    ));
+                }
+                sqls.extend(self.translate_modify_column(tb, tb_name, &column)?);
+            }
+
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                sqls.push(format!(
+                    "{} RENAME COLUMN {} TO {}",
+                    alter_table,
+                    Self::escape(&old_column_name),
+                    Self::escape(&new_column_name)
+                ));
+            }
+
+            AlterTableOperation::AlterColumnType { column } => {
+                let col_type = DdlTranslator::mysql_to_pg_col_type(&column)?;
+                sqls.push(format!(
+                    "{} ALTER COLUMN {} TYPE {} USING {}::{}",
+                    alter_table,
+                    Self::escape(&column.column_name),
+                    col_type,
+                    Self::escape(&column.column_name),
+                    col_type
+                ));
+            }
+
+            AlterTableOperation::SetColumnNotNull { column_name } => {
+                sqls.push(format!(
+                    "{} ALTER COLUMN {} SET NOT NULL",
+                    alter_table,
+                    Self::escape(&column_name)
+                ));
+            }
+
+            AlterTableOperation::DropColumnNotNull { column_name } => {
+                sqls.push(format!(
+                    "{} ALTER COLUMN {} DROP NOT NULL",
+                    alter_table,
+                    Self::escape(&column_name)
+                ));
+            }
+
+            AlterTableOperation::SetColumnDefault {
+                column_name,
+                default_value,
+            } => {
+                sqls.push(format!(
+                    "{} ALTER COLUMN {} SET DEFAULT {}",
+                    alter_table,
+                    Self::escape(&column_name),
+                    Self::translate_default_value(&default_value)?
+                ));
+            }
+
+            AlterTableOperation::DropColumnDefault { column_name } => {
+                sqls.push(format!(
+                    "{} ALTER COLUMN {} DROP DEFAULT",
+                    alter_table,
+                    Self::escape(&column_name)
+                ));
+            }
+
+            AlterTableOperation::AddIndex { index } => {
+                sqls.push(self.translate_add_index(tb, tb_name, &index)?);
+            }
+
+            AlterTableOperation::DropIndex { index_name } => {
+                let (schema, _) = self.ddl_data.get_schema_tb();
+                sqls.push(self.get_index_sql(
+                    &schema,
+                    tb_name,
+                    &index_name,
+                    "DROP INDEX %I.%I",
+                    &[],
+                ));
+            }
+
+            AlterTableOperation::RenameIndex {
+                old_index_name,
+                new_index_name,
+            } => {
+                let (schema, _) = self.ddl_data.get_schema_tb();
+                sqls.push(self.get_index_sql(
+                    &schema,
+                    tb_name,
+                    &old_index_name,
+                    "ALTER INDEX %I.%I RENAME TO %I",
+                    &[&Self::get_index_name(tb_name, &new_index_name)],
+                ));
+            }
+
+            AlterTableOperation::DropPrimaryKey => {
+                sqls.push(Self::drop_primary_key_sql(tb, tb_name));
+            }
+
+            AlterTableOperation::AddConstraint { constraint } => {
+                sqls.push(format!(
+                    "{} ADD {}",
+                    alter_table,
+                    self.translate_constraint(&constraint)?
+                ));
+            }
+
+            AlterTableOperation::DropConstraint { constraint_name } => {
+                sqls.push(format!(
+                    "{} DROP CONSTRAINT {}",
+                    alter_table,
+                    Self::escape(&constraint_name)
+                ));
+            }
+
+            AlterTableOperation::RenameConstraint {
+                old_constraint_name,
+                new_constraint_name,
+            } => {
+                sqls.push(format!(
+                    "{} RENAME CONSTRAINT {} TO {}",
+                    alter_table,
+                    Self::escape(&old_constraint_name),
+                    Self::escape(&new_constraint_name)
+                ));
+            }
+
+            AlterTableOperation::SetTableComment { comment } => {
+                sqls.push(format!(
+                    "COMMENT ON TABLE {} IS {}",
+                    tb,
+                    Self::quote_str(&comment)
+                ));
+            }
+
+            // ENGINE=InnoDB, AUTO_INCREMENT=100, ALGORITHM=INPLACE ...
+            AlterTableOperation::TableOption { option } => {
+                if option.to_lowercase().starts_with("partition") {
+                    bail! {self.untranslatable("partition operations")}
+                }
+            }
+        }
+        Ok(sqls)
+    }
+
+    fn translate_modify_column(
+        &self,
+        tb: &str,
+        tb_name: &str,
+        column: &ColumnDefinition,
+    ) -> anyhow::Result<Vec<String>> {
+        if column.generated.is_some() {
+            bail! {self.untranslatable("modifying generated column")}
+        }
+
+        // mysql MODIFY / CHANGE replaces the whole column definition
+        let alter_column = format!(
+            "ALTER TABLE {} ALTER COLUMN {}",
+            tb,
+            Self::escape(&column.column_name)
+        );
+        let col_type = DdlTranslator::mysql_to_pg_col_type(column)?;
+        let mut sqls = vec![format!(
+            "{} TYPE {} USING {}::{}",
+            alter_column,
+            col_type,
+            Self::escape(&column.column_name),
+            col_type
+        )];
+
+        if column.is_nullable() {
+            sqls.push(format!("{} DROP NOT NULL", alter_column));
+        } else {
+            sqls.push(format!("{} SET NOT NULL", alter_column));
+        }
+
+        match &column.default_value {
+            Some(default_value) => sqls.push(format!(
+                "{} SET DEFAULT {}",
+                alter_column,
+                Self::translate_default_value(default_value)?
+            )),
+            None if !column.auto_increment => sqls.push(format!("{} DROP DEFAULT", alter_column)),
+            None => {}
+        }
+
+        let comment = match &column.comment {
+            Some(comment) => Self::quote_str(comment),
+            None => "NULL".to_string(),
+        };
+        sqls.push(format!(
+            "COMMENT ON COLUMN {}.{} IS {}",
+            tb,
+            Self::escape(&column.column_name),
+            comment
+        ));
+
+        if column.unique_key {
+            sqls.push(self.translate_unique_column(tb, tb_name, column));
+        }
+        Ok(sqls)
+    }
+
+    /// column definition inside CREATE TABLE / ALTER TABLE ADD COLUMN
+    fn translate_column(
+        &self,
+        column: &ColumnDefinition,
+        inline_pk: bool,
+    ) -> anyhow::Result<String> {
+        let mut sql = format!(
+            "{} {}",
+            Self::escape(&column.column_name),
+            DdlTranslator::mysql_to_pg_col_type(column)?
+        );
+
+        if let Some(expr) = &column.generated {
+            // pg (before 18) supports STORED generated columns only, since the values of
+            // generated columns are computed by target, VIRTUAL is translated to STORED
+            sql = format!(
+                "{} GENERATED ALWAYS AS ({}) STORED",
+                sql,
+                Self::translate_expression(expr)?
+            );
+        } else if column.auto_increment {
+            sql = format!("{} {}", sql, PG_IDENTITY);
+        } else if let Some(default_value) = &column.default_value {
+            sql = format!(
+                "{} DEFAULT {}",
+                sql,
+                Self::translate_default_value(default_value)?
+            );
+        }
+
+        if !column.is_nullable() {
+            sql = format!("{} NOT NULL", sql);
+        }
+        if inline_pk && column.primary_key {
+            sql = format!("{} PRIMARY KEY", sql);
+        }
+        // ON UPDATE CURRENT_TIMESTAMP is ignored since the updated values come from source,
+        // CHARACTER SET / COLLATE are ignored since pg uses database level encoding
+        Ok(sql)
+    }
+
+    /// sqls to be executed after the column created: comment, unique index
+    fn translate_column_extra(
+        &self,
+        tb: &str,
+        tb_name: &str,
+        column: &ColumnDefinition,
+    ) -> Vec<String> {
+        let mut sqls = Vec::new();
+        if let Some(comment) = &column.comment {
+            sqls.push(format!(
+                "COMMENT ON COLUMN {}.{} IS {}",
+                tb,
+                Self::escape(&column.column_name),
+                Self::quote_str(comment)
+            ));
+        }
+        if column.unique_key {
+            sqls.push(self.translate_unique_column(tb, tb_name, column));
+        }
+        sqls
+    }
+
+    fn translate_unique_column(
+        &self,
+        tb: &str,
+        tb_name: &str,
+        column: &ColumnDefinition,
+    ) -> String {
+        // in mysql, the name of the unique index defined in column is the column name
+        format!(
+            "CREATE UNIQUE INDEX {} ON {} ({})",
+            Self::escape(&Self::get_index_name(tb_name, &column.column_name)),
+            tb,
+            Self::escape(&column.column_name)
+        )
+    }
+
+    fn translate_add_index(
+        &self,
+        tb: &str,
+        tb_name: &str,
+        index: &IndexDefinition,
+    ) -> anyhow::Result<String> {
+        let unique = match index.index_kind {
+            IndexDefinitionKind::Primary => {
+                return Ok(format!(
+                    "ALTER TABLE {} ADD PRIMARY KEY ({})",
+                    tb,
+                    Self::escape_cols(&index.columns)
+                ))
+            }
+            IndexDefinitionKind::Unique => "UNIQUE ",
+            IndexDefinitionKind::Normal => "",
+            IndexDefinitionKind::FullText | IndexDefinitionKind::Spatial => {
+                bail! {self.untranslatable(&format!("{} index", index.index_kind))}
+            }
+        };
+
+        // in mysql, the default name of an index is its first column
+        let index_name = match &index.index_name {
+            Some(name) => name.clone(),
+            None => index.columns[0].clone(),
+        };
+        Ok(format!(
+            "CREATE {}INDEX {} ON {} ({})",
+            unique,
+            Self::escape(&Self::get_index_name(tb_name, &index_name)),
+            tb,
+            Self::escape_cols(&index.columns)
+        ))
+    }
+
+    fn translate_constraint(&self, constraint: &ConstraintDefinition) -> anyhow::Result<String> {
+        let (name, sql) = match constraint {
+            ConstraintDefinition::ForeignKey {
+                name,
+                columns,
+                ref_schema,
+                ref_tb,
+                ref_columns,
+                options,
+            } => {
+                let mut sql = format!(
+                    "FOREIGN KEY ({}) REFERENCES {} ({})",
+                    Self::escape_cols(columns),
+                    self.get_tb(ref_schema, ref_tb),
+                    Self::escape_cols(ref_columns)
+                );
+                if !options.is_empty() {
+                    sql = format!("{} {}", sql, options);
+                }
+                (name, sql)
+            }
+
+            ConstraintDefinition::Check { name, expression } => (
+                name,
+                format!("CHECK ({})", Self::translate_expression(expression)?),
+            ),
+        };
+
+        match name {
+            Some(name) => Ok(format!("CONSTRAINT {} {}", Self::escape(name), sql)),
+            None => Ok(sql),
+        }
+    }
+
+    fn translate_rename_table(
+        &self,
+        schema: &str,
+        tb: &str,
+        new_schema: &str,
+        new_tb: &str,
+    ) -> Vec<String> {
+        let schema = self.get_schema(schema);
+        let new_schema = self.get_schema(new_schema);
+        let mut sqls = Vec::new();
+        if schema != new_schema {
+            sqls.push(format!(
+                "ALTER TABLE {} SET SCHEMA {}",
+                self.get_tb(&schema, tb),
+                Self::escape(&new_schema)
+            ));
+        }
+        if tb != new_tb {
+            sqls.push(format!(
+                "ALTER TABLE {} RENAME TO {}",
+                self.get_tb(&new_schema, tb),
+                Self::escape(new_tb)
+            ));
+        }
+        sqls
+    }
+
+    fn drop_primary_key_sql(tb: &str, tb_name: &str) -> String {
+        // default name of primary key constraint in pg: {tb}_pkey
+        format!(
+            "ALTER TABLE {} DROP CONSTRAINT {}",
+            tb,
+            Self::escape(&format!("{}_pkey", tb_name))
+        )
+    }
+
+    /// DEFAULT / ON UPDATE values, refer to MysqlDefinitionParser::parse_default_value
+    fn translate_default_value(default_value: &str) -> anyhow::Result<String> {
+        let lower = default_value.to_lowercase();
+        if lower.starts_with('(') {
+            return Self::translate_expression(default_value);
+        }
+        if lower.starts_with("current_timestamp") || lower.starts_with("now(") {
+            return Ok(default_value.to_string());
+        }
+
+        // charset introducer / bit / hex literals: _utf8mb4'abc', b'01', x'ff'
+        if let Some(quote_pos) = default_value.find('\'') {
+            let prefix = &lower[..quote_pos];
+            let literal = &default_value[quote_pos..];
+            return match prefix {
+                "" => Ok(Self::translate_str_literal(literal)),
+                "b" => Ok(format!("B{}", literal)),
+                "x" => Ok(format!("'\\x{}'::bytea", literal.trim_matches('\''))),
+                _ if prefix.starts_with('_') => Ok(Self::translate_str_literal(literal)),
+                _ => bail! {Error::DdlTranslateError(format!(
+                    "unsupported default value: {}",
+                    default_value
+                ))},
+            };
+        }
+
+        match lower.as_str() {
+            "true" | "false" => Ok(if lower == "true" { "1" } else { "0" }.to_string()),
+            _ => Ok(default_value.to_string()),
+        }
+    }
+
+    /// mysql string literal -> pg string literal
+    fn translate_str_literal(literal: &str) -> String {
+        let inner = &literal[1..literal.len() - 1];
+        if inner.contains('\\') {
+            // mysql treats backslash as escape char by default
+            format!("E'{}'", inner)
+        } else {
+            literal.to_string()
+        }
+    }
+
+    /// replace mysql identifier quotes in expressions: `a` + 1 -> "a" + 1
+    fn translate_expression(expr: &str) -> anyhow::Result<String> {
+        let stream = TokenStream::new(expr, '`')?;
+        let mut result = String::new();
+        let mut last_end = 0;
+        for token in stream.tokens.iter() {
+            result.push_str(&expr[last_end..token.start]);
+            if token.kind == TokenKind::QuotedIdentifier {
+                result.push_str(&Self::escape(&token.to_identifier()));
+            } else if token.kind == TokenKind::StringLiteral {
+                result.push_str(&Self::translate_str_literal(
+                    &token.text[token.text.find('\'').unwrap_or(0)..],
+                ));
+            } else {
+                result.push_str(&token.text);
+            }
+            last_end = token.end;
+        }
+        result.push_str(&expr[last_end..]);
+        Ok(result)
+    }
+
+    fn get_schema(&self, schema: &str) -> String {
+        if schema.is_empty() {
+            self.ddl_data.default_schema.clone()
+        } else {
+            schema.to_string()
+        }
+    }
+
+    fn get_tb(&self, schema: &str, tb: &str) -> String {
+        let schema = self.get_schema(schema);
+        if schema.is_empty() {
+            Self::escape(tb)
+        } else {
+            format!("{}.{}", Self::escape(&schema), Self::escape(tb))
+        }
+    }
+
+    /// index names are unique per table in mysql but per schema in pg,
+    /// so indexes are named as: {tb}_{index_name}, names longer than the pg identifier limit
+    /// are truncated and suffixed by the hash of the full name to keep them unique
+    fn get_index_name(tb_name: &str, index_name: &str) -> String {
+        let name = format!("{}_{}", tb_name, index_name);
+        if name.len() <= PG_MAX_IDENTIFIER_LEN {
+            return name;
+        }
+
+        // fnv-1a, stable across runs, since the name is built again to drop / rename the index
+        let hash = name.bytes().fold(0x811c9dc5u32, |hash, b| {
+            (hash ^ b as u32).wrapping_mul(0x01000193)
+        });
+        let suffix = format!("_{:08x}", hash);
+        let mut end = PG_MAX_IDENTIFIER_LEN - suffix.len();
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}{}", &name[..end], suffix)
+    }
+
+    /// indexes created by other ways (e.g. struct migration) keep their mysql names,
+    /// so find the index of the table by both names when executing the action,
+    /// action is a format string of pg with args: schema, index_name, extra_args...
+    fn get_index_sql(
+        &self,
+        schema: &str,
+        tb_name: &str,
+        index_name: &str,
+        action: &str,
+        extra_args: &[&str],
+    ) -> String {
+        let schema = self.get_schema(schema);
+        let tb = self.get_tb(&schema, tb_name);
+        let translated_name = Self::get_index_name(tb_name, index_name);
+        let mut args = vec![Self::quote_str(&schema), "index_name".to_string()];
+        args.extend(extra_args.iter().map(|i| Self::quote_str(i)));
+        format!(
+            "DO $$ DECLARE index_name text; BEGIN \
+            SELECT c.relname INTO index_name FROM pg_index i JOIN pg_class c ON c.oid = i.indexrelid \
+            WHERE i.indrelid = {}::regclass AND c.relname IN ({}, {}) \
+            ORDER BY c.relname = {} DESC LIMIT 1; \
+            IF index_name IS NULL THEN RAISE EXCEPTION 'index % not found on table %', {}, {}; END IF; \
+            EXECUTE format({}, {}); END $$",
+            Self::quote_str(&tb),
+            Self::quote_str(&translated_name),
+            Self::quote_str(index_name),
+            Self::quote_str(&translated_name),
+            Self::quote_str(index_name),
+            Self::quote_str(&tb),
+            Self::quote_str(action),
+            args.join(", ")
+        )
+    }
+
+    fn escape(identifier: &str) -> String {
+        SqlUtil::escape_by_db_type(identifier, &DbType::Pg)
+    }
+
+    fn escape_cols(cols: &[String]) -> String {
+        cols.iter()
+            .map(|i| Self::escape(i))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    fn quote_str(str: &str) -> String {
+        format!("'{}'", str.replace('\'', "''"))
+    }
+
+    fn untranslatable(&self, reason: &str) -> Error {
+        Error::DdlTranslateError(format!(
+            "{} can not be translated from mysql to pg, ddl: {}",
+            reason, self.ddl_data.query
+        ))
+    }
+}
+
+/// column position (FIRST / AFTER) is not supported by targets other than mysql
+fn warn_column_position(ddl_data: &DdlData, operation: &AlterTableOperation, dst: &str) {
+    let (column_name, position) = match operation {
+        AlterTableOperation::AddColumn { column, position }
+        | AlterTableOperation::ModifyColumn { column, position }
+        | AlterTableOperation::ChangeColumn {
+            column, position, ..
+        } => (&column.column_name, position),
+        _ => return,
+    };
+
+    let position = match position {
+        Some(ColumnPosition::First) => "FIRST".to_string(),
+        Some(ColumnPosition::After(col)) => format!("AFTER {}", col),
+        None => return,
+    };
+    log_warn!(
+        "column position is not supported by {} and ignored, column: {}, position: {}, ddl: {}",
+        dst,
+        column_name,
+        position,
+        ddl_data.query
+    );
+}
+
+/// Tables in starrocks / doris / clickhouse are created by struct migration since their
+/// key models and engines can not be derived from mysql ddl, indexes / constraints / comments
+/// are managed differently, so only ddls of databases, tables and columns are translated.
+struct MysqlToOlapTranslator<'a> {
+    ddl_data: &'a DdlData,
+    dst_db_type: &'a DbType,
+}
+
+impl<'a> MysqlToOlapTranslator<'a> {
+    fn new(ddl_data: &'a DdlData, dst_db_type: &'a DbType) -> Self {
+        Self {
+            ddl_data,
+            dst_db_type,
+        }
+    }
+
+    fn translate(&self) -> anyhow::Result<Vec<String>> {
+        let mut sqls = Vec::new();
+        match &self.ddl_data.statement {
+            DdlStatement::CreateDatabase(s) => {
+                let if_not_exists = if s.if_not_exists {
+                    " IF NOT EXISTS"
+                } else {
+                    ""
+                };
+                sqls.push(format!(
+                    "CREATE DATABASE{} {}",
+                    if_not_exists,
+                    Self::escape(&s.db)
+                ));
+            }
+
+            DdlStatement::DropDatabase(s) => {
+                let if_exists = if s.if_exists { " IF EXISTS" } else { "" };
+                sqls.push(format!(
+                    "DROP DATABASE{} {}",
+                    if_exists,
+                    Self::escape(&s.db)
+                ));
+            }
+
+            DdlStatement::AlterDatabase(_) => {}
+
+            DdlStatement::MysqlCreateTable(_) => {
+                bail! {self.untranslatable("CREATE TABLE, it should be created by struct migration")}
+            }
+
+            DdlStatement::MysqlAlterTable(s) => {
+                let tb = self.get_tb(&s.db, &s.tb);
+                let operations = if self.ddl_data.alter_operations.is_empty() {
+                    // parse again to get the error
+                    MysqlDefinitionParser::parse_alter_table(&s.unparsed)?
+                } else {
+                    self.ddl_data.alter_operations.clone()
+                };
+                for operation in operations {
+                    sqls.extend(self.translate_alter_operation(&tb, operation)?);
+                }
+            }
+
+            DdlStatement::MysqlAlterTableRename(s) => {
+                if !s.unparsed.trim().is_empty() {
+                    bail! {self.untranslatable("ALTER TABLE ... RENAME combined with other operations")}
+                }
+                sqls.push(self.translate_rename_table(&s.db, &s.tb, &s.new_db, &s.new_tb)?);
+            }
+
+            DdlStatement::RenameTable(s) => {
+                sqls.push(self.translate_rename_table(
+                    &s.schema,
+                    &s.tb,
+                    &s.new_schema,
+                    &s.new_tb,
+                )?);
+            }
+
+            DdlStatement::RenameMultiTable(s) => {
+                for (i, (schema, tb)) in s.schema_tbs.iter().enumerate() {
+                    let (new_schema, new_tb) = &s.new_schema_tbs[i];
+                    sqls.push(self.translate_rename_table(schema, tb, new_schema, new_tb)?);
+                }
+            }
+
+            DdlStatement::DropTable(s) => {
+                let if_exists = if s.if_exists { " IF EXISTS" } else { "" };
+                sqls.push(format!(
+                    "DROP TABLE{} {}",
+                    if_exists,
+                    self.get_tb(&s.schema, &s.tb)
+                ));
+            }
+
+            DdlStatement::DropMultiTable(s) => {
+                // starrocks / doris / clickhouse drop one table per statement
+                let if_exists = if s.if_exists { " IF EXISTS" } else { "" };
+                for (schema, tb) in s.schema_tbs.iter() {
+                    sqls.push(format!(
+                        "DROP TABLE{} {}",
+                        if_exists,
+                        self.get_tb(schema, tb)
+                    ));
+                }
+            }
+
+            DdlStatement::MysqlTruncateTable(s) => {
+                sqls.push(format!("TRUNCATE TABLE {}", self.get_tb(&s.db, &s.tb)));
+            }
+
+            // secondary indexes are not replicated
+            DdlStatement::MysqlCreateIndex(_) | DdlStatement::MysqlDropIndex(_) => {}
+
+            _ => bail! {self.untranslatable("unsupported ddl type")},
+        }
+        Ok(sqls)
+    }
+
+    fn translate_alter_operation(
+        &self,
+        tb: &str,
+        operation: AlterTableOperation,
+    ) -> anyhow::Result<Vec<String>> {
+        let alter_table = format!("ALTER TABLE {}", tb);
+        let mut sqls = Vec::new();
+        warn_column_position(self.ddl_data, &operation, &self.dst_db_type.to_string());
+        match operation {
+            AlterTableOperation::AddColumn { column, .. } => {
+                // existing rows have no value for the new column
+                sqls.push(format!(
+                    "{} ADD COLUMN {} {}",
+                    alter_table,
+                    Self::escape(&column.column_name),
+                    self.get_col_type(&column, true)
+                ));
+            }
+
+            AlterTableOperation::DropColumn { column_name } => {
+                sqls.push(format!(
+                    "{} DROP COLUMN {}",
+                    alter_table,
+                    Self::escape(&column_name)
+                ));
+            }
+
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                sqls.push(self.translate_rename_column(tb, &old_column_name, &new_column_name));
+            }
+
+            AlterTableOperation::ChangeColumn {
+                old_column_name,
+                column,
+                ..
+            } => {
+                if old_column_name != column.column_name {
+                    sqls.push(self.translate_rename_column(
+                        tb,
+                        &old_column_name,
+                        &column.column_name,
+                    ));
+                }
+                sqls.push(self.translate_modify_column(tb, &column));
+            }
+
+            AlterTableOperation::ModifyColumn { column, .. }
+            | AlterTableOperation::AlterColumnType { column } => {
+                sqls.push(self.translate_modify_column(tb, &column));
+            }
+
+            // indexes, constraints, defaults, comments and table options
+            _ => {}
+        }
+        Ok(sqls)
+    }
+
+    fn translate_modify_column(&self, tb: &str, column: &ColumnDefinition) -> String {
+        format!(
+            "ALTER TABLE {} MODIFY COLUMN {} {}",
+            tb,
+            Self::escape(&column.column_name),
+            self.get_col_type(column, column.is_nullable())
+        )
+    }
+
+    fn translate_rename_column(
+        &self,
+        tb: &str,
+        old_column_name: &str,
+        new_column_name: &str,
+    ) -> String {
+        let to = if *self.dst_db_type == DbType::Doris {
+            ""
+        } else {
+            " TO"
+        };
+        format!(
+            "ALTER TABLE {} RENAME COLUMN {}{} {}",
+            tb,
+            Self::escape(old_column_name),
+            to,
+            Self::escape(new_column_name)
+        )
+    }
+
+    fn translate_rename_table(
+        &self,
+        schema: &str,
+        tb: &str,
+        new_schema: &str,
+        new_tb: &str,
+    ) -> anyhow::Result<String> {
+        if *self.dst_db_type == DbType::ClickHouse {
+            return Ok(format!(
+                "RENAME TABLE {} TO {}",
+                self.get_tb(schema, tb),
+                self.get_tb(new_schema, new_tb)
+            ));
+        }
+
+        // starrocks / doris: ALTER TABLE db.tb RENAME new_tb
+        if self.get_schema(schema) != self.get_schema(new_schema) {
+            bail! {self.untranslatable("renaming table across databases")}
+        }
+        Ok(format!(
+            "ALTER TABLE {} RENAME {}",
+            self.get_tb(schema, tb),
+            Self::escape(new_tb)
+        ))
+    }
+
+    fn get_col_type(&self, column: &ColumnDefinition, is_nullable: bool) -> String {
+        let col_type = column.to_mysql_col_type();
+        if *self.dst_db_type == DbType::ClickHouse {
+            let col_type = DdlTranslator::mysql_to_clickhouse_col_type(&col_type);
+            if is_nullable {
+                format!("Nullable({})", col_type)
+            } else {
+                col_type
+            }
+        } else {
+            let col_type = DdlTranslator::mysql_to_starrocks_col_type(&col_type, self.dst_db_type);
+            let nullable = if is_nullable { "NULL" } else { "NOT NULL" };
+            format!("{} {}", col_type, nullable)
+        }
+    }
+
+    fn get_schema(&self, schema: &str) -> String {
+        if schema.is_empty() {
+            self.ddl_data.default_schema.clone()
+        } else {
+            schema.to_string()
+        }
+    }
+
+    fn get_tb(&self, schema: &str, tb: &str) -> String {
+        let schema = self.get_schema(schema);
+        if schema.is_empty() {
+            Self::escape(tb)
+        } else {
+            format!("{}.{}", Self::escape(&schema), Self::escape(tb))
+        }
+    }
+
+    fn escape(identifier: &str) -> String {
+        SqlUtil::escape_by_db_type(identifier, &DbType::Mysql)
+    }
+
+    fn untranslatable(&self, reason: &str) -> Error {
+        Error::DdlTranslateError(format!(
+            "{} can not be translated from mysql to {}, ddl: {}",
+            reason, self.dst_db_type, self.ddl_data.query
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::ddl_meta::ddl_parser::DdlParser;
+
+    fn translate(sql: &str) -> anyhow::Result<Vec<String>> {
+        translate_to(sql, &DbType::Pg)
+    }
+
+    fn translate_to(sql: &str, dst_db_type: &DbType) -> anyhow::Result<Vec<String>> {
+        let parser = DdlParser::new(DbType::Mysql);
+        let mut ddl_data = parser.parse(sql).unwrap();
+        ddl_data.default_schema = "db_1".into();
+        DdlTranslator::translate(&ddl_data, dst_db_type)
+    }
+
+    fn index_sql(schema: &str, tb: &str, index: &str, action: &str) -> String {
+        format!(
+            r#"DO $$ DECLARE index_name text; BEGIN SELECT c.relname INTO index_name FROM pg_index i JOIN pg_class c ON c.oid = i.indexrelid WHERE i.indrelid = '"{schema}"."{tb}"'::regclass AND c.relname IN ('{tb}_{index}', '{index}') ORDER BY c.relname = '{tb}_{index}' DESC LIMIT 1; IF index_name IS NULL THEN RAISE EXCEPTION 'index % not found on table %', '{index}', '"{schema}"."{tb}"'; END IF; EXECUTE format({action}); END $$"#
+        )
+    }
+
+    #[test]
+    fn test_compare_olap_col_type() {
+        let compare = DdlTranslator::compare_olap_col_type;
+        assert_eq!(compare("BIGINT", "INT"), Some(Ordering::Less));
+        assert_eq!(compare("int(11)", "BIGINT"), Some(Ordering::Greater));
+        assert_eq!(compare("int(11)", "INT"), Some(Ordering::Equal));
+        assert_eq!(compare("varchar(255)", "VARCHAR(64)"), Some(Ordering::Less));
+        assert_eq!(compare("varchar(64)", "STRING"), Some(Ordering::Greater));
+        assert_eq!(
+            compare("decimal(10,2)", "DECIMAL(12,2)"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare("decimal(10,2)", "DECIMAL(10,4)"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(compare("Nullable(Int64)", "Int32"), Some(Ordering::Less));
+        assert_eq!(compare("Nullable(Int32)", "Int64"), Some(Ordering::Greater));
+        assert_eq!(compare("Nullable(String)", "String"), Some(Ordering::Equal));
+        assert_eq!(
+            compare("DateTime64(6)", "DateTime64(3)"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(compare("INT", "VARCHAR(64)"), None);
+        assert_eq!(compare("JSON", "STRING"), None);
+    }
+
+    #[test]
+    fn test_translate_create_table() {
+        let sql = "CREATE TABLE IF NOT EXISTS `tb_1` (
+            `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+            `f_1` tinyint(1) DEFAULT '1',
+            `f_2` varchar(64) NOT NULL DEFAULT '' COMMENT 'f_2 comment',
+            `f_3` datetime(3) DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3),
+            `f_4` int unsigned,
+            `f_5` decimal(10,2),
+            `f_6` longblob,
+            `f_7` enum('a','b'),
+            PRIMARY KEY (`id`),
+            UNIQUE KEY `uk_1` (`f_2`),
+            KEY (`f_4`, `f_5`)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='tb comment'";
+
+        let expect_sqls = vec![
+            r#"CREATE TABLE IF NOT EXISTS "db_1"."tb_1" ("id" bigint GENERATED BY DEFAULT AS IDENTITY NOT NULL, "f_1" smallint DEFAULT '1', "f_2" varchar(64) DEFAULT '' NOT NULL, "f_3" timestamp(3) DEFAULT CURRENT_TIMESTAMP(3), "f_4" bigint, "f_5" numeric(10,2), "f_6" bytea, "f_7" varchar(255), PRIMARY KEY ("id"))"#,
+            r#"COMMENT ON COLUMN "db_1"."tb_1"."f_2" IS 'f_2 comment'"#,
+            r#"CREATE UNIQUE INDEX "tb_1_uk_1" ON "db_1"."tb_1" ("f_2")"#,
+            r#"CREATE INDEX "tb_1_f_4" ON "db_1"."tb_1" ("f_4", "f_5")"#,
+            r#"COMMENT ON TABLE "db_1"."tb_1" IS 'tb comment'"#,
+        ];
+        assert_eq!(translate(sql).unwrap(), expect_sqls);
+    }
+
+    #[test]
+    fn test_translate_alter_table() {
+        let sql = "ALTER TABLE db_2.tb_1 ADD COLUMN f_8 int NOT NULL DEFAULT 0 AFTER id,
+            DROP COLUMN f_1,
+            CHANGE f_2 f_9 varchar(128),
+            ADD UNIQUE INDEX uk_2 (f_8),
+            DROP INDEX uk_1,
+            DROP PRIMARY KEY,
+            ENGINE=InnoDB";
+
+        let expect_sqls = vec![
+            r#"ALTER TABLE "db_2"."tb_1" ADD COLUMN "f_8" integer DEFAULT 0 NOT NULL"#.to_string(),
+            r#"ALTER TABLE "db_2"."tb_1" DROP COLUMN "f_1""#.to_string(),
+            r#"ALTER TABLE "db_2"."tb_1" RENAME COLUMN "f_2" TO "f_9""#.to_string(),
+            r#"ALTER TABLE "db_2"."tb_1" ALTER COLUMN "f_9" TYPE varchar(128) USING "f_9"::varchar(128)"#.to_string(),
+            r#"ALTER TABLE "db_2"."tb_1" ALTER COLUMN "f_9" DROP NOT NULL"#.to_string(),
+            r#"ALTER TABLE "db_2"."tb_1" ALTER COLUMN "f_9" DROP DEFAULT"#.to_string(),
+            r#"COMMENT ON COLUMN "db_2"."tb_1"."f_9" IS NULL"#.to_string(),
+            r#"CREATE UNIQUE INDEX "tb_1_uk_2" ON "db_2"."tb_1" ("f_8")"#.to_string(),
+            index_sql(
+                "db_2",
+                "tb_1",
+                "uk_1",
+                "'DROP INDEX %I.%I', 'db_2', index_name",
+            ),
+            r#"ALTER TABLE "db_2"."tb_1" DROP CONSTRAINT "tb_1_pkey""#.to_string(),
+        ];
+        assert_eq!(translate(sql).unwrap(), expect_sqls);
+    }
+
+    #[test]
+    fn test_translate_others() {
+        let cases = vec![
+            ("CREATE DATABASE db_3", vec![r#"CREATE SCHEMA "db_3""#]),
+            (
+                "DROP DATABASE IF EXISTS db_3",
+                vec![r#"DROP SCHEMA IF EXISTS "db_3" CASCADE"#],
+            ),
+            (
+                "RENAME TABLE tb_1 TO db_3.tb_2",
+                vec![
+                    r#"ALTER TABLE "db_1"."tb_1" SET SCHEMA "db_3""#,
+                    r#"ALTER TABLE "db_3"."tb_1" RENAME TO "tb_2""#,
+                ],
+            ),
+            (
+                "TRUNCATE TABLE tb_1",
+                vec![r#"TRUNCATE TABLE "db_1"."tb_1""#],
+            ),
+            (
+                "CREATE UNIQUE INDEX idx_1 ON tb_1 (f_1, f_2)",
+                vec![r#"CREATE UNIQUE INDEX "tb_1_idx_1" ON "db_1"."tb_1" ("f_1", "f_2")"#],
+            ),
+        ];
+
+        for (sql, expect_sqls) in cases {
+            assert_eq!(translate(sql).unwrap(), expect_sqls);
+        }
+
+        // indexes not created by translator keep their mysql names
+        assert_eq!(
+            translate("DROP INDEX idx_1 ON tb_1").unwrap(),
+            vec![index_sql(
+                "db_1",
+                "tb_1",
+                "idx_1",
+                "'DROP INDEX %I.%I', 'db_1', index_name"
+            )]
+        );
+        assert_eq!(
+            translate("ALTER TABLE tb_1 RENAME INDEX idx_1 TO idx_2").unwrap(),
+            vec![index_sql(
+                "db_1",
+                "tb_1",
+                "idx_1",
+                "'ALTER INDEX %I.%I RENAME TO %I', 'db_1', index_name, 'tb_1_idx_2'"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_get_index_name() {
+        assert_eq!(DdlTranslator::get_index_name("tb_1", "idx_1"), "tb_1_idx_1");
+
+        let tb = "t".repeat(40);
+        let name_1 = DdlTranslator::get_index_name(&tb, &format!("{}_1", "i".repeat(30)));
+        let name_2 = DdlTranslator::get_index_name(&tb, &format!("{}_2", "i".repeat(30)));
+        assert_eq!(name_1.len(), PG_MAX_IDENTIFIER_LEN);
+        assert_eq!(name_2.len(), PG_MAX_IDENTIFIER_LEN);
+        assert_eq!(name_1[..54], name_2[..54]);
+        assert_ne!(name_1, name_2);
+        // stable for drop / rename
+        assert_eq!(
+            name_1,
+            DdlTranslator::get_index_name(&tb, &format!("{}_1", "i".repeat(30)))
+        );
+    }
+
+    #[test]
+    fn test_translate_mysql_to_olap() {
+        let sql = "ALTER TABLE db_2.tb_1 ADD COLUMN f_8 int NOT NULL DEFAULT 0 AFTER id,
+            DROP COLUMN f_1,
+            CHANGE f_2 f_9 varchar(128) NOT NULL,
+            MODIFY f_3 bigint,
+            ADD UNIQUE INDEX uk_2 (f_8),
+            ENGINE=InnoDB";
+
+        let expect_sqls = vec![
+            "ALTER TABLE `db_2`.`tb_1` ADD COLUMN `f_8` INT NULL",
+            "ALTER TABLE `db_2`.`tb_1` DROP COLUMN `f_1`",
+            "ALTER TABLE `db_2`.`tb_1` RENAME COLUMN `f_2` TO `f_9`",
+            "ALTER TABLE `db_2`.`tb_1` MODIFY COLUMN `f_9` VARCHAR(128) NOT NULL",
+            "ALTER TABLE `db_2`.`tb_1` MODIFY COLUMN `f_3` BIGINT NULL",
+        ];
+        assert_eq!(translate_to(sql, &DbType::StarRocks).unwrap(), expect_sqls);
+
+        let expect_sqls = vec![
+            "ALTER TABLE `db_2`.`tb_1` ADD COLUMN `f_8` Nullable(Int32)",
+            "ALTER TABLE `db_2`.`tb_1` DROP COLUMN `f_1`",
+            "ALTER TABLE `db_2`.`tb_1` RENAME COLUMN `f_2` TO `f_9`",
+            "ALTER TABLE `db_2`.`tb_1` MODIFY COLUMN `f_9` String",
+            "ALTER TABLE `db_2`.`tb_1` MODIFY COLUMN `f_3` Nullable(Int64)",
+        ];
+        assert_eq!(translate_to(sql, &DbType::ClickHouse).unwrap(), expect_sqls);
+
+        let cases = vec![
+            (
+                "RENAME TABLE tb_1 TO tb_2",
+                &DbType::Doris,
+                vec!["ALTER TABLE `db_1`.`tb_1` RENAME `tb_2`"],
+            ),
+            (
+                "RENAME TABLE tb_1 TO db_3.tb_2",
+                &DbType::ClickHouse,
+                vec!["RENAME TABLE `db_1`.`tb_1` TO `db_3`.`tb_2`"],
+            ),
+            (
+                "ALTER TABLE tb_1 RENAME COLUMN f_1 TO f_2",
+                &DbType::Doris,
+                vec!["ALTER TABLE `db_1`.`tb_1` RENAME COLUMN `f_1` `f_2`"],
+            ),
+            (
+                "TRUNCATE TABLE tb_1",
+                &DbType::StarRocks,
+                vec!["TRUNCATE TABLE `db_1`.`tb_1`"],
+            ),
+            (
+                "CREATE INDEX idx_1 ON tb_1 (f_1)",
+                &DbType::StarRocks,
+                vec![],
+            ),
+        ];
+        for (sql, dst_db_type, expect_sqls) in cases {
+            assert_eq!(translate_to(sql, dst_db_type).unwrap(), expect_sqls);
+        }
+
+        for sql in [
+            "CREATE TABLE tb_1 (id int)",
+            "RENAME TABLE tb_1 TO db_3.tb_2",
+        ] {
+            let err = translate_to(sql, &DbType::StarRocks).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<Error>(),
+                Some(Error::DdlTranslateError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_translate_untranslatable() {
+        let sqls = [
+            "CREATE FULLTEXT INDEX idx_1 ON tb_1 (f_1)",
+            "CREATE TABLE tb_1 (id int, f_1 geometry)",
+            "ALTER TABLE tb_1 ADD SPATIAL INDEX idx_1 (f_1)",
+        ];
+        for sql in sqls {
+            let err = translate(sql).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<Error>(),
+                Some(Error::DdlTranslateError(_))
+            ));
+        }
+    }
+}
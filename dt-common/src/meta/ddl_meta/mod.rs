@@ -1,5 +1,10 @@
 pub mod ddl_data;
+pub mod ddl_definition;
 pub mod ddl_parser;
 pub mod ddl_statement;
+mod ddl_tokenizer;
+pub mod ddl_translator;
 pub mod ddl_type;
 mod keywords;
+pub mod mysql_definition_parser;
+pub mod pg_definition_parser;
@@ -0,0 +1,634 @@
+use anyhow::bail;
+
+use super::{
+    ddl_definition::{
+        AlterTableOperation, ColumnDefinition, ColumnPosition, ConstraintDefinition,
+        IndexDefinition, IndexDefinitionKind, TableDefinition,
+    },
+    ddl_tokenizer::{TokenKind, TokenStream},
+};
+
+const MYSQL_QUOTE: char = '`';
+
+/// Parse the unparsed parts of mysql ddl:
+///     CREATE TABLE a.b ({create_definition}, ...) {table_options}
+///     ALTER TABLE a.b {alter_specification}, ...
+pub struct MysqlDefinitionParser {}
+
+impl MysqlDefinitionParser {
+    // https://dev.mysql.com/doc/refman/8.4/en/create-table.html
+    pub fn parse_create_table(unparsed: &str) -> anyhow::Result<TableDefinition> {
+        let mut stream = TokenStream::new(unparsed, MYSQL_QUOTE)?;
+        if stream.peek_word("like") || stream.peek_word("as") || stream.peek_word("select") {
+            bail! {stream.unexpected("CREATE TABLE ... LIKE / AS SELECT not supported")}
+        }
+
+        let mut definition = TableDefinition::default();
+        for item in stream.expect_list()? {
+            let mut item_stream = TokenStream::new(&item, MYSQL_QUOTE)?;
+            if let Some(index) = Self::parse_index(&mut item_stream)? {
+                definition.indexes.push(index);
+            } else if let Some(constraint) = Self::parse_constraint(&mut item_stream)? {
+                definition.constraints.push(constraint);
+            } else {
+                definition
+                    .columns
+                    .push(Self::parse_column(&mut item_stream)?);
+            }
+        }
+
+        // table options: ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='abc'
+        while !stream.is_end() {
+            if stream.eat_word("comment") {
+                stream.eat_symbol('=');
+                definition.comment = Some(stream.expect_string()?);
+            } else {
+                stream.next_token();
+            }
+        }
+        Ok(definition)
+    }
+
+    // https://dev.mysql.com/doc/refman/8.4/en/alter-table.html
+    pub fn parse_alter_table(unparsed: &str) -> anyhow::Result<Vec<AlterTableOperation>> {
+        let mut operations = Vec::new();
+        for item in TokenStream::split_by_comma(unparsed) {
+            let mut stream = TokenStream::new(&item, MYSQL_QUOTE)?;
+            operations.push(Self::parse_alter_specification(&mut stream)?);
+            if !stream.is_end() {
+                bail! {stream.unexpected("unexpected tokens in alter specification")}
+            }
+        }
+        Ok(operations)
+    }
+
+    // https://dev.mysql.com/doc/refman/8.4/en/create-index.html
+    pub fn parse_create_index(
+        index_kind: &Option<String>,
+        index_name: &str,
+        unparsed: &str,
+    ) -> anyhow::Result<IndexDefinition> {
+        let index_kind = match index_kind.as_ref().map(|i| i.to_lowercase()).as_deref() {
+            Some("unique") => IndexDefinitionKind::Unique,
+            Some("fulltext") => IndexDefinitionKind::FullText,
+            Some("spatial") => IndexDefinitionKind::Spatial,
+            _ => IndexDefinitionKind::Normal,
+        };
+
+        let mut stream = TokenStream::new(unparsed, MYSQL_QUOTE)?;
+        let mut columns = Vec::new();
+        for item in stream.expect_list()? {
+            columns.push(Self::parse_key_part(&item)?);
+        }
+        Ok(IndexDefinition {
+            index_name: Some(index_name.to_string()),
+            index_kind,
+            columns,
+        })
+    }
+
+    fn parse_alter_specification(stream: &mut TokenStream) -> anyhow::Result<AlterTableOperation> {
+        if stream.eat_word("add") {
+            if let Some(index) = Self::parse_index(stream)? {
+                return Ok(AlterTableOperation::AddIndex { index });
+            }
+            if let Some(constraint) = Self::parse_constraint(stream)? {
+                return Ok(AlterTableOperation::AddConstraint { constraint });
+            }
+            stream.eat_word("column");
+            if stream.peek_symbol('(') {
+                bail! {stream.unexpected("ADD COLUMN (col1, col2) not supported")}
+            }
+            let column = Self::parse_column(stream)?;
+            let position = Self::parse_position(stream)?;
+            return Ok(AlterTableOperation::AddColumn { column, position });
+        }
+
+        if stream.eat_word("drop") {
+            if stream.eat_words(&["primary", "key"]) {
+                return Ok(AlterTableOperation::DropPrimaryKey);
+            }
+            if stream.eat_word("index") || stream.eat_word("key") {
+                let index_name = stream.expect_identifier()?;
+                return Ok(AlterTableOperation::DropIndex { index_name });
+            }
+            if stream.eat_words(&["foreign", "key"])
+                || stream.eat_word("constraint")
+                || stream.eat_word("check")
+            {
+                let constraint_name = stream.expect_identifier()?;
+                return Ok(AlterTableOperation::DropConstraint { constraint_name });
+            }
+            stream.eat_word("column");
+            let column_name = stream.expect_identifier()?;
+            return Ok(AlterTableOperation::DropColumn { column_name });
+        }
+
+        if stream.eat_word("modify") {
+            stream.eat_word("column");
+            let column = Self::parse_column(stream)?;
+            let position = Self::parse_position(stream)?;
+            return Ok(AlterTableOperation::ModifyColumn { column, position });
+        }
+
+        if stream.eat_word("change") {
+            stream.eat_word("column");
+            let old_column_name = stream.expect_identifier()?;
+            let column = Self::parse_column(stream)?;
+            let position = Self::parse_position(stream)?;
+            return Ok(AlterTableOperation::ChangeColumn {
+                old_column_name,
+                column,
+                position,
+            });
+        }
+
+        if stream.eat_word("rename") {
+            if stream.eat_word("column") {
+                let old_column_name = stream.expect_identifier()?;
+                if !stream.eat_word("to") {
+                    bail! {stream.unexpected("expect: TO")}
+                }
+                let new_column_name = stream.expect_identifier()?;
+                return Ok(AlterTableOperation::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                });
+            }
+            if stream.eat_word("index") || stream.eat_word("key") {
+                let old_index_name = stream.expect_identifier()?;
+                if !stream.eat_word("to") {
+                    bail! {stream.unexpected("expect: TO")}
+                }
+                let new_index_name = stream.expect_identifier()?;
+                return Ok(AlterTableOperation::RenameIndex {
+                    old_index_name,
+                    new_index_name,
+                });
+            }
+            bail! {stream.unexpected("unsupported RENAME")}
+        }
+
+        if stream.eat_word("alter") {
+            stream.eat_word("column");
+            let column_name = stream.expect_identifier()?;
+            if stream.eat_words(&["set", "default"]) {
+                let default_value = Self::parse_default_value(stream)?;
+                return Ok(AlterTableOperation::SetColumnDefault {
+                    column_name,
+                    default_value,
+                });
+            }
+            if stream.eat_words(&["drop", "default"]) {
+                return Ok(AlterTableOperation::DropColumnDefault { column_name });
+            }
+            bail! {stream.unexpected("unsupported ALTER COLUMN")}
+        }
+
+        if stream.eat_word("comment") {
+            stream.eat_symbol('=');
+            let comment = stream.expect_string()?;
+            return Ok(AlterTableOperation::SetTableComment { comment });
+        }
+
+        // ENGINE=InnoDB, AUTO_INCREMENT=100, CONVERT TO CHARACTER SET utf8mb4, ALGORITHM=INPLACE, LOCK=NONE ...
+        let option = stream.take_until_comma();
+        Ok(AlterTableOperation::TableOption { option })
+    }
+
+    fn parse_position(stream: &mut TokenStream) -> anyhow::Result<Option<ColumnPosition>> {
+        if stream.eat_word("first") {
+            return Ok(Some(ColumnPosition::First));
+        }
+        if stream.eat_word("after") {
+            return Ok(Some(ColumnPosition::After(stream.expect_identifier()?)));
+        }
+        Ok(None)
+    }
+
+    /// return None if the definition is not an index
+    fn parse_index(stream: &mut TokenStream) -> anyhow::Result<Option<IndexDefinition>> {
+        let origin_pos = stream.pos;
+        // CONSTRAINT [symbol] PRIMARY KEY / UNIQUE
+        let mut constraint_name = None;
+        if stream.eat_word("constraint")
+            && !stream.peek_word("primary")
+            && !stream.peek_word("unique")
+            && !stream.peek_word("foreign")
+            && !stream.peek_word("check")
+        {
+            constraint_name = Some(stream.expect_identifier()?);
+        }
+
+        let index_kind = if stream.eat_words(&["primary", "key"]) {
+            IndexDefinitionKind::Primary
+        } else if stream.eat_word("unique") {
+            IndexDefinitionKind::Unique
+        } else if stream.eat_word("fulltext") {
+            IndexDefinitionKind::FullText
+        } else if stream.eat_word("spatial") {
+            IndexDefinitionKind::Spatial
+        } else if constraint_name.is_none()
+            && (stream.peek_word("index") || stream.peek_word("key"))
+        {
+            IndexDefinitionKind::Normal
+        } else {
+            stream.pos = origin_pos;
+            return Ok(None);
+        };
+
+        if index_kind != IndexDefinitionKind::Primary {
+            let _ = stream.eat_word("index") || stream.eat_word("key");
+        }
+
+        let mut index_name = constraint_name;
+        if !stream.peek_symbol('(') && !stream.peek_word("using") {
+            index_name = Some(stream.expect_identifier()?);
+        }
+        if stream.eat_word("using") {
+            stream.next_token();
+        }
+
+        let mut columns = Vec::new();
+        for item in stream.expect_list()? {
+            columns.push(Self::parse_key_part(&item)?);
+        }
+
+        // index options: USING BTREE, COMMENT 'abc', VISIBLE, KEY_BLOCK_SIZE=1 ...
+        while !stream.is_end() && !stream.peek_symbol(',') {
+            stream.next_token();
+        }
+
+        Ok(Some(IndexDefinition {
+            index_name,
+            index_kind,
+            columns,
+        }))
+    }
+
+    /// key_part: {col_name [(length)] | (expr)} [ASC | DESC]
+    fn parse_key_part(item: &str) -> anyhow::Result<String> {
+        let mut stream = TokenStream::new(item, MYSQL_QUOTE)?;
+        if stream.peek_symbol('(') {
+            bail! {stream.unexpected("functional key part not supported")}
+        }
+        stream.expect_identifier()
+    }
+
+    /// return None if the definition is not a foreign key / check constraint
+    fn parse_constraint(stream: &mut TokenStream) -> anyhow::Result<Option<ConstraintDefinition>> {
+        let origin_pos = stream.pos;
+        let mut name = None;
+        if stream.eat_word("constraint")
+            && !stream.peek_word("foreign")
+            && !stream.peek_word("check")
+        {
+            name = Some(stream.expect_identifier()?);
+        }
+
+        if stream.eat_words(&["foreign", "key"]) {
+            if !stream.peek_symbol('(') {
+                // index_name
+                stream.expect_identifier()?;
+            }
+            let mut columns = Vec::new();
+            for item in stream.expect_list()? {
+                columns.push(Self::parse_key_part(&item)?);
+            }
+
+            if !stream.eat_word("references") {
+                bail! {stream.unexpected("expect: REFERENCES")}
+            }
+            let mut ref_schema = String::new();
+            let mut ref_tb = stream.expect_identifier()?;
+            if stream.eat_symbol('.') {
+                ref_schema = ref_tb;
+                ref_tb = stream.expect_identifier()?;
+            }
+            let mut ref_columns = Vec::new();
+            for item in stream.expect_list()? {
+                ref_columns.push(Self::parse_key_part(&item)?);
+            }
+            let options = stream.take_until_comma();
+            return Ok(Some(ConstraintDefinition::ForeignKey {
+                name,
+                columns,
+                ref_schema,
+                ref_tb,
+                ref_columns,
+                options,
+            }));
+        }
+
+        if stream.eat_word("check") {
+            let expression = stream.expect_group()?;
+            // [NOT] ENFORCED
+            while !stream.is_end() && !stream.peek_symbol(',') {
+                stream.next_token();
+            }
+            return Ok(Some(ConstraintDefinition::Check { name, expression }));
+        }
+
+        stream.pos = origin_pos;
+        Ok(None)
+    }
+
+    fn parse_column(stream: &mut TokenStream) -> anyhow::Result<ColumnDefinition> {
+        let column_name = stream.expect_identifier()?;
+        let mut column = ColumnDefinition {
+            column_name,
+            ..Default::default()
+        };
+        Self::parse_data_type(stream, &mut column)?;
+
+        while let Some(token) = stream.peek() {
+            if token.is_symbol(',') {
+                break;
+            }
+            if matches!(token.text.to_lowercase().as_str(), "first" | "after")
+                && token.kind == TokenKind::Word
+            {
+                // position in alter table
+                break;
+            }
+
+            if stream.eat_words(&["not", "null"]) {
+                column.nullable = Some(false);
+            } else if stream.eat_word("null") {
+                column.nullable = Some(true);
+            } else if stream.eat_word("default") {
+                column.default_value = Some(Self::parse_default_value(stream)?);
+            } else if stream.eat_word("auto_increment") {
+                column.auto_increment = true;
+            } else if stream.eat_words(&["primary", "key"]) {
+                column.primary_key = true;
+            } else if stream.eat_word("unique") {
+                stream.eat_word("key");
+                column.unique_key = true;
+            } else if stream.eat_word("key") {
+                column.primary_key = true;
+            } else if stream.eat_word("comment") {
+                column.comment = Some(stream.expect_string()?);
+            } else if stream.eat_words(&["character", "set"]) || stream.eat_word("charset") {
+                column.character_set = Some(stream.expect_identifier()?);
+            } else if stream.eat_word("collate") {
+                column.collation = Some(stream.expect_identifier()?);
+            } else if stream.eat_words(&["on", "update"]) {
+                column.on_update = Some(Self::parse_default_value(stream)?);
+            } else if stream.eat_words(&["generated", "always", "as"]) || stream.eat_word("as") {
+                column.generated = Some(stream.expect_group()?);
+            } else if stream.eat_word("stored") {
+                column.generated_stored = true;
+            } else if stream.eat_word("virtual")
+                || stream.eat_word("visible")
+                || stream.eat_word("invisible")
+            {
+            } else if stream.eat_word("column_format")
+                || stream.eat_word("storage")
+                || stream.eat_word("srid")
+            {
+                stream.next_token();
+            } else if stream.eat_word("check") {
+                stream.expect_group()?;
+            } else if stream.eat_word("references") {
+                // inline references are ignored by mysql
+                stream.take_until_comma();
+            } else {
+                bail! {stream.unexpected("unsupported column attribute")}
+            }
+        }
+        Ok(column)
+    }
+
+    fn parse_data_type(
+        stream: &mut TokenStream,
+        column: &mut ColumnDefinition,
+    ) -> anyhow::Result<()> {
+        let start = match stream.peek() {
+            Some(t) if t.kind == TokenKind::Word => t.start,
+            _ => bail! {stream.unexpected("expect data type")},
+        };
+
+        let mut data_type = stream.next_token().unwrap().text.to_lowercase();
+        // double precision, national char, long varchar ...
+        if (data_type == "double" && stream.eat_word("precision"))
+            || (data_type == "long" && stream.peek_word("varchar"))
+        {
+            if data_type == "long" {
+                stream.next_token();
+                data_type = "mediumtext".into();
+            }
+        } else if data_type == "national" {
+            data_type = stream.expect_identifier()?.to_lowercase();
+        }
+
+        let mut end = stream.tokens[stream.pos - 1].end;
+        if stream.peek_symbol('(') {
+            column.type_args = stream.expect_list()?;
+            end = stream.tokens[stream.pos - 1].end;
+        }
+
+        loop {
+            if stream.eat_word("unsigned") {
+                column.unsigned = true;
+            } else if !(stream.eat_word("signed")
+                || stream.eat_word("zerofill")
+                || stream.eat_word("binary")
+                || stream.eat_word("varying"))
+            {
+                break;
+            }
+            end = stream.tokens[stream.pos - 1].end;
+        }
+
+        column.column_type = stream.sql[start..end].to_string();
+        column.data_type = match data_type.as_str() {
+            "integer" => "int".into(),
+            "bool" | "boolean" => {
+                column.type_args = vec!["1".into()];
+                "tinyint".into()
+            }
+            "dec" | "numeric" | "fixed" => "decimal".into(),
+            "real" => "double".into(),
+            "character" => "char".into(),
+            _ => data_type,
+        };
+        Ok(())
+    }
+
+    /// DEFAULT / ON UPDATE values:
+    ///     literal: 'abc', _utf8mb4'abc', b'01', 1, -1.5, NULL, TRUE
+    ///     function: CURRENT_TIMESTAMP, CURRENT_TIMESTAMP(6), now()
+    ///     expression: (rand() * rand())
+    fn parse_default_value(stream: &mut TokenStream) -> anyhow::Result<String> {
+        let token = match stream.peek() {
+            Some(t) => t.clone(),
+            None => bail! {stream.unexpected("expect default value")},
+        };
+
+        if token.is_symbol('(') {
+            let expr = stream.expect_group()?;
+            return Ok(format!("({})", expr));
+        }
+
+        stream.next_token();
+        if token.kind == TokenKind::Operator && (token.text == "-" || token.text == "+") {
+            let number = Self::parse_default_value(stream)?;
+            return Ok(format!("{}{}", token.text, number));
+        }
+
+        if token.kind == TokenKind::Word {
+            let mut value = token.text.clone();
+            // 1.5
+            if stream.peek_symbol('.') {
+                stream.next_token();
+                if let Some(decimal) = stream.next_token() {
+                    value = format!("{}.{}", value, decimal.text);
+                }
+            }
+            // CURRENT_TIMESTAMP(6), now()
+            if stream.peek_symbol('(') {
+                let args = stream.expect_group()?;
+                value = format!("{}({})", value, args);
+            }
+            return Ok(value);
+        }
+        Ok(token.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_create_table() {
+        let sql = "(`id` int(11) unsigned NOT NULL AUTO_INCREMENT,
+            `name` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_bin DEFAULT 'a,b' COMMENT 'it''s name',
+            price decimal(10,2) DEFAULT -1.5,
+            updated_at datetime(3) DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3),
+            f_gen int GENERATED ALWAYS AS ((`id` + 1)) STORED,
+            PRIMARY KEY (`id`),
+            UNIQUE KEY `uk_name` (`name`(10)),
+            KEY idx_price (price, updated_at) USING BTREE,
+            CONSTRAINT fk_1 FOREIGN KEY (id) REFERENCES db_1.tb_2 (id) ON DELETE CASCADE
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='table comment'";
+
+        let definition = MysqlDefinitionParser::parse_create_table(sql).unwrap();
+        assert_eq!(definition.columns.len(), 5);
+        assert_eq!(definition.comment, Some("table comment".into()));
+
+        let id = &definition.columns[0];
+        assert_eq!(id.column_name, "id");
+        assert_eq!(id.column_type, "int(11) unsigned");
+        assert_eq!(id.data_type, "int");
+        assert!(id.unsigned);
+        assert!(id.auto_increment);
+        assert_eq!(id.nullable, Some(false));
+
+        let name = &definition.columns[1];
+        assert_eq!(name.data_type, "varchar");
+        assert_eq!(name.type_args, vec!["255".to_string()]);
+        assert_eq!(name.default_value, Some("'a,b'".into()));
+        assert_eq!(name.comment, Some("it's name".into()));
+        assert_eq!(name.character_set, Some("utf8mb4".into()));
+        assert_eq!(name.collation, Some("utf8mb4_bin".into()));
+
+        assert_eq!(definition.columns[2].default_value, Some("-1.5".into()));
+        assert_eq!(
+            definition.columns[3].on_update,
+            Some("CURRENT_TIMESTAMP(3)".into())
+        );
+        assert_eq!(definition.columns[4].generated, Some("(`id` + 1)".into()));
+        assert!(definition.columns[4].generated_stored);
+
+        assert_eq!(definition.get_primary_key(), vec!["id".to_string()]);
+        assert_eq!(definition.indexes.len(), 3);
+        assert_eq!(
+            definition.indexes[1].index_kind,
+            IndexDefinitionKind::Unique
+        );
+        assert_eq!(definition.indexes[1].index_name, Some("uk_name".into()));
+        assert_eq!(definition.indexes[1].columns, vec!["name".to_string()]);
+        assert_eq!(
+            definition.indexes[2].columns,
+            vec!["price".to_string(), "updated_at".to_string()]
+        );
+
+        assert_eq!(
+            definition.constraints[0],
+            ConstraintDefinition::ForeignKey {
+                name: Some("fk_1".into()),
+                columns: vec!["id".into()],
+                ref_schema: "db_1".into(),
+                ref_tb: "tb_2".into(),
+                ref_columns: vec!["id".into()],
+                options: "ON DELETE CASCADE".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_alter_table() {
+        let sql = "ADD COLUMN f_1 bigint NOT NULL DEFAULT 0 AFTER id,
+            DROP COLUMN f_2,
+            MODIFY f_3 varchar(100) NULL,
+            CHANGE COLUMN f_4 f_5 text,
+            RENAME COLUMN f_6 TO f_7,
+            ALTER COLUMN f_8 SET DEFAULT 'abc',
+            ADD INDEX idx_1 (f_1),
+            DROP INDEX idx_2,
+            DROP PRIMARY KEY,
+            ENGINE=InnoDB";
+
+        let operations = MysqlDefinitionParser::parse_alter_table(sql).unwrap();
+        assert_eq!(operations.len(), 10);
+        match &operations[0] {
+            AlterTableOperation::AddColumn { column, position } => {
+                assert_eq!(column.column_name, "f_1");
+                assert_eq!(column.default_value, Some("0".into()));
+                assert_eq!(position, &Some(ColumnPosition::After("id".into())));
+            }
+            _ => panic!(),
+        }
+        assert_eq!(
+            operations[1],
+            AlterTableOperation::DropColumn {
+                column_name: "f_2".into()
+            }
+        );
+        match &operations[3] {
+            AlterTableOperation::ChangeColumn {
+                old_column_name,
+                column,
+                ..
+            } => {
+                assert_eq!(old_column_name, "f_4");
+                assert_eq!(column.column_name, "f_5");
+                assert_eq!(column.data_type, "text");
+            }
+            _ => panic!(),
+        }
+        assert_eq!(
+            operations[4],
+            AlterTableOperation::RenameColumn {
+                old_column_name: "f_6".into(),
+                new_column_name: "f_7".into()
+            }
+        );
+        assert_eq!(
+            operations[5],
+            AlterTableOperation::SetColumnDefault {
+                column_name: "f_8".into(),
+                default_value: "'abc'".into()
+            }
+        );
+        assert_eq!(operations[8], AlterTableOperation::DropPrimaryKey);
+        assert_eq!(
+            operations[9],
+            AlterTableOperation::TableOption {
+                option: "ENGINE=InnoDB".into()
+            }
+        );
+    }
+}
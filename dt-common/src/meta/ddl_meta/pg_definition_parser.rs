@@ -0,0 +1,489 @@
+use anyhow::bail;
+
+use super::{
+    ddl_definition::{
+        AlterTableOperation, ColumnDefinition, ConstraintDefinition, IndexDefinition,
+        IndexDefinitionKind,
+    },
+    ddl_tokenizer::{TokenKind, TokenStream},
+};
+
+const PG_QUOTE: char = '"';
+
+// keywords which end a data type / default value in column definition
+const COLUMN_CONSTRAINT_KEYWORDS: [&str; 11] = [
+    "constraint",
+    "not",
+    "null",
+    "check",
+    "default",
+    "generated",
+    "unique",
+    "primary",
+    "references",
+    "collate",
+    "using",
+];
+
+/// Parse the unparsed parts of pg ddl:
+///     ALTER TABLE [IF EXISTS] [ONLY] a.b {action}, ...
+pub struct PgDefinitionParser {}
+
+impl PgDefinitionParser {
+    // https://www.postgresql.org/docs/current/sql-altertable.html
+    pub fn parse_alter_table(unparsed: &str) -> anyhow::Result<Vec<AlterTableOperation>> {
+        let mut operations = Vec::new();
+        for item in TokenStream::split_by_comma(unparsed.trim().trim_end_matches(';')) {
+            let mut stream = TokenStream::new(&item, PG_QUOTE)?;
+            operations.push(Self::parse_alter_action(&mut stream)?);
+            if !stream.is_end() {
+                bail! {stream.unexpected("unexpected tokens in alter action")}
+            }
+        }
+        Ok(operations)
+    }
+
+    fn parse_alter_action(stream: &mut TokenStream) -> anyhow::Result<AlterTableOperation> {
+        let origin_pos = stream.pos;
+
+        if stream.eat_word("add") {
+            if let Some(operation) = Self::parse_table_constraint(stream)? {
+                return Ok(operation);
+            }
+            stream.eat_word("column");
+            stream.eat_words(&["if", "not", "exists"]);
+            let column = Self::parse_column(stream)?;
+            return Ok(AlterTableOperation::AddColumn {
+                column,
+                position: None,
+            });
+        }
+
+        if stream.eat_word("drop") {
+            if stream.eat_word("constraint") {
+                stream.eat_words(&["if", "exists"]);
+                let constraint_name = stream.expect_identifier()?;
+                Self::eat_drop_behavior(stream);
+                return Ok(AlterTableOperation::DropConstraint { constraint_name });
+            }
+            stream.eat_word("column");
+            stream.eat_words(&["if", "exists"]);
+            let column_name = stream.expect_identifier()?;
+            Self::eat_drop_behavior(stream);
+            return Ok(AlterTableOperation::DropColumn { column_name });
+        }
+
+        if stream.eat_word("rename") {
+            if stream.eat_word("constraint") {
+                let old_constraint_name = stream.expect_identifier()?;
+                Self::expect_word(stream, "to")?;
+                let new_constraint_name = stream.expect_identifier()?;
+                return Ok(AlterTableOperation::RenameConstraint {
+                    old_constraint_name,
+                    new_constraint_name,
+                });
+            }
+            stream.eat_word("column");
+            let old_column_name = stream.expect_identifier()?;
+            Self::expect_word(stream, "to")?;
+            let new_column_name = stream.expect_identifier()?;
+            return Ok(AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            });
+        }
+
+        if stream.eat_word("alter") {
+            stream.eat_word("column");
+            let column_name = stream.expect_identifier()?;
+
+            if stream.eat_words(&["set", "data", "type"]) || stream.eat_word("type") {
+                let mut column = ColumnDefinition {
+                    column_name,
+                    ..Default::default()
+                };
+                Self::parse_data_type(stream, &mut column)?;
+                if stream.eat_word("collate") {
+                    column.collation = Some(stream.expect_identifier()?);
+                }
+                if stream.eat_word("using") {
+                    stream.take_until_comma();
+                }
+                return Ok(AlterTableOperation::AlterColumnType { column });
+            }
+            if stream.eat_words(&["set", "default"]) {
+                let default_value = Self::parse_default_value(stream)?;
+                return Ok(AlterTableOperation::SetColumnDefault {
+                    column_name,
+                    default_value,
+                });
+            }
+            if stream.eat_words(&["drop", "default"]) {
+                return Ok(AlterTableOperation::DropColumnDefault { column_name });
+            }
+            if stream.eat_words(&["set", "not", "null"]) {
+                return Ok(AlterTableOperation::SetColumnNotNull { column_name });
+            }
+            if stream.eat_words(&["drop", "not", "null"]) {
+                return Ok(AlterTableOperation::DropColumnNotNull { column_name });
+            }
+        }
+
+        // OWNER TO, SET TABLESPACE, ENABLE TRIGGER, REPLICA IDENTITY,
+        // ALTER COLUMN ... SET STATISTICS ...
+        stream.pos = origin_pos;
+        let option = stream.take_until_comma();
+        Ok(AlterTableOperation::TableOption { option })
+    }
+
+    /// return None if the action is not adding a table constraint
+    fn parse_table_constraint(
+        stream: &mut TokenStream,
+    ) -> anyhow::Result<Option<AlterTableOperation>> {
+        let origin_pos = stream.pos;
+        let mut name = None;
+        if stream.eat_word("constraint") {
+            name = Some(stream.expect_identifier()?);
+        }
+
+        let index_kind = if stream.eat_words(&["primary", "key"]) {
+            Some(IndexDefinitionKind::Primary)
+        } else if stream.eat_word("unique") {
+            stream.eat_words(&["nulls", "not", "distinct"]);
+            stream.eat_words(&["nulls", "distinct"]);
+            Some(IndexDefinitionKind::Unique)
+        } else {
+            None
+        };
+
+        if let Some(index_kind) = index_kind {
+            let mut columns = Vec::new();
+            if stream.peek_symbol('(') {
+                for item in stream.expect_list()? {
+                    columns.push(Self::parse_column_name(&item)?);
+                }
+            }
+            // INCLUDE (...), WITH (...), USING INDEX TABLESPACE, DEFERRABLE ...
+            stream.take_until_comma();
+            let index = IndexDefinition {
+                index_name: name,
+                index_kind,
+                columns,
+            };
+            return Ok(Some(AlterTableOperation::AddIndex { index }));
+        }
+
+        if stream.eat_words(&["foreign", "key"]) {
+            let mut columns = Vec::new();
+            for item in stream.expect_list()? {
+                columns.push(Self::parse_column_name(&item)?);
+            }
+
+            Self::expect_word(stream, "references")?;
+            let mut ref_schema = String::new();
+            let mut ref_tb = stream.expect_identifier()?;
+            if stream.eat_symbol('.') {
+                ref_schema = ref_tb;
+                ref_tb = stream.expect_identifier()?;
+            }
+            let mut ref_columns = Vec::new();
+            if stream.peek_symbol('(') {
+                for item in stream.expect_list()? {
+                    ref_columns.push(Self::parse_column_name(&item)?);
+                }
+            }
+            let options = stream.take_until_comma();
+            let constraint = ConstraintDefinition::ForeignKey {
+                name,
+                columns,
+                ref_schema,
+                ref_tb,
+                ref_columns,
+                options,
+            };
+            return Ok(Some(AlterTableOperation::AddConstraint { constraint }));
+        }
+
+        if stream.eat_word("check") {
+            let expression = stream.expect_group()?;
+            // NO INHERIT, NOT VALID
+            stream.take_until_comma();
+            let constraint = ConstraintDefinition::Check { name, expression };
+            return Ok(Some(AlterTableOperation::AddConstraint { constraint }));
+        }
+
+        if name.is_some() {
+            // EXCLUDE, or other constraints not supported
+            bail! {stream.unexpected("unsupported table constraint")}
+        }
+
+        stream.pos = origin_pos;
+        Ok(None)
+    }
+
+    fn parse_column_name(item: &str) -> anyhow::Result<String> {
+        let mut stream = TokenStream::new(item, PG_QUOTE)?;
+        if stream.peek_symbol('(') {
+            bail! {stream.unexpected("expression in key columns not supported")}
+        }
+        stream.expect_identifier()
+    }
+
+    fn parse_column(stream: &mut TokenStream) -> anyhow::Result<ColumnDefinition> {
+        let column_name = stream.expect_identifier()?;
+        let mut column = ColumnDefinition {
+            column_name,
+            ..Default::default()
+        };
+        Self::parse_data_type(stream, &mut column)?;
+
+        while !stream.is_end() && !stream.peek_symbol(',') {
+            if stream.eat_word("constraint") {
+                stream.expect_identifier()?;
+            } else if stream.eat_words(&["not", "null"]) {
+                column.nullable = Some(false);
+            } else if stream.eat_word("null") {
+                column.nullable = Some(true);
+            } else if stream.eat_word("default") {
+                column.default_value = Some(Self::parse_default_value(stream)?);
+            } else if stream.eat_words(&["primary", "key"]) {
+                column.primary_key = true;
+            } else if stream.eat_word("unique") {
+                stream.eat_words(&["nulls", "not", "distinct"]);
+                stream.eat_words(&["nulls", "distinct"]);
+                column.unique_key = true;
+            } else if stream.eat_word("collate") {
+                column.collation = Some(stream.expect_identifier()?);
+            } else if stream.eat_word("check") {
+                stream.expect_group()?;
+                stream.eat_words(&["no", "inherit"]);
+            } else if stream.eat_word("generated") {
+                if stream.eat_words(&["always", "as", "identity"])
+                    || stream.eat_words(&["by", "default", "as", "identity"])
+                {
+                    column.auto_increment = true;
+                    if stream.peek_symbol('(') {
+                        // sequence options
+                        stream.expect_group()?;
+                    }
+                } else if stream.eat_words(&["always", "as"]) {
+                    column.generated = Some(stream.expect_group()?);
+                    column.generated_stored = stream.eat_word("stored");
+                } else {
+                    bail! {stream.unexpected("unsupported GENERATED")}
+                }
+            } else if stream.eat_word("references") {
+                stream.take_until_comma();
+            } else if stream.eat_word("deferrable")
+                || stream.eat_words(&["not", "deferrable"])
+                || stream.eat_words(&["initially", "deferred"])
+                || stream.eat_words(&["initially", "immediate"])
+            {
+            } else {
+                bail! {stream.unexpected("unsupported column constraint")}
+            }
+        }
+        Ok(column)
+    }
+
+    /// data types: integer, character varying(255), numeric(10,2), timestamp(3) with time zone,
+    /// int[], "MyType", public.my_type
+    fn parse_data_type(
+        stream: &mut TokenStream,
+        column: &mut ColumnDefinition,
+    ) -> anyhow::Result<()> {
+        let start = match stream.peek() {
+            Some(t) if matches!(t.kind, TokenKind::Word | TokenKind::QuotedIdentifier) => t.start,
+            _ => bail! {stream.unexpected("expect data type")},
+        };
+
+        let mut end = start;
+        let mut data_type: Vec<String> = Vec::new();
+        while let Some(token) = stream.peek() {
+            if token.is_symbol(',')
+                || COLUMN_CONSTRAINT_KEYWORDS
+                    .iter()
+                    .any(|keyword| token.is_word(keyword))
+            {
+                break;
+            }
+
+            if token.is_symbol('(') {
+                if column.type_args.is_empty() {
+                    column.type_args = stream.expect_list()?;
+                } else {
+                    stream.skip_group()?;
+                }
+                end = stream.tokens[stream.pos - 1].end;
+                continue;
+            }
+
+            let text = token.text.to_lowercase();
+            match data_type.last() {
+                // schema qualified type, array type
+                Some(last) if Self::is_type_joiner(last) || Self::is_type_joiner(&text) => {
+                    let last = data_type.pop().unwrap();
+                    data_type.push(format!("{}{}", last, text));
+                }
+                _ => data_type.push(text),
+            }
+            end = token.end;
+            stream.next_token();
+        }
+
+        column.column_type = stream.sql[start..end].to_string();
+        column.data_type = data_type.join(" ");
+        Ok(())
+    }
+
+    fn is_type_joiner(text: &str) -> bool {
+        matches!(text, "." | "[" | "]")
+    }
+
+    /// DEFAULT values: 'abc'::character varying, nextval('seq_1'::regclass), now(), -1, NULL
+    fn parse_default_value(stream: &mut TokenStream) -> anyhow::Result<String> {
+        let start = match stream.peek() {
+            Some(t) => t.start,
+            None => bail! {stream.unexpected("expect default value")},
+        };
+
+        let mut end = start;
+        let mut is_first = true;
+        while let Some(token) = stream.peek() {
+            if token.is_symbol(',')
+                || (!is_first
+                    && COLUMN_CONSTRAINT_KEYWORDS
+                        .iter()
+                        .any(|keyword| token.is_word(keyword)))
+            {
+                break;
+            }
+            is_first = false;
+
+            if token.is_symbol('(') {
+                stream.skip_group()?;
+                end = stream.tokens[stream.pos - 1].end;
+                continue;
+            }
+            end = token.end;
+            stream.next_token();
+        }
+        Ok(stream.sql[start..end].to_string())
+    }
+
+    fn eat_drop_behavior(stream: &mut TokenStream) {
+        let _ = stream.eat_word("cascade") || stream.eat_word("restrict");
+    }
+
+    fn expect_word(stream: &mut TokenStream, word: &str) -> anyhow::Result<()> {
+        if !stream.eat_word(word) {
+            bail! {stream.unexpected(&format!("expect: {}", word.to_uppercase()))}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_alter_table() {
+        let sql = r#"ADD COLUMN IF NOT EXISTS "f_1" character varying(255) DEFAULT 'a'::character varying NOT NULL,
+            ADD f_2 timestamp(3) with time zone DEFAULT now(),
+            ADD f_3 int[],
+            DROP COLUMN IF EXISTS f_4 CASCADE,
+            ALTER COLUMN f_5 TYPE numeric(10,2) USING f_5::numeric(10,2),
+            ALTER f_6 SET DEFAULT nextval('seq_1'::regclass),
+            ALTER COLUMN f_7 DROP NOT NULL,
+            RENAME COLUMN f_8 TO f_9,
+            ADD CONSTRAINT uk_1 UNIQUE (f_1, f_2),
+            DROP CONSTRAINT fk_1,
+            OWNER TO user_1"#;
+
+        let operations = PgDefinitionParser::parse_alter_table(sql).unwrap();
+        assert_eq!(operations.len(), 11);
+
+        match &operations[0] {
+            AlterTableOperation::AddColumn { column, .. } => {
+                assert_eq!(column.column_name, "f_1");
+                assert_eq!(column.column_type, "character varying(255)");
+                assert_eq!(column.data_type, "character varying");
+                assert_eq!(column.type_args, vec!["255".to_string()]);
+                assert_eq!(column.default_value, Some("'a'::character varying".into()));
+                assert_eq!(column.nullable, Some(false));
+            }
+            _ => panic!(),
+        }
+        match &operations[1] {
+            AlterTableOperation::AddColumn { column, .. } => {
+                assert_eq!(column.data_type, "timestamp with time zone");
+                assert_eq!(column.type_args, vec!["3".to_string()]);
+                assert_eq!(column.default_value, Some("now()".into()));
+            }
+            _ => panic!(),
+        }
+        match &operations[2] {
+            AlterTableOperation::AddColumn { column, .. } => {
+                assert_eq!(column.data_type, "int[]");
+            }
+            _ => panic!(),
+        }
+        assert_eq!(
+            operations[3],
+            AlterTableOperation::DropColumn {
+                column_name: "f_4".into()
+            }
+        );
+        match &operations[4] {
+            AlterTableOperation::AlterColumnType { column } => {
+                assert_eq!(column.column_name, "f_5");
+                assert_eq!(column.data_type, "numeric");
+                assert_eq!(column.type_args, vec!["10".to_string(), "2".to_string()]);
+            }
+            _ => panic!(),
+        }
+        assert_eq!(
+            operations[5],
+            AlterTableOperation::SetColumnDefault {
+                column_name: "f_6".into(),
+                default_value: "nextval('seq_1'::regclass)".into()
+            }
+        );
+        assert_eq!(
+            operations[6],
+            AlterTableOperation::DropColumnNotNull {
+                column_name: "f_7".into()
+            }
+        );
+        assert_eq!(
+            operations[7],
+            AlterTableOperation::RenameColumn {
+                old_column_name: "f_8".into(),
+                new_column_name: "f_9".into()
+            }
+        );
+        assert_eq!(
+            operations[8],
+            AlterTableOperation::AddIndex {
+                index: IndexDefinition {
+                    index_name: Some("uk_1".into()),
+                    index_kind: IndexDefinitionKind::Unique,
+                    columns: vec!["f_1".into(), "f_2".into()],
+                }
+            }
+        );
+        assert_eq!(
+            operations[9],
+            AlterTableOperation::DropConstraint {
+                constraint_name: "fk_1".into()
+            }
+        );
+        assert_eq!(
+            operations[10],
+            AlterTableOperation::TableOption {
+                option: "OWNER TO user_1".into()
+            }
+        );
+    }
+}
@@ -3,6 +3,7 @@
 pub mod adaptor;
 pub mod avro;
 pub mod col_value;
+pub mod condition;
 pub mod ddl_meta;
 pub mod dt_data;
 pub mod dt_queue;
@@ -1,3 +1,8 @@
 pub mod mongo_cdc_source;
 pub mod mongo_constant;
+pub mod mongo_ddl_parser;
+pub mod mongo_id_range;
 pub mod mongo_key;
+pub mod mongo_oplog_merger;
+pub mod mongo_row_converter;
+pub mod mongo_update_parser;
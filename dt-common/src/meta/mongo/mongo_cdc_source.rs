@@ -8,6 +8,9 @@ pub enum MongoCdcSource {
     #[strum(serialize = "op_log")]
     OpLog,
 
+    #[strum(serialize = "sharded_op_log")]
+    ShardedOpLog,
+
     #[strum(serialize = "change_stream")]
     ChangeStream,
 }
@@ -17,6 +20,7 @@ impl FromStr for MongoCdcSource {
     fn from_str(str: &str) -> Result<Self, Self::Err> {
         match str {
             "op_log" => Ok(Self::OpLog),
+            "sharded_op_log" => Ok(Self::ShardedOpLog),
             _ => Ok(Self::ChangeStream),
         }
     }
@@ -6,4 +6,5 @@ impl MongoConstants {
     pub const DIFF_DOC: &'static str = "diff_doc";
     pub const SET: &'static str = "$set";
     pub const UNSET: &'static str = "$unset";
+    pub const PUSH: &'static str = "$push";
 }
@@ -0,0 +1,287 @@
+use mongodb::bson::{Bson, Document};
+
+use crate::{
+    config::config_enums::DbType,
+    meta::ddl_meta::{
+        ddl_data::DdlData,
+        ddl_statement::{
+            DdlStatement, DropDatabaseStatement, DropTableStatement, MongoCreateIndexesStatement,
+            MongoCreateStatement, MongoDropIndexesStatement, RenameTableStatement,
+        },
+        ddl_type::DdlType,
+    },
+};
+
+pub struct MongoDdlParser {}
+
+impl MongoDdlParser {
+    /// parse the "o" field of an oplog "c" entry, the first key of it is the command name,
+    /// None if the command is not a ddl, e.g. applyOps, startIndexBuild, collMod
+    pub fn parse_oplog_command(db: &str, o: &Document) -> Option<DdlData> {
+        let (command, value) = o.iter().next()?;
+        let ddl_data = match command.as_str() {
+            // { "create": "tb_1", "idIndex": { "v": 2, "key": { "_id": 1 }, "name": "_id_" }, "capped": true, ... }
+            "create" => {
+                let mut options = o.clone();
+                options.remove("create");
+                // _id index is created with the collection
+                options.remove("idIndex");
+                Self::create_collection(db, value.as_str()?, options)
+            }
+
+            // { "drop": "tb_1" }
+            "drop" => Self::drop_collection(db, value.as_str()?),
+
+            // { "renameCollection": "db_1.tb_1", "to": "db_2.tb_2", "stayTemp": false }
+            "renameCollection" => {
+                let (src_db, src_tb) = Self::split_ns(value.as_str()?)?;
+                let (dst_db, dst_tb) = Self::split_ns(o.get_str("to").ok()?)?;
+                Self::rename_collection(src_db, src_tb, dst_db, dst_tb)
+            }
+
+            // { "dropDatabase": 1 }
+            "dropDatabase" => Self::drop_database(db),
+
+            // index created on an empty collection:
+            // { "createIndexes": "tb_1", "v": 2, "key": { "a": 1 }, "name": "a_1", "unique": true }
+            "createIndexes" => {
+                let mut spec = o.clone();
+                spec.remove("createIndexes");
+                Self::create_indexes(db, value.as_str()?, vec![spec])
+            }
+
+            // index built on a non-empty collection by two-phase index build (4.4+):
+            // { "commitIndexBuild": "tb_1", "indexBuildUUID": ..., "indexes": [{ "v": 2, "key": { "a": 1 }, "name": "a_1" }] }
+            "commitIndexBuild" => {
+                let mut specs = Vec::new();
+                for index in o.get_array("indexes").ok()? {
+                    if let Bson::Document(spec) = index {
+                        specs.push(spec.clone());
+                    }
+                }
+                Self::create_indexes(db, value.as_str()?, specs)
+            }
+
+            // { "dropIndexes": "tb_1", "index": "a_1" }, deleteIndexes is the legacy name
+            "dropIndexes" | "deleteIndexes" => {
+                Self::drop_indexes(db, value.as_str()?, o.get_str("index").ok()?)
+            }
+
+            _ => return None,
+        };
+        Some(ddl_data)
+    }
+
+    pub fn create_collection(db: &str, tb: &str, options: Document) -> DdlData {
+        let statement = MongoCreateStatement {
+            db: db.into(),
+            tb: tb.into(),
+            options: Self::to_extjson(options),
+        };
+        Self::build(
+            db,
+            DdlType::CreateTable,
+            DdlStatement::MongoCreate(statement),
+        )
+    }
+
+    pub fn drop_collection(db: &str, tb: &str) -> DdlData {
+        let statement = DropTableStatement {
+            schema: db.into(),
+            tb: tb.into(),
+            ..Default::default()
+        };
+        Self::build(db, DdlType::DropTable, DdlStatement::DropTable(statement))
+    }
+
+    pub fn rename_collection(db: &str, tb: &str, new_db: &str, new_tb: &str) -> DdlData {
+        let statement = RenameTableStatement {
+            schema: db.into(),
+            tb: tb.into(),
+            new_schema: new_db.into(),
+            new_tb: new_tb.into(),
+            ..Default::default()
+        };
+        Self::build(
+            db,
+            DdlType::RenameTable,
+            DdlStatement::RenameTable(statement),
+        )
+    }
+
+    pub fn drop_database(db: &str) -> DdlData {
+        let statement = DropDatabaseStatement {
+            db: db.into(),
+            ..Default::default()
+        };
+        Self::build(
+            db,
+            DdlType::DropDatabase,
+            DdlStatement::DropDatabase(statement),
+        )
+    }
+
+    pub fn create_indexes(db: &str, tb: &str, specs: Vec<Document>) -> DdlData {
+        let statement = MongoCreateIndexesStatement {
+            db: db.into(),
+            tb: tb.into(),
+            indexes: specs.into_iter().map(Self::to_extjson).collect(),
+        };
+        Self::build(
+            db,
+            DdlType::CreateIndex,
+            DdlStatement::MongoCreateIndexes(statement),
+        )
+    }
+
+    pub fn drop_indexes(db: &str, tb: &str, index_name: &str) -> DdlData {
+        let statement = MongoDropIndexesStatement {
+            db: db.into(),
+            tb: tb.into(),
+            index_name: index_name.into(),
+        };
+        Self::build(
+            db,
+            DdlType::DropIndex,
+            DdlStatement::MongoDropIndexes(statement),
+        )
+    }
+
+    fn build(db: &str, ddl_type: DdlType, statement: DdlStatement) -> DdlData {
+        let mut ddl_data = DdlData {
+            default_schema: db.into(),
+            ddl_type,
+            db_type: DbType::Mongo,
+            statement,
+            ..Default::default()
+        };
+        ddl_data.query = ddl_data.to_sql();
+        ddl_data
+    }
+
+    fn split_ns(ns: &str) -> Option<(&str, &str)> {
+        ns.split_once('.')
+    }
+
+    fn to_extjson(doc: Document) -> String {
+        // canonical mode keeps the bson types, e.g. int32 / int64
+        Bson::Document(doc).into_canonical_extjson().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use crate::meta::ddl_meta::ddl_type::DdlType;
+
+    use super::MongoDdlParser;
+
+    #[test]
+    fn test_parse_create() {
+        let o = doc! {
+            "create": "tb_1",
+            "idIndex": { "v": 2, "key": { "_id": 1 }, "name": "_id_" },
+            "capped": true,
+            "size": 4096_i64,
+        };
+        let ddl_data = MongoDdlParser::parse_oplog_command("db_1", &o).unwrap();
+        assert_eq!(ddl_data.ddl_type, DdlType::CreateTable);
+        assert_eq!(
+            ddl_data.get_schema_tb(),
+            ("db_1".to_string(), "tb_1".to_string())
+        );
+
+        let (db, command) = ddl_data.statement.to_mongo_command().unwrap().unwrap();
+        assert_eq!(db, "db_1");
+        assert_eq!(
+            command,
+            doc! { "create": "tb_1", "capped": true, "size": 4096_i64 }
+        );
+        assert_eq!(
+            ddl_data.query,
+            r#"{"create":"tb_1","capped":true,"size":4096}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_rename() {
+        let o = doc! { "renameCollection": "db_1.tb.1", "to": "db_2.tb_2", "stayTemp": false };
+        let ddl_data = MongoDdlParser::parse_oplog_command("admin", &o).unwrap();
+        assert_eq!(ddl_data.ddl_type, DdlType::RenameTable);
+        assert_eq!(
+            ddl_data.get_schema_tb(),
+            ("db_1".to_string(), "tb.1".to_string())
+        );
+        assert_eq!(
+            ddl_data.get_rename_to_schema_tb(),
+            ("db_2".to_string(), "tb_2".to_string())
+        );
+
+        let (db, command) = ddl_data.statement.to_mongo_command().unwrap().unwrap();
+        assert_eq!(db, "admin");
+        assert_eq!(
+            command,
+            doc! { "renameCollection": "db_1.tb.1", "to": "db_2.tb_2", "dropTarget": true }
+        );
+    }
+
+    #[test]
+    fn test_parse_indexes() {
+        let o = doc! { "createIndexes": "tb_1", "v": 2, "key": { "a": 1 }, "name": "a_1", "unique": true };
+        let ddl_data = MongoDdlParser::parse_oplog_command("db_1", &o).unwrap();
+        assert_eq!(ddl_data.ddl_type, DdlType::CreateIndex);
+        let (_, command) = ddl_data.statement.to_mongo_command().unwrap().unwrap();
+        assert_eq!(
+            command,
+            doc! { "createIndexes": "tb_1", "indexes": [{ "v": 2, "key": { "a": 1 }, "name": "a_1", "unique": true }] }
+        );
+
+        let o = doc! {
+            "commitIndexBuild": "tb_1",
+            "indexes": [{ "v": 2, "key": { "a": 1 }, "name": "a_1" }, { "v": 2, "key": { "b": -1 }, "name": "b_-1" }],
+        };
+        let ddl_data = MongoDdlParser::parse_oplog_command("db_1", &o).unwrap();
+        assert_eq!(ddl_data.ddl_type, DdlType::CreateIndex);
+        let (_, command) = ddl_data.statement.to_mongo_command().unwrap().unwrap();
+        assert_eq!(
+            command,
+            doc! {
+                "createIndexes": "tb_1",
+                "indexes": [{ "v": 2, "key": { "a": 1 }, "name": "a_1" }, { "v": 2, "key": { "b": -1 }, "name": "b_-1" }],
+            }
+        );
+
+        let o = doc! { "dropIndexes": "tb_1", "index": "a_1" };
+        let ddl_data = MongoDdlParser::parse_oplog_command("db_1", &o).unwrap();
+        assert_eq!(ddl_data.ddl_type, DdlType::DropIndex);
+        let (_, command) = ddl_data.statement.to_mongo_command().unwrap().unwrap();
+        assert_eq!(command, doc! { "dropIndexes": "tb_1", "index": "a_1" });
+    }
+
+    #[test]
+    fn test_parse_drop() {
+        let o = doc! { "drop": "tb_1" };
+        let ddl_data = MongoDdlParser::parse_oplog_command("db_1", &o).unwrap();
+        assert_eq!(ddl_data.ddl_type, DdlType::DropTable);
+        let (db, command) = ddl_data.statement.to_mongo_command().unwrap().unwrap();
+        assert_eq!(db, "db_1");
+        assert_eq!(command, doc! { "drop": "tb_1" });
+
+        let o = doc! { "dropDatabase": 1 };
+        let ddl_data = MongoDdlParser::parse_oplog_command("db_1", &o).unwrap();
+        assert_eq!(ddl_data.ddl_type, DdlType::DropDatabase);
+        let (db, command) = ddl_data.statement.to_mongo_command().unwrap().unwrap();
+        assert_eq!(db, "db_1");
+        assert_eq!(command, doc! { "dropDatabase": 1 });
+    }
+
+    #[test]
+    fn test_parse_not_ddl() {
+        let o = doc! { "applyOps": [{ "op": "d", "ns": "db_1.tb_1", "o": { "_id": 1 } }] };
+        assert!(MongoDdlParser::parse_oplog_command("admin", &o).is_none());
+
+        let o = doc! { "startIndexBuild": "tb_1", "indexes": [] };
+        assert!(MongoDdlParser::parse_oplog_command("db_1", &o).is_none());
+    }
+}
@@ -0,0 +1,260 @@
+use mongodb::bson::{doc, oid::ObjectId, Bson, Document};
+
+use super::mongo_constant::MongoConstants;
+
+// refer: https://www.mongodb.com/docs/manual/reference/bson-type-comparison-order/
+// $gt / $lte only match values of the same type bracket as the operand, so types
+// sorted before / after the bracket are matched by $type explicitly
+const TYPE_BRACKETS: [&[&str]; 13] = [
+    &["minKey"],
+    &["null", "undefined"],
+    &["number"],
+    &["string", "symbol"],
+    &["object"],
+    &["array"],
+    &["binData"],
+    &["objectId"],
+    &["bool"],
+    &["date"],
+    &["timestamp"],
+    &["regex"],
+    &["maxKey"],
+];
+
+/// a range of _id in the order of "sort by _id", both bounds are unlimited if None
+#[derive(Debug, Clone, PartialEq)]
+pub struct MongoIdRange {
+    // exclusive
+    pub start: Option<Bson>,
+    // inclusive
+    pub end: Option<Bson>,
+}
+
+impl MongoIdRange {
+    /// split (start, +∞) by the sorted sample ids into at most range_count ranges
+    pub fn split(start: Option<Bson>, sorted_ids: &[Bson], range_count: usize) -> Vec<Self> {
+        let mut boundaries: Vec<&Bson> = Vec::new();
+        if !sorted_ids.is_empty() {
+            for i in 1..range_count {
+                let id = &sorted_ids[i * sorted_ids.len() / range_count];
+                if boundaries.last() != Some(&id) {
+                    boundaries.push(id);
+                }
+            }
+        }
+
+        let mut ranges = Vec::new();
+        let mut range_start = start;
+        for boundary in boundaries {
+            ranges.push(Self {
+                start: range_start,
+                end: Some(boundary.clone()),
+            });
+            range_start = Some(boundary.clone());
+        }
+        ranges.push(Self {
+            start: range_start,
+            end: None,
+        });
+        ranges
+    }
+
+    pub fn to_filter(&self) -> Option<Document> {
+        let start_filter = self
+            .start
+            .as_ref()
+            .map(|id| Self::build_filter(id, "$gt", true));
+        let end_filter = self
+            .end
+            .as_ref()
+            .map(|id| Self::build_filter(id, "$lte", false));
+
+        match (start_filter, end_filter) {
+            (Some(start_filter), Some(end_filter)) => {
+                Some(doc! { "$and": [start_filter, end_filter] })
+            }
+            (Some(filter), None) | (None, Some(filter)) => Some(filter),
+            (None, None) => None,
+        }
+    }
+
+    /// resume values are stored in canonical extended json to keep the bson type of _id
+    pub fn to_resume_value(id: &Bson) -> String {
+        id.clone().into_canonical_extjson().to_string()
+    }
+
+    pub fn from_resume_value(value: &str) -> anyhow::Result<Bson> {
+        // resume values of older versions are hex strings of ObjectId
+        if let Ok(oid) = ObjectId::parse_str(value) {
+            return Ok(Bson::ObjectId(oid));
+        }
+        let value: serde_json::Value = serde_json::from_str(value)?;
+        Ok(Bson::try_from(value)?)
+    }
+
+    fn build_filter(id: &Bson, op: &str, after: bool) -> Document {
+        let cmp_filter = doc! { MongoConstants::ID: { op: id.clone() } };
+        let bracket = match Self::get_type_bracket(id) {
+            Some(bracket) => bracket,
+            None => return cmp_filter,
+        };
+
+        let other_types: Vec<&str> = if after {
+            TYPE_BRACKETS[bracket + 1..].concat()
+        } else {
+            TYPE_BRACKETS[..bracket].concat()
+        };
+        if other_types.is_empty() {
+            return cmp_filter;
+        }
+        doc! { "$or": [cmp_filter, { MongoConstants::ID: { "$type": other_types } }] }
+    }
+
+    fn get_type_bracket(id: &Bson) -> Option<usize> {
+        let bracket = match id {
+            Bson::MinKey => 0,
+            Bson::Null | Bson::Undefined => 1,
+            Bson::Int32(_) | Bson::Int64(_) | Bson::Double(_) | Bson::Decimal128(_) => 2,
+            Bson::String(_) | Bson::Symbol(_) => 3,
+            Bson::Document(_) => 4,
+            Bson::Array(_) => 5,
+            Bson::Binary(_) => 6,
+            Bson::ObjectId(_) => 7,
+            Bson::Boolean(_) => 8,
+            Bson::DateTime(_) => 9,
+            Bson::Timestamp(_) => 10,
+            Bson::RegularExpression(_) => 11,
+            Bson::MaxKey => 12,
+            // JavaScriptCode, DbPointer, etc.
+            _ => return None,
+        };
+        Some(bracket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, oid::ObjectId, Bson};
+
+    use super::MongoIdRange;
+
+    #[test]
+    fn test_split() {
+        let ids: Vec<Bson> = (0..10).map(Bson::Int32).collect();
+        let ranges = MongoIdRange::split(None, &ids, 3);
+        assert_eq!(
+            ranges,
+            vec![
+                MongoIdRange {
+                    start: None,
+                    end: Some(Bson::Int32(3)),
+                },
+                MongoIdRange {
+                    start: Some(Bson::Int32(3)),
+                    end: Some(Bson::Int32(6)),
+                },
+                MongoIdRange {
+                    start: Some(Bson::Int32(6)),
+                    end: None,
+                },
+            ]
+        );
+
+        // duplicate boundaries are merged
+        let ids = vec![
+            Bson::Int32(1),
+            Bson::Int32(1),
+            Bson::Int32(1),
+            Bson::Int32(2),
+        ];
+        let ranges = MongoIdRange::split(Some(Bson::Int32(0)), &ids, 4);
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0].start, Some(Bson::Int32(0)));
+        assert_eq!(ranges[1].end, Some(Bson::Int32(2)));
+
+        // no samples
+        let ranges = MongoIdRange::split(None, &[], 1);
+        assert_eq!(
+            ranges,
+            vec![MongoIdRange {
+                start: None,
+                end: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_to_filter() {
+        let range = MongoIdRange {
+            start: None,
+            end: None,
+        };
+        assert_eq!(range.to_filter(), None);
+
+        let range = MongoIdRange {
+            start: Some(Bson::String("a".into())),
+            end: None,
+        };
+        assert_eq!(
+            range.to_filter().unwrap(),
+            doc! { "$or": [
+                { "_id": { "$gt": "a" } },
+                { "_id": { "$type": ["object", "array", "binData", "objectId", "bool", "date", "timestamp", "regex", "maxKey"] } },
+            ] }
+        );
+
+        let range = MongoIdRange {
+            start: Some(Bson::Int32(1)),
+            end: Some(Bson::String("a".into())),
+        };
+        assert_eq!(
+            range.to_filter().unwrap(),
+            doc! { "$and": [
+                { "$or": [
+                    { "_id": { "$gt": 1 } },
+                    { "_id": { "$type": ["string", "symbol", "object", "array", "binData", "objectId", "bool", "date", "timestamp", "regex", "maxKey"] } },
+                ] },
+                { "$or": [
+                    { "_id": { "$lte": "a" } },
+                    { "_id": { "$type": ["minKey", "null", "undefined", "number"] } },
+                ] },
+            ] }
+        );
+
+        let range = MongoIdRange {
+            start: None,
+            end: Some(Bson::MinKey),
+        };
+        assert_eq!(
+            range.to_filter().unwrap(),
+            doc! { "_id": { "$lte": Bson::MinKey } }
+        );
+    }
+
+    #[test]
+    fn test_resume_value() {
+        let oid = ObjectId::parse_str("648195af9aa9cadd41a9dcb3").unwrap();
+        let ids = vec![
+            Bson::ObjectId(oid),
+            Bson::String("648195af9aa9cadd41a9dcb3".into()),
+            Bson::Int32(1),
+            Bson::Int64(1),
+            Bson::Double(1.5),
+            Bson::Document(doc! { "a": 1, "b": "x" }),
+        ];
+        for id in ids {
+            let value = MongoIdRange::to_resume_value(&id);
+            assert_eq!(MongoIdRange::from_resume_value(&value).unwrap(), id);
+        }
+
+        assert_eq!(
+            MongoIdRange::to_resume_value(&Bson::Int64(1)),
+            r#"{"$numberLong":"1"}"#
+        );
+        // compatible with resume values of older versions
+        assert_eq!(
+            MongoIdRange::from_resume_value("648195af9aa9cadd41a9dcb3").unwrap(),
+            Bson::ObjectId(oid)
+        );
+    }
+}
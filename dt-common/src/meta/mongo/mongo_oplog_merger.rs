@@ -0,0 +1,147 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+use anyhow::bail;
+use mongodb::bson::{Document, Timestamp};
+
+use crate::error::Error;
+
+struct PendingEntry {
+    ts: Timestamp,
+    shard_index: usize,
+    seq: u64,
+    doc: Document,
+}
+
+impl PendingEntry {
+    fn key(&self) -> (Timestamp, usize, u64) {
+        (self.ts, self.shard_index, self.seq)
+    }
+}
+
+impl PartialEq for PendingEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for PendingEntry {}
+
+impl PartialOrd for PendingEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// merges oplog entries tailed from multiple shards in the order of cluster time (ts),
+/// an entry is released only after every shard has been read up to its ts, so no entry
+/// with a smaller ts can arrive later. Idle shards are advanced by their periodic noops.
+pub struct MongoOplogMerger {
+    watermarks: Vec<Option<Timestamp>>,
+    pending: BinaryHeap<Reverse<PendingEntry>>,
+    seq: u64,
+}
+
+impl MongoOplogMerger {
+    pub fn new(shard_count: usize) -> Self {
+        Self {
+            watermarks: vec![None; shard_count],
+            pending: BinaryHeap::new(),
+            seq: 0,
+        }
+    }
+
+    /// entries of a shard must be pushed in the order they are read from its oplog
+    pub fn push(&mut self, shard_index: usize, doc: Document) -> anyhow::Result<()> {
+        let ts = match doc.get_timestamp("ts") {
+            Ok(ts) => ts,
+            Err(_) => bail! {Error::Unexpected(format!(
+                "oplog entry without ts, shard_index: {}, entry: {:?}",
+                shard_index, doc
+            ))},
+        };
+
+        self.watermarks[shard_index] = Some(ts);
+        self.seq += 1;
+        self.pending.push(Reverse(PendingEntry {
+            ts,
+            shard_index,
+            seq: self.seq,
+            doc,
+        }));
+        Ok(())
+    }
+
+    /// the next entry in cluster time order with its shard index, None if it is not decidable yet
+    pub fn pop(&mut self) -> Option<(usize, Document)> {
+        let ts = self.pending.peek()?.0.ts;
+        for watermark in self.watermarks.iter() {
+            match watermark {
+                Some(watermark) if *watermark >= ts => {}
+                _ => return None,
+            }
+        }
+
+        let entry = self.pending.pop()?.0;
+        Some((entry.shard_index, entry.doc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, Timestamp};
+
+    use super::MongoOplogMerger;
+
+    fn entry(time: u32, increment: u32, op: &str) -> mongodb::bson::Document {
+        doc! { "ts": Timestamp { time, increment }, "op": op }
+    }
+
+    fn pop_all(merger: &mut MongoOplogMerger) -> Vec<(usize, u32, u32)> {
+        let mut result = Vec::new();
+        while let Some((shard_index, doc)) = merger.pop() {
+            let ts = doc.get_timestamp("ts").unwrap();
+            result.push((shard_index, ts.time, ts.increment));
+        }
+        result
+    }
+
+    #[test]
+    fn test_merge_by_ts() {
+        let mut merger = MongoOplogMerger::new(2);
+        merger.push(0, entry(10, 1, "i")).unwrap();
+        merger.push(0, entry(12, 1, "u")).unwrap();
+        // shard 1 has not been read yet
+        assert!(pop_all(&mut merger).is_empty());
+
+        merger.push(1, entry(11, 1, "i")).unwrap();
+        assert_eq!(pop_all(&mut merger), vec![(0, 10, 1), (1, 11, 1)]);
+
+        // the same ts in different shards
+        merger.push(1, entry(12, 1, "d")).unwrap();
+        assert_eq!(pop_all(&mut merger), vec![(0, 12, 1), (1, 12, 1)]);
+
+        merger.push(1, entry(12, 2, "i")).unwrap();
+        assert!(pop_all(&mut merger).is_empty());
+
+        // a periodic noop of the idle shard releases entries of other shards
+        merger.push(0, entry(13, 1, "n")).unwrap();
+        assert_eq!(pop_all(&mut merger), vec![(1, 12, 2)]);
+        merger.push(1, entry(20, 1, "n")).unwrap();
+        assert_eq!(pop_all(&mut merger), vec![(0, 13, 1)]);
+    }
+
+    #[test]
+    fn test_entry_without_ts() {
+        let mut merger = MongoOplogMerger::new(1);
+        assert!(merger.push(0, doc! { "op": "n" }).is_err());
+    }
+}
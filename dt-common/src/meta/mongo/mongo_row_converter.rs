@@ -0,0 +1,1017 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use mongodb::bson::{Bson, Document};
+use serde::Deserialize;
+
+use crate::{
+    config::{config_enums::DbType, mongo_mapping_config::MongoMappingConfig},
+    error::Error,
+    meta::{
+        col_value::ColValue,
+        row_data::RowData,
+        row_type::RowType,
+        struct_meta::{
+            statement::{
+                mysql_create_table_statement::MysqlCreateTableStatement,
+                pg_create_table_statement::PgCreateTableStatement,
+                struct_statement::StructStatement,
+            },
+            structure::{
+                column::Column,
+                constraint::{Constraint, ConstraintType},
+                table::Table,
+            },
+        },
+    },
+};
+
+use super::mongo_constant::MongoConstants;
+
+const IDX_COL: &str = "_idx";
+const VALUE_COL: &str = "value";
+const JSON_PREFIX: &str = "json:";
+
+#[derive(Deserialize)]
+struct CollectionMapType {
+    db: String,
+    tb: String,
+    #[serde(default)]
+    col_map: HashMap<String, String>,
+    #[serde(default)]
+    child_tb_map: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct CollectionRule {
+    // HashMap<field_path, col>
+    col_map: HashMap<String, String>,
+    // Vec<(array_path, child_tb)>, sorted by array_path
+    child_tbs: Vec<(String, String)>,
+}
+
+// Vec<(col, col_type)>, col_type is None if all sampled values are null
+type ColTypes = Vec<(String, Option<SqlColType>)>;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SqlColType {
+    Bool,
+    Int,
+    BigInt,
+    Double,
+    Decimal,
+    DateTime,
+    String,
+    Binary,
+    Json,
+}
+
+/// flatten mongo documents into rows:
+/// nested documents -> columns joined by separator (up to flatten_depth), arrays -> json columns,
+/// arrays in child_tb_map -> rows of child tables {_id, _idx, flattened element / value}
+pub struct MongoRowConverter {
+    flatten_depth: usize,
+    separator: String,
+    // HashMap<(db, tb), CollectionRule>, db / tb are the routed names
+    rules: HashMap<(String, String), CollectionRule>,
+    default_rule: CollectionRule,
+}
+
+impl MongoRowConverter {
+    pub fn new(config: &MongoMappingConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            flatten_depth: config.flatten_depth,
+            separator: config.separator.clone(),
+            rules: Self::parse_rules(&config.collection_map)?,
+            default_rule: CollectionRule::default(),
+        })
+    }
+
+    pub fn has_child_tbs(&self) -> bool {
+        self.rules.values().any(|rule| !rule.child_tbs.is_empty())
+    }
+
+    pub fn convert(&self, row_data: RowData) -> anyhow::Result<Vec<RowData>> {
+        let before_doc = Self::get_doc(&row_data.before, MongoConstants::DOC);
+        let after_doc = Self::get_doc(&row_data.after, MongoConstants::DOC);
+        let diff_doc = Self::get_doc(&row_data.after, MongoConstants::DIFF_DOC);
+        // not a mongo row
+        if before_doc.is_none() && after_doc.is_none() && diff_doc.is_none() {
+            return Ok(vec![row_data]);
+        }
+
+        let (schema, tb) = (&row_data.schema, &row_data.tb);
+        let rule = self.get_rule(schema, tb);
+        let id = match after_doc
+            .or(before_doc)
+            .and_then(|doc| doc.get(MongoConstants::ID))
+        {
+            Some(id) => id,
+            None => bail! {Error::Unexpected(format!(
+                "schema: {}, tb: {}, no _id found in mongo row",
+                schema, tb
+            ))},
+        };
+
+        let rows = match row_data.row_type {
+            RowType::Insert => self.convert_doc(schema, tb, rule, after_doc.unwrap()),
+
+            RowType::Delete => {
+                let before = Self::build_id_cols(id, None);
+                let mut rows = vec![RowData::new(
+                    schema.into(),
+                    tb.into(),
+                    RowType::Delete,
+                    Some(before),
+                    None,
+                )];
+                Self::push_child_deletes(&mut rows, schema, rule, id);
+                rows
+            }
+
+            // the full document is written as an insert, which replaces the existing row
+            // if sinker.replace=true, the missing fields are set to NULL since partial_upsert
+            // is not allowed with [mongo_mapping]. rows of child tables are deleted by _id
+            // and inserted again
+            RowType::Update => match (after_doc, diff_doc) {
+                (Some(doc), _) => {
+                    let mut rows = Vec::new();
+                    Self::push_child_deletes(&mut rows, schema, rule, id);
+                    rows.extend(self.convert_doc(schema, tb, rule, doc));
+                    rows
+                }
+                (None, Some(diff_doc)) => self.convert_diff(schema, tb, rule, id, diff_doc)?,
+                (None, None) => vec![],
+            },
+        };
+        Ok(rows)
+    }
+
+    /// infer target tables by converting the sampled documents of a collection
+    pub fn build_create_table_statements(
+        &self,
+        db: &str,
+        tb: &str,
+        docs: &[Document],
+        db_type: &DbType,
+    ) -> anyhow::Result<Vec<StructStatement>> {
+        let rule = self.get_rule(db, tb);
+        // Vec<(tb, col_types)>, the parent table comes first
+        let mut tables: Vec<(String, ColTypes)> = vec![(tb.into(), vec![])];
+        for (_, child_tb) in rule.child_tbs.iter() {
+            tables.push((child_tb.clone(), vec![]));
+        }
+
+        for doc in docs {
+            if !doc.contains_key(MongoConstants::ID) {
+                continue;
+            }
+            for row_data in self.convert_doc(db, tb, rule, doc) {
+                let (_, cols) = tables.iter_mut().find(|(i, _)| *i == row_data.tb).unwrap();
+                Self::merge_col_types(cols, row_data.after.as_ref().unwrap());
+            }
+        }
+
+        let mut statements = Vec::new();
+        for (i, (table_name, cols)) in tables.into_iter().enumerate() {
+            let id_cols = if i == 0 {
+                vec![MongoConstants::ID]
+            } else {
+                vec![MongoConstants::ID, IDX_COL]
+            };
+            // tables without sampled rows still have key columns
+            let mut all_cols: ColTypes = id_cols
+                .iter()
+                .filter(|col| !cols.iter().any(|(c, _)| c == *col))
+                .map(|col| (col.to_string(), None))
+                .collect();
+            all_cols.extend(cols);
+            all_cols.sort_by_key(|(col, _)| !id_cols.contains(&col.as_str()));
+
+            let statement =
+                Self::build_create_table_statement(db, &table_name, &all_cols, &id_cols, db_type)?;
+            statements.push(statement);
+        }
+        Ok(statements)
+    }
+
+    fn convert_doc(
+        &self,
+        schema: &str,
+        tb: &str,
+        rule: &CollectionRule,
+        doc: &Document,
+    ) -> Vec<RowData> {
+        let mut after = HashMap::new();
+        self.flatten(rule, doc, "", "", 0, &mut after);
+        for (path, col) in rule.col_map.iter() {
+            if let Some(value) = Self::get_by_path(doc, path) {
+                after.insert(col.clone(), Self::to_col_value(value));
+            }
+        }
+
+        let mut rows = vec![RowData::new(
+            schema.into(),
+            tb.into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        )];
+
+        if let Some(id) = doc.get(MongoConstants::ID) {
+            for (path, child_tb) in rule.child_tbs.iter() {
+                if let Some(Bson::Array(elements)) = Self::get_by_path(doc, path) {
+                    self.push_child_rows(&mut rows, schema, child_tb, id, elements);
+                }
+            }
+        }
+        rows
+    }
+
+    fn convert_diff(
+        &self,
+        schema: &str,
+        tb: &str,
+        rule: &CollectionRule,
+        id: &Bson,
+        diff_doc: &Document,
+    ) -> anyhow::Result<Vec<RowData>> {
+        let mut after = HashMap::new();
+        for (op, fields) in diff_doc.iter() {
+            let fields = match fields.as_document() {
+                Some(fields) => fields,
+                None => continue,
+            };
+
+            for (path, value) in fields.iter() {
+                let mapped = match op.as_str() {
+                    MongoConstants::SET => self.set_cols(rule, path, Some(value), &mut after),
+                    MongoConstants::UNSET => self.set_cols(rule, path, None, &mut after),
+                    // truncated arrays
+                    _ => false,
+                };
+
+                // the target row would drift from the source if the update is skipped
+                if !mapped {
+                    bail! {Error::Unexpected(format!(
+                        "schema: {}, tb: {}, _id: {}, update of path can not be mapped to columns and the full document is unknown, {}: {{ {}: {} }}",
+                        schema,
+                        tb,
+                        id,
+                        op,
+                        path,
+                        value
+                    ))}
+                }
+            }
+        }
+
+        if after.is_empty() {
+            return Ok(Vec::new());
+        }
+        let before = Self::build_id_cols(id, None);
+        Ok(vec![RowData::new(
+            schema.into(),
+            tb.into(),
+            RowType::Update,
+            Some(before),
+            Some(after),
+        )])
+    }
+
+    fn set_cols(
+        &self,
+        rule: &CollectionRule,
+        path: &str,
+        value: Option<&Bson>,
+        cols: &mut HashMap<String, ColValue>,
+    ) -> bool {
+        let to_col_value = |value: Option<&Bson>| value.map_or(ColValue::None, Self::to_col_value);
+
+        if let Some(col) = rule.col_map.get(path) {
+            cols.insert(col.clone(), to_col_value(value));
+            return true;
+        }
+
+        // a sub field of a mapped path is stored in a json column, which can not be partially updated
+        if rule
+            .col_map
+            .keys()
+            .any(|mapped_path| path.starts_with(&format!("{}.", mapped_path)))
+        {
+            return false;
+        }
+
+        // rows of child tables can only be rebuilt from the full array
+        if rule.child_tbs.iter().any(|(array_path, _)| {
+            array_path == path
+                || array_path.starts_with(&format!("{}.", path))
+                || path.starts_with(&format!("{}.", array_path))
+        }) {
+            return false;
+        }
+
+        // numeric fields are treated as array indexes, arrays are stored in json columns
+        let fields: Vec<&str> = path.split('.').collect();
+        let depth = fields.len() - 1;
+        if depth > self.flatten_depth || fields.iter().any(|i| i.parse::<usize>().is_ok()) {
+            return false;
+        }
+
+        let col = fields.join(&self.separator);
+        match value {
+            Some(Bson::Document(sub_doc)) if depth < self.flatten_depth => {
+                self.flatten(rule, sub_doc, path, &col, depth + 1, cols);
+            }
+            _ => {
+                cols.insert(col, to_col_value(value));
+            }
+        }
+
+        // mapped paths under the set document
+        for (mapped_path, mapped_col) in rule.col_map.iter() {
+            if let Some(sub_path) = mapped_path.strip_prefix(&format!("{}.", path)) {
+                let sub_value = match value {
+                    Some(Bson::Document(sub_doc)) => Self::get_by_path(sub_doc, sub_path),
+                    _ => None,
+                };
+                cols.insert(mapped_col.clone(), to_col_value(sub_value));
+            }
+        }
+        true
+    }
+
+    fn flatten(
+        &self,
+        rule: &CollectionRule,
+        doc: &Document,
+        path_prefix: &str,
+        col_prefix: &str,
+        depth: usize,
+        cols: &mut HashMap<String, ColValue>,
+    ) {
+        for (field, value) in doc.iter() {
+            let path = Self::join(path_prefix, field, ".");
+            if rule.col_map.contains_key(&path) || rule.child_tbs.iter().any(|(i, _)| *i == path) {
+                continue;
+            }
+
+            let col = Self::join(col_prefix, field, &self.separator);
+            match value {
+                Bson::Document(sub_doc) if depth < self.flatten_depth => {
+                    self.flatten(rule, sub_doc, &path, &col, depth + 1, cols);
+                }
+                _ => {
+                    cols.insert(col, Self::to_col_value(value));
+                }
+            }
+        }
+    }
+
+    fn push_child_rows(
+        &self,
+        rows: &mut Vec<RowData>,
+        schema: &str,
+        child_tb: &str,
+        id: &Bson,
+        elements: &[Bson],
+    ) {
+        for (idx, element) in elements.iter().enumerate() {
+            rows.push(self.build_child_row(schema, child_tb, id, idx as i64, element));
+        }
+    }
+
+    fn build_child_row(
+        &self,
+        schema: &str,
+        child_tb: &str,
+        id: &Bson,
+        idx: i64,
+        element: &Bson,
+    ) -> RowData {
+        let mut after = Self::build_id_cols(id, Some(idx));
+        match element {
+            Bson::Document(doc) => self.flatten(&self.default_rule, doc, "", "", 0, &mut after),
+            _ => {
+                after.insert(VALUE_COL.into(), Self::to_col_value(element));
+            }
+        }
+        RowData::new(
+            schema.into(),
+            child_tb.into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        )
+    }
+
+    /// deletes by _id only, all rows of the document in child tables are removed
+    fn push_child_deletes(rows: &mut Vec<RowData>, schema: &str, rule: &CollectionRule, id: &Bson) {
+        for (_, child_tb) in rule.child_tbs.iter() {
+            let before = Self::build_id_cols(id, None);
+            let mut row_data = RowData::new(
+                schema.into(),
+                child_tb.into(),
+                RowType::Delete,
+                Some(before),
+                None,
+            );
+            row_data.partial_key = true;
+            rows.push(row_data);
+        }
+    }
+
+    fn build_id_cols(id: &Bson, idx: Option<i64>) -> HashMap<String, ColValue> {
+        let mut cols = HashMap::from([(MongoConstants::ID.to_string(), Self::to_col_value(id))]);
+        if let Some(idx) = idx {
+            cols.insert(IDX_COL.into(), ColValue::LongLong(idx));
+        }
+        cols
+    }
+
+    fn to_col_value(value: &Bson) -> ColValue {
+        match value {
+            Bson::Null | Bson::Undefined => ColValue::None,
+            Bson::Boolean(v) => ColValue::Bool(*v),
+            Bson::Int32(v) => ColValue::Long(*v),
+            Bson::Int64(v) => ColValue::LongLong(*v),
+            Bson::Double(v) => ColValue::Double(*v),
+            Bson::Decimal128(v) => ColValue::Decimal(v.to_string()),
+            Bson::String(v) | Bson::Symbol(v) | Bson::JavaScriptCode(v) => {
+                ColValue::String(v.clone())
+            }
+            Bson::ObjectId(v) => ColValue::String(v.to_hex()),
+            Bson::Binary(v) => ColValue::Blob(v.bytes.clone()),
+            Bson::DateTime(v) => {
+                match chrono::DateTime::from_timestamp_millis(v.timestamp_millis()) {
+                    Some(dt) => ColValue::DateTime(dt.format("%Y-%m-%d %H:%M:%S%.3f").to_string()),
+                    None => ColValue::LongLong(v.timestamp_millis()),
+                }
+            }
+            Bson::Document(_) | Bson::Array(_) => {
+                ColValue::Json2(value.clone().into_relaxed_extjson().to_string())
+            }
+            // timestamp, regex, min / max key...
+            _ => ColValue::String(value.clone().into_relaxed_extjson().to_string()),
+        }
+    }
+
+    fn to_sql_col_type(col_value: &ColValue) -> Option<SqlColType> {
+        match col_value {
+            ColValue::Bool(_) => Some(SqlColType::Bool),
+            ColValue::Long(_) => Some(SqlColType::Int),
+            ColValue::LongLong(_) => Some(SqlColType::BigInt),
+            ColValue::Double(_) => Some(SqlColType::Double),
+            ColValue::Decimal(_) => Some(SqlColType::Decimal),
+            ColValue::DateTime(_) => Some(SqlColType::DateTime),
+            ColValue::Blob(_) => Some(SqlColType::Binary),
+            ColValue::Json2(_) => Some(SqlColType::Json),
+            ColValue::None => None,
+            _ => Some(SqlColType::String),
+        }
+    }
+
+    fn merge_col_types(cols: &mut ColTypes, col_values: &HashMap<String, ColValue>) {
+        let mut new_cols: Vec<&String> = col_values.keys().collect();
+        new_cols.sort();
+        for col in new_cols {
+            let col_type = Self::to_sql_col_type(&col_values[col]);
+            match cols.iter_mut().find(|(c, _)| c == col) {
+                Some((_, existing)) => *existing = Self::merge_col_type(*existing, col_type),
+                None => cols.push((col.clone(), col_type)),
+            }
+        }
+    }
+
+    fn merge_col_type(a: Option<SqlColType>, b: Option<SqlColType>) -> Option<SqlColType> {
+        use SqlColType::*;
+        match (a, b) {
+            (None, t) | (t, None) => t,
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(Int), Some(BigInt)) | (Some(BigInt), Some(Int)) => Some(BigInt),
+            (Some(Int | BigInt | Double), Some(Int | BigInt | Double)) => Some(Double),
+            (Some(Int | BigInt | Decimal), Some(Int | BigInt | Decimal)) => Some(Decimal),
+            // mixed types, e.g. a field of both string and document values
+            _ => Some(String),
+        }
+    }
+
+    fn build_create_table_statement(
+        db: &str,
+        tb: &str,
+        cols: &ColTypes,
+        id_cols: &[&str],
+        db_type: &DbType,
+    ) -> anyhow::Result<StructStatement> {
+        let mut table = Table {
+            database_name: db.into(),
+            schema_name: db.into(),
+            table_name: tb.into(),
+            ..Default::default()
+        };
+
+        for (i, (col, col_type)) in cols.iter().enumerate() {
+            let is_id_col = id_cols.contains(&col.as_str());
+            // columns of null values only
+            let col_type = match col_type {
+                Some(col_type) => *col_type,
+                None if col == IDX_COL => SqlColType::BigInt,
+                None => SqlColType::String,
+            };
+            let column_type = match db_type {
+                DbType::Mysql => Self::get_mysql_col_type(col_type, is_id_col),
+                DbType::Pg => Self::get_pg_col_type(col_type),
+                DbType::ClickHouse => Self::get_clickhouse_col_type(col_type),
+                DbType::StarRocks | DbType::Doris => {
+                    Self::get_starrocks_col_type(col_type, is_id_col)
+                }
+                _ => bail! {Error::ConfigError(format!(
+                    "creating tables for mongo documents is not supported for {}",
+                    db_type
+                ))},
+            };
+
+            table.columns.push(Column {
+                column_name: col.clone(),
+                ordinal_position: i as u32 + 1,
+                is_nullable: !is_id_col,
+                column_type: column_type.into(),
+                column_key: if is_id_col {
+                    "PRI".into()
+                } else {
+                    String::new()
+                },
+                ..Default::default()
+            });
+        }
+
+        // tables of olap targets are created by their struct sinkers from the column types
+        let statement = if *db_type != DbType::Pg {
+            StructStatement::MysqlCreateTable(MysqlCreateTableStatement {
+                table,
+                constraints: vec![],
+                indexes: vec![],
+            })
+        } else {
+            let definition = format!(
+                "PRIMARY KEY ({})",
+                id_cols
+                    .iter()
+                    .map(|col| format!(r#""{}""#, col))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+            let constraint = Constraint {
+                database_name: String::new(),
+                schema_name: db.into(),
+                table_name: tb.into(),
+                constraint_name: format!("{}_pkey", tb),
+                constraint_type: ConstraintType::Primary,
+                definition,
+            };
+            StructStatement::PgCreateTable(PgCreateTableStatement {
+                table,
+                table_comments: vec![],
+                column_comments: vec![],
+                constraints: vec![constraint],
+                indexes: vec![],
+                sequences: vec![],
+                sequence_owners: vec![],
+            })
+        };
+        Ok(statement)
+    }
+
+    fn get_mysql_col_type(col_type: SqlColType, is_id_col: bool) -> &'static str {
+        match col_type {
+            SqlColType::Bool => "tinyint(1)",
+            SqlColType::Int => "int",
+            SqlColType::BigInt => "bigint",
+            SqlColType::Double => "double",
+            SqlColType::Decimal => "decimal(65,30)",
+            SqlColType::DateTime => "datetime(3)",
+            // key columns can not be text
+            SqlColType::String if is_id_col => "varchar(255)",
+            SqlColType::String => "longtext",
+            SqlColType::Binary if is_id_col => "varbinary(255)",
+            SqlColType::Binary => "longblob",
+            SqlColType::Json if is_id_col => "varchar(255)",
+            SqlColType::Json => "json",
+        }
+    }
+
+    fn get_pg_col_type(col_type: SqlColType) -> &'static str {
+        match col_type {
+            SqlColType::Bool => "boolean",
+            SqlColType::Int => "integer",
+            SqlColType::BigInt => "bigint",
+            SqlColType::Double => "double precision",
+            SqlColType::Decimal => "numeric",
+            SqlColType::DateTime => "timestamp(3)",
+            SqlColType::String => "text",
+            SqlColType::Binary => "bytea",
+            SqlColType::Json => "jsonb",
+        }
+    }
+
+    fn get_clickhouse_col_type(col_type: SqlColType) -> &'static str {
+        match col_type {
+            SqlColType::Bool => "Bool",
+            SqlColType::Int => "Int32",
+            SqlColType::BigInt => "Int64",
+            SqlColType::Double => "Float64",
+            SqlColType::Decimal => "Decimal128(30)",
+            SqlColType::DateTime => "DateTime64(3)",
+            SqlColType::String | SqlColType::Binary | SqlColType::Json => "String",
+        }
+    }
+
+    fn get_starrocks_col_type(col_type: SqlColType, is_id_col: bool) -> &'static str {
+        match col_type {
+            SqlColType::Bool => "BOOLEAN",
+            SqlColType::Int => "INT",
+            SqlColType::BigInt => "BIGINT",
+            SqlColType::Double => "DOUBLE",
+            SqlColType::Decimal => "DECIMAL(38,10)",
+            SqlColType::DateTime => "DATETIME",
+            // key columns can not be string / json
+            SqlColType::String | SqlColType::Binary | SqlColType::Json if is_id_col => {
+                "VARCHAR(255)"
+            }
+            SqlColType::String | SqlColType::Binary => "STRING",
+            SqlColType::Json => "JSON",
+        }
+    }
+
+    fn get_rule(&self, db: &str, tb: &str) -> &CollectionRule {
+        self.rules
+            .get(&(db.into(), tb.into()))
+            .unwrap_or(&self.default_rule)
+    }
+
+    fn get_doc<'a>(
+        col_values: &'a Option<HashMap<String, ColValue>>,
+        col: &str,
+    ) -> Option<&'a Document> {
+        match col_values.as_ref().and_then(|i| i.get(col)) {
+            Some(ColValue::MongoDoc(doc)) => Some(doc),
+            _ => None,
+        }
+    }
+
+    fn get_by_path<'a>(doc: &'a Document, path: &str) -> Option<&'a Bson> {
+        let (field, sub_path) = path.split_once('.').unwrap_or((path, ""));
+        let value = doc.get(field)?;
+        if sub_path.is_empty() {
+            return Some(value);
+        }
+        match value {
+            Bson::Document(sub_doc) => Self::get_by_path(sub_doc, sub_path),
+            _ => None,
+        }
+    }
+
+    fn join(prefix: &str, field: &str, separator: &str) -> String {
+        if prefix.is_empty() {
+            field.into()
+        } else {
+            format!("{}{}{}", prefix, separator, field)
+        }
+    }
+
+    fn parse_rules(config_str: &str) -> anyhow::Result<HashMap<(String, String), CollectionRule>> {
+        let mut rules = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(rules);
+        }
+
+        let config: Vec<CollectionMapType> =
+            match serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX)) {
+                Ok(config) => config,
+                Err(err) => bail! {Error::ConfigError(format!(
+                    "invalid mongo mapping config: [{}], error: {}",
+                    config_str, err
+                ))},
+            };
+
+        for i in config {
+            let mut child_tbs: Vec<(String, String)> = i.child_tb_map.into_iter().collect();
+            child_tbs.sort();
+            rules.insert(
+                (i.db, i.tb),
+                CollectionRule {
+                    col_map: i.col_map,
+                    child_tbs,
+                },
+            );
+        }
+        Ok(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, oid::ObjectId, DateTime};
+
+    use super::*;
+
+    fn new_converter() -> MongoRowConverter {
+        let config = MongoMappingConfig {
+            flatten_depth: 1,
+            separator: "_".into(),
+            sample_size: 100,
+            collection_map: r#"json:[{"db":"app","tb":"users","col_map":{"profile.address.city":"city"},"child_tb_map":{"orders":"user_orders","tags":"user_tags"}}]"#.into(),
+        };
+        MongoRowConverter::new(&config).unwrap()
+    }
+
+    fn new_converter_without_child_tbs() -> MongoRowConverter {
+        let config = MongoMappingConfig {
+            flatten_depth: 1,
+            separator: "_".into(),
+            sample_size: 100,
+            collection_map:
+                r#"json:[{"db":"app","tb":"users","col_map":{"profile.address.city":"city"}}]"#
+                    .into(),
+        };
+        MongoRowConverter::new(&config).unwrap()
+    }
+
+    fn new_row(row_type: RowType, before: Option<Document>, after: Option<Document>) -> RowData {
+        let to_cols = |doc: Option<Document>, col: &str| {
+            doc.map(|doc| HashMap::from([(col.to_string(), ColValue::MongoDoc(doc))]))
+        };
+        let after_col = if row_type == RowType::Update
+            && after
+                .as_ref()
+                .is_some_and(|doc| doc.keys().any(|key| key.starts_with('$')))
+        {
+            MongoConstants::DIFF_DOC
+        } else {
+            MongoConstants::DOC
+        };
+        RowData::new(
+            "app".into(),
+            "users".into(),
+            row_type,
+            to_cols(before, MongoConstants::DOC),
+            to_cols(after, after_col),
+        )
+    }
+
+    fn get_col(row_data: &RowData, col: &str) -> ColValue {
+        let col_values = if row_data.row_type == RowType::Delete {
+            &row_data.before
+        } else {
+            &row_data.after
+        };
+        col_values.as_ref().unwrap().get(col).unwrap().clone()
+    }
+
+    #[test]
+    fn test_convert_insert() {
+        let converter = new_converter();
+        assert!(converter.has_child_tbs());
+        assert!(!new_converter_without_child_tbs().has_child_tbs());
+        let oid = ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap();
+        let doc = doc! {
+            "_id": oid,
+            "name": "a",
+            "age": 10,
+            "score": 1.5_f64,
+            "created": DateTime::from_millis(1704067200123),
+            "profile": { "nick": "b", "address": { "city": "c", "zip": "d" } },
+            "scores": [1, 2],
+            "orders": [{ "no": 1_i64, "item": { "sku": "x" } }, { "no": 2_i64 }],
+            "tags": ["t1", "t2"],
+        };
+        let rows = converter
+            .convert(new_row(RowType::Insert, None, Some(doc)))
+            .unwrap();
+        assert_eq!(rows.len(), 5);
+
+        let parent = &rows[0];
+        assert_eq!(parent.tb, "users");
+        assert_eq!(parent.row_type, RowType::Insert);
+        assert_eq!(
+            get_col(parent, "_id"),
+            ColValue::String("65a1b2c3d4e5f60718293a4b".into())
+        );
+        assert_eq!(get_col(parent, "age"), ColValue::Long(10));
+        assert_eq!(get_col(parent, "score"), ColValue::Double(1.5));
+        assert_eq!(
+            get_col(parent, "created"),
+            ColValue::DateTime("2024-01-01 00:00:00.123".into())
+        );
+        assert_eq!(
+            get_col(parent, "profile_nick"),
+            ColValue::String("b".into())
+        );
+        // deeper than flatten_depth
+        assert_eq!(
+            get_col(parent, "profile_address"),
+            ColValue::Json2(r#"{"city":"c","zip":"d"}"#.into())
+        );
+        assert_eq!(get_col(parent, "city"), ColValue::String("c".into()));
+        assert_eq!(get_col(parent, "scores"), ColValue::Json2("[1,2]".into()));
+        assert!(!parent.after.as_ref().unwrap().contains_key("orders"));
+        assert!(!parent.after.as_ref().unwrap().contains_key("tags"));
+
+        let order = &rows[1];
+        assert_eq!(order.tb, "user_orders");
+        assert_eq!(get_col(order, "_id"), get_col(parent, "_id"));
+        assert_eq!(get_col(order, "_idx"), ColValue::LongLong(0));
+        assert_eq!(get_col(order, "no"), ColValue::LongLong(1));
+        assert_eq!(get_col(order, "item_sku"), ColValue::String("x".into()));
+        assert_eq!(get_col(&rows[2], "_idx"), ColValue::LongLong(1));
+
+        let tag = &rows[4];
+        assert_eq!(tag.tb, "user_tags");
+        assert_eq!(get_col(tag, "value"), ColValue::String("t2".into()));
+
+        // not a mongo row
+        let row_data = RowData::new("a".into(), "b".into(), RowType::Insert, None, None);
+        assert_eq!(converter.convert(row_data).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_convert_update_and_delete() {
+        let converter = new_converter();
+        let key = doc! { "_id": 1 };
+
+        // full document, rows of child tables are deleted by _id and inserted again
+        let doc = doc! { "_id": 1, "name": "a", "tags": ["t1"] };
+        let rows = converter
+            .convert(new_row(RowType::Update, Some(key.clone()), Some(doc)))
+            .unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].tb, "user_orders");
+        assert_eq!(rows[1].tb, "user_tags");
+        assert_eq!(rows[1].row_type, RowType::Delete);
+        assert_eq!(rows[1].before.as_ref().unwrap().len(), 1);
+        assert!(rows[1].partial_key);
+        assert_eq!(rows[2].row_type, RowType::Insert);
+        assert_eq!(get_col(&rows[2], "_id"), ColValue::Long(1));
+        assert_eq!(rows[3].tb, "user_tags");
+        assert_eq!(rows[3].row_type, RowType::Insert);
+
+        // partial update
+        let diff_doc = doc! {
+            "$set": {
+                "name": "b",
+                "profile": { "nick": "n", "address": { "city": "c" } },
+            },
+            "$unset": { "age": "" },
+        };
+        let rows = converter
+            .convert(new_row(RowType::Update, Some(key.clone()), Some(diff_doc)))
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let parent = &rows[0];
+        assert_eq!(parent.row_type, RowType::Update);
+        assert_eq!(parent.before.as_ref().unwrap().len(), 1);
+        assert_eq!(get_col(parent, "name"), ColValue::String("b".into()));
+        assert_eq!(
+            get_col(parent, "profile_nick"),
+            ColValue::String("n".into())
+        );
+        assert_eq!(get_col(parent, "city"), ColValue::String("c".into()));
+        assert_eq!(get_col(parent, "age"), ColValue::None);
+        assert_eq!(parent.after.as_ref().unwrap().len(), 5);
+
+        // paths which can not be mapped to columns
+        for diff_doc in [
+            doc! { "$set": { "profile.address.zip": "z" } },
+            doc! { "$set": { "scores.1": 3 } },
+            doc! { "$set": { "tags": ["t0"] } },
+            doc! { "$push": { "orders": { "$each": [], "$slice": 1 } } },
+        ] {
+            assert!(converter
+                .convert(new_row(RowType::Update, Some(key.clone()), Some(diff_doc)))
+                .is_err());
+        }
+
+        let rows = converter
+            .convert(new_row(RowType::Delete, Some(key), None))
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(get_col(&rows[0], "_id"), ColValue::Long(1));
+        assert_eq!(rows[2].tb, "user_tags");
+        assert_eq!(get_col(&rows[2], "_id"), ColValue::Long(1));
+        assert!(!rows[2].before.as_ref().unwrap().contains_key("_idx"));
+    }
+
+    #[test]
+    fn test_build_create_table_statements() {
+        let converter = new_converter();
+        let docs = vec![
+            doc! { "_id": "a", "n": 1, "v": 1, "x": null, "tags": ["t"] },
+            doc! { "_id": "b", "n": 2_i64, "v": "s", "m": { "k": true } },
+        ];
+        let statements = converter
+            .build_create_table_statements("app", "users", &docs, &DbType::Mysql)
+            .unwrap();
+        assert_eq!(statements.len(), 3);
+
+        let cols = |statement: &StructStatement| match statement {
+            StructStatement::MysqlCreateTable(s) => s
+                .table
+                .columns
+                .iter()
+                .map(|c| {
+                    (
+                        c.column_name.clone(),
+                        c.column_type.clone(),
+                        c.column_key.clone(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            StructStatement::PgCreateTable(s) => s
+                .table
+                .columns
+                .iter()
+                .map(|c| {
+                    (
+                        c.column_name.clone(),
+                        c.column_type.clone(),
+                        c.column_key.clone(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        let to_tuples = |cols: &[(&str, &str, &str)]| {
+            cols.iter()
+                .map(|(a, b, c)| (a.to_string(), b.to_string(), c.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            cols(&statements[0]),
+            to_tuples(&[
+                ("_id", "varchar(255)", "PRI"),
+                ("n", "bigint", ""),
+                ("v", "longtext", ""),
+                ("x", "longtext", ""),
+                ("m_k", "tinyint(1)", ""),
+            ])
+        );
+        // no sampled rows
+        assert_eq!(
+            cols(&statements[1]),
+            to_tuples(&[("_id", "varchar(255)", "PRI"), ("_idx", "bigint", "PRI")])
+        );
+        assert_eq!(
+            cols(&statements[2]),
+            to_tuples(&[
+                ("_id", "varchar(255)", "PRI"),
+                ("_idx", "bigint", "PRI"),
+                ("value", "longtext", ""),
+            ])
+        );
+
+        let statements = converter
+            .build_create_table_statements("app", "users", &docs, &DbType::Pg)
+            .unwrap();
+        match &statements[2] {
+            StructStatement::PgCreateTable(s) => {
+                assert_eq!(s.table.columns[1].column_type, "bigint");
+                assert_eq!(
+                    s.constraints[0].definition,
+                    r#"PRIMARY KEY ("_id", "_idx")"#
+                );
+            }
+            _ => panic!(),
+        }
+
+        let statements = converter
+            .build_create_table_statements("app", "users", &docs, &DbType::ClickHouse)
+            .unwrap();
+        assert_eq!(
+            cols(&statements[0]),
+            to_tuples(&[
+                ("_id", "String", "PRI"),
+                ("n", "Int64", ""),
+                ("v", "String", ""),
+                ("x", "String", ""),
+                ("m_k", "Bool", ""),
+            ])
+        );
+
+        let statements = converter
+            .build_create_table_statements("app", "users", &docs, &DbType::StarRocks)
+            .unwrap();
+        assert_eq!(
+            cols(&statements[2]),
+            to_tuples(&[
+                ("_id", "VARCHAR(255)", "PRI"),
+                ("_idx", "BIGINT", "PRI"),
+                ("value", "STRING", ""),
+            ])
+        );
+
+        assert!(converter
+            .build_create_table_statements("app", "users", &docs, &DbType::Redis)
+            .is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use mongodb::{
+    bson::{doc, Bson, Document},
+    change_stream::event::UpdateDescription,
+};
+
+use super::mongo_constant::MongoConstants;
+
+#[derive(Default)]
+struct UpdateOps {
+    set: Document,
+    unset: Document,
+    // arrays truncated by { "$push": { <path>: { "$each": [], "$slice": <new_size> } } }
+    truncate: Document,
+}
+
+impl UpdateOps {
+    fn into_update_doc(self) -> Option<Document> {
+        // $push conflicts with any other operator on the same path or its parent / sub paths,
+        // in which case the update can not be applied in a single partial update
+        for path in self.truncate.keys() {
+            let conflict = self
+                .set
+                .keys()
+                .chain(self.unset.keys())
+                .chain(self.truncate.keys().filter(|other| *other != path))
+                .any(|other| Self::is_conflict(path, other));
+            if conflict {
+                return None;
+            }
+        }
+
+        let mut update_doc = Document::new();
+        if !self.set.is_empty() {
+            update_doc.insert(MongoConstants::SET, self.set);
+        }
+        if !self.unset.is_empty() {
+            update_doc.insert(MongoConstants::UNSET, self.unset);
+        }
+        if !self.truncate.is_empty() {
+            update_doc.insert(MongoConstants::PUSH, self.truncate);
+        }
+        Some(update_doc)
+    }
+
+    fn is_conflict(path: &str, other: &str) -> bool {
+        path == other
+            || other.starts_with(&format!("{}.", path))
+            || path.starts_with(&format!("{}.", other))
+    }
+
+    fn truncate_doc(new_size: Bson) -> Document {
+        doc! { "$each": [], "$slice": new_size }
+    }
+}
+
+pub struct MongoUpdateParser {}
+
+impl MongoUpdateParser {
+    /// parse the "o" field of an oplog "u" entry into an update document with $set / $unset / $push,
+    /// None if the entry is a replacement or can not be applied as a partial update
+    pub fn parse_oplog_update(o: &Document) -> Option<Document> {
+        if Self::is_v2_diff(o) {
+            // 5.0+: { "$v": 2, "diff": { "u": { "a": 1 }, "sb": { "i": { "c": 2 } } } }
+            let mut ops = UpdateOps::default();
+            Self::parse_object_diff(o.get_document("diff").ok()?, "", &mut ops)?;
+            return ops.into_update_doc();
+        }
+
+        // 4.4 and earlier: { "$v": 1, "$set": { "a": 1, "b.c": 2 }, "$unset": { "d": true } }
+        let mut update_doc = Document::new();
+        for (key, value) in o {
+            match key.as_str() {
+                "$v" => {}
+                MongoConstants::SET | MongoConstants::UNSET => {
+                    update_doc.insert(key, value.clone());
+                }
+                _ => return None,
+            }
+        }
+
+        if update_doc.is_empty() {
+            None
+        } else {
+            Some(update_doc)
+        }
+    }
+
+    /// the "o" field of an oplog "u" entry generated by replaceOne is the full new document
+    pub fn is_oplog_replacement(o: &Document) -> bool {
+        !o.keys().any(|key| key.starts_with('$'))
+    }
+
+    /// parse the updateDescription of a change stream update event,
+    /// None if it can not be applied as a partial update
+    pub fn parse_update_description(description: &UpdateDescription) -> Option<Document> {
+        let mut ops = UpdateOps::default();
+        for (path, value) in description.updated_fields.iter() {
+            ops.set.insert(path, value.clone());
+        }
+        for path in description.removed_fields.iter() {
+            ops.unset.insert(path, "");
+        }
+        if let Some(truncated_arrays) = &description.truncated_arrays {
+            for array in truncated_arrays {
+                ops.truncate.insert(
+                    &array.field,
+                    UpdateOps::truncate_doc(Bson::Int32(array.new_size)),
+                );
+            }
+        }
+        ops.into_update_doc()
+    }
+
+    fn is_v2_diff(o: &Document) -> bool {
+        matches!(o.get("$v"), Some(Bson::Int32(2)) | Some(Bson::Int64(2))) && o.contains_key("diff")
+    }
+
+    // refer: https://github.com/mongodb/mongo/blob/master/src/mongo/db/update/document_diff_serialization.h
+    // object diff: { "d": { <field>: false }, "u": { <field>: <value> }, "i": { <field>: <value> }, "s<field>": <sub diff> }
+    fn parse_object_diff(diff: &Document, prefix: &str, ops: &mut UpdateOps) -> Option<()> {
+        for (key, value) in diff {
+            match key.as_str() {
+                "i" | "u" => {
+                    for (field, field_value) in value.as_document()? {
+                        ops.set
+                            .insert(Self::join_path(prefix, field)?, field_value.clone());
+                    }
+                }
+
+                "d" => {
+                    for field in value.as_document()?.keys() {
+                        ops.unset.insert(Self::join_path(prefix, field)?, "");
+                    }
+                }
+
+                _ if key.starts_with('s') => {
+                    let path = Self::join_path(prefix, &key[1..])?;
+                    Self::parse_sub_diff(value.as_document()?, &path, ops)?;
+                }
+
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    // array diff: { "a": true, "l": <new_size>, "u<index>": <value>, "s<index>": <sub diff> }
+    fn parse_array_diff(diff: &Document, path: &str, ops: &mut UpdateOps) -> Option<()> {
+        for (key, value) in diff {
+            match key.as_str() {
+                "a" => {}
+
+                "l" => {
+                    ops.truncate
+                        .insert(path, UpdateOps::truncate_doc(value.clone()));
+                }
+
+                _ if key.starts_with('u') => {
+                    let index: usize = key[1..].parse().ok()?;
+                    ops.set.insert(format!("{}.{}", path, index), value.clone());
+                }
+
+                _ if key.starts_with('s') => {
+                    let index: usize = key[1..].parse().ok()?;
+                    let sub_path = format!("{}.{}", path, index);
+                    Self::parse_sub_diff(value.as_document()?, &sub_path, ops)?;
+                }
+
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    fn parse_sub_diff(diff: &Document, path: &str, ops: &mut UpdateOps) -> Option<()> {
+        if let Some(Bson::Boolean(true)) = diff.get("a") {
+            Self::parse_array_diff(diff, path, ops)
+        } else {
+            Self::parse_object_diff(diff, path, ops)
+        }
+    }
+
+    fn join_path(prefix: &str, field: &str) -> Option<String> {
+        // field names with "." or "$" can not be expressed by update operators
+        if field.is_empty() || field.contains('.') || field.starts_with('$') {
+            return None;
+        }
+
+        if prefix.is_empty() {
+            Some(field.into())
+        } else {
+            Some(format!("{}.{}", prefix, field))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::{
+        bson::{doc, from_document},
+        change_stream::event::UpdateDescription,
+    };
+
+    use super::MongoUpdateParser;
+
+    #[test]
+    fn test_parse_oplog_update_v1() {
+        let o = doc! { "$v": 1, "$set": { "a": 1, "b.c": "x" }, "$unset": { "d": true } };
+        assert_eq!(
+            MongoUpdateParser::parse_oplog_update(&o).unwrap(),
+            doc! { "$set": { "a": 1, "b.c": "x" }, "$unset": { "d": true } }
+        );
+
+        // replacement
+        let o = doc! { "_id": 1, "a": 1 };
+        assert!(MongoUpdateParser::parse_oplog_update(&o).is_none());
+        assert!(MongoUpdateParser::is_oplog_replacement(&o));
+    }
+
+    #[test]
+    fn test_parse_oplog_update_v2() {
+        let o = doc! {
+            "$v": 2,
+            "diff": {
+                "d": { "x": false },
+                "u": { "a": 2 },
+                "i": { "b": "new" },
+                "sc": { "u": { "d": 3 }, "se": { "d": { "f": false } } },
+            }
+        };
+        assert!(!MongoUpdateParser::is_oplog_replacement(&o));
+        assert_eq!(
+            MongoUpdateParser::parse_oplog_update(&o).unwrap(),
+            doc! {
+                "$set": { "a": 2, "b": "new", "c.d": 3 },
+                "$unset": { "x": "", "c.e.f": "" },
+            }
+        );
+
+        // array diff
+        let o = doc! {
+            "$v": 2,
+            "diff": { "sarr": { "a": true, "u1": "x", "s2": { "u": { "k": 1 } } } }
+        };
+        assert_eq!(
+            MongoUpdateParser::parse_oplog_update(&o).unwrap(),
+            doc! { "$set": { "arr.1": "x", "arr.2.k": 1 } }
+        );
+
+        // array truncated
+        let o = doc! { "$v": 2, "diff": { "sarr": { "a": true, "l": 2 }, "u": { "b": 1 } } };
+        assert_eq!(
+            MongoUpdateParser::parse_oplog_update(&o).unwrap(),
+            doc! {
+                "$set": { "b": 1 },
+                "$push": { "arr": { "$each": [], "$slice": 2 } },
+            }
+        );
+
+        // array truncated and updated, can not be applied partially
+        let o = doc! { "$v": 2, "diff": { "sarr": { "a": true, "l": 2, "u1": "x" } } };
+        assert!(MongoUpdateParser::parse_oplog_update(&o).is_none());
+
+        // field name with "."
+        let o = doc! { "$v": 2, "diff": { "u": { "a.b": 1 } } };
+        assert!(MongoUpdateParser::parse_oplog_update(&o).is_none());
+    }
+
+    #[test]
+    fn test_parse_update_description() {
+        let description: UpdateDescription = from_document(doc! {
+            "updatedFields": { "a": 1, "b.c": "x" },
+            "removedFields": ["d"],
+            "truncatedArrays": [{ "field": "arr", "newSize": 3 }],
+        })
+        .unwrap();
+        assert_eq!(
+            MongoUpdateParser::parse_update_description(&description).unwrap(),
+            doc! {
+                "$set": { "a": 1, "b.c": "x" },
+                "$unset": { "d": "" },
+                "$push": { "arr": { "$each": [], "$slice": 3 } },
+            }
+        );
+
+        let description: UpdateDescription = from_document(doc! {
+            "updatedFields": { "arr.1": 1 },
+            "removedFields": [],
+            "truncatedArrays": [{ "field": "arr", "newSize": 3 }],
+        })
+        .unwrap();
+        assert!(MongoUpdateParser::parse_update_description(&description).is_none());
+    }
+}
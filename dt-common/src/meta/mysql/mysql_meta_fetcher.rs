@@ -29,6 +29,7 @@ const CHARACTER_SET_NAME: &str = "CHARACTER_SET_NAME";
 const NUMERIC_PRECISION: &str = "NUMERIC_PRECISION";
 const NUMERIC_SCALE: &str = "NUMERIC_SCALE";
 const IS_NULLABLE: &str = "IS_NULLABLE";
+const EXTRA: &str = "EXTRA";
 
 impl MysqlMetaFetcher {
     pub async fn new(conn_pool: Pool<MySql>) -> anyhow::Result<Self> {
@@ -83,7 +84,7 @@ impl MysqlMetaFetcher {
     ) -> anyhow::Result<&'a MysqlTbMeta> {
         let full_name = format!("{}.{}", schema, tb);
         if !self.cache.contains_key(&full_name) {
-            let (cols, col_origin_type_map, col_type_map) =
+            let (cols, col_origin_type_map, col_type_map, generated_cols) =
                 Self::parse_cols(&self.conn_pool, &self.db_type, schema, tb).await?;
             let key_map = Self::parse_keys(&self.conn_pool, schema, tb).await?;
             let (order_col, partition_col, id_cols) =
@@ -103,6 +104,7 @@ impl MysqlMetaFetcher {
                 order_col,
                 partition_col,
                 id_cols,
+                generated_cols,
                 foreign_keys,
                 ref_by_foreign_keys,
             };
@@ -124,10 +126,12 @@ impl MysqlMetaFetcher {
         Vec<String>,
         HashMap<String, String>,
         HashMap<String, MysqlColType>,
+        Vec<String>,
     )> {
         let mut cols = Vec::new();
         let mut col_origin_type_map = HashMap::new();
         let mut col_type_map = HashMap::new();
+        let mut generated_cols = Vec::new();
 
         let sql = if matches!(db_type, DbType::Mysql) {
             "SELECT * FROM information_schema.columns
@@ -155,7 +159,13 @@ impl MysqlMetaFetcher {
             cols.push(col.clone());
             let (origin_type, col_type) = Self::get_col_type(&row).await?;
             col_origin_type_map.insert(col.clone(), origin_type);
-            col_type_map.insert(col, col_type);
+            col_type_map.insert(col.clone(), col_type);
+
+            // EXTRA: VIRTUAL GENERATED / STORED GENERATED, may not exist in starrocks / doris
+            let extra = row.try_get::<String, _>(EXTRA).unwrap_or_default();
+            if extra.to_uppercase().contains(" GENERATED") {
+                generated_cols.push(col);
+            }
         }
 
         if cols.is_empty() {
@@ -164,7 +174,7 @@ impl MysqlMetaFetcher {
                 schema, tb
             )) }
         }
-        Ok((cols, col_origin_type_map, col_type_map))
+        Ok((cols, col_origin_type_map, col_type_map, generated_cols))
     }
 
     async fn get_col_type(row: &MySqlRow) -> anyhow::Result<(String, MysqlColType)> {
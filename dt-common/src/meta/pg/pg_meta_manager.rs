@@ -77,7 +77,7 @@ impl PgMetaManager {
         let full_name = format!(r#""{}"."{}""#, schema, tb);
         if !self.name_to_tb_meta.contains_key(&full_name) {
             let oid = Self::get_oid(&self.conn_pool, schema, tb).await?;
-            let (cols, col_origin_type_map, col_type_map) =
+            let (cols, col_origin_type_map, col_type_map, generated_cols) =
                 Self::parse_cols(&self.conn_pool, &mut self.type_registry, schema, tb).await?;
             let key_map = Self::parse_keys(&self.conn_pool, schema, tb).await?;
             let (order_col, partition_col, id_cols) =
@@ -96,6 +96,7 @@ impl PgMetaManager {
                 order_col,
                 partition_col,
                 id_cols,
+                generated_cols,
                 foreign_keys,
                 ref_by_foreign_keys,
             };
@@ -134,14 +135,16 @@ impl PgMetaManager {
         Vec<String>,
         HashMap<String, String>,
         HashMap<String, PgColType>,
+        Vec<String>,
     )> {
         let mut cols = Vec::new();
         let mut col_origin_type_map = HashMap::new();
         let mut col_type_map = HashMap::new();
+        let mut generated_cols = Vec::new();
 
         // get cols of the table
         let sql = format!(
-            "SELECT column_name FROM information_schema.columns 
+            "SELECT column_name, is_generated FROM information_schema.columns 
             WHERE table_schema='{}' AND table_name = '{}' 
             ORDER BY ordinal_position;",
             schema, tb
@@ -149,6 +152,11 @@ impl PgMetaManager {
         let mut rows = sqlx::query(&sql).fetch(conn_pool);
         while let Some(row) = rows.try_next().await? {
             let col: String = row.try_get("column_name")?;
+            // is_generated: ALWAYS for GENERATED ALWAYS AS (expr) STORED, otherwise NEVER
+            let is_generated: Option<String> = row.try_get("is_generated")?;
+            if is_generated.is_some_and(|v| v.eq_ignore_ascii_case("ALWAYS")) {
+                generated_cols.push(col.clone());
+            }
             cols.push(col);
         }
 
@@ -179,7 +187,7 @@ impl PgMetaManager {
             col_type_map.insert(col, col_type);
         }
 
-        Ok((cols, col_origin_type_map, col_type_map))
+        Ok((cols, col_origin_type_map, col_type_map, generated_cols))
     }
 
     async fn parse_keys(
@@ -45,6 +45,9 @@ pub enum Position {
         operation_time: u32,
         timestamp: String,
     },
+    MongoShardedCdc {
+        shards: Vec<MongoShardPosition>,
+    },
     Redis {
         repl_id: String,
         repl_port: u64,
@@ -52,6 +55,9 @@ pub enum Position {
         now_db_id: i64,
         timestamp: String,
     },
+    RedisCluster {
+        shards: Vec<RedisShardPosition>,
+    },
     FoxlakeS3 {
         schema: String,
         tb: String,
@@ -59,6 +65,26 @@ pub enum Position {
     },
 }
 
+/// replication position of a single master in a redis cluster source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct RedisShardPosition {
+    pub node_id: String,
+    pub address: String,
+    pub repl_id: String,
+    pub repl_offset: u64,
+    pub now_db_id: i64,
+    pub timestamp: String,
+}
+
+/// oplog position of a single shard in a sharded mongo source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct MongoShardPosition {
+    pub shard: String,
+    pub operation_time: u32,
+    pub increment: u32,
+    pub timestamp: String,
+}
+
 impl Position {
     pub fn format_timestamp_millis(millis: i64) -> String {
         if let Some(naive_datetime) = DateTime::from_timestamp_millis(millis) {
@@ -128,6 +154,8 @@ mod test {
         let strs = [
             r#"{"type":"None"}"#,
             r#"{"type":"RdbSnapshot","db_type":"mysql","schema":"test_db_1","tb":"numeric_table","order_col":"f_0","value":"127"}"#,
+            r#"{"type":"MongoShardedCdc","shards":[{"shard":"shard01","operation_time":1728551829,"increment":3,"timestamp":"2024-10-10 09:17:09.000"},{"shard":"shard02","operation_time":1728551830,"increment":1,"timestamp":"2024-10-10 09:17:10.000"}]}"#,
+            r#"{"type":"RedisCluster","shards":[{"node_id":"0e9d360631a20c27f629267bf3e01de8e8c4cbec","address":"172.28.0.11:6379","repl_id":"8b2a1a1b5c2c2e9e4f8f2f7c8f3c3a0f6e9d1b2c","repl_offset":1024,"now_db_id":0,"timestamp":""}]}"#,
         ];
 
         for str in strs {
@@ -9,7 +9,7 @@ use super::{
     pg::pg_meta_manager::PgMetaManager, rdb_tb_meta::RdbTbMeta,
 };
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct RdbMetaManager {
     pub mysql_meta_manager: Option<MysqlMetaManager>,
     pub pg_meta_manager: Option<PgMetaManager>,
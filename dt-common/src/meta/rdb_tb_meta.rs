@@ -14,6 +14,8 @@ pub struct RdbTbMeta {
     pub order_col: Option<String>,
     pub partition_col: String,
     pub id_cols: Vec<String>,
+    // generated (mysql: virtual / stored, pg: stored) cols, values of them can NOT be written
+    pub generated_cols: Vec<String>,
     pub foreign_keys: Vec<ForeignKey>,
     pub ref_by_foreign_keys: Vec<ForeignKey>,
 }
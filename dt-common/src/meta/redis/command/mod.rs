@@ -2,3 +2,4 @@ pub mod cmd_constants;
 pub mod cmd_encoder;
 pub mod cmd_meta;
 pub mod key_parser;
+pub mod row_converter;
@@ -0,0 +1,558 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+use chrono::Utc;
+use regex::Regex;
+
+use crate::{
+    config::{config_token_parser::ConfigTokenParser, redis_mapping_config::RedisMappingConfig},
+    error::Error,
+    log_warn,
+    meta::{
+        col_value::ColValue,
+        redis::{
+            redis_entry::RedisEntry,
+            redis_object::{RedisCmd, RedisObject, RedisString},
+        },
+        row_data::RowData,
+        row_type::RowType,
+    },
+    utils::sql_util::SqlUtil,
+};
+
+use super::key_parser::KeyParser;
+
+const KEY_COL: &str = "key";
+const VALUE_COL: &str = "value";
+const MEMBER_COL: &str = "member";
+const SCORE_COL: &str = "score";
+
+const REDIS_ESCAPE: char = '"';
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum MappingType {
+    String,
+    Hash,
+    Zset,
+}
+
+struct MappingRule {
+    pattern: Regex,
+    schema: String,
+    tb: String,
+}
+
+/// convert redis entries into rows by key patterns:
+/// string -> {key, value}, hash -> {key, field_1, field_2...}, zset -> {key, member, score}
+pub struct RedisRowConverter {
+    string_rules: Vec<MappingRule>,
+    hash_rules: Vec<MappingRule>,
+    zset_rules: Vec<MappingRule>,
+    key_parser: KeyParser,
+}
+
+impl RedisRowConverter {
+    pub fn new(config: &RedisMappingConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            string_rules: Self::parse_rules(&config.string_map)?,
+            hash_rules: Self::parse_rules(&config.hash_map)?,
+            zset_rules: Self::parse_rules(&config.zset_map)?,
+            key_parser: KeyParser::new(),
+        })
+    }
+
+    pub fn convert(&self, entry: &RedisEntry) -> anyhow::Result<Vec<RowData>> {
+        if entry.is_base {
+            Ok(self.convert_object(entry))
+        } else {
+            self.convert_cmd(&entry.cmd)
+        }
+    }
+
+    fn convert_object(&self, entry: &RedisEntry) -> Vec<RowData> {
+        let key = &entry.key;
+        let mut rows = Vec::new();
+        match &entry.value {
+            RedisObject::String(obj) => {
+                if let Some(rule) = self.match_rule(MappingType::String, key) {
+                    let after = Self::build_string_cols(key, &obj.value);
+                    rows.push(rule.new_row(RowType::Insert, None, Some(after)));
+                }
+            }
+
+            RedisObject::Hash(obj) => {
+                if let Some(rule) = self.match_rule(MappingType::Hash, key) {
+                    let mut after = Self::build_key_cols(key);
+                    for (field, value) in obj.value.iter() {
+                        after.insert(field.to_string(), Self::to_col_value(value));
+                    }
+                    rows.push(rule.new_row(RowType::Insert, None, Some(after)));
+                }
+            }
+
+            RedisObject::Zset(obj) => {
+                if let Some(rule) = self.match_rule(MappingType::Zset, key) {
+                    for i in obj.elements.iter() {
+                        let after = Self::build_zset_cols(key, &i.member, Some(&i.score));
+                        rows.push(rule.new_row(RowType::Insert, None, Some(after)));
+                    }
+                }
+            }
+
+            // list / set / stream / module values have no row mapping
+            _ => {}
+        }
+        rows
+    }
+
+    fn convert_cmd(&self, cmd: &RedisCmd) -> anyhow::Result<Vec<RowData>> {
+        let cmd_name = cmd.get_str_arg(0).to_uppercase();
+        let args: Vec<RedisString> = cmd
+            .args
+            .iter()
+            .map(|i| RedisString::from(i.clone()))
+            .collect();
+
+        let mut rows = Vec::new();
+        match cmd_name.as_str() {
+            // SET key value [EX seconds] ...
+            "SET" | "SETNX" | "GETSET" => {
+                self.push_string_row(&mut rows, cmd, &args, 1, 2)?;
+            }
+
+            // SETEX key seconds value
+            "SETEX" | "PSETEX" => {
+                self.push_string_row(&mut rows, cmd, &args, 1, 3)?;
+            }
+
+            // MSET key value [key value ...]
+            "MSET" | "MSETNX" => {
+                Self::check_args(cmd, args.len() % 2 == 1)?;
+                for i in (1..args.len()).step_by(2) {
+                    self.push_string_row(&mut rows, cmd, &args, i, i + 1)?;
+                }
+            }
+
+            // HSET key field value [field value ...], the key may not exist in the target,
+            // an insert with part of the cols is upserted by rdb sinkers with partial_upsert
+            "HSET" | "HMSET" | "HSETNX" => {
+                Self::check_args(cmd, args.len() >= 4 && args.len() % 2 == 0)?;
+                if let Some(rule) = self.match_rule(MappingType::Hash, &args[1]) {
+                    let mut after = Self::build_key_cols(&args[1]);
+                    for i in (2..args.len()).step_by(2) {
+                        after.insert(args[i].to_string(), Self::to_col_value(&args[i + 1]));
+                    }
+                    rows.push(rule.new_row(RowType::Insert, None, Some(after)));
+                }
+            }
+
+            // HDEL key field [field ...], nothing to delete if the key not exists in the target
+            "HDEL" => {
+                Self::check_args(cmd, args.len() >= 3)?;
+                if let Some(rule) = self.match_rule(MappingType::Hash, &args[1]) {
+                    let mut after = Self::build_key_cols(&args[1]);
+                    for field in args.iter().skip(2) {
+                        after.insert(field.to_string(), ColValue::None);
+                    }
+                    let before = Self::build_key_cols(&args[1]);
+                    rows.push(rule.new_row(RowType::Update, Some(before), Some(after)));
+                }
+            }
+
+            // ZADD key [NX | XX] [GT | LT] [CH] [INCR] score member [score member ...]
+            "ZADD" => {
+                Self::check_args(cmd, args.len() >= 4)?;
+                if let Some(rule) = self.match_rule(MappingType::Zset, &args[1]) {
+                    let mut i = 2;
+                    while i < args.len() {
+                        match args[i].to_string().to_uppercase().as_str() {
+                            "NX" | "XX" | "GT" | "LT" | "CH" => i += 1,
+                            "INCR" => {
+                                log_warn!("redis cmd not supported by row mapping: [{}]", cmd);
+                                return Ok(rows);
+                            }
+                            _ => break,
+                        }
+                    }
+
+                    Self::check_args(cmd, i < args.len() && (args.len() - i) % 2 == 0)?;
+                    for j in (i..args.len()).step_by(2) {
+                        let after = Self::build_zset_cols(&args[1], &args[j + 1], Some(&args[j]));
+                        rows.push(rule.new_row(RowType::Insert, None, Some(after)));
+                    }
+                }
+            }
+
+            // ZREM key member [member ...]
+            "ZREM" => {
+                Self::check_args(cmd, args.len() >= 3)?;
+                if let Some(rule) = self.match_rule(MappingType::Zset, &args[1]) {
+                    for member in args.iter().skip(2) {
+                        let before = Self::build_zset_cols(&args[1], member, None);
+                        rows.push(rule.new_row(RowType::Delete, Some(before), None));
+                    }
+                }
+            }
+
+            // DEL key [key ...]
+            "DEL" | "UNLINK" => {
+                Self::check_args(cmd, args.len() >= 2)?;
+                for key in args.iter().skip(1) {
+                    self.push_delete_rows(&mut rows, key);
+                }
+            }
+
+            // EXPIRE key seconds, an expired key will be deleted immediately
+            "EXPIRE" | "PEXPIRE" | "EXPIREAT" | "PEXPIREAT" => {
+                Self::check_args(cmd, args.len() >= 3)?;
+                let time: i64 = args[2]
+                    .to_string()
+                    .parse()
+                    .with_context(|| format!("invalid expire time in redis cmd: [{}]", cmd))?;
+                let now_ms = Utc::now().timestamp_millis();
+                let expired = match cmd_name.as_str() {
+                    "EXPIREAT" => time * 1000 <= now_ms,
+                    "PEXPIREAT" => time <= now_ms,
+                    _ => time <= 0,
+                };
+                if expired {
+                    self.push_delete_rows(&mut rows, &args[1]);
+                }
+            }
+
+            _ => {
+                let mut cmd = cmd.clone();
+                // commands without keys (SELECT, MULTI, PING...) are ignored silently
+                if cmd.parse_keys(&self.key_parser).is_ok()
+                    && cmd
+                        .keys
+                        .iter()
+                        .any(|key| self.is_mapped(&RedisString::from(key.clone())))
+                {
+                    log_warn!("redis cmd not supported by row mapping: [{}]", cmd);
+                }
+            }
+        }
+        Ok(rows)
+    }
+
+    fn push_string_row(
+        &self,
+        rows: &mut Vec<RowData>,
+        cmd: &RedisCmd,
+        args: &[RedisString],
+        key_index: usize,
+        value_index: usize,
+    ) -> anyhow::Result<()> {
+        Self::check_args(cmd, args.len() > value_index)?;
+        let key = &args[key_index];
+        if let Some(rule) = self.match_rule(MappingType::String, key) {
+            let after = Self::build_string_cols(key, &args[value_index]);
+            rows.push(rule.new_row(RowType::Insert, None, Some(after)));
+        }
+        Ok(())
+    }
+
+    fn push_delete_rows(&self, rows: &mut Vec<RowData>, key: &RedisString) {
+        for mapping_type in [MappingType::String, MappingType::Hash, MappingType::Zset] {
+            if let Some(rule) = self.match_rule(mapping_type, key) {
+                let before = Self::build_key_cols(key);
+                rows.push(rule.new_row(RowType::Delete, Some(before), None));
+            }
+        }
+    }
+
+    fn is_mapped(&self, key: &RedisString) -> bool {
+        [MappingType::String, MappingType::Hash, MappingType::Zset]
+            .into_iter()
+            .any(|i| self.match_rule(i, key).is_some())
+    }
+
+    fn match_rule(&self, mapping_type: MappingType, key: &RedisString) -> Option<&MappingRule> {
+        let rules = match mapping_type {
+            MappingType::String => &self.string_rules,
+            MappingType::Hash => &self.hash_rules,
+            MappingType::Zset => &self.zset_rules,
+        };
+        let key = key.to_string();
+        rules.iter().find(|i| i.pattern.is_match(&key))
+    }
+
+    fn build_key_cols(key: &RedisString) -> HashMap<String, ColValue> {
+        HashMap::from([(KEY_COL.to_string(), Self::to_col_value(key))])
+    }
+
+    fn build_string_cols(key: &RedisString, value: &RedisString) -> HashMap<String, ColValue> {
+        let mut cols = Self::build_key_cols(key);
+        cols.insert(VALUE_COL.to_string(), Self::to_col_value(value));
+        cols
+    }
+
+    fn build_zset_cols(
+        key: &RedisString,
+        member: &RedisString,
+        score: Option<&RedisString>,
+    ) -> HashMap<String, ColValue> {
+        let mut cols = Self::build_key_cols(key);
+        cols.insert(MEMBER_COL.to_string(), Self::to_col_value(member));
+        if let Some(score) = score {
+            let score = match score.to_string().parse::<f64>() {
+                Ok(v) => ColValue::Double(v),
+                Err(_) => Self::to_col_value(score),
+            };
+            cols.insert(SCORE_COL.to_string(), score);
+        }
+        cols
+    }
+
+    fn to_col_value(value: &RedisString) -> ColValue {
+        match String::from_utf8(value.bytes.clone()) {
+            Ok(v) => ColValue::String(v),
+            Err(_) => ColValue::Blob(value.bytes.clone()),
+        }
+    }
+
+    fn check_args(cmd: &RedisCmd, valid: bool) -> anyhow::Result<()> {
+        if !valid {
+            bail! {Error::RedisCmdError(format!(
+                "invalid args in redis cmd: [{}]",
+                cmd
+            ))}
+        }
+        Ok(())
+    }
+
+    fn parse_rules(config_str: &str) -> anyhow::Result<Vec<MappingRule>> {
+        let escape_pair = (REDIS_ESCAPE, REDIS_ESCAPE);
+        let mut rules = Vec::new();
+        if config_str.trim().is_empty() {
+            return Ok(rules);
+        }
+
+        // "user:*":app.users,"order:*":app.orders
+        let tokens = ConfigTokenParser::parse(config_str, &[':', '.', ','], &[escape_pair]);
+        if tokens.len() % 3 != 0 {
+            bail! {Error::ConfigError(format!(
+                "invalid redis mapping config: [{}], key patterns with special characters should be enclosed by {}",
+                config_str, REDIS_ESCAPE
+            ))}
+        }
+
+        for i in tokens.chunks(3) {
+            let pattern = SqlUtil::unescape(&i[0], &escape_pair);
+            // same wildchars as filter: '*' for multiple chars, '?' for 0-1 chars
+            let regex = format!(
+                "^{}$",
+                regex::escape(&pattern)
+                    .replace("\\*", ".*")
+                    .replace("\\?", ".?")
+            );
+            rules.push(MappingRule {
+                pattern: Regex::new(&regex)
+                    .with_context(|| format!("invalid redis key pattern: [{}]", pattern))?,
+                schema: SqlUtil::unescape(&i[1], &escape_pair),
+                tb: SqlUtil::unescape(&i[2], &escape_pair),
+            });
+        }
+        Ok(rules)
+    }
+}
+
+impl MappingRule {
+    fn new_row(
+        &self,
+        row_type: RowType,
+        before: Option<HashMap<String, ColValue>>,
+        after: Option<HashMap<String, ColValue>>,
+    ) -> RowData {
+        RowData::new(
+            self.schema.clone(),
+            self.tb.clone(),
+            row_type,
+            before,
+            after,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::meta::redis::redis_object::{HashObject, StringObject, ZSetEntry, ZsetObject};
+
+    use super::*;
+
+    fn new_converter() -> RedisRowConverter {
+        let config = RedisMappingConfig {
+            string_map: r#""session:*":app.sessions"#.into(),
+            hash_map: r#""user:*":app.users,"user?":app.users_2"#.into(),
+            zset_map: r#""rank:*":app.ranks"#.into(),
+        };
+        RedisRowConverter::new(&config).unwrap()
+    }
+
+    fn new_cmd_entry(args: &[&str]) -> RedisEntry {
+        let mut entry = RedisEntry::new();
+        entry.cmd = RedisCmd::from_str_args(args);
+        entry
+    }
+
+    fn get_col(cols: &Option<HashMap<String, ColValue>>, col: &str) -> ColValue {
+        cols.as_ref().unwrap().get(col).unwrap().clone()
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let converter = new_converter();
+        assert_eq!(converter.hash_rules.len(), 2);
+        assert_eq!(converter.hash_rules[1].schema, "app");
+        assert_eq!(converter.hash_rules[1].tb, "users_2");
+
+        let key = |k: &str| RedisString::from(k.to_string());
+        let rule = converter.match_rule(MappingType::Hash, &key("user:1"));
+        assert_eq!(rule.unwrap().tb, "users");
+        let rule = converter.match_rule(MappingType::Hash, &key("user1"));
+        assert_eq!(rule.unwrap().tb, "users_2");
+        assert!(converter
+            .match_rule(MappingType::Hash, &key("user_12"))
+            .is_none());
+        assert!(converter
+            .match_rule(MappingType::String, &key("user:1"))
+            .is_none());
+
+        let config = RedisMappingConfig {
+            hash_map: "user:*:app.users".into(),
+            ..Default::default()
+        };
+        assert!(RedisRowConverter::new(&config).is_err());
+    }
+
+    #[test]
+    fn test_convert_snapshot() {
+        let converter = new_converter();
+
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = RedisString::from("user:1".to_string());
+        let mut obj = HashObject::new();
+        obj.value.insert(
+            RedisString::from("name".to_string()),
+            RedisString::from("a".to_string()),
+        );
+        obj.value.insert(
+            RedisString::from("age".to_string()),
+            RedisString::from("10".to_string()),
+        );
+        entry.value = RedisObject::Hash(obj);
+        let rows = converter.convert(&entry).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].row_type, RowType::Insert);
+        assert_eq!(
+            get_col(&rows[0].after, "key"),
+            ColValue::String("user:1".into())
+        );
+        assert_eq!(
+            get_col(&rows[0].after, "age"),
+            ColValue::String("10".into())
+        );
+
+        entry.key = RedisString::from("rank:1".to_string());
+        let mut obj = ZsetObject::new();
+        for (member, score) in [("a", "1.5"), ("b", "2")] {
+            obj.elements.push(ZSetEntry {
+                member: RedisString::from(member.to_string()),
+                score: RedisString::from(score.to_string()),
+            });
+        }
+        entry.value = RedisObject::Zset(obj);
+        let rows = converter.convert(&entry).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(get_col(&rows[0].after, "score"), ColValue::Double(1.5));
+        assert_eq!(
+            get_col(&rows[1].after, "member"),
+            ColValue::String("b".into())
+        );
+
+        // unmapped key
+        entry.key = RedisString::from("other:1".to_string());
+        let mut obj = StringObject::new();
+        obj.value = RedisString::from(vec![0xff, 0xfe]);
+        entry.value = RedisObject::String(obj.clone());
+        assert!(converter.convert(&entry).unwrap().is_empty());
+
+        entry.key = RedisString::from("session:1".to_string());
+        entry.value = RedisObject::String(obj);
+        let rows = converter.convert(&entry).unwrap();
+        assert_eq!(
+            get_col(&rows[0].after, "value"),
+            ColValue::Blob(vec![0xff, 0xfe])
+        );
+    }
+
+    #[test]
+    fn test_convert_cmd() {
+        let converter = new_converter();
+        let convert = |args: &[&str]| converter.convert(&new_cmd_entry(args)).unwrap();
+
+        let rows = convert(&["SET", "session:1", "v1", "EX", "100"]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].row_type, RowType::Insert);
+        assert_eq!(
+            get_col(&rows[0].after, "value"),
+            ColValue::String("v1".into())
+        );
+
+        let rows = convert(&["SETEX", "session:1", "100", "v2"]);
+        assert_eq!(
+            get_col(&rows[0].after, "value"),
+            ColValue::String("v2".into())
+        );
+
+        let rows = convert(&["MSET", "session:1", "v1", "other", "v2", "session:2", "v3"]);
+        assert_eq!(rows.len(), 2);
+
+        // a key new to the target
+        let rows = convert(&["HSET", "user:2", "name", "b", "age", "11"]);
+        assert_eq!(rows[0].row_type, RowType::Insert);
+        assert!(rows[0].before.is_none());
+        assert_eq!(rows[0].after.as_ref().unwrap().len(), 3);
+        assert_eq!(
+            get_col(&rows[0].after, "key"),
+            ColValue::String("user:2".into())
+        );
+
+        let rows = convert(&["HSETNX", "user:1", "name", "c"]);
+        assert_eq!(rows[0].row_type, RowType::Insert);
+        assert_eq!(rows[0].after.as_ref().unwrap().len(), 2);
+
+        let rows = convert(&["HDEL", "user:1", "age"]);
+        assert_eq!(get_col(&rows[0].after, "age"), ColValue::None);
+
+        let rows = convert(&["ZADD", "rank:1", "NX", "CH", "1", "a", "2", "b"]);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(get_col(&rows[1].after, "score"), ColValue::Double(2.0));
+        assert!(convert(&["ZADD", "rank:1", "INCR", "1", "a"]).is_empty());
+
+        let rows = convert(&["ZREM", "rank:1", "a", "b"]);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].row_type, RowType::Delete);
+        assert_eq!(
+            get_col(&rows[0].before, "member"),
+            ColValue::String("a".into())
+        );
+
+        let rows = convert(&["DEL", "user:1", "rank:1", "other"]);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].tb, "ranks");
+        assert_eq!(rows[1].row_type, RowType::Delete);
+
+        assert_eq!(convert(&["EXPIRE", "user:1", "0"]).len(), 1);
+        assert!(convert(&["EXPIRE", "user:1", "100"]).is_empty());
+        assert_eq!(convert(&["PEXPIREAT", "user:1", "1000"]).len(), 1);
+
+        assert!(convert(&["LPUSH", "user:1", "a"]).is_empty());
+        assert!(convert(&["SELECT", "1"]).is_empty());
+        assert!(converter
+            .convert(&new_cmd_entry(&["HSET", "user:1", "name"]))
+            .is_err());
+    }
+}
@@ -0,0 +1,9 @@
+pub struct RedisConstants {}
+
+impl RedisConstants {
+    // the id col of redis check log
+    pub const KEY: &'static str = "key";
+    pub const TYPE: &'static str = "type";
+    pub const TTL: &'static str = "ttl";
+    pub const VALUE: &'static str = "value";
+}
@@ -22,7 +22,9 @@ pub struct RedisEntry {
     pub cmd: RedisCmd,
     pub data_size: usize,
     pub slot: i32,
+    // LFU frequency / LRU idle seconds, -1 if unknown
     pub freq: i64,
+    pub idle: i64,
 }
 
 impl RedisEntry {
@@ -43,6 +45,7 @@ impl RedisEntry {
             data_size: 0,
             slot: 0,
             freq: -1,
+            idle: -1,
         }
     }
 
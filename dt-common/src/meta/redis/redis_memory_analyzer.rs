@@ -0,0 +1,444 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use strum::EnumString;
+
+use super::{
+    redis_entry::RedisEntry,
+    redis_object::{RedisObject, RedisString},
+};
+
+const KEY_TYPES: [&str; 8] = [
+    "string", "list", "hash", "set", "zset", "stream", "module", "unknown",
+];
+const ELEMENT_COUNT_BUCKETS: [(u64, &str); 5] = [
+    (1, "1"),
+    (10, "2-10"),
+    (100, "11-100"),
+    (1000, "101-1000"),
+    (10000, "1001-10000"),
+];
+const ELEMENT_COUNT_BUCKET_MAX: &str = "10000+";
+const TTL_BUCKETS: [(i64, &str); 3] = [
+    (3600 * 1000, "lt_1h"),
+    (24 * 3600 * 1000, "1h_1d"),
+    (7 * 24 * 3600 * 1000, "1d_7d"),
+];
+const TTL_BUCKET_MAX: &str = "gt_7d";
+const TTL_BUCKET_NONE: &str = "no_ttl";
+const ENCODINGS: [&str; 14] = [
+    "int",
+    "embstr",
+    "raw",
+    "linkedlist",
+    "quicklist",
+    "ziplist",
+    "listpack",
+    "zipmap",
+    "intset",
+    "hashtable",
+    "skiplist",
+    "stream",
+    "module",
+    "unknown",
+];
+// strings longer than this are stored as raw instead of embstr
+const EMBSTR_SIZE_LIMIT: usize = 44;
+
+#[derive(EnumString, Clone, Default, Debug, PartialEq)]
+pub enum MemoryReportFormat {
+    #[default]
+    #[strum(serialize = "json")]
+    Json,
+    #[strum(serialize = "csv")]
+    Csv,
+}
+
+/// aggregated statistics of the keys sharing a prefix
+#[derive(Serialize, Clone, Debug)]
+pub struct PrefixStatistic {
+    pub prefix: String,
+    pub key_count: u64,
+    pub memory: u64,
+    pub memory_by_type: BTreeMap<String, u64>,
+    pub key_count_by_type: BTreeMap<String, u64>,
+    pub element_count: BTreeMap<String, u64>,
+    pub ttl: BTreeMap<String, u64>,
+    pub encoding: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize)]
+pub struct MemoryReport {
+    pub total: PrefixStatistic,
+    pub prefix_count: usize,
+    pub top_prefixes: Vec<PrefixStatistic>,
+}
+
+/// aggregates keyspace statistics grouped by key prefix, e.g. with delimiters ":" and depth 2,
+/// keys "user:1:name" and "user:1:age" are grouped as "user:1",
+/// keys with fewer delimiters are grouped by as many segments as they have, keys without any by ""
+pub struct RedisMemoryAnalyzer {
+    pub delimiters: Vec<char>,
+    pub depth: usize,
+    pub top_n: usize,
+    pub total: PrefixStatistic,
+    pub prefixes: HashMap<String, PrefixStatistic>,
+}
+
+impl RedisMemoryAnalyzer {
+    pub fn new(delimiters: &str, depth: usize, top_n: usize) -> Self {
+        Self {
+            delimiters: delimiters.chars().collect(),
+            depth,
+            top_n,
+            total: PrefixStatistic::new("*"),
+            prefixes: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, entry: &RedisEntry) {
+        let key_type = entry.get_type();
+        let memory = entry.get_data_malloc_size() as u64;
+        let element_count_bucket = Self::get_element_count_bucket(&entry.value);
+        let ttl_bucket = Self::get_ttl_bucket(entry.expire_ms);
+        let encoding = Self::get_encoding(entry);
+
+        let prefix = self.get_prefix(&entry.key);
+        let prefix_statistic = self
+            .prefixes
+            .entry(prefix.clone())
+            .or_insert_with(|| PrefixStatistic::new(&prefix));
+        for statistic in [&mut self.total, prefix_statistic] {
+            statistic.key_count += 1;
+            statistic.memory += memory;
+            *statistic
+                .memory_by_type
+                .entry(key_type.clone())
+                .or_default() += memory;
+            *statistic
+                .key_count_by_type
+                .entry(key_type.clone())
+                .or_default() += 1;
+            *statistic
+                .element_count
+                .entry(element_count_bucket.to_string())
+                .or_default() += 1;
+            *statistic.ttl.entry(ttl_bucket.to_string()).or_default() += 1;
+            *statistic.encoding.entry(encoding.to_string()).or_default() += 1;
+        }
+    }
+
+    pub fn get_report(&self) -> MemoryReport {
+        let mut prefixes: Vec<&PrefixStatistic> = self.prefixes.values().collect();
+        prefixes.sort_by(|a, b| b.memory.cmp(&a.memory).then(a.prefix.cmp(&b.prefix)));
+        MemoryReport {
+            total: self.total.clone(),
+            prefix_count: self.prefixes.len(),
+            top_prefixes: prefixes.into_iter().take(self.top_n).cloned().collect(),
+        }
+    }
+
+    /// header line + one line for total + one line for each of the top prefixes
+    pub fn get_csv_report(&self) -> Vec<String> {
+        let report = self.get_report();
+        let mut lines = vec![PrefixStatistic::get_csv_header()];
+        lines.push(report.total.to_csv_line());
+        for statistic in report.top_prefixes.iter() {
+            lines.push(statistic.to_csv_line());
+        }
+        lines
+    }
+
+    pub fn get_prefix(&self, key: &RedisString) -> String {
+        let key = key.to_string();
+        let mut end = 0;
+        let mut segments = 0;
+        for (i, c) in key.char_indices() {
+            if segments >= self.depth {
+                break;
+            }
+            if self.delimiters.contains(&c) {
+                end = i;
+                segments += 1;
+            }
+        }
+        key[..end].to_string()
+    }
+
+    fn get_element_count_bucket(value: &RedisObject) -> &'static str {
+        let count = match value {
+            RedisObject::List(v) => v.elements.len(),
+            RedisObject::Hash(v) => v.value.len(),
+            RedisObject::Set(v) => v.elements.len(),
+            RedisObject::Zset(v) => v.elements.len(),
+            RedisObject::Stream(v) => v
+                .cmds
+                .iter()
+                .filter(|cmd| cmd.get_name().eq_ignore_ascii_case("xadd"))
+                .count(),
+            _ => 1,
+        } as u64;
+
+        for (max, bucket) in ELEMENT_COUNT_BUCKETS {
+            if count <= max {
+                return bucket;
+            }
+        }
+        ELEMENT_COUNT_BUCKET_MAX
+    }
+
+    fn get_ttl_bucket(expire_ms: i64) -> &'static str {
+        if expire_ms <= 0 {
+            return TTL_BUCKET_NONE;
+        }
+        for (max, bucket) in TTL_BUCKETS {
+            if expire_ms < max {
+                return bucket;
+            }
+        }
+        TTL_BUCKET_MAX
+    }
+
+    fn get_encoding(entry: &RedisEntry) -> &'static str {
+        if let RedisObject::String(v) = &entry.value {
+            return if String::from_utf8_lossy(v.value.as_bytes())
+                .parse::<i64>()
+                .is_ok()
+            {
+                "int"
+            } else if v.value.bytes.len() <= EMBSTR_SIZE_LIMIT {
+                "embstr"
+            } else {
+                "raw"
+            };
+        }
+
+        // refer to: RDB_TYPE_* in entry_parser
+        match entry.value_type_byte {
+            1 => "linkedlist",
+            2 | 4 => "hashtable",
+            3 | 5 => "skiplist",
+            6 | 7 => "module",
+            9 => "zipmap",
+            10 | 12 | 13 => "ziplist",
+            11 => "intset",
+            14 | 18 => "quicklist",
+            15 | 19 | 21 => "stream",
+            16 | 17 | 20 => "listpack",
+            _ => "unknown",
+        }
+    }
+}
+
+impl PrefixStatistic {
+    pub fn new(prefix: &str) -> Self {
+        let init = |labels: &[&str]| -> BTreeMap<String, u64> {
+            labels.iter().map(|i| (i.to_string(), 0)).collect()
+        };
+        let mut element_count_labels: Vec<&str> =
+            ELEMENT_COUNT_BUCKETS.iter().map(|i| i.1).collect();
+        element_count_labels.push(ELEMENT_COUNT_BUCKET_MAX);
+        let mut ttl_labels = vec![TTL_BUCKET_NONE];
+        ttl_labels.extend(TTL_BUCKETS.iter().map(|i| i.1));
+        ttl_labels.push(TTL_BUCKET_MAX);
+
+        Self {
+            prefix: prefix.to_string(),
+            key_count: 0,
+            memory: 0,
+            memory_by_type: init(&KEY_TYPES),
+            key_count_by_type: init(&KEY_TYPES),
+            element_count: init(&element_count_labels),
+            ttl: init(&ttl_labels),
+            encoding: init(&ENCODINGS),
+        }
+    }
+
+    fn get_csv_header() -> String {
+        let empty = Self::new("");
+        let mut columns = vec![
+            "prefix".to_string(),
+            "key_count".to_string(),
+            "memory".to_string(),
+        ];
+        for (name, map) in empty.get_maps() {
+            for label in map.keys() {
+                columns.push(format!("{}_{}", name, label));
+            }
+        }
+        columns.join(",")
+    }
+
+    fn to_csv_line(&self) -> String {
+        let mut values = vec![
+            Self::escape_csv(&self.prefix),
+            self.key_count.to_string(),
+            self.memory.to_string(),
+        ];
+        for (_, map) in self.get_maps() {
+            for count in map.values() {
+                values.push(count.to_string());
+            }
+        }
+        values.join(",")
+    }
+
+    fn get_maps(&self) -> [(&str, &BTreeMap<String, u64>); 5] {
+        [
+            ("memory", &self.memory_by_type),
+            ("key_count", &self.key_count_by_type),
+            ("element_count", &self.element_count),
+            ("ttl", &self.ttl),
+            ("encoding", &self.encoding),
+        ]
+    }
+
+    fn escape_csv(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::meta::redis::redis_object::{
+        HashObject, ListObject, RedisString, StringObject, ZSetEntry, ZsetObject,
+    };
+
+    use super::*;
+
+    fn string_entry(key: &str, value: &str, expire_ms: i64) -> RedisEntry {
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = RedisString::from(key.to_string());
+        entry.value = RedisObject::String(StringObject {
+            key: entry.key.clone(),
+            value: RedisString::from(value.to_string()),
+        });
+        entry.expire_ms = expire_ms;
+        entry
+    }
+
+    #[test]
+    fn test_get_prefix() {
+        let analyzer = RedisMemoryAnalyzer::new(":", 2, 10);
+        let prefix = |key: &str| analyzer.get_prefix(&RedisString::from(key.to_string()));
+        assert_eq!(prefix("user:1:name"), "user:1");
+        assert_eq!(prefix("user:1:name:first"), "user:1");
+        assert_eq!(prefix("user:1"), "user");
+        assert_eq!(prefix("user"), "");
+
+        let analyzer = RedisMemoryAnalyzer::new(":|", 1, 10);
+        let prefix = |key: &str| analyzer.get_prefix(&RedisString::from(key.to_string()));
+        assert_eq!(prefix("order|1"), "order");
+        assert_eq!(prefix("order:1|2"), "order");
+    }
+
+    #[test]
+    fn test_analyze() {
+        let mut analyzer = RedisMemoryAnalyzer::new(":", 1, 2);
+        analyzer.add(&string_entry("user:1", "12345", 0));
+        analyzer.add(&string_entry("user:2", "abc", 60 * 1000));
+        analyzer.add(&string_entry(
+            "order:1",
+            &"a".repeat(100),
+            2 * 24 * 3600 * 1000,
+        ));
+        analyzer.add(&string_entry("session", "a", 10 * 24 * 3600 * 1000));
+
+        let mut entry = RedisEntry::new();
+        entry.key = RedisString::from("user:list".to_string());
+        entry.value_type_byte = 18;
+        entry.value = RedisObject::List(ListObject {
+            key: entry.key.clone(),
+            elements: (0..20).map(|i| RedisString::from(i.to_string())).collect(),
+        });
+        analyzer.add(&entry);
+
+        let mut entry = RedisEntry::new();
+        entry.key = RedisString::from("user:hash".to_string());
+        entry.value_type_byte = 4;
+        let value: HashMap<RedisString, RedisString> = (0..200)
+            .map(|i| {
+                (
+                    RedisString::from(i.to_string()),
+                    RedisString::from(i.to_string()),
+                )
+            })
+            .collect();
+        entry.value = RedisObject::Hash(HashObject {
+            key: entry.key.clone(),
+            value,
+        });
+        analyzer.add(&entry);
+
+        let mut entry = RedisEntry::new();
+        entry.key = RedisString::from("rank".to_string());
+        entry.value_type_byte = 17;
+        entry.value = RedisObject::Zset(ZsetObject {
+            key: entry.key.clone(),
+            elements: vec![ZSetEntry {
+                member: RedisString::from("m".to_string()),
+                score: RedisString::from("1".to_string()),
+            }],
+        });
+        analyzer.add(&entry);
+
+        let report = analyzer.get_report();
+        let total = &report.total;
+        assert_eq!(report.prefix_count, 3);
+        assert_eq!(total.key_count, 7);
+        assert_eq!(total.key_count_by_type["string"], 4);
+        assert_eq!(total.key_count_by_type["list"], 1);
+        assert_eq!(total.key_count_by_type["hash"], 1);
+        assert_eq!(total.key_count_by_type["zset"], 1);
+        assert_eq!(total.key_count_by_type["set"], 0);
+        assert_eq!(total.element_count["1"], 5);
+        assert_eq!(total.element_count["11-100"], 1);
+        assert_eq!(total.element_count["101-1000"], 1);
+        assert_eq!(total.ttl["no_ttl"], 4);
+        assert_eq!(total.ttl["lt_1h"], 1);
+        assert_eq!(total.ttl["1d_7d"], 1);
+        assert_eq!(total.ttl["gt_7d"], 1);
+        assert_eq!(total.encoding["int"], 1);
+        assert_eq!(total.encoding["embstr"], 2);
+        assert_eq!(total.encoding["raw"], 1);
+        assert_eq!(total.encoding["quicklist"], 1);
+        assert_eq!(total.encoding["hashtable"], 1);
+        assert_eq!(total.encoding["listpack"], 1);
+
+        // top 2 prefixes ordered by memory
+        assert_eq!(report.top_prefixes.len(), 2);
+        let user = &report.top_prefixes[0];
+        assert_eq!(user.prefix, "user");
+        assert_eq!(user.key_count, 4);
+        assert_eq!(user.memory, user.memory_by_type.values().sum::<u64>());
+        assert_eq!(report.top_prefixes[1].prefix, "order");
+        assert_eq!(
+            total.memory,
+            analyzer.prefixes.values().map(|i| i.memory).sum::<u64>()
+        );
+
+        let csv = analyzer.get_csv_report();
+        assert_eq!(csv.len(), 4);
+        let columns = csv[0].split(',').count();
+        assert!(csv.iter().all(|line| line.split(',').count() == columns));
+        assert!(csv[1].starts_with("*,7,"));
+        assert!(csv[2].starts_with("user,4,"));
+    }
+
+    #[test]
+    fn test_report_format() {
+        use std::str::FromStr;
+        assert_eq!(
+            MemoryReportFormat::from_str("csv").unwrap(),
+            MemoryReportFormat::Csv
+        );
+        assert!(MemoryReportFormat::from_str("xml").is_err());
+    }
+}
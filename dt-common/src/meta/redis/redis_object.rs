@@ -82,12 +82,33 @@ impl ListObject {
     }
 }
 
+/// values saved by a module's rdb_save callback, in the order they were saved
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModuleValue {
+    SInt(i64),
+    UInt(u64),
+    Float(f32),
+    Double(f64),
+    String(RedisString),
+}
+
 #[derive(Debug, Clone)]
-pub struct ModuleObject {}
+pub struct ModuleObject {
+    pub key: RedisString,
+    // 9 chars type name, e.g. ReJSON-RL, MBbloom--, MBbloomCF, TSDB-TYPE
+    pub module_name: String,
+    pub encver: u64,
+    pub values: Vec<ModuleValue>,
+}
 
 impl ModuleObject {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            key: RedisString::new(),
+            module_name: String::new(),
+            encver: 0,
+            values: vec![],
+        }
     }
 }
 
@@ -330,7 +351,16 @@ impl RedisObject {
                 }
                 size + v.key.bytes.len()
             }
-            RedisObject::Module(_) => 0,
+            RedisObject::Module(v) => {
+                let mut size = 0;
+                for i in v.values.iter() {
+                    size += match i {
+                        ModuleValue::String(s) => s.bytes.len(),
+                        _ => 8,
+                    }
+                }
+                size + v.key.bytes.len()
+            }
             RedisObject::Unknown => 0,
         }
     }
@@ -23,6 +23,10 @@ pub struct RowData {
     pub before: Option<HashMap<String, ColValue>>,
     pub after: Option<HashMap<String, ColValue>>,
     pub data_size: usize,
+    // set by converters for deletes removing all rows matched by the key cols in before,
+    // e.g. rows of a mongo document in child tables are deleted by _id
+    #[serde(skip)]
+    pub partial_key: bool,
 }
 
 impl std::fmt::Display for RowData {
@@ -46,6 +50,7 @@ impl RowData {
             before,
             after,
             data_size: 0,
+            partial_key: false,
         };
         me.data_size = me.get_data_malloc_size();
         me
@@ -65,6 +70,7 @@ impl RowData {
             before: self.after.clone(),
             after: self.before.clone(),
             data_size: self.data_size,
+            partial_key: self.partial_key,
         }
     }
 
@@ -183,7 +189,11 @@ impl RowData {
         // refer to: https://docs.oracle.com/javase/6/docs/api/java/util/List.html#hashCode%28%29
         let mut hash_code = 1u128;
         for col in tb_meta.id_cols.iter() {
-            let col_hash_code = col_values.get(col).unwrap().hash_code();
+            // rows deleted by part of the key cols, e.g. rows of mongo child tables deleted by _id
+            let col_hash_code = match col_values.get(col) {
+                Some(col_value) => col_value.hash_code(),
+                None => return 0,
+            };
             // col_hash_code is 0 if col_value is ColValue::None,
             // consider fowlling case,
             // create table a(id int, value int, unique key(id, value));
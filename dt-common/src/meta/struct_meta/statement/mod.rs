@@ -1,3 +1,4 @@
+pub mod mongo_create_collection_statement;
 pub mod mysql_create_database_statement;
 pub mod mysql_create_table_statement;
 pub mod pg_create_schema_statement;
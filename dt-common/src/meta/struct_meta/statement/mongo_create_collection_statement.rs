@@ -0,0 +1,131 @@
+use mongodb::bson::{doc, Bson, Document};
+
+use crate::rdb_filter::RdbFilter;
+
+use crate::meta::struct_meta::structure::structure_type::StructureType;
+
+pub const MONGO_COLLECTION_TYPE_VIEW: &str = "view";
+const ID_INDEX_NAME: &str = "_id_";
+
+#[derive(Debug, Clone)]
+pub struct MongoCreateCollectionStatement {
+    pub db: String,
+    pub tb: String,
+    // collection, view, timeseries
+    pub collection_type: String,
+    // options returned by listCollections, e.g. capped, validator, collation, timeseries, clusteredIndex
+    pub options: Document,
+    // index specs returned by listIndexes
+    pub indexes: Vec<Document>,
+}
+
+impl MongoCreateCollectionStatement {
+    pub fn route(&mut self, dst_db: &str, dst_tb: &str) {
+        self.db = dst_db.to_string();
+        self.tb = dst_tb.to_string();
+    }
+
+    pub fn is_view(&self) -> bool {
+        self.collection_type == MONGO_COLLECTION_TYPE_VIEW
+    }
+
+    /// commands to be executed in [db], collections are created by create,
+    /// indexes are created by createIndexes one by one
+    pub fn to_commands(&self, filter: &RdbFilter) -> Vec<(String, Document)> {
+        let mut commands = Vec::new();
+
+        if !filter.filter_structure(&StructureType::Table) {
+            let key = format!("collection.{}.{}", self.db, self.tb);
+            let mut command = doc! { "create": &self.tb };
+            command.extend(self.options.clone());
+            commands.push((key, command));
+        }
+
+        if !filter.filter_structure(&StructureType::Index) {
+            for index in self.indexes.iter() {
+                let name = index.get_str("name").unwrap_or_default();
+                // _id index and clustered index are created with the collection
+                if name == ID_INDEX_NAME || index.get_bool("clustered").unwrap_or(false) {
+                    continue;
+                }
+
+                let mut spec = index.clone();
+                spec.remove("ns");
+                let key = format!("index.{}.{}.{}", self.db, self.tb, name);
+                let command = doc! { "createIndexes": &self.tb, "indexes": [spec] };
+                commands.push((key, command));
+            }
+        }
+        commands
+    }
+
+    pub fn to_sqls(&self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
+        let sqls = self
+            .to_commands(filter)
+            .into_iter()
+            .map(|(key, command)| {
+                let sql = Bson::Document(command).into_relaxed_extjson().to_string();
+                (key, sql)
+            })
+            .collect();
+        Ok(sqls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use crate::{
+        config::{config_enums::DbType, filter_config::FilterConfig},
+        rdb_filter::RdbFilter,
+    };
+
+    use super::MongoCreateCollectionStatement;
+
+    #[test]
+    fn test_to_commands() {
+        let statement = MongoCreateCollectionStatement {
+            db: "db_1".into(),
+            tb: "tb_1".into(),
+            collection_type: "collection".into(),
+            options: doc! { "capped": true, "size": 4096, "validator": { "a": { "$type": "int" } } },
+            indexes: vec![
+                doc! { "v": 2, "key": { "_id": 1 }, "name": "_id_" },
+                doc! { "v": 2, "key": { "a": 1 }, "name": "a_1", "unique": true, "ns": "db_1.tb_1" },
+                doc! { "v": 2, "key": { "b": 1 }, "name": "b_1", "expireAfterSeconds": 60 },
+            ],
+        };
+
+        let config = FilterConfig {
+            do_structures: "*".into(),
+            ..Default::default()
+        };
+        let filter = RdbFilter::from_config(&config, &DbType::Mongo).unwrap();
+        let commands = statement.to_commands(&filter);
+        assert_eq!(commands.len(), 3);
+        assert_eq!(commands[0].0, "collection.db_1.tb_1");
+        assert_eq!(
+            commands[0].1,
+            doc! { "create": "tb_1", "capped": true, "size": 4096, "validator": { "a": { "$type": "int" } } }
+        );
+        assert_eq!(commands[1].0, "index.db_1.tb_1.a_1");
+        assert_eq!(
+            commands[1].1,
+            doc! { "createIndexes": "tb_1", "indexes": [{ "v": 2, "key": { "a": 1 }, "name": "a_1", "unique": true }] }
+        );
+        assert_eq!(commands[2].0, "index.db_1.tb_1.b_1");
+
+        let config = FilterConfig {
+            do_structures: "index".into(),
+            ..Default::default()
+        };
+        let filter = RdbFilter::from_config(&config, &DbType::Mongo).unwrap();
+        let sqls = statement.to_sqls(&filter).unwrap();
+        assert_eq!(sqls.len(), 2);
+        assert_eq!(
+            sqls[0].1,
+            r#"{"createIndexes":"tb_1","indexes":[{"v":2,"key":{"a":1},"name":"a_1","unique":true}]}"#
+        );
+    }
+}
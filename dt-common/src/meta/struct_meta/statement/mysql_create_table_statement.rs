@@ -4,7 +4,7 @@ use crate::{config::config_enums::DbType, rdb_filter::RdbFilter};
 use crate::meta::struct_meta::structure::{
     column::Column,
     constraint::Constraint,
-    index::{Index, IndexKind},
+    index::{Index, IndexColumn, IndexKind},
     structure_type::StructureType,
     table::Table,
 };
@@ -32,6 +32,52 @@ impl MysqlCreateTableStatement {
         }
     }
 
+    /// adds shard_col storing "schema.tb" of the source table, and appends it to the primary key
+    /// and unique indexes, so rows merged from many source tables keep unique keys
+    pub fn add_shard_col(&mut self, shard_col: &str) {
+        let columns = &mut self.table.columns;
+        if columns.iter().any(|i| i.column_name == shard_col) {
+            return;
+        }
+
+        let column_key = if columns.iter().any(|i| i.column_key == "PRI") {
+            "PRI"
+        } else {
+            ""
+        };
+        let ordinal_position = columns
+            .iter()
+            .map(|i| i.ordinal_position)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        columns.push(Column {
+            column_name: shard_col.to_string(),
+            ordinal_position,
+            is_nullable: false,
+            column_type: "varchar(255)".to_string(),
+            column_key: column_key.to_string(),
+            ..Default::default()
+        });
+
+        for index in self.indexes.iter_mut() {
+            if index.index_kind != IndexKind::Unique {
+                continue;
+            }
+            let seq_in_index = index
+                .columns
+                .iter()
+                .map(|i| i.seq_in_index)
+                .max()
+                .unwrap_or(0)
+                + 1;
+            index.columns.push(IndexColumn {
+                column_name: shard_col.to_string(),
+                seq_in_index,
+            });
+        }
+    }
+
     pub fn to_sqls(&mut self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
         let mut sqls = Vec::new();
 
@@ -90,7 +136,7 @@ impl MysqlCreateTableStatement {
             )
         }
 
-        // Todo: table partition; column visible, generated(information_schema.column.GENERATION_EXPRESSION)
+        // Todo: table partition
         let mut sql = format!(
             "CREATE TABLE IF NOT EXISTS `{}`.`{}` ({}{})",
             table.database_name, table.table_name, columns_sql, pk_str
@@ -131,6 +177,12 @@ impl MysqlCreateTableStatement {
                 line.push_str(&format!(" COLLATE {}", i.collation_name))
             }
 
+            // CREATE TABLE a(b int, c int GENERATED ALWAYS AS (b + 1) VIRTUAL);
+            if let Some(expr) = &i.generation_expression {
+                let stored = if i.is_virtual { "VIRTUAL" } else { "STORED" };
+                line.push_str(&format!(" GENERATED ALWAYS AS ({}) {}", expr, stored));
+            }
+
             match &i.column_default {
                 Some(ColumnDefault::Expression(v)) => line.push_str(&format!(" DEFAULT {}", v)),
                 Some(ColumnDefault::Literal(v)) => {
@@ -150,11 +202,27 @@ impl MysqlCreateTableStatement {
             // mysql 8.0:
             //  DEFAULT_GENERATED
             //  DEFAULT_GENERATED on update CURRENT_TIMESTAMP
-            let extra = i.extra.replacen("DEFAULT_GENERATED", "", 1);
+            // mysql 8.0.23+:
+            //  INVISIBLE
+            //  VIRTUAL GENERATED INVISIBLE
+            //  STORED GENERATED
+            let extra = i
+                .extra
+                .replacen("DEFAULT_GENERATED", "", 1)
+                .replacen("VIRTUAL GENERATED", "", 1)
+                .replacen("STORED GENERATED", "", 1)
+                .replacen("INVISIBLE", "", 1)
+                .trim()
+                .to_string();
             if !extra.is_empty() {
                 line.push_str(&format!(" {}", extra));
             }
 
+            // CREATE TABLE a(b int, c int INVISIBLE);
+            if i.invisible {
+                line.push_str(" INVISIBLE");
+            }
+
             let nullable = if !i.is_nullable {
                 String::from("NOT NULL")
             } else {
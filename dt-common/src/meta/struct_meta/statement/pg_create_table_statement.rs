@@ -63,6 +63,66 @@ impl PgCreateTableStatement {
         }
     }
 
+    /// adds shard_col storing "schema.tb" of the source table, and appends it to the primary key
+    /// and unique keys, so rows merged from many source tables keep unique keys
+    pub fn add_shard_col(&mut self, shard_col: &str) {
+        let columns = &mut self.table.columns;
+        if columns.iter().any(|i| i.column_name == shard_col) {
+            return;
+        }
+
+        let ordinal_position = columns
+            .iter()
+            .map(|i| i.ordinal_position)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        columns.push(Column {
+            column_name: shard_col.to_string(),
+            ordinal_position,
+            is_nullable: false,
+            column_type: "varchar(255)".to_string(),
+            ..Default::default()
+        });
+
+        // definitions of constraints: PRIMARY KEY (id), UNIQUE (code) INCLUDE (name)
+        for constraint in self.constraints.iter_mut() {
+            if matches!(
+                constraint.constraint_type,
+                ConstraintType::Primary | ConstraintType::Unique
+            ) {
+                Self::append_key_col(&mut constraint.definition, shard_col);
+            }
+        }
+
+        // definitions of indexes: CREATE UNIQUE INDEX idx ON public.tb USING btree (code)
+        for index in self.indexes.iter_mut() {
+            if index.index_kind == IndexKind::Unique {
+                Self::append_key_col(&mut index.definition, shard_col);
+            }
+        }
+    }
+
+    // appends col to the first parenthesized column list of the definition
+    fn append_key_col(definition: &mut String, col: &str) {
+        let start = match definition.find('(') {
+            Some(start) => start,
+            None => return,
+        };
+        let mut depth = 0;
+        let end = definition[start..].char_indices().find_map(|(i, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => return None,
+            }
+            (depth == 0).then_some(start + i)
+        });
+        if let Some(end) = end {
+            definition.insert_str(end, &format!(r#", "{}""#, col));
+        }
+    }
+
     pub fn to_sqls(&mut self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
         let mut sqls = Vec::new();
 
@@ -162,6 +222,9 @@ impl PgCreateTableStatement {
                 }
                 None => {}
             }
+            if let Some(expr) = &column.generation_expression {
+                sql.push_str(format!("GENERATED ALWAYS AS ({}) STORED ", expr).as_str())
+            }
             if let Some(x) = &column.generated {
                 if x == "ALWAYS" {
                     sql.push_str("GENERATED ALWAYS AS IDENTITY ")
@@ -1,6 +1,7 @@
 use crate::rdb_filter::RdbFilter;
 
 use super::{
+    mongo_create_collection_statement::MongoCreateCollectionStatement,
     mysql_create_database_statement::MysqlCreateDatabaseStatement,
     mysql_create_table_statement::MysqlCreateTableStatement,
     pg_create_schema_statement::PgCreateSchemaStatement,
@@ -13,6 +14,7 @@ pub enum StructStatement {
     PgCreateSchema(PgCreateSchemaStatement),
     MysqlCreateTable(MysqlCreateTableStatement),
     PgCreateTable(PgCreateTableStatement),
+    MongoCreateCollection(MongoCreateCollectionStatement),
     #[default]
     Unknown,
 }
@@ -24,6 +26,7 @@ impl StructStatement {
             Self::PgCreateSchema(s) => s.to_sqls(filter),
             Self::MysqlCreateTable(s) => s.to_sqls(filter),
             Self::PgCreateTable(s) => s.to_sqls(filter),
+            Self::MongoCreateCollection(s) => s.to_sqls(filter),
             _ => Ok(vec![]),
         }
     }
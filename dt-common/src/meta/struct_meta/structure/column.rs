@@ -9,6 +9,11 @@ pub struct Column {
     pub extra: String,       // auto_increment
     pub column_comment: String,
     pub generated: Option<String>,
+    // mysql: VIRTUAL / STORED generated column, pg: GENERATED ALWAYS AS (...) STORED
+    pub generation_expression: Option<String>,
+    pub is_virtual: bool,
+    // mysql 8.0.23+
+    pub invisible: bool,
     pub character_set_name: String,
     pub collation_name: String,
 }
@@ -1,7 +1,17 @@
+use std::collections::VecDeque;
+
 use crate::meta::position::Position;
 
 #[derive(Default)]
 pub struct Syncer {
     pub received_position: Position,
     pub committed_position: Position,
+    // (position, is_ddl, state) of shard ddl states, waiting for the items they were pushed with
+    pub shard_ddl_states: VecDeque<(Position, bool, String)>,
+    // the shard ddl state at committed_position
+    pub shard_ddl_state: String,
+    // (position, state) of online ddl states, waiting for the heartbeats they were pushed with
+    pub online_ddl_states: VecDeque<(Position, String)>,
+    // the online ddl state at committed_position
+    pub online_ddl_state: String,
 }
@@ -4,8 +4,11 @@ use crate::{
     config::{
         config_enums::DbType, config_token_parser::ConfigTokenParser, filter_config::FilterConfig,
     },
+    log_warn,
     meta::{
-        ddl_meta::ddl_type::DdlType, row_type::RowType,
+        ddl_meta::ddl_type::DdlType,
+        redis::{command::key_parser::KeyParser, redis_entry::RedisEntry},
+        row_type::RowType,
         struct_meta::structure::structure_type::StructureType,
     },
     utils::sql_util::SqlUtil,
@@ -19,6 +22,8 @@ type IgnoreCols = HashMap<(String, String), HashSet<String>>;
 type WhereConditions = HashMap<(String, String), String>;
 
 const JSON_PREFIX: &str = "json:";
+const REGEX_PREFIX: &str = "regex:";
+const REDIS_ESCAPE_PAIR: (char, char) = ('"', '"');
 
 #[derive(Debug, Clone)]
 pub struct RdbFilter {
@@ -32,6 +37,9 @@ pub struct RdbFilter {
     pub do_structures: HashSet<String>,
     pub do_ddls: HashSet<String>,
     pub ignore_cmds: HashSet<String>,
+    // redis key patterns
+    pub do_keys: Vec<Regex>,
+    pub ignore_keys: Vec<Regex>,
     pub where_conditions: WhereConditions,
     pub cache: HashMap<(String, String), bool>,
 }
@@ -49,6 +57,8 @@ impl RdbFilter {
             do_structures: Self::parse_single_tokens(&config.do_structures, db_type)?,
             do_ddls: Self::parse_single_tokens(&config.do_ddls, db_type)?,
             ignore_cmds: Self::parse_single_tokens(&config.ignore_cmds, db_type)?,
+            do_keys: Self::parse_key_patterns(&config.do_keys)?,
+            ignore_keys: Self::parse_key_patterns(&config.ignore_keys)?,
             where_conditions: Self::parse_where_conditions(&config.where_conditions)?,
             cache: HashMap::new(),
         })
@@ -120,6 +130,38 @@ impl RdbFilter {
         self.ignore_cmds.contains(cmd)
     }
 
+    pub fn has_key_filter(&self) -> bool {
+        !self.do_keys.is_empty() || !self.ignore_keys.is_empty()
+    }
+
+    pub fn filter_key(&self, key: &str) -> bool {
+        if self.ignore_keys.iter().any(|i| i.is_match(key)) {
+            return true;
+        }
+        !self.do_keys.is_empty() && !self.do_keys.iter().any(|i| i.is_match(key))
+    }
+
+    /// rdb entries are filtered by their keys, commands are filtered only if all their keys
+    /// are filtered, commands without keys (e.g. flushdb) or unknown to key_parser are kept
+    pub fn filter_redis_entry(&self, entry: &mut RedisEntry, key_parser: &KeyParser) -> bool {
+        if entry.is_base {
+            return self.filter_key(&entry.key.to_string());
+        }
+
+        if entry.cmd.keys.is_empty() && entry.cmd.parse_keys(key_parser).is_err() {
+            return false;
+        }
+        let keys = &entry.cmd.keys;
+        let filtered_count = keys.iter().filter(|key| self.filter_key(key)).count();
+        if filtered_count > 0 && filtered_count < keys.len() {
+            log_warn!(
+                "command kept since only part of its keys are filtered: [{}]",
+                entry.cmd
+            );
+        }
+        !keys.is_empty() && filtered_count == keys.len()
+    }
+
     pub fn get_ignore_cols(&self, schema: &str, tb: &str) -> Option<&HashSet<String>> {
         self.ignore_cols.get(&(schema.to_string(), tb.to_string()))
     }
@@ -214,6 +256,56 @@ impl RdbFilter {
         ConfigTokenParser::parse_config(config_str, db_type, &delimiters)
     }
 
+    fn parse_key_patterns(config_str: &str) -> anyhow::Result<Vec<Regex>> {
+        // do_keys=user:*,"a,b*",regex:^order:\d+$
+        let mut results = Vec::new();
+        for token in ConfigTokenParser::parse(config_str, &[','], &[REDIS_ESCAPE_PAIR]) {
+            let token = SqlUtil::unescape(&token, &REDIS_ESCAPE_PAIR);
+            if token.is_empty() {
+                continue;
+            }
+            let pattern = match token.strip_prefix(REGEX_PREFIX) {
+                Some(regex) => regex.to_string(),
+                None => Self::glob_to_regex(&token),
+            };
+            let regex = Regex::new(&pattern)
+                .with_context(|| format!("invalid key pattern: [{}]", token))?;
+            results.push(regex);
+        }
+        Ok(results)
+    }
+
+    /// redis style glob: * for any chars, ? for a single char, [abc] / [^a-z] for a char class,
+    /// \ for escaping
+    fn glob_to_regex(glob: &str) -> String {
+        let mut pattern = String::from("(?s)^");
+        let mut chars = glob.chars();
+        let mut in_class = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        pattern.push_str(&regex::escape(&next.to_string()));
+                    }
+                }
+                '*' if !in_class => pattern.push_str(".*"),
+                '?' if !in_class => pattern.push('.'),
+                '[' if !in_class => {
+                    in_class = true;
+                    pattern.push('[');
+                }
+                ']' if in_class => {
+                    in_class = false;
+                    pattern.push(']');
+                }
+                '^' | '-' if in_class => pattern.push(c),
+                _ => pattern.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        pattern.push('$');
+        pattern
+    }
+
     fn parse_ignore_cols(config_str: &str) -> anyhow::Result<IgnoreCols> {
         let mut results = IgnoreCols::new();
         if config_str.trim().is_empty() {
@@ -258,6 +350,8 @@ impl RdbFilter {
 #[cfg(test)]
 mod tests {
 
+    use crate::meta::redis::redis_object::RedisCmd;
+
     use super::*;
 
     #[test]
@@ -852,4 +946,64 @@ mod tests {
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Update));
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Delete));
     }
+
+    #[test]
+    fn test_rdb_filter_redis_keys() {
+        let db_type = DbType::Redis;
+        let config = FilterConfig {
+            do_schemas: "*".to_string(),
+            do_keys: r#"user:*,order:?,"a,b*",session:[0-9]*,regex:^cart:\d+$"#.to_string(),
+            ignore_keys: r"user:tmp:*,user:\*".to_string(),
+            ..Default::default()
+        };
+        let rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(rdb_fitler.has_key_filter());
+        assert!(!rdb_fitler.filter_key("user:1"));
+        assert!(!rdb_fitler.filter_key("order:1"));
+        assert!(rdb_fitler.filter_key("order:12"));
+        assert!(!rdb_fitler.filter_key("a,b.c"));
+        assert!(!rdb_fitler.filter_key("session:1abc"));
+        assert!(rdb_fitler.filter_key("session:abc"));
+        assert!(!rdb_fitler.filter_key("cart:123"));
+        assert!(rdb_fitler.filter_key("cart:12a"));
+        assert!(rdb_fitler.filter_key("product:1"));
+        // ignore_keys
+        assert!(rdb_fitler.filter_key("user:tmp:1"));
+        assert!(rdb_fitler.filter_key("user:*"));
+        // '.' is not a wildcard
+        assert!(rdb_fitler.filter_key("order.1"));
+
+        // commands
+        let key_parser = KeyParser::new();
+        let assert_cmd = |args: &[&str], filtered: bool| {
+            let mut entry = RedisEntry::new();
+            entry.cmd = RedisCmd::from_str_args(args);
+            assert_eq!(
+                rdb_fitler.filter_redis_entry(&mut entry, &key_parser),
+                filtered
+            );
+        };
+        assert_cmd(&["set", "user:1", "v"], false);
+        assert_cmd(&["set", "product:1", "v"], true);
+        assert_cmd(&["mset", "product:1", "v", "product:2", "v"], true);
+        // part of the keys are filtered
+        assert_cmd(&["mset", "user:1", "v", "product:2", "v"], false);
+        // commands without keys
+        assert_cmd(&["flushdb"], false);
+
+        // without key filters
+        let config = FilterConfig {
+            do_schemas: "*".to_string(),
+            ..Default::default()
+        };
+        let rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_fitler.has_key_filter());
+        assert!(!rdb_fitler.filter_key("product:1"));
+
+        let config = FilterConfig {
+            do_keys: "session:[0-9".to_string(),
+            ..Default::default()
+        };
+        assert!(RdbFilter::from_config(&config, &db_type).is_err());
+    }
 }
@@ -0,0 +1,382 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Context;
+use chrono::Utc;
+use mongodb::bson::{Bson, Document};
+
+use crate::{
+    config::config_enums::DbType,
+    meta::{
+        col_value::ColValue,
+        condition::{
+            condition_expr::{ColumnResolver, ConditionExpr, EvalContext},
+            condition_parser::ConditionParser,
+            condition_value::ConditionValue,
+        },
+        mongo::mongo_constant::MongoConstants,
+        row_data::RowData,
+        row_type::RowType,
+    },
+    rdb_filter::RdbFilter,
+};
+
+struct RowCondition {
+    expr: ConditionExpr,
+    cols: HashSet<String>,
+}
+
+/// applies where_conditions to cdc rows, updates moving rows into / out of the subset
+/// are converted to inserts (upserts) / deletes
+pub struct RowFilter {
+    db_type: DbType,
+    conditions: HashMap<(String, String), RowCondition>,
+}
+
+impl RowFilter {
+    pub fn from_rdb_filter(filter: &RdbFilter) -> anyhow::Result<Self> {
+        let mut conditions = HashMap::new();
+        for ((schema, tb), condition) in filter.where_conditions.iter() {
+            // fail instead of syncing rows out of the subset
+            let expr = ConditionParser::parse(condition, &filter.db_type).with_context(|| {
+                format!(
+                    "where_conditions of {}.{} can not be evaluated in cdc, condition: {}",
+                    schema, tb, condition
+                )
+            })?;
+            let cols = expr.get_cols();
+            conditions.insert((schema.clone(), tb.clone()), RowCondition { expr, cols });
+        }
+
+        Ok(Self {
+            db_type: filter.db_type.clone(),
+            conditions,
+        })
+    }
+
+    /// None if the row is filtered, full_before should be false if before contains only
+    /// the key of the row, in which case after should be the full row
+    pub fn filter_row(&self, row_data: RowData, full_before: bool) -> Option<RowData> {
+        let condition = match self
+            .conditions
+            .get(&(row_data.schema.clone(), row_data.tb.clone()))
+        {
+            Some(condition) => condition,
+            None => return Some(row_data),
+        };
+
+        match row_data.row_type {
+            RowType::Insert => match self.eval(condition, &row_data.after) {
+                Some(false) => None,
+                _ => Some(row_data),
+            },
+
+            RowType::Delete => {
+                if !full_before {
+                    return Some(row_data);
+                }
+                match self.eval(condition, &row_data.before) {
+                    Some(false) => None,
+                    _ => Some(row_data),
+                }
+            }
+
+            RowType::Update => {
+                let before_matched = if full_before {
+                    self.eval(condition, &row_data.before)
+                } else {
+                    None
+                };
+                match (before_matched, self.eval(condition, &row_data.after)) {
+                    // the row can not be evaluated, e.g. columns are not in the binlog
+                    (_, None) => Some(row_data),
+                    (Some(true), Some(true)) => Some(row_data),
+                    (Some(false), Some(false)) => None,
+                    // moved into the subset
+                    (Some(false), Some(true)) => Some(Self::to_upsert(row_data)),
+                    (None, Some(true)) if !full_before => Some(Self::to_upsert(row_data)),
+                    (None, Some(true)) => Some(row_data),
+                    // moved out of the subset, or it is unknown whether it was in the subset,
+                    // deleting a row not existing in target is harmless
+                    (_, Some(false)) => Some(row_data.split_update_row_data().0),
+                }
+            }
+        }
+    }
+
+    fn eval(
+        &self,
+        condition: &RowCondition,
+        col_values: &Option<HashMap<String, ColValue>>,
+    ) -> Option<bool> {
+        let col_values = col_values.as_ref()?;
+        let ctx = EvalContext {
+            now: Utc::now().naive_utc(),
+            case_insensitive: self.db_type == DbType::Mysql,
+        };
+
+        // mongo documents, fields not existing are NULL
+        if let Some(ColValue::MongoDoc(doc)) = col_values.get(MongoConstants::DOC) {
+            let resolver = MongoDocResolver { doc };
+            return Some(condition.expr.eval(&resolver, &ctx).unwrap_or(false));
+        }
+
+        // columns not existing are unknown, e.g. ignored or not in a minimal row image
+        let resolver = ColValuesResolver { col_values };
+        if condition
+            .cols
+            .iter()
+            .any(|col| resolver.get_col_value(col).is_none())
+        {
+            return None;
+        }
+        Some(condition.expr.eval(&resolver, &ctx).unwrap_or(false))
+    }
+
+    fn to_upsert(row_data: RowData) -> RowData {
+        let RowData {
+            schema,
+            tb,
+            before,
+            after,
+            ..
+        } = row_data;
+
+        // the full document replaces the target document by upsert
+        if let Some(mut after) = after {
+            if after.contains_key(MongoConstants::DOC) {
+                after.remove(MongoConstants::DIFF_DOC);
+                return RowData::new(schema, tb, RowType::Update, before, Some(after));
+            }
+            return RowData::new(schema, tb, RowType::Insert, None, Some(after));
+        }
+        RowData::new(schema, tb, RowType::Insert, None, None)
+    }
+}
+
+struct ColValuesResolver<'a> {
+    col_values: &'a HashMap<String, ColValue>,
+}
+
+impl ColValuesResolver<'_> {
+    fn get_col_value(&self, col: &str) -> Option<&ColValue> {
+        if let Some(col_value) = self.col_values.get(col) {
+            return Some(col_value);
+        }
+        // unquoted identifiers are case insensitive
+        self.col_values
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(col))
+            .map(|(_, col_value)| col_value)
+    }
+}
+
+impl ColumnResolver for ColValuesResolver<'_> {
+    fn resolve(&self, col: &str) -> ConditionValue {
+        match self.get_col_value(col) {
+            Some(col_value) => ConditionValue::from_col_value(col_value),
+            None => ConditionValue::Null,
+        }
+    }
+}
+
+struct MongoDocResolver<'a> {
+    doc: &'a Document,
+}
+
+impl ColumnResolver for MongoDocResolver<'_> {
+    // col is a field path like "profile.age" or "tags.0"
+    fn resolve(&self, col: &str) -> ConditionValue {
+        let mut value: Option<&Bson> = None;
+        for (i, field) in col.split('.').enumerate() {
+            value = match (i, value) {
+                (0, _) => self.doc.get(field),
+                (_, Some(Bson::Document(doc))) => doc.get(field),
+                (_, Some(Bson::Array(array))) => {
+                    field.parse::<usize>().ok().and_then(|i| array.get(i))
+                }
+                _ => None,
+            };
+            if value.is_none() {
+                break;
+            }
+        }
+        value.map_or(ConditionValue::Null, ConditionValue::from_bson)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use mongodb::bson::doc;
+
+    use super::RowFilter;
+    use crate::{
+        config::{config_enums::DbType, filter_config::FilterConfig},
+        meta::{
+            col_value::ColValue, mongo::mongo_constant::MongoConstants, row_data::RowData,
+            row_type::RowType,
+        },
+        rdb_filter::RdbFilter,
+    };
+
+    fn build_row_filter(db_type: &DbType, condition: &str) -> RowFilter {
+        RowFilter::from_rdb_filter(&build_rdb_filter(db_type, condition)).unwrap()
+    }
+
+    fn build_rdb_filter(db_type: &DbType, condition: &str) -> RdbFilter {
+        let config = FilterConfig {
+            do_tbs: "db_1.*".into(),
+            where_conditions: format!(
+                r#"json:[{{"db":"db_1","tb":"tb_1","condition":"{}"}}]"#,
+                condition
+            ),
+            ..Default::default()
+        };
+        RdbFilter::from_config(&config, db_type).unwrap()
+    }
+
+    fn build_col_values(id: i32, tenant_id: Option<i32>) -> HashMap<String, ColValue> {
+        let mut col_values = HashMap::new();
+        col_values.insert("id".to_string(), ColValue::Long(id));
+        if let Some(tenant_id) = tenant_id {
+            col_values.insert("tenant_id".to_string(), ColValue::Long(tenant_id));
+        }
+        col_values
+    }
+
+    fn build_row_data(
+        row_type: RowType,
+        before: Option<HashMap<String, ColValue>>,
+        after: Option<HashMap<String, ColValue>>,
+    ) -> RowData {
+        RowData::new("db_1".into(), "tb_1".into(), row_type, before, after)
+    }
+
+    #[test]
+    fn test_filter_rdb_row() {
+        let row_filter = build_row_filter(&DbType::Mysql, "tenant_id = 42");
+
+        let insert = build_row_data(RowType::Insert, None, Some(build_col_values(1, Some(42))));
+        assert_eq!(row_filter.filter_row(insert.clone(), true), Some(insert));
+        let insert = build_row_data(RowType::Insert, None, Some(build_col_values(1, Some(7))));
+        assert_eq!(row_filter.filter_row(insert, true), None);
+
+        let delete = build_row_data(RowType::Delete, Some(build_col_values(1, Some(7))), None);
+        assert_eq!(row_filter.filter_row(delete.clone(), true), None);
+        // before contains only the key
+        assert_eq!(row_filter.filter_row(delete.clone(), false), Some(delete));
+
+        // other tables are not filtered
+        let mut other = build_row_data(RowType::Insert, None, Some(build_col_values(1, Some(7))));
+        other.tb = "tb_2".into();
+        assert_eq!(row_filter.filter_row(other.clone(), true), Some(other));
+
+        // conditions can not be evaluated in cdc
+        let filter = build_rdb_filter(&DbType::Mysql, "tenant_id IN (SELECT id FROM db_1.tenants)");
+        assert!(RowFilter::from_rdb_filter(&filter).is_err());
+    }
+
+    #[test]
+    fn test_filter_rdb_update() {
+        let row_filter = build_row_filter(&DbType::Pg, "tenant_id = 42");
+        let update = |before: Option<i32>, after: Option<i32>| {
+            build_row_data(
+                RowType::Update,
+                Some(build_col_values(1, before)),
+                Some(build_col_values(1, after)),
+            )
+        };
+
+        let row_data = update(Some(42), Some(42));
+        assert_eq!(
+            row_filter.filter_row(row_data.clone(), true),
+            Some(row_data)
+        );
+        assert_eq!(row_filter.filter_row(update(Some(7), Some(8)), true), None);
+
+        // moved into the subset
+        let row_data = row_filter
+            .filter_row(update(Some(7), Some(42)), true)
+            .unwrap();
+        assert_eq!(row_data.row_type, RowType::Insert);
+        assert_eq!(row_data.before, None);
+        assert_eq!(row_data.after, Some(build_col_values(1, Some(42))));
+
+        // moved out of the subset
+        let row_data = row_filter
+            .filter_row(update(Some(42), Some(7)), true)
+            .unwrap();
+        assert_eq!(row_data.row_type, RowType::Delete);
+        assert_eq!(row_data.before, Some(build_col_values(1, Some(42))));
+
+        // before contains only the key
+        let row_data = row_filter
+            .filter_row(update(None, Some(42)), false)
+            .unwrap();
+        assert_eq!(row_data.row_type, RowType::Insert);
+        let row_data = row_filter.filter_row(update(None, Some(7)), false).unwrap();
+        assert_eq!(row_data.row_type, RowType::Delete);
+
+        // columns of the condition are not in the row
+        let row_data = update(None, None);
+        assert_eq!(
+            row_filter.filter_row(row_data.clone(), true),
+            Some(row_data)
+        );
+    }
+
+    #[test]
+    fn test_filter_mongo_row() {
+        let row_filter = build_row_filter(&DbType::Mongo, "profile.tenant_id = 42");
+        let build_doc_row = |row_type: RowType, tenant_id: i32| {
+            let mut before = HashMap::new();
+            before.insert(
+                MongoConstants::DOC.to_string(),
+                ColValue::MongoDoc(doc! { "_id": 1 }),
+            );
+            let mut after = HashMap::new();
+            after.insert(
+                MongoConstants::DOC.to_string(),
+                ColValue::MongoDoc(doc! { "_id": 1, "profile": { "tenant_id": tenant_id } }),
+            );
+            after.insert(
+                MongoConstants::DIFF_DOC.to_string(),
+                ColValue::MongoDoc(doc! { "$set": { "profile.tenant_id": tenant_id } }),
+            );
+            match row_type {
+                RowType::Insert => build_row_data(row_type, None, Some(after)),
+                _ => build_row_data(row_type, Some(before), Some(after)),
+            }
+        };
+
+        let insert = build_doc_row(RowType::Insert, 42);
+        assert_eq!(row_filter.filter_row(insert.clone(), false), Some(insert));
+        assert_eq!(
+            row_filter.filter_row(build_doc_row(RowType::Insert, 7), false),
+            None
+        );
+
+        // the full document replaces the target document
+        let row_data = row_filter
+            .filter_row(build_doc_row(RowType::Update, 42), false)
+            .unwrap();
+        assert_eq!(row_data.row_type, RowType::Update);
+        let after = row_data.after.unwrap();
+        assert!(after.contains_key(MongoConstants::DOC));
+        assert!(!after.contains_key(MongoConstants::DIFF_DOC));
+
+        let row_data = row_filter
+            .filter_row(build_doc_row(RowType::Update, 7), false)
+            .unwrap();
+        assert_eq!(row_data.row_type, RowType::Delete);
+
+        // partial updates of op_log without the full document are kept
+        let mut row_data = build_doc_row(RowType::Update, 7);
+        row_data.after.as_mut().unwrap().remove(MongoConstants::DOC);
+        assert_eq!(
+            row_filter.filter_row(row_data.clone(), false),
+            Some(row_data)
+        );
+    }
+}
@@ -89,6 +89,26 @@ impl RedisUtil {
         )}
     }
 
+    /// return: (master_replid, master_replid2), a master accepts PSYNC with either of them,
+    /// replid2 is the replication id inherited from its former master after a failover
+    pub fn get_replication_ids(conn: &mut redis::Connection) -> anyhow::Result<(String, String)> {
+        let cmd = RedisCmd::from_str_args(&["INFO", "replication"]);
+        let value = conn.req_packed_command(&CmdEncoder::encode(&cmd))?;
+        if let redis::Value::BulkString(data) = value {
+            let info = String::from_utf8(data)?;
+            let get_field = |name: &str| {
+                info.lines()
+                    .find_map(|line| line.trim().strip_prefix(name))
+                    .map(|v| v.to_string())
+                    .unwrap_or_default()
+            };
+            return Ok((get_field("master_replid:"), get_field("master_replid2:")));
+        }
+        bail! {Error::RedisResultError(
+            "can not get replication ids by INFO replication".into(),
+        )}
+    }
+
     pub fn parse_result_as_string(value: Value) -> anyhow::Result<Vec<String>> {
         let mut results = Vec::new();
         match value {
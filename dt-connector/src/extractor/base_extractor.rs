@@ -24,7 +24,11 @@ use dt_common::{
 };
 use serde_json::json;
 
-use crate::{data_marker::DataMarker, rdb_router::RdbRouter};
+use crate::{
+    data_marker::DataMarker,
+    rdb_router::RdbRouter,
+    shard_ddl_coordinator::{ShardDdlCoordinator, ShardItem},
+};
 
 use super::extractor_monitor::ExtractorMonitor;
 
@@ -35,6 +39,7 @@ pub struct BaseExtractor {
     pub monitor: ExtractorMonitor,
     pub data_marker: Option<DataMarker>,
     pub time_filter: TimeFilter,
+    pub shard_ddl_coordinator: Option<ShardDdlCoordinator>,
 }
 
 impl BaseExtractor {
@@ -58,6 +63,11 @@ impl BaseExtractor {
             return Ok(());
         }
 
+        let position = match &mut self.shard_ddl_coordinator {
+            Some(coordinator) => coordinator.on_push(&dt_data, position),
+            None => position,
+        };
+
         self.monitor.counters.record_count += 1;
         self.monitor.counters.data_size += dt_data.get_data_size();
         self.monitor.try_flush(false);
@@ -107,11 +117,27 @@ impl BaseExtractor {
     }
 
     pub async fn push_row(&mut self, row_data: RowData, position: Position) -> anyhow::Result<()> {
+        if let Some(coordinator) = &mut self.shard_ddl_coordinator {
+            let (src_schema, src_tb) = (row_data.schema.clone(), row_data.tb.clone());
+            let row_data = self.router.route_row(row_data);
+            if let Some(item) = coordinator.on_row(&src_schema, &src_tb, row_data, position)? {
+                return self.push_shard_items(vec![item]).await;
+            }
+            return Ok(());
+        }
+
         let row_data = self.router.route_row(row_data);
         self.push_dt_data(DtData::Dml { row_data }, position).await
     }
 
     pub async fn push_ddl(&mut self, ddl_data: DdlData, position: Position) -> anyhow::Result<()> {
+        if let Some(coordinator) = &mut self.shard_ddl_coordinator {
+            let (src_schema, src_tb) = ddl_data.get_schema_tb();
+            let ddl_data = self.router.route_ddl(ddl_data);
+            let items = coordinator.on_ddl(&src_schema, &src_tb, ddl_data, position)?;
+            return self.push_shard_items(items).await;
+        }
+
         let ddl_data = self.router.route_ddl(ddl_data);
         while !self.buffer.is_empty() {
             TimeUtil::sleep_millis(1).await;
@@ -119,6 +145,25 @@ impl BaseExtractor {
         self.push_dt_data(DtData::Ddl { ddl_data }, position).await
     }
 
+    async fn push_shard_items(&mut self, items: Vec<ShardItem>) -> anyhow::Result<()> {
+        for item in items {
+            match item {
+                ShardItem::Row(row_data, position) => {
+                    self.push_dt_data(DtData::Dml { row_data }, position)
+                        .await?
+                }
+                ShardItem::Ddl(ddl_data, position) => {
+                    while !self.buffer.is_empty() {
+                        TimeUtil::sleep_millis(1).await;
+                    }
+                    self.push_dt_data(DtData::Ddl { ddl_data }, position)
+                        .await?
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub async fn push_struct(&mut self, struct_data: StructData) -> anyhow::Result<()> {
         let struct_data = self.router.route_struct(struct_data);
         self.push_dt_data(DtData::Struct { struct_data }, Position::None)
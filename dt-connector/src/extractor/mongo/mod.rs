@@ -1,3 +1,4 @@
 pub mod mongo_cdc_extractor;
 pub mod mongo_check_extractor;
 pub mod mongo_snapshot_extractor;
+pub mod mongo_struct_extractor;
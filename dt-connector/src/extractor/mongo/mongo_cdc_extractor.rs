@@ -7,28 +7,35 @@ use std::{
     time::Instant,
 };
 
+use anyhow::bail;
 use async_trait::async_trait;
 use chrono::Utc;
 use dt_common::meta::{
     col_value::ColValue,
+    ddl_meta::ddl_data::DdlData,
     dt_data::DtData,
-    mongo::{mongo_cdc_source::MongoCdcSource, mongo_constant::MongoConstants},
-    position::Position,
+    mongo::{
+        mongo_cdc_source::MongoCdcSource, mongo_constant::MongoConstants,
+        mongo_ddl_parser::MongoDdlParser, mongo_oplog_merger::MongoOplogMerger,
+        mongo_update_parser::MongoUpdateParser,
+    },
+    position::{MongoShardPosition, Position},
     row_data::RowData,
     row_type::RowType,
     syncer::Syncer,
 };
 use dt_common::{
-    config::config_enums::DbType, log_error, log_info, rdb_filter::RdbFilter,
-    utils::time_util::TimeUtil,
+    config::config_enums::DbType, error::Error, log_error, log_info, log_warn,
+    rdb_filter::RdbFilter, row_filter::RowFilter, utils::time_util::TimeUtil,
 };
 use mongodb::{
-    bson::{doc, Bson, Document, Timestamp},
-    change_stream::event::{OperationType, ResumeToken},
-    options::{ChangeStreamOptions, FullDocumentBeforeChangeType, FullDocumentType, UpdateOptions},
+    bson::{self, doc, Bson, Document, Timestamp},
+    change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken},
+    options::{ClientOptions, CursorType, FindOptions, ServerAddress, UpdateOptions},
     Client,
 };
 use serde_json::json;
+use tokio::sync::mpsc;
 
 use crate::{
     extractor::{base_extractor::BaseExtractor, resumer::cdc_resumer::CdcResumer},
@@ -36,10 +43,16 @@ use crate::{
 };
 
 const SYSTEM_DBS: [&str; 3] = ["admin", "config", "local"];
+const SYSTEM_COLLECTION_PREFIX: &str = "system.";
+const SHARD_OPLOG_CHANNEL_SIZE: usize = 1000;
+
+type ShardOplogEntry = (usize, anyhow::Result<Document>);
 
 pub struct MongoCdcExtractor {
     pub base_extractor: BaseExtractor,
     pub filter: RdbFilter,
+    pub row_filter: RowFilter,
+    pub url: String,
     pub resume_token: String,
     pub start_timestamp: u32,
     pub source: MongoCdcSource,
@@ -49,19 +62,29 @@ pub struct MongoCdcExtractor {
     pub heartbeat_tb: String,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
+    // set if [mongo_mapping] is configured, update op_logs are emitted with full documents
+    // since partial updates of arrays / deep paths can not be mapped to columns
+    pub full_doc_update: bool,
 }
 
 #[async_trait]
 impl Extractor for MongoCdcExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
-        if let Position::MongoCdc {
-            resume_token,
-            operation_time,
-            ..
-        } = &self.resumer.current_position
-        {
-            self.resume_token = resume_token.to_owned();
-            self.start_timestamp = operation_time.to_owned();
+        let resume = match &self.resumer.current_position {
+            Position::MongoCdc {
+                resume_token,
+                operation_time,
+                ..
+            } => {
+                self.resume_token = resume_token.to_owned();
+                self.start_timestamp = operation_time.to_owned();
+                true
+            }
+            // positions of shards are resumed when their oplogs are tailed
+            Position::MongoShardedCdc { .. } => true,
+            _ => false,
+        };
+        if resume {
             log_info!("resume from: {}", self.resumer.current_position);
             self.base_extractor
                 .push_dt_data(
@@ -83,6 +106,7 @@ impl Extractor for MongoCdcExtractor {
 
         match self.source {
             MongoCdcSource::OpLog => self.extract_oplog().await?,
+            MongoCdcSource::ShardedOpLog => self.extract_sharded_oplog().await?,
             MongoCdcSource::ChangeStream => self.extract_change_stream().await?,
         }
         self.base_extractor.wait_task_finish().await
@@ -100,8 +124,8 @@ impl MongoCdcExtractor {
         let filter = doc! {
             "ts": { "$gte": start_timestamp }
         };
-        let options = mongodb::options::FindOptions::builder()
-            .cursor_type(mongodb::options::CursorType::TailableAwait)
+        let options = FindOptions::builder()
+            .cursor_type(CursorType::TailableAwait)
             .build();
 
         let oplog = self
@@ -112,109 +136,355 @@ impl MongoCdcExtractor {
 
         while cursor.advance().await? {
             let doc: Document = cursor.deserialize_current()?;
-            // https://github.com/mongodb/mongo/blob/master/src/mongo/db/repl/oplog.cpp
-            // op:
-            //     "i" insert
-            //     "u" update
-            //     "d" delete
-            //     "c" db cmd
-            //     "n" no op
-            //     "xi" insert global index key
-            //     "xd" delete global index key
-
-            let op = Self::get_op(&doc);
-            let mut row_type = RowType::Insert;
-            let mut before = HashMap::new();
-            let mut after = HashMap::new();
-            let o = doc.get("o");
-            let o2 = doc.get("o2");
-            let ts = doc.get("ts");
-            let ns = doc.get("ns");
-
-            match op.as_str() {
-                "i" => {
-                    after.insert(
-                        MongoConstants::DOC.to_string(),
-                        ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
-                    );
+            let position = Self::build_oplog_position(&doc.get("ts"));
+            self.parse_oplog(doc, position).await?;
+        }
+        Ok(())
+    }
+
+    async fn extract_sharded_oplog(&mut self) -> anyhow::Result<()> {
+        let shards = self.get_shards().await?;
+        if shards.is_empty() {
+            bail! {Error::ExtractorError(
+                "no shards found in config.shards, url of source=sharded_op_log should be a mongos".into()
+            )}
+        }
+
+        let start_timestamp = self.parse_start_timestamp();
+        let positions =
+            Self::init_shard_positions(&shards, &self.resumer.current_position, start_timestamp);
+
+        let (sender, receiver) = mpsc::channel(SHARD_OPLOG_CHANNEL_SIZE);
+        let mut tail_tasks = Vec::new();
+        for (index, ((shard, host), position)) in shards.iter().zip(positions.iter()).enumerate() {
+            let ts = Timestamp {
+                time: position.operation_time,
+                increment: position.increment,
+            };
+            let filter = doc! { "ts": { "$gt": ts } };
+
+            log_info!(
+                "start tailing shard oplog, shard: {}, host: {}, filter: {}",
+                shard,
+                host,
+                filter
+            );
+            let client = self.create_shard_client(host).await?;
+            let sender = sender.clone();
+            tail_tasks.push(tokio::spawn(async move {
+                if let Err(err) = Self::tail_shard_oplog(index, &client, filter, &sender).await {
+                    let _ = sender.send((index, Err(err))).await;
                 }
-                "u" => {
-                    row_type = RowType::Update;
-                    // for update op log, doc.o contains only diff instead of full doc
-                    let after_doc = o.unwrap().as_document().unwrap();
-                    // refer: https://www.mongodb.com/community/forums/t/oplog-update-entry-without-set-and-unset/171771
-                    // https://www.mongodb.com/docs/manual/reference/operator/update/#update-operators-1
-                    // in MongoDB 4.4 and earlier, after_doc contains $set with all new document fields,
-                    // after that, after_doc contains diff with only changed fields.
-                    let diff_doc = if let Some(doc) = after_doc.get("diff") {
-                        let doc = doc.as_document().unwrap();
-                        if let Some(i_doc) = doc.get("i") {
-                            doc! {MongoConstants::SET: i_doc.as_document().unwrap()}
-                        } else if let Some(u_doc) = doc.get("u") {
-                            doc! {MongoConstants::SET: u_doc.as_document().unwrap()}
-                        } else if let Some(d_doc) = doc.get("d") {
-                            doc! {MongoConstants::UNSET: d_doc.as_document().unwrap()}
-                        } else {
-                            doc! {}
-                        }
-                    } else if let Some(set_doc) = after_doc.get(MongoConstants::SET) {
-                        doc! {MongoConstants::SET: set_doc.as_document().unwrap()}
-                    } else if let Some(unset_doc) = after_doc.get(MongoConstants::UNSET) {
-                        doc! {MongoConstants::UNSET: unset_doc.as_document().unwrap()}
-                    } else {
-                        doc! {}
-                    };
+            }));
+        }
+        drop(sender);
 
-                    if diff_doc.is_empty() {
-                        log_error!(
-                            "update op_log is neither $set nor $unset, ignore, o2: {:?}, o: {:?}",
-                            o2,
-                            o
+        let result = self.merge_shard_oplogs(positions, receiver).await;
+        for task in tail_tasks {
+            task.abort();
+        }
+        result
+    }
+
+    /// positions of shards to tail after, shards not in the resumed position (added after the
+    /// position was logged, or logged before any entry was taken) start right before the earliest
+    /// resumed shard position, or the start timestamp if no shard is resumed
+    fn init_shard_positions(
+        shards: &[(String, String)],
+        resumed_position: &Position,
+        start_timestamp: Timestamp,
+    ) -> Vec<MongoShardPosition> {
+        let resumed_shards: Vec<&MongoShardPosition> = match resumed_position {
+            Position::MongoShardedCdc { shards } => {
+                shards.iter().filter(|i| i.operation_time > 0).collect()
+            }
+            _ => Vec::new(),
+        };
+        let start_timestamp = resumed_shards
+            .iter()
+            .map(|shard| Timestamp {
+                time: shard.operation_time,
+                increment: shard.increment,
+            })
+            .min()
+            .unwrap_or(start_timestamp);
+        // entries are tailed by "$gt", so unseen shards start right before start_timestamp
+        let (time, increment) = if start_timestamp.increment > 0 {
+            (start_timestamp.time, start_timestamp.increment - 1)
+        } else {
+            (start_timestamp.time.saturating_sub(1), u32::MAX)
+        };
+
+        shards
+            .iter()
+            .map(
+                |(shard, _)| match resumed_shards.iter().find(|i| i.shard == *shard) {
+                    Some(position) => (*position).clone(),
+                    None => MongoShardPosition {
+                        shard: shard.clone(),
+                        operation_time: time,
+                        increment,
+                        timestamp: Position::format_timestamp_millis(time as i64 * 1000),
+                    },
+                },
+            )
+            .collect()
+    }
+
+    async fn merge_shard_oplogs(
+        &mut self,
+        mut positions: Vec<MongoShardPosition>,
+        mut receiver: mpsc::Receiver<ShardOplogEntry>,
+    ) -> anyhow::Result<()> {
+        let mut merger = MongoOplogMerger::new(positions.len());
+        let mut primary_shards = self.get_primary_shards().await?;
+        while let Some((index, entry)) = receiver.recv().await {
+            merger.push(index, entry?)?;
+            while let Some((index, doc)) = merger.pop() {
+                // noops also move positions forward, they are carried by the next data
+                let ts = doc.get_timestamp("ts")?;
+                let shard_position = &mut positions[index];
+                shard_position.operation_time = ts.time;
+                shard_position.increment = ts.increment;
+                shard_position.timestamp = Position::format_timestamp_millis(ts.time as i64 * 1000);
+
+                // a ddl is written to the oplog of each shard involved,
+                // only the one of the primary shard of the database is taken
+                if let Some(db) = Self::get_oplog_ddl_db(&doc) {
+                    let shard = &positions[index].shard;
+                    if !self
+                        .is_primary_shard(&mut primary_shards, &db, shard)
+                        .await?
+                    {
+                        log_info!(
+                            "ddl of non-primary shard ignored, shard: {}, entry: {}",
+                            shard,
+                            doc
                         );
                         continue;
                     }
+                }
 
+                let position = Position::MongoShardedCdc {
+                    shards: positions.clone(),
+                };
+                self.parse_oplog(doc, position).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn tail_shard_oplog(
+        index: usize,
+        client: &Client,
+        filter: Document,
+        sender: &mpsc::Sender<ShardOplogEntry>,
+    ) -> anyhow::Result<()> {
+        let options = FindOptions::builder()
+            .cursor_type(CursorType::TailableAwait)
+            .build();
+        let oplog = client.database("local").collection::<Document>("oplog.rs");
+        let mut cursor = oplog.find(filter, options).await?;
+
+        while cursor.advance().await? {
+            let doc: Document = cursor.deserialize_current()?;
+            if sender.send((index, Ok(doc))).await.is_err() {
+                // the merger stopped
+                return Ok(());
+            }
+        }
+        // entries of other shards can not be merged without this shard
+        bail! {Error::ExtractorError(format!(
+            "oplog cursor of shard closed, shard index: {}",
+            index
+        ))}
+    }
+
+    async fn get_shards(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let mut cursor = self
+            .mongo_client
+            .database("config")
+            .collection::<Document>("shards")
+            .find(None, None)
+            .await?;
+
+        let mut shards = Vec::new();
+        while cursor.advance().await? {
+            let doc: Document = cursor.deserialize_current()?;
+            if let (Ok(id), Ok(host)) = (doc.get_str("_id"), doc.get_str("host")) {
+                shards.push((id.to_string(), host.to_string()));
+            }
+        }
+        Ok(shards)
+    }
+
+    /// HashMap<db, primary shard>
+    async fn get_primary_shards(&self) -> anyhow::Result<HashMap<String, String>> {
+        let mut cursor = self
+            .mongo_client
+            .database("config")
+            .collection::<Document>("databases")
+            .find(None, None)
+            .await?;
+
+        let mut primary_shards = HashMap::new();
+        while cursor.advance().await? {
+            let doc: Document = cursor.deserialize_current()?;
+            if let (Ok(db), Ok(primary)) = (doc.get_str("_id"), doc.get_str("primary")) {
+                primary_shards.insert(db.to_string(), primary.to_string());
+            }
+        }
+        Ok(primary_shards)
+    }
+
+    async fn is_primary_shard(
+        &self,
+        primary_shards: &mut HashMap<String, String>,
+        db: &str,
+        shard: &str,
+    ) -> anyhow::Result<bool> {
+        // databases created after startup
+        if !primary_shards.contains_key(db) {
+            primary_shards.extend(self.get_primary_shards().await?);
+        }
+        // the database was dropped before its primary shard was loaded,
+        // take ddls from the shard whose ddl comes first
+        let primary_shard = primary_shards
+            .entry(db.to_string())
+            .or_insert_with(|| shard.to_string());
+        Ok(primary_shard == shard)
+    }
+
+    async fn create_shard_client(&self, host: &str) -> anyhow::Result<Client> {
+        // host of a shard: <replica set name>/<host1>:<port1>,<host2>:<port2>,
+        // credentials and other options of the mongos url are reused
+        let (repl_set_name, hosts) = match host.split_once('/') {
+            Some((repl_set_name, hosts)) => (Some(repl_set_name.to_string()), hosts),
+            None => (None, host),
+        };
+
+        let mut client_options = ClientOptions::parse_async(&self.url).await?;
+        client_options.app_name = Some(self.app_name.clone());
+        client_options.hosts = hosts
+            .split(',')
+            .map(ServerAddress::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        client_options.direct_connection = None;
+        client_options.repl_set_name = repl_set_name;
+        Ok(Client::with_options(client_options)?)
+    }
+
+    async fn parse_oplog(&mut self, doc: Document, position: Position) -> anyhow::Result<()> {
+        // entries written by chunk migrations between shards are not user changes
+        if Self::is_from_migrate(&doc) {
+            return Ok(());
+        }
+
+        // https://github.com/mongodb/mongo/blob/master/src/mongo/db/repl/oplog.cpp
+        // op:
+        //     "i" insert
+        //     "u" update
+        //     "d" delete
+        //     "c" db cmd
+        //     "n" no op
+        //     "xi" insert global index key
+        //     "xd" delete global index key
+
+        let op = Self::get_op(&doc);
+        let mut row_type = RowType::Insert;
+        let mut before = HashMap::new();
+        let mut after = HashMap::new();
+        let o = doc.get("o");
+        let o2 = doc.get("o2");
+        let ns = doc.get("ns");
+
+        match op.as_str() {
+            "i" => {
+                after.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
+                );
+            }
+            "u" => {
+                row_type = RowType::Update;
+                // for update op log, doc.o contains only diff instead of full doc,
+                // doc.o2 contains the document key
+                let o = o.unwrap().as_document().unwrap();
+                let o2 = o2.unwrap().as_document().unwrap();
+                before.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(o2.clone()),
+                );
+
+                let diff_doc = if self.full_doc_update {
+                    None
+                } else {
+                    MongoUpdateParser::parse_oplog_update(o)
+                };
+                if let Some(diff_doc) = diff_doc {
+                    if diff_doc.is_empty() {
+                        return Ok(());
+                    }
                     after.insert(
                         MongoConstants::DIFF_DOC.to_string(),
-                        ColValue::MongoDoc(diff_doc.clone()),
-                    );
-                    before.insert(
-                        MongoConstants::DOC.to_string(),
-                        ColValue::MongoDoc(o2.unwrap().as_document().unwrap().clone()),
+                        ColValue::MongoDoc(diff_doc),
                     );
-                }
-                "d" => {
-                    row_type = RowType::Delete;
-                    before.insert(
-                        MongoConstants::DOC.to_string(),
-                        ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
-                    );
-                }
-                // TODO, DDL
-                "c" | "xi" | "xd" => {
-                    // after version 7.0, the oplog generated by deleteMany is "c" instead of "d"
-                    let data = Self::extract_oplog_delete_many(&doc);
-                    for (row_data, position) in data {
-                        self.push_row_to_buf(row_data, position).await.unwrap();
+                } else {
+                    // replacement, or the diff can not be applied as a partial update
+                    let full_doc = if MongoUpdateParser::is_oplog_replacement(o) {
+                        Some(o.clone())
+                    } else {
+                        self.lookup_full_doc(&ns, o2).await?
+                    };
+
+                    if let Some(full_doc) = full_doc {
+                        after.insert(
+                            MongoConstants::DOC.to_string(),
+                            ColValue::MongoDoc(full_doc),
+                        );
+                    } else {
+                        log_warn!(
+                            "update op_log can not be applied and the document no longer exists, ignore, o2: {:?}, o: {:?}",
+                            o2,
+                            o
+                        );
+                        return Ok(());
                     }
-                    continue;
                 }
-                "n" => {
-                    // TODO, heartbeat
-                    // Document({"op": String("n"), "ns": String(""), "o": Document({"msg": String("periodic noop")}), "ts": Timestamp { time: 1693470874, increment: 1 }, "t": Int64(67), "v": Int64(2), "wall": DateTime(2023-08-31 8:34:34.19 +00:00:00)})
-                    continue;
+            }
+            "d" => {
+                row_type = RowType::Delete;
+                before.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
+                );
+            }
+            "c" | "xi" | "xd" => {
+                if op == "c" {
+                    if let Some(ddl_data) = Self::parse_oplog_ddl(ns, o) {
+                        self.push_ddl_to_buf(ddl_data, position).await?;
+                        return Ok(());
+                    }
                 }
-                _ => {
-                    continue;
+                // after version 7.0, the oplog generated by deleteMany is "c" instead of "d"
+                let data = Self::extract_oplog_delete_many(&doc, &position);
+                for (row_data, position) in data {
+                    self.push_row_to_buf(row_data, position).await.unwrap();
                 }
+                return Ok(());
+            }
+            "n" => {
+                // TODO, heartbeat
+                // Document({"op": String("n"), "ns": String(""), "o": Document({"msg": String("periodic noop")}), "ts": Timestamp { time: 1693470874, increment: 1 }, "t": Int64(67), "v": Int64(2), "wall": DateTime(2023-08-31 8:34:34.19 +00:00:00)})
+                return Ok(());
+            }
+            _ => {
+                return Ok(());
             }
-
-            // get db & tb
-            let (row_data, position) =
-                Self::build_oplog_row_data(&ns, &ts, row_type, before, after);
-            self.push_row_to_buf(row_data, position).await?;
         }
-        Ok(())
+
+        let row_data = Self::build_oplog_row_data(&ns, row_type, before, after);
+        self.push_row_to_buf(row_data, position).await
     }
 
     fn get_op(doc: &Document) -> String {
@@ -225,7 +495,7 @@ impl MongoCdcExtractor {
         op.into()
     }
 
-    fn extract_oplog_delete_many(doc: &Document) -> Vec<(RowData, Position)> {
+    fn extract_oplog_delete_many(doc: &Document, position: &Position) -> Vec<(RowData, Position)> {
         // Some(Document({
         //     "applyOps": Array([Document({
         //         "op": String("d"),
@@ -252,7 +522,6 @@ impl MongoCdcExtractor {
 
         let mut data = vec![];
         let o = doc.get("o");
-        let ts = doc.get("ts");
 
         if o.is_none() || o.unwrap().as_document().is_none() {
             return data;
@@ -277,7 +546,7 @@ impl MongoCdcExtractor {
             let op = Self::get_op(item);
             let ns = item.get("ns");
 
-            if op.as_str() != "d" {
+            if op.as_str() != "d" || Self::is_from_migrate(item) {
                 continue;
             }
 
@@ -288,25 +557,58 @@ impl MongoCdcExtractor {
                 ColValue::MongoDoc(o.unwrap().as_document().unwrap().clone()),
             );
 
-            data.push(Self::build_oplog_row_data(
-                &ns,
-                &ts,
-                RowType::Delete,
-                before,
-                HashMap::new(),
-            ));
+            let row_data = Self::build_oplog_row_data(&ns, RowType::Delete, before, HashMap::new());
+            data.push((row_data, position.clone()));
         }
         data
     }
 
+    async fn lookup_full_doc(
+        &self,
+        ns: &Option<&Bson>,
+        document_key: &Document,
+    ) -> anyhow::Result<Option<Document>> {
+        let ns = ns.unwrap().as_str().unwrap();
+        let (db, tb) = ns.split_once('.').unwrap();
+        let collection = self.mongo_client.database(db).collection::<Document>(tb);
+        Ok(collection.find_one(document_key.clone(), None).await?)
+    }
+
+    fn parse_oplog_ddl(ns: Option<&Bson>, o: Option<&Bson>) -> Option<DdlData> {
+        // ns of command entries: db_1.$cmd
+        let db = ns?.as_str()?.split('.').next()?;
+        MongoDdlParser::parse_oplog_command(db, o?.as_document()?)
+    }
+
+    fn get_oplog_ddl_db(doc: &Document) -> Option<String> {
+        if Self::get_op(doc) != "c" {
+            return None;
+        }
+        Self::parse_oplog_ddl(doc.get("ns"), doc.get("o")).map(|_| {
+            let ns = doc.get_str("ns").unwrap_or_default();
+            ns.split('.').next().unwrap_or_default().to_string()
+        })
+    }
+
+    fn build_oplog_position(ts: &Option<&Bson>) -> Position {
+        let ts = ts.unwrap().as_timestamp().unwrap();
+        Position::MongoCdc {
+            resume_token: String::new(),
+            operation_time: ts.time,
+            timestamp: Position::format_timestamp_millis(ts.time as i64 * 1000),
+        }
+    }
+
+    fn is_from_migrate(doc: &Document) -> bool {
+        matches!(doc.get("fromMigrate"), Some(Bson::Boolean(true)))
+    }
+
     fn build_oplog_row_data(
         ns: &Option<&Bson>,
-        ts: &Option<&Bson>,
         row_type: RowType,
         before: HashMap<String, ColValue>,
         after: HashMap<String, ColValue>,
-    ) -> (RowData, Position) {
-        let ts = ts.unwrap().as_timestamp().unwrap();
+    ) -> RowData {
         let ns = ns.unwrap().as_str().unwrap();
 
         // get db & tb
@@ -320,106 +622,205 @@ impl MongoCdcExtractor {
         };
         let after = if after.is_empty() { None } else { Some(after) };
 
-        // get ts for position
-        let position = Position::MongoCdc {
-            resume_token: String::new(),
-            operation_time: ts.time,
-            timestamp: Position::format_timestamp_millis(ts.time as i64 * 1000),
-        };
-        let row_data = RowData::new(db, tb, row_type, before, after);
-        (row_data, position)
+        RowData::new(db, tb, row_type, before, after)
     }
 
     async fn extract_change_stream(&mut self) -> anyhow::Result<()> {
-        let (resume_token, start_timestamp) = if self.resume_token.is_empty() {
-            (None, Some(self.parse_start_timestamp()))
+        // refer: https://www.mongodb.com/docs/manual/changeStreams/
+        // Starting in MongoDB 6.0, you can use change stream events to output the version of
+        // a document before and after changes (the document pre- and post-images).
+        let mut stage = doc! {
+            "allChangesForCluster": true,
+            "fullDocument": "updateLookup",
+            "fullDocumentBeforeChange": "whenAvailable",
+        };
+        // showExpandedEvents (6.0+) is not supported by ChangeStreamOptions of current driver,
+        // so the $changeStream stage is built by ourselves to receive create / createIndexes / dropIndexes,
+        // it is only set if ddls are needed, so that older versions still work for dmls
+        if !self.filter.filter_all_ddl() {
+            stage.insert("showExpandedEvents", true);
+        }
+        if self.resume_token.is_empty() {
+            stage.insert("startAtOperationTime", self.parse_start_timestamp());
         } else {
             let token: ResumeToken = serde_json::from_str(&self.resume_token)?;
-            (Some(token), None)
-        };
+            stage.insert("startAfter", bson::to_bson(&token)?);
+        }
+        let pipeline = vec![doc! { "$changeStream": stage }];
 
-        // refer: https://www.mongodb.com/docs/manual/changeStreams/
-        // Starting in MongoDB 6.0, you can use change stream events to output the version of
-        // a document before and after changes (the document pre- and post-images)
-        let stream_options = ChangeStreamOptions::builder()
-            .start_at_operation_time(start_timestamp)
-            .start_after(resume_token)
-            .full_document(Some(FullDocumentType::UpdateLookup))
-            .full_document_before_change(Some(FullDocumentBeforeChangeType::WhenAvailable))
-            .build();
+        let mut cursor = self
+            .mongo_client
+            .database("admin")
+            .aggregate(pipeline, None)
+            .await?;
+        while cursor.advance().await? {
+            let raw_doc: Document = cursor.deserialize_current()?;
+            self.parse_change_stream_event(raw_doc).await?;
+        }
+        bail! {Error::ExtractorError("change stream closed".into())}
+    }
 
-        let mut change_stream = self.mongo_client.watch(None, stream_options).await?;
-        loop {
-            let result = change_stream.next_if_any().await?;
-            if let Some(doc) = result {
-                let resume_token = doc.id;
-                let position = if let Some(operation_time) = doc.cluster_time {
-                    Position::MongoCdc {
-                        resume_token: json!(resume_token).to_string(),
-                        operation_time: operation_time.time,
-                        timestamp: Position::format_timestamp_millis(
-                            operation_time.time as i64 * 1000,
-                        ),
-                    }
-                } else {
-                    Position::MongoCdc {
-                        resume_token: json!(resume_token).to_string(),
-                        operation_time: 0,
-                        timestamp: String::new(),
-                    }
-                };
+    async fn parse_change_stream_event(&mut self, raw_doc: Document) -> anyhow::Result<()> {
+        let resume_token: ResumeToken = bson::from_bson(raw_doc.get("_id").cloned().unwrap())?;
+        let position = if let Ok(operation_time) = raw_doc.get_timestamp("clusterTime") {
+            Position::MongoCdc {
+                resume_token: json!(resume_token).to_string(),
+                operation_time: operation_time.time,
+                timestamp: Position::format_timestamp_millis(operation_time.time as i64 * 1000),
+            }
+        } else {
+            Position::MongoCdc {
+                resume_token: json!(resume_token).to_string(),
+                operation_time: 0,
+                timestamp: String::new(),
+            }
+        };
 
-                let (mut db, mut tb) = (String::new(), String::new());
-                if let Some(ns) = doc.ns {
-                    db = ns.db.clone();
-                    if let Some(coll) = ns.coll {
-                        tb = coll.clone();
-                    }
+        let operation_type = raw_doc.get_str("operationType").unwrap_or_default();
+        match operation_type {
+            // expanded events, refer: https://www.mongodb.com/docs/manual/reference/change-events/
+            "create" | "createIndexes" | "dropIndexes" => {
+                let (db, tb) = match raw_doc.get_document("ns") {
+                    Ok(ns) => (
+                        ns.get_str("db").unwrap_or_default().to_string(),
+                        ns.get_str("coll").unwrap_or_default().to_string(),
+                    ),
+                    Err(_) => return Ok(()),
+                };
+                let description = raw_doc
+                    .get_document("operationDescription")
+                    .cloned()
+                    .unwrap_or_default();
+                for ddl_data in Self::parse_expanded_event(operation_type, &db, &tb, description) {
+                    self.push_ddl_to_buf(ddl_data, position.clone()).await?;
                 }
+                return Ok(());
+            }
+            "insert" | "delete" | "update" | "replace" | "drop" | "rename" | "dropDatabase" => {}
+            // TODO, heartbeat
+            _ => return Ok(()),
+        }
+
+        let doc: ChangeStreamEvent<Document> = bson::from_document(raw_doc)?;
+        let (mut db, mut tb) = (String::new(), String::new());
+        if let Some(ns) = doc.ns {
+            db = ns.db.clone();
+            if let Some(coll) = ns.coll {
+                tb = coll.clone();
+            }
+        }
 
-                let mut row_type = RowType::Insert;
-                let mut before = HashMap::new();
-                let mut after = HashMap::new();
+        let mut row_type = RowType::Insert;
+        let mut before = HashMap::new();
+        let mut after = HashMap::new();
 
-                match doc.operation_type {
-                    OperationType::Insert => {
+        match doc.operation_type {
+            OperationType::Insert => {
+                after.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(doc.full_document.unwrap()),
+                );
+            }
+
+            OperationType::Delete => {
+                row_type = RowType::Delete;
+                before.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(doc.document_key.unwrap()),
+                );
+            }
+
+            OperationType::Update | OperationType::Replace => {
+                row_type = RowType::Update;
+                // the minimal update is preferred by sinkers, the full document is kept
+                // as the fallback, it is looked up for Update and may be newer than the event
+                if let Some(diff_doc) = doc
+                    .update_description
+                    .as_ref()
+                    .and_then(MongoUpdateParser::parse_update_description)
+                {
+                    if !diff_doc.is_empty() {
                         after.insert(
-                            MongoConstants::DOC.to_string(),
-                            ColValue::MongoDoc(doc.full_document.unwrap()),
+                            MongoConstants::DIFF_DOC.to_string(),
+                            ColValue::MongoDoc(diff_doc),
                         );
                     }
+                }
+                if let Some(document) = doc.full_document {
+                    after.insert(
+                        MongoConstants::DOC.to_string(),
+                        ColValue::MongoDoc(document),
+                    );
+                }
 
-                    OperationType::Delete => {
-                        row_type = RowType::Delete;
-                        before.insert(
-                            MongoConstants::DOC.to_string(),
-                            ColValue::MongoDoc(doc.document_key.unwrap()),
-                        );
-                    }
+                if after.is_empty() {
+                    return Ok(());
+                }
+                before.insert(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(doc.document_key.unwrap()),
+                );
+            }
 
-                    OperationType::Update | OperationType::Replace => {
-                        row_type = RowType::Update;
-                        if let Some(document) = doc.full_document {
-                            before.insert(
-                                MongoConstants::DOC.to_string(),
-                                ColValue::MongoDoc(doc.document_key.unwrap()),
-                            );
-                            after.insert(
-                                MongoConstants::DOC.to_string(),
-                                ColValue::MongoDoc(document),
-                            );
-                        }
-                    }
+            OperationType::Drop => {
+                let ddl_data = MongoDdlParser::drop_collection(&db, &tb);
+                return self.push_ddl_to_buf(ddl_data, position).await;
+            }
 
-                    // TODO, heartbeat and DDL
-                    _ => {
-                        continue;
-                    }
+            OperationType::Rename => {
+                if let Some(to) = doc.to {
+                    let new_tb = to.coll.unwrap_or_default();
+                    let ddl_data = MongoDdlParser::rename_collection(&db, &tb, &to.db, &new_tb);
+                    self.push_ddl_to_buf(ddl_data, position).await?;
                 }
+                return Ok(());
+            }
 
-                let row_data = RowData::new(db, tb, row_type, Some(before), Some(after));
-                self.push_row_to_buf(row_data, position).await?;
+            OperationType::DropDatabase => {
+                let ddl_data = MongoDdlParser::drop_database(&db);
+                return self.push_ddl_to_buf(ddl_data, position).await;
             }
+
+            _ => return Ok(()),
+        }
+
+        let row_data = RowData::new(db, tb, row_type, Some(before), Some(after));
+        self.push_row_to_buf(row_data, position).await
+    }
+
+    /// operationDescription of expanded events:
+    /// create: { "idIndex": { "v": 2, "key": { "_id": 1 }, "name": "_id_" }, "capped": true, ... }
+    /// createIndexes / dropIndexes: { "indexes": [{ "v": 2, "key": { "a": 1 }, "name": "a_1" }] }
+    fn parse_expanded_event(
+        operation_type: &str,
+        db: &str,
+        tb: &str,
+        mut description: Document,
+    ) -> Vec<DdlData> {
+        let mut specs = Vec::new();
+        if let Ok(indexes) = description.get_array("indexes") {
+            for index in indexes {
+                if let Bson::Document(spec) = index {
+                    specs.push(spec.clone());
+                }
+            }
+        }
+
+        match operation_type {
+            "create" => {
+                // _id index is created with the collection
+                description.remove("idIndex");
+                vec![MongoDdlParser::create_collection(db, tb, description)]
+            }
+            "createIndexes" if !specs.is_empty() => {
+                vec![MongoDdlParser::create_indexes(db, tb, specs)]
+            }
+            "dropIndexes" => specs
+                .iter()
+                .filter_map(|spec| spec.get_str("name").ok())
+                .map(|name| MongoDdlParser::drop_indexes(db, tb, name))
+                .collect(),
+            _ => Vec::new(),
         }
     }
 
@@ -435,7 +836,27 @@ impl MongoCdcExtractor {
         {
             return Ok(());
         }
-        self.base_extractor.push_row(row_data, position).await
+        // before contains only the _id
+        match self.row_filter.filter_row(row_data, false) {
+            Some(row_data) => self.base_extractor.push_row(row_data, position).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn push_ddl_to_buf(
+        &mut self,
+        ddl_data: DdlData,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        let (db, tb) = ddl_data.get_schema_tb();
+        if SYSTEM_DBS.contains(&db.as_str())
+            || tb.starts_with(SYSTEM_COLLECTION_PREFIX)
+            || self.filter.filter_ddl(&db, &tb, &ddl_data.ddl_type)
+        {
+            return Ok(());
+        }
+        log_info!("received ddl: {}", ddl_data.query);
+        self.base_extractor.push_ddl(ddl_data, position).await
     }
 
     fn parse_start_timestamp(&mut self) -> Timestamp {
@@ -490,35 +911,9 @@ impl MongoCdcExtractor {
         client: &Client,
     ) -> anyhow::Result<()> {
         let (received_resume_token, received_operation_time, received_timestamp) =
-            if let Position::MongoCdc {
-                resume_token,
-                operation_time,
-                timestamp,
-            } = &syncer.lock().unwrap().received_position
-            {
-                (
-                    resume_token.to_owned(),
-                    *operation_time,
-                    timestamp.to_owned(),
-                )
-            } else {
-                (String::new(), 0, String::new())
-            };
+            Self::get_heartbeat_position(&syncer.lock().unwrap().received_position);
         let (committed_resume_token, committed_operation_time, committed_timestamp) =
-            if let Position::MongoCdc {
-                resume_token,
-                operation_time,
-                timestamp,
-            } = &syncer.lock().unwrap().committed_position
-            {
-                (
-                    resume_token.to_owned(),
-                    *operation_time,
-                    timestamp.to_owned(),
-                )
-            } else {
-                (String::new(), 0, String::new())
-            };
+            Self::get_heartbeat_position(&syncer.lock().unwrap().committed_position);
 
         let query_doc = doc! {MongoConstants::ID: app_name };
         let update_doc = doc! {MongoConstants::SET: doc! {MongoConstants::ID: app_name,
@@ -541,4 +936,94 @@ impl MongoCdcExtractor {
         }
         Ok(())
     }
+
+    fn get_heartbeat_position(position: &Position) -> (String, u32, String) {
+        match position {
+            Position::MongoCdc {
+                resume_token,
+                operation_time,
+                timestamp,
+            } => (
+                resume_token.to_owned(),
+                *operation_time,
+                timestamp.to_owned(),
+            ),
+            // the slowest shard
+            Position::MongoShardedCdc { shards } => {
+                match shards
+                    .iter()
+                    .min_by_key(|i| (i.operation_time, i.increment))
+                {
+                    Some(shard) => (String::new(), shard.operation_time, shard.timestamp.clone()),
+                    None => (String::new(), 0, String::new()),
+                }
+            }
+            _ => (String::new(), 0, String::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::position::{MongoShardPosition, Position};
+    use mongodb::bson::Timestamp;
+
+    use super::MongoCdcExtractor;
+
+    fn new_shard_position(shard: &str, operation_time: u32, increment: u32) -> MongoShardPosition {
+        MongoShardPosition {
+            shard: shard.into(),
+            operation_time,
+            increment,
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_init_shard_positions() {
+        let shards = vec![
+            ("shard01".to_string(), "host01".to_string()),
+            ("shard02".to_string(), "host02".to_string()),
+            ("shard03".to_string(), "host03".to_string()),
+        ];
+        let start_timestamp = Timestamp {
+            time: 1728551000,
+            increment: 0,
+        };
+        let get_ts = |positions: &[MongoShardPosition]| -> Vec<(u32, u32)> {
+            positions
+                .iter()
+                .map(|i| (i.operation_time, i.increment))
+                .collect()
+        };
+
+        // not resumed, all shards start right before the start timestamp
+        let positions =
+            MongoCdcExtractor::init_shard_positions(&shards, &Position::None, start_timestamp);
+        assert_eq!(get_ts(&positions), vec![(1728550999, u32::MAX); 3]);
+
+        // shard02 logged before any entry taken, shard03 added after the position was logged,
+        // both start right before the earliest resumed shard position
+        let resumed_position = Position::MongoShardedCdc {
+            shards: vec![
+                new_shard_position("shard01", 1728551829, 3),
+                new_shard_position("shard02", 0, 0),
+            ],
+        };
+        let positions =
+            MongoCdcExtractor::init_shard_positions(&shards, &resumed_position, start_timestamp);
+        assert_eq!(
+            get_ts(&positions),
+            vec![(1728551829, 3), (1728551829, 2), (1728551829, 2)]
+        );
+        assert_eq!(positions[2].shard, "shard03");
+
+        // no shard really resumed
+        let resumed_position = Position::MongoShardedCdc {
+            shards: vec![new_shard_position("shard01", 0, 0)],
+        };
+        let positions =
+            MongoCdcExtractor::init_shard_positions(&shards, &resumed_position, start_timestamp);
+        assert_eq!(get_ts(&positions), vec![(1728550999, u32::MAX); 3]);
+    }
 }
@@ -1,29 +1,41 @@
+use std::{cmp, collections::HashMap, sync::Arc};
+
 use async_trait::async_trait;
 use dt_common::meta::{
     col_value::ColValue,
-    mongo::{mongo_constant::MongoConstants, mongo_key::MongoKey},
+    dt_data::{DtData, DtItem},
+    dt_queue::DtQueue,
+    mongo::{mongo_constant::MongoConstants, mongo_id_range::MongoIdRange, mongo_key::MongoKey},
     position::Position,
     row_data::RowData,
     row_type::RowType,
 };
-use dt_common::{config::config_enums::DbType, log_info};
+use dt_common::{config::config_enums::DbType, log_debug, log_info};
 use mongodb::{
-    bson::{doc, oid::ObjectId, Bson, Document},
-    options::FindOptions,
-    Client,
+    bson::{doc, Bson, Document},
+    options::{AggregateOptions, FindOptions},
+    Client, Collection,
 };
-use std::collections::HashMap;
+use serde_json::json;
+use tokio::task::JoinHandle;
 
 use crate::{
     extractor::{base_extractor::BaseExtractor, resumer::snapshot_resumer::SnapshotResumer},
+    rdb_router::RdbRouter,
     Extractor,
 };
 
+// sample more ids than ranges so the ranges are more even
+const SAMPLES_PER_RANGE: usize = 10;
+const MAX_RANGE_COUNT: usize = 1000;
+
 pub struct MongoSnapshotExtractor {
     pub base_extractor: BaseExtractor,
     pub resumer: SnapshotResumer,
     pub db: String,
     pub tb: String,
+    pub parallel_size: usize,
+    pub batch_size: usize,
     pub mongo_client: Client,
 }
 
@@ -31,9 +43,11 @@ pub struct MongoSnapshotExtractor {
 impl Extractor for MongoSnapshotExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
         log_info!(
-            "MongoSnapshotExtractor starts, schema: {}, tb: {}",
+            "MongoSnapshotExtractor starts, schema: {}, tb: {}, batch_size: {}, parallel_size: {}",
             self.db,
-            self.tb
+            self.tb,
+            self.batch_size,
+            self.parallel_size
         );
         self.extract_internal().await?;
         self.base_extractor.wait_task_finish().await
@@ -47,75 +61,226 @@ impl Extractor for MongoSnapshotExtractor {
 
 impl MongoSnapshotExtractor {
     pub async fn extract_internal(&mut self) -> anyhow::Result<()> {
-        log_info!("start extracting data from {}.{}", self.db, self.tb);
-
-        let filter = if let Some(resume_value) =
+        let parallel_extract = self.parallel_size > 1;
+        let start_id = if let Some(resume_value) =
             self.resumer
-                .get_resume_value(&self.db, &self.tb, MongoConstants::ID, false)
+                .get_resume_value(&self.db, &self.tb, MongoConstants::ID, parallel_extract)
         {
-            let start_id = ObjectId::parse_str(resume_value)?;
-            log_info!("start_id: {}", start_id.to_string());
-            Some(doc! {MongoConstants::ID: {"$gt": start_id}})
+            Some(MongoIdRange::from_resume_value(&resume_value)?)
         } else {
             None
         };
 
-        // order by asc
-        let find_options = FindOptions::builder()
-            .sort(doc! {MongoConstants::ID: 1})
-            .build();
+        log_info!(
+            "start extracting data from {}.{}, start_id: {}",
+            self.db,
+            self.tb,
+            start_id
+                .as_ref()
+                .map_or(String::new(), MongoIdRange::to_resume_value)
+        );
 
-        let collection = self
-            .mongo_client
-            .database(&self.db)
-            .collection::<Document>(&self.tb);
-        let mut cursor = collection.find(filter, find_options).await?;
+        let extracted_count = if parallel_extract {
+            log_info!("parallel extracting, parallel_size: {}", self.parallel_size);
+            self.parallel_extract(start_id).await?
+        } else {
+            self.serial_extract(start_id).await?
+        };
+
+        log_info!(
+            "end extracting data from {}.{}, all count: {}",
+            self.db,
+            self.tb,
+            extracted_count
+        );
+        Ok(())
+    }
+
+    async fn serial_extract(&mut self, start_id: Option<Bson>) -> anyhow::Result<usize> {
+        let range = MongoIdRange {
+            start: start_id,
+            end: None,
+        };
+        let mut cursor = self
+            .collection()
+            .find(range.to_filter(), Self::build_find_options())
+            .await?;
+
+        let mut extracted_count = 0;
         while cursor.advance().await? {
             let doc = cursor.deserialize_current()?;
-            let object_id = Self::get_object_id(&doc);
+            let position = Self::build_position(&self.db, &self.tb, &doc);
+            let row_data = Self::build_row_data(&self.db, &self.tb, doc);
+            self.base_extractor.push_row(row_data, position).await?;
+            extracted_count += 1;
+        }
+        Ok(extracted_count)
+    }
 
-            let mut after = HashMap::new();
-            let id: String = if let Some(key) = MongoKey::from_doc(&doc) {
-                key.to_string()
-            } else {
-                String::new()
-            };
-            after.insert(MongoConstants::ID.to_string(), ColValue::String(id));
-            after.insert(MongoConstants::DOC.to_string(), ColValue::MongoDoc(doc));
-            let row_data = RowData::new(
-                self.db.clone(),
-                self.tb.clone(),
-                RowType::Insert,
-                None,
-                Some(after),
-            );
+    async fn parallel_extract(&mut self, start_id: Option<Bson>) -> anyhow::Result<usize> {
+        let ranges = self.split_ranges(start_id).await?;
+        log_info!(
+            "{}.{} is split into {} _id ranges",
+            self.db,
+            self.tb,
+            ranges.len()
+        );
+
+        let router = Arc::new(self.base_extractor.router.clone());
+        let mut extracted_count = 0;
+        for sub_ranges in ranges.chunks(self.parallel_size) {
+            // send a checkpoint position before each loop, all data before it has been extracted
+            self.send_checkpoint_position(&sub_ranges[0].start).await?;
+
+            let mut futures = Vec::new();
+            for range in sub_ranges {
+                let buffer = self.base_extractor.buffer.clone();
+                let router = router.clone();
+                let collection = self.collection();
+                let db = self.db.clone();
+                let tb = self.tb.clone();
+                let range = range.clone();
+
+                let future: JoinHandle<anyhow::Result<usize>> = tokio::spawn(async move {
+                    Self::extract_range(&buffer, &router, &collection, &db, &tb, &range).await
+                });
+                futures.push(future);
+            }
+
+            for future in futures {
+                extracted_count += future.await??;
+            }
+        }
+        Ok(extracted_count)
+    }
+
+    /// split (start_id, +∞) into ranges by sampled _ids, each range has about batch_size docs
+    async fn split_ranges(&self, start_id: Option<Bson>) -> anyhow::Result<Vec<MongoIdRange>> {
+        let collection = self.collection();
+        let doc_count = collection.estimated_document_count(None).await? as usize;
+        if doc_count <= self.batch_size {
+            return Ok(MongoIdRange::split(start_id, &[], 1));
+        }
+        let range_count = cmp::min(
+            cmp::max(doc_count / cmp::max(self.batch_size, 1), self.parallel_size),
+            MAX_RANGE_COUNT,
+        );
+
+        let mut pipeline = Vec::new();
+        let start_range = MongoIdRange {
+            start: start_id.clone(),
+            end: None,
+        };
+        if let Some(filter) = start_range.to_filter() {
+            pipeline.push(doc! { "$match": filter });
+        }
+        pipeline.push(doc! { "$sample": { "size": (range_count * SAMPLES_PER_RANGE) as i64 } });
+        pipeline.push(doc! { "$project": { MongoConstants::ID: 1 } });
+        pipeline.push(doc! { "$sort": { MongoConstants::ID: 1 } });
+
+        let options = AggregateOptions::builder().allow_disk_use(true).build();
+        let mut cursor = collection.aggregate(pipeline, options).await?;
+        let mut sorted_ids = Vec::new();
+        while cursor.advance().await? {
+            let doc = cursor.deserialize_current()?;
+            if let Some(id) = doc.get(MongoConstants::ID) {
+                sorted_ids.push(id.clone());
+            }
+        }
+        Ok(MongoIdRange::split(start_id, &sorted_ids, range_count))
+    }
+
+    async fn extract_range(
+        buffer: &Arc<DtQueue>,
+        router: &Arc<RdbRouter>,
+        collection: &Collection<Document>,
+        db: &str,
+        tb: &str,
+        range: &MongoIdRange,
+    ) -> anyhow::Result<usize> {
+        let mut cursor = collection
+            .find(range.to_filter(), Self::build_find_options())
+            .await?;
+
+        let mut extracted_count = 0;
+        while cursor.advance().await? {
+            let doc = cursor.deserialize_current()?;
+            let position = Self::build_position(db, tb, &doc);
+            let row_data = Self::build_row_data(db, tb, doc);
+            Self::push_row(buffer, router, row_data, position).await?;
+            extracted_count += 1;
+        }
+        Ok(extracted_count)
+    }
+
+    async fn push_row(
+        buffer: &Arc<DtQueue>,
+        router: &Arc<RdbRouter>,
+        row_data: RowData,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        let row_data = router.route_row(row_data);
+        let dt_data = DtData::Dml { row_data };
+        let item = DtItem {
+            dt_data,
+            position,
+            data_origin_node: String::new(),
+        };
+        log_debug!("extracted item: {}", json!(item));
+        buffer.push(item).await
+    }
+
+    async fn send_checkpoint_position(&mut self, id: &Option<Bson>) -> anyhow::Result<()> {
+        if let Some(id) = id {
             let position = Position::RdbSnapshot {
                 db_type: DbType::Mongo.to_string(),
                 schema: self.db.clone(),
                 tb: self.tb.clone(),
                 order_col: MongoConstants::ID.into(),
-                value: object_id,
+                value: MongoIdRange::to_resume_value(id),
             };
-
-            self.base_extractor.push_row(row_data, position).await?;
+            let commit = DtData::Commit { xid: String::new() };
+            self.base_extractor.push_dt_data(commit, position).await?;
         }
-
-        log_info!(
-            "end extracting data from {}.{}, all count: {}",
-            self.db,
-            self.tb,
-            self.base_extractor.monitor.counters.record_count
-        );
         Ok(())
     }
 
-    fn get_object_id(doc: &Document) -> String {
-        if let Some(id) = doc.get(MongoConstants::ID) {
-            match id {
-                Bson::ObjectId(v) => return v.to_string(),
-                _ => return String::new(),
-            }
+    fn build_row_data(db: &str, tb: &str, doc: Document) -> RowData {
+        let mut after = HashMap::new();
+        let id: String = if let Some(key) = MongoKey::from_doc(&doc) {
+            key.to_string()
+        } else {
+            String::new()
+        };
+        after.insert(MongoConstants::ID.to_string(), ColValue::String(id));
+        after.insert(MongoConstants::DOC.to_string(), ColValue::MongoDoc(doc));
+        RowData::new(db.into(), tb.into(), RowType::Insert, None, Some(after))
+    }
+
+    fn build_position(db: &str, tb: &str, doc: &Document) -> Position {
+        // _id of any bson type is resumable since the value is stored as extended json
+        let value = doc
+            .get(MongoConstants::ID)
+            .map_or(String::new(), MongoIdRange::to_resume_value);
+        Position::RdbSnapshot {
+            db_type: DbType::Mongo.to_string(),
+            schema: db.into(),
+            tb: tb.into(),
+            order_col: MongoConstants::ID.into(),
+            value,
         }
-        String::new()
+    }
+
+    fn build_find_options() -> FindOptions {
+        // order by asc
+        FindOptions::builder()
+            .sort(doc! {MongoConstants::ID: 1})
+            .build()
+    }
+
+    fn collection(&self) -> Collection<Document> {
+        self.mongo_client
+            .database(&self.db)
+            .collection::<Document>(&self.tb)
     }
 }
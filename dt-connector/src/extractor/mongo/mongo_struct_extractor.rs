@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::DbType,
+    log_info,
+    meta::{
+        dt_data::DtData,
+        mongo::mongo_row_converter::MongoRowConverter,
+        position::Position,
+        struct_meta::{
+            statement::{
+                mysql_create_database_statement::MysqlCreateDatabaseStatement,
+                pg_create_schema_statement::PgCreateSchemaStatement,
+                struct_statement::StructStatement,
+            },
+            struct_data::StructData,
+            structure::{database::Database, schema::Schema},
+        },
+    },
+    rdb_filter::RdbFilter,
+};
+use mongodb::{
+    bson::{doc, Document},
+    Client,
+};
+
+use crate::{
+    extractor::base_extractor::BaseExtractor,
+    meta_fetcher::mongo::mongo_struct_fetcher::MongoStructFetcher, Extractor,
+};
+
+pub struct MongoStructExtractor {
+    pub base_extractor: BaseExtractor,
+    pub mongo_client: Client,
+    pub db: String,
+    pub filter: RdbFilter,
+    // set if [mongo_mapping] is configured, collections are migrated as tables of the sinker
+    pub row_converter: Option<MongoRowConverter>,
+    pub sinker_db_type: DbType,
+    pub sample_size: usize,
+}
+
+#[async_trait]
+impl Extractor for MongoStructExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!("MongoStructExtractor starts, schema: {}", self.db);
+        self.extract_internal().await?;
+        self.base_extractor.wait_task_finish().await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.mongo_client.clone().shutdown().await;
+        Ok(())
+    }
+}
+
+impl MongoStructExtractor {
+    pub async fn extract_internal(&mut self) -> anyhow::Result<()> {
+        let mut fetcher = MongoStructFetcher {
+            mongo_client: self.mongo_client.clone(),
+            db: self.db.clone(),
+            filter: Some(self.filter.to_owned()),
+        };
+
+        let statements = fetcher.get_create_collection_statements("").await?;
+        if self.row_converter.is_some() {
+            let tbs = statements
+                .into_iter()
+                .filter(|i| !i.is_view())
+                .map(|i| i.tb)
+                .collect();
+            return self.extract_tables(tbs).await;
+        }
+
+        for statement in statements {
+            self.push_dt_data(StructStatement::MongoCreateCollection(statement))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// infer tables from sampled documents, the table names are routed before
+    /// flattening since [mongo_mapping] refers to the routed names
+    async fn extract_tables(&mut self, tbs: Vec<String>) -> anyhow::Result<()> {
+        let statement = match self.sinker_db_type {
+            DbType::Pg => StructStatement::PgCreateSchema(PgCreateSchemaStatement {
+                schema: Schema {
+                    name: self.db.clone(),
+                },
+            }),
+            _ => StructStatement::MysqlCreateDatabase(MysqlCreateDatabaseStatement {
+                database: Database {
+                    name: self.db.clone(),
+                    ..Default::default()
+                },
+            }),
+        };
+        self.push_dt_data(statement).await?;
+
+        for tb in tbs.iter() {
+            let docs = self.sample_docs(tb).await?;
+            let (dst_db, dst_tb) = self.base_extractor.router.get_tb_map(&self.db, tb);
+            log_info!(
+                "{}.{} is migrated as {}.{}, sampled documents: {}",
+                self.db,
+                tb,
+                dst_db,
+                dst_tb,
+                docs.len()
+            );
+
+            let statements = self
+                .row_converter
+                .as_ref()
+                .unwrap()
+                .build_create_table_statements(&dst_db, &dst_tb, &docs, &self.sinker_db_type)?;
+            for statement in statements {
+                let struct_data = StructData {
+                    schema: dst_db.clone(),
+                    statement,
+                };
+                self.base_extractor
+                    .push_dt_data(DtData::Struct { struct_data }, Position::None)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn sample_docs(&self, tb: &str) -> anyhow::Result<Vec<Document>> {
+        let pipeline = vec![doc! { "$sample": { "size": self.sample_size as i64 } }];
+        let mut cursor = self
+            .mongo_client
+            .database(&self.db)
+            .collection::<Document>(tb)
+            .aggregate(pipeline, None)
+            .await?;
+
+        let mut docs = Vec::new();
+        while cursor.advance().await? {
+            docs.push(cursor.deserialize_current()?);
+        }
+        Ok(docs)
+    }
+
+    pub async fn push_dt_data(&mut self, statement: StructStatement) -> anyhow::Result<()> {
+        let struct_data = StructData {
+            schema: self.db.clone(),
+            statement,
+        };
+        self.base_extractor.push_struct(struct_data).await
+    }
+}
@@ -31,13 +31,14 @@ use mysql_binlog_connector_rust::{
 
 use dt_common::{
     config::config_enums::DbType, error::Error, log_error, log_info, rdb_filter::RdbFilter,
-    utils::time_util::TimeUtil,
+    row_filter::RowFilter, utils::time_util::TimeUtil,
 };
 
 use crate::{
     close_conn_pool,
     extractor::{
-        base_extractor::BaseExtractor, mysql::binlog_util::BinlogUtil,
+        base_extractor::BaseExtractor,
+        mysql::{binlog_util::BinlogUtil, online_ddl_util::OnlineDdlTracker},
         resumer::cdc_resumer::CdcResumer,
     },
     Extractor,
@@ -48,6 +49,7 @@ pub struct MysqlCdcExtractor {
     pub meta_manager: MysqlMetaManager,
     pub conn_pool: Pool<MySql>,
     pub filter: RdbFilter,
+    pub row_filter: RowFilter,
     pub url: String,
     pub binlog_filename: String,
     pub binlog_position: u32,
@@ -60,6 +62,7 @@ pub struct MysqlCdcExtractor {
     pub heartbeat_tb: String,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
+    pub online_ddl_tracker: OnlineDdlTracker,
 }
 
 struct Context {
@@ -297,6 +300,7 @@ impl MysqlCdcExtractor {
                 self.base_extractor
                     .push_dt_data(commit, position.clone())
                     .await?;
+                self.push_online_ddl_state(&position).await?;
             }
 
             _ => {}
@@ -310,7 +314,10 @@ impl MysqlCdcExtractor {
         row_data: RowData,
         position: Position,
     ) -> anyhow::Result<()> {
-        self.base_extractor.push_row(row_data, position).await
+        match self.row_filter.filter_row(row_data, true) {
+            Some(row_data) => self.base_extractor.push_row(row_data, position).await,
+            None => Ok(()),
+        }
     }
 
     async fn parse_row_data(
@@ -380,16 +387,57 @@ impl MysqlCdcExtractor {
                 let (db, tb) = sub_ddl_data.get_schema_tb();
                 // invalidate metadata cache
                 self.meta_manager.invalidate_cache(&db, &tb);
-                if !self.filter.filter_ddl(&db, &tb, &sub_ddl_data.ddl_type) {
-                    self.base_extractor
-                        .push_ddl(sub_ddl_data.clone(), position.clone())
-                        .await?;
+
+                let origin_exists = self.gh_ost_origin_exists(&db, &tb).await;
+                for sub_ddl_data in self.online_ddl_tracker.on_ddl(sub_ddl_data, origin_exists) {
+                    let (db, tb) = sub_ddl_data.get_schema_tb();
+                    if !self.filter.filter_ddl(&db, &tb, &sub_ddl_data.ddl_type) {
+                        self.base_extractor
+                            .push_ddl(sub_ddl_data, position.clone())
+                            .await?;
+                    }
                 }
             }
 
             if let Some(meta_center) = &mut self.meta_manager.meta_center {
                 meta_center.sync_from_ddl(&ddl_data).await?;
             }
+            self.push_online_ddl_state(&position).await?;
+        }
+        Ok(())
+    }
+
+    /// whether the original table of a table named as gh-ost shadow table exists
+    async fn gh_ost_origin_exists(&mut self, db: &str, tb: &str) -> bool {
+        match OnlineDdlTracker::parse_gh_ost_tb(tb) {
+            Some((origin_tb, _)) => self.meta_manager.get_tb_meta(db, &origin_tb).await.is_ok(),
+            None => false,
+        }
+    }
+
+    /// the state of online ddl tracker is persisted with the checkpoint of a heartbeat pushed
+    /// after the ddl, it is delayed while the checkpoint can not move: before start_time,
+    /// or shard ddls are held
+    async fn push_online_ddl_state(&mut self, position: &Position) -> anyhow::Result<()> {
+        if !self.base_extractor.time_filter.started
+            || self
+                .base_extractor
+                .shard_ddl_coordinator
+                .as_ref()
+                .is_some_and(|i| i.is_holding())
+        {
+            return Ok(());
+        }
+
+        if let Some(state) = self.online_ddl_tracker.take_state() {
+            self.syncer
+                .lock()
+                .unwrap()
+                .online_ddl_states
+                .push_back((position.clone(), state));
+            self.base_extractor
+                .push_dt_data(DtData::Heartbeat {}, position.clone())
+                .await?;
         }
         Ok(())
     }
@@ -397,6 +445,10 @@ impl MysqlCdcExtractor {
     fn filter_event(&mut self, table_map_event: &TableMapEvent, row_type: RowType) -> bool {
         let db = &table_map_event.database_name;
         let tb = &table_map_event.table_name;
+        if self.online_ddl_tracker.is_shadow_tb(db, tb) {
+            return true;
+        }
+
         let filtered = self.filter.filter_event(db, tb, &row_type);
         if filtered {
             return !self.base_extractor.is_data_marker_info(db, tb);
@@ -0,0 +1,531 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Context;
+use dt_common::{
+    config::config_enums::OnlineDdlPolicyEnum,
+    log_info, log_warn,
+    meta::ddl_meta::{ddl_data::DdlData, ddl_statement::DdlStatement, ddl_type::DdlType},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShadowTableKind {
+    // gh-ost: _tb_gho, pt-osc: _tb_new, a copy of the original table with the new schema
+    Ghost,
+    // gh-ost: _tb_ghc, used by gh-ost to record its own progress
+    Changelog,
+    // gh-ost: _tb_del, pt-osc: _tb_old, the original table renamed during cut-over
+    Old,
+}
+
+const GH_OST_SUFFIXES: [(&str, ShadowTableKind); 3] = [
+    ("_gho", ShadowTableKind::Ghost),
+    ("_ghc", ShadowTableKind::Changelog),
+    ("_del", ShadowTableKind::Old),
+];
+
+const PT_OSC_NEW_SUFFIX: &str = "_new";
+const PT_OSC_OLD_SUFFIX: &str = "_old";
+
+type SchemaTb = (String, String);
+
+/// state of the tracker persisted with checkpoints
+#[derive(Default, Serialize, Deserialize)]
+struct TrackerState {
+    // (db, shadow_tb, origin_tb, kind)
+    shadow_tbs: Vec<(String, String, String, ShadowTableKind)>,
+    // (db, origin_tb, pending ddls)
+    pending_ddls: Vec<(String, String, Vec<DdlData>)>,
+}
+
+/// tracks shadow tables created by gh-ost / pt-online-schema-change in binlog.
+///
+/// - gh-ost tables are named _tb_gho, _tb_ghc, _tb_del, they are tracked only if the original
+///   table exists, since the names may also be taken by normal tables.
+/// - pt-osc tables are recognized by the statements of pt-osc: CREATE TABLE `_tb_new` LIKE `tb`,
+///   and the cut-over: RENAME TABLE `tb` TO `_tb_old`, `_tb_new` TO `tb`, since _tb_new / _tb_old
+///   may also be names of normal tables.
+///
+/// the tracked tables and the pending ddls are persisted with the checkpoint, so an online ddl
+/// in progress survives restarts.
+pub struct OnlineDdlTracker {
+    policy: OnlineDdlPolicyEnum,
+    // (db, shadow_tb) -> (origin_tb, kind)
+    shadow_tbs: HashMap<SchemaTb, (String, ShadowTableKind)>,
+    // (db, origin_tb) -> alters on the ghost table, applied to the original table at cut-over
+    pending_ddls: HashMap<SchemaTb, Vec<DdlData>>,
+    dirty: bool,
+}
+
+impl OnlineDdlTracker {
+    /// state: the state persisted with the checkpoint,
+    /// src_tbs: tables of source, gh-ost shadow tables in them are tracked if there is no state
+    pub fn new(
+        policy: OnlineDdlPolicyEnum,
+        state: &str,
+        src_tbs: &[SchemaTb],
+    ) -> anyhow::Result<Self> {
+        let mut me = Self {
+            policy,
+            shadow_tbs: HashMap::new(),
+            pending_ddls: HashMap::new(),
+            dirty: false,
+        };
+        if me.policy == OnlineDdlPolicyEnum::None {
+            return Ok(me);
+        }
+
+        if !state.is_empty() {
+            let state: TrackerState = serde_json::from_str(state)
+                .with_context(|| format!("invalid online ddl state: [{}]", state))?;
+            for (db, shadow_tb, origin_tb, kind) in state.shadow_tbs {
+                me.shadow_tbs.insert((db, shadow_tb), (origin_tb, kind));
+            }
+            for (db, origin_tb, ddls) in state.pending_ddls {
+                me.pending_ddls.insert((db, origin_tb), ddls);
+            }
+        } else {
+            // online ddls started before the task, alters on ghost tables before it are unknown
+            let src_tbs: HashSet<&SchemaTb> = src_tbs.iter().collect();
+            for (db, tb) in src_tbs.iter() {
+                if let Some((origin_tb, kind)) = Self::parse_gh_ost_tb(tb) {
+                    if src_tbs.contains(&(db.clone(), origin_tb.clone())) {
+                        log_warn!(
+                            "online ddl in progress before task starts, shadow table: {}.{}",
+                            db,
+                            tb
+                        );
+                        me.add_shadow_tb(db.clone(), tb.clone(), origin_tb, kind);
+                    }
+                }
+            }
+        }
+        Ok(me)
+    }
+
+    /// dml of shadow tables are dropped: rows of the ghost table are copied from / synced with
+    /// the original table, whose dml are synced already
+    pub fn is_shadow_tb(&self, db: &str, tb: &str) -> bool {
+        self.policy != OnlineDdlPolicyEnum::None && self.get_shadow_tb(db, tb).is_some()
+    }
+
+    /// returns the state if it changed since the last call
+    pub fn take_state(&mut self) -> Option<String> {
+        if !self.dirty {
+            return None;
+        }
+        self.dirty = false;
+
+        let mut state = TrackerState::default();
+        for ((db, shadow_tb), (origin_tb, kind)) in self.shadow_tbs.iter() {
+            state.shadow_tbs.push((
+                db.clone(),
+                shadow_tb.clone(),
+                origin_tb.clone(),
+                kind.clone(),
+            ));
+        }
+        for ((db, origin_tb), ddls) in self.pending_ddls.iter() {
+            state
+                .pending_ddls
+                .push((db.clone(), origin_tb.clone(), ddls.clone()));
+        }
+        state
+            .shadow_tbs
+            .sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        state
+            .pending_ddls
+            .sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        Some(json!(state).to_string())
+    }
+
+    /// returns the ddls which should be pushed,
+    /// origin_exists: whether the original table of a table named as gh-ost shadow table exists
+    pub fn on_ddl(&mut self, mut ddl_data: DdlData, origin_exists: bool) -> Vec<DdlData> {
+        if self.policy == OnlineDdlPolicyEnum::None {
+            return vec![ddl_data];
+        }
+
+        let (db, tb) = ddl_data.get_schema_tb();
+        match &ddl_data.statement {
+            DdlStatement::MysqlCreateTable(s) => {
+                if let Some(origin_tb) = Self::parse_pt_osc_create_like(&tb, &s.unparsed) {
+                    log_info!("online ddl ghost table created: {}", ddl_data.query);
+                    self.add_shadow_tb(db, tb, origin_tb, ShadowTableKind::Ghost);
+                    return Vec::new();
+                }
+
+                if let Some((origin_tb, kind)) = Self::parse_gh_ost_tb(&tb) {
+                    // CREATE TABLE `_tb_gho` LIKE `tb` implies tb exists
+                    if origin_exists
+                        || Self::parse_like_tb(&s.unparsed).is_some_and(|i| i == origin_tb)
+                    {
+                        log_info!("online ddl shadow table created: {}", ddl_data.query);
+                        self.add_shadow_tb(db, tb, origin_tb, kind);
+                        return Vec::new();
+                    }
+                }
+            }
+
+            DdlStatement::RenameTable(_) | DdlStatement::MysqlAlterTableRename(_) => {
+                let (new_db, new_tb) = ddl_data.get_rename_to_schema_tb();
+                return self.on_rename(db, tb, new_db, new_tb, ddl_data);
+            }
+
+            _ => {}
+        }
+
+        match self.get_shadow_tb(&db, &tb) {
+            // the alter on ghost table is what the user actually runs on the original table
+            Some((origin_tb, ShadowTableKind::Ghost))
+                if ddl_data.ddl_type == DdlType::AlterTable
+                    && self.policy == OnlineDdlPolicyEnum::Map =>
+            {
+                ddl_data.statement.route(db.clone(), origin_tb.clone());
+                self.pending_ddls
+                    .entry((db, origin_tb))
+                    .or_default()
+                    .push(ddl_data);
+                self.dirty = true;
+                Vec::new()
+            }
+
+            Some((origin_tb, kind)) => {
+                if ddl_data.ddl_type == DdlType::DropTable {
+                    self.shadow_tbs.remove(&(db.clone(), tb));
+                    // online ddl aborted
+                    if kind == ShadowTableKind::Ghost {
+                        self.pending_ddls.remove(&(db, origin_tb));
+                    }
+                    self.dirty = true;
+                }
+                Vec::new()
+            }
+
+            None => vec![ddl_data],
+        }
+    }
+
+    /// cut-over: RENAME TABLE tb TO _tb_del, _tb_gho TO tb
+    fn on_rename(
+        &mut self,
+        db: String,
+        tb: String,
+        new_db: String,
+        new_tb: String,
+        ddl_data: DdlData,
+    ) -> Vec<DdlData> {
+        // the original table renamed to _tb_old by pt-osc, or to _tb_del by gh-ost
+        let is_old_tb = Self::parse_pt_osc_tb(&new_tb, PT_OSC_OLD_SUFFIX).is_some_and(|i| i == tb)
+            || Self::parse_gh_ost_tb(&new_tb) == Some((tb.clone(), ShadowTableKind::Old));
+        if db == new_db
+            && is_old_tb
+            && self
+                .shadow_tbs
+                .iter()
+                .any(|((ghost_db, _), (origin_tb, kind))| {
+                    *ghost_db == db && *origin_tb == tb && *kind == ShadowTableKind::Ghost
+                })
+        {
+            self.add_shadow_tb(new_db, new_tb, tb, ShadowTableKind::Old);
+            return Vec::new();
+        }
+
+        match self.get_shadow_tb(&db, &tb) {
+            Some((origin_tb, ShadowTableKind::Ghost)) if db == new_db && origin_tb == new_tb => {
+                self.shadow_tbs.remove(&(db.clone(), tb));
+                let ddls = self
+                    .pending_ddls
+                    .remove(&(db, origin_tb))
+                    .unwrap_or_default();
+                self.dirty = true;
+                log_info!(
+                    "online ddl cut-over: {}, pending ddls: {:?}",
+                    ddl_data.query,
+                    ddls.iter().map(|i| i.to_sql()).collect::<Vec<String>>()
+                );
+                ddls
+            }
+
+            Some(_) => Vec::new(),
+
+            None if self.get_shadow_tb(&new_db, &new_tb).is_some() => {
+                log_info!("online ddl cut-over ignored: {}", ddl_data.query);
+                Vec::new()
+            }
+
+            None => vec![ddl_data],
+        }
+    }
+
+    fn add_shadow_tb(&mut self, db: String, tb: String, origin_tb: String, kind: ShadowTableKind) {
+        self.shadow_tbs.insert((db, tb), (origin_tb, kind));
+        self.dirty = true;
+    }
+
+    fn get_shadow_tb(&self, db: &str, tb: &str) -> Option<(String, ShadowTableKind)> {
+        self.shadow_tbs
+            .get(&(db.to_string(), tb.to_string()))
+            .cloned()
+    }
+
+    /// gh-ost: _tb_gho, _tb_ghc, _tb_del, returns the original table and the shadow table kind
+    pub fn parse_gh_ost_tb(tb: &str) -> Option<(String, ShadowTableKind)> {
+        let name = tb.strip_prefix('_')?;
+        for (suffix, kind) in GH_OST_SUFFIXES.iter() {
+            if let Some(origin_tb) = name.strip_suffix(suffix) {
+                // gh-ost with --timestamp-old-table: _tb_20240102030405_del
+                let origin_tb = if *kind == ShadowTableKind::Old {
+                    Self::strip_timestamp(origin_tb)
+                } else {
+                    origin_tb
+                };
+
+                if origin_tb.is_empty() {
+                    return None;
+                }
+                return Some((origin_tb.to_string(), kind.clone()));
+            }
+        }
+        None
+    }
+
+    /// pt-osc: _tb_new / _tb_old, more underscores are prefixed if the name is taken
+    fn parse_pt_osc_tb<'a>(tb: &'a str, suffix: &str) -> Option<&'a str> {
+        let origin_tb = tb.strip_prefix('_')?.strip_suffix(suffix)?;
+        if origin_tb.is_empty() {
+            return None;
+        }
+        Some(origin_tb)
+    }
+
+    /// pt-osc: CREATE TABLE `db`.`_tb_new` LIKE `db`.`tb`, returns the original table
+    fn parse_pt_osc_create_like(tb: &str, unparsed: &str) -> Option<String> {
+        let like_tb = Self::parse_like_tb(unparsed)?;
+        // __tb_new if _tb_new is taken
+        let mut name = Self::parse_pt_osc_tb(tb, PT_OSC_NEW_SUFFIX)?;
+        loop {
+            if name == like_tb {
+                return Some(like_tb.to_string());
+            }
+            name = name.strip_prefix('_')?;
+        }
+    }
+
+    /// the unparsed part of CREATE TABLE `tb_1` LIKE `db`.`tb`, returns tb
+    fn parse_like_tb(unparsed: &str) -> Option<&str> {
+        let unparsed = unparsed
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')');
+        let (keyword, like_tb) = unparsed.trim().split_at_checked(4)?;
+        if !keyword.eq_ignore_ascii_case("like") {
+            return None;
+        }
+
+        let like_tb = like_tb.trim();
+        let like_tb = like_tb.rsplit_once('.').map_or(like_tb, |(_, i)| i);
+        Some(like_tb.trim_matches('`'))
+    }
+
+    fn strip_timestamp(tb: &str) -> &str {
+        if let Some((origin_tb, timestamp)) = tb.rsplit_once('_') {
+            if timestamp.len() == 14 && timestamp.chars().all(|c| c.is_ascii_digit()) {
+                return origin_tb;
+            }
+        }
+        tb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::{config::config_enums::DbType, meta::ddl_meta::ddl_parser::DdlParser};
+
+    use super::*;
+
+    fn on_ddl(tracker: &mut OnlineDdlTracker, sql: &str) -> Vec<String> {
+        on_ddl_with_origin(tracker, sql, true)
+    }
+
+    fn on_ddl_with_origin(
+        tracker: &mut OnlineDdlTracker,
+        sql: &str,
+        origin_exists: bool,
+    ) -> Vec<String> {
+        let mut res = Vec::new();
+        let ddl_data = DdlParser::new(DbType::Mysql).parse(sql).unwrap();
+        for sub_ddl_data in ddl_data.split_to_multi() {
+            res.extend(
+                tracker
+                    .on_ddl(sub_ddl_data, origin_exists)
+                    .iter()
+                    .map(|i| i.to_sql()),
+            );
+        }
+        res
+    }
+
+    fn new_tracker(policy: OnlineDdlPolicyEnum) -> OnlineDdlTracker {
+        OnlineDdlTracker::new(policy, "", &[]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_gh_ost_tb() {
+        let cases = [
+            ("_tb_1_gho", Some(("tb_1", ShadowTableKind::Ghost))),
+            ("_tb_1_ghc", Some(("tb_1", ShadowTableKind::Changelog))),
+            ("_tb_1_del", Some(("tb_1", ShadowTableKind::Old))),
+            (
+                "_tb_1_20240102030405_del",
+                Some(("tb_1", ShadowTableKind::Old)),
+            ),
+            ("_tb_1_new", None),
+            ("_tb_1_old", None),
+            ("tb_1_gho", None),
+            ("_tb_1", None),
+            ("_gho", None),
+        ];
+
+        for (tb, expected) in cases {
+            let expected = expected.map(|(origin_tb, kind)| (origin_tb.to_string(), kind));
+            assert_eq!(
+                OnlineDdlTracker::parse_gh_ost_tb(tb),
+                expected,
+                "tb: {}",
+                tb
+            );
+        }
+    }
+
+    #[test]
+    fn test_gh_ost_map() {
+        let mut tracker = new_tracker(OnlineDdlPolicyEnum::Map);
+        let sqls = [
+            "create /* gh-ost */ table `db_1`.`_tb_1_ghc` (id bigint auto_increment, primary key(id))",
+            "create /* gh-ost */ table `db_1`.`_tb_1_gho` like `db_1`.`tb_1`",
+            "alter /* gh-ost */ table `db_1`.`_tb_1_gho` add column f_2 int",
+        ];
+        for sql in sqls {
+            assert!(on_ddl(&mut tracker, sql).is_empty());
+        }
+        assert!(tracker.is_shadow_tb("db_1", "_tb_1_gho"));
+        assert!(tracker.is_shadow_tb("db_1", "_tb_1_ghc"));
+        assert!(!tracker.is_shadow_tb("db_1", "tb_1"));
+
+        // the alter is applied to the original table at cut-over
+        let ddls = on_ddl(
+            &mut tracker,
+            "rename /* gh-ost */ table `db_1`.`tb_1` to `db_1`.`_tb_1_del`, `db_1`.`_tb_1_gho` to `db_1`.`tb_1`",
+        );
+        assert_eq!(ddls, vec!["ALTER TABLE `db_1`.`tb_1` add column f_2 int"]);
+        assert!(on_ddl(
+            &mut tracker,
+            "drop /* gh-ost */ table if exists `db_1`.`_tb_1_del`"
+        )
+        .is_empty());
+        assert!(on_ddl(
+            &mut tracker,
+            "drop /* gh-ost */ table if exists `db_1`.`_tb_1_ghc`"
+        )
+        .is_empty());
+
+        // normal ddls
+        let sql = "ALTER TABLE `db_1`.`tb_1` ADD COLUMN f_3 int";
+        assert_eq!(on_ddl(&mut tracker, sql), vec![sql]);
+    }
+
+    #[test]
+    fn test_pt_osc_map() {
+        let mut tracker = new_tracker(OnlineDdlPolicyEnum::Map);
+        // _tb_1_new taken by a normal table
+        let sql = "CREATE TABLE `db_1`.`_tb_1_new` (id int)";
+        assert_eq!(on_ddl(&mut tracker, sql), vec![sql]);
+        assert!(!tracker.is_shadow_tb("db_1", "_tb_1_new"));
+
+        let sqls = [
+            "CREATE TABLE `db_1`.`__tb_1_new` LIKE `db_1`.`tb_1`",
+            "ALTER TABLE `db_1`.`__tb_1_new` ADD COLUMN f_2 int",
+        ];
+        for sql in sqls {
+            assert!(on_ddl(&mut tracker, sql).is_empty());
+        }
+        assert!(tracker.is_shadow_tb("db_1", "__tb_1_new"));
+
+        let ddls = on_ddl(
+            &mut tracker,
+            "RENAME TABLE `db_1`.`tb_1` TO `db_1`.`_tb_1_old`, `db_1`.`__tb_1_new` TO `db_1`.`tb_1`",
+        );
+        assert_eq!(ddls, vec!["ALTER TABLE `db_1`.`tb_1` ADD COLUMN f_2 int"]);
+        assert!(tracker.is_shadow_tb("db_1", "_tb_1_old"));
+        assert!(!tracker.is_shadow_tb("db_1", "__tb_1_new"));
+        assert!(on_ddl(&mut tracker, "DROP TABLE IF EXISTS `db_1`.`_tb_1_old`").is_empty());
+        assert!(!tracker.is_shadow_tb("db_1", "_tb_1_old"));
+
+        // a normal table named _tb_2_old
+        let sql = "RENAME TABLE `db_1`.`tb_2` TO `db_1`.`_tb_2_old`";
+        assert_eq!(on_ddl(&mut tracker, sql), vec![sql]);
+    }
+
+    #[test]
+    fn test_drop() {
+        let mut tracker = new_tracker(OnlineDdlPolicyEnum::Drop);
+        let sqls = [
+            "CREATE TABLE `db_1`.`_tb_1_gho` LIKE `db_1`.`tb_1`",
+            "ALTER TABLE `db_1`.`_tb_1_gho` ADD COLUMN f_2 int",
+            "RENAME TABLE `db_1`.`tb_1` TO `db_1`.`_tb_1_del`, `db_1`.`_tb_1_gho` TO `db_1`.`tb_1`",
+        ];
+        for sql in sqls {
+            assert!(on_ddl(&mut tracker, sql).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_gh_ost_name_taken() {
+        let mut tracker = new_tracker(OnlineDdlPolicyEnum::Map);
+        // a normal table named like a gh-ost table while tb_2 does not exist
+        let sql = "CREATE TABLE `db_1`.`_tb_2_del` (id int)";
+        assert_eq!(on_ddl_with_origin(&mut tracker, sql, false), vec![sql]);
+        assert!(!tracker.is_shadow_tb("db_1", "_tb_2_del"));
+        let sql = "DROP TABLE `db_1`.`_tb_2_del`";
+        assert_eq!(on_ddl_with_origin(&mut tracker, sql, false), vec![sql]);
+
+        // existing tables named like gh-ost tables are tracked only if the original table exists
+        let src_tbs = [
+            ("db_1".to_string(), "tb_1".to_string()),
+            ("db_1".to_string(), "_tb_1_gho".to_string()),
+            ("db_1".to_string(), "_tb_2_ghc".to_string()),
+        ];
+        let tracker = OnlineDdlTracker::new(OnlineDdlPolicyEnum::Map, "", &src_tbs).unwrap();
+        assert!(tracker.is_shadow_tb("db_1", "_tb_1_gho"));
+        assert!(!tracker.is_shadow_tb("db_1", "_tb_2_ghc"));
+    }
+
+    #[test]
+    fn test_resume() {
+        let mut tracker = new_tracker(OnlineDdlPolicyEnum::Map);
+        assert!(tracker.take_state().is_none());
+        let sqls = [
+            "CREATE TABLE `db_1`.`_tb_1_gho` LIKE `db_1`.`tb_1`",
+            "ALTER TABLE `db_1`.`_tb_1_gho` ADD COLUMN f_2 int",
+        ];
+        for sql in sqls {
+            assert!(on_ddl(&mut tracker, sql).is_empty());
+        }
+        let state = tracker.take_state().unwrap();
+        assert!(tracker.take_state().is_none());
+
+        // the pending alter is applied at cut-over after restarting
+        let mut tracker = OnlineDdlTracker::new(OnlineDdlPolicyEnum::Map, &state, &[]).unwrap();
+        assert!(tracker.is_shadow_tb("db_1", "_tb_1_gho"));
+        let ddls = on_ddl(
+            &mut tracker,
+            "RENAME TABLE `db_1`.`tb_1` TO `db_1`.`_tb_1_del`, `db_1`.`_tb_1_gho` TO `db_1`.`tb_1`",
+        );
+        assert_eq!(ddls, vec!["ALTER TABLE `db_1`.`tb_1` ADD COLUMN f_2 int"]);
+        assert!(tracker.is_shadow_tb("db_1", "_tb_1_del"));
+
+        assert!(OnlineDdlTracker::new(OnlineDdlPolicyEnum::Map, "invalid", &[]).is_err());
+    }
+}
@@ -31,6 +31,7 @@ use dt_common::{
     error::Error,
     log_error, log_info,
     rdb_filter::RdbFilter,
+    row_filter::RowFilter,
     utils::time_util::TimeUtil,
 };
 
@@ -59,6 +60,7 @@ pub struct PgCdcExtractor {
     pub meta_manager: PgMetaManager,
     pub conn_pool: Pool<Postgres>,
     pub filter: RdbFilter,
+    pub row_filter: RowFilter,
     pub url: String,
     pub slot_name: String,
     pub pub_name: String,
@@ -332,7 +334,10 @@ impl PgCdcExtractor {
             return self.decode_ddl(&row_data, position).await;
         }
 
-        self.push_row_to_buf(row_data, position.clone()).await
+        match self.row_filter.filter_row(row_data, true) {
+            Some(row_data) => self.push_row_to_buf(row_data, position.clone()).await,
+            None => Ok(()),
+        }
     }
 
     async fn decode_update(
@@ -348,6 +353,8 @@ impl PgCdcExtractor {
         }
 
         let basic = &tb_meta.basic;
+        // before is the full row only if REPLICA IDENTITY FULL
+        let full_before = event.old_tuple().is_some();
         let col_values_after = self.parse_row_data(&tb_meta, event.new_tuple().tuple_data())?;
         let col_values_before = if let Some(old_tuple) = event.old_tuple() {
             self.parse_row_data(&tb_meta, old_tuple.tuple_data())?
@@ -370,7 +377,10 @@ impl PgCdcExtractor {
             Some(col_values_before),
             Some(col_values_after),
         );
-        self.push_row_to_buf(row_data, position.clone()).await
+        match self.row_filter.filter_row(row_data, full_before) {
+            Some(row_data) => self.push_row_to_buf(row_data, position.clone()).await,
+            None => Ok(()),
+        }
     }
 
     async fn decode_delete(
@@ -385,6 +395,7 @@ impl PgCdcExtractor {
             return Ok(());
         }
 
+        let full_before = event.old_tuple().is_some();
         let col_values = if let Some(old_tuple) = event.old_tuple() {
             self.parse_row_data(&tb_meta, old_tuple.tuple_data())?
         } else if let Some(key_tuple) = event.key_tuple() {
@@ -400,7 +411,10 @@ impl PgCdcExtractor {
             Some(col_values),
             None,
         );
-        self.push_row_to_buf(row_data, position.clone()).await
+        match self.row_filter.filter_row(row_data, full_before) {
+            Some(row_data) => self.push_row_to_buf(row_data, position.clone()).await,
+            None => Ok(()),
+        }
     }
 
     async fn decode_ddl(&mut self, row_data: &RowData, position: &Position) -> anyhow::Result<()> {
@@ -1,14 +1,23 @@
 use anyhow::bail;
 use dt_common::error::Error;
-use dt_common::meta::redis::redis_object::{ModuleObject, RedisString};
+use dt_common::meta::redis::redis_object::{ModuleObject, ModuleValue, RedisString};
 
 use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
+use crate::extractor::redis::StreamReader;
 
 pub struct ModuleParser {}
 
 const MODULE_TYPE_NAME_CHAR_SET: &str =
     "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
 
+// opcodes written before each value by RDB_TYPE_MODULE_2
+const RDB_MODULE_OPCODE_EOF: u64 = 0;
+const RDB_MODULE_OPCODE_SINT: u64 = 1;
+const RDB_MODULE_OPCODE_UINT: u64 = 2;
+const RDB_MODULE_OPCODE_FLOAT: u64 = 3;
+const RDB_MODULE_OPCODE_DOUBLE: u64 = 4;
+const RDB_MODULE_OPCODE_STRING: u64 = 5;
+
 impl ModuleParser {
     pub fn load_from_buffer(
         reader: &mut RdbReader,
@@ -23,12 +32,38 @@ impl ModuleParser {
         }
 
         let module_id = reader.read_length()?;
-        let module_name = Self::module_type_name_by_id(module_id);
-        // Not supported
-        bail! {Error::RedisRdbError(format!(
-            "unsupported module type: [{}]",
-            module_name
-        ))}
+        let mut obj = ModuleObject::new();
+        obj.module_name = Self::module_type_name_by_id(module_id);
+        obj.encver = module_id & 1023;
+
+        // every value of RDB_TYPE_MODULE_2 is prefixed by an opcode, so the value can be
+        // walked through without knowing the module, raw bytes are kept for RESTORE
+        loop {
+            let opcode = reader.read_length()?;
+            let value = match opcode {
+                RDB_MODULE_OPCODE_EOF => break,
+                RDB_MODULE_OPCODE_SINT => ModuleValue::SInt(reader.read_length()? as i64),
+                RDB_MODULE_OPCODE_UINT => ModuleValue::UInt(reader.read_length()?),
+                RDB_MODULE_OPCODE_FLOAT => {
+                    let buf = reader.read_bytes(4)?;
+                    ModuleValue::Float(f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]))
+                }
+                RDB_MODULE_OPCODE_DOUBLE => ModuleValue::Double(reader.read_double()?),
+                RDB_MODULE_OPCODE_STRING => ModuleValue::String(reader.read_string()?),
+                _ => {
+                    bail! {Error::RedisRdbError(format!(
+                        "unknown module opcode: {}, module: [{}], key: [{}]",
+                        opcode,
+                        obj.module_name,
+                        key
+                    ))}
+                }
+            };
+            obj.values.push(value);
+        }
+
+        obj.key = key;
+        Ok(obj)
     }
 
     pub fn module_type_name_by_id(module_id: u64) -> String {
@@ -1,5 +1,10 @@
+use std::io::Cursor;
+
 use anyhow::bail;
-use dt_common::meta::redis::{redis_entry::RedisEntry, redis_object::RedisCmd};
+use dt_common::meta::redis::{
+    redis_entry::RedisEntry,
+    redis_object::{RedisCmd, RedisString},
+};
 use dt_common::{error::Error, log_debug, log_info};
 use sqlx::types::chrono;
 
@@ -194,8 +199,12 @@ impl RdbParser<'_> {
                     entry.value = value.unwrap();
                     entry.value_type_byte = type_byte;
                     entry.expire_ms = self.expire_ms;
-                    // reset expire_ms
+                    entry.freq = self.freq;
+                    entry.idle = self.idle;
+                    // reset expire_ms, freq, idle
                     self.expire_ms = 0;
+                    self.freq = -1;
+                    self.idle = -1;
                     return Ok(Some(entry));
                 }
             }
@@ -203,4 +212,36 @@ impl RdbParser<'_> {
 
         Ok(None)
     }
+
+    /// parse the payload returned by DUMP:
+    /// [type_byte][serialized value][rdb version: 2 bytes][crc64: 8 bytes]
+    pub fn parse_dump(key: RedisString, payload: &[u8]) -> anyhow::Result<RedisEntry> {
+        if payload.len() <= 11 {
+            bail! {Error::RedisRdbError(format!(
+                "invalid dump payload, key: {}",
+                key
+            ))}
+        }
+
+        let type_byte = payload[0];
+        let value_bytes = &payload[1..payload.len() - 10];
+        let mut cursor = Cursor::new(value_bytes);
+        let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(&mut cursor);
+        let mut reader = RdbReader {
+            conn: &mut stream_reader,
+            rdb_length: value_bytes.len(),
+            position: 0,
+            copy_raw: true,
+            raw_bytes: Vec::new(),
+        };
+
+        let value = EntryParser::parse_object(&mut reader, type_byte, key.clone())?;
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.raw_bytes = reader.drain_raw_bytes();
+        entry.key = key;
+        entry.value = value;
+        entry.value_type_byte = type_byte;
+        Ok(entry)
+    }
 }
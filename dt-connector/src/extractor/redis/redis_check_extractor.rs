@@ -0,0 +1,77 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    error::Error,
+    log_info,
+    meta::{position::Position, redis::redis_constant::RedisConstants},
+    rdb_filter::RdbFilter,
+    utils::redis_util::RedisUtil,
+};
+use redis::{Connection, Value};
+
+use crate::{
+    check_log::check_log::CheckLog,
+    extractor::{
+        base_check_extractor::BaseCheckExtractor,
+        base_extractor::BaseExtractor,
+        redis::{
+            redis_psync_extractor::RedisPsyncExtractor, redis_scan_extractor::RedisScanExtractor,
+        },
+    },
+    BatchCheckExtractor, Extractor,
+};
+
+pub struct RedisCheckExtractor {
+    pub base_extractor: BaseExtractor,
+    pub conn: Connection,
+    pub filter: RdbFilter,
+    pub check_log_dir: String,
+    pub batch_size: usize,
+}
+
+#[async_trait]
+impl Extractor for RedisCheckExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!("RedisCheckExtractor starts");
+        let base_check_extractor = BaseCheckExtractor {
+            check_log_dir: self.check_log_dir.clone(),
+            batch_size: self.batch_size,
+        };
+        base_check_extractor.extract(self).await?;
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+#[async_trait]
+impl BatchCheckExtractor for RedisCheckExtractor {
+    async fn batch_extract(&mut self, check_logs: &[CheckLog]) -> anyhow::Result<()> {
+        // all logs in the same batch share the same db
+        let db = &check_logs[0].schema;
+        let db_id: i64 = db.parse()?;
+        let cmd = ["SELECT", db];
+        if Value::Okay != RedisUtil::send_cmd(&mut self.conn, &cmd)? {
+            bail! {Error::RedisResultError(format!("\"SELECT {}\" failed", db))}
+        }
+
+        for check_log in check_logs.iter() {
+            let key = match check_log.id_col_values.get(RedisConstants::KEY) {
+                Some(Some(key)) => key,
+                _ => continue,
+            };
+
+            // the key may have been removed from source since checked
+            if let Some(mut entry) = RedisScanExtractor::dump_entry(&mut self.conn, key.as_bytes())?
+            {
+                entry.db_id = db_id;
+                RedisPsyncExtractor::push_to_buf(
+                    &mut self.base_extractor,
+                    &mut self.filter,
+                    entry,
+                    Position::None,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
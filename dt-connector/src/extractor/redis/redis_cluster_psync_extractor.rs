@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::config::config_enums::{DbType, ExtractType};
+use dt_common::config::config_token_parser::ConfigTokenParser;
+use dt_common::error::Error;
+use dt_common::meta::position::{Position, RedisShardPosition};
+use dt_common::meta::redis::cluster_node::ClusterNode;
+use dt_common::meta::syncer::Syncer;
+use dt_common::rdb_filter::RdbFilter;
+use dt_common::time_filter::TimeFilter;
+use dt_common::utils::redis_util::RedisUtil;
+use dt_common::utils::sql_util::SqlUtil;
+use dt_common::utils::time_util::TimeUtil;
+use dt_common::{log_error, log_info, log_position, log_warn};
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::extractor::base_extractor::BaseExtractor;
+use crate::extractor::extractor_monitor::ExtractorMonitor;
+use crate::extractor::resumer::cdc_resumer::CdcResumer;
+use crate::Extractor;
+
+use super::redis_client::RedisClient;
+use super::redis_psync_extractor::RedisPsyncExtractor;
+
+const REFRESH_INTERVAL_SECS: u64 = 10;
+
+/// the position slot of a shard in the multi-shard position shared by all shard extractors
+#[derive(Clone)]
+pub struct RedisClusterShard {
+    pub index: usize,
+    pub positions: Arc<async_mutex::Mutex<Vec<RedisShardPosition>>>,
+}
+
+pub struct ShardTask {
+    node_id: String,
+    shut_down: Arc<AtomicBool>,
+    handle: JoinHandle<anyhow::Result<()>>,
+}
+
+/// runs a PSYNC stream for each master of a redis cluster and merges them into one pipeline,
+/// masters are re-resolved periodically to follow failover and resharding
+pub struct RedisClusterPsyncExtractor {
+    pub base_extractor: BaseExtractor,
+    pub url: String,
+    pub repl_port: u64,
+    pub keepalive_interval_secs: u64,
+    pub heartbeat_interval_secs: u64,
+    pub heartbeat_key: String,
+    pub syncer: Arc<Mutex<Syncer>>,
+    pub filter: RdbFilter,
+    pub resumer: CdcResumer,
+    pub extract_type: ExtractType,
+    pub shard_tasks: HashMap<usize, ShardTask>,
+}
+
+#[async_trait]
+impl Extractor for RedisClusterPsyncExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!(
+            "RedisClusterPsyncExtractor starts, url: {}, extract_type: {}, resumer: {}",
+            self.url,
+            self.extract_type,
+            self.resumer
+        );
+
+        let shards = match &self.resumer.checkpoint_position {
+            Position::RedisCluster { shards } => shards.clone(),
+            _ => Vec::new(),
+        };
+        let positions = Arc::new(async_mutex::Mutex::new(shards));
+
+        self.refresh_shards(&positions, true).await?;
+        if matches!(
+            self.extract_type,
+            ExtractType::Cdc | ExtractType::SnapshotAndCdc
+        ) {
+            loop {
+                TimeUtil::sleep_millis(REFRESH_INTERVAL_SECS * 1000).await;
+                if let Err(err) = self.refresh_shards(&positions, false).await {
+                    log_error!("refresh redis cluster shards failed, error: {:?}", err);
+                }
+            }
+        }
+
+        self.base_extractor.wait_task_finish().await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.stop_shard_tasks();
+        Ok(())
+    }
+}
+
+impl Drop for RedisClusterPsyncExtractor {
+    fn drop(&mut self) {
+        // shard tasks are spawned, they should not outlive the extractor
+        self.stop_shard_tasks();
+    }
+}
+
+impl RedisClusterPsyncExtractor {
+    async fn refresh_shards(
+        &mut self,
+        positions: &Arc<async_mutex::Mutex<Vec<RedisShardPosition>>>,
+        is_startup: bool,
+    ) -> anyhow::Result<()> {
+        let masters = self.get_master_nodes(positions).await?;
+
+        // stop streams which were broken or whose node is no longer a master, eg: demoted
+        // after failover, their shards will be continued by the current masters
+        let mut stopped_indexes = Vec::new();
+        for (index, task) in self.shard_tasks.iter() {
+            if task.handle.is_finished() || !masters.iter().any(|i| i.id == task.node_id) {
+                stopped_indexes.push(*index);
+            }
+        }
+        for index in stopped_indexes {
+            let task = self.shard_tasks.remove(&index).unwrap();
+            task.shut_down.store(true, Ordering::Release);
+            task.handle.abort();
+            match task.handle.await {
+                Ok(Err(err)) => log_warn!(
+                    "redis cluster shard stopped, node_id: {}, error: {:?}",
+                    task.node_id,
+                    err
+                ),
+                _ => log_warn!("redis cluster shard stopped, node_id: {}", task.node_id),
+            }
+        }
+
+        for master in masters.iter() {
+            if self.shard_tasks.values().any(|i| i.node_id == master.id) {
+                continue;
+            }
+
+            if let Err(err) = self.start_shard(master, positions, is_startup).await {
+                // snapshot data of the shard would be lost if it is not started
+                if !matches!(
+                    self.extract_type,
+                    ExtractType::Cdc | ExtractType::SnapshotAndCdc
+                ) {
+                    return Err(err);
+                }
+                log_error!(
+                    "start redis cluster shard failed, node: {}, error: {:?}",
+                    master.address,
+                    err
+                );
+            }
+        }
+
+        if is_startup {
+            for (index, shard) in positions.lock().await.iter().enumerate() {
+                if !self.shard_tasks.contains_key(&index) && !shard.repl_id.is_empty() {
+                    log_warn!("no master can continue the shard position: {:?}", shard);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn start_shard(
+        &mut self,
+        master: &ClusterNode,
+        positions: &Arc<async_mutex::Mutex<Vec<RedisShardPosition>>>,
+        is_startup: bool,
+    ) -> anyhow::Result<()> {
+        let node_url = self.get_node_url(&master.address)?;
+        let mut conn = RedisUtil::create_redis_conn(&node_url).await?;
+        let (repl_id, repl_id2) = RedisUtil::get_replication_ids(&mut conn)?;
+
+        // a master can continue a shard position if it owns the replication id, either as
+        // its own or inherited from the former master it replaced
+        let index = {
+            let mut positions = positions.lock().await;
+            let index = positions.iter().position(|shard| {
+                !shard.repl_id.is_empty() && (shard.repl_id == repl_id || shard.repl_id == repl_id2)
+            });
+            // the shard was never synced, eg: it failed to start last time
+            let index = index.or_else(|| {
+                positions.iter().enumerate().position(|(i, shard)| {
+                    shard.repl_id.is_empty()
+                        && shard.node_id == master.id
+                        && !self.shard_tasks.contains_key(&i)
+                })
+            });
+            match index {
+                Some(index) => index,
+                None => {
+                    positions.push(RedisShardPosition {
+                        node_id: master.id.clone(),
+                        address: master.address.clone(),
+                        ..Default::default()
+                    });
+                    positions.len() - 1
+                }
+            }
+        };
+
+        // the shard may still be streamed from a failed master which is listed as master
+        // until it rejoins as a replica, stop it since the shard is taken over
+        if let Some(task) = self.shard_tasks.remove(&index) {
+            log_warn!(
+                "redis cluster shard taken over, from node_id: {}, to node_id: {}",
+                task.node_id,
+                master.id
+            );
+            task.shut_down.store(true, Ordering::Release);
+            task.handle.abort();
+            let _ = task.handle.await;
+        }
+
+        let shard_position = positions.lock().await[index].clone();
+        let is_new_shard = shard_position.repl_id.is_empty();
+        // rdb of shards found at startup are pushed only if snapshot is required, but shards
+        // found later (eg: added by resharding) or resynced must push rdb to avoid data loss
+        let extract_type = match self.extract_type {
+            ExtractType::Cdc if !is_startup || !is_new_shard => ExtractType::SnapshotAndCdc,
+            _ => self.extract_type.clone(),
+        };
+
+        let shut_down = Arc::new(AtomicBool::new(false));
+        let mut extractor = RedisPsyncExtractor {
+            base_extractor: self.build_shard_base_extractor(shut_down.clone()),
+            conn: RedisClient::new(&node_url).await?,
+            repl_id: shard_position.repl_id,
+            repl_offset: shard_position.repl_offset,
+            repl_port: self.repl_port,
+            now_db_id: shard_position.now_db_id,
+            keepalive_interval_secs: self.keepalive_interval_secs,
+            heartbeat_interval_secs: self.heartbeat_interval_secs,
+            heartbeat_key: self.get_shard_heartbeat_key(master),
+            syncer: self.syncer.clone(),
+            filter: self.filter.clone(),
+            resumer: CdcResumer::default(),
+            extract_type,
+            cluster_shard: Some(RedisClusterShard {
+                index,
+                positions: positions.clone(),
+            }),
+        };
+
+        log_info!(
+            "start redis cluster shard, node: {}, index: {}, repl_id: {}, repl_offset: {}",
+            master.address,
+            index,
+            extractor.repl_id,
+            extractor.repl_offset
+        );
+        if extractor.start_psync().await? {
+            extractor.receive_rdb().await?;
+            extractor.base_extractor.monitor.try_flush(true);
+        }
+
+        // all rdb data of the shard were pushed, data of other shards pushed later will carry
+        // the new position of this shard
+        {
+            let mut positions = positions.lock().await;
+            let shard_position = &mut positions[index];
+            shard_position.node_id = master.id.clone();
+            shard_position.address = master.address.clone();
+            shard_position.repl_id = extractor.repl_id.clone();
+            shard_position.repl_offset = extractor.repl_offset;
+            shard_position.now_db_id = extractor.now_db_id;
+            let position = Position::RedisCluster {
+                shards: positions.clone(),
+            };
+            log_position!("current_position | {}", position.to_string());
+        }
+
+        if !matches!(
+            self.extract_type,
+            ExtractType::Cdc | ExtractType::SnapshotAndCdc
+        ) {
+            return Ok(());
+        }
+
+        let handle = tokio::spawn(async move { extractor.receive_aof().await });
+        self.shard_tasks.insert(
+            index,
+            ShardTask {
+                node_id: master.id.clone(),
+                shut_down,
+                handle,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get_master_nodes(
+        &self,
+        positions: &Arc<async_mutex::Mutex<Vec<RedisShardPosition>>>,
+    ) -> anyhow::Result<Vec<ClusterNode>> {
+        // the configured node may be the failed one, try known shard nodes as well
+        let mut urls = vec![self.url.clone()];
+        for shard in positions.lock().await.iter() {
+            if !shard.address.is_empty() {
+                urls.push(self.get_node_url(&shard.address)?);
+            }
+        }
+
+        for url in urls.iter() {
+            let nodes = match RedisUtil::create_redis_conn(url).await {
+                Ok(mut conn) => RedisUtil::get_cluster_master_nodes(&mut conn),
+                Err(err) => Err(err),
+            };
+            match nodes {
+                Ok(nodes) => return Ok(nodes),
+                Err(err) => log_warn!(
+                    "get redis cluster nodes failed, url: {}, error: {:?}",
+                    url,
+                    err
+                ),
+            }
+        }
+        bail! {Error::ExtractorError(
+            "can not get redis cluster master nodes".into(),
+        )}
+    }
+
+    fn get_node_url(&self, address: &str) -> anyhow::Result<String> {
+        let url_info = Url::parse(&self.url)?;
+        let username = url_info.username();
+        let password = url_info.password().unwrap_or("");
+        Ok(format!("redis://{}:{}@{}", username, password, address))
+    }
+
+    fn get_shard_heartbeat_key(&self, master: &ClusterNode) -> String {
+        // a key only exists in one shard, add a hash tag routed to the master for each shard
+        let heartbeat_db_key = ConfigTokenParser::parse(
+            &self.heartbeat_key,
+            &['.'],
+            &SqlUtil::get_escape_pairs(&DbType::Redis),
+        );
+        if heartbeat_db_key.len() != 2 {
+            return self.heartbeat_key.clone();
+        }
+
+        match master
+            .slots
+            .first()
+            .and_then(|slot| master.slot_hash_tag_map.get(slot))
+        {
+            Some(hash_tag) => format!(
+                "{}.{}{{{}}}",
+                heartbeat_db_key[0], heartbeat_db_key[1], hash_tag
+            ),
+            None => String::new(),
+        }
+    }
+
+    fn build_shard_base_extractor(&self, shut_down: Arc<AtomicBool>) -> BaseExtractor {
+        let time_filter = &self.base_extractor.time_filter;
+        BaseExtractor {
+            buffer: self.base_extractor.buffer.clone(),
+            router: self.base_extractor.router.clone(),
+            shut_down,
+            monitor: ExtractorMonitor::new(self.base_extractor.monitor.monitor.clone()),
+            data_marker: self.base_extractor.data_marker.clone(),
+            time_filter: TimeFilter {
+                start_timestamp: time_filter.start_timestamp,
+                end_timestamp: time_filter.end_timestamp,
+                started: time_filter.started,
+                ended: time_filter.ended,
+            },
+            shard_ddl_coordinator: None,
+        }
+    }
+
+    fn stop_shard_tasks(&mut self) {
+        for (_, task) in self.shard_tasks.drain() {
+            task.shut_down.store(true, Ordering::Release);
+            task.handle.abort();
+        }
+    }
+}
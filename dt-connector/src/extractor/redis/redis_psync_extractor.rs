@@ -4,6 +4,7 @@ use dt_common::config::config_enums::{DbType, ExtractType};
 use dt_common::config::config_token_parser::ConfigTokenParser;
 use dt_common::meta::dt_data::DtData;
 use dt_common::meta::position::Position;
+use dt_common::meta::redis::command::key_parser::KeyParser;
 use dt_common::meta::redis::redis_entry::RedisEntry;
 use dt_common::meta::redis::redis_object::RedisCmd;
 use dt_common::meta::syncer::Syncer;
@@ -23,10 +24,14 @@ use crate::extractor::resumer::cdc_resumer::CdcResumer;
 use crate::Extractor;
 
 use super::redis_client::RedisClient;
+use super::redis_cluster_psync_extractor::RedisClusterShard;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
+// shared by all redis extractors, building a KeyParser parses all command metas
+static KEY_PARSER: OnceLock<KeyParser> = OnceLock::new();
+
 pub struct RedisPsyncExtractor {
     pub base_extractor: BaseExtractor,
     pub conn: RedisClient,
@@ -41,6 +46,8 @@ pub struct RedisPsyncExtractor {
     pub filter: RdbFilter,
     pub resumer: CdcResumer,
     pub extract_type: ExtractType,
+    // set if this extractor runs as one shard of a redis cluster source
+    pub cluster_shard: Option<RedisClusterShard>,
 }
 
 #[async_trait]
@@ -108,14 +115,30 @@ impl RedisPsyncExtractor {
 
         if let Value::Status(s) = value {
             log_info!("PSYNC command response status: {:?}", s);
+            let tokens: Vec<&str> = s.split_whitespace().collect();
             if full_sync {
-                let tokens: Vec<&str> = s.split_whitespace().collect();
                 self.repl_id = tokens[1].to_string();
                 self.repl_offset = tokens[2].parse::<u64>()?;
-            } else if s != "CONTINUE" {
+            } else if tokens[0] == "FULLRESYNC" && self.cluster_shard.is_some() {
+                // the shard master can not continue from the position, eg: the backlog was
+                // overwritten, the caller decides whether the rdb should be pushed
+                log_warn!(
+                    "PSYNC can not continue, repl_id: {}, repl_offset: {}, response: {}",
+                    self.repl_id,
+                    self.repl_offset,
+                    s
+                );
+                self.repl_id = tokens[1].to_string();
+                self.repl_offset = tokens[2].parse::<u64>()?;
+                return Ok(true);
+            } else if tokens[0] != "CONTINUE" {
                 bail! {Error::ExtractorError(
                     "PSYNC command response is NOT CONTINUE".into(),
                 )}
+            } else if tokens.len() > 1 {
+                // +CONTINUE <new_repl_id>, the replication id changed, eg: a replica was
+                // promoted to master and accepted the PSYNC with the id of its former master
+                self.repl_id = tokens[1].to_string();
             }
         } else {
             bail! {Error::ExtractorError(
@@ -125,7 +148,7 @@ impl RedisPsyncExtractor {
         Ok(full_sync)
     }
 
-    async fn receive_rdb(&mut self) -> anyhow::Result<()> {
+    pub async fn receive_rdb(&mut self) -> anyhow::Result<()> {
         let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(&mut self.conn);
         // format: \n\n\n$<length>\r\n<rdb>
         loop {
@@ -165,8 +188,8 @@ impl RedisPsyncExtractor {
             repl_stream_db_id: 0,
             now_db_id: self.now_db_id,
             expire_ms: 0,
-            idle: 0,
-            freq: 0,
+            idle: -1,
+            freq: -1,
             is_end: false,
         };
 
@@ -205,6 +228,12 @@ impl RedisPsyncExtractor {
             }
         }
 
+        self.now_db_id = parser.now_db_id;
+        // in cluster mode, the position of all shards is logged by RedisClusterPsyncExtractor
+        if self.cluster_shard.is_some() {
+            return Ok(());
+        }
+
         // this log to mark the snapshot rdb was all received
         let position = Position::Redis {
             repl_id: self.repl_id.clone(),
@@ -217,7 +246,7 @@ impl RedisPsyncExtractor {
         Ok(())
     }
 
-    async fn receive_aof(&mut self) -> anyhow::Result<()> {
+    pub async fn receive_aof(&mut self) -> anyhow::Result<()> {
         let heartbeat_db_key = ConfigTokenParser::parse(
             &self.heartbeat_key,
             &['.'],
@@ -312,9 +341,7 @@ impl RedisPsyncExtractor {
 
                 // a single ping(should NOT be in a transaction)
                 if cmd_name == "ping" {
-                    self.base_extractor
-                        .push_dt_data(DtData::Heartbeat {}, position)
-                        .await?;
+                    self.push_aof_data(DtData::Heartbeat {}, position).await?;
                     continue;
                 }
 
@@ -328,12 +355,54 @@ impl RedisPsyncExtractor {
                 entry.cmd = cmd;
                 entry.db_id = self.now_db_id;
 
-                Self::push_to_buf(&mut self.base_extractor, &mut self.filter, entry, position)
+                self.push_aof_data(DtData::Redis { entry }, position)
                     .await?;
             }
         }
     }
 
+    async fn push_aof_data(&mut self, dt_data: DtData, position: Position) -> anyhow::Result<()> {
+        let shard = match &self.cluster_shard {
+            Some(shard) => shard.clone(),
+            None => return self.push_dt_data(dt_data, position).await,
+        };
+
+        // in cluster mode, each pushed position is a snapshot of all shards, the shared
+        // positions stay locked until the data is pushed, so the snapshots are in the same
+        // order as the data in buffer and a committed snapshot never covers unsinked data
+        let mut positions = shard.positions.lock().await;
+        let mut shards = positions.clone();
+        if let Position::Redis {
+            repl_id,
+            repl_offset,
+            now_db_id,
+            timestamp,
+            ..
+        } = position
+        {
+            let shard_position = &mut shards[shard.index];
+            shard_position.repl_id = repl_id;
+            shard_position.repl_offset = repl_offset;
+            shard_position.now_db_id = now_db_id;
+            shard_position.timestamp = timestamp;
+        }
+        let position = Position::RedisCluster {
+            shards: shards.clone(),
+        };
+        self.push_dt_data(dt_data, position).await?;
+        *positions = shards;
+        Ok(())
+    }
+
+    async fn push_dt_data(&mut self, dt_data: DtData, position: Position) -> anyhow::Result<()> {
+        match dt_data {
+            DtData::Redis { entry } => {
+                Self::push_to_buf(&mut self.base_extractor, &mut self.filter, entry, position).await
+            }
+            _ => self.base_extractor.push_dt_data(dt_data, position).await,
+        }
+    }
+
     async fn handle_redis_value(&mut self, value: Value) -> anyhow::Result<RedisCmd> {
         let mut cmd = RedisCmd::new();
         match value {
@@ -361,8 +430,17 @@ impl RedisPsyncExtractor {
     async fn keep_alive_ack(&mut self) -> anyhow::Result<()> {
         // send replconf ack to keep the connection alive
         let mut position_repl_offset = self.repl_offset;
-        if let Position::Redis { repl_offset, .. } = self.syncer.lock().unwrap().committed_position
-        {
+        let committed_repl_offset = match &self.syncer.lock().unwrap().committed_position {
+            Position::Redis { repl_offset, .. } => Some(*repl_offset),
+            Position::RedisCluster { shards } => self
+                .cluster_shard
+                .as_ref()
+                .and_then(|shard| shards.get(shard.index))
+                .filter(|i| i.repl_id == self.repl_id)
+                .map(|i| i.repl_offset),
+            _ => None,
+        };
+        if let Some(repl_offset) = committed_repl_offset {
             if repl_offset >= self.repl_offset {
                 position_repl_offset = repl_offset
             }
@@ -445,11 +523,23 @@ impl RedisPsyncExtractor {
         mut entry: RedisEntry,
         position: Position,
     ) -> anyhow::Result<()> {
-        // currently only support db filter
         if filter.filter_schema(&entry.db_id.to_string()) {
             return Ok(());
         }
 
+        // data marker entries are neither filtered nor routed by key rules
+        let is_data_marker = base_extractor
+            .data_marker
+            .as_ref()
+            .is_some_and(|data_marker| data_marker.is_redis_marker_info(&entry));
+        if !is_data_marker {
+            let key_parser = KEY_PARSER.get_or_init(KeyParser::new);
+            if filter.has_key_filter() && filter.filter_redis_entry(&mut entry, key_parser) {
+                return Ok(());
+            }
+            entry = base_extractor.router.route_redis_entry(entry, key_parser);
+        }
+
         entry.data_size = entry.get_data_malloc_size();
         base_extractor
             .push_dt_data(DtData::Redis { entry }, position)
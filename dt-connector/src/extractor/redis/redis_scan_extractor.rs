@@ -6,20 +6,28 @@ use dt_common::{
         dt_data::DtData,
         position::Position,
         redis::{
+            command::cmd_encoder::CmdEncoder,
             redis_entry::RedisEntry,
-            redis_object::{RedisObject, RedisString},
+            redis_object::{RedisCmd, RedisObject, RedisString},
             redis_statistic_type::RedisStatisticType,
         },
     },
     utils::redis_util::RedisUtil,
 };
-use redis::{Connection, Value};
+use redis::{Connection, ConnectionLike, Value};
 
-use crate::{extractor::base_extractor::BaseExtractor, Extractor};
+use crate::{
+    extractor::{
+        base_extractor::BaseExtractor,
+        redis::{rdb::rdb_parser::RdbParser, redis_psync_extractor::RedisPsyncExtractor},
+    },
+    Extractor,
+};
 
 pub struct RedisScanExtractor {
     pub base_extractor: BaseExtractor,
-    pub statistic_type: RedisStatisticType,
+    // None: dump and push the whole entry of each key
+    pub statistic_type: Option<RedisStatisticType>,
     pub scan_count: u64,
     pub conn: Connection,
     pub filter: RdbFilter,
@@ -30,7 +38,7 @@ impl Extractor for RedisScanExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
         log_info!("RedisScanExtractor starts");
 
-        if let RedisStatisticType::HotKey = self.statistic_type {
+        if let Some(RedisStatisticType::HotKey) = self.statistic_type {
             let maxmemory_policy = self.get_maxmemory_policy().await?;
             if maxmemory_policy != "allkeys-lfu" {
                 bail! {Error::MetadataError(format!(
@@ -62,8 +70,14 @@ impl Extractor for RedisScanExtractor {
                 cursor = result[0].parse()?;
                 for key in result.iter().skip(1) {
                     match self.statistic_type {
-                        RedisStatisticType::HotKey => self.analyze_hot_key(db_id, key).await?,
-                        RedisStatisticType::BigKey => self.analyze_big_key(db_id, key).await?,
+                        Some(RedisStatisticType::HotKey) => {
+                            self.analyze_hot_key(db_id, key).await?
+                        }
+                        Some(RedisStatisticType::BigKey) => {
+                            self.analyze_big_key(db_id, key).await?
+                        }
+                        Some(RedisStatisticType::Memory) => self.analyze_memory(db_id, key).await?,
+                        None => self.dump_key(db_id, key).await?,
                     }
                 }
 
@@ -134,6 +148,64 @@ impl RedisScanExtractor {
             .await
     }
 
+    async fn analyze_memory(&mut self, db_id: i64, key: &str) -> anyhow::Result<()> {
+        if let Some(mut entry) = Self::dump_entry(&mut self.conn, key.as_bytes())? {
+            // use the memory reported by redis instead of the serialized size
+            let cmd = ["MEMORY", "USAGE", key];
+            if let Ok(data_size) = self.query(&cmd).await?[0].parse() {
+                entry.data_size = data_size;
+            }
+            entry.db_id = db_id;
+            self.base_extractor
+                .push_dt_data(DtData::Redis { entry }, Position::None)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn dump_key(&mut self, db_id: i64, key: &str) -> anyhow::Result<()> {
+        if let Some(mut entry) = Self::dump_entry(&mut self.conn, key.as_bytes())? {
+            entry.db_id = db_id;
+            RedisPsyncExtractor::push_to_buf(
+                &mut self.base_extractor,
+                &mut self.filter,
+                entry,
+                Position::None,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// get the entry of key by DUMP and PTTL, None if the key not exists
+    pub fn dump_entry(conn: &mut Connection, key: &[u8]) -> anyhow::Result<Option<RedisEntry>> {
+        let mut packed_cmds = Vec::new();
+        for name in ["DUMP", "PTTL"] {
+            let cmd = RedisCmd::from_args(vec![name.as_bytes().to_vec(), key.to_vec()]);
+            packed_cmds.extend_from_slice(&CmdEncoder::encode(&cmd));
+        }
+        let values = conn.req_packed_commands(&packed_cmds, 0, 2)?;
+
+        let payload = match &values[0] {
+            Value::BulkString(payload) => payload,
+            Value::Nil => return Ok(None),
+            value => bail! {Error::RedisResultError(format!(
+                "unexpected DUMP result: {:?}, key: {}",
+                value,
+                String::from_utf8_lossy(key)
+            ))},
+        };
+
+        let mut entry = RdbParser::parse_dump(RedisString::from(key.to_vec()), payload)?;
+        // -1: no expire, -2: not exists
+        if let Value::Int(pttl) = values[1] {
+            if pttl > 0 {
+                entry.expire_ms = pttl;
+            }
+        }
+        Ok(Some(entry))
+    }
+
     async fn query(&mut self, cmd: &[&str]) -> anyhow::Result<Vec<String>> {
         let result = RedisUtil::send_cmd(&mut self.conn, cmd)?;
         RedisUtil::parse_result_as_string(result)
@@ -43,8 +43,8 @@ impl Extractor for RedisSnapshotFileExtractor {
             repl_stream_db_id: 0,
             now_db_id: 0,
             expire_ms: 0,
-            idle: 0,
-            freq: 0,
+            idle: -1,
+            freq: -1,
             is_end: false,
         };
 
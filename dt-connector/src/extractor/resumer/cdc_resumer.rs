@@ -13,12 +13,19 @@ use dt_common::{
 };
 use serde_json::json;
 
-use super::{CURRENT_POSITION_LOG_FLAG, TAIL_POSITION_COUNT};
+use super::{
+    CURRENT_POSITION_LOG_FLAG, ONLINE_DDL_STATE_LOG_FLAG, SHARD_DDL_STATE_LOG_FLAG,
+    TAIL_POSITION_COUNT,
+};
 
 #[derive(Clone, Default)]
 pub struct CdcResumer {
     pub current_position: Position,
     pub checkpoint_position: Position,
+    // state of the shard ddl coordinator at checkpoint_position
+    pub shard_ddl_state: String,
+    // state of the online ddl tracker at checkpoint_position
+    pub online_ddl_state: String,
 }
 
 impl CdcResumer {
@@ -73,6 +80,18 @@ impl CdcResumer {
     }
 
     fn load_resume_line(&mut self, line: &str) {
+        // 2025-02-18 04:13:04.655541 | shard_ddl_state | [{"schema":"db","tb":"tb", ...}]
+        if let Some((_, state)) = line.split_once(SHARD_DDL_STATE_LOG_FLAG) {
+            self.shard_ddl_state = state.trim().to_string();
+            return;
+        }
+
+        // 2025-02-18 04:13:04.655541 | online_ddl_state | {"shadow_tbs":[...],"pending_ddls":[...]}
+        if let Some((_, state)) = line.split_once(ONLINE_DDL_STATE_LOG_FLAG) {
+            self.online_ddl_state = state.trim().to_string();
+            return;
+        }
+
         let position = Position::from_log(line);
         // ignore position log lines like:
         // 2025-02-18 04:13:04.655541 | checkpoint_position | {"type":"None"}
@@ -2,4 +2,6 @@ pub mod cdc_resumer;
 pub mod snapshot_resumer;
 
 const CURRENT_POSITION_LOG_FLAG: &str = "| current_position |";
+const SHARD_DDL_STATE_LOG_FLAG: &str = "| shard_ddl_state |";
+const ONLINE_DDL_STATE_LOG_FLAG: &str = "| online_ddl_state |";
 const TAIL_POSITION_COUNT: usize = 200;
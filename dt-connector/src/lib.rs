@@ -9,6 +9,7 @@ pub mod extractor;
 pub mod meta_fetcher;
 pub mod rdb_query_builder;
 pub mod rdb_router;
+pub mod shard_ddl_coordinator;
 pub mod sinker;
 
 use async_trait::async_trait;
@@ -1,3 +1,5 @@
+pub mod mongo;
+
 pub mod mysql;
 
 pub mod pg;
@@ -0,0 +1 @@
+pub mod mongo_struct_fetcher;
@@ -0,0 +1,117 @@
+use dt_common::{
+    meta::struct_meta::statement::mongo_create_collection_statement::MongoCreateCollectionStatement,
+    rdb_filter::RdbFilter,
+};
+use mongodb::{
+    bson::{doc, Bson, Document},
+    Client,
+};
+
+const SYSTEM_COLLECTION_PREFIX: &str = "system.";
+
+pub struct MongoStructFetcher {
+    pub mongo_client: Client,
+    pub db: String,
+    pub filter: Option<RdbFilter>,
+}
+
+impl MongoStructFetcher {
+    /// if tb is empty, get statements of all collections in db, views are placed
+    /// after collections since they are created on their source collections
+    pub async fn get_create_collection_statements(
+        &mut self,
+        tb: &str,
+    ) -> anyhow::Result<Vec<MongoCreateCollectionStatement>> {
+        let tbs = if tb.is_empty() {
+            self.mongo_client
+                .database(&self.db)
+                .list_collection_names(None)
+                .await?
+        } else {
+            vec![tb.to_string()]
+        };
+
+        let mut collections = Vec::new();
+        let mut views = Vec::new();
+        for tb in tbs.iter() {
+            // system.views, system.buckets.* of time-series collections, etc.
+            if tb.starts_with(SYSTEM_COLLECTION_PREFIX) || self.filter_tb(tb) {
+                continue;
+            }
+
+            if let Some(statement) = self.get_create_collection_statement(tb).await? {
+                if statement.is_view() {
+                    views.push(statement);
+                } else {
+                    collections.push(statement);
+                }
+            }
+        }
+
+        collections.sort_by(|a, b| a.tb.cmp(&b.tb));
+        views.sort_by(|a, b| a.tb.cmp(&b.tb));
+        collections.extend(views);
+        Ok(collections)
+    }
+
+    async fn get_create_collection_statement(
+        &self,
+        tb: &str,
+    ) -> anyhow::Result<Option<MongoCreateCollectionStatement>> {
+        let command = doc! { "listCollections": 1, "filter": { "name": tb } };
+        let collections = self.run_list_command(command).await?;
+        let collection = match collections.into_iter().next() {
+            Some(collection) => collection,
+            None => return Ok(None),
+        };
+
+        let mut statement = MongoCreateCollectionStatement {
+            db: self.db.clone(),
+            tb: tb.to_string(),
+            collection_type: collection.get_str("type").unwrap_or_default().to_string(),
+            options: collection
+                .get_document("options")
+                .cloned()
+                .unwrap_or_default(),
+            indexes: Vec::new(),
+        };
+
+        // views have no indexes
+        if !statement.is_view() {
+            let command = doc! { "listIndexes": tb };
+            let mut indexes = self.run_list_command(command).await?;
+            indexes.sort_by(|a, b| {
+                a.get_str("name")
+                    .unwrap_or_default()
+                    .cmp(b.get_str("name").unwrap_or_default())
+            });
+            statement.indexes = indexes;
+        }
+        Ok(Some(statement))
+    }
+
+    /// run listCollections / listIndexes, all results are returned in the first batch
+    /// since the collection name is specified and a collection has at most 64 indexes
+    async fn run_list_command(&self, command: Document) -> anyhow::Result<Vec<Document>> {
+        let result = self
+            .mongo_client
+            .database(&self.db)
+            .run_command(command, None)
+            .await?;
+
+        let mut docs = Vec::new();
+        for item in result.get_document("cursor")?.get_array("firstBatch")? {
+            if let Bson::Document(doc) = item {
+                docs.push(doc.clone());
+            }
+        }
+        Ok(docs)
+    }
+
+    fn filter_tb(&mut self, tb: &str) -> bool {
+        if let Some(filter) = &mut self.filter {
+            return filter.filter_tb(&self.db, tb);
+        }
+        false
+    }
+}
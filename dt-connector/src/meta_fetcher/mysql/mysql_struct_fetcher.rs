@@ -120,7 +120,8 @@ impl MysqlStructFetcher {
                 c.EXTRA, 
                 c.COLUMN_COMMENT, 
                 c.CHARACTER_SET_NAME, 
-                c.COLLATION_NAME 
+                c.COLLATION_NAME, 
+                c.GENERATION_EXPRESSION 
             FROM information_schema.tables t
             LEFT JOIN information_schema.columns c
             ON t.TABLE_SCHEMA = c.TABLE_SCHEMA AND t.TABLE_NAME = c.TABLE_NAME
@@ -148,7 +149,20 @@ impl MysqlStructFetcher {
             let is_nullable = Self::get_str_with_null(&row, "IS_NULLABLE")?.to_lowercase() == "yes";
             let extra = Self::get_str_with_null(&row, "EXTRA")?;
             let column_name = Self::get_str_with_null(&row, "COLUMN_NAME")?;
-            let column_default = if let Some(column_default_str) = row.get("COLUMN_DEFAULT") {
+            // EXTRA: VIRTUAL GENERATED, STORED GENERATED, INVISIBLE, DEFAULT_GENERATED INVISIBLE
+            let upper_extra = extra.to_uppercase();
+            let invisible = upper_extra.contains("INVISIBLE");
+            let is_virtual = upper_extra.contains("VIRTUAL GENERATED");
+            let generation_expression = if is_virtual || upper_extra.contains("STORED GENERATED") {
+                // mysql 8.0 escapes quotes in GENERATION_EXPRESSION, e.g.: concat(`a`,_utf8mb4\'_\')
+                Some(Self::get_str_with_null(&row, "GENERATION_EXPRESSION")?.replace("\\'", "'"))
+            } else {
+                None
+            };
+            let column_default = if generation_expression.is_some() {
+                // generated columns can not have default values
+                None
+            } else if let Some(column_default_str) = row.get("COLUMN_DEFAULT") {
                 Some(
                     self.parse_column_default(&db, &tb, &column_name, column_default_str, &extra)
                         .await?,
@@ -168,6 +182,9 @@ impl MysqlStructFetcher {
                 character_set_name: Self::get_str_with_null(&row, "CHARACTER_SET_NAME")?,
                 collation_name: Self::get_str_with_null(&row, "COLLATION_NAME")?,
                 generated: None,
+                generation_expression,
+                is_virtual,
+                invisible,
             };
 
             if let Some(table) = results.get_mut(&tb) {
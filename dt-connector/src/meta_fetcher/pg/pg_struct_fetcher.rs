@@ -332,6 +332,8 @@ impl PgStructFetcher {
                 c.numeric_scale,
                 c.is_identity,
                 c.identity_generation,
+                c.is_generated,
+                c.generation_expression,
                 c.ordinal_position
             FROM information_schema.columns c
             JOIN information_schema.tables t 
@@ -362,12 +364,20 @@ impl PgStructFetcher {
             let column_default = row
                 .get::<Option<String>, _>("column_default")
                 .map(ColumnDefault::Literal);
+            // pg 12+: GENERATED ALWAYS AS (expr) STORED, is_generated: ALWAYS / NEVER
+            let generation_expression =
+                if Self::get_str_with_null(&row, "is_generated")?.to_uppercase() == "ALWAYS" {
+                    row.get::<Option<String>, _>("generation_expression")
+                } else {
+                    None
+                };
             let column = Column {
                 column_name: Self::get_str_with_null(&row, "column_name")?,
                 ordinal_position: ordinal_position as u32,
                 column_default,
                 is_nullable,
                 generated: generation_rule,
+                generation_expression,
                 ..Default::default()
             };
 
@@ -28,6 +28,8 @@ pub struct RdbQueryBuilder<'a> {
     ignore_cols: Option<&'a HashSet<String>>,
     pg_tb_meta: Option<&'a PgTbMeta>,
     mysql_tb_meta: Option<&'a MysqlTbMeta>,
+    partial_upsert: bool,
+    mysql_row_alias: bool,
 }
 
 impl RdbQueryBuilder<'_> {
@@ -42,6 +44,8 @@ impl RdbQueryBuilder<'_> {
             mysql_tb_meta: Some(tb_meta),
             db_type: DbType::Mysql,
             ignore_cols,
+            partial_upsert: false,
+            mysql_row_alias: false,
         }
     }
 
@@ -56,6 +60,48 @@ impl RdbQueryBuilder<'_> {
             mysql_tb_meta: None,
             db_type: DbType::Pg,
             ignore_cols,
+            partial_upsert: false,
+            mysql_row_alias: false,
+        }
+    }
+
+    /// if enabled, a replaced row with part of the cols only updates the given cols
+    /// of the existing row instead of setting the missing cols to NULL
+    #[inline(always)]
+    pub fn with_partial_upsert(mut self, partial_upsert: bool) -> Self {
+        self.partial_upsert = partial_upsert;
+        self
+    }
+
+    /// if enabled, MySQL upserts refer to the inserted values by row alias (8.0.19+),
+    /// otherwise by VALUES(col), which is deprecated since 8.0.20
+    #[inline(always)]
+    pub fn with_mysql_row_alias(mut self, mysql_row_alias: bool) -> Self {
+        self.mysql_row_alias = mysql_row_alias;
+        self
+    }
+
+    /// the row alias is not supported by MySQL before 8.0.19 or MariaDB, e.g. 5.7.44, 10.6.12-MariaDB
+    pub fn supports_mysql_row_alias(version: &str) -> bool {
+        if version.to_lowercase().contains("mariadb") {
+            return false;
+        }
+        let numbers: Vec<u32> = version
+            .split(|c: char| !c.is_ascii_digit())
+            .take(3)
+            .map_while(|i| i.parse().ok())
+            .collect();
+        numbers.len() == 3 && (numbers[0], numbers[1], numbers[2]) >= (8, 0, 19)
+    }
+
+    /// whether the row is replaced with part of the cols, see with_partial_upsert
+    pub fn is_partial_upsert(&self, row_data: &RowData) -> bool {
+        match &row_data.after {
+            Some(after) if self.partial_upsert => self
+                .get_insert_cols()
+                .iter()
+                .any(|col| !after.contains_key(col)),
+            _ => false,
         }
     }
 
@@ -175,9 +221,10 @@ impl RdbQueryBuilder<'_> {
         let mut malloc_size = 0;
         let mut placeholder_index = 1;
         let mut row_values = Vec::new();
+        let insert_cols = self.get_insert_cols();
         for _ in 0..batch_size {
             let mut col_values = Vec::new();
-            for col in self.rdb_tb_meta.cols.iter() {
+            for col in insert_cols.iter() {
                 col_values.push(self.get_placeholder(placeholder_index, col)?);
                 placeholder_index += 1;
             }
@@ -188,7 +235,7 @@ impl RdbQueryBuilder<'_> {
             "INSERT INTO {}.{}({}) VALUES{}",
             self.escape(&self.rdb_tb_meta.schema),
             self.escape(&self.rdb_tb_meta.tb),
-            self.escape_cols(&self.rdb_tb_meta.cols).join(","),
+            self.escape_cols(&insert_cols).join(","),
             row_values.join(",")
         );
 
@@ -197,7 +244,7 @@ impl RdbQueryBuilder<'_> {
         for row_data in data.iter().skip(start_index).take(batch_size) {
             malloc_size += row_data.data_size;
             let after = row_data.after.as_ref().unwrap();
-            for col_name in self.rdb_tb_meta.cols.iter() {
+            for col_name in insert_cols.iter() {
                 cols.push(col_name.clone());
                 binds.push(after.get(col_name));
             }
@@ -214,13 +261,17 @@ impl RdbQueryBuilder<'_> {
         row_data: &'a RowData,
         placeholder: bool,
     ) -> anyhow::Result<RdbQueryInfo<'a>> {
+        if self.is_partial_upsert(row_data) {
+            return self.get_upsert_query(row_data, placeholder);
+        }
+
         let mut query_info = self.get_insert_query(row_data, placeholder)?;
         if self.db_type == DbType::Pg {
             let mut index = query_info.cols.len() + 1;
             let after = row_data.after.as_ref().unwrap();
             let mut set_pairs = Vec::new();
             for col in self.rdb_tb_meta.cols.iter() {
-                if self.rdb_tb_meta.id_cols.contains(col) {
+                if self.rdb_tb_meta.id_cols.contains(col) || self.is_generated_col(col) {
                     continue;
                 }
                 let sql_value = self.get_sql_value(index, col, &after.get(col), placeholder)?;
@@ -244,23 +295,92 @@ impl RdbQueryBuilder<'_> {
         Ok(query_info)
     }
 
+    /// the row has part of the cols, e.g. mapped from HSET of redis,
+    /// insert it or update the given cols of the existing row, other cols are kept.
+    /// MySQL: INSERT ... AS new ON DUPLICATE KEY UPDATE if mysql_row_alias, otherwise
+    /// INSERT ... ON DUPLICATE KEY UPDATE col=VALUES(col)
+    fn get_upsert_query<'a>(
+        &self,
+        row_data: &'a RowData,
+        placeholder: bool,
+    ) -> anyhow::Result<RdbQueryInfo<'a>> {
+        let after = row_data.after.as_ref().unwrap();
+        let insert_cols = self
+            .get_insert_cols()
+            .into_iter()
+            .filter(|col| after.contains_key(col))
+            .collect();
+        let mut query_info = self.get_insert_query_by_cols(row_data, insert_cols, placeholder)?;
+
+        let mut set_pairs = Vec::new();
+        for col in query_info.cols.iter() {
+            if self.rdb_tb_meta.id_cols.contains(col) {
+                continue;
+            }
+            let col = self.escape(col);
+            if self.db_type == DbType::Pg {
+                set_pairs.push(format!("{}=EXCLUDED.{}", col, col));
+            } else if self.mysql_row_alias {
+                set_pairs.push(format!("{}=new.{}", col, col));
+            } else {
+                set_pairs.push(format!("{}=VALUES({})", col, col));
+            }
+        }
+
+        if self.db_type == DbType::Pg {
+            let action = if set_pairs.is_empty() {
+                "NOTHING".to_string()
+            } else {
+                format!("UPDATE SET {}", set_pairs.join(","))
+            };
+            query_info.sql = format!(
+                "{} ON CONFLICT ({}) DO {}",
+                query_info.sql,
+                SqlUtil::escape_cols(&self.rdb_tb_meta.id_cols, &self.db_type).join(","),
+                action
+            );
+        } else if set_pairs.is_empty() {
+            query_info.sql = format!(
+                "INSERT IGNORE{}",
+                query_info.sql.trim_start_matches("INSERT")
+            );
+        } else {
+            let row_alias = if self.mysql_row_alias { " AS new" } else { "" };
+            query_info.sql = format!(
+                "{}{} ON DUPLICATE KEY UPDATE {}",
+                query_info.sql,
+                row_alias,
+                set_pairs.join(",")
+            );
+        }
+        Ok(query_info)
+    }
+
     fn get_insert_query<'a>(
         &self,
         row_data: &'a RowData,
         placeholder: bool,
+    ) -> anyhow::Result<RdbQueryInfo<'a>> {
+        self.get_insert_query_by_cols(row_data, self.get_insert_cols(), placeholder)
+    }
+
+    fn get_insert_query_by_cols<'a>(
+        &self,
+        row_data: &'a RowData,
+        insert_cols: Vec<String>,
+        placeholder: bool,
     ) -> anyhow::Result<RdbQueryInfo<'a>> {
         let mut cols = Vec::new();
         let mut binds = Vec::new();
         let after = row_data.after.as_ref().unwrap();
-        for col_name in self.rdb_tb_meta.cols.iter() {
-            cols.push(col_name.clone());
-            binds.push(after.get(col_name));
+        for col_name in insert_cols {
+            binds.push(after.get(&col_name));
+            cols.push(col_name);
         }
 
         let mut col_values = Vec::new();
-        for i in 0..self.rdb_tb_meta.cols.len() {
-            let sql_value =
-                self.get_sql_value(i + 1, &self.rdb_tb_meta.cols[i], &binds[i], placeholder)?;
+        for i in 0..cols.len() {
+            let sql_value = self.get_sql_value(i + 1, &cols[i], &binds[i], placeholder)?;
             col_values.push(sql_value);
         }
 
@@ -268,7 +388,7 @@ impl RdbQueryBuilder<'_> {
             "INSERT INTO {}.{}({}) VALUES({})",
             self.escape(&self.rdb_tb_meta.schema),
             self.escape(&self.rdb_tb_meta.tb),
-            self.escape_cols(&self.rdb_tb_meta.cols).join(","),
+            self.escape_cols(&cols).join(","),
             col_values.join(",")
         );
 
@@ -281,7 +401,19 @@ impl RdbQueryBuilder<'_> {
         placeholder: bool,
     ) -> anyhow::Result<RdbQueryInfo<'a>> {
         let before = row_data.before.as_ref().unwrap();
-        let (where_sql, not_null_cols) = self.get_where_info(1, before, placeholder)?;
+        let (where_sql, not_null_cols) = if row_data.partial_key {
+            // missing key cols are not used as conditions, only for rows marked by converters
+            let key_cols: Vec<String> = self
+                .rdb_tb_meta
+                .id_cols
+                .iter()
+                .filter(|col| before.contains_key(*col))
+                .cloned()
+                .collect();
+            self.get_where_info_by_cols(1, &key_cols, before, placeholder)?
+        } else {
+            self.get_where_info(1, before, placeholder)?
+        };
         let mut sql = format!(
             "DELETE FROM {}.{} WHERE {}",
             self.escape(&self.rdb_tb_meta.schema),
@@ -313,6 +445,9 @@ impl RdbQueryBuilder<'_> {
         let mut set_cols = Vec::new();
         let mut set_pairs = Vec::new();
         for (col, _) in after.iter() {
+            if self.is_generated_col(col) {
+                continue;
+            }
             set_cols.push(col.clone());
             let sql_value = self.get_sql_value(index, col, &after.get(col), placeholder)?;
             set_pairs.push(format!("{}={}", self.escape(col), sql_value));
@@ -432,15 +567,25 @@ impl RdbQueryBuilder<'_> {
     }
 
     fn get_where_info(
+        &self,
+        index: usize,
+        col_value_map: &HashMap<String, ColValue>,
+        placeholder: bool,
+    ) -> anyhow::Result<(String, Vec<String>)> {
+        self.get_where_info_by_cols(index, &self.rdb_tb_meta.id_cols, col_value_map, placeholder)
+    }
+
+    fn get_where_info_by_cols(
         &self,
         mut index: usize,
+        cols: &[String],
         col_value_map: &HashMap<String, ColValue>,
         placeholder: bool,
     ) -> anyhow::Result<(String, Vec<String>)> {
         let mut where_sql = String::new();
         let mut not_null_cols = Vec::new();
 
-        for col in self.rdb_tb_meta.id_cols.iter() {
+        for col in cols.iter() {
             if !where_sql.is_empty() {
                 where_sql += " AND";
             }
@@ -578,6 +723,24 @@ impl RdbQueryBuilder<'_> {
         Ok("?".to_string())
     }
 
+    /// generated cols are computed by target, they should NOT be written
+    fn get_insert_cols(&self) -> Vec<String> {
+        if self.rdb_tb_meta.generated_cols.is_empty() {
+            return self.rdb_tb_meta.cols.clone();
+        }
+        self.rdb_tb_meta
+            .cols
+            .iter()
+            .filter(|col| !self.is_generated_col(col))
+            .cloned()
+            .collect()
+    }
+
+    #[inline(always)]
+    fn is_generated_col(&self, col: &str) -> bool {
+        self.rdb_tb_meta.generated_cols.iter().any(|i| i == col)
+    }
+
     fn escape(&self, origin: &str) -> String {
         SqlUtil::escape_by_db_type(origin, &self.db_type)
     }
@@ -586,3 +749,223 @@ impl RdbQueryBuilder<'_> {
         SqlUtil::escape_cols(cols, &self.db_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{
+        col_value::ColValue,
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+        pg::pg_tb_meta::PgTbMeta,
+        rdb_tb_meta::RdbTbMeta,
+        row_data::RowData,
+        row_type::RowType,
+    };
+
+    use super::RdbQueryBuilder;
+
+    fn new_rdb_tb_meta() -> RdbTbMeta {
+        RdbTbMeta {
+            schema: "app".into(),
+            tb: "users".into(),
+            id_cols: vec!["key".into()],
+            key_map: HashMap::from([("primary".into(), vec!["key".into()])]),
+            cols: vec!["key".into(), "name".into(), "age".into()],
+            ..Default::default()
+        }
+    }
+
+    fn new_mysql_tb_meta() -> MysqlTbMeta {
+        let basic = new_rdb_tb_meta();
+        let col_type = MysqlColType::Varchar {
+            length: 255,
+            charset: "utf8mb4".into(),
+        };
+        MysqlTbMeta {
+            col_type_map: basic
+                .cols
+                .iter()
+                .map(|i| (i.clone(), col_type.clone()))
+                .collect(),
+            basic,
+        }
+    }
+
+    fn new_pg_tb_meta() -> PgTbMeta {
+        PgTbMeta {
+            basic: new_rdb_tb_meta(),
+            oid: 0,
+            col_type_map: HashMap::new(),
+        }
+    }
+
+    fn new_insert_row(cols: &[(&str, &str)]) -> RowData {
+        let after = cols
+            .iter()
+            .map(|(k, v)| (k.to_string(), ColValue::String(v.to_string())))
+            .collect();
+        RowData::new(
+            "app".into(),
+            "users".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        )
+    }
+
+    #[test]
+    fn test_get_replace_query() {
+        let tb_meta = new_mysql_tb_meta();
+        let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
+
+        let row_data = new_insert_row(&[("key", "user:1"), ("name", "a"), ("age", "10")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            "REPLACE INTO `app`.`users`(`key`,`name`,`age`) VALUES('user:1','a','10');"
+        );
+
+        // missing cols are replaced by NULL by default
+        let row_data = new_insert_row(&[("key", "user:1"), ("age", "11")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            "REPLACE INTO `app`.`users`(`key`,`name`,`age`) VALUES('user:1',NULL,'11');"
+        );
+    }
+
+    #[test]
+    fn test_get_mysql_partial_upsert_query() {
+        let tb_meta = new_mysql_tb_meta();
+        let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None)
+            .with_partial_upsert(true)
+            .with_mysql_row_alias(true);
+
+        // a row with all cols is still replaced
+        let row_data = new_insert_row(&[("key", "user:1"), ("name", "a"), ("age", "10")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            "REPLACE INTO `app`.`users`(`key`,`name`,`age`) VALUES('user:1','a','10');"
+        );
+
+        // only the given cols are upserted
+        let row_data = new_insert_row(&[("key", "user:1"), ("age", "11")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO `app`.`users`(`key`,`age`) VALUES('user:1','11') AS new ON DUPLICATE KEY UPDATE `age`=new.`age`;"
+        );
+
+        let row_data = new_insert_row(&[("key", "user:1")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            "INSERT IGNORE INTO `app`.`users`(`key`) VALUES('user:1');"
+        );
+
+        // servers without row alias
+        let query_builder =
+            RdbQueryBuilder::new_for_mysql(&tb_meta, None).with_partial_upsert(true);
+        let row_data = new_insert_row(&[("key", "user:1"), ("age", "11")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO `app`.`users`(`key`,`age`) VALUES('user:1','11') ON DUPLICATE KEY UPDATE `age`=VALUES(`age`);"
+        );
+    }
+
+    #[test]
+    fn test_supports_mysql_row_alias() {
+        for (version, expected) in [
+            ("8.0.19", true),
+            ("8.4.0-log", true),
+            ("9.1.0", true),
+            ("8.0.18", false),
+            ("5.7.44-log", false),
+            ("10.6.12-MariaDB-1:10.6.12+maria~ubu2004", false),
+            ("", false),
+        ] {
+            assert_eq!(
+                RdbQueryBuilder::supports_mysql_row_alias(version),
+                expected,
+                "{}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_pg_partial_upsert_query() {
+        let tb_meta = new_pg_tb_meta();
+        let query_builder = RdbQueryBuilder::new_for_pg(&tb_meta, None).with_partial_upsert(true);
+
+        let row_data = new_insert_row(&[("key", "user:1"), ("name", "a"), ("age", "11")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            r#"INSERT INTO "app"."users"("key","name","age") VALUES('user:1','a','11') ON CONFLICT ("key") DO UPDATE SET "name"='a',"age"='11';"#
+        );
+
+        let row_data = new_insert_row(&[("key", "user:1"), ("age", "11")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            r#"INSERT INTO "app"."users"("key","age") VALUES('user:1','11') ON CONFLICT ("key") DO UPDATE SET "age"=EXCLUDED."age";"#
+        );
+
+        let row_data = new_insert_row(&[("key", "user:1")]);
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            r#"INSERT INTO "app"."users"("key") VALUES('user:1') ON CONFLICT ("key") DO NOTHING;"#
+        );
+    }
+
+    #[test]
+    fn test_get_delete_query_by_part_of_key() {
+        let mut tb_meta = new_mysql_tb_meta();
+        tb_meta.basic.id_cols = vec!["key".into(), "name".into()];
+        tb_meta.basic.key_map =
+            HashMap::from([("primary".into(), vec!["key".into(), "name".into()])]);
+        let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
+
+        let before = HashMap::from([
+            ("key".to_string(), ColValue::String("user:1".into())),
+            ("name".to_string(), ColValue::None),
+        ]);
+        let row_data = RowData::new(
+            "app".into(),
+            "users".into(),
+            RowType::Delete,
+            Some(before),
+            None,
+        );
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            "DELETE FROM `app`.`users` WHERE `key` = 'user:1' AND `name` IS NULL;"
+        );
+
+        // an ordinary delete missing key cols is not widened
+        let before = HashMap::from([("key".to_string(), ColValue::String("user:1".into()))]);
+        let mut row_data = RowData::new(
+            "app".into(),
+            "users".into(),
+            RowType::Delete,
+            Some(before),
+            None,
+        );
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(
+            sql,
+            "DELETE FROM `app`.`users` WHERE `key` = 'user:1' AND `name` IS NULL;"
+        );
+
+        // all rows of the key are deleted if marked by converters
+        row_data.partial_key = true;
+        let sql = query_builder.get_query_sql(&row_data, true).unwrap();
+        assert_eq!(sql, "DELETE FROM `app`.`users` WHERE `key` = 'user:1';");
+    }
+}
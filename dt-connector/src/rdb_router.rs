@@ -1,25 +1,50 @@
-use anyhow::Ok;
+use anyhow::{bail, Ok};
 use dt_common::{
     config::{
         config_enums::DbType, config_token_parser::ConfigTokenParser, router_config::RouterConfig,
     },
+    error::Error,
     meta::{
         ddl_meta::{ddl_data::DdlData, ddl_statement::DdlStatement},
+        redis::{
+            command::key_parser::KeyParser,
+            redis_entry::RedisEntry,
+            redis_object::{RedisCmd, RedisObject, RedisString},
+        },
         struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
     },
     utils::sql_util::SqlUtil,
 };
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
 use dt_common::meta::{col_value::ColValue, row_data::RowData};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 type SchemaMap = HashMap<String, String>;
 type TbMap = HashMap<(String, String), (String, String)>;
 type TbColMap = HashMap<(String, String), HashMap<String, String>>;
+// HashMap<(src_schema, src_tb), Option<(dst_schema, dst_tb, index of the matched route)>>
+type TbPatternCache = HashMap<(String, String), Option<(String, String, usize)>>;
 
 const JSON_PREFIX: &str = "json:";
 
+/// routes tables whose "schema.tb" fully matches src to dst_db.dst_tb, dst_db / dst_tb may refer to
+/// capture groups of src, eg: $1, ${1}, ${name}. If shard_col is set, "src_schema.src_tb" is
+/// injected as shard_col so rows merged from many shards keep unique keys.
+#[derive(Debug, Clone)]
+pub struct TbPatternRoute {
+    pub src: Regex,
+    pub dst_db: String,
+    pub dst_tb: String,
+    pub shard_col: String,
+    // set in reversed routers, rows are routed back to the source table stored in shard_col
+    pub reversed: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RdbRouter {
     // HashMap<src_schema, dst_schema>
@@ -30,6 +55,13 @@ pub struct RdbRouter {
     pub col_map: TbColMap,
     // HashMap<(src_schema, src_tb), String>
     pub topic_map: HashMap<(String, String), String>,
+    // Vec<(src_key_prefix, dst_key_prefix)>, the first matched prefix is used
+    pub key_prefix_map: Vec<(String, String)>,
+    // checked after tb_map and before schema_map, the first matched route is used
+    #[serde(skip)]
+    pub tb_pattern_map: Vec<TbPatternRoute>,
+    #[serde(skip)]
+    tb_pattern_cache: Arc<RwLock<TbPatternCache>>,
 }
 
 impl RdbRouter {
@@ -40,16 +72,24 @@ impl RdbRouter {
                 tb_map,
                 col_map,
                 topic_map,
+                key_prefix_map,
+                tb_pattern_map,
+                ..
             } => {
                 let schema_map = Self::parse_schema_map(schema_map, db_type)?;
                 let tb_map = Self::parse_tb_map(tb_map, db_type)?;
                 let col_map = Self::parse_col_map(col_map)?;
                 let topic_map = Self::parse_topic_map(topic_map, db_type)?;
+                let key_prefix_map = Self::parse_key_prefix_map(key_prefix_map)?;
+                let tb_pattern_map = Self::parse_tb_pattern_map(tb_pattern_map)?;
                 Ok(Self {
                     schema_map,
                     tb_map,
                     col_map,
                     topic_map,
+                    key_prefix_map,
+                    tb_pattern_map,
+                    tb_pattern_cache: Arc::default(),
                 })
             }
         }
@@ -62,14 +102,78 @@ impl RdbRouter {
         schema
     }
 
-    pub fn get_tb_map<'a>(&'a self, schema: &'a str, tb: &'a str) -> (&'a str, &'a str) {
+    pub fn get_tb_map(&self, schema: &str, tb: &str) -> (String, String) {
         if let Some((dst_schema, dst_tb)) = self.tb_map.get(&(schema.into(), tb.into())) {
+            return (dst_schema.into(), dst_tb.into());
+        }
+        if let Some((dst_schema, dst_tb, _)) = self.get_tb_pattern_map(schema, tb) {
             return (dst_schema, dst_tb);
         }
         if let Some(dst_schema) = self.schema_map.get(schema) {
-            return (dst_schema, tb);
+            return (dst_schema.into(), tb.into());
+        }
+        (schema.into(), tb.into())
+    }
+
+    /// for reversed routers, returns the source table of a target table, fails if the target
+    /// table is routed by tb_pattern_map, since it may be merged from many source tables
+    pub fn get_reversed_tb_map(&self, schema: &str, tb: &str) -> anyhow::Result<(String, String)> {
+        if !self.tb_map.contains_key(&(schema.into(), tb.into())) {
+            let dst = format!("{}.{}", schema, tb);
+            if self
+                .tb_pattern_map
+                .iter()
+                .any(|i| i.reversed && i.src.is_match(&dst))
+            {
+                bail! {Error::ConfigError(format!(
+                    "source table of {} can not be resolved since it is routed by tb_pattern_map",
+                    dst
+                ))}
+            }
+        }
+        Ok(self.get_tb_map(schema, tb))
+    }
+
+    /// returns (dst_schema, dst_tb, route) of the first matched pattern route,
+    /// reversed routes are not matched by names since the source table is stored in rows
+    fn get_tb_pattern_map(
+        &self,
+        schema: &str,
+        tb: &str,
+    ) -> Option<(String, String, &TbPatternRoute)> {
+        let key = (schema.to_string(), tb.to_string());
+        if let Some(cached) = self.tb_pattern_cache.read().unwrap().get(&key) {
+            return cached.as_ref().map(|(dst_schema, dst_tb, i)| {
+                (dst_schema.clone(), dst_tb.clone(), &self.tb_pattern_map[*i])
+            });
         }
-        (schema, tb)
+
+        let matched = self.match_tb_pattern_map(schema, tb);
+        self.tb_pattern_cache
+            .write()
+            .unwrap()
+            .insert(key, matched.clone());
+        matched.map(|(dst_schema, dst_tb, i)| (dst_schema, dst_tb, &self.tb_pattern_map[i]))
+    }
+
+    fn match_tb_pattern_map(&self, schema: &str, tb: &str) -> Option<(String, String, usize)> {
+        let src = format!("{}.{}", schema, tb);
+        for (i, route) in self.tb_pattern_map.iter().enumerate() {
+            if route.reversed {
+                continue;
+            }
+            if let Some(caps) = route.src.captures(&src) {
+                let mut dst_schema = String::new();
+                let mut dst_tb = String::new();
+                caps.expand(&route.dst_db, &mut dst_schema);
+                caps.expand(&route.dst_tb, &mut dst_tb);
+                if dst_tb.is_empty() {
+                    dst_tb = tb.into();
+                }
+                return Some((dst_schema, dst_tb, i));
+            }
+        }
+        None
     }
 
     pub fn get_col_map(&self, schema: &str, tb: &str) -> Option<&HashMap<String, String>> {
@@ -93,13 +197,13 @@ impl RdbRouter {
         let mut reverse_tb_map = HashMap::new();
         let mut reverse_tb_col_map = HashMap::new();
 
-        for (src_schema_tb, col_map) in self.col_map.iter() {
+        for ((src_schema, src_tb), col_map) in self.col_map.iter() {
             let mut reverse_col_map = HashMap::new();
             for (src_col, dst_col) in col_map.iter() {
                 reverse_col_map.insert(dst_col.into(), src_col.into());
             }
-            let dst_tb = self.tb_map.get(src_schema_tb).unwrap();
-            reverse_tb_col_map.insert(dst_tb.clone(), reverse_col_map);
+            let dst_tb = self.get_tb_map(src_schema, src_tb);
+            reverse_tb_col_map.insert(dst_tb, reverse_col_map);
         }
 
         for (src_tb, dst_tb) in self.tb_map.iter() {
@@ -110,21 +214,62 @@ impl RdbRouter {
             reverse_schema_map.insert(dst_db.to_owned(), src_schema.to_owned());
         }
 
+        // source tables of pattern routes can not be resolved by names, rows of routes with
+        // shard_col are routed back by the source table stored in shard_col
+        let reverse_tb_pattern_map = self
+            .tb_pattern_map
+            .iter()
+            .filter(|i| !i.reversed)
+            .map(|i| TbPatternRoute {
+                src: Self::template_to_regex(&i.dst_db, &i.dst_tb),
+                dst_db: String::new(),
+                dst_tb: String::new(),
+                shard_col: i.shard_col.clone(),
+                reversed: true,
+            })
+            .collect();
+
         Self {
             schema_map: reverse_schema_map,
             tb_map: reverse_tb_map,
             col_map: reverse_tb_col_map,
             // topic_map should not be reversed
             topic_map: self.topic_map.clone(),
+            key_prefix_map: self
+                .key_prefix_map
+                .iter()
+                .map(|(src, dst)| (dst.clone(), src.clone()))
+                .collect(),
+            tb_pattern_map: reverse_tb_pattern_map,
+            tb_pattern_cache: Arc::default(),
+        }
+    }
+
+    /// reversed routers of checkers route rows back to source tables, which are resolved by
+    /// shard_col for pattern routes
+    pub fn check_reversible(&self) -> anyhow::Result<()> {
+        for route in self.tb_pattern_map.iter() {
+            if !route.reversed && route.shard_col.is_empty() {
+                bail! {Error::ConfigError(format!(
+                    "tb_pattern_map src: {} has no shard_col, which is required for check tasks",
+                    route.src
+                ))}
+            }
         }
+        Ok(())
     }
 
     pub fn route_row(&self, mut row_data: RowData) -> RowData {
         // tb map
         let (schema, tb) = (row_data.schema.clone(), row_data.tb.clone());
         let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
-        row_data.schema = dst_schema.to_string();
-        row_data.tb = dst_tb.to_string();
+        row_data.schema = dst_schema;
+        row_data.tb = dst_tb;
+
+        // shard col of pattern routes
+        if !self.tb_pattern_map.is_empty() {
+            self.route_shard_col(&mut row_data, &schema, &tb);
+        }
 
         // col map
         let col_map = self.get_col_map(&schema, &tb);
@@ -157,7 +302,76 @@ impl RdbRouter {
         row_data
     }
 
+    /// shard_col of the pattern route matched by the source table
+    fn get_shard_col(&self, schema: &str, tb: &str) -> Option<String> {
+        if self.tb_map.contains_key(&(schema.into(), tb.into())) {
+            return None;
+        }
+        self.get_tb_pattern_map(schema, tb)
+            .map(|(_, _, route)| route.shard_col.clone())
+            .filter(|shard_col| !shard_col.is_empty())
+    }
+
+    fn route_shard_col(&self, row_data: &mut RowData, schema: &str, tb: &str) {
+        if self.tb_map.contains_key(&(schema.into(), tb.into())) {
+            return;
+        }
+
+        // injects "schema.tb" of the source table
+        if let Some((_, _, route)) = self.get_tb_pattern_map(schema, tb) {
+            if route.shard_col.is_empty() {
+                return;
+            }
+            let shard = ColValue::String(format!("{}.{}", schema, tb));
+            for col_values in [&mut row_data.before, &mut row_data.after]
+                .into_iter()
+                .flatten()
+            {
+                col_values.insert(route.shard_col.clone(), shard.clone());
+            }
+            row_data.refresh_data_size();
+            return;
+        }
+
+        // routes the row back to the source table stored in shard col, and removes shard col
+        let src = format!("{}.{}", schema, tb);
+        for route in self.tb_pattern_map.iter().filter(|i| i.reversed) {
+            if !route.src.is_match(&src) {
+                continue;
+            }
+            if route.shard_col.is_empty() {
+                return;
+            }
+
+            let shard = [&row_data.after, &row_data.before]
+                .into_iter()
+                .flatten()
+                .find_map(|col_values| col_values.get(&route.shard_col))
+                .and_then(|col_value| col_value.to_option_string());
+            if let Some((src_schema, src_tb)) = shard.as_ref().and_then(|i| i.split_once('.')) {
+                row_data.schema = src_schema.into();
+                row_data.tb = src_tb.into();
+                for col_values in [&mut row_data.before, &mut row_data.after]
+                    .into_iter()
+                    .flatten()
+                {
+                    col_values.remove(&route.shard_col);
+                }
+                row_data.refresh_data_size();
+            }
+            return;
+        }
+    }
+
     pub fn route_ddl(&self, mut ddl_data: DdlData) -> DdlData {
+        // col map, only the parsed alter operations are routed
+        let (src_schema, src_tb) = ddl_data.get_schema_tb();
+        if let Some(col_map) = self.get_col_map(&src_schema, &src_tb) {
+            for operation in ddl_data.alter_operations.iter_mut() {
+                operation.route_cols(col_map);
+            }
+        }
+
         match &mut ddl_data.statement {
             DdlStatement::MysqlAlterTableRename(_)
             | DdlStatement::PgAlterTableRename(_)
@@ -167,17 +381,17 @@ impl RdbRouter {
                 let (dst_schema, dst_tb) = self.get_tb_map(&src_schema, &src_tb);
                 let (dst_new_schema, dst_new_tb) = self.get_tb_map(&src_new_schema, &src_new_tb);
                 ddl_data.statement.route_rename_table(
-                    dst_schema.into(),
-                    dst_tb.into(),
-                    dst_new_schema.into(),
-                    dst_new_tb.into(),
+                    dst_schema,
+                    dst_tb,
+                    dst_new_schema,
+                    dst_new_tb,
                 );
             }
 
             _ => {
                 let (src_schema, src_tb) = ddl_data.get_schema_tb();
                 let (dst_schema, dst_tb) = self.get_tb_map(&src_schema, &src_tb);
-                ddl_data.statement.route(dst_schema.into(), dst_tb.into());
+                ddl_data.statement.route(dst_schema, dst_tb);
             }
         }
 
@@ -191,7 +405,10 @@ impl RdbRouter {
             StructStatement::MysqlCreateTable(s) => {
                 let (schema, tb) = (s.table.database_name.clone(), s.table.table_name.clone());
                 let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
-                s.route(dst_schema, dst_tb)
+                s.route(&dst_schema, &dst_tb);
+                if let Some(shard_col) = self.get_shard_col(&schema, &tb) {
+                    s.add_shard_col(&shard_col);
+                }
             }
 
             StructStatement::MysqlCreateDatabase(s) => {
@@ -202,7 +419,10 @@ impl RdbRouter {
             StructStatement::PgCreateTable(s) => {
                 let (schema, tb) = (s.table.schema_name.clone(), s.table.table_name.clone());
                 let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
-                s.route(dst_schema, dst_tb)
+                s.route(&dst_schema, &dst_tb);
+                if let Some(shard_col) = self.get_shard_col(&schema, &tb) {
+                    s.add_shard_col(&shard_col);
+                }
             }
 
             StructStatement::PgCreateSchema(s) => {
@@ -210,6 +430,12 @@ impl RdbRouter {
                 s.route(&dst_schema)
             }
 
+            StructStatement::MongoCreateCollection(s) => {
+                let (schema, tb) = (s.db.clone(), s.tb.clone());
+                let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
+                s.route(&dst_schema, &dst_tb)
+            }
+
             _ => {}
         }
 
@@ -217,6 +443,177 @@ impl RdbRouter {
         struct_data
     }
 
+    /// route db by db_map and rewrite key prefixes by key_prefix_map,
+    /// keys of commands are located by key_parser
+    pub fn route_redis_entry(&self, mut entry: RedisEntry, key_parser: &KeyParser) -> RedisEntry {
+        if !self.schema_map.is_empty() {
+            if let Some(dst_db_id) = self.get_redis_db_map(entry.db_id) {
+                entry.db_id = dst_db_id;
+            }
+            if !entry.is_base {
+                self.route_redis_db_args(&mut entry.cmd);
+            }
+        }
+
+        if self.key_prefix_map.is_empty() {
+            return entry;
+        }
+
+        if !entry.is_base {
+            self.route_redis_cmd_keys(&mut entry.cmd, key_parser);
+            return entry;
+        }
+
+        if let Some(dst_key) = self.get_key_prefix_map(entry.key.as_bytes()) {
+            entry.key = RedisString::from(dst_key);
+            let key = entry.key.clone();
+            match &mut entry.value {
+                RedisObject::String(obj) => obj.key = key,
+                RedisObject::List(obj) => obj.key = key,
+                RedisObject::Hash(obj) => obj.key = key,
+                RedisObject::Set(obj) => obj.key = key,
+                RedisObject::Zset(obj) => obj.key = key,
+                RedisObject::Module(obj) => obj.key = key,
+                RedisObject::Stream(obj) => {
+                    obj.key = key;
+                    for cmd in obj.cmds.iter_mut() {
+                        self.route_redis_cmd_keys(cmd, key_parser);
+                    }
+                }
+                RedisObject::Unknown => {}
+            }
+        }
+        entry
+    }
+
+    fn route_redis_cmd_keys(&self, cmd: &mut RedisCmd, key_parser: &KeyParser) {
+        if cmd.key_indexes.is_empty() && cmd.parse_keys(key_parser).is_err() {
+            return;
+        }
+
+        // key_indexes are 1-based
+        for (i, key_index) in cmd.key_indexes.iter().enumerate() {
+            if let Some(dst_key) = self.get_key_prefix_map(&cmd.args[key_index - 1]) {
+                cmd.keys[i] = String::from_utf8_lossy(&dst_key).to_string();
+                cmd.args[key_index - 1] = dst_key;
+            }
+        }
+    }
+
+    /// db ids in args: MOVE key db, SWAPDB index1 index2, COPY source destination DB db
+    fn route_redis_db_args(&self, cmd: &mut RedisCmd) {
+        let db_arg_indexes: Vec<usize> = match cmd.get_name().to_lowercase().as_str() {
+            "move" => vec![2],
+            "swapdb" => vec![1, 2],
+            "copy" => cmd
+                .args
+                .iter()
+                .position(|i| i.eq_ignore_ascii_case(b"db"))
+                .map(|i| vec![i + 1])
+                .unwrap_or_default(),
+            _ => return,
+        };
+
+        for i in db_arg_indexes {
+            let dst_db_id = cmd
+                .args
+                .get(i)
+                .and_then(|arg| String::from_utf8_lossy(arg).parse().ok())
+                .and_then(|db_id| self.get_redis_db_map(db_id));
+            if let Some(dst_db_id) = dst_db_id {
+                cmd.args[i] = dst_db_id.to_string().into_bytes();
+            }
+        }
+    }
+
+    fn get_redis_db_map(&self, db_id: i64) -> Option<i64> {
+        self.schema_map
+            .get(&db_id.to_string())
+            .and_then(|i| i.parse().ok())
+    }
+
+    fn get_key_prefix_map(&self, key: &[u8]) -> Option<Vec<u8>> {
+        for (src, dst) in self.key_prefix_map.iter() {
+            if let Some(suffix) = key.strip_prefix(src.as_bytes()) {
+                let mut dst_key = dst.as_bytes().to_vec();
+                dst_key.extend_from_slice(suffix);
+                return Some(dst_key);
+            }
+        }
+        None
+    }
+
+    fn parse_key_prefix_map(config_str: &str) -> anyhow::Result<Vec<(String, String)>> {
+        // key_prefix_map="prod:":"staging:","tmp.":"temp."
+        let tokens = Self::parse_config(config_str, &DbType::Redis)?;
+        if tokens.len() % 2 != 0 {
+            bail! {Error::ConfigError(format!(
+                "invalid key_prefix_map: {}",
+                config_str
+            ))}
+        }
+        let mut results = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            results.push((tokens[i].to_string(), tokens[i + 1].to_string()));
+            i += 2;
+        }
+        Ok(results)
+    }
+
+    fn parse_tb_pattern_map(config_str: &str) -> anyhow::Result<Vec<TbPatternRoute>> {
+        let mut results = Vec::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct TbPatternRouteType {
+            src: String,
+            dst_db: String,
+            #[serde(default)]
+            dst_tb: String,
+            #[serde(default)]
+            shard_col: String,
+        }
+        // tb_pattern_map=json:[{"src":"db_(\\d+)\\.order_(\\d+)","dst_db":"orders","dst_tb":"order_all","shard_col":"src_shard"}]
+        let config: Vec<TbPatternRouteType> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            let src = match Regex::new(&format!("^(?:{})$", i.src)) {
+                std::result::Result::Ok(src) => src,
+                Err(e) => bail! {Error::ConfigError(format!(
+                    "invalid tb_pattern_map src: {}, error: {}",
+                    i.src, e
+                ))},
+            };
+            results.push(TbPatternRoute {
+                src,
+                dst_db: i.dst_db,
+                dst_tb: i.dst_tb,
+                shard_col: i.shard_col,
+                reversed: false,
+            });
+        }
+        Ok(results)
+    }
+
+    // builds the regex matching names generated by dst_db / dst_tb templates
+    fn template_to_regex(dst_db: &str, dst_tb: &str) -> Regex {
+        let group_ref = Regex::new(r"\$(\{[^}]*\}|[0-9A-Za-z_]+)").unwrap();
+        let to_regex = |template: &str| {
+            if template.is_empty() {
+                return ".+".to_string();
+            }
+            group_ref
+                .split(template)
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".+")
+        };
+        Regex::new(&format!("^{}\\.{}$", to_regex(dst_db), to_regex(dst_tb))).unwrap()
+    }
+
     fn parse_schema_map(config_str: &str, db_type: &DbType) -> anyhow::Result<SchemaMap> {
         // db_map=src_db_1:dst_db_1,src_db_2:dst_db_2
         let mut schema_map = HashMap::new();
@@ -303,7 +700,40 @@ impl RdbRouter {
 mod tests {
     use std::collections::HashMap;
 
-    use dt_common::config::{config_enums::DbType, router_config::RouterConfig};
+    use dt_common::{
+        config::{
+            config_enums::{DbType, ShardDdlModeEnum},
+            router_config::RouterConfig,
+        },
+        meta::{
+            col_value::ColValue,
+            ddl_meta::{
+                ddl_definition::{AlterTableOperation, ColumnPosition},
+                ddl_parser::DdlParser,
+            },
+            redis::{
+                command::key_parser::KeyParser,
+                redis_entry::RedisEntry,
+                redis_object::{RedisCmd, RedisObject, RedisString, StreamObject},
+            },
+            row_data::RowData,
+            row_type::RowType,
+            struct_meta::{
+                statement::{
+                    mysql_create_table_statement::MysqlCreateTableStatement,
+                    pg_create_table_statement::PgCreateTableStatement,
+                    struct_statement::StructStatement,
+                },
+                struct_data::StructData,
+                structure::{
+                    column::Column,
+                    constraint::{Constraint, ConstraintType},
+                    index::{Index, IndexColumn, IndexKind},
+                    table::Table,
+                },
+            },
+        },
+    };
 
     use super::{RdbRouter, TbColMap, TbMap};
 
@@ -424,6 +854,11 @@ mod tests {
             tb_map: tb_map_str.into(),
             col_map: col_map_str.into(),
             topic_map: topic_map.into(),
+            key_prefix_map: String::new(),
+            tb_pattern_map: String::new(),
+            shard_ddl_mode: ShardDdlModeEnum::None,
+            shard_ddl_max_held_items: 0,
+            shard_ddl_max_held_secs: 0,
         };
         let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
 
@@ -450,4 +885,382 @@ mod tests {
         assert_eq!(router.get_topic("db:1", "tb:2"), "test2");
         assert_eq!(router.get_topic("db:2", "tb:1"), "test");
     }
+
+    #[test]
+    fn test_route_redis_entry() {
+        let config = RouterConfig::Rdb {
+            schema_map: "0:1,2:3".into(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_prefix_map: r#""prod:":"staging:","prod.":"staging.",tmp:temp"#.into(),
+            tb_pattern_map: String::new(),
+            shard_ddl_mode: ShardDdlModeEnum::None,
+            shard_ddl_max_held_items: 0,
+            shard_ddl_max_held_secs: 0,
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Redis).unwrap();
+        let key_parser = KeyParser::new();
+
+        let route_cmd = |db_id: i64, args: &[&str]| -> (i64, String) {
+            let mut entry = RedisEntry::new();
+            entry.db_id = db_id;
+            entry.cmd = RedisCmd::from_str_args(args);
+            let entry = router.route_redis_entry(entry, &key_parser);
+            (entry.db_id, entry.cmd.to_string())
+        };
+        assert_eq!(
+            route_cmd(0, &["set", "prod:1", "prod:v"]),
+            (1, "set staging:1 prod:v".into())
+        );
+        assert_eq!(
+            route_cmd(2, &["mset", "prod.1", "v1", "other", "v2", "tmp1", "v3"]),
+            (3, "mset staging.1 v1 other v2 temp1 v3".into())
+        );
+        assert_eq!(
+            route_cmd(4, &["del", "prod:1"]),
+            (4, "del staging:1".into())
+        );
+        assert_eq!(
+            route_cmd(0, &["move", "prod:1", "2"]),
+            (1, "move staging:1 3".into())
+        );
+        assert_eq!(
+            route_cmd(0, &["swapdb", "0", "4"]),
+            (1, "swapdb 1 4".into())
+        );
+        assert_eq!(
+            route_cmd(0, &["copy", "prod:1", "prod:2", "DB", "2", "REPLACE"]),
+            (1, "copy staging:1 staging:2 DB 3 REPLACE".into())
+        );
+        assert_eq!(route_cmd(0, &["flushdb"]), (1, "flushdb".into()));
+
+        // rdb entry
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.db_id = 2;
+        entry.key = RedisString::from("prod:stream".to_string());
+        let mut stream = StreamObject::new();
+        stream.key = entry.key.clone();
+        stream.cmds = vec![
+            RedisCmd::from_str_args(&["xadd", "prod:stream", "1-0", "f", "v"]),
+            RedisCmd::from_str_args(&["xgroup", "create", "prod:stream", "g", "0"]),
+        ];
+        entry.value = RedisObject::Stream(stream);
+        let entry = router.route_redis_entry(entry, &key_parser);
+        assert_eq!(entry.db_id, 3);
+        assert_eq!(entry.key.to_string(), "staging:stream");
+        if let RedisObject::Stream(stream) = &entry.value {
+            assert_eq!(stream.key.to_string(), "staging:stream");
+            assert_eq!(stream.cmds[0].to_string(), "xadd staging:stream 1-0 f v");
+            assert_eq!(
+                stream.cmds[1].to_string(),
+                "xgroup create staging:stream g 0"
+            );
+        } else {
+            panic!("unexpected value")
+        }
+
+        // reverse
+        let reverse_router = router.reverse();
+        let mut entry = RedisEntry::new();
+        entry.db_id = 1;
+        entry.cmd = RedisCmd::from_str_args(&["set", "staging:1", "v"]);
+        let entry = reverse_router.route_redis_entry(entry, &key_parser);
+        assert_eq!(
+            (entry.db_id, entry.cmd.to_string()),
+            (0, "set prod:1 v".into())
+        );
+
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_prefix_map: "prod:staging,tmp".into(),
+            tb_pattern_map: String::new(),
+            shard_ddl_mode: ShardDdlModeEnum::None,
+            shard_ddl_max_held_items: 0,
+            shard_ddl_max_held_secs: 0,
+        };
+        assert!(RdbRouter::from_config(&config, &DbType::Redis).is_err());
+    }
+
+    #[test]
+    fn test_tb_pattern_map() {
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: "db_00.order_0000:orders.order_0000".into(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_prefix_map: String::new(),
+            tb_pattern_map: r#"json:[{"src":"db_(\\d+)\\.order_(\\d+)","dst_db":"orders","dst_tb":"order_all","shard_col":"src_shard"},{"src":"log_(?P<month>\\d+)\\..*","dst_db":"log_${month}_bak"}]"#.into(),
+            shard_ddl_mode: ShardDdlModeEnum::None,
+            shard_ddl_max_held_items: 0,
+            shard_ddl_max_held_secs: 0,
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
+
+        let get_tb_map = |schema: &str, tb: &str| router.get_tb_map(schema, tb);
+        assert_eq!(
+            get_tb_map("db_01", "order_0002"),
+            ("orders".into(), "order_all".into())
+        );
+        assert_eq!(
+            get_tb_map("log_202401", "tb_1"),
+            ("log_202401_bak".into(), "tb_1".into())
+        );
+        // exact tb_map first
+        assert_eq!(
+            get_tb_map("db_00", "order_0000"),
+            ("orders".into(), "order_0000".into())
+        );
+        // pattern matches are cached
+        assert!(router
+            .tb_pattern_cache
+            .read()
+            .unwrap()
+            .contains_key(&("db_01".into(), "order_0002".into())));
+        // not fully matched
+        assert_eq!(
+            get_tb_map("db_01", "order_0002_bak"),
+            ("db_01".into(), "order_0002_bak".into())
+        );
+
+        // shard col added to the created table and appended to the primary key
+        let struct_data = StructData {
+            schema: "db_01".into(),
+            statement: StructStatement::MysqlCreateTable(MysqlCreateTableStatement {
+                table: Table {
+                    database_name: "db_01".into(),
+                    table_name: "order_0002".into(),
+                    columns: vec![Column {
+                        column_name: "id".into(),
+                        ordinal_position: 1,
+                        column_type: "int".into(),
+                        column_key: "PRI".into(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                constraints: Vec::new(),
+                indexes: vec![Index {
+                    index_name: "uk_code".into(),
+                    index_kind: IndexKind::Unique,
+                    columns: vec![IndexColumn {
+                        column_name: "code".into(),
+                        seq_in_index: 1,
+                    }],
+                    ..Default::default()
+                }],
+            }),
+        };
+        match router.route_struct(struct_data).statement {
+            StructStatement::MysqlCreateTable(s) => {
+                assert_eq!(
+                    (s.table.database_name.as_str(), s.table.table_name.as_str()),
+                    ("orders", "order_all")
+                );
+                let shard_col = &s.table.columns[1];
+                assert_eq!(
+                    (
+                        shard_col.column_name.as_str(),
+                        shard_col.column_key.as_str()
+                    ),
+                    ("src_shard", "PRI")
+                );
+                assert_eq!(
+                    s.indexes[0].columns[1],
+                    IndexColumn {
+                        column_name: "src_shard".into(),
+                        seq_in_index: 2,
+                    }
+                );
+            }
+            _ => panic!("unexpected statement"),
+        }
+
+        let struct_data = StructData {
+            schema: "db_01".into(),
+            statement: StructStatement::PgCreateTable(PgCreateTableStatement {
+                table: Table {
+                    schema_name: "db_01".into(),
+                    table_name: "order_0002".into(),
+                    ..Default::default()
+                },
+                table_comments: Vec::new(),
+                column_comments: Vec::new(),
+                constraints: vec![Constraint {
+                    database_name: String::new(),
+                    schema_name: "db_01".into(),
+                    table_name: "order_0002".into(),
+                    constraint_name: "order_0002_code_key".into(),
+                    constraint_type: ConstraintType::Unique,
+                    definition: "UNIQUE (code) INCLUDE (name)".into(),
+                }],
+                indexes: vec![Index {
+                    index_name: "uk_lower_code".into(),
+                    index_kind: IndexKind::Unique,
+                    definition: "CREATE UNIQUE INDEX uk_lower_code ON db_01.order_0002 USING btree (lower(code))".into(),
+                    ..Default::default()
+                }],
+                sequences: Vec::new(),
+                sequence_owners: Vec::new(),
+            }),
+        };
+        match router.route_struct(struct_data).statement {
+            StructStatement::PgCreateTable(s) => {
+                assert_eq!(
+                    s.constraints[0].definition,
+                    r#"UNIQUE (code, "src_shard") INCLUDE (name)"#
+                );
+                assert_eq!(
+                    s.indexes[0].definition,
+                    r#"CREATE UNIQUE INDEX uk_lower_code ON db_01.order_0002 USING btree (lower(code), "src_shard")"#
+                );
+            }
+            _ => panic!("unexpected statement"),
+        }
+
+        // shard col injected
+        let mut after = HashMap::new();
+        after.insert("id".to_string(), ColValue::Long(1));
+        let row_data = RowData::new(
+            "db_01".into(),
+            "order_0002".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        let routed = router.route_row(row_data.clone());
+        assert_eq!(
+            (routed.schema.as_str(), routed.tb.as_str()),
+            ("orders", "order_all")
+        );
+        assert_eq!(
+            routed.after.as_ref().unwrap().get("src_shard"),
+            Some(&ColValue::String("db_01.order_0002".into()))
+        );
+
+        // routed back by shard col
+        let reverse_router = router.reverse();
+        let reversed = reverse_router.route_row(routed);
+        assert_eq!(reversed, row_data);
+        assert_eq!(
+            reverse_router.get_tb_map("orders", "order_0000"),
+            ("db_00".into(), "order_0000".into())
+        );
+        assert_eq!(
+            reverse_router
+                .get_reversed_tb_map("orders", "order_0000")
+                .unwrap(),
+            ("db_00".into(), "order_0000".into())
+        );
+        // source tables of pattern routes can not be resolved
+        assert!(reverse_router
+            .get_reversed_tb_map("orders", "order_all")
+            .is_err());
+        assert!(reverse_router
+            .get_reversed_tb_map("log_202401_bak", "tb_1")
+            .is_err());
+
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_prefix_map: String::new(),
+            tb_pattern_map: r#"json:[{"src":"db_(\\d+","dst_db":"orders"}]"#.into(),
+            shard_ddl_mode: ShardDdlModeEnum::None,
+            shard_ddl_max_held_items: 0,
+            shard_ddl_max_held_secs: 0,
+        };
+        assert!(RdbRouter::from_config(&config, &DbType::Mysql).is_err());
+    }
+
+    #[test]
+    fn test_tb_pattern_map_reversible() {
+        let build_router = |tb_pattern_map: &str| {
+            let config = RouterConfig::Rdb {
+                schema_map: String::new(),
+                tb_map: String::new(),
+                col_map: String::new(),
+                topic_map: String::new(),
+                key_prefix_map: String::new(),
+                tb_pattern_map: format!("json:{}", tb_pattern_map),
+                shard_ddl_mode: ShardDdlModeEnum::None,
+                shard_ddl_max_held_items: 0,
+                shard_ddl_max_held_secs: 0,
+            };
+            RdbRouter::from_config(&config, &DbType::Mysql).unwrap()
+        };
+
+        let router = build_router(
+            r#"[{"src":"db_(\\d+)\\.order_(\\d+)","dst_db":"orders","dst_tb":"order_all","shard_col":"src_shard"}]"#,
+        );
+        assert!(router.check_reversible().is_ok());
+        // shard_col is required even if tables are not merged
+        let router = build_router(
+            r#"[{"src":"db_(\\d+)\\.order_(\\d+)","dst_db":"orders_$1","dst_tb":"order_${2}"}]"#,
+        );
+        assert!(router.check_reversible().is_err());
+    }
+
+    #[test]
+    fn test_route_ddl_col_map() {
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: "db_1.tb_1:dst_db_1.dst_tb_1".into(),
+            col_map:
+                r#"json:[{"db":"db_1","tb":"tb_1","col_map":{"f_1":"dst_f_1","f_2":"dst_f_2"}}]"#
+                    .into(),
+            topic_map: String::new(),
+            key_prefix_map: String::new(),
+            tb_pattern_map: String::new(),
+            shard_ddl_mode: ShardDdlModeEnum::None,
+            shard_ddl_max_held_items: 0,
+            shard_ddl_max_held_secs: 0,
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
+
+        let sql = "ALTER TABLE db_1.tb_1 ADD COLUMN f_3 int AFTER f_1, DROP COLUMN f_2, RENAME COLUMN f_1 TO f_4, ADD INDEX idx_1 (f_1, f_3)";
+        let ddl_data = DdlParser::new(DbType::Mysql).parse(sql).unwrap();
+        let routed = router.route_ddl(ddl_data);
+        assert_eq!(
+            routed.get_schema_tb(),
+            ("dst_db_1".to_string(), "dst_tb_1".to_string())
+        );
+
+        let operations = &routed.alter_operations;
+        assert_eq!(operations.len(), 4);
+        match &operations[0] {
+            AlterTableOperation::AddColumn { column, position } => {
+                assert_eq!(column.column_name, "f_3");
+                assert_eq!(position, &Some(ColumnPosition::After("dst_f_1".into())));
+            }
+            _ => panic!("unexpected operation: {:?}", operations[0]),
+        }
+        assert_eq!(
+            operations[1],
+            AlterTableOperation::DropColumn {
+                column_name: "dst_f_2".into()
+            }
+        );
+        assert_eq!(
+            operations[2],
+            AlterTableOperation::RenameColumn {
+                old_column_name: "dst_f_1".into(),
+                new_column_name: "f_4".into()
+            }
+        );
+        match &operations[3] {
+            AlterTableOperation::AddIndex { index } => {
+                assert_eq!(
+                    index.columns,
+                    vec!["dst_f_1".to_string(), "f_3".to_string()]
+                )
+            }
+            _ => panic!("unexpected operation: {:?}", operations[3]),
+        }
+    }
 }
@@ -0,0 +1,807 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::{bail, Context};
+use dt_common::{
+    config::{config_enums::ShardDdlModeEnum, router_config::RouterConfig},
+    error::Error,
+    log_info, log_warn,
+    meta::{
+        ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
+        dt_data::DtData,
+        position::Position,
+        row_data::RowData,
+        syncer::Syncer,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::rdb_router::RdbRouter;
+
+type SchemaTb = (String, String);
+
+pub enum ShardItem {
+    Row(RowData, Position),
+    Ddl(DdlData, Position),
+}
+
+/// coordinates ddl of sharded source tables routed into the same target table,
+/// so that each ddl is applied to the target only once.
+///
+/// - optimistic: the first ddl is applied, identical ddls from other shards are swallowed,
+///   a shard executing a different ddl before catching up fails the task.
+/// - pessimistic: rows and ddls of shards which executed a ddl are held, the ddl is applied when
+///   all shards routed into the target executed the same ddl, then the held items are replayed.
+///
+/// only alter ddls are coordinated, truncate / drop / rename of a single shard fails the task.
+/// the state is persisted with checkpoints, and restored when the task restarts.
+pub struct ShardDdlCoordinator {
+    mode: ShardDdlModeEnum,
+    // held items are kept in memory, the task fails if a pending ddl exceeds the limits
+    max_held_items: usize,
+    max_held_secs: u64,
+    // (dst_schema, dst_tb) -> target, only targets merged from multiple source tables
+    targets: HashMap<SchemaTb, ShardTarget>,
+    syncer: Arc<Mutex<Syncer>>,
+    // the last checkpoint pushed while no ddl was pending, and the state at it
+    checkpoint_position: Position,
+    checkpoint_states: Vec<TargetState>,
+    // the state changed since the last checkpoint pushed
+    dirty: bool,
+    // the last state passed to the pipeline
+    last_state: String,
+}
+
+#[derive(Default)]
+struct ShardTarget {
+    // ddls applied to the target table, in order
+    applied_ddls: Vec<String>,
+    // (src_schema, src_tb) -> count of applied ddls the shard executed
+    shard_versions: HashMap<SchemaTb, usize>,
+    // count of ddls sent to the target, larger than applied_ddls.len() if the task restarted from
+    // a checkpoint held by a pending ddl, ddls already sent are not sent again when replayed
+    sent_count: usize,
+    // pessimistic mode only
+    pending: Option<PendingDdl>,
+}
+
+struct PendingDdl {
+    sql: String,
+    ddl_data: DdlData,
+    // shards executed the pending ddl
+    shards: HashSet<SchemaTb>,
+    // later rows / ddls of the shards above, replayed after the pending ddl is applied
+    held_items: Vec<(SchemaTb, ShardItem)>,
+    start_time: Instant,
+}
+
+/// state of a target persisted with checkpoints
+#[derive(Clone, Serialize, Deserialize)]
+struct TargetState {
+    schema: String,
+    tb: String,
+    applied_ddls: Vec<String>,
+    // (src_schema, src_tb, version)
+    shard_versions: Vec<(String, String, usize)>,
+    sent_count: usize,
+}
+
+impl ShardDdlCoordinator {
+    /// src_tbs: source tables matched by filter, state: the state persisted with the checkpoint
+    pub fn new(
+        config: &RouterConfig,
+        router: &RdbRouter,
+        src_tbs: &[SchemaTb],
+        state: &str,
+        syncer: Arc<Mutex<Syncer>>,
+    ) -> anyhow::Result<Option<Self>> {
+        let RouterConfig::Rdb {
+            shard_ddl_mode,
+            shard_ddl_max_held_items,
+            shard_ddl_max_held_secs,
+            ..
+        } = config;
+        if *shard_ddl_mode == ShardDdlModeEnum::None {
+            return Ok(None);
+        }
+
+        let mut targets: HashMap<SchemaTb, ShardTarget> = HashMap::new();
+        for (schema, tb) in src_tbs {
+            targets
+                .entry(router.get_tb_map(schema, tb))
+                .or_default()
+                .shard_versions
+                .insert((schema.clone(), tb.clone()), 0);
+        }
+        targets.retain(|_, target| target.shard_versions.len() > 1);
+
+        if !state.is_empty() {
+            // the state is logged with every checkpoint until it changes
+            syncer.lock().unwrap().shard_ddl_state = state.to_string();
+            let states: Vec<TargetState> = serde_json::from_str(state)
+                .with_context(|| format!("invalid shard ddl state: [{}]", state))?;
+            for i in states {
+                if let Some(target) = targets.get_mut(&(i.schema, i.tb)) {
+                    target.applied_ddls = i.applied_ddls;
+                    target.sent_count = i.sent_count;
+                    for (schema, tb, version) in i.shard_versions {
+                        if let Some(v) = target.shard_versions.get_mut(&(schema, tb)) {
+                            *v = version;
+                        }
+                    }
+                }
+            }
+        }
+
+        for ((schema, tb), target) in targets.iter() {
+            log_info!(
+                "shard ddl coordinated, target: {}.{}, shards: {:?}",
+                schema,
+                tb,
+                target.shard_versions.keys()
+            );
+        }
+
+        let mut me = Self {
+            mode: shard_ddl_mode.clone(),
+            max_held_items: *shard_ddl_max_held_items,
+            max_held_secs: *shard_ddl_max_held_secs,
+            targets,
+            syncer,
+            checkpoint_position: Position::None,
+            checkpoint_states: Vec::new(),
+            dirty: false,
+            last_state: state.to_string(),
+        };
+        me.checkpoint_states = me.get_target_states();
+        Ok(Some(me))
+    }
+
+    /// row_data has been routed, returns None if the row is held
+    pub fn on_row(
+        &mut self,
+        src_schema: &str,
+        src_tb: &str,
+        row_data: RowData,
+        position: Position,
+    ) -> anyhow::Result<Option<ShardItem>> {
+        let dst = (row_data.schema.clone(), row_data.tb.clone());
+        let target = match self.targets.get_mut(&dst) {
+            Some(target) => target,
+            None => return Ok(Some(ShardItem::Row(row_data, position))),
+        };
+        let shard = (src_schema.to_string(), src_tb.to_string());
+
+        if Self::is_held(target, &shard) {
+            let item = ShardItem::Row(row_data, position);
+            Self::hold(
+                target,
+                &dst,
+                shard,
+                item,
+                self.max_held_items,
+                self.max_held_secs,
+            )?;
+            return Ok(None);
+        }
+
+        // shards created after the task started have the latest structure
+        if !target.shard_versions.contains_key(&shard) {
+            target
+                .shard_versions
+                .insert(shard, target.applied_ddls.len());
+            self.dirty = true;
+        }
+        Ok(Some(ShardItem::Row(row_data, position)))
+    }
+
+    /// ddl_data has been routed, returns the items to be pushed in order
+    pub fn on_ddl(
+        &mut self,
+        src_schema: &str,
+        src_tb: &str,
+        ddl_data: DdlData,
+        position: Position,
+    ) -> anyhow::Result<Vec<ShardItem>> {
+        let (dst_schema, dst_tb) = ddl_data.get_schema_tb();
+        let target = match self.targets.get_mut(&(dst_schema.clone(), dst_tb.clone())) {
+            Some(target) => target,
+            // database level ddl, or the target is not merged from multiple shards
+            None => return Ok(vec![ShardItem::Ddl(ddl_data, position)]),
+        };
+
+        match ddl_data.ddl_type {
+            DdlType::AlterTable | DdlType::CreateIndex | DdlType::DropIndex => {}
+            DdlType::CreateTable => {
+                log_info!(
+                    "shard ddl ignored since the target exists, shard: {}.{}, target: {}.{}, sql: {}",
+                    src_schema,
+                    src_tb,
+                    dst_schema,
+                    dst_tb,
+                    ddl_data.to_sql()
+                );
+                return Ok(Vec::new());
+            }
+            DdlType::DropTable | DdlType::TruncateTable | DdlType::RenameTable => {
+                bail! {Error::ExtractorError(format!(
+                    "{} of a single shard is not supported for targets merged from multiple shards, shard: {}.{}, target: {}.{}, sql: {}",
+                    ddl_data.ddl_type, src_schema, src_tb, dst_schema, dst_tb, ddl_data.to_sql()
+                ))}
+            }
+            _ => return Ok(vec![ShardItem::Ddl(ddl_data, position)]),
+        }
+
+        let sql = ddl_data.to_sql();
+        let shard = (src_schema.to_string(), src_tb.to_string());
+
+        if Self::is_held(target, &shard) {
+            let dst = (dst_schema, dst_tb);
+            let item = ShardItem::Ddl(ddl_data, position);
+            Self::hold(
+                target,
+                &dst,
+                shard,
+                item,
+                self.max_held_items,
+                self.max_held_secs,
+            )?;
+            return Ok(Vec::new());
+        }
+
+        self.dirty = true;
+        // the ddl was applied by another shard
+        let version = *target.shard_versions.entry(shard.clone()).or_default();
+        if let Some(i) = target.applied_ddls[version..]
+            .iter()
+            .position(|applied_sql| *applied_sql == sql)
+        {
+            log_info!(
+                "shard ddl swallowed, shard: {}.{}, target: {}.{}, sql: {}",
+                src_schema,
+                src_tb,
+                dst_schema,
+                dst_tb,
+                sql
+            );
+            target.shard_versions.insert(shard, version + i + 1);
+            return Ok(Vec::new());
+        }
+
+        if version < target.applied_ddls.len() {
+            bail! {Error::ExtractorError(format!(
+                "shard ddl conflict, shard: {}.{}, target: {}.{}, sql: {}, expected: {}",
+                src_schema, src_tb, dst_schema, dst_tb, sql, target.applied_ddls[version]
+            ))}
+        }
+
+        if self.mode == ShardDdlModeEnum::Optimistic {
+            target
+                .shard_versions
+                .insert(shard, target.applied_ddls.len() + 1);
+            if Self::apply(target, sql) {
+                return Ok(vec![ShardItem::Ddl(ddl_data, position)]);
+            }
+            return Ok(Vec::new());
+        }
+
+        match &mut target.pending {
+            Some(pending) if pending.sql != sql => {
+                bail! {Error::ExtractorError(format!(
+                    "shard ddl conflict, shard: {}.{}, target: {}.{}, sql: {}, pending: {}",
+                    src_schema, src_tb, dst_schema, dst_tb, sql, pending.sql
+                ))}
+            }
+            Some(pending) => {
+                pending.shards.insert(shard);
+            }
+            None => {
+                target.pending = Some(PendingDdl {
+                    sql,
+                    ddl_data,
+                    shards: HashSet::from([shard]),
+                    held_items: Vec::new(),
+                    start_time: Instant::now(),
+                });
+            }
+        }
+
+        // wait for other shards
+        let pending = target.pending.as_ref().unwrap();
+        let waiting_shards = Self::get_waiting_shards(target);
+        if !waiting_shards.is_empty() {
+            log_warn!(
+                "shard ddl pending, target: {}.{}, sql: {}, waiting shards: {:?}",
+                dst_schema,
+                dst_tb,
+                pending.sql,
+                waiting_shards
+            );
+            return Ok(Vec::new());
+        }
+
+        let pending = target.pending.take().unwrap();
+        for shard in pending.shards {
+            target
+                .shard_versions
+                .insert(shard, target.applied_ddls.len() + 1);
+        }
+
+        // the ddl and the held items are pushed at the position of the last shard executing it,
+        // since the state after it is persisted with the checkpoint
+        let mut items = Vec::new();
+        if Self::apply(target, pending.sql) {
+            items.push(ShardItem::Ddl(pending.ddl_data, position.clone()));
+        }
+
+        // replay held items, they may be held again by a new pending ddl
+        for ((src_schema, src_tb), item) in pending.held_items {
+            match item {
+                ShardItem::Row(row_data, position) => {
+                    items.extend(self.on_row(&src_schema, &src_tb, row_data, position)?);
+                }
+                ShardItem::Ddl(ddl_data, _) => {
+                    items.extend(self.on_ddl(&src_schema, &src_tb, ddl_data, position.clone())?);
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    /// whether items are held by pending ddls, the checkpoint does not move then
+    pub fn is_holding(&self) -> bool {
+        self.targets.values().any(|i| i.pending.is_some())
+    }
+
+    /// called for each item pushed to the pipeline, returns the position to push it with.
+    /// rows and ddls held by pending ddls will be extracted again if the task restarts,
+    /// so the checkpoint does not pass the earliest of them.
+    pub fn on_push(&mut self, dt_data: &DtData, position: Position) -> Position {
+        if !matches!(
+            dt_data,
+            DtData::Commit { .. } | DtData::Ddl { .. } | DtData::Heartbeat {}
+        ) {
+            return position;
+        }
+
+        let holding = self.is_holding();
+        let position = if holding {
+            self.checkpoint_position.clone()
+        } else {
+            self.checkpoint_position = position.clone();
+            position
+        };
+
+        if !self.dirty {
+            return position;
+        }
+        self.dirty = false;
+
+        let states = self.get_target_states();
+        if holding {
+            // ddls sent can not be replayed, so sent_count is always the latest
+            for (checkpoint_state, state) in self.checkpoint_states.iter_mut().zip(states) {
+                checkpoint_state.sent_count = state.sent_count;
+            }
+        } else {
+            self.checkpoint_states = states;
+        }
+
+        let state = json!(self.checkpoint_states).to_string();
+        if state != self.last_state {
+            self.syncer.lock().unwrap().shard_ddl_states.push_back((
+                position.clone(),
+                dt_data.is_ddl(),
+                state.clone(),
+            ));
+            self.last_state = state;
+        }
+        position
+    }
+
+    fn is_held(target: &ShardTarget, shard: &SchemaTb) -> bool {
+        target
+            .pending
+            .as_ref()
+            .is_some_and(|pending| pending.shards.contains(shard))
+    }
+
+    /// held items are kept in memory, fail with the waiting shards if the pending ddl holds
+    /// too many items or too long, the task can be restarted after the waiting shards catch up
+    fn hold(
+        target: &mut ShardTarget,
+        dst: &SchemaTb,
+        shard: SchemaTb,
+        item: ShardItem,
+        max_held_items: usize,
+        max_held_secs: u64,
+    ) -> anyhow::Result<()> {
+        let pending = target.pending.as_mut().unwrap();
+        pending.held_items.push((shard, item));
+
+        let held_count = pending.held_items.len();
+        let held_secs = pending.start_time.elapsed().as_secs();
+        if (max_held_items > 0 && held_count > max_held_items)
+            || (max_held_secs > 0 && held_secs > max_held_secs)
+        {
+            let pending = target.pending.as_ref().unwrap();
+            bail! {Error::ExtractorError(format!(
+                "shard ddl pending for too long, held items: {}, held secs: {}, target: {}.{}, sql: {}, waiting shards: {:?}",
+                held_count, held_secs, dst.0, dst.1, pending.sql, Self::get_waiting_shards(target)
+            ))}
+        }
+        Ok(())
+    }
+
+    /// shards routed into the target which have not executed the pending ddl
+    fn get_waiting_shards(target: &ShardTarget) -> Vec<&SchemaTb> {
+        let mut waiting_shards: Vec<&SchemaTb> = match &target.pending {
+            Some(pending) => target
+                .shard_versions
+                .keys()
+                .filter(|i| !pending.shards.contains(*i))
+                .collect(),
+            None => Vec::new(),
+        };
+        waiting_shards.sort();
+        waiting_shards
+    }
+
+    /// returns false if the ddl was sent before the task restarted
+    fn apply(target: &mut ShardTarget, sql: String) -> bool {
+        target.applied_ddls.push(sql);
+        if target.applied_ddls.len() <= target.sent_count {
+            log_info!(
+                "shard ddl sent before restarting, sql: {}",
+                target.applied_ddls.last().unwrap()
+            );
+            return false;
+        }
+        target.sent_count = target.applied_ddls.len();
+        true
+    }
+
+    fn get_target_states(&self) -> Vec<TargetState> {
+        let mut states: Vec<TargetState> = self
+            .targets
+            .iter()
+            .map(|((schema, tb), target)| {
+                let mut shard_versions: Vec<(String, String, usize)> = target
+                    .shard_versions
+                    .iter()
+                    .map(|((src_schema, src_tb), version)| {
+                        (src_schema.clone(), src_tb.clone(), *version)
+                    })
+                    .collect();
+                shard_versions.sort();
+                TargetState {
+                    schema: schema.clone(),
+                    tb: tb.clone(),
+                    applied_ddls: target.applied_ddls.clone(),
+                    shard_versions,
+                    sent_count: target.sent_count,
+                }
+            })
+            .collect();
+        states.sort_by(|a, b| (&a.schema, &a.tb).cmp(&(&b.schema, &b.tb)));
+        states
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use dt_common::{
+        config::{
+            config_enums::{DbType, ShardDdlModeEnum},
+            router_config::RouterConfig,
+        },
+        meta::{
+            col_value::ColValue, ddl_meta::ddl_parser::DdlParser, dt_data::DtData,
+            position::Position, row_data::RowData, row_type::RowType, syncer::Syncer,
+        },
+    };
+
+    use super::{ShardDdlCoordinator, ShardItem};
+    use crate::rdb_router::RdbRouter;
+
+    const SHARDS: [(&str, &str); 3] = [("db_1", "tb_1"), ("db_2", "tb_1"), ("db_3", "tb_1")];
+
+    fn build(
+        mode: ShardDdlModeEnum,
+        state: &str,
+    ) -> (ShardDdlCoordinator, RdbRouter, Arc<Mutex<Syncer>>) {
+        build_with_limit(mode, state, 0)
+    }
+
+    fn build_with_limit(
+        mode: ShardDdlModeEnum,
+        state: &str,
+        max_held_items: usize,
+    ) -> (ShardDdlCoordinator, RdbRouter, Arc<Mutex<Syncer>>) {
+        // db_3.tb_1 is not merged
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: "db_1.tb_1:db.tb,db_2.tb_1:db.tb".into(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            key_prefix_map: String::new(),
+            tb_pattern_map: String::new(),
+            shard_ddl_mode: mode,
+            shard_ddl_max_held_items: max_held_items,
+            shard_ddl_max_held_secs: 0,
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
+        let src_tbs: Vec<(String, String)> = SHARDS
+            .iter()
+            .map(|(schema, tb)| (schema.to_string(), tb.to_string()))
+            .collect();
+        let syncer = Arc::new(Mutex::new(Syncer::default()));
+        let coordinator =
+            ShardDdlCoordinator::new(&config, &router, &src_tbs, state, syncer.clone())
+                .unwrap()
+                .unwrap();
+        (coordinator, router, syncer)
+    }
+
+    fn position(lsn: &str) -> Position {
+        Position::PgCdc {
+            lsn: lsn.into(),
+            timestamp: String::new(),
+        }
+    }
+
+    fn on_row(
+        coordinator: &mut ShardDdlCoordinator,
+        router: &RdbRouter,
+        shard: usize,
+        id: i32,
+    ) -> Option<ShardItem> {
+        let (schema, tb) = SHARDS[shard];
+        let after = HashMap::from([("id".to_string(), ColValue::Long(id))]);
+        let row_data = RowData::new(schema.into(), tb.into(), RowType::Insert, None, Some(after));
+        coordinator
+            .on_row(schema, tb, router.route_row(row_data), Position::None)
+            .unwrap()
+    }
+
+    fn on_ddl(
+        coordinator: &mut ShardDdlCoordinator,
+        router: &RdbRouter,
+        shard: usize,
+        alter: &str,
+    ) -> anyhow::Result<Vec<ShardItem>> {
+        let (schema, tb) = SHARDS[shard];
+        on_sql(
+            coordinator,
+            router,
+            shard,
+            &format!("ALTER TABLE {}.{} {}", schema, tb, alter),
+        )
+    }
+
+    fn on_sql(
+        coordinator: &mut ShardDdlCoordinator,
+        router: &RdbRouter,
+        shard: usize,
+        sql: &str,
+    ) -> anyhow::Result<Vec<ShardItem>> {
+        let (schema, tb) = SHARDS[shard];
+        let ddl_data = DdlParser::new(DbType::Mysql).parse(sql).unwrap();
+        coordinator.on_ddl(schema, tb, router.route_ddl(ddl_data), Position::None)
+    }
+
+    fn on_commit(coordinator: &mut ShardDdlCoordinator, lsn: &str) -> Position {
+        let commit = DtData::Commit { xid: String::new() };
+        coordinator.on_push(&commit, position(lsn))
+    }
+
+    fn to_strings(items: &[ShardItem]) -> Vec<String> {
+        items
+            .iter()
+            .map(|i| match i {
+                ShardItem::Row(row_data, _) => {
+                    let id = row_data.after.as_ref().unwrap()["id"].to_option_string();
+                    format!("row {}", id.unwrap())
+                }
+                ShardItem::Ddl(ddl_data, _) => ddl_data.to_sql(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_optimistic() {
+        let (mut coordinator, router, _) = build(ShardDdlModeEnum::Optimistic, "");
+        assert!(on_row(&mut coordinator, &router, 0, 1).is_some());
+        assert!(on_row(&mut coordinator, &router, 1, 2).is_some());
+
+        // the first ddl is applied, the identical one is swallowed
+        let items = on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c1 int").unwrap();
+        assert_eq!(
+            to_strings(&items),
+            vec!["ALTER TABLE `db`.`tb` ADD COLUMN c1 int"]
+        );
+        assert!(on_row(&mut coordinator, &router, 0, 3).is_some());
+        let items = on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c1 int").unwrap();
+        assert!(items.is_empty());
+
+        // a shard diverging before catching up fails
+        let items = on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c2 int").unwrap();
+        assert_eq!(items.len(), 1);
+        assert!(on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c3 int").is_err());
+    }
+
+    #[test]
+    fn test_pessimistic() {
+        let (mut coordinator, router, _) = build(ShardDdlModeEnum::Pessimistic, "");
+        assert!(on_row(&mut coordinator, &router, 0, 1).is_some());
+        assert!(on_row(&mut coordinator, &router, 1, 2).is_some());
+
+        // rows and ddls of shard 0 are held until shard 1 executes the same ddl
+        assert!(on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c1 int")
+            .unwrap()
+            .is_empty());
+        assert!(on_row(&mut coordinator, &router, 0, 3).is_none());
+        assert!(on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c2 int")
+            .unwrap()
+            .is_empty());
+        assert!(on_row(&mut coordinator, &router, 0, 4).is_none());
+        assert!(on_row(&mut coordinator, &router, 1, 5).is_some());
+
+        // after c1 is applied, shard 0 is held again by c2
+        let items = on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c1 int").unwrap();
+        assert_eq!(
+            to_strings(&items),
+            vec!["ALTER TABLE `db`.`tb` ADD COLUMN c1 int", "row 3"]
+        );
+        assert!(on_row(&mut coordinator, &router, 1, 6).is_some());
+
+        let items = on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c2 int").unwrap();
+        assert_eq!(
+            to_strings(&items),
+            vec!["ALTER TABLE `db`.`tb` ADD COLUMN c2 int", "row 4"]
+        );
+
+        // a different ddl while another is pending fails
+        assert!(on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c3 int")
+            .unwrap()
+            .is_empty());
+        assert!(on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c4 int").is_err());
+    }
+
+    #[test]
+    fn test_pessimistic_shards() {
+        // shard 1 has no traffic, but it is still waited for
+        let (mut coordinator, router, _) = build(ShardDdlModeEnum::Pessimistic, "");
+        assert!(on_row(&mut coordinator, &router, 0, 1).is_some());
+        assert!(on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c1 int")
+            .unwrap()
+            .is_empty());
+
+        // the target of shard 2 is not merged
+        let items = on_ddl(&mut coordinator, &router, 2, "ADD COLUMN c1 int").unwrap();
+        assert_eq!(
+            to_strings(&items),
+            vec!["ALTER TABLE `db_3`.`tb_1` ADD COLUMN c1 int"]
+        );
+    }
+
+    #[test]
+    fn test_held_limit() {
+        let (mut coordinator, router, _) = build_with_limit(ShardDdlModeEnum::Pessimistic, "", 2);
+        assert!(on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c1 int")
+            .unwrap()
+            .is_empty());
+        assert!(on_row(&mut coordinator, &router, 0, 1).is_none());
+        assert!(on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c2 int")
+            .unwrap()
+            .is_empty());
+
+        // fails with the waiting shards
+        let (schema, tb) = SHARDS[0];
+        let after = HashMap::from([("id".to_string(), ColValue::Long(2))]);
+        let row_data = RowData::new(schema.into(), tb.into(), RowType::Insert, None, Some(after));
+        let err = coordinator
+            .on_row(schema, tb, router.route_row(row_data), Position::None)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("waiting shards"));
+        assert!(err.to_string().contains("db_2"));
+    }
+
+    #[test]
+    fn test_non_alter_ddl() {
+        let (mut coordinator, router, _) = build(ShardDdlModeEnum::Optimistic, "");
+        // truncate / drop / rename of a single shard fails
+        assert!(on_sql(&mut coordinator, &router, 0, "TRUNCATE TABLE db_1.tb_1").is_err());
+        assert!(on_sql(&mut coordinator, &router, 0, "DROP TABLE db_1.tb_1").is_err());
+        assert!(on_sql(
+            &mut coordinator,
+            &router,
+            0,
+            "RENAME TABLE db_1.tb_1 TO db_1.tb_2"
+        )
+        .is_err());
+        // the merged target exists
+        assert!(on_sql(
+            &mut coordinator,
+            &router,
+            1,
+            "CREATE TABLE db_2.tb_1 (id int)"
+        )
+        .unwrap()
+        .is_empty());
+        // not merged
+        assert_eq!(
+            on_sql(&mut coordinator, &router, 2, "DROP TABLE db_3.tb_1")
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let (mut coordinator, router, syncer) = build(ShardDdlModeEnum::Pessimistic, "");
+        assert_eq!(on_commit(&mut coordinator, "1"), position("1"));
+
+        // the checkpoint does not pass the pending ddl
+        assert!(on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c1 int")
+            .unwrap()
+            .is_empty());
+        assert_eq!(on_commit(&mut coordinator, "2"), position("1"));
+        assert!(on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c2 int")
+            .unwrap()
+            .is_empty());
+
+        // c1 is applied but shard 0 is held again by c2
+        let items = on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c1 int").unwrap();
+        assert_eq!(items.len(), 1);
+        let ddl = DtData::Ddl {
+            ddl_data: match &items[0] {
+                ShardItem::Ddl(ddl_data, _) => ddl_data.clone(),
+                _ => panic!("unexpected item"),
+            },
+        };
+        assert_eq!(coordinator.on_push(&ddl, position("3")), position("1"));
+
+        // restart from the checkpoint, c1 is not applied again
+        let (_, _, state) = syncer.lock().unwrap().shard_ddl_states.pop_back().unwrap();
+        let (mut coordinator, router, _) = build(ShardDdlModeEnum::Pessimistic, &state);
+        for alter in ["ADD COLUMN c1 int", "ADD COLUMN c2 int"] {
+            assert!(on_ddl(&mut coordinator, &router, 0, alter)
+                .unwrap()
+                .is_empty());
+        }
+        let items = on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c1 int").unwrap();
+        assert!(items.is_empty());
+        let items = on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c2 int").unwrap();
+        assert_eq!(
+            to_strings(&items),
+            vec!["ALTER TABLE `db`.`tb` ADD COLUMN c2 int"]
+        );
+    }
+
+    #[test]
+    fn test_resume() {
+        let (mut coordinator, router, syncer) = build(ShardDdlModeEnum::Optimistic, "");
+        let items = on_ddl(&mut coordinator, &router, 0, "ADD COLUMN c1 int").unwrap();
+        assert_eq!(items.len(), 1);
+        on_commit(&mut coordinator, "1");
+
+        // the ddl applied before restarting is swallowed
+        let (_, _, state) = syncer.lock().unwrap().shard_ddl_states.pop_back().unwrap();
+        let (mut coordinator, router, _) = build(ShardDdlModeEnum::Optimistic, &state);
+        assert!(on_ddl(&mut coordinator, &router, 1, "ADD COLUMN c1 int")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,13 +1,71 @@
+use std::cmp::Ordering;
+
 use anyhow::bail;
+use async_trait::async_trait;
 use dt_common::log_error;
+use dt_common::meta::ddl_meta::ddl_definition::AlterTableOperation;
 use dt_common::meta::struct_meta::struct_data::StructData;
 use dt_common::{
-    config::config_enums::ConflictPolicyEnum, error::Error, log_info, rdb_filter::RdbFilter,
+    config::config_enums::{ConflictPolicyEnum, DbType},
+    error::Error,
+    log_info, log_warn,
+    meta::{
+        ddl_meta::{
+            ddl_data::DdlData, ddl_definition::ColumnDefinition, ddl_translator::DdlTranslator,
+            ddl_type::DdlType,
+        },
+        mysql::mysql_col_type::MysqlColType,
+        pg::pg_tb_meta::PgTbMeta,
+        rdb_meta_manager::RdbMetaManager,
+    },
+    rdb_filter::RdbFilter,
 };
 use sqlx::{query, MySql, Pool, Postgres};
 
+use crate::rdb_router::RdbRouter;
+
+// reserved cols of starrocks / doris / clickhouse tables, maintained by ape_dts
+pub const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
+pub const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
+
 pub struct BaseStructSinker {}
 
+/// dialect specific pieces of olap struct sinkers (starrocks / doris / clickhouse) to evolve
+/// column changes of source tables, the shared logic is in BaseStructSinker
+#[async_trait]
+pub trait OlapEvolver: Send + Sync {
+    fn get_dst_db_type(&self) -> DbType;
+
+    fn mysql_to_dst_col_type(&self, mysql_col_type: &MysqlColType) -> String;
+
+    fn pg_to_dst_col_type(&self, col: &str, tb_meta: &PgTbMeta) -> anyhow::Result<String>;
+
+    /// type of the target column, None if it does not exist
+    async fn get_dst_col_type(
+        &self,
+        schema: &str,
+        tb: &str,
+        col: &str,
+    ) -> anyhow::Result<Option<String>>;
+
+    /// existing rows have no value for the added column, it should be nullable
+    fn build_add_col_sql(&self, full_tb: &str, col: &str, col_type: &str) -> String;
+
+    fn build_rename_col_sql(&self, full_tb: &str, old_col: &str, new_col: &str) -> String;
+
+    fn build_modify_col_sql(
+        &self,
+        full_tb: &str,
+        col: &str,
+        col_type: &str,
+        is_nullable: bool,
+    ) -> String;
+
+    fn get_router(&self) -> &RdbRouter;
+
+    fn get_extractor_meta_manager(&mut self) -> &mut RdbMetaManager;
+}
+
 pub enum DBConnPool {
     MySQL(Pool<MySql>),
     PostgreSQL(Pool<Postgres>),
@@ -41,6 +99,266 @@ impl BaseStructSinker {
         Ok(())
     }
 
+    pub fn is_reserved_col(col: &str) -> bool {
+        col == SIGN_COL_NAME || col == TIMESTAMP_COL_NAME
+    }
+
+    /// whether a column change of source touches the reserved cols, it should not be evolved
+    pub fn is_reserved_operation(operation: &AlterTableOperation) -> bool {
+        match operation {
+            AlterTableOperation::AddColumn { column, .. }
+            | AlterTableOperation::ModifyColumn { column, .. }
+            | AlterTableOperation::AlterColumnType { column } => {
+                Self::is_reserved_col(&column.column_name)
+            }
+            AlterTableOperation::ChangeColumn {
+                old_column_name,
+                column,
+                ..
+            } => {
+                Self::is_reserved_col(old_column_name) || Self::is_reserved_col(&column.column_name)
+            }
+            AlterTableOperation::DropColumn { column_name } => Self::is_reserved_col(column_name),
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => Self::is_reserved_col(old_column_name) || Self::is_reserved_col(new_column_name),
+            _ => false,
+        }
+    }
+
+    /// translates column changes of source table into target ddls, mysql ddls are translated
+    /// by DdlTranslator, pg ddls by the parsed alter operations, other ddls are ignored
+    pub async fn get_evolve_sqls<T: OlapEvolver>(
+        sinker: &mut T,
+        ddl_data: &DdlData,
+    ) -> anyhow::Result<Vec<String>> {
+        if ddl_data.ddl_type != DdlType::AlterTable {
+            return Ok(vec![]);
+        }
+
+        // unparsed mysql ddls are still translated from the query by DdlTranslator
+        if ddl_data.db_type != DbType::Mysql && ddl_data.alter_operations.is_empty() {
+            bail! {Error::StructError(format!(
+                "alter operations of ddl are not parsed, can not evolve: {}",
+                ddl_data.query
+            ))}
+        }
+
+        // reserved cols are maintained by ape_dts
+        let mut ddl_data = ddl_data.clone();
+        if !ddl_data.alter_operations.is_empty() {
+            ddl_data
+                .alter_operations
+                .retain(|i| !Self::is_reserved_operation(i));
+            if ddl_data.alter_operations.is_empty() {
+                return Ok(vec![]);
+            }
+        }
+
+        let (schema, tb) = ddl_data.get_schema_tb();
+        if ddl_data.db_type == DbType::Mysql {
+            if !ddl_data.alter_operations.is_empty() {
+                ddl_data.alter_operations =
+                    Self::remove_skipped_modify(sinker, &schema, &tb, ddl_data.alter_operations)
+                        .await?;
+                if ddl_data.alter_operations.is_empty() {
+                    return Ok(vec![]);
+                }
+            }
+            return DdlTranslator::translate(&ddl_data, &sinker.get_dst_db_type());
+        }
+
+        let full_tb = format!("`{}`.`{}`", schema, tb);
+        let mut sqls = vec![];
+        for operation in ddl_data.alter_operations.iter() {
+            match operation {
+                AlterTableOperation::AddColumn { column, .. } => {
+                    let col_type = Self::get_evolve_col_type(sinker, &ddl_data, column).await?;
+                    sqls.push(sinker.build_add_col_sql(&full_tb, &column.column_name, &col_type));
+                }
+
+                AlterTableOperation::DropColumn { column_name } => {
+                    sqls.push(format!(
+                        "ALTER TABLE {} DROP COLUMN `{}`",
+                        full_tb, column_name
+                    ));
+                }
+
+                AlterTableOperation::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                } => {
+                    if old_column_name != new_column_name {
+                        sqls.push(sinker.build_rename_col_sql(
+                            &full_tb,
+                            old_column_name,
+                            new_column_name,
+                        ));
+                    }
+                }
+
+                AlterTableOperation::ChangeColumn {
+                    old_column_name,
+                    column,
+                    ..
+                } => {
+                    if *old_column_name != column.column_name {
+                        sqls.push(sinker.build_rename_col_sql(
+                            &full_tb,
+                            old_column_name,
+                            &column.column_name,
+                        ));
+                    }
+                    if let Some(sql) =
+                        Self::get_modify_col_sql(sinker, &full_tb, &ddl_data, column).await?
+                    {
+                        sqls.push(sql);
+                    }
+                }
+
+                AlterTableOperation::ModifyColumn { column, .. }
+                | AlterTableOperation::AlterColumnType { column } => {
+                    if let Some(sql) =
+                        Self::get_modify_col_sql(sinker, &full_tb, &ddl_data, column).await?
+                    {
+                        sqls.push(sql);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+        Ok(sqls)
+    }
+
+    async fn get_modify_col_sql<T: OlapEvolver>(
+        sinker: &mut T,
+        full_tb: &str,
+        ddl_data: &DdlData,
+        column: &ColumnDefinition,
+    ) -> anyhow::Result<Option<String>> {
+        let col_type = Self::get_evolve_col_type(sinker, ddl_data, column).await?;
+        let (schema, tb) = ddl_data.get_schema_tb();
+        if Self::skip_modify_col(sinker, &schema, &tb, &column.column_name, &col_type).await? {
+            return Ok(None);
+        }
+
+        let is_nullable = ddl_data.db_type == DbType::Pg || column.is_nullable();
+        Ok(Some(sinker.build_modify_col_sql(
+            full_tb,
+            &column.column_name,
+            &col_type,
+            is_nullable,
+        )))
+    }
+
+    /// removes MODIFY operations of mysql ddl which should be skipped by skip_modify_col
+    async fn remove_skipped_modify<T: OlapEvolver>(
+        sinker: &T,
+        schema: &str,
+        tb: &str,
+        operations: Vec<AlterTableOperation>,
+    ) -> anyhow::Result<Vec<AlterTableOperation>> {
+        let mut res = Vec::new();
+        for operation in operations {
+            match operation {
+                AlterTableOperation::ModifyColumn { ref column, .. }
+                | AlterTableOperation::AlterColumnType { ref column } => {
+                    let col_type = sinker.mysql_to_dst_col_type(&column.to_mysql_col_type());
+                    if !Self::skip_modify_col(sinker, schema, tb, &column.column_name, &col_type)
+                        .await?
+                    {
+                        res.push(operation);
+                    }
+                }
+
+                AlterTableOperation::ChangeColumn {
+                    ref old_column_name,
+                    ref column,
+                    ..
+                } => {
+                    let col_type = sinker.mysql_to_dst_col_type(&column.to_mysql_col_type());
+                    if !Self::skip_modify_col(sinker, schema, tb, old_column_name, &col_type)
+                        .await?
+                    {
+                        res.push(operation);
+                    } else if *old_column_name != column.column_name {
+                        res.push(AlterTableOperation::RenameColumn {
+                            old_column_name: old_column_name.clone(),
+                            new_column_name: column.column_name.clone(),
+                        });
+                    }
+                }
+
+                _ => res.push(operation),
+            }
+        }
+        Ok(res)
+    }
+
+    /// MODIFY is skipped if the target column type is unchanged, or narrowed
+    /// which will be rejected by target
+    async fn skip_modify_col<T: OlapEvolver>(
+        sinker: &T,
+        schema: &str,
+        tb: &str,
+        col: &str,
+        col_type: &str,
+    ) -> anyhow::Result<bool> {
+        let dst_col_type = match sinker.get_dst_col_type(schema, tb, col).await? {
+            Some(dst_col_type) => dst_col_type,
+            None => return Ok(false),
+        };
+
+        match DdlTranslator::compare_olap_col_type(&dst_col_type, col_type) {
+            Some(Ordering::Equal) => Ok(true),
+            Some(Ordering::Less) => {
+                log_warn!(
+                    "narrowing column type skipped, table: `{}`.`{}`, column: {}, {} -> {}",
+                    schema,
+                    tb,
+                    col,
+                    dst_col_type,
+                    col_type
+                );
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn get_evolve_col_type<T: OlapEvolver>(
+        sinker: &mut T,
+        ddl_data: &DdlData,
+        column: &ColumnDefinition,
+    ) -> anyhow::Result<String> {
+        match ddl_data.db_type {
+            // pg ddl only contains raw types, get the new col type from source after refreshing
+            DbType::Pg => {
+                let (dst_schema, dst_tb) = ddl_data.get_schema_tb();
+                let reverse_router = sinker.get_router().reverse();
+                let (schema, tb) = reverse_router.get_reversed_tb_map(&dst_schema, &dst_tb)?;
+                if let Some(meta_manager) =
+                    sinker.get_extractor_meta_manager().pg_meta_manager.as_mut()
+                {
+                    meta_manager.invalidate_cache(&schema, &tb);
+                    let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?.to_owned();
+                    return sinker.pg_to_dst_col_type(&column.column_name, &tb_meta);
+                }
+                bail! {Error::StructError(format!(
+                    "no pg meta manager to evolve column: {}",
+                    column.column_name
+                ))}
+            }
+
+            _ => bail! {Error::StructError(format!(
+                "auto evolve from {} is not supported",
+                ddl_data.db_type
+            ))},
+        }
+    }
+
     async fn execute(pool: &DBConnPool, sql: &str) -> anyhow::Result<()> {
         match pool {
             DBConnPool::MySQL(pool) => match query(sql).execute(pool).await {
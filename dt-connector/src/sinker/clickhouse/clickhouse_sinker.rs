@@ -11,17 +11,24 @@ use chrono::Utc;
 use dt_common::{
     config::config_enums::DbType,
     error::Error,
-    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+    meta::{
+        col_value::ColValue, ddl_meta::ddl_data::DdlData, row_data::RowData, row_type::RowType,
+    },
     monitor::monitor::Monitor,
     utils::sql_util::SqlUtil,
 };
 use reqwest::{Client, Method, Response, StatusCode};
 use serde_json::json;
 
-use crate::{call_batch_fn, sinker::base_sinker::BaseSinker, Sinker};
-
-const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
-const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
+use crate::{
+    call_batch_fn,
+    sinker::{
+        base_sinker::BaseSinker,
+        base_struct_sinker::{SIGN_COL_NAME, TIMESTAMP_COL_NAME},
+        clickhouse::clickhouse_struct_sinker::ClickhouseStructSinker,
+    },
+    Sinker,
+};
 
 #[derive(Clone)]
 pub struct ClickhouseSinker {
@@ -33,6 +40,8 @@ pub struct ClickhouseSinker {
     pub password: String,
     pub monitor: Arc<Mutex<Monitor>>,
     pub sync_timestamp: i64,
+    // set if auto_evolve is enabled, used to apply source column changes to target
+    pub struct_sinker: Option<ClickhouseStructSinker>,
 }
 
 #[async_trait]
@@ -45,6 +54,13 @@ impl Sinker for ClickhouseSinker {
         call_batch_fn!(self, data, Self::batch_sink);
         Ok(())
     }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, batch: bool) -> anyhow::Result<()> {
+        if let Some(struct_sinker) = self.struct_sinker.as_mut() {
+            struct_sinker.sink_ddl(data, batch).await?;
+        }
+        Ok(())
+    }
 }
 
 impl ClickhouseSinker {
@@ -1,11 +1,18 @@
-use crate::{rdb_router::RdbRouter, Sinker};
+use crate::{
+    rdb_router::RdbRouter,
+    sinker::base_struct_sinker::{
+        BaseStructSinker, OlapEvolver, SIGN_COL_NAME, TIMESTAMP_COL_NAME,
+    },
+    Sinker,
+};
 
 use anyhow::bail;
 use clickhouse::Client;
 use dt_common::{
-    config::config_enums::ConflictPolicyEnum,
+    config::config_enums::{ConflictPolicyEnum, DbType},
     log_error, log_info,
     meta::{
+        ddl_meta::{ddl_data::DdlData, ddl_translator::DdlTranslator},
         mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
         pg::{pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
         rdb_meta_manager::RdbMetaManager,
@@ -20,9 +27,7 @@ use dt_common::{
 
 use async_trait::async_trait;
 
-const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
 const SIGN_COL_TYPE: &str = "Int8";
-const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
 const TIMESTAMP_COL_TYPE: &str = "Int64";
 
 #[derive(Clone)]
@@ -50,16 +55,20 @@ impl Sinker for ClickhouseStructSinker {
                 }
 
                 StructStatement::MysqlCreateTable(statement) => {
-                    let (schema, tb) = reverse_router
-                        .get_tb_map(&statement.table.database_name, &statement.table.table_name);
-                    if let Some(meta_manager) =
+                    let sql = if let Some(meta_manager) =
                         self.extractor_meta_manager.mysql_meta_manager.as_mut()
                     {
-                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?;
-                        let sql =
-                            Self::get_create_table_sql(&statement.table, Some(tb_meta), None)?;
-                        self.execute_sql(&sql).await?;
-                    }
+                        let (schema, tb) = reverse_router.get_reversed_tb_map(
+                            &statement.table.database_name,
+                            &statement.table.table_name,
+                        )?;
+                        let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?;
+                        Self::get_create_table_sql(&statement.table, Some(tb_meta), None)?
+                    } else {
+                        // tables inferred from mongo documents, columns have target types
+                        Self::get_create_table_sql(&statement.table, None, None)?
+                    };
+                    self.execute_sql(&sql).await?;
                 }
 
                 StructStatement::PgCreateSchema(statement) => {
@@ -68,11 +77,13 @@ impl Sinker for ClickhouseStructSinker {
                 }
 
                 StructStatement::PgCreateTable(statement) => {
-                    let (schema, tb) = reverse_router
-                        .get_tb_map(&statement.table.schema_name, &statement.table.table_name);
+                    let (schema, tb) = reverse_router.get_reversed_tb_map(
+                        &statement.table.schema_name,
+                        &statement.table.table_name,
+                    )?;
                     if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
                     {
-                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?.to_owned();
                         let sql =
                             Self::get_create_table_sql(&statement.table, None, Some(&tb_meta))?;
                         self.execute_sql(&sql).await?;
@@ -85,18 +96,123 @@ impl Sinker for ClickhouseStructSinker {
 
         Ok(())
     }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        for ddl_data in data {
+            let sqls = match BaseStructSinker::get_evolve_sqls(self, &ddl_data).await {
+                Ok(sqls) => sqls,
+                Err(error) => {
+                    log_error!("evolve ddl failed, error: {}", error);
+                    match self.conflict_policy {
+                        ConflictPolicyEnum::Interrupt => return Err(error),
+                        ConflictPolicyEnum::Ignore => continue,
+                    }
+                }
+            };
+            for sql in sqls {
+                self.execute_sql(&sql).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OlapEvolver for ClickhouseStructSinker {
+    fn get_dst_db_type(&self) -> DbType {
+        DbType::ClickHouse
+    }
+
+    fn mysql_to_dst_col_type(&self, mysql_col_type: &MysqlColType) -> String {
+        DdlTranslator::mysql_to_clickhouse_col_type(mysql_col_type)
+    }
+
+    fn pg_to_dst_col_type(&self, col: &str, tb_meta: &PgTbMeta) -> anyhow::Result<String> {
+        Self::get_dst_col_type_from_pg(col, tb_meta)
+    }
+
+    async fn get_dst_col_type(
+        &self,
+        schema: &str,
+        tb: &str,
+        col: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let sql = "SELECT type FROM system.columns WHERE database = ? AND table = ? AND name = ?";
+        let dst_col_type = self
+            .client
+            .query(sql)
+            .bind(schema)
+            .bind(tb)
+            .bind(col)
+            .fetch_optional::<String>()
+            .await?;
+        Ok(dst_col_type)
+    }
+
+    fn build_add_col_sql(&self, full_tb: &str, col: &str, col_type: &str) -> String {
+        format!(
+            "ALTER TABLE {} ADD COLUMN `{}` {}",
+            full_tb,
+            col,
+            Self::to_nullable(col_type, true)
+        )
+    }
+
+    fn build_rename_col_sql(&self, full_tb: &str, old_col: &str, new_col: &str) -> String {
+        format!(
+            "ALTER TABLE {} RENAME COLUMN `{}` TO `{}`",
+            full_tb, old_col, new_col
+        )
+    }
+
+    fn build_modify_col_sql(
+        &self,
+        full_tb: &str,
+        col: &str,
+        col_type: &str,
+        is_nullable: bool,
+    ) -> String {
+        format!(
+            "ALTER TABLE {} MODIFY COLUMN `{}` {}",
+            full_tb,
+            col,
+            Self::to_nullable(col_type, is_nullable)
+        )
+    }
+
+    fn get_router(&self) -> &RdbRouter {
+        &self.router
+    }
+
+    fn get_extractor_meta_manager(&mut self) -> &mut RdbMetaManager {
+        &mut self.extractor_meta_manager
+    }
 }
 
 impl ClickhouseStructSinker {
+    fn to_nullable(col_type: &str, is_nullable: bool) -> String {
+        // Nested type Array() cannot be inside Nullable type
+        if is_nullable && !col_type.starts_with("Array") {
+            format!("Nullable({})", col_type)
+        } else {
+            col_type.to_string()
+        }
+    }
+
     fn get_create_table_sql(
         table: &Table,
         mysql_tb_meta: Option<&MysqlTbMeta>,
         pg_tb_meta: Option<&PgTbMeta>,
     ) -> anyhow::Result<String> {
-        let rdb_tb_meta = if let Some(tb_meta) = pg_tb_meta {
-            &tb_meta.basic
-        } else {
-            &mysql_tb_meta.as_ref().unwrap().basic
+        let id_cols = match (mysql_tb_meta, pg_tb_meta) {
+            (Some(tb_meta), _) => tb_meta.basic.id_cols.clone(),
+            (_, Some(tb_meta)) => tb_meta.basic.id_cols.clone(),
+            _ => table
+                .columns
+                .iter()
+                .filter(|column| column.column_key == "PRI")
+                .map(|column| column.column_name.clone())
+                .collect(),
         };
 
         let mut dst_cols = vec![];
@@ -109,7 +225,7 @@ impl ClickhouseStructSinker {
         dst_cols.push(format!("`{}` {}", TIMESTAMP_COL_NAME, TIMESTAMP_COL_TYPE));
 
         // engine, default: ReplacingMergeTree
-        let schema = if mysql_tb_meta.is_some() {
+        let schema = if pg_tb_meta.is_none() {
             &table.database_name
         } else {
             &table.schema_name
@@ -122,9 +238,8 @@ impl ClickhouseStructSinker {
             TIMESTAMP_COL_NAME
         );
 
-        if !rdb_tb_meta.id_cols.is_empty() {
-            let order_by = rdb_tb_meta
-                .id_cols
+        if !id_cols.is_empty() {
+            let order_by = id_cols
                 .iter()
                 .map(|i| format!("`{}`", i))
                 .collect::<Vec<String>>()
@@ -140,11 +255,11 @@ impl ClickhouseStructSinker {
         pg_tb_meta: Option<&PgTbMeta>,
     ) -> anyhow::Result<String> {
         let col = &column.column_name;
-        let dst_col_type = if let Some(tb_meta) = mysql_tb_meta {
-            Self::get_dst_col_type_from_mysql(col, tb_meta)
-        } else {
-            Self::get_dst_col_type_from_pg(col, pg_tb_meta.unwrap())
-        }?;
+        let dst_col_type = match (mysql_tb_meta, pg_tb_meta) {
+            (Some(tb_meta), _) => Self::get_dst_col_type_from_mysql(col, tb_meta)?,
+            (_, Some(tb_meta)) => Self::get_dst_col_type_from_pg(col, tb_meta)?,
+            _ => column.column_type.clone(),
+        };
 
         // Nested type Array() cannot be inside Nullable type
         let mut dst_col = if column.is_nullable && !dst_col_type.starts_with("Array") {
@@ -162,51 +277,7 @@ impl ClickhouseStructSinker {
 
     fn get_dst_col_type_from_mysql(col: &str, tb_meta: &MysqlTbMeta) -> anyhow::Result<String> {
         let mysql_col_type = tb_meta.get_col_type(col)?;
-        let dst_col = match mysql_col_type {
-            MysqlColType::TinyInt { unsigned: false } => "Int8",
-            MysqlColType::TinyInt { unsigned: true } => "UInt8",
-            MysqlColType::SmallInt { unsigned: false } => "Int16",
-            MysqlColType::SmallInt { unsigned: true } => "UInt16",
-            MysqlColType::MediumInt { unsigned: false } => "Int32",
-            MysqlColType::MediumInt { unsigned: true } => "UInt32",
-            MysqlColType::Int { unsigned: false } => "Int32",
-            MysqlColType::Int { unsigned: true } => "UInt32",
-            MysqlColType::BigInt { unsigned: false } => "Int64",
-            MysqlColType::BigInt { unsigned: true } => "UInt64",
-
-            MysqlColType::Float => "Float32",
-            MysqlColType::Double => "Float64",
-            MysqlColType::Decimal { precision, scale } => {
-                format!("Decimal({},{})", precision, scale).leak()
-            }
-
-            MysqlColType::Time { .. } => "String",
-            MysqlColType::Date { .. } => "Date32",
-            MysqlColType::DateTime { .. } => "DateTime64(6)",
-            MysqlColType::Timestamp { .. } => "DateTime64(6)",
-            MysqlColType::Year => "Int32",
-
-            MysqlColType::Char { .. }
-            | MysqlColType::Varchar { .. }
-            | MysqlColType::TinyText { .. }
-            | MysqlColType::MediumText { .. }
-            | MysqlColType::Text { .. }
-            | MysqlColType::LongText { .. } => "String",
-
-            MysqlColType::Binary { length: _ } => "String",
-            MysqlColType::VarBinary { length: _ } => "String",
-            MysqlColType::TinyBlob
-            | MysqlColType::MediumBlob
-            | MysqlColType::Blob
-            | MysqlColType::LongBlob => "String",
-
-            MysqlColType::Bit => "UInt64",
-            MysqlColType::Set { items: _ } => "String",
-            MysqlColType::Enum { items: _ } => "String",
-            MysqlColType::Json => "String",
-            MysqlColType::Unknown => "String",
-        };
-        Ok(dst_col.to_string())
+        Ok(DdlTranslator::mysql_to_clickhouse_col_type(mysql_col_type))
     }
 
     fn get_dst_col_type_from_pg(col: &str, tb_meta: &PgTbMeta) -> anyhow::Result<String> {
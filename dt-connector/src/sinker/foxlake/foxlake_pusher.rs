@@ -166,9 +166,9 @@ impl FoxlakePusher {
 
             let (src_schema, src_tb) = self
                 .reverse_router
-                .get_tb_map(&tb_meta.basic.schema, &tb_meta.basic.tb);
+                .get_reversed_tb_map(&tb_meta.basic.schema, &tb_meta.basic.tb)?;
             let (data_file_name, meta_file_name, sequence_info) =
-                self.get_s3_file_info(src_schema, src_tb);
+                self.get_s3_file_info(&src_schema, &src_tb);
 
             let s3_file_meta = S3FileMeta {
                 schema: tb_meta.basic.schema.clone(),
@@ -10,7 +10,7 @@ use mongodb::{
     Client,
 };
 
-use dt_common::{log_error, monitor::monitor::Monitor};
+use dt_common::{log_error, monitor::monitor::Monitor, rdb_filter::RdbFilter};
 
 use dt_common::meta::{
     col_value::ColValue,
@@ -18,10 +18,12 @@ use dt_common::meta::{
     rdb_tb_meta::RdbTbMeta,
     row_data::RowData,
     row_type::RowType,
+    struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
 };
 
 use crate::{
     call_batch_fn,
+    meta_fetcher::mongo::mongo_struct_fetcher::MongoStructFetcher,
     rdb_router::RdbRouter,
     sinker::{base_checker::BaseChecker, base_sinker::BaseSinker},
     Sinker,
@@ -33,6 +35,7 @@ pub struct MongoChecker {
     pub batch_size: usize,
     pub mongo_client: Client,
     pub monitor: Arc<Mutex<Monitor>>,
+    pub filter: RdbFilter,
 }
 
 #[async_trait]
@@ -50,6 +53,14 @@ impl Sinker for MongoChecker {
         self.mongo_client.clone().shutdown().await;
         Ok(())
     }
+
+    async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        self.serial_check_struct(data).await
+    }
 }
 
 impl MongoChecker {
@@ -129,6 +140,35 @@ impl MongoChecker {
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, 0, start_time)
     }
 
+    async fn serial_check_struct(&mut self, mut data: Vec<StructData>) -> anyhow::Result<()> {
+        for src_data in data.iter_mut() {
+            let src_statement = &mut src_data.statement;
+            let mut dst_statement = match &src_statement {
+                StructStatement::MongoCreateCollection(s) => {
+                    let mut struct_fetcher = MongoStructFetcher {
+                        mongo_client: self.mongo_client.clone(),
+                        db: s.db.clone(),
+                        filter: None,
+                    };
+                    let mut dst_statement = struct_fetcher
+                        .get_create_collection_statements(&s.tb)
+                        .await?;
+                    if dst_statement.is_empty() {
+                        StructStatement::Unknown
+                    } else {
+                        StructStatement::MongoCreateCollection(dst_statement.remove(0))
+                    }
+                }
+
+                _ => StructStatement::Unknown,
+            };
+
+            // collection options and index definitions are compared
+            BaseChecker::compare_struct(src_statement, &mut dst_statement, &self.filter)?;
+        }
+        Ok(())
+    }
+
     fn mock_tb_meta(schema: &str, tb: &str) -> RdbTbMeta {
         RdbTbMeta {
             schema: schema.into(),
@@ -3,28 +3,37 @@ use std::{
     time::Instant,
 };
 
+use anyhow::bail;
 use async_trait::async_trait;
 use mongodb::{
     bson::{doc, Document},
-    options::UpdateOptions,
+    error::ErrorKind,
+    options::{ReplaceOptions, UpdateOptions},
     Client, Collection,
 };
 
-use dt_common::{log_error, monitor::monitor::Monitor};
+use dt_common::{
+    config::config_enums::ConflictPolicyEnum, error::Error, log_error, log_info, log_warn,
+    monitor::monitor::Monitor,
+};
 
 use dt_common::meta::{
-    col_value::ColValue, mongo::mongo_constant::MongoConstants, row_data::RowData,
-    row_type::RowType,
+    col_value::ColValue, ddl_meta::ddl_data::DdlData, mongo::mongo_constant::MongoConstants,
+    row_data::RowData, row_type::RowType,
 };
 
 use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
 
+// NamespaceNotFound, IndexNotFound, NamespaceExists
+const IGNORED_DDL_ERROR_CODES: [i32; 3] = [26, 27, 48];
+
 #[derive(Clone)]
 pub struct MongoSinker {
     pub router: RdbRouter,
     pub batch_size: usize,
     pub mongo_client: Client,
     pub monitor: Arc<Mutex<Monitor>>,
+    pub ddl_conflict_policy: ConflictPolicyEnum,
 }
 
 #[async_trait]
@@ -50,6 +59,44 @@ impl Sinker for MongoSinker {
         Ok(())
     }
 
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        for ddl_data in data {
+            if let Some((db, command)) = ddl_data.statement.to_mongo_command()? {
+                log_info!("sink ddl, db: {}, command: {}", db, ddl_data.to_sql());
+                let error = match self
+                    .mongo_client
+                    .database(&db)
+                    .run_command(command, None)
+                    .await
+                {
+                    Ok(_) => continue,
+                    Err(error) => error,
+                };
+
+                // the ddl was applied already, e.g. the task restarted from an earlier position
+                let code = match error.kind.as_ref() {
+                    ErrorKind::Command(e) => Some(e.code),
+                    _ => None,
+                };
+                if code.is_some_and(|i| IGNORED_DDL_ERROR_CODES.contains(&i)) {
+                    log_info!(
+                        "ddl has no effect, skipped: {}, error: {}",
+                        ddl_data.to_sql(),
+                        error
+                    );
+                    continue;
+                }
+
+                log_error!("ddl failed, error: {}", error);
+                match self.ddl_conflict_policy {
+                    ConflictPolicyEnum::Interrupt => bail! {Error::MongodbError(error)},
+                    ConflictPolicyEnum::Ignore => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
     async fn close(&mut self) -> anyhow::Result<()> {
         self.mongo_client.clone().shutdown().await;
         Ok(())
@@ -93,28 +140,45 @@ impl MongoSinker {
                     let before = row_data.before.as_mut().unwrap();
                     let after = row_data.after.as_mut().unwrap();
 
-                    let query_doc =
-                        if let Some(ColValue::MongoDoc(doc)) = before.remove(MongoConstants::DOC) {
-                            Some(doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()})
-                        } else {
-                            None
-                        };
-
-                    let update_doc =
-                        if let Some(ColValue::MongoDoc(doc)) = after.remove(MongoConstants::DOC) {
-                            Some(doc)
-                        } else if let Some(ColValue::MongoDoc(doc)) =
-                            after.remove(MongoConstants::DIFF_DOC)
-                        {
-                            // for Update row_data from oplog (NOT change stream), after contains diff_doc instead of doc
-                            Some(doc)
-                        } else {
-                            None
-                        };
-
-                    if query_doc.is_some() && update_doc.is_some() {
-                        self.upsert(&collection, query_doc.unwrap(), update_doc.unwrap())
+                    let query_doc = match before.remove(MongoConstants::DOC) {
+                        Some(ColValue::MongoDoc(doc)) => {
+                            doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()}
+                        }
+                        _ => continue,
+                    };
+
+                    if let Some(ColValue::MongoDoc(diff_doc)) =
+                        after.remove(MongoConstants::DIFF_DOC)
+                    {
+                        // minimal update with $set / $unset / $push, NOT upsert since it would
+                        // create a document with only the changed fields if the target is missing
+                        if diff_doc.is_empty() {
+                            continue;
+                        }
+                        let matched_count = self
+                            .update(&collection, query_doc.clone(), diff_doc)
                             .await?;
+                        if matched_count > 0 {
+                            continue;
+                        }
+
+                        // the target document is missing, e.g. filtered in snapshot,
+                        // replace it by the full document if exists
+                        match after.remove(MongoConstants::DOC) {
+                            Some(ColValue::MongoDoc(doc)) => {
+                                self.replace(&collection, query_doc, doc).await?
+                            }
+                            _ => log_warn!(
+                                "partial update can not be applied and the document does not exist in target, ignore, schema: {}, tb: {}, query: {}",
+                                row_data.schema,
+                                row_data.tb,
+                                query_doc
+                            ),
+                        }
+                    } else if let Some(ColValue::MongoDoc(doc)) = after.remove(MongoConstants::DOC)
+                    {
+                        // fallback: replace the whole document
+                        self.replace(&collection, query_doc, doc).await?;
                     }
                 }
             }
@@ -194,6 +258,19 @@ impl MongoSinker {
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
     }
 
+    async fn replace(
+        &mut self,
+        collection: &Collection<Document>,
+        query_doc: Document,
+        doc: Document,
+    ) -> anyhow::Result<()> {
+        let options = ReplaceOptions::builder().upsert(true).build();
+        collection
+            .replace_one(query_doc, doc, Some(options))
+            .await?;
+        Ok(())
+    }
+
     async fn upsert(
         &mut self,
         collection: &Collection<Document>,
@@ -206,4 +283,15 @@ impl MongoSinker {
             .await?;
         Ok(())
     }
+
+    /// returns the count of matched documents
+    async fn update(
+        &mut self,
+        collection: &Collection<Document>,
+        query_doc: Document,
+        update_doc: Document,
+    ) -> anyhow::Result<u64> {
+        let result = collection.update_one(query_doc, update_doc, None).await?;
+        Ok(result.matched_count)
+    }
 }
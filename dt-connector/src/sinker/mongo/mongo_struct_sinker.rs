@@ -0,0 +1,57 @@
+use crate::Sinker;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::ConflictPolicyEnum,
+    error::Error,
+    log_error, log_info,
+    meta::struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
+    rdb_filter::RdbFilter,
+};
+use mongodb::{bson::Bson, Client};
+
+#[derive(Clone)]
+pub struct MongoStructSinker {
+    pub mongo_client: Client,
+    pub conflict_policy: ConflictPolicyEnum,
+    pub filter: RdbFilter,
+}
+
+#[async_trait]
+impl Sinker for MongoStructSinker {
+    async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
+        for struct_data in data {
+            if let StructStatement::MongoCreateCollection(statement) = &struct_data.statement {
+                let db = self.mongo_client.database(&statement.db);
+                for (_, command) in statement.to_commands(&self.filter) {
+                    log_info!(
+                        "ddl begin: {}",
+                        Bson::Document(command.clone()).into_relaxed_extjson()
+                    );
+                    match db.run_command(command, None).await {
+                        Ok(_) => {
+                            log_info!("ddl succeed");
+                        }
+
+                        Err(error) => {
+                            log_error!("ddl failed, error: {}", error);
+                            match self.conflict_policy {
+                                ConflictPolicyEnum::Interrupt => {
+                                    bail! {Error::MongodbError(error)}
+                                }
+                                ConflictPolicyEnum::Ignore => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.mongo_client.clone().shutdown().await;
+        Ok(())
+    }
+}
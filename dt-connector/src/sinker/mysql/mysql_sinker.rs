@@ -37,6 +37,7 @@ pub struct MysqlSinker {
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub replace: bool,
+    pub partial_upsert: bool,
 }
 
 #[async_trait]
@@ -116,10 +117,14 @@ impl MysqlSinker {
                 .await
                 .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
         }
+        let mysql_row_alias =
+            RdbQueryBuilder::supports_mysql_row_alias(&self.meta_manager.meta_fetcher.version);
         for row_data in data.iter() {
             data_size += row_data.data_size;
             let tb_meta = self.meta_manager.get_tb_meta_by_row_data(row_data).await?;
-            let query_builder = RdbQueryBuilder::new_for_mysql(tb_meta, None);
+            let query_builder = RdbQueryBuilder::new_for_mysql(tb_meta, None)
+                .with_partial_upsert(self.partial_upsert)
+                .with_mysql_row_alias(mysql_row_alias);
 
             let query_info = query_builder.get_query_info(row_data, self.replace)?;
             let query = query_builder.create_mysql_query(&query_info);
@@ -176,7 +181,14 @@ impl MysqlSinker {
             .get_tb_meta_by_row_data(&data[0])
             .await?
             .to_owned();
-        let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
+        let query_builder =
+            RdbQueryBuilder::new_for_mysql(&tb_meta, None).with_partial_upsert(self.partial_upsert);
+
+        // rows with part of the cols can not be batch inserted
+        let sub_data = &data[start_index..start_index + batch_size];
+        if self.replace && sub_data.iter().any(|i| query_builder.is_partial_upsert(i)) {
+            return self.serial_sink(sub_data).await;
+        }
 
         let (query_info, data_size) =
             query_builder.get_batch_insert_query(data, start_index, batch_size, self.replace)?;
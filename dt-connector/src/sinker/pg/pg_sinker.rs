@@ -11,8 +11,9 @@ use crate::{
 
 use anyhow::Context;
 use dt_common::{
+    config::config_enums::{ConflictPolicyEnum, DbType},
     log_error, log_info,
-    meta::ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
+    meta::ddl_meta::{ddl_data::DdlData, ddl_translator::DdlTranslator, ddl_type::DdlType},
     monitor::monitor::Monitor,
 };
 use sqlx::{
@@ -34,6 +35,8 @@ pub struct PgSinker {
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub replace: bool,
+    pub partial_upsert: bool,
+    pub ddl_conflict_policy: ConflictPolicyEnum,
 }
 
 #[async_trait]
@@ -61,6 +64,22 @@ impl Sinker for PgSinker {
 
     async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
         for ddl_data in data {
+            // ddls from other db types (e.g. mysql) need to be translated into pg dialect
+            let sqls = match DdlTranslator::translate(&ddl_data, &DbType::Pg) {
+                Ok(sqls) => sqls,
+                Err(error) => {
+                    log_error!("translate ddl failed, error: {}", error);
+                    match self.ddl_conflict_policy {
+                        ConflictPolicyEnum::Interrupt => return Err(error),
+                        ConflictPolicyEnum::Ignore => continue,
+                    }
+                }
+            };
+            if sqls.is_empty() {
+                log_info!("ddl has no effect in pg, skipped: {}", ddl_data.query);
+                continue;
+            }
+
             let (schema, _tb) = ddl_data.get_schema_tb();
             let conn_options = PgConnectOptions::from_str(&self.url)?;
             let mut pool_options = PgPoolOptions::new().max_connections(1);
@@ -68,7 +87,12 @@ impl Sinker for PgSinker {
 
             if !schema.is_empty() {
                 match ddl_data.ddl_type {
-                    DdlType::CreateSchema | DdlType::DropSchema | DdlType::AlterSchema => {}
+                    DdlType::CreateSchema
+                    | DdlType::DropSchema
+                    | DdlType::AlterSchema
+                    | DdlType::CreateDatabase
+                    | DdlType::DropDatabase
+                    | DdlType::AlterDatabase => {}
                     _ => {
                         pool_options = pool_options.after_connect(move |conn, _meta| {
                             let sql = sql.clone();
@@ -81,13 +105,17 @@ impl Sinker for PgSinker {
                 }
             }
 
-            let sql = ddl_data.to_sql();
-            log_info!("sink ddl, schema: {}, sql: {}", schema, sql);
-
             let conn_pool = pool_options.connect_with(conn_options).await?;
-            let query = sqlx::query(&sql);
-            query.execute(&conn_pool).await?;
+            let result = Self::execute_ddl_sqls(&conn_pool, &schema, &sqls).await;
             conn_pool.close().await;
+            // the transaction is rolled back on errors, so no partial ddl is left
+            if let Err(error) = result {
+                log_error!("sink ddl failed, error: {}", error);
+                match self.ddl_conflict_policy {
+                    ConflictPolicyEnum::Interrupt => return Err(error),
+                    ConflictPolicyEnum::Ignore => continue,
+                }
+            }
         }
         Ok(())
     }
@@ -106,6 +134,28 @@ impl Sinker for PgSinker {
 }
 
 impl PgSinker {
+    /// sqls translated from one ddl are executed in a transaction since pg ddls are transactional,
+    /// a single sql is executed directly since some (e.g. CREATE DATABASE) can not run in it
+    async fn execute_ddl_sqls(
+        conn_pool: &Pool<Postgres>,
+        schema: &str,
+        sqls: &[String],
+    ) -> anyhow::Result<()> {
+        if let [sql] = sqls {
+            log_info!("sink ddl, schema: {}, sql: {}", schema, sql);
+            sqlx::query(sql).execute(conn_pool).await?;
+            return Ok(());
+        }
+
+        let mut tx = conn_pool.begin().await?;
+        for sql in sqls {
+            log_info!("sink ddl in transaction, schema: {}, sql: {}", schema, sql);
+            sqlx::query(sql).execute(&mut tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
     async fn serial_sink(&mut self, data: &[RowData]) -> anyhow::Result<()> {
         let start_time = Instant::now();
         let mut data_size = 0;
@@ -121,7 +171,8 @@ impl PgSinker {
             data_size += row_data.data_size;
 
             let tb_meta = self.meta_manager.get_tb_meta_by_row_data(row_data).await?;
-            let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, None);
+            let query_builder =
+                RdbQueryBuilder::new_for_pg(tb_meta, None).with_partial_upsert(self.partial_upsert);
 
             let query_info = query_builder.get_query_info(row_data, self.replace)?;
             let query = query_builder.create_pg_query(&query_info);
@@ -175,7 +226,14 @@ impl PgSinker {
             .get_tb_meta_by_row_data(&data[0])
             .await?
             .to_owned();
-        let query_builder = RdbQueryBuilder::new_for_pg(&tb_meta, None);
+        let query_builder =
+            RdbQueryBuilder::new_for_pg(&tb_meta, None).with_partial_upsert(self.partial_upsert);
+
+        // rows with part of the cols can not be batch inserted
+        let sub_data = &data[start_index..start_index + batch_size];
+        if self.replace && sub_data.iter().any(|i| query_builder.is_partial_upsert(i)) {
+            return self.serial_sink(sub_data).await;
+        }
 
         let (query_info, data_size) =
             query_builder.get_batch_insert_query(data, start_index, batch_size, self.replace)?;
@@ -1,12 +1,13 @@
-use anyhow::bail;
-use dt_common::error::Error;
 use dt_common::meta::redis::{
     redis_entry::RedisEntry,
     redis_object::{
-        HashObject, ListObject, ModuleObject, RedisCmd, SetObject, StringObject, ZsetObject,
+        HashObject, ListObject, ModuleObject, RedisCmd, RedisObject, RedisString, SetObject,
+        StringObject, ZsetObject,
     },
 };
 
+use super::module_rewriter::ModuleRewriter;
+
 const CRC64_TABLE: [u64; 256] = [
     0x0000000000000000,
     0x7ad870c830358979,
@@ -294,10 +295,9 @@ impl EntryRewriter {
         Ok(cmds)
     }
 
-    pub fn rewrite_module(_obj: &mut ModuleObject) -> anyhow::Result<Vec<RedisCmd>> {
-        bail! {Error::RedisRdbError(
-            "module rewrite not implemented".into(),
-        )}
+    /// None if the module is not recognized and should be written by RESTORE
+    pub fn rewrite_module(obj: &ModuleObject) -> anyhow::Result<Option<Vec<RedisCmd>>> {
+        ModuleRewriter::rewrite(obj)
     }
 
     pub fn rewrite_set(obj: &mut SetObject) -> anyhow::Result<Vec<RedisCmd>> {
@@ -346,6 +346,101 @@ impl EntryRewriter {
         Ok(cmd)
     }
 
+    /// rewrite a collection into commands with at most chunk_size elements and about chunk_bytes
+    /// bytes each, the key is deleted before the first chunk and the ttl is set after the last one,
+    /// None if the value can not be split, e.g. string, module
+    pub fn rewrite_in_chunks(
+        entry: &mut RedisEntry,
+        version: f32,
+        chunk_size: usize,
+        chunk_bytes: usize,
+    ) -> anyhow::Result<Option<Vec<RedisCmd>>> {
+        let key = &entry.key;
+        let mut cmds = vec![RedisCmd::from_args(vec![
+            b"del".to_vec(),
+            key.as_bytes().to_vec(),
+        ])];
+
+        match &mut entry.value {
+            RedisObject::List(obj) => cmds.extend(Self::split_into_chunks(
+                "rpush",
+                key,
+                obj.elements.iter().map(|i| vec![i]),
+                chunk_size,
+                chunk_bytes,
+            )),
+            RedisObject::Set(obj) => cmds.extend(Self::split_into_chunks(
+                "sadd",
+                key,
+                obj.elements.iter().map(|i| vec![i]),
+                chunk_size,
+                chunk_bytes,
+            )),
+            RedisObject::Hash(obj) => {
+                // hset with multiple fields is supported since 4.0
+                let name = if version >= 4.0 { "hset" } else { "hmset" };
+                cmds.extend(Self::split_into_chunks(
+                    name,
+                    key,
+                    obj.value.iter().map(|(k, v)| vec![k, v]),
+                    chunk_size,
+                    chunk_bytes,
+                ))
+            }
+            RedisObject::Zset(obj) => cmds.extend(Self::split_into_chunks(
+                "zadd",
+                key,
+                obj.elements.iter().map(|i| vec![&i.score, &i.member]),
+                chunk_size,
+                chunk_bytes,
+            )),
+            // each stream entry is added by a separate xadd
+            RedisObject::Stream(obj) => cmds.append(&mut obj.cmds),
+            _ => return Ok(None),
+        }
+
+        if let Some(expire_cmd) = Self::rewrite_expire(entry)? {
+            cmds.push(expire_cmd);
+        }
+        Ok(Some(cmds))
+    }
+
+    fn split_into_chunks<'a>(
+        name: &str,
+        key: &RedisString,
+        elements: impl Iterator<Item = Vec<&'a RedisString>>,
+        chunk_size: usize,
+        chunk_bytes: usize,
+    ) -> Vec<RedisCmd> {
+        let mut cmds = Vec::new();
+        let mut cmd = RedisCmd::new();
+        let (mut count, mut bytes) = (0, 0);
+        for element in elements {
+            let element_bytes: usize = element.iter().map(|i| i.bytes.len()).sum();
+            // a chunk has at least one element even if it exceeds chunk_bytes
+            if count > 0 && (count >= chunk_size || bytes + element_bytes > chunk_bytes) {
+                cmds.push(cmd);
+                cmd = RedisCmd::new();
+                (count, bytes) = (0, 0);
+            }
+
+            if count == 0 {
+                cmd.add_str_arg(name);
+                cmd.add_redis_arg(key);
+            }
+            for arg in element {
+                cmd.add_redis_arg(arg);
+            }
+            count += 1;
+            bytes += element_bytes;
+        }
+
+        if count > 0 {
+            cmds.push(cmd);
+        }
+        cmds
+    }
+
     pub fn rewrite_expire(entry: &RedisEntry) -> anyhow::Result<Option<RedisCmd>> {
         if entry.expire_ms == 0 {
             return Ok(None);
@@ -368,7 +463,11 @@ impl EntryRewriter {
     }
 
     fn calc_crc64(p: &[u8]) -> u64 {
-        let mut crc: u64 = 0;
+        Self::update_crc64(0, p)
+    }
+
+    /// crc64 (Jones) used by DUMP payloads and RDB files, can be computed incrementally
+    pub fn update_crc64(mut crc: u64, p: &[u8]) -> u64 {
         for b in p {
             let inx = (crc as u8) ^ *b;
             crc = CRC64_TABLE[inx as usize] ^ (crc >> 8);
@@ -376,3 +475,136 @@ impl EntryRewriter {
         crc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::redis::redis_object::{StreamObject, ZSetEntry};
+
+    use super::*;
+
+    fn new_entry(value: RedisObject) -> RedisEntry {
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = RedisString::from("key".to_string());
+        entry.value = value;
+        entry
+    }
+
+    fn strings(count: usize) -> Vec<RedisString> {
+        (0..count)
+            .map(|i| RedisString::from(format!("{:04}", i)))
+            .collect()
+    }
+
+    fn cmds_to_strings(cmds: &[RedisCmd]) -> Vec<String> {
+        cmds.iter().map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn test_rewrite_in_chunks_by_size() {
+        let mut entry = new_entry(RedisObject::List(ListObject {
+            key: RedisString::from("key".to_string()),
+            elements: strings(5),
+        }));
+        entry.expire_ms = 1000;
+
+        let cmds = EntryRewriter::rewrite_in_chunks(&mut entry, 7.0, 2, 1024)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            cmds_to_strings(&cmds),
+            vec![
+                "del key",
+                "rpush key 0000 0001",
+                "rpush key 0002 0003",
+                "rpush key 0004",
+                "pexpire key 1000",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_in_chunks_by_bytes() {
+        // 4 bytes for each member and each score
+        let elements = strings(5)
+            .into_iter()
+            .map(|member| ZSetEntry {
+                member,
+                score: RedisString::from("1.50".to_string()),
+            })
+            .collect();
+        let mut entry = new_entry(RedisObject::Zset(ZsetObject {
+            key: RedisString::from("key".to_string()),
+            elements,
+        }));
+
+        let cmds = EntryRewriter::rewrite_in_chunks(&mut entry, 7.0, 100, 20)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            cmds_to_strings(&cmds),
+            vec![
+                "del key",
+                "zadd key 1.50 0000 1.50 0001",
+                "zadd key 1.50 0002 1.50 0003",
+                "zadd key 1.50 0004",
+            ]
+        );
+
+        // an element larger than chunk_bytes is still written
+        let cmds = EntryRewriter::rewrite_in_chunks(&mut entry, 7.0, 100, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cmds.len(), 6);
+    }
+
+    #[test]
+    fn test_rewrite_hash_in_chunks() {
+        let value: HashMap<RedisString, RedisString> =
+            strings(10).into_iter().zip(strings(10)).collect();
+        let mut entry = new_entry(RedisObject::Hash(HashObject {
+            key: RedisString::from("key".to_string()),
+            value,
+        }));
+
+        let cmds = EntryRewriter::rewrite_in_chunks(&mut entry, 7.0, 3, 1024)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cmds.len(), 5);
+        assert!(cmds[1..].iter().all(|i| i.get_name() == "hset"));
+        let field_count: usize = cmds[1..].iter().map(|i| (i.args.len() - 2) / 2).sum();
+        assert_eq!(field_count, 10);
+
+        let cmds = EntryRewriter::rewrite_in_chunks(&mut entry, 3.2, 3, 1024)
+            .unwrap()
+            .unwrap();
+        assert!(cmds[1..].iter().all(|i| i.get_name() == "hmset"));
+    }
+
+    #[test]
+    fn test_rewrite_in_chunks_not_splittable() {
+        let mut entry = new_entry(RedisObject::String(StringObject {
+            key: RedisString::from("key".to_string()),
+            value: RedisString::from("value".to_string()),
+        }));
+        assert!(EntryRewriter::rewrite_in_chunks(&mut entry, 7.0, 2, 1024)
+            .unwrap()
+            .is_none());
+
+        let mut stream = StreamObject::new();
+        stream.cmds = vec![
+            RedisCmd::from_str_args(&["xadd", "key", "1-0", "f", "v"]),
+            RedisCmd::from_str_args(&["xadd", "key", "2-0", "f", "v"]),
+        ];
+        let mut entry = new_entry(RedisObject::Stream(stream));
+        let cmds = EntryRewriter::rewrite_in_chunks(&mut entry, 7.0, 2, 1024)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            cmds_to_strings(&cmds),
+            vec!["del key", "xadd key 1-0 f v", "xadd key 2-0 f v"]
+        );
+    }
+}
@@ -1,3 +1,7 @@
 pub mod entry_rewriter;
+pub mod module_rewriter;
+pub mod rdb_writer;
+pub mod redis_checker;
 pub mod redis_sinker;
+pub mod redis_snapshot_file_sinker;
 pub mod redis_statistic_sinker;
@@ -0,0 +1,334 @@
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    log_warn,
+    meta::redis::redis_object::{ModuleObject, ModuleValue, RedisCmd, RedisString},
+};
+
+const REJSON: &str = "ReJSON-RL";
+const BLOOM_FILTER: &str = "MBbloom--";
+const CUCKOO_FILTER: &str = "MBbloomCF";
+const TIME_SERIES: &str = "TSDB-TYPE";
+
+// RedisJSON 2.x saves the whole document as a json string
+const REJSON_ENCVERS: [u64; 2] = [2, 3];
+// RedisBloom saves growth / expansion since encver 4
+const BLOOM_ENCVERS: [u64; 1] = [4];
+const CUCKOO_ENCVERS: [u64; 1] = [4];
+
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// rewrite module values into the module's own commands, returns None if the module
+/// is not recognized or its values are not decoded (RedisTimeSeries), in which case
+/// RESTORE should be used
+pub struct ModuleRewriter {}
+
+impl ModuleRewriter {
+    pub fn rewrite(obj: &ModuleObject) -> anyhow::Result<Option<Vec<RedisCmd>>> {
+        type RewriteFn = fn(&ModuleObject) -> Option<Vec<RedisCmd>>;
+        let (encvers, rewrite_fn): (&[u64], RewriteFn) = match obj.module_name.as_str() {
+            REJSON => (&REJSON_ENCVERS, Self::rewrite_json),
+            BLOOM_FILTER => (&BLOOM_ENCVERS, Self::rewrite_bloom),
+            CUCKOO_FILTER => (&CUCKOO_ENCVERS, Self::rewrite_cuckoo),
+            // time series chunks are gorilla compressed and not decoded, written by RESTORE
+            TIME_SERIES => {
+                log_warn!(
+                    "RedisTimeSeries value can not be rewritten, key: [{}], will use RESTORE",
+                    obj.key
+                );
+                return Ok(None);
+            }
+            _ => return Ok(None),
+        };
+
+        if !encvers.contains(&obj.encver) {
+            bail! {Error::SinkerError(format!(
+                "unsupported encoding version of module: [{}], encver: {}, key: [{}], use method=restore instead",
+                obj.module_name, obj.encver, obj.key
+            ))}
+        }
+
+        match rewrite_fn(obj) {
+            Some(cmds) => Ok(Some(cmds)),
+            None => bail! {Error::SinkerError(format!(
+                "failed to decode value of module: [{}], encver: {}, key: [{}]",
+                obj.module_name, obj.encver, obj.key
+            ))},
+        }
+    }
+
+    fn rewrite_json(obj: &ModuleObject) -> Option<Vec<RedisCmd>> {
+        let json = match obj.values.first() {
+            Some(ModuleValue::String(json)) => json,
+            _ => return None,
+        };
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg("JSON.SET");
+        cmd.add_redis_arg(&obj.key);
+        cmd.add_str_arg("$");
+        cmd.add_redis_arg(json);
+        Some(vec![cmd])
+    }
+
+    /// rdb: size, nfilters, options, growth,
+    /// [entries, error, hashes, bpe, bits, n2, bf, size] * nfilters
+    fn rewrite_bloom(obj: &ModuleObject) -> Option<Vec<RedisCmd>> {
+        if obj.values.len() < 4 {
+            return None;
+        }
+
+        let size = Self::get_uint(&obj.values[0])?;
+        let nfilters = Self::get_uint(&obj.values[1])?;
+        let options = Self::get_uint(&obj.values[2])?;
+        let growth = Self::get_uint(&obj.values[3])?;
+        if obj.values.len() != 4 + 8 * nfilters as usize {
+            return None;
+        }
+
+        // same layout as the header returned by BF.SCANDUMP
+        let mut header = Vec::new();
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&(nfilters as u32).to_le_bytes());
+        header.extend_from_slice(&(options as u32).to_le_bytes());
+        header.extend_from_slice(&(growth as u32).to_le_bytes());
+
+        let mut buffers = Vec::new();
+        for link in obj.values[4..].chunks(8) {
+            let entries = Self::get_uint(&link[0])?;
+            let error = Self::get_double(&link[1])?;
+            let hashes = Self::get_uint(&link[2])?;
+            let bpe = Self::get_double(&link[3])?;
+            let bits = Self::get_uint(&link[4])?;
+            let n2 = Self::get_uint(&link[5])?;
+            let bf = Self::get_string(&link[6])?;
+            let link_size = Self::get_uint(&link[7])?;
+
+            header.extend_from_slice(&(bf.bytes.len() as u64).to_le_bytes());
+            header.extend_from_slice(&bits.to_le_bytes());
+            header.extend_from_slice(&link_size.to_le_bytes());
+            header.extend_from_slice(&error.to_le_bytes());
+            header.extend_from_slice(&bpe.to_le_bytes());
+            header.extend_from_slice(&(hashes as u32).to_le_bytes());
+            header.extend_from_slice(&entries.to_le_bytes());
+            header.push(n2 as u8);
+            buffers.push(bf);
+        }
+
+        Some(Self::build_load_chunk_cmds(
+            "BF.LOADCHUNK",
+            &obj.key,
+            header,
+            &buffers,
+        ))
+    }
+
+    /// rdb: numFilters, numBuckets, numItems, numDeletes, bucketSize, maxIterations, expansion,
+    /// [numBuckets, data] * numFilters
+    fn rewrite_cuckoo(obj: &ModuleObject) -> Option<Vec<RedisCmd>> {
+        if obj.values.len() < 7 {
+            return None;
+        }
+
+        let num_filters = Self::get_uint(&obj.values[0])?;
+        let num_buckets = Self::get_uint(&obj.values[1])?;
+        let num_items = Self::get_uint(&obj.values[2])?;
+        let num_deletes = Self::get_uint(&obj.values[3])?;
+        let bucket_size = Self::get_uint(&obj.values[4])?;
+        let max_iterations = Self::get_uint(&obj.values[5])?;
+        let expansion = Self::get_uint(&obj.values[6])?;
+        if obj.values.len() != 7 + 2 * num_filters as usize {
+            return None;
+        }
+
+        // same layout as the header returned by CF.SCANDUMP
+        let mut header = Vec::new();
+        header.extend_from_slice(&num_items.to_le_bytes());
+        header.extend_from_slice(&num_buckets.to_le_bytes());
+        header.extend_from_slice(&num_deletes.to_le_bytes());
+        header.extend_from_slice(&num_filters.to_le_bytes());
+        header.extend_from_slice(&(bucket_size as u16).to_le_bytes());
+        header.extend_from_slice(&(max_iterations as u16).to_le_bytes());
+        header.extend_from_slice(&(expansion as u16).to_le_bytes());
+
+        let mut buffers = Vec::new();
+        for filter in obj.values[7..].chunks(2) {
+            Self::get_uint(&filter[0])?;
+            buffers.push(Self::get_string(&filter[1])?);
+        }
+
+        Some(Self::build_load_chunk_cmds(
+            "CF.LOADCHUNK",
+            &obj.key,
+            header,
+            &buffers,
+        ))
+    }
+
+    /// the header is loaded with iter 1, then each data chunk with iter:
+    /// offset of the chunk in all filter buffers + chunk length + 1,
+    /// a chunk should never cross filter buffers
+    fn build_load_chunk_cmds(
+        cmd_name: &str,
+        key: &RedisString,
+        header: Vec<u8>,
+        buffers: &[&RedisString],
+    ) -> Vec<RedisCmd> {
+        let new_cmd = |iter: usize, data: Vec<u8>| {
+            let mut cmd = RedisCmd::new();
+            cmd.add_str_arg(cmd_name);
+            cmd.add_redis_arg(key);
+            cmd.add_str_arg(&iter.to_string());
+            cmd.add_arg(data);
+            cmd
+        };
+
+        // LOADCHUNK with iter 1 fails if the key already exists
+        let mut del_cmd = RedisCmd::new();
+        del_cmd.add_str_arg("DEL");
+        del_cmd.add_redis_arg(key);
+
+        let mut cmds = vec![del_cmd, new_cmd(1, header)];
+        let mut offset = 0;
+        for buffer in buffers {
+            for chunk in buffer.bytes.chunks(MAX_CHUNK_SIZE) {
+                offset += chunk.len();
+                cmds.push(new_cmd(offset + 1, chunk.to_vec()));
+            }
+        }
+        cmds
+    }
+
+    fn get_uint(value: &ModuleValue) -> Option<u64> {
+        match value {
+            ModuleValue::UInt(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn get_double(value: &ModuleValue) -> Option<f64> {
+        match value {
+            ModuleValue::Double(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn get_string(value: &ModuleValue) -> Option<&RedisString> {
+        match value {
+            ModuleValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_module_obj(module_name: &str, encver: u64, values: Vec<ModuleValue>) -> ModuleObject {
+        let mut obj = ModuleObject::new();
+        obj.key = RedisString::from("key_1".to_string());
+        obj.module_name = module_name.into();
+        obj.encver = encver;
+        obj.values = values;
+        obj
+    }
+
+    #[test]
+    fn test_rewrite_json() {
+        let json = r#"{"a":1,"b":["x"]}"#;
+        let obj = new_module_obj(
+            REJSON,
+            3,
+            vec![ModuleValue::String(RedisString::from(json.to_string()))],
+        );
+        let cmds = ModuleRewriter::rewrite(&obj).unwrap().unwrap();
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(
+            cmds[0].args_to_string(),
+            vec!["JSON.SET", "key_1", "$", json]
+        );
+
+        // RedisJSON 1.x tree encoding
+        let obj = new_module_obj(REJSON, 0, vec![ModuleValue::UInt(1)]);
+        assert!(ModuleRewriter::rewrite(&obj).is_err());
+    }
+
+    #[test]
+    fn test_rewrite_bloom() {
+        let bf_1 = RedisString::from(vec![1u8; 10]);
+        let bf_2 = RedisString::from(vec![2u8; 20]);
+        let mut values = vec![
+            ModuleValue::UInt(3),
+            ModuleValue::UInt(2),
+            ModuleValue::UInt(0),
+            ModuleValue::UInt(2),
+        ];
+        for bf in [bf_1, bf_2] {
+            values.extend_from_slice(&[
+                ModuleValue::UInt(100),
+                ModuleValue::Double(0.01),
+                ModuleValue::UInt(7),
+                ModuleValue::Double(9.585),
+                ModuleValue::UInt(bf.bytes.len() as u64 * 8),
+                ModuleValue::UInt(0),
+                ModuleValue::String(bf),
+                ModuleValue::UInt(1),
+            ]);
+        }
+
+        let obj = new_module_obj(BLOOM_FILTER, 4, values.clone());
+        let cmds = ModuleRewriter::rewrite(&obj).unwrap().unwrap();
+        assert_eq!(cmds.len(), 4);
+        assert_eq!(cmds[0].args_to_string(), vec!["DEL", "key_1"]);
+        // header: 20 bytes + 53 bytes per link
+        assert_eq!(cmds[1].get_str_arg(2), "1");
+        assert_eq!(cmds[1].args[3].len(), 20 + 53 * 2);
+        assert_eq!(cmds[2].get_str_arg(2), "11");
+        assert_eq!(cmds[2].args[3], vec![1u8; 10]);
+        assert_eq!(cmds[3].get_str_arg(2), "31");
+        assert_eq!(cmds[3].args[3], vec![2u8; 20]);
+
+        // without growth
+        let obj = new_module_obj(BLOOM_FILTER, 2, values.clone());
+        assert!(ModuleRewriter::rewrite(&obj).is_err());
+
+        // values not match nfilters
+        let obj = new_module_obj(BLOOM_FILTER, 4, values[..values.len() - 1].to_vec());
+        assert!(ModuleRewriter::rewrite(&obj).is_err());
+    }
+
+    #[test]
+    fn test_rewrite_cuckoo() {
+        let mut values: Vec<ModuleValue> = [1, 8, 2, 0, 2, 20, 1]
+            .into_iter()
+            .map(ModuleValue::UInt)
+            .collect();
+        values.push(ModuleValue::UInt(8));
+        values.push(ModuleValue::String(RedisString::from(vec![0u8; 16])));
+
+        let obj = new_module_obj(CUCKOO_FILTER, 4, values.clone());
+        let cmds = ModuleRewriter::rewrite(&obj).unwrap().unwrap();
+        assert_eq!(cmds.len(), 3);
+        assert_eq!(cmds[1].args[0], b"CF.LOADCHUNK".to_vec());
+        assert_eq!(cmds[1].args[3].len(), 8 * 4 + 2 * 3);
+        assert_eq!(cmds[2].get_str_arg(2), "17");
+
+        // older encodings have no bucketSize / maxIterations / expansion
+        for encver in [2, 3, 5] {
+            let obj = new_module_obj(CUCKOO_FILTER, encver, values.clone());
+            assert!(ModuleRewriter::rewrite(&obj).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rewrite_unknown_module() {
+        let obj = new_module_obj("search000", 2, vec![]);
+        assert!(ModuleRewriter::rewrite(&obj).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rewrite_time_series() {
+        let obj = new_module_obj(TIME_SERIES, 6, vec![]);
+        assert!(ModuleRewriter::rewrite(&obj).unwrap().is_none());
+    }
+}
@@ -0,0 +1,685 @@
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    meta::redis::{
+        redis_entry::RedisEntry,
+        redis_object::{
+            HashObject, ListObject, RedisObject, RedisString, SetObject, StringObject, ZsetObject,
+        },
+    },
+};
+
+use super::entry_rewriter::EntryRewriter;
+
+// refer: https://github.com/redis/redis/blob/unstable/src/rdb.h
+const RDB_TYPE_STRING: u8 = 0;
+const RDB_TYPE_SET: u8 = 2;
+const RDB_TYPE_HASH: u8 = 4;
+const RDB_TYPE_ZSET_2: u8 = 5;
+const RDB_TYPE_MODULE_2: u8 = 7;
+const RDB_TYPE_ZSET_ZIPLIST: u8 = 12;
+const RDB_TYPE_HASH_ZIPLIST: u8 = 13;
+const RDB_TYPE_LIST_QUICKLIST: u8 = 14;
+const RDB_TYPE_STREAM_LISTPACKS: u8 = 15;
+const RDB_TYPE_HASH_LISTPACK: u8 = 16;
+const RDB_TYPE_ZSET_LISTPACK: u8 = 17;
+const RDB_TYPE_LIST_QUICKLIST_2: u8 = 18;
+const RDB_TYPE_STREAM_LISTPACKS_2: u8 = 19;
+const RDB_TYPE_SET_LISTPACK: u8 = 20;
+const RDB_TYPE_STREAM_LISTPACKS_3: u8 = 21;
+
+const RDB_OPCODE_IDLE: u8 = 0xf8;
+const RDB_OPCODE_FREQ: u8 = 0xf9;
+const RDB_OPCODE_AUX: u8 = 0xfa;
+const RDB_OPCODE_EXPIRETIME_MS: u8 = 0xfc;
+const RDB_OPCODE_SELECTDB: u8 = 0xfe;
+const RDB_OPCODE_EOF: u8 = 0xff;
+
+const QUICKLIST_NODE_CONTAINER_PACKED: u8 = 2;
+
+// Redis 5.0 / 6.x
+const MIN_RDB_VERSION: u32 = 9;
+// Redis 7.4
+const MAX_RDB_VERSION: u32 = 12;
+// listpack replaces ziplist since Redis 7.0
+const LISTPACK_RDB_VERSION: u32 = 10;
+// small sets are encoded as listpack since Redis 7.2
+const SET_LISTPACK_RDB_VERSION: u32 = 11;
+
+// default thresholds of Redis: hash/zset/set-max-listpack-entries, hash/zset-max-listpack-value
+const MAX_PACKED_ENTRIES: usize = 128;
+const MAX_PACKED_VALUE: usize = 64;
+// default list-max-listpack-size: -2 (8 KB per quicklist node)
+const MAX_QUICKLIST_NODE_SIZE: usize = 8 * 1024;
+
+/// serialize redis entries into RDB format, the output of each write_* should be
+/// appended to the file in order, the CRC64 checksum is accumulated by the writer
+pub struct RdbWriter {
+    pub version: u32,
+    now_db_id: i64,
+    crc: u64,
+}
+
+impl RdbWriter {
+    pub fn new(version: u32) -> anyhow::Result<Self> {
+        if !(MIN_RDB_VERSION..=MAX_RDB_VERSION).contains(&version) {
+            bail! {Error::ConfigError(format!(
+                "rdb_version should be between {} and {}, got: {}",
+                MIN_RDB_VERSION, MAX_RDB_VERSION, version
+            ))}
+        }
+        Ok(Self {
+            version,
+            now_db_id: -1,
+            crc: 0,
+        })
+    }
+
+    pub fn write_header(&mut self, ctime_secs: i64) -> Vec<u8> {
+        let mut buf = format!("REDIS{:04}", self.version).into_bytes();
+        Self::write_aux(&mut buf, "redis-bits", b"64");
+        Self::write_aux(&mut buf, "ctime", ctime_secs.to_string().as_bytes());
+        self.checksum(buf)
+    }
+
+    /// returns None if the entry can not be written into rdb
+    pub fn write_entry(
+        &mut self,
+        entry: &RedisEntry,
+        now_ms: i64,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut buf = Vec::new();
+        if entry.db_id != self.now_db_id {
+            buf.push(RDB_OPCODE_SELECTDB);
+            Self::write_length(&mut buf, entry.db_id as u64);
+        }
+
+        if !entry.cmd.args.is_empty() {
+            // lua scripts in rdb aux fields are parsed as commands
+            if !Self::write_script(&mut buf, entry) {
+                return Ok(None);
+            }
+        } else {
+            let mut value_buf = Vec::new();
+            let type_byte = match &entry.value {
+                RedisObject::String(obj) => self.write_string_obj(&mut value_buf, obj),
+                RedisObject::List(obj) => self.write_list_obj(&mut value_buf, obj),
+                RedisObject::Set(obj) => self.write_set_obj(&mut value_buf, obj),
+                RedisObject::Zset(obj) => self.write_zset_obj(&mut value_buf, obj)?,
+                RedisObject::Hash(obj) => self.write_hash_obj(&mut value_buf, obj),
+                // streams and module values are written by the raw serialized value
+                RedisObject::Stream(_) | RedisObject::Module(_) => {
+                    if !entry.is_raw() || !self.is_raw_type_supported(entry.value_type_byte) {
+                        return Ok(None);
+                    }
+                    value_buf.extend_from_slice(&entry.raw_bytes);
+                    entry.value_type_byte
+                }
+                RedisObject::Unknown => return Ok(None),
+            };
+
+            if entry.expire_ms > 0 {
+                buf.push(RDB_OPCODE_EXPIRETIME_MS);
+                buf.extend_from_slice(&(now_ms + entry.expire_ms).to_le_bytes());
+            }
+            if entry.freq >= 0 {
+                buf.push(RDB_OPCODE_FREQ);
+                buf.push(entry.freq.min(u8::MAX as i64) as u8);
+            } else if entry.idle >= 0 {
+                buf.push(RDB_OPCODE_IDLE);
+                Self::write_length(&mut buf, entry.idle as u64);
+            }
+            buf.push(type_byte);
+            Self::write_string(&mut buf, entry.key.as_bytes());
+            buf.extend_from_slice(&value_buf);
+        }
+
+        self.now_db_id = entry.db_id;
+        Ok(Some(self.checksum(buf)))
+    }
+
+    pub fn write_eof(&mut self) -> Vec<u8> {
+        let mut buf = self.checksum(vec![RDB_OPCODE_EOF]);
+        buf.extend_from_slice(&self.crc.to_le_bytes());
+        buf
+    }
+
+    fn checksum(&mut self, buf: Vec<u8>) -> Vec<u8> {
+        self.crc = EntryRewriter::update_crc64(self.crc, &buf);
+        buf
+    }
+
+    fn write_script(buf: &mut Vec<u8>, entry: &RedisEntry) -> bool {
+        // script load [body]
+        if entry.cmd.args.len() != 3 || !entry.cmd.get_str_arg(0).eq_ignore_ascii_case("script") {
+            return false;
+        }
+        Self::write_aux(buf, "lua", &entry.cmd.args[2]);
+        true
+    }
+
+    fn write_string_obj(&self, buf: &mut Vec<u8>, obj: &StringObject) -> u8 {
+        Self::write_string(buf, obj.value.as_bytes());
+        RDB_TYPE_STRING
+    }
+
+    fn write_list_obj(&self, buf: &mut Vec<u8>, obj: &ListObject) -> u8 {
+        let mut nodes: Vec<&[RedisString]> = Vec::new();
+        let (mut start, mut node_size) = (0, 0);
+        for (i, ele) in obj.elements.iter().enumerate() {
+            if i > start && node_size + ele.bytes.len() > MAX_QUICKLIST_NODE_SIZE {
+                nodes.push(&obj.elements[start..i]);
+                (start, node_size) = (i, 0);
+            }
+            node_size += ele.bytes.len();
+        }
+        nodes.push(&obj.elements[start..]);
+
+        Self::write_length(buf, nodes.len() as u64);
+        if self.version >= LISTPACK_RDB_VERSION {
+            for node in nodes {
+                Self::write_length(buf, QUICKLIST_NODE_CONTAINER_PACKED as u64);
+                Self::write_string(buf, &Self::build_list_pack(node));
+            }
+            RDB_TYPE_LIST_QUICKLIST_2
+        } else {
+            for node in nodes {
+                Self::write_string(buf, &Self::build_zip_list(node));
+            }
+            RDB_TYPE_LIST_QUICKLIST
+        }
+    }
+
+    fn write_set_obj(&self, buf: &mut Vec<u8>, obj: &SetObject) -> u8 {
+        if self.version >= SET_LISTPACK_RDB_VERSION
+            && Self::can_pack(obj.elements.len(), obj.elements.iter())
+        {
+            Self::write_string(buf, &Self::build_list_pack(&obj.elements));
+            return RDB_TYPE_SET_LISTPACK;
+        }
+
+        Self::write_length(buf, obj.elements.len() as u64);
+        for ele in obj.elements.iter() {
+            Self::write_string(buf, ele.as_bytes());
+        }
+        RDB_TYPE_SET
+    }
+
+    fn write_hash_obj(&self, buf: &mut Vec<u8>, obj: &HashObject) -> u8 {
+        if Self::can_pack(obj.value.len(), obj.value.iter().flat_map(|(k, v)| [k, v])) {
+            let elements: Vec<RedisString> = obj
+                .value
+                .iter()
+                .flat_map(|(k, v)| [k.clone(), v.clone()])
+                .collect();
+            return self.write_packed(
+                buf,
+                &elements,
+                RDB_TYPE_HASH_LISTPACK,
+                RDB_TYPE_HASH_ZIPLIST,
+            );
+        }
+
+        Self::write_length(buf, obj.value.len() as u64);
+        for (field, value) in obj.value.iter() {
+            Self::write_string(buf, field.as_bytes());
+            Self::write_string(buf, value.as_bytes());
+        }
+        RDB_TYPE_HASH
+    }
+
+    fn write_zset_obj(&self, buf: &mut Vec<u8>, obj: &ZsetObject) -> anyhow::Result<u8> {
+        let mut scores = Vec::with_capacity(obj.elements.len());
+        for i in obj.elements.iter() {
+            match i.score.to_string().parse::<f64>() {
+                Ok(score) => scores.push(score),
+                Err(_) => bail! {Error::RedisRdbError(format!(
+                    "invalid zset score: {}, key: {}",
+                    i.score, obj.key
+                ))},
+            }
+        }
+
+        // member and score are saved as elements of the packed list, in ascending order by score
+        if Self::can_pack(obj.elements.len(), obj.elements.iter().map(|i| &i.member)) {
+            let mut indexes: Vec<usize> = (0..obj.elements.len()).collect();
+            indexes.sort_by(|a, b| {
+                scores[*a].total_cmp(&scores[*b]).then_with(|| {
+                    obj.elements[*a]
+                        .member
+                        .bytes
+                        .cmp(&obj.elements[*b].member.bytes)
+                })
+            });
+            let mut elements = Vec::with_capacity(obj.elements.len() * 2);
+            for i in indexes {
+                elements.push(obj.elements[i].member.clone());
+                elements.push(RedisString::from(Self::format_score(scores[i])));
+            }
+            return Ok(self.write_packed(
+                buf,
+                &elements,
+                RDB_TYPE_ZSET_LISTPACK,
+                RDB_TYPE_ZSET_ZIPLIST,
+            ));
+        }
+
+        Self::write_length(buf, obj.elements.len() as u64);
+        for (i, score) in obj.elements.iter().zip(scores) {
+            Self::write_string(buf, i.member.as_bytes());
+            buf.extend_from_slice(&score.to_le_bytes());
+        }
+        Ok(RDB_TYPE_ZSET_2)
+    }
+
+    fn write_packed(
+        &self,
+        buf: &mut Vec<u8>,
+        elements: &[RedisString],
+        list_pack_type: u8,
+        zip_list_type: u8,
+    ) -> u8 {
+        if self.version >= LISTPACK_RDB_VERSION {
+            Self::write_string(buf, &Self::build_list_pack(elements));
+            list_pack_type
+        } else {
+            Self::write_string(buf, &Self::build_zip_list(elements));
+            zip_list_type
+        }
+    }
+
+    fn is_raw_type_supported(&self, type_byte: u8) -> bool {
+        match type_byte {
+            RDB_TYPE_STREAM_LISTPACKS => true,
+            RDB_TYPE_STREAM_LISTPACKS_2 => self.version >= 10,
+            RDB_TYPE_STREAM_LISTPACKS_3 => self.version >= 11,
+            RDB_TYPE_MODULE_2 => true,
+            _ => false,
+        }
+    }
+
+    fn can_pack<'a>(len: usize, mut elements: impl Iterator<Item = &'a RedisString>) -> bool {
+        len <= MAX_PACKED_ENTRIES && elements.all(|i| i.bytes.len() <= MAX_PACKED_VALUE)
+    }
+
+    /// same as d2string in Redis: integral scores are formatted without fraction
+    fn format_score(score: f64) -> String {
+        if score.is_infinite() {
+            return if score > 0.0 { "inf" } else { "-inf" }.into();
+        }
+        if score.fract() == 0.0 && score.abs() < (1u64 << 53) as f64 {
+            return (score as i64).to_string();
+        }
+        format!("{}", score)
+    }
+
+    fn write_aux(buf: &mut Vec<u8>, key: &str, value: &[u8]) {
+        buf.push(RDB_OPCODE_AUX);
+        Self::write_string(buf, key.as_bytes());
+        Self::write_string(buf, value);
+    }
+
+    fn write_length(buf: &mut Vec<u8>, len: u64) {
+        if len < 1 << 6 {
+            buf.push(len as u8);
+        } else if len < 1 << 14 {
+            buf.push(0x40 | (len >> 8) as u8);
+            buf.push(len as u8);
+        } else if len <= u32::MAX as u64 {
+            buf.push(0x80);
+            buf.extend_from_slice(&(len as u32).to_be_bytes());
+        } else {
+            buf.push(0x81);
+            buf.extend_from_slice(&len.to_be_bytes());
+        }
+    }
+
+    fn write_string(buf: &mut Vec<u8>, value: &[u8]) {
+        Self::write_length(buf, value.len() as u64);
+        buf.extend_from_slice(value);
+    }
+
+    /// parse the value as integer only if it is the canonical representation, like string2ll
+    fn parse_int(value: &RedisString, max_len: usize) -> Option<i64> {
+        if value.bytes.is_empty() || value.bytes.len() > max_len {
+            return None;
+        }
+        let s = std::str::from_utf8(&value.bytes).ok()?;
+        let v = s.parse::<i64>().ok()?;
+        if v.to_string() == s {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    // refer: https://github.com/redis/redis/blob/unstable/src/listpack.c
+    fn build_list_pack(elements: &[RedisString]) -> Vec<u8> {
+        let mut buf = vec![0u8; 6];
+        for ele in elements.iter() {
+            let start = buf.len();
+            if let Some(v) = Self::parse_int(ele, 20) {
+                if (0..=127).contains(&v) {
+                    buf.push(v as u8);
+                } else if (-4096..=4095).contains(&v) {
+                    let uv = (v as u64) & 0x1fff;
+                    buf.push(0xc0 | (uv >> 8) as u8);
+                    buf.push(uv as u8);
+                } else if (i16::MIN as i64..=i16::MAX as i64).contains(&v) {
+                    buf.push(0xf1);
+                    buf.extend_from_slice(&(v as i16).to_le_bytes());
+                } else if (-(1 << 23)..(1 << 23)).contains(&v) {
+                    buf.push(0xf2);
+                    buf.extend_from_slice(&(v as i32).to_le_bytes()[..3]);
+                } else if (i32::MIN as i64..=i32::MAX as i64).contains(&v) {
+                    buf.push(0xf3);
+                    buf.extend_from_slice(&(v as i32).to_le_bytes());
+                } else {
+                    buf.push(0xf4);
+                    buf.extend_from_slice(&v.to_le_bytes());
+                }
+            } else {
+                let len = ele.bytes.len();
+                if len < 64 {
+                    buf.push(0x80 | len as u8);
+                } else if len < 4096 {
+                    buf.push(0xe0 | (len >> 8) as u8);
+                    buf.push(len as u8);
+                } else {
+                    buf.push(0xf0);
+                    buf.extend_from_slice(&(len as u32).to_le_bytes());
+                }
+                buf.extend_from_slice(ele.as_bytes());
+            }
+
+            let entry_len = buf.len() - start;
+            Self::write_list_pack_backlen(&mut buf, entry_len);
+        }
+        buf.push(0xff);
+
+        let total_bytes = buf.len() as u32;
+        let num_elements = elements.len().min(u16::MAX as usize) as u16;
+        buf[0..4].copy_from_slice(&total_bytes.to_le_bytes());
+        buf[4..6].copy_from_slice(&num_elements.to_le_bytes());
+        buf
+    }
+
+    fn write_list_pack_backlen(buf: &mut Vec<u8>, len: usize) {
+        let mut bytes = vec![(len & 127) as u8];
+        let mut rest = len >> 7;
+        while rest > 0 {
+            bytes.push((rest & 127) as u8);
+            rest >>= 7;
+        }
+        // the lowest 7 bits are at the end, every byte except the first one has the highest bit set
+        for (i, b) in bytes.iter().rev().enumerate() {
+            if i == 0 {
+                buf.push(*b);
+            } else {
+                buf.push(*b | 128);
+            }
+        }
+    }
+
+    // refer: https://github.com/redis/redis/blob/7.0/src/ziplist.c
+    fn build_zip_list(elements: &[RedisString]) -> Vec<u8> {
+        let mut buf = vec![0u8; 10];
+        let mut prev_len = 0;
+        let mut tail_offset = buf.len();
+        for ele in elements.iter() {
+            let start = buf.len();
+            tail_offset = start;
+            if prev_len < 254 {
+                buf.push(prev_len as u8);
+            } else {
+                buf.push(0xfe);
+                buf.extend_from_slice(&(prev_len as u32).to_le_bytes());
+            }
+
+            if let Some(v) = Self::parse_int(ele, 31) {
+                if (0..=12).contains(&v) {
+                    buf.push(0xf1 + v as u8);
+                } else if (i8::MIN as i64..=i8::MAX as i64).contains(&v) {
+                    buf.push(0xfe);
+                    buf.push(v as i8 as u8);
+                } else if (i16::MIN as i64..=i16::MAX as i64).contains(&v) {
+                    buf.push(0xc0);
+                    buf.extend_from_slice(&(v as i16).to_le_bytes());
+                } else if (-(1 << 23)..(1 << 23)).contains(&v) {
+                    buf.push(0xf0);
+                    buf.extend_from_slice(&(v as i32).to_le_bytes()[..3]);
+                } else if (i32::MIN as i64..=i32::MAX as i64).contains(&v) {
+                    buf.push(0xd0);
+                    buf.extend_from_slice(&(v as i32).to_le_bytes());
+                } else {
+                    buf.push(0xe0);
+                    buf.extend_from_slice(&v.to_le_bytes());
+                }
+            } else {
+                let len = ele.bytes.len();
+                if len <= 0x3f {
+                    buf.push(len as u8);
+                } else if len <= 0x3fff {
+                    buf.push(0x40 | (len >> 8) as u8);
+                    buf.push(len as u8);
+                } else {
+                    buf.push(0x80);
+                    buf.extend_from_slice(&(len as u32).to_be_bytes());
+                }
+                buf.extend_from_slice(ele.as_bytes());
+            }
+            prev_len = buf.len() - start;
+        }
+        buf.push(0xff);
+
+        let total_bytes = buf.len() as u32;
+        let num_elements = elements.len().min(u16::MAX as usize) as u16;
+        buf[0..4].copy_from_slice(&total_bytes.to_le_bytes());
+        buf[4..8].copy_from_slice(&(tail_offset as u32).to_le_bytes());
+        buf[8..10].copy_from_slice(&num_elements.to_le_bytes());
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, io::Cursor};
+
+    use dt_common::meta::redis::redis_object::{StreamObject, ZSetEntry};
+
+    use super::*;
+    use crate::extractor::redis::{
+        rdb::{rdb_parser::RdbParser, reader::rdb_reader::RdbReader},
+        StreamReader,
+    };
+
+    fn rs(s: &str) -> RedisString {
+        RedisString::from(s.to_string())
+    }
+
+    fn new_entry(db_id: i64, key: &str, value: RedisObject) -> RedisEntry {
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.db_id = db_id;
+        entry.key = rs(key);
+        entry.value = value;
+        entry
+    }
+
+    fn build_entries(big: bool) -> Vec<RedisEntry> {
+        let count = if big { 1000 } else { 10 };
+        let values: Vec<String> = (0..count)
+            .map(|i| match i % 4 {
+                0 => i.to_string(),
+                1 => (-(i as i64) * 100000).to_string(),
+                2 => format!("v_{}", i),
+                _ => "x".repeat(if big { 100 } else { 20 }),
+            })
+            .collect();
+
+        let mut string_obj = StringObject::new();
+        string_obj.value = rs("hello");
+        let mut list_obj = ListObject::new();
+        list_obj.elements = values.iter().map(|i| rs(i)).collect();
+        let mut set_obj = SetObject::new();
+        set_obj.elements = (0..count).map(|i| rs(&format!("m_{}", i))).collect();
+        let mut hash_obj = HashObject::new();
+        for (i, v) in values.iter().enumerate() {
+            hash_obj.value.insert(rs(&format!("f_{}", i)), rs(v));
+        }
+        let mut zset_obj = ZsetObject::new();
+        for i in 0..count {
+            zset_obj.elements.push(ZSetEntry {
+                member: rs(&format!("m_{}", i)),
+                score: rs(&format!("{}", (count - i) as f64 / 4.0)),
+            });
+        }
+
+        let mut entries = vec![
+            new_entry(0, "string_1", RedisObject::String(string_obj)),
+            new_entry(0, "list_1", RedisObject::List(list_obj)),
+            new_entry(1, "set_1", RedisObject::Set(set_obj)),
+            new_entry(1, "hash_1", RedisObject::Hash(hash_obj)),
+            new_entry(2, "zset_1", RedisObject::Zset(zset_obj)),
+        ];
+        entries[0].expire_ms = 100000;
+        entries[1].freq = 5;
+        entries[2].idle = 10;
+        entries
+    }
+
+    fn write_rdb(version: u32, entries: &[RedisEntry]) -> Vec<u8> {
+        let mut writer = RdbWriter::new(version).unwrap();
+        let mut rdb = writer.write_header(0);
+        for entry in entries {
+            rdb.extend(writer.write_entry(entry, 0).unwrap().unwrap());
+        }
+        rdb.extend(writer.write_eof());
+        rdb
+    }
+
+    fn parse_rdb(rdb: &[u8]) -> Vec<RedisEntry> {
+        let mut cursor = Cursor::new(rdb);
+        let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(&mut cursor);
+        let reader = RdbReader {
+            conn: &mut stream_reader,
+            rdb_length: rdb.len(),
+            position: 0,
+            copy_raw: false,
+            raw_bytes: Vec::new(),
+        };
+        let mut parser = RdbParser {
+            reader,
+            repl_stream_db_id: 0,
+            now_db_id: 0,
+            expire_ms: 0,
+            idle: -1,
+            freq: -1,
+            is_end: false,
+        };
+        parser.load_meta().unwrap();
+
+        let mut entries = Vec::new();
+        while !parser.is_end {
+            if let Some(entry) = parser.load_entry().unwrap() {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    fn assert_entries_eq(expected: &[RedisEntry], actual: &[RedisEntry]) {
+        assert_eq!(expected.len(), actual.len());
+        for (e, a) in expected.iter().zip(actual) {
+            assert_eq!(e.db_id, a.db_id);
+            assert_eq!(e.key, a.key);
+            assert_eq!(e.freq, a.freq);
+            assert_eq!(e.idle, a.idle);
+            assert_eq!(e.expire_ms > 0, a.expire_ms > 0);
+            match (&e.value, &a.value) {
+                (RedisObject::String(e), RedisObject::String(a)) => assert_eq!(e.value, a.value),
+                (RedisObject::List(e), RedisObject::List(a)) => assert_eq!(e.elements, a.elements),
+                (RedisObject::Set(e), RedisObject::Set(a)) => assert_eq!(e.elements, a.elements),
+                (RedisObject::Hash(e), RedisObject::Hash(a)) => assert_eq!(e.value, a.value),
+                (RedisObject::Zset(e), RedisObject::Zset(a)) => {
+                    let to_map = |obj: &ZsetObject| -> HashMap<RedisString, f64> {
+                        obj.elements
+                            .iter()
+                            .map(|i| (i.member.clone(), i.score.to_string().parse().unwrap()))
+                            .collect()
+                    };
+                    assert_eq!(to_map(e), to_map(a));
+                }
+                _ => panic!("type mismatch, key: {}", e.key),
+            }
+        }
+    }
+
+    #[test]
+    fn test_crc64() {
+        assert_eq!(
+            EntryRewriter::update_crc64(0, b"123456789"),
+            0xe9c6d914c4b8d9ca
+        );
+        let crc = EntryRewriter::update_crc64(0, b"12345");
+        assert_eq!(
+            EntryRewriter::update_crc64(crc, b"6789"),
+            0xe9c6d914c4b8d9ca
+        );
+    }
+
+    #[test]
+    fn test_write_rdb() {
+        for version in [9, 10, 11, 12] {
+            for big in [false, true] {
+                let entries = build_entries(big);
+                let rdb = write_rdb(version, &entries);
+                assert_eq!(&rdb[..9], format!("REDIS{:04}", version).as_bytes());
+
+                let (content, checksum) = rdb.split_at(rdb.len() - 8);
+                assert_eq!(
+                    EntryRewriter::update_crc64(0, content).to_le_bytes(),
+                    checksum
+                );
+                assert_entries_eq(&entries, &parse_rdb(&rdb));
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_encodings() {
+        let get_type_bytes = |version: u32, big: bool| -> Vec<u8> {
+            let mut writer = RdbWriter::new(version).unwrap();
+            build_entries(big)
+                .into_iter()
+                .map(|mut entry| {
+                    (entry.db_id, entry.expire_ms, entry.freq, entry.idle) = (0, 0, -1, -1);
+                    writer.now_db_id = -1;
+                    // selectdb, db_id, type
+                    writer.write_entry(&entry, 0).unwrap().unwrap()[2]
+                })
+                .collect()
+        };
+
+        assert_eq!(get_type_bytes(9, false), vec![0, 14, 2, 13, 12]);
+        assert_eq!(get_type_bytes(10, false), vec![0, 18, 2, 16, 17]);
+        assert_eq!(get_type_bytes(11, false), vec![0, 18, 20, 16, 17]);
+        assert_eq!(get_type_bytes(11, true), vec![0, 18, 2, 4, 5]);
+
+        let entries = build_entries(false);
+        let mut writer = RdbWriter::new(9).unwrap();
+        let buf = writer.write_entry(&entries[1], 0).unwrap().unwrap();
+        assert_eq!(buf[..5], [RDB_OPCODE_SELECTDB, 0, RDB_OPCODE_FREQ, 5, 14]);
+        // no selectdb for the same db
+        let buf = writer.write_entry(&entries[0], 1000).unwrap().unwrap();
+        assert_eq!(buf[0], RDB_OPCODE_EXPIRETIME_MS);
+        assert_eq!(buf[1..9], 101000i64.to_le_bytes());
+
+        // stream without raw bytes
+        let entry = new_entry(0, "stream_1", RedisObject::Stream(StreamObject::new()));
+        assert!(writer.write_entry(&entry, 0).unwrap().is_none());
+
+        assert!(RdbWriter::new(8).is_err());
+        assert!(RdbWriter::new(13).is_err());
+    }
+}
@@ -0,0 +1,421 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    error::Error,
+    meta::{
+        dt_data::{DtData, DtItem},
+        redis::{
+            cluster_node::ClusterNode,
+            command::cmd_encoder::CmdEncoder,
+            redis_constant::RedisConstants,
+            redis_entry::RedisEntry,
+            redis_object::{RedisCmd, RedisObject, RedisString},
+        },
+    },
+    monitor::monitor::Monitor,
+};
+use redis::{Connection, ConnectionLike, Value};
+
+use crate::{
+    call_batch_fn,
+    check_log::{
+        check_log::{CheckLog, DiffColValue},
+        log_type::LogType,
+    },
+    sinker::{base_checker::BaseChecker, base_sinker::BaseSinker},
+    Sinker,
+};
+
+// max diff items of a single collection key to be written into check log
+const MAX_DIFF_COUNT: usize = 10;
+
+pub struct RedisChecker {
+    pub cluster_node: Option<ClusterNode>,
+    pub batch_size: usize,
+    pub conn: Connection,
+    pub now_db_id: i64,
+    pub ttl_tolerance_secs: u64,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+#[async_trait]
+impl Sinker for RedisChecker {
+    async fn sink_raw(&mut self, mut data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        call_batch_fn!(self, data, Self::batch_check);
+        Ok(())
+    }
+
+    fn get_id(&self) -> String {
+        if let Some(node) = &self.cluster_node {
+            node.address.clone()
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl RedisChecker {
+    async fn batch_check(
+        &mut self,
+        data: &mut [DtItem],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+
+        let mut cmds = Vec::new();
+        // (entry, index of the TYPE result in pipeline results)
+        let mut checks = Vec::new();
+        for dt_item in data.iter().skip(start_index).take(batch_size) {
+            data_size += dt_item.dt_data.get_data_size();
+            let entry = match &dt_item.dt_data {
+                // only keys decoded from rdb / dump can be checked, aof cmds are ignored
+                DtData::Redis { entry } if entry.is_base && !entry.key.bytes.is_empty() => entry,
+                _ => continue,
+            };
+
+            if entry.db_id != self.now_db_id {
+                cmds.push(RedisCmd::from_str_args(&[
+                    "SELECT",
+                    &entry.db_id.to_string(),
+                ]));
+                self.now_db_id = entry.db_id;
+            }
+
+            checks.push((entry, cmds.len()));
+            cmds.push(Self::build_key_cmd("TYPE", &entry.key, &[]));
+            cmds.push(Self::build_key_cmd("PTTL", &entry.key, &[]));
+            cmds.push(Self::build_fetch_cmd(entry));
+        }
+
+        if cmds.is_empty() {
+            return Ok(());
+        }
+
+        let mut packed_cmds = Vec::new();
+        for cmd in cmds.iter() {
+            packed_cmds.extend_from_slice(&CmdEncoder::encode(cmd));
+        }
+        let values = match self.conn.req_packed_commands(&packed_cmds, 0, cmds.len()) {
+            Ok(values) => values,
+            Err(error) => {
+                bail! {Error::SinkerError(format!(
+                    "batch check failed, error: {:?}",
+                    error
+                ))}
+            }
+        };
+
+        let mut miss = Vec::new();
+        let mut diff = Vec::new();
+        for (entry, i) in checks {
+            let dst_type = Self::parse_string(&values[i]);
+            if dst_type == "none" {
+                miss.push(Self::build_check_log(entry, LogType::Miss, HashMap::new()));
+                continue;
+            }
+
+            let mut diff_col_values = HashMap::new();
+            // TYPE returns the module type name for module values, e.g. ReJSON-RL
+            let src_type = match &entry.value {
+                RedisObject::Module(obj) => obj.module_name.clone(),
+                _ => entry.get_type(),
+            };
+            if src_type != dst_type {
+                diff_col_values.insert(
+                    RedisConstants::TYPE.to_string(),
+                    DiffColValue {
+                        src: Some(src_type),
+                        dst: Some(dst_type),
+                    },
+                );
+            } else {
+                Self::compare_value(&entry.value, &values[i + 2], &mut diff_col_values);
+            }
+            self.compare_ttl(entry.expire_ms, &values[i + 1], &mut diff_col_values);
+
+            if !diff_col_values.is_empty() {
+                diff.push(Self::build_check_log(entry, LogType::Diff, diff_col_values));
+            }
+        }
+        BaseChecker::log_dml(miss, diff);
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    fn build_key_cmd(name: &str, key: &RedisString, args: &[&str]) -> RedisCmd {
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg(name);
+        cmd.add_redis_arg(key);
+        for arg in args {
+            cmd.add_str_arg(arg);
+        }
+        cmd
+    }
+
+    fn build_fetch_cmd(entry: &RedisEntry) -> RedisCmd {
+        match &entry.value {
+            RedisObject::String(_) => Self::build_key_cmd("GET", &entry.key, &[]),
+            RedisObject::Hash(_) => Self::build_key_cmd("HGETALL", &entry.key, &[]),
+            RedisObject::List(_) => Self::build_key_cmd("LRANGE", &entry.key, &["0", "-1"]),
+            RedisObject::Set(_) => Self::build_key_cmd("SMEMBERS", &entry.key, &[]),
+            RedisObject::Zset(_) => {
+                Self::build_key_cmd("ZRANGE", &entry.key, &["0", "-1", "WITHSCORES"])
+            }
+            RedisObject::Stream(_) => Self::build_key_cmd("XRANGE", &entry.key, &["-", "+"]),
+            // module values are opaque, only the type is compared
+            RedisObject::Module(_) | RedisObject::Unknown => {
+                Self::build_key_cmd("EXISTS", &entry.key, &[])
+            }
+        }
+    }
+
+    fn compare_value(
+        src: &RedisObject,
+        dst: &Value,
+        diff_col_values: &mut HashMap<String, DiffColValue>,
+    ) {
+        match src {
+            RedisObject::String(obj) => {
+                let dst = Self::parse_bytes(dst);
+                if obj.value.as_bytes() != dst.as_slice() {
+                    diff_col_values.insert(
+                        RedisConstants::VALUE.to_string(),
+                        DiffColValue {
+                            src: Some(obj.value.to_string()),
+                            dst: Some(String::from_utf8_lossy(&dst).to_string()),
+                        },
+                    );
+                }
+            }
+
+            RedisObject::Hash(obj) => {
+                let src_map: HashMap<Vec<u8>, Vec<u8>> = obj
+                    .value
+                    .iter()
+                    .map(|(k, v)| (k.bytes.clone(), v.bytes.clone()))
+                    .collect();
+                let dst_map = Self::parse_pairs(dst).into_iter().collect();
+                Self::compare_map("field", &src_map, &dst_map, diff_col_values);
+            }
+
+            RedisObject::List(obj) => {
+                let dst_elements = Self::parse_array(dst);
+                let len = obj.elements.len().max(dst_elements.len());
+                for i in 0..len {
+                    let src_element = obj.elements.get(i).map(|i| i.bytes.clone());
+                    let dst_element = dst_elements.get(i).cloned();
+                    if src_element != dst_element {
+                        Self::add_diff(
+                            format!("index.{}", i),
+                            src_element,
+                            dst_element,
+                            diff_col_values,
+                        );
+                    }
+                }
+            }
+
+            RedisObject::Set(obj) => {
+                let src_map = obj
+                    .elements
+                    .iter()
+                    .map(|i| (i.bytes.clone(), i.bytes.clone()))
+                    .collect();
+                let dst_map = Self::parse_array(dst)
+                    .into_iter()
+                    .map(|i| (i.clone(), i))
+                    .collect();
+                Self::compare_map("member", &src_map, &dst_map, diff_col_values);
+            }
+
+            RedisObject::Zset(obj) => {
+                let dst_map: HashMap<Vec<u8>, Vec<u8>> =
+                    Self::parse_pairs(dst).into_iter().collect();
+                let mut src_map = HashMap::new();
+                for element in obj.elements.iter() {
+                    // scores may be formatted differently, e.g. "1" and "1.0"
+                    let src_score = Self::parse_score(&element.score.bytes);
+                    let score = match dst_map.get(&element.member.bytes) {
+                        Some(dst_score) if Self::parse_score(dst_score) == src_score => {
+                            dst_score.clone()
+                        }
+                        _ => element.score.bytes.clone(),
+                    };
+                    src_map.insert(element.member.bytes.clone(), score);
+                }
+                Self::compare_map("member", &src_map, &dst_map, diff_col_values);
+            }
+
+            RedisObject::Stream(obj) => {
+                // xadd key id field value [field value ...]
+                let src_map = obj
+                    .cmds
+                    .iter()
+                    .filter(|cmd| cmd.args.len() > 2 && cmd.args[0] == b"xadd")
+                    .map(|cmd| (cmd.args[2].clone(), cmd.args[3..].join(&b' ')))
+                    .collect();
+                let dst_map = Self::parse_stream_entries(dst);
+                Self::compare_map("entry", &src_map, &dst_map, diff_col_values);
+            }
+
+            RedisObject::Module(_) | RedisObject::Unknown => {}
+        }
+    }
+
+    fn compare_ttl(
+        &self,
+        src_expire_ms: i64,
+        dst: &Value,
+        diff_col_values: &mut HashMap<String, DiffColValue>,
+    ) {
+        // PTTL: -1 no expire, -2 key not exists
+        let dst_expire_ms = match dst {
+            Value::Int(i) if *i >= 0 => *i,
+            _ => 0,
+        };
+
+        let is_diff = if src_expire_ms > 0 && dst_expire_ms > 0 {
+            let tolerance_ms = self.ttl_tolerance_secs as i64 * 1000;
+            (src_expire_ms - dst_expire_ms).abs() > tolerance_ms
+        } else {
+            (src_expire_ms > 0) != (dst_expire_ms > 0)
+        };
+
+        if is_diff {
+            let to_option = |i: i64| if i > 0 { Some(i.to_string()) } else { None };
+            diff_col_values.insert(
+                RedisConstants::TTL.to_string(),
+                DiffColValue {
+                    src: to_option(src_expire_ms),
+                    dst: to_option(dst_expire_ms),
+                },
+            );
+        }
+    }
+
+    fn compare_map(
+        prefix: &str,
+        src_map: &HashMap<Vec<u8>, Vec<u8>>,
+        dst_map: &HashMap<Vec<u8>, Vec<u8>>,
+        diff_col_values: &mut HashMap<String, DiffColValue>,
+    ) {
+        let col = |k: &[u8]| format!("{}.{}", prefix, String::from_utf8_lossy(k));
+        for (k, src_v) in src_map.iter() {
+            let dst_v = dst_map.get(k);
+            if dst_v != Some(src_v) {
+                Self::add_diff(col(k), Some(src_v.clone()), dst_v.cloned(), diff_col_values);
+            }
+        }
+
+        for (k, dst_v) in dst_map.iter() {
+            if !src_map.contains_key(k) {
+                Self::add_diff(col(k), None, Some(dst_v.clone()), diff_col_values);
+            }
+        }
+    }
+
+    fn add_diff(
+        col: String,
+        src: Option<Vec<u8>>,
+        dst: Option<Vec<u8>>,
+        diff_col_values: &mut HashMap<String, DiffColValue>,
+    ) {
+        if diff_col_values.len() >= MAX_DIFF_COUNT {
+            return;
+        }
+        let to_string = |v: Vec<u8>| String::from_utf8_lossy(&v).to_string();
+        diff_col_values.insert(
+            col,
+            DiffColValue {
+                src: src.map(to_string),
+                dst: dst.map(to_string),
+            },
+        );
+    }
+
+    fn build_check_log(
+        entry: &RedisEntry,
+        log_type: LogType,
+        diff_col_values: HashMap<String, DiffColValue>,
+    ) -> CheckLog {
+        let mut id_col_values = HashMap::new();
+        id_col_values.insert(RedisConstants::KEY.to_string(), Some(entry.key.to_string()));
+        CheckLog {
+            log_type,
+            schema: entry.db_id.to_string(),
+            tb: String::new(),
+            id_col_values,
+            diff_col_values,
+        }
+    }
+
+    fn parse_score(score: &[u8]) -> Option<f64> {
+        String::from_utf8_lossy(score).parse().ok()
+    }
+
+    fn parse_string(value: &Value) -> String {
+        String::from_utf8_lossy(&Self::parse_bytes(value)).to_string()
+    }
+
+    fn parse_bytes(value: &Value) -> Vec<u8> {
+        match value {
+            Value::BulkString(v) => v.clone(),
+            Value::SimpleString(v) => v.as_bytes().to_vec(),
+            Value::Int(v) => v.to_string().into_bytes(),
+            Value::Double(v) => v.to_string().into_bytes(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn parse_array(value: &Value) -> Vec<Vec<u8>> {
+        match value {
+            Value::Array(values) | Value::Set(values) => {
+                values.iter().map(Self::parse_bytes).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn parse_pairs(value: &Value) -> Vec<(Vec<u8>, Vec<u8>)> {
+        match value {
+            Value::Map(pairs) => pairs
+                .iter()
+                .map(|(k, v)| (Self::parse_bytes(k), Self::parse_bytes(v)))
+                .collect(),
+            _ => Self::parse_array(value)
+                .chunks(2)
+                .filter(|i| i.len() == 2)
+                .map(|i| (i[0].clone(), i[1].clone()))
+                .collect(),
+        }
+    }
+
+    fn parse_stream_entries(value: &Value) -> HashMap<Vec<u8>, Vec<u8>> {
+        // XRANGE: [[id, [field, value, ...]], ...]
+        let mut entries = HashMap::new();
+        if let Value::Array(values) = value {
+            for v in values {
+                if let Value::Array(id_and_fields) = v {
+                    if id_and_fields.len() == 2 {
+                        let id = Self::parse_bytes(&id_and_fields[0]);
+                        let fields = Self::parse_array(&id_and_fields[1]).join(&b' ');
+                        entries.insert(id, fields);
+                    }
+                }
+            }
+        }
+        entries
+    }
+}
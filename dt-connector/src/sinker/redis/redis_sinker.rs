@@ -7,12 +7,14 @@ use anyhow::bail;
 use async_trait::async_trait;
 use dt_common::error::Error;
 use dt_common::log_debug;
+use dt_common::log_info;
 use dt_common::meta::dt_data::DtData;
 use dt_common::meta::dt_data::DtItem;
 use dt_common::meta::rdb_meta_manager::RdbMetaManager;
 use dt_common::meta::redis::cluster_node::ClusterNode;
 use dt_common::meta::redis::command::cmd_encoder::CmdEncoder;
 use dt_common::meta::redis::command::key_parser::KeyParser;
+use dt_common::meta::redis::redis_entry::RedisEntry;
 use dt_common::meta::redis::redis_object::RedisCmd;
 use dt_common::meta::redis::redis_object::RedisObject;
 use dt_common::meta::redis::redis_write_method::RedisWriteMethod;
@@ -30,6 +32,8 @@ use crate::Sinker;
 
 use super::entry_rewriter::EntryRewriter;
 
+const LARGE_KEY_PROGRESS_INTERVAL_SECS: u64 = 10;
+
 pub struct RedisSinker {
     pub cluster_node: Option<ClusterNode>,
     pub batch_size: usize,
@@ -41,6 +45,11 @@ pub struct RedisSinker {
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub key_parser: KeyParser,
+    // 0: disabled, otherwise collections are written by chunked commands,
+    // and commands of a key larger than chunk_bytes are sent in multiple batches
+    pub chunk_bytes: usize,
+    // max elements in a chunked command
+    pub chunk_size: usize,
 }
 
 #[async_trait]
@@ -93,14 +102,25 @@ impl RedisSinker {
         let mut data_size = 0;
 
         let mut cmds = Vec::new();
+        let mut cmd_count = 0;
         for dt_item in data.iter_mut().skip(start_index).take(batch_size) {
             data_size += dt_item.dt_data.get_data_size();
-            cmds.extend_from_slice(&self.rewrite_entry(&mut dt_item.dt_data)?);
+            let entry_cmds = self.rewrite_entry(&mut dt_item.dt_data)?;
+            if self.is_large_entry(&entry_cmds) {
+                // sink previous commands first to keep the order
+                self.batch_sink(&cmds).await?;
+                cmd_count += cmds.len() + entry_cmds.len();
+                cmds.clear();
+                self.sink_large_entry(&dt_item.dt_data, &entry_cmds).await?;
+            } else {
+                cmds.extend(entry_cmds);
+            }
         }
 
         self.batch_sink(&cmds).await?;
+        cmd_count += cmds.len();
 
-        BaseSinker::update_batch_monitor(&mut self.monitor, cmds.len(), data_size, start_time)
+        BaseSinker::update_batch_monitor(&mut self.monitor, cmd_count, data_size, start_time)
     }
 
     async fn serial_sink_raw(&mut self, data: &mut [DtItem]) -> anyhow::Result<()> {
@@ -110,6 +130,10 @@ impl RedisSinker {
         for dt_item in data.iter_mut() {
             data_size += dt_item.dt_data.get_data_size();
             let cmds = self.rewrite_entry(&mut dt_item.dt_data)?;
+            if self.is_large_entry(&cmds) {
+                self.sink_large_entry(&dt_item.dt_data, &cmds).await?;
+                continue;
+            }
             for cmd in cmds {
                 self.batch_sink(&[cmd]).await?;
             }
@@ -131,6 +155,14 @@ impl RedisSinker {
 
             match self.method {
                 RedisWriteMethod::Restore => {
+                    // a large RESTORE payload may block the target or exceed proto-max-bulk-len
+                    if entry.is_raw() && entry.raw_bytes.len() > self.chunk_bytes {
+                        if let Some(chunk_cmds) = self.rewrite_in_chunks(entry)? {
+                            cmds.extend(chunk_cmds);
+                            return Ok(cmds);
+                        }
+                    }
+
                     if entry.is_raw() {
                         let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
                         cmds.push(cmd);
@@ -140,6 +172,11 @@ impl RedisSinker {
                 }
 
                 RedisWriteMethod::Rewrite => {
+                    if let Some(chunk_cmds) = self.rewrite_in_chunks(entry)? {
+                        cmds.extend(chunk_cmds);
+                        return Ok(cmds);
+                    }
+
                     let mut rewrite_cmds = match entry.value {
                         RedisObject::String(ref mut obj) => EntryRewriter::rewrite_string(obj),
                         RedisObject::List(ref mut obj) => EntryRewriter::rewrite_list(obj),
@@ -147,9 +184,13 @@ impl RedisSinker {
                         RedisObject::Hash(ref mut obj) => EntryRewriter::rewrite_hash(obj),
                         RedisObject::Zset(ref mut obj) => EntryRewriter::rewrite_zset(obj),
                         RedisObject::Stream(ref mut obj) => Ok(obj.cmds.drain(..).collect()),
-                        RedisObject::Module(_) => {
-                            let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
-                            Ok(vec![cmd])
+                        RedisObject::Module(ref obj) => {
+                            if let Some(cmds) = EntryRewriter::rewrite_module(obj)? {
+                                Ok(cmds)
+                            } else {
+                                let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
+                                Ok(vec![cmd])
+                            }
                         }
                         _ => bail! {Error::SinkerError("rewrite not implemented".into())},
                     }?;
@@ -162,6 +203,78 @@ impl RedisSinker {
         }
         Ok(cmds)
     }
+
+    fn rewrite_in_chunks(&self, entry: &mut RedisEntry) -> anyhow::Result<Option<Vec<RedisCmd>>> {
+        if self.chunk_bytes == 0 {
+            return Ok(None);
+        }
+        EntryRewriter::rewrite_in_chunks(entry, self.version, self.chunk_size, self.chunk_bytes)
+    }
+
+    fn is_large_entry(&self, cmds: &[RedisCmd]) -> bool {
+        self.chunk_bytes > 0 && Self::get_cmds_size(cmds) > self.chunk_bytes
+    }
+
+    /// sink commands of a large key in batches of about chunk_bytes, and log the progress
+    async fn sink_large_entry(
+        &mut self,
+        dt_data: &DtData,
+        cmds: &[RedisCmd],
+    ) -> anyhow::Result<()> {
+        let key = match dt_data {
+            DtData::Redis { entry } => entry.key.to_string(),
+            _ => String::new(),
+        };
+        let start_time = Instant::now();
+        let total_bytes = Self::get_cmds_size(cmds);
+        log_info!(
+            "sinking large key: {}, commands: {}, bytes: {}",
+            key,
+            cmds.len(),
+            total_bytes
+        );
+
+        let mut last_log_time = Instant::now();
+        let (mut start, mut sent_bytes) = (0, 0);
+        while start < cmds.len() {
+            let (mut end, mut bytes) = (start, 0);
+            while end < cmds.len()
+                && (end == start || bytes + cmds[end].get_malloc_size() <= self.chunk_bytes)
+            {
+                bytes += cmds[end].get_malloc_size();
+                end += 1;
+            }
+
+            self.batch_sink(&cmds[start..end]).await?;
+            start = end;
+            sent_bytes += bytes;
+
+            if last_log_time.elapsed().as_secs() >= LARGE_KEY_PROGRESS_INTERVAL_SECS {
+                log_info!(
+                    "sinking large key: {}, sent commands: {}/{}, sent bytes: {}/{}",
+                    key,
+                    start,
+                    cmds.len(),
+                    sent_bytes,
+                    total_bytes
+                );
+                last_log_time = Instant::now();
+            }
+        }
+
+        log_info!(
+            "large key sinked: {}, commands: {}, bytes: {}, elapsed ms: {}",
+            key,
+            cmds.len(),
+            total_bytes,
+            start_time.elapsed().as_millis()
+        );
+        Ok(())
+    }
+
+    fn get_cmds_size(cmds: &[RedisCmd]) -> usize {
+        cmds.iter().map(|i| i.get_malloc_size()).sum()
+    }
 }
 
 /// sink dml
@@ -0,0 +1,111 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::Utc;
+use dt_common::{
+    log_info, log_warn,
+    meta::dt_data::{DtData, DtItem},
+    monitor::monitor::Monitor,
+};
+
+use crate::{sinker::base_sinker::BaseSinker, Sinker};
+
+use super::rdb_writer::RdbWriter;
+
+/// write snapshot entries into a RDB file, the file is written to [file_path].tmp
+/// and renamed to [file_path] when the task finishes, so a partial file is never left as a valid one
+pub struct RedisSnapshotFileSinker {
+    pub file_path: String,
+    pub rdb_writer: RdbWriter,
+    pub file: Option<BufWriter<File>>,
+    pub skipped_count: usize,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+#[async_trait]
+impl Sinker for RedisSnapshotFileSinker {
+    async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let start_time = Instant::now();
+        let now_ms = Utc::now().timestamp_millis();
+        let mut buf = Vec::new();
+        let mut data_size = 0;
+        for dt_item in data.iter() {
+            if let DtData::Redis { entry } = &dt_item.dt_data {
+                if !entry.is_base {
+                    self.log_skipped(&entry.cmd.to_string());
+                    continue;
+                }
+
+                match self.rdb_writer.write_entry(entry, now_ms)? {
+                    Some(entry_buf) => {
+                        data_size += entry.get_data_malloc_size();
+                        buf.extend_from_slice(&entry_buf);
+                    }
+                    None => self.log_skipped(&entry.key.to_string()),
+                }
+            }
+        }
+
+        self.get_file()?.write_all(&buf)?;
+        BaseSinker::update_batch_monitor(&mut self.monitor, data.len(), data_size, start_time)
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        // the header is written by get_file if no data was sinked
+        self.get_file()?;
+        let eof = self.rdb_writer.write_eof();
+        let file = self.file.as_mut().unwrap();
+        file.write_all(&eof)?;
+        file.flush()?;
+        self.file = None;
+
+        let tmp_file_path = Self::get_tmp_file_path(&self.file_path);
+        fs::rename(&tmp_file_path, &self.file_path)
+            .with_context(|| format!("failed to rename rdb file: {}", tmp_file_path))?;
+        log_info!(
+            "rdb file finished: {}, rdb version: {}, skipped count: {}",
+            self.file_path,
+            self.rdb_writer.version,
+            self.skipped_count
+        );
+        Ok(())
+    }
+}
+
+impl RedisSnapshotFileSinker {
+    fn get_file(&mut self) -> anyhow::Result<&mut BufWriter<File>> {
+        if self.file.is_none() {
+            let tmp_file_path = Self::get_tmp_file_path(&self.file_path);
+            if let Some(dir) = Path::new(&tmp_file_path).parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let mut file = BufWriter::new(
+                File::create(&tmp_file_path)
+                    .with_context(|| format!("failed to create rdb file: {}", tmp_file_path))?,
+            );
+            file.write_all(&self.rdb_writer.write_header(Utc::now().timestamp()))?;
+            self.file = Some(file);
+        }
+        Ok(self.file.as_mut().unwrap())
+    }
+
+    fn log_skipped(&mut self, item: &str) {
+        self.skipped_count += 1;
+        log_warn!("can not be written into rdb file, skipped: {}", item);
+    }
+
+    fn get_tmp_file_path(file_path: &str) -> String {
+        format!("{}.tmp", file_path)
+    }
+}
@@ -5,6 +5,7 @@ use async_trait::async_trait;
 use dt_common::log_statistic;
 use dt_common::meta::dt_data::DtData;
 use dt_common::meta::dt_data::DtItem;
+use dt_common::meta::redis::redis_memory_analyzer::{MemoryReportFormat, RedisMemoryAnalyzer};
 use dt_common::meta::redis::redis_statistic_type::RedisStatisticType;
 use dt_common::monitor::monitor::Monitor;
 use serde::Serialize;
@@ -17,6 +18,9 @@ pub struct RedisStatisticSinker {
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_size_threshold: usize,
     pub freq_threshold: i64,
+    // only for RedisStatisticType::Memory
+    pub memory_analyzer: Option<RedisMemoryAnalyzer>,
+    pub report_format: MemoryReportFormat,
 }
 
 #[derive(Serialize)]
@@ -64,6 +68,33 @@ impl Sinker for RedisStatisticSinker {
                         };
                         log_statistic!("{}", json!(info).to_string());
                     }
+
+                    RedisStatisticType::Memory => {
+                        // commands from cdc are not counted
+                        if !entry.is_base {
+                            continue;
+                        }
+                        if let Some(analyzer) = &mut self.memory_analyzer {
+                            analyzer.add(entry);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        // the report is logged once all keys are analyzed
+        if let Some(analyzer) = &self.memory_analyzer {
+            match self.report_format {
+                MemoryReportFormat::Json => {
+                    log_statistic!("{}", json!(analyzer.get_report()).to_string())
+                }
+                MemoryReportFormat::Csv => {
+                    for line in analyzer.get_csv_report() {
+                        log_statistic!("{}", line);
+                    }
                 }
             }
         }
@@ -6,7 +6,15 @@ use std::{
     time::Instant,
 };
 
-use crate::{call_batch_fn, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{
+    call_batch_fn,
+    sinker::{
+        base_sinker::BaseSinker,
+        base_struct_sinker::{SIGN_COL_NAME, TIMESTAMP_COL_NAME},
+        starrocks::starrocks_struct_sinker::StarrocksStructSinker,
+    },
+    Sinker,
+};
 use anyhow::bail;
 use async_trait::async_trait;
 use chrono::Utc;
@@ -21,15 +29,14 @@ use dt_common::{
     monitor::monitor::Monitor,
 };
 use dt_common::{
-    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+    meta::{
+        col_value::ColValue, ddl_meta::ddl_data::DdlData, row_data::RowData, row_type::RowType,
+    },
     utils::sql_util::SqlUtil,
 };
 use reqwest::{header, Client, Method, Response, StatusCode};
 use serde_json::{json, Value};
 
-const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
-const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
-
 #[derive(Clone)]
 pub struct StarRocksSinker {
     pub db_type: DbType,
@@ -43,6 +50,8 @@ pub struct StarRocksSinker {
     pub monitor: Arc<Mutex<Monitor>>,
     pub sync_timestamp: i64,
     pub hard_delete: bool,
+    // set if auto_evolve is enabled, used to apply source column changes to target
+    pub struct_sinker: Option<StarrocksStructSinker>,
 }
 
 #[async_trait]
@@ -59,6 +68,20 @@ impl Sinker for StarRocksSinker {
         }
         Ok(())
     }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, batch: bool) -> anyhow::Result<()> {
+        if let Some(struct_sinker) = self.struct_sinker.as_mut() {
+            struct_sinker.sink_ddl(data, batch).await?;
+        }
+        Ok(())
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        for ddl_data in data.iter() {
+            self.meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+        }
+        Ok(())
+    }
 }
 
 impl StarRocksSinker {
@@ -1,14 +1,20 @@
-use crate::{rdb_router::RdbRouter, Sinker};
+use crate::{
+    rdb_router::RdbRouter,
+    sinker::base_struct_sinker::{
+        BaseStructSinker, OlapEvolver, SIGN_COL_NAME, TIMESTAMP_COL_NAME,
+    },
+    Sinker,
+};
 
 use anyhow::bail;
 use dt_common::{
     config::config_enums::{ConflictPolicyEnum, DbType},
     log_error, log_info,
     meta::{
+        ddl_meta::{ddl_data::DdlData, ddl_translator::DdlTranslator},
         mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
         pg::{pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
         rdb_meta_manager::RdbMetaManager,
-        rdb_tb_meta::RdbTbMeta,
         struct_meta::{
             statement::struct_statement::StructStatement,
             struct_data::StructData,
@@ -20,11 +26,9 @@ use dt_common::{
 
 use async_trait::async_trait;
 use futures::TryStreamExt;
-use sqlx::{MySql, Pool};
+use sqlx::{MySql, Pool, Row};
 
-const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
 const SIGN_COL_TYPE: &str = "BOOLEAN";
-const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
 const TIMESTAMP_COL_TYPE: &str = "BIGINT";
 
 #[derive(Clone)]
@@ -57,16 +61,20 @@ impl Sinker for StarrocksStructSinker {
                 }
 
                 StructStatement::MysqlCreateTable(statement) => {
-                    let (schema, tb) = reverse_router
-                        .get_tb_map(&statement.table.database_name, &statement.table.table_name);
-                    if let Some(meta_manager) =
+                    let sql = if let Some(meta_manager) =
                         self.extractor_meta_manager.mysql_meta_manager.as_mut()
                     {
-                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
-                        let sql =
-                            self.get_create_table_sql(&statement.table, Some(&tb_meta), None)?;
-                        self.execute_sql(&sql).await?;
-                    }
+                        let (schema, tb) = reverse_router.get_reversed_tb_map(
+                            &statement.table.database_name,
+                            &statement.table.table_name,
+                        )?;
+                        let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?.to_owned();
+                        self.get_create_table_sql(&statement.table, Some(&tb_meta), None)?
+                    } else {
+                        // tables inferred from mongo documents, columns have target types
+                        self.get_create_table_sql(&statement.table, None, None)?
+                    };
+                    self.execute_sql(&sql).await?;
                 }
 
                 StructStatement::PgCreateSchema(statement) => {
@@ -75,11 +83,13 @@ impl Sinker for StarrocksStructSinker {
                 }
 
                 StructStatement::PgCreateTable(statement) => {
-                    let (schema, tb) = reverse_router
-                        .get_tb_map(&statement.table.schema_name, &statement.table.table_name);
+                    let (schema, tb) = reverse_router.get_reversed_tb_map(
+                        &statement.table.schema_name,
+                        &statement.table.table_name,
+                    )?;
                     if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
                     {
-                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?.to_owned();
                         let sql =
                             self.get_create_table_sql(&statement.table, None, Some(&tb_meta))?;
                         self.execute_sql(&sql).await?;
@@ -92,6 +102,105 @@ impl Sinker for StarrocksStructSinker {
 
         Ok(())
     }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        for ddl_data in data {
+            let sqls = match BaseStructSinker::get_evolve_sqls(self, &ddl_data).await {
+                Ok(sqls) => sqls,
+                Err(error) => {
+                    log_error!("evolve ddl failed, error: {}", error);
+                    match self.conflict_policy {
+                        ConflictPolicyEnum::Interrupt => return Err(error),
+                        ConflictPolicyEnum::Ignore => continue,
+                    }
+                }
+            };
+            for sql in sqls {
+                self.execute_sql(&sql).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OlapEvolver for StarrocksStructSinker {
+    fn get_dst_db_type(&self) -> DbType {
+        self.db_type.clone()
+    }
+
+    fn mysql_to_dst_col_type(&self, mysql_col_type: &MysqlColType) -> String {
+        DdlTranslator::mysql_to_starrocks_col_type(mysql_col_type, &self.db_type)
+    }
+
+    fn pg_to_dst_col_type(&self, col: &str, tb_meta: &PgTbMeta) -> anyhow::Result<String> {
+        self.get_dst_col_type_from_pg(col, tb_meta)
+    }
+
+    async fn get_dst_col_type(
+        &self,
+        schema: &str,
+        tb: &str,
+        col: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let sql = format!(
+            "SELECT COLUMN_TYPE FROM information_schema.columns WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME = '{}' AND COLUMN_NAME = '{}'",
+            schema.replace('\'', "''"),
+            tb.replace('\'', "''"),
+            col.replace('\'', "''")
+        );
+        let row = sqlx::query(&sql)
+            .disable_arguments()
+            .fetch_optional(&self.conn_pool)
+            .await?;
+        match row {
+            Some(row) => Ok(Some(row.try_get(0)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn build_add_col_sql(&self, full_tb: &str, col: &str, col_type: &str) -> String {
+        format!(
+            "ALTER TABLE {} ADD COLUMN `{}` {} NULL",
+            full_tb, col, col_type
+        )
+    }
+
+    fn build_rename_col_sql(&self, full_tb: &str, old_col: &str, new_col: &str) -> String {
+        if self.db_type == DbType::Doris {
+            format!(
+                "ALTER TABLE {} RENAME COLUMN `{}` `{}`",
+                full_tb, old_col, new_col
+            )
+        } else {
+            format!(
+                "ALTER TABLE {} RENAME COLUMN `{}` TO `{}`",
+                full_tb, old_col, new_col
+            )
+        }
+    }
+
+    fn build_modify_col_sql(
+        &self,
+        full_tb: &str,
+        col: &str,
+        col_type: &str,
+        is_nullable: bool,
+    ) -> String {
+        let nullable = if is_nullable { "NULL" } else { "NOT NULL" };
+        format!(
+            "ALTER TABLE {} MODIFY COLUMN `{}` {} {}",
+            full_tb, col, col_type, nullable
+        )
+    }
+
+    fn get_router(&self) -> &RdbRouter {
+        &self.router
+    }
+
+    fn get_extractor_meta_manager(&mut self) -> &mut RdbMetaManager {
+        &mut self.extractor_meta_manager
+    }
 }
 
 impl StarrocksStructSinker {
@@ -101,10 +210,10 @@ impl StarrocksStructSinker {
         mysql_tb_meta: Option<&MysqlTbMeta>,
         pg_tb_meta: Option<&PgTbMeta>,
     ) -> anyhow::Result<String> {
-        let rdb_tb_meta = if let Some(tb_meta) = pg_tb_meta {
-            &tb_meta.basic
-        } else {
-            &mysql_tb_meta.as_ref().unwrap().basic
+        let id_cols = match (mysql_tb_meta, pg_tb_meta) {
+            (Some(tb_meta), _) => tb_meta.basic.id_cols.clone(),
+            (_, Some(tb_meta)) => tb_meta.basic.id_cols.clone(),
+            _ => Self::get_id_cols(table),
         };
 
         let mut dst_cols = vec![];
@@ -112,22 +221,17 @@ impl StarrocksStructSinker {
         // In StarRocks:
         // Key columns must be the first few columns of the schema and the order
         // of the key columns must be consistent with the order of the schema.
-        for col in rdb_tb_meta.id_cols.iter() {
+        for col in id_cols.iter() {
             for column in table.columns.iter() {
                 if column.column_name == *col {
-                    dst_cols.push(self.get_dst_col(
-                        column,
-                        mysql_tb_meta,
-                        pg_tb_meta,
-                        rdb_tb_meta,
-                    )?);
+                    dst_cols.push(self.get_dst_col(column, mysql_tb_meta, pg_tb_meta, &id_cols)?);
                 }
             }
         }
 
         for column in table.columns.iter() {
-            if !rdb_tb_meta.id_cols.contains(&column.column_name) {
-                dst_cols.push(self.get_dst_col(column, mysql_tb_meta, pg_tb_meta, rdb_tb_meta)?);
+            if !id_cols.contains(&column.column_name) {
+                dst_cols.push(self.get_dst_col(column, mysql_tb_meta, pg_tb_meta, &id_cols)?);
             }
         }
 
@@ -137,7 +241,7 @@ impl StarrocksStructSinker {
             dst_cols.push(format!("`{}` {}", TIMESTAMP_COL_NAME, TIMESTAMP_COL_TYPE));
         }
 
-        let schema = if mysql_tb_meta.is_some() {
+        let schema = if pg_tb_meta.is_none() {
             &table.database_name
         } else {
             &table.schema_name
@@ -149,9 +253,8 @@ impl StarrocksStructSinker {
             dst_cols.join(", "),
         );
 
-        if !rdb_tb_meta.id_cols.is_empty() {
-            let primary_keys = rdb_tb_meta
-                .id_cols
+        if !id_cols.is_empty() {
+            let primary_keys = id_cols
                 .iter()
                 .map(|i| format!("`{}`", i))
                 .collect::<Vec<String>>()
@@ -166,7 +269,7 @@ impl StarrocksStructSinker {
             if !table.table_comment.is_empty() {
                 sql = format!("{} COMMENT '{}'", sql, table.table_comment);
             }
-            sql = format!("{} DISTRIBUTED BY HASH(`{}`)", sql, rdb_tb_meta.id_cols[0]);
+            sql = format!("{} DISTRIBUTED BY HASH(`{}`)", sql, id_cols[0]);
         }
 
         if self.backend_count < 3 {
@@ -180,14 +283,14 @@ impl StarrocksStructSinker {
         column: &Column,
         mysql_tb_meta: Option<&MysqlTbMeta>,
         pg_tb_meta: Option<&PgTbMeta>,
-        rdb_tb_meta: &RdbTbMeta,
+        id_cols: &[String],
     ) -> anyhow::Result<String> {
         let col = &column.column_name;
-        let dst_col_type = if let Some(tb_meta) = mysql_tb_meta {
-            self.get_dst_col_type_from_mysql(col, tb_meta)
-        } else {
-            self.get_dst_col_type_from_pg(col, pg_tb_meta.unwrap())
-        }?;
+        let dst_col_type = match (mysql_tb_meta, pg_tb_meta) {
+            (Some(tb_meta), _) => self.get_dst_col_type_from_mysql(col, tb_meta)?,
+            (_, Some(tb_meta)) => self.get_dst_col_type_from_pg(col, tb_meta)?,
+            _ => column.column_type.clone(),
+        };
 
         // The delete operation in Doris (-H "merge_type: delete") is implemented by inserting a record marked for deletion,
         // which means it validates the values of all fields (even if -H "strict_mode: false" is set).
@@ -200,7 +303,7 @@ impl StarrocksStructSinker {
         // At this point, attempting to delete the data using the following stream_load method will fail
         // because the value of f_1 field (null) is invalid:
         //      curl -X PUT "http://127.0.0.1:8042/api/public/tb_1/_stream_load" -H "Expect: 100-continue" -H "format: json"  -H "strip_outer_array: true" -H "timezone: UTC" -H "merge_type: delete" -u "root:" -d '[{"id": 1, value: null}]'
-        let mut is_nullable = pg_tb_meta.is_some() && !rdb_tb_meta.id_cols.contains(col);
+        let mut is_nullable = pg_tb_meta.is_some() && !id_cols.contains(col);
         is_nullable |= column.is_nullable;
         let mut dst_col = if is_nullable {
             format!("`{}` {}", col, dst_col_type)
@@ -215,77 +318,25 @@ impl StarrocksStructSinker {
         Ok(dst_col)
     }
 
+    fn get_id_cols(table: &Table) -> Vec<String> {
+        table
+            .columns
+            .iter()
+            .filter(|column| column.column_key == "PRI")
+            .map(|column| column.column_name.clone())
+            .collect()
+    }
+
     fn get_dst_col_type_from_mysql(
         &self,
         col: &str,
         tb_meta: &MysqlTbMeta,
     ) -> anyhow::Result<String> {
         let mysql_col_type = tb_meta.get_col_type(col)?;
-        let dst_col = match mysql_col_type {
-            MysqlColType::TinyInt { unsigned: false } => "TINYINT",
-            MysqlColType::TinyInt { unsigned: true } => "SMALLINT",
-            MysqlColType::SmallInt { unsigned: false } => "SMALLINT",
-            MysqlColType::SmallInt { unsigned: true } => "INT",
-            MysqlColType::MediumInt { unsigned: false } => "INT",
-            MysqlColType::MediumInt { unsigned: true } => "BIGINT",
-            MysqlColType::Int { unsigned: false } => "INT",
-            MysqlColType::Int { unsigned: true } => "BIGINT",
-            MysqlColType::BigInt { unsigned: false } => "BIGINT",
-            MysqlColType::BigInt { unsigned: true } => "LARGEINT",
-
-            MysqlColType::Float => "FLOAT",
-            MysqlColType::Double => "DOUBLE",
-            MysqlColType::Decimal { precision, scale } => {
-                // https://docs.starrocks.io/docs/sql-reference/data-types/numeric/DECIMAL/
-                if *precision <= 38 {
-                    format!("DECIMAL({},{})", precision, scale).leak()
-                } else {
-                    "STRING"
-                }
-            }
-
-            MysqlColType::Time { .. } => "VARCHAR(255)",
-            MysqlColType::Year => "INT",
-
-            // https://github.com/apache/doris-flink-connector/blob/master/flink-doris-connector/src/main/java/org/apache/doris/flink/tools/cdc/mysql/MysqlType.java
-            MysqlColType::Date { .. } => "DATE",
-            MysqlColType::DateTime { precision, .. }
-            | MysqlColType::Timestamp { precision, .. } => {
-                if self.db_type == DbType::StarRocks {
-                    "DATETIME"
-                } else {
-                    format!("DATETIME({})", precision).leak()
-                }
-            }
-
-            MysqlColType::Char { length: v, .. } => format!("CHAR({})", v).leak(),
-            MysqlColType::Varchar { length: v, .. } => format!("VARCHAR({})", v).leak(),
-
-            MysqlColType::TinyText { .. }
-            | MysqlColType::MediumText { .. }
-            | MysqlColType::Text { .. }
-            | MysqlColType::LongText { .. } => "STRING",
-
-            MysqlColType::Binary { .. }
-            | MysqlColType::VarBinary { .. }
-            | MysqlColType::TinyBlob
-            | MysqlColType::MediumBlob
-            | MysqlColType::Blob
-            | MysqlColType::LongBlob => {
-                if self.db_type == DbType::StarRocks {
-                    "VARBINARY"
-                } else {
-                    "STRING"
-                }
-            }
-
-            MysqlColType::Bit => "BIGINT",
-            MysqlColType::Set { items: _ } => "VARCHAR(255)",
-            MysqlColType::Enum { items: _ } => "VARCHAR(255)",
-            MysqlColType::Json => "JSON",
-            MysqlColType::Unknown => "STRING",
-        };
-        Ok(dst_col.to_string())
+        Ok(DdlTranslator::mysql_to_starrocks_col_type(
+            mysql_col_type,
+            &self.db_type,
+        ))
     }
 
     fn get_dst_col_type_from_pg(&self, col: &str, tb_meta: &PgTbMeta) -> anyhow::Result<String> {
@@ -115,9 +115,11 @@ impl MongoMerger {
             RowType::Update => {
                 let before = row_data.before.as_ref().unwrap();
                 let after = row_data.after.as_ref().unwrap();
-                // for Update row_data from oplog (NOT change stream), after contains diff_doc instead of doc,
-                // in which case we can NOT transfer Update into Delete + Insert
-                if after.get(MongoConstants::DOC).is_none() {
+                // if after contains diff_doc, the partial update should be applied as it is,
+                // we can NOT transfer Update into Delete + Insert, doc is only merged as a fallback
+                if after.get(MongoConstants::DOC).is_none()
+                    || after.get(MongoConstants::DIFF_DOC).is_some()
+                {
                     return None;
                 } else if let Some(ColValue::MongoDoc(doc)) = before.get(MongoConstants::DOC) {
                     return MongoKey::from_doc(doc);
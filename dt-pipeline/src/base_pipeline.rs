@@ -13,7 +13,9 @@ use dt_common::{
         ddl_meta::ddl_data::DdlData,
         dt_data::{DtData, DtItem},
         dt_queue::DtQueue,
+        mongo::mongo_row_converter::MongoRowConverter,
         position::Position,
+        redis::command::row_converter::RedisRowConverter,
         row_data::RowData,
         syncer::Syncer,
     },
@@ -26,7 +28,10 @@ use dt_common::{
 use dt_connector::{data_marker::DataMarker, Sinker};
 use dt_parallelizer::Parallelizer;
 
-use crate::{lua_processor::LuaProcessor, Pipeline};
+use crate::{
+    lua_processor::LuaProcessor, transform_processor::TransformProcessor,
+    wasm_processor::WasmProcessor, Pipeline,
+};
 
 pub struct BasePipeline {
     pub buffer: Arc<DtQueue>,
@@ -39,7 +44,11 @@ pub struct BasePipeline {
     pub syncer: Arc<Mutex<Syncer>>,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
+    pub transform_processor: Option<TransformProcessor>,
     pub lua_processor: Option<LuaProcessor>,
+    pub wasm_processor: Option<WasmProcessor>,
+    pub redis_row_converter: Option<RedisRowConverter>,
+    pub mongo_row_converter: Option<MongoRowConverter>,
 }
 
 enum SinkMethod {
@@ -90,6 +99,20 @@ impl Pipeline for BasePipeline {
                 self.parallelizer.drain(self.buffer.as_ref()).await?
             };
 
+            // redis entries are sinked as rows to relational / json targets
+            let data = if let Some(redis_row_converter) = &self.redis_row_converter {
+                Self::convert_redis(redis_row_converter, data)?
+            } else {
+                data
+            };
+
+            // mongo documents are flattened into rows of relational / analytical targets
+            let data = if let Some(mongo_row_converter) = &self.mongo_row_converter {
+                Self::convert_mongo(mongo_row_converter, data)?
+            } else {
+                data
+            };
+
             if let Some(data_marker) = &mut self.data_marker {
                 if !data.is_empty() {
                     data_marker.write().unwrap().data_origin_node =
@@ -97,6 +120,10 @@ impl Pipeline for BasePipeline {
                 }
             }
 
+            // shard ddl states are persisted with the checkpoints of the items they were pushed with
+            self.pop_shard_ddl_states(&data);
+            self.pop_online_ddl_states(&data);
+
             // process all row_datas in buffer at a time
             let (count, last_received, last_commit) = match self.get_sink_method(&data) {
                 SinkMethod::Ddl => self.sink_ddl(data).await?,
@@ -168,11 +195,19 @@ impl BasePipeline {
         let (mut data, last_received_position, last_commit_position) = Self::fetch_dml(all_data);
         let count = data.len();
         if count > 0 {
+            if let Some(transform_processor) = &self.transform_processor {
+                data = transform_processor.process(data)?;
+            }
+
             // execute lua processor
             if let Some(lua_processor) = &self.lua_processor {
                 data = lua_processor.process(data)?;
             }
 
+            if let Some(wasm_processor) = &mut self.wasm_processor {
+                data = wasm_processor.process(data)?;
+            }
+
             self.parallelizer.sink_dml(data, &self.sinkers).await?
         }
         Ok((count, last_received_position, last_commit_position))
@@ -182,9 +217,15 @@ impl BasePipeline {
         &mut self,
         all_data: Vec<DtItem>,
     ) -> anyhow::Result<(usize, Option<Position>, Option<Position>)> {
-        let (data, last_received_position, last_commit_position) = Self::fetch_ddl(all_data);
+        let (mut data, last_received_position, last_commit_position) = Self::fetch_ddl(all_data);
         let count = data.len();
-        if count > 0 {
+        if let Some(lua_processor) = &self.lua_processor {
+            data = lua_processor.process_ddl(data)?;
+        }
+        if let Some(wasm_processor) = &mut self.wasm_processor {
+            data = wasm_processor.process_ddl(data)?;
+        }
+        if !data.is_empty() {
             self.parallelizer
                 .sink_ddl(data.clone(), &self.sinkers)
                 .await?;
@@ -196,6 +237,65 @@ impl BasePipeline {
         Ok((count, last_received_position, last_commit_position))
     }
 
+    fn convert_redis(
+        redis_row_converter: &RedisRowConverter,
+        mut data: Vec<DtItem>,
+    ) -> anyhow::Result<Vec<DtItem>> {
+        let mut result = Vec::new();
+        for i in data.drain(..) {
+            let entry = match &i.dt_data {
+                DtData::Redis { entry } => entry,
+                _ => {
+                    result.push(i);
+                    continue;
+                }
+            };
+
+            for row_data in redis_row_converter.convert(entry)? {
+                result.push(DtItem {
+                    dt_data: DtData::Dml { row_data },
+                    position: i.position.clone(),
+                    data_origin_node: i.data_origin_node.clone(),
+                });
+            }
+
+            // each redis cmd is a transaction, commit its position even if no rows produced
+            if !matches!(i.position, Position::None) {
+                result.push(DtItem {
+                    dt_data: DtData::Commit { xid: String::new() },
+                    position: i.position,
+                    data_origin_node: i.data_origin_node,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    fn convert_mongo(
+        mongo_row_converter: &MongoRowConverter,
+        mut data: Vec<DtItem>,
+    ) -> anyhow::Result<Vec<DtItem>> {
+        let mut result = Vec::new();
+        for i in data.drain(..) {
+            let row_data = match i.dt_data {
+                DtData::Dml { row_data } => row_data,
+                dt_data => {
+                    result.push(DtItem { dt_data, ..i });
+                    continue;
+                }
+            };
+
+            for row_data in mongo_row_converter.convert(row_data)? {
+                result.push(DtItem {
+                    dt_data: DtData::Dml { row_data },
+                    position: i.position.clone(),
+                    data_origin_node: i.data_origin_node.clone(),
+                });
+            }
+        }
+        Ok(result)
+    }
+
     pub fn fetch_raw(data: &[DtItem]) -> (Option<Position>, Option<Position>) {
         let mut last_received_position = Option::None;
         let mut last_commit_position = Option::None;
@@ -294,6 +394,37 @@ impl BasePipeline {
         SinkMethod::Raw
     }
 
+    fn pop_shard_ddl_states(&self, data: &[DtItem]) {
+        let mut syncer = self.syncer.lock().unwrap();
+        for i in data {
+            match syncer.shard_ddl_states.front() {
+                Some((position, is_ddl, _)) if *position == i.position && *is_ddl == i.is_ddl() => {
+                    let (_, _, state) = syncer.shard_ddl_states.pop_front().unwrap();
+                    syncer.shard_ddl_state = state;
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+
+    fn pop_online_ddl_states(&self, data: &[DtItem]) {
+        let mut syncer = self.syncer.lock().unwrap();
+        for i in data {
+            if !matches!(i.dt_data, DtData::Heartbeat {}) {
+                continue;
+            }
+            match syncer.online_ddl_states.front() {
+                Some((position, _)) if *position == i.position => {
+                    let (_, state) = syncer.online_ddl_states.pop_front().unwrap();
+                    syncer.online_ddl_state = state;
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+
     fn record_checkpoint(
         &self,
         last_checkpoint_time: Option<Instant>,
@@ -309,6 +440,16 @@ impl BasePipeline {
         log_position!("current_position | {}", last_received_position.to_string());
         log_position!("checkpoint_position | {}", last_commit_position.to_string());
 
+        let shard_ddl_state = self.syncer.lock().unwrap().shard_ddl_state.clone();
+        if !shard_ddl_state.is_empty() {
+            log_position!("shard_ddl_state | {}", shard_ddl_state);
+        }
+
+        let online_ddl_state = self.syncer.lock().unwrap().online_ddl_state.clone();
+        if !online_ddl_state.is_empty() {
+            log_position!("online_ddl_state | {}", online_ddl_state);
+        }
+
         if !matches!(last_commit_position, Position::None) {
             self.syncer.lock().unwrap().committed_position = last_commit_position.to_owned();
         }
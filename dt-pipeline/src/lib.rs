@@ -1,6 +1,8 @@
 pub mod base_pipeline;
 pub mod http_server_pipeline;
 pub mod lua_processor;
+pub mod transform_processor;
+pub mod wasm_processor;
 
 use async_trait::async_trait;
 
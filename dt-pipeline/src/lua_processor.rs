@@ -1,86 +1,300 @@
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
 use std::str::FromStr;
+use std::sync::Arc;
 
+use anyhow::bail;
+use dt_common::config::config_enums::LuaModeEnum;
+use dt_common::error::Error;
 use dt_common::meta::col_value::ColValue;
+use dt_common::meta::ddl_meta::ddl_data::DdlData;
+use dt_common::meta::ddl_meta::ddl_parser::DdlParser;
 use dt_common::meta::row_data::RowData;
 use dt_common::meta::row_type::RowType;
-use mlua::{IntoLua, Lua};
+use mlua::{Function, IntoLua, Lua};
 
+const JSON_PREFIX: &str = "json:";
+const INIT: &str = "init";
+const PROCESS_ROW: &str = "process_row";
+const PROCESS_DDL: &str = "process_ddl";
+const LOOKUP: &str = "lookup";
+
+type LookupTables = HashMap<String, serde_json::Map<String, serde_json::Value>>;
+
+/// runs user lua code in the mode of lua_mode.
+/// legacy: the code is executed for each row with global variables: schema, tb, row_type, before,
+/// after, a new lua state is created for each batch, so global variables are not kept between batches.
+/// hooks: the code is executed once at start in a long-lived lua state and then init() is called,
+/// process_row / process_ddl are called for each row / ddl, and may return nil, one row / ddl or a
+/// list of them, global variables are kept between rows.
 pub struct LuaProcessor {
-    pub lua_code: String,
+    lua_code: String,
+    lookup_tables: Arc<LookupTables>,
+    // the long-lived lua state in hooks mode, None in legacy mode
+    lua: Option<Lua>,
 }
 
 impl LuaProcessor {
+    pub fn new(lua_code: &str, lua_mode: LuaModeEnum, lookup_files: &str) -> anyhow::Result<Self> {
+        let lookup_tables = Arc::new(Self::load_lookup_files(lookup_files)?);
+        let lua = match lua_mode {
+            LuaModeEnum::Legacy => {
+                // compiled at start so syntax errors fail the task before any row is processed
+                Lua::new().load(lua_code).into_function()?;
+                None
+            }
+            LuaModeEnum::Hooks => Some(Self::load_hooks(lua_code, lookup_tables.clone())?),
+        };
+
+        Ok(Self {
+            lua_code: lua_code.into(),
+            lookup_tables,
+            lua,
+        })
+    }
+
     pub fn process(&self, data: Vec<RowData>) -> anyhow::Result<Vec<RowData>> {
         let mut new_data = Vec::new();
+        match &self.lua {
+            None => {
+                let lua = Self::new_lua(self.lookup_tables.clone())?;
+                let chunk = lua.load(&self.lua_code).into_function()?;
+                for row_data in data {
+                    if let Some(row_data) = Self::process_row_by_chunk(&lua, &chunk, row_data)? {
+                        new_data.push(row_data);
+                    }
+                }
+            }
+
+            Some(lua) => {
+                let process_row: Option<Function> = lua.globals().get(PROCESS_ROW)?;
+                let process_row = match process_row {
+                    Some(process_row) => process_row,
+                    None => return Ok(data),
+                };
+                for row_data in data {
+                    let col_kinds = Self::get_col_kinds(&row_data);
+                    let lua_row = Self::row_data_to_lua_table(lua, row_data)?;
+                    let result: mlua::Value = process_row.call(lua_row)?;
+                    new_data.extend(Self::lua_value_to_rows(result, &col_kinds)?);
+                }
+            }
+        }
+        Ok(new_data)
+    }
+
+    pub fn process_ddl(&self, data: Vec<DdlData>) -> anyhow::Result<Vec<DdlData>> {
+        let lua = match &self.lua {
+            Some(lua) => lua,
+            None => return Ok(data),
+        };
+        let process_ddl: Option<Function> = lua.globals().get(PROCESS_DDL)?;
+        let process_ddl = match process_ddl {
+            Some(process_ddl) => process_ddl,
+            None => return Ok(data),
+        };
+
+        let mut new_data = Vec::new();
+        for ddl_data in data {
+            let lua_ddl = Self::ddl_data_to_lua_table(lua, &ddl_data)?;
+            let result: mlua::Value = process_ddl.call(lua_ddl)?;
+            let lua_ddls = match result {
+                mlua::Value::Nil => continue,
+                mlua::Value::Table(table) if table.contains_key("query")? => vec![table],
+                mlua::Value::Table(table) => table
+                    .sequence_values::<mlua::Table>()
+                    .collect::<mlua::Result<Vec<_>>>()?,
+                _ => bail! {Error::PipelineError(
+                    "process_ddl should return nil, a ddl or a list of ddls".into()
+                )},
+            };
+            for lua_ddl in lua_ddls {
+                new_data.push(Self::lua_table_to_ddl_data(lua_ddl, &ddl_data)?);
+            }
+        }
+        Ok(new_data)
+    }
+
+    fn new_lua(lookup_tables: Arc<LookupTables>) -> anyhow::Result<Lua> {
         let lua = Lua::new();
+        Self::register_lookup(&lua, lookup_tables)?;
+        Ok(lua)
+    }
 
-        for row_data in data {
-            // to lua
-            let (lua_before, blob_before) = self.col_values_to_lua_table(row_data.before, &lua)?;
-            let (lua_after, blob_after) = self.col_values_to_lua_table(row_data.after, &lua)?;
+    // executes the code once and calls init(), the code should define process_row or process_ddl
+    fn load_hooks(lua_code: &str, lookup_tables: Arc<LookupTables>) -> anyhow::Result<Lua> {
+        let lua = Self::new_lua(lookup_tables)?;
+        lua.load(lua_code).exec()?;
 
-            lua.globals().set("before", lua_before)?;
-            lua.globals().set("after", lua_after)?;
-            lua.globals().set("schema", row_data.schema)?;
-            lua.globals().set("tb", row_data.tb)?;
-            lua.globals()
-                .set("row_type", row_data.row_type.to_string())?;
+        let mut hook_defined = false;
+        for hook in [PROCESS_ROW, PROCESS_DDL] {
+            if let mlua::Value::Function(_) = lua.globals().get::<_, mlua::Value>(hook)? {
+                hook_defined = true;
+            }
+        }
+        if !hook_defined {
+            bail! {Error::ConfigError(format!(
+                "lua_mode is {}, but neither {} nor {} is defined in lua code",
+                LuaModeEnum::Hooks,
+                PROCESS_ROW,
+                PROCESS_DDL
+            ))}
+        }
+
+        {
+            let init: Option<Function> = lua.globals().get(INIT)?;
+            if let Some(init) = init {
+                init.call::<_, ()>(())?;
+            }
+        }
+        Ok(lua)
+    }
+
+    fn process_row_by_chunk(
+        lua: &Lua,
+        chunk: &Function,
+        row_data: RowData,
+    ) -> anyhow::Result<Option<RowData>> {
+        let col_kinds = Self::get_col_kinds(&row_data);
+
+        // to lua
+        let lua_before = Self::col_values_to_lua_table(lua, row_data.before)?;
+        let lua_after = Self::col_values_to_lua_table(lua, row_data.after)?;
+        lua.globals().set("before", lua_before)?;
+        lua.globals().set("after", lua_after)?;
+        lua.globals().set("schema", row_data.schema)?;
+        lua.globals().set("tb", row_data.tb)?;
+        lua.globals()
+            .set("row_type", row_data.row_type.to_string())?;
+
+        // execute lua
+        chunk.call::<_, ()>(())?;
+
+        // row filtered
+        let row_type: String = lua.globals().get("row_type")?;
+        if row_type.is_empty() {
+            return Ok(None);
+        }
+
+        // from lua
+        let lua_before: mlua::Table = lua.globals().get("before")?;
+        let lua_after: mlua::Table = lua.globals().get("after")?;
+        let before = Self::lua_table_to_col_values(lua_before, &col_kinds)?;
+        let after = Self::lua_table_to_col_values(lua_after, &col_kinds)?;
 
-            // execute lua
-            lua.load(&self.lua_code).exec()?;
+        let schema = lua.globals().get("schema")?;
+        let tb = lua.globals().get("tb")?;
+        let row_type = RowType::from_str(&row_type)?;
+        Ok(Some(RowData::new(schema, tb, row_type, before, after)))
+    }
+
+    fn row_data_to_lua_table(lua: &Lua, row_data: RowData) -> anyhow::Result<mlua::Table> {
+        let table = lua.create_table()?;
+        table.set(
+            "before",
+            Self::col_values_to_lua_table(lua, row_data.before)?,
+        )?;
+        table.set("after", Self::col_values_to_lua_table(lua, row_data.after)?)?;
+        table.set("schema", row_data.schema)?;
+        table.set("tb", row_data.tb)?;
+        table.set("row_type", row_data.row_type.to_string())?;
+        Ok(table)
+    }
 
+    // a row, a list of rows or nil
+    fn lua_value_to_rows(
+        lua_value: mlua::Value,
+        col_kinds: &HashMap<String, ColValue>,
+    ) -> anyhow::Result<Vec<RowData>> {
+        let lua_rows = match lua_value {
+            mlua::Value::Nil => return Ok(Vec::new()),
+            mlua::Value::Table(table) if table.contains_key("row_type")? => vec![table],
+            mlua::Value::Table(table) => table
+                .sequence_values::<mlua::Table>()
+                .collect::<mlua::Result<Vec<_>>>()?,
+            _ => bail! {Error::PipelineError(
+                "process_row should return nil, a row or a list of rows".into()
+            )},
+        };
+
+        let mut rows = Vec::new();
+        for lua_row in lua_rows {
+            let row_type: String = lua_row.get("row_type")?;
             // row filtered
-            let row_type: String = lua.globals().get("row_type")?;
             if row_type.is_empty() {
                 continue;
             }
+            let before: Option<mlua::Table> = lua_row.get("before")?;
+            let after: Option<mlua::Table> = lua_row.get("after")?;
+            let before = match before {
+                Some(before) => Self::lua_table_to_col_values(before, col_kinds)?,
+                None => None,
+            };
+            let after = match after {
+                Some(after) => Self::lua_table_to_col_values(after, col_kinds)?,
+                None => None,
+            };
+            rows.push(RowData::new(
+                lua_row.get("schema")?,
+                lua_row.get("tb")?,
+                RowType::from_str(&row_type)?,
+                before,
+                after,
+            ));
+        }
+        Ok(rows)
+    }
 
-            // from lua
-            let lua_before: mlua::Table = lua.globals().get("before")?;
-            let lua_after: mlua::Table = lua.globals().get("after")?;
-            let before = self.lua_table_to_col_values(lua_before, blob_before)?;
-            let after = self.lua_table_to_col_values(lua_after, blob_after)?;
+    fn ddl_data_to_lua_table<'lua>(
+        lua: &'lua Lua,
+        ddl_data: &DdlData,
+    ) -> anyhow::Result<mlua::Table<'lua>> {
+        let (schema, tb) = ddl_data.get_schema_tb();
+        let table = lua.create_table()?;
+        table.set("db_type", ddl_data.db_type.to_string())?;
+        table.set("ddl_type", ddl_data.ddl_type.to_string())?;
+        table.set("default_schema", ddl_data.default_schema.clone())?;
+        table.set("schema", schema)?;
+        table.set("tb", tb)?;
+        table.set("query", ddl_data.query.clone())?;
+        Ok(table)
+    }
+
+    // only query and default_schema can be rewritten, a rewritten query is parsed again
+    fn lua_table_to_ddl_data(lua_ddl: mlua::Table, origin: &DdlData) -> anyhow::Result<DdlData> {
+        let query: String = lua_ddl.get("query")?;
+        let default_schema: Option<String> = lua_ddl.get("default_schema")?;
+        let default_schema = default_schema.unwrap_or_else(|| origin.default_schema.clone());
 
-            let schema = lua.globals().get("schema")?;
-            let tb = lua.globals().get("tb")?;
-            let row_type = RowType::from_str(&row_type)?;
-            let new_row_data = RowData::new(schema, tb, row_type, before, after);
-            new_data.push(new_row_data);
+        if query == origin.query {
+            let mut ddl_data = origin.clone();
+            ddl_data.default_schema = default_schema;
+            return Ok(ddl_data);
         }
 
-        Ok(new_data)
+        let mut ddl_data = DdlParser::new(origin.db_type.clone()).parse(&query)?;
+        ddl_data.default_schema = default_schema;
+        ddl_data.query = query;
+        Ok(ddl_data)
     }
 
-    fn col_values_to_lua_table<'lua>(
-        &'lua self,
+    fn col_values_to_lua_table(
+        lua: &Lua,
         col_values: Option<HashMap<String, ColValue>>,
-        lua: &'lua mlua::Lua,
-    ) -> anyhow::Result<(mlua::Table<'lua>, HashMap<String, ColValue>)> {
+    ) -> anyhow::Result<mlua::Table> {
         let lua_table = lua.create_table()?;
-        let mut blob_col_values = HashMap::new();
-
         if let Some(map) = col_values {
             for (key, col_value) in map {
-                let lua_value = match col_value {
-                    // do not support editing Blob columns in lua, pass empty values into lua
-                    ColValue::Blob(_) => {
-                        blob_col_values.insert(key.clone(), col_value);
-                        self.col_value_to_lua_value(ColValue::Blob(Vec::new()), lua)?
-                    }
-                    _ => self.col_value_to_lua_value(col_value, lua)?,
-                };
-                lua_table.set(key, lua_value)?;
+                lua_table.set(key, Self::col_value_to_lua_value(lua, col_value)?)?;
             }
         }
-
-        Ok((lua_table, blob_col_values))
+        Ok(lua_table)
     }
 
     fn lua_table_to_col_values(
-        &self,
         lua_table: mlua::Table,
-        blob_col_values: HashMap<String, ColValue>,
+        col_kinds: &HashMap<String, ColValue>,
     ) -> anyhow::Result<Option<HashMap<String, ColValue>>> {
         if lua_table.is_empty() {
             return Ok(None);
@@ -88,33 +302,14 @@ impl LuaProcessor {
 
         let mut map = HashMap::new();
         for pair in lua_table.pairs() {
-            let pair = pair?;
-            let lua_value: mlua::Value = pair.1;
-            let col_value = self.lua_value_to_col_value(lua_value)?;
-            map.insert(pair.0, col_value);
+            let (col, lua_value): (String, mlua::Value) = pair?;
+            let col_value = Self::lua_value_to_col_value(lua_value, col_kinds.get(&col))?;
+            map.insert(col, col_value);
         }
-
-        for (col, blob_col_value) in blob_col_values {
-            // if some col was removed(set to nil) in lua, the col should not exist in map
-            // Some(col_value) = map.get(&col) means: col was NOT removed in lua
-            if let Some(col_value) = map.get(&col) {
-                // since we passed mlua::Value::NULL into lua for blob columns,
-                // *col_value == ColValue::None means: column value was not removed and not changed in lua,
-                // in this case, set the original blob_col_value back
-                if *col_value == ColValue::None {
-                    map.insert(col, blob_col_value);
-                }
-            }
-        }
-
         Ok(Some(map))
     }
 
-    fn col_value_to_lua_value<'lua>(
-        &'lua self,
-        col_value: ColValue,
-        lua: &'lua mlua::Lua,
-    ) -> anyhow::Result<mlua::Value<'lua>> {
+    fn col_value_to_lua_value(lua: &Lua, col_value: ColValue) -> anyhow::Result<mlua::Value> {
         let lua_value = match col_value {
             ColValue::Bool(v) => mlua::Value::Boolean(v),
             ColValue::Tiny(v) => mlua::Value::Integer(v as i64),
@@ -143,25 +338,247 @@ impl LuaProcessor {
             | ColValue::Enum2(v)
             | ColValue::Json2(v) => v.into_lua(lua)?,
 
-            ColValue::RawString(_) => col_value.to_string().into_lua(lua)?,
+            // lua strings are byte arrays
+            ColValue::RawString(v) | ColValue::Blob(v) | ColValue::Json(v) => {
+                mlua::Value::String(lua.create_string(v)?)
+            }
+            ColValue::Json3(v) => v.to_string().into_lua(lua)?,
 
-            ColValue::Json3(_)
-            | ColValue::Blob(_)
-            | ColValue::Json(_)
-            | ColValue::MongoDoc(_)
-            | ColValue::None => mlua::Value::NULL,
+            // mongo documents can not be edited in lua
+            ColValue::MongoDoc(_) | ColValue::None => mlua::Value::NULL,
         };
         Ok(lua_value)
     }
 
-    fn lua_value_to_col_value(&self, lua_value: mlua::Value) -> anyhow::Result<ColValue> {
-        let col_value = match lua_value {
-            mlua::Value::Boolean(v) => ColValue::Bool(v),
-            mlua::Value::Integer(v) => ColValue::LongLong(v),
-            mlua::Value::Number(v) => ColValue::Double(v),
-            mlua::Value::String(v) => ColValue::String(v.to_str()?.to_string()),
+    // kind is the original value of the column, used to restore binary / json / mongo values
+    fn lua_value_to_col_value(
+        lua_value: mlua::Value,
+        kind: Option<&ColValue>,
+    ) -> anyhow::Result<ColValue> {
+        let col_value = match (lua_value, kind) {
+            (mlua::Value::String(v), Some(ColValue::Blob(_))) => {
+                ColValue::Blob(v.as_bytes().to_vec())
+            }
+            (mlua::Value::String(v), Some(ColValue::RawString(_))) => {
+                ColValue::RawString(v.as_bytes().to_vec())
+            }
+            (mlua::Value::String(v), Some(ColValue::Json(_))) => {
+                ColValue::Json(v.as_bytes().to_vec())
+            }
+            (mlua::Value::String(v), Some(ColValue::Json3(_))) => {
+                match serde_json::from_slice(v.as_bytes()) {
+                    Ok(json) => ColValue::Json3(json),
+                    Err(_) => ColValue::String(v.to_str()?.to_string()),
+                }
+            }
+            (mlua::Value::LightUserData(v), Some(ColValue::MongoDoc(doc))) if v.0.is_null() => {
+                ColValue::MongoDoc(doc.clone())
+            }
+
+            (mlua::Value::Boolean(v), _) => ColValue::Bool(v),
+            (mlua::Value::Integer(v), _) => ColValue::LongLong(v),
+            (mlua::Value::Number(v), _) => ColValue::Double(v),
+            (mlua::Value::String(v), _) => ColValue::String(v.to_str()?.to_string()),
             _ => ColValue::None,
         };
         Ok(col_value)
     }
+
+    // binary / json / mongo columns of the row, binary values are not kept since only kinds are needed
+    fn get_col_kinds(row_data: &RowData) -> HashMap<String, ColValue> {
+        let mut col_kinds = HashMap::new();
+        for col_values in [&row_data.before, &row_data.after].into_iter().flatten() {
+            for (col, col_value) in col_values {
+                let kind = match col_value {
+                    ColValue::Blob(_) => ColValue::Blob(Vec::new()),
+                    ColValue::RawString(_) => ColValue::RawString(Vec::new()),
+                    ColValue::Json(_) => ColValue::Json(Vec::new()),
+                    ColValue::Json3(_) => ColValue::Json3(serde_json::Value::Null),
+                    ColValue::MongoDoc(_) => col_value.clone(),
+                    _ => continue,
+                };
+                col_kinds.insert(col.clone(), kind);
+            }
+        }
+        col_kinds
+    }
+
+    // lua_lookup_files=json:{"countries":"./dict/countries.json"}, each file is a json object
+    fn load_lookup_files(lookup_files: &str) -> anyhow::Result<LookupTables> {
+        let mut lookup_tables = LookupTables::new();
+        if lookup_files.trim().is_empty() {
+            return Ok(lookup_tables);
+        }
+
+        let files: HashMap<String, String> =
+            serde_json::from_str(lookup_files.trim().trim_start_matches(JSON_PREFIX))?;
+        for (name, file) in files {
+            let mut content = String::new();
+            File::open(&file)?.read_to_string(&mut content)?;
+            match serde_json::from_str(&content)? {
+                serde_json::Value::Object(map) => {
+                    lookup_tables.insert(name, map);
+                }
+                _ => bail! {Error::ConfigError(format!(
+                    "lookup file should be a json object, file: {}",
+                    file
+                ))},
+            }
+        }
+        Ok(lookup_tables)
+    }
+
+    // lookup(name, key) returns the value of key in the lookup table, or nil,
+    // the tables are read-only since values are copied into lua for each call
+    fn register_lookup(lua: &Lua, lookup_tables: Arc<LookupTables>) -> anyhow::Result<()> {
+        let lookup = lua.create_function(move |lua, (name, key): (String, mlua::Value)| {
+            let key = match key {
+                mlua::Value::String(v) => v.to_str()?.to_string(),
+                mlua::Value::Integer(v) => v.to_string(),
+                mlua::Value::Number(v) => v.to_string(),
+                _ => return Ok(mlua::Value::Nil),
+            };
+            match lookup_tables.get(&name).and_then(|table| table.get(&key)) {
+                Some(value) => Self::json_to_lua_value(lua, value),
+                None => Ok(mlua::Value::Nil),
+            }
+        })?;
+        lua.globals().set(LOOKUP, lookup)?;
+        Ok(())
+    }
+
+    fn json_to_lua_value<'lua>(
+        lua: &'lua Lua,
+        value: &serde_json::Value,
+    ) -> mlua::Result<mlua::Value<'lua>> {
+        let lua_value = match value {
+            serde_json::Value::Null => mlua::Value::Nil,
+            serde_json::Value::Bool(v) => mlua::Value::Boolean(*v),
+            serde_json::Value::Number(v) => match v.as_i64() {
+                Some(v) => mlua::Value::Integer(v),
+                None => mlua::Value::Number(v.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(v) => mlua::Value::String(lua.create_string(v)?),
+            serde_json::Value::Array(values) => {
+                let table = lua.create_table()?;
+                for (i, v) in values.iter().enumerate() {
+                    table.set(i + 1, Self::json_to_lua_value(lua, v)?)?;
+                }
+                mlua::Value::Table(table)
+            }
+            serde_json::Value::Object(map) => {
+                let table = lua.create_table()?;
+                for (k, v) in map {
+                    table.set(k.as_str(), Self::json_to_lua_value(lua, v)?)?;
+                }
+                mlua::Value::Table(table)
+            }
+        };
+        Ok(lua_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::{
+        config::config_enums::LuaModeEnum,
+        meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+    };
+
+    use super::LuaProcessor;
+
+    fn build_row_data(id: i64) -> RowData {
+        let after = HashMap::from([("id".to_string(), ColValue::LongLong(id))]);
+        RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        )
+    }
+
+    fn get_col(row_data: &RowData, col: &str) -> ColValue {
+        row_data.after.as_ref().unwrap().get(col).unwrap().clone()
+    }
+
+    #[test]
+    fn test_legacy_mode() {
+        // executed once for each row only, but not at start
+        let lua_code = r#"
+            count = (count or 0) + 1
+            after["count"] = count
+            after["next_id"] = after["id"] + 1
+        "#;
+        let processor = LuaProcessor::new(lua_code, LuaModeEnum::Legacy, "").unwrap();
+        let rows = processor
+            .process(vec![build_row_data(1), build_row_data(2)])
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(get_col(&rows[0], "count"), ColValue::LongLong(1));
+        assert_eq!(get_col(&rows[0], "next_id"), ColValue::LongLong(2));
+        assert_eq!(get_col(&rows[1], "count"), ColValue::LongLong(2));
+
+        // global variables are not kept between batches
+        let rows = processor.process(vec![build_row_data(3)]).unwrap();
+        assert_eq!(get_col(&rows[0], "count"), ColValue::LongLong(1));
+
+        // runtime errors of legacy scripts only fail the batch, even if hooks are referred
+        let lua_code = r#"
+            -- process_row is not called in legacy mode
+            after["next_id"] = after["id"] + 1
+        "#;
+        let processor = LuaProcessor::new(lua_code, LuaModeEnum::Legacy, "").unwrap();
+        let mut row_data = build_row_data(1);
+        row_data.after = None;
+        assert!(processor.process(vec![row_data]).is_err());
+    }
+
+    #[test]
+    fn test_hook_mode() {
+        let lua_code = r#"
+            function init()
+                prefix = "new_"
+            end
+
+            function process_row(row)
+                if row.after.id == 2 then
+                    return nil
+                end
+                row.tb = prefix .. row.tb
+                return row
+            end
+        "#;
+        let processor = LuaProcessor::new(lua_code, LuaModeEnum::Hooks, "").unwrap();
+        let rows = processor
+            .process(vec![build_row_data(1), build_row_data(2)])
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].tb, "new_tb_1");
+        assert_eq!(get_col(&rows[0], "id"), ColValue::LongLong(1));
+    }
+
+    #[test]
+    fn test_hook_mode_error() {
+        let lua_code = r#"
+            function process_row(row)
+                return row
+            end
+            error("failed to load config")
+        "#;
+        let err = LuaProcessor::new(lua_code, LuaModeEnum::Hooks, "")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("failed to load config"));
+
+        // hooks are required in hooks mode
+        let lua_code = r#"after["id"] = 1"#;
+        assert!(LuaProcessor::new(lua_code, LuaModeEnum::Hooks, "").is_err());
+
+        // syntax errors are returned in both modes
+        assert!(LuaProcessor::new("after[", LuaModeEnum::Legacy, "").is_err());
+        assert!(LuaProcessor::new("after[", LuaModeEnum::Hooks, "").is_err());
+    }
 }
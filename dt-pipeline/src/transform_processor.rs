@@ -0,0 +1,621 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::bail;
+use chrono::Utc;
+use dt_common::{
+    config::{config_enums::DbType, transform_config::TransformConfig},
+    error::Error,
+    meta::{
+        col_value::ColValue,
+        condition::{
+            condition_expr::{ColumnResolver, EvalContext, Operand},
+            condition_parser::ConditionParser,
+            condition_value::ConditionValue,
+        },
+        row_data::RowData,
+    },
+};
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+const JSON_PREFIX: &str = "json:";
+const WILDCARD: &str = "*";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+
+// rules=json:[{"db":"app","tb":"users","col":"phone","type":"mask","keep_prefix":3,"keep_suffix":4}]
+#[derive(Deserialize)]
+struct RuleConfig {
+    db: String,
+    tb: String,
+    col: String,
+    #[serde(flatten)]
+    transform: TransformType,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TransformType {
+    Hash,
+    Hmac,
+    Mask {
+        #[serde(default)]
+        keep_prefix: usize,
+        #[serde(default)]
+        keep_suffix: usize,
+        #[serde(default = "default_mask_char")]
+        mask_char: char,
+    },
+    Null,
+    Constant {
+        value: serde_json::Value,
+    },
+    RegexReplace {
+        pattern: String,
+        replacement: String,
+    },
+    Cast {
+        to: String,
+    },
+    Compute {
+        expr: String,
+    },
+    Add {
+        value: serde_json::Value,
+    },
+    Drop,
+}
+
+fn default_mask_char() -> char {
+    '*'
+}
+
+#[derive(Debug)]
+enum CastType {
+    String,
+    Int,
+    Float,
+    Decimal,
+    Bool,
+    Date,
+    DateTime,
+}
+
+enum Transform {
+    Hash,
+    Hmac,
+    Mask {
+        keep_prefix: usize,
+        keep_suffix: usize,
+        mask_char: char,
+    },
+    Null,
+    Constant(ColValue),
+    RegexReplace(Regex, String),
+    Cast(CastType),
+    Compute(Operand, HashSet<String>),
+    Add(ColValue),
+    Drop,
+}
+
+struct TransformRule {
+    db: String,
+    tb: String,
+    col: String,
+    transform: Transform,
+}
+
+impl TransformRule {
+    fn matches(&self, schema: &str, tb: &str) -> bool {
+        (self.db == WILDCARD || self.db == schema) && (self.tb == WILDCARD || self.tb == tb)
+    }
+}
+
+/// applies column transforms to both before and after of rows in the configured order,
+/// all transforms are deterministic (except computed columns using NOW()), so masked keys
+/// of the same values are still equal and joinable
+pub struct TransformProcessor {
+    rules: Vec<TransformRule>,
+    hmac_key: Vec<u8>,
+}
+
+impl TransformProcessor {
+    pub fn new(config: &TransformConfig, db_type: &DbType) -> anyhow::Result<Self> {
+        let mut rules = Vec::new();
+        if !config.rules.trim().is_empty() {
+            let rule_configs: Vec<RuleConfig> =
+                serde_json::from_str(config.rules.trim().trim_start_matches(JSON_PREFIX))?;
+            for i in rule_configs {
+                let transform = Self::build_transform(i.transform, db_type)?;
+                if matches!(transform, Transform::Hmac) && config.hmac_key.is_empty() {
+                    bail! {Error::ConfigError(format!(
+                        "hmac_key is required by the hmac transform of column: {}.{}.{}",
+                        i.db, i.tb, i.col
+                    ))}
+                }
+                rules.push(TransformRule {
+                    db: i.db,
+                    tb: i.tb,
+                    col: i.col,
+                    transform,
+                });
+            }
+        }
+
+        Ok(Self {
+            rules,
+            hmac_key: config.hmac_key.as_bytes().to_vec(),
+        })
+    }
+
+    pub fn process(&self, data: Vec<RowData>) -> anyhow::Result<Vec<RowData>> {
+        let mut new_data = Vec::new();
+        for row_data in data {
+            let rules: Vec<&TransformRule> = self
+                .rules
+                .iter()
+                .filter(|i| i.matches(&row_data.schema, &row_data.tb))
+                .collect();
+            if rules.is_empty() {
+                new_data.push(row_data);
+                continue;
+            }
+
+            let RowData {
+                schema,
+                tb,
+                row_type,
+                mut before,
+                mut after,
+                partial_key,
+                ..
+            } = row_data;
+            for col_values in [&mut before, &mut after].into_iter().flatten() {
+                for rule in rules.iter() {
+                    self.apply(rule, col_values)?;
+                }
+            }
+            let mut row_data = RowData::new(schema, tb, row_type, before, after);
+            row_data.partial_key = partial_key;
+            new_data.push(row_data);
+        }
+        Ok(new_data)
+    }
+
+    fn apply(
+        &self,
+        rule: &TransformRule,
+        col_values: &mut HashMap<String, ColValue>,
+    ) -> anyhow::Result<()> {
+        match &rule.transform {
+            Transform::Add(value) => {
+                col_values.insert(rule.col.clone(), value.clone());
+                return Ok(());
+            }
+
+            Transform::Drop => {
+                col_values.remove(&rule.col);
+                return Ok(());
+            }
+
+            Transform::Compute(expr, cols) => {
+                // skip images without all source columns, e.g. before of pg without REPLICA IDENTITY FULL
+                if cols.iter().any(|col| !col_values.contains_key(col)) {
+                    return Ok(());
+                }
+                let ctx = EvalContext {
+                    now: Utc::now().naive_utc(),
+                    case_insensitive: false,
+                };
+                let value = expr.eval(&ColValuesResolver { col_values }, &ctx);
+                col_values.insert(rule.col.clone(), Self::condition_value_to_col_value(value));
+                return Ok(());
+            }
+
+            _ => {}
+        }
+
+        // other transforms only apply to existing columns
+        let col_value = match col_values.get(&rule.col) {
+            Some(col_value) => col_value,
+            None => return Ok(()),
+        };
+        let new_col_value = match &rule.transform {
+            Transform::Null => ColValue::None,
+            Transform::Constant(value) => value.clone(),
+            _ if *col_value == ColValue::None => ColValue::None,
+            Transform::Hash => {
+                ColValue::String(hex::encode(Sha256::digest(Self::get_bytes(col_value))))
+            }
+            Transform::Hmac => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(&self.hmac_key)
+                    .map_err(|e| Error::ConfigError(e.to_string()))?;
+                mac.update(&Self::get_bytes(col_value));
+                ColValue::String(hex::encode(mac.finalize().into_bytes()))
+            }
+            Transform::Mask {
+                keep_prefix,
+                keep_suffix,
+                mask_char,
+            } => ColValue::String(Self::mask(
+                &col_value.to_option_string().unwrap_or_default(),
+                *keep_prefix,
+                *keep_suffix,
+                *mask_char,
+            )),
+            Transform::RegexReplace(regex, replacement) => {
+                let value = col_value.to_option_string().unwrap_or_default();
+                ColValue::String(regex.replace_all(&value, replacement.as_str()).to_string())
+            }
+            Transform::Cast(cast_type) => Self::cast(col_value, cast_type)?,
+            Transform::Add(_) | Transform::Drop | Transform::Compute(..) => return Ok(()),
+        };
+        col_values.insert(rule.col.clone(), new_col_value);
+        Ok(())
+    }
+
+    fn build_transform(
+        transform_type: TransformType,
+        db_type: &DbType,
+    ) -> anyhow::Result<Transform> {
+        let transform = match transform_type {
+            TransformType::Hash => Transform::Hash,
+            TransformType::Hmac => Transform::Hmac,
+            TransformType::Mask {
+                keep_prefix,
+                keep_suffix,
+                mask_char,
+            } => Transform::Mask {
+                keep_prefix,
+                keep_suffix,
+                mask_char,
+            },
+            TransformType::Null => Transform::Null,
+            TransformType::Constant { value } => {
+                Transform::Constant(Self::json_to_col_value(value))
+            }
+            TransformType::RegexReplace {
+                pattern,
+                replacement,
+            } => Transform::RegexReplace(Regex::new(&pattern)?, replacement),
+            TransformType::Cast { to } => {
+                let cast_type = match to.to_lowercase().as_str() {
+                    "string" => CastType::String,
+                    "int" => CastType::Int,
+                    "float" => CastType::Float,
+                    "decimal" => CastType::Decimal,
+                    "bool" => CastType::Bool,
+                    "date" => CastType::Date,
+                    "datetime" => CastType::DateTime,
+                    _ => bail! {Error::ConfigError(format!("unsupported cast type: {}", to))},
+                };
+                Transform::Cast(cast_type)
+            }
+            TransformType::Compute { expr } => {
+                let operand = ConditionParser::parse_operand_expr(&expr, db_type)?;
+                let cols = operand.get_cols();
+                Transform::Compute(operand, cols)
+            }
+            TransformType::Add { value } => Transform::Add(Self::json_to_col_value(value)),
+            TransformType::Drop => Transform::Drop,
+        };
+        Ok(transform)
+    }
+
+    fn get_bytes(col_value: &ColValue) -> Vec<u8> {
+        match col_value {
+            ColValue::Blob(v) | ColValue::RawString(v) | ColValue::Json(v) => v.clone(),
+            _ => col_value
+                .to_option_string()
+                .unwrap_or_default()
+                .into_bytes(),
+        }
+    }
+
+    /// masks letters and digits, separators like - @ . are kept to preserve the format
+    fn mask(value: &str, keep_prefix: usize, keep_suffix: usize, mask_char: char) -> String {
+        let len = value.chars().count();
+        value
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                if i < keep_prefix || i + keep_suffix >= len || !c.is_alphanumeric() {
+                    c
+                } else {
+                    mask_char
+                }
+            })
+            .collect()
+    }
+
+    fn cast(col_value: &ColValue, cast_type: &CastType) -> anyhow::Result<ColValue> {
+        let value = ConditionValue::from_col_value(col_value);
+        let result = match cast_type {
+            CastType::String => col_value.to_option_string().map(ColValue::String),
+            CastType::Int => match value.to_number() {
+                Some(ConditionValue::Int(v)) => i64::try_from(v).ok().map(ColValue::LongLong),
+                Some(ConditionValue::Float(v)) if v.fract() == 0.0 && v.abs() < i64::MAX as f64 => {
+                    Some(ColValue::LongLong(v as i64))
+                }
+                _ => None,
+            },
+            CastType::Float => value
+                .to_number()
+                .and_then(|v| v.to_f64())
+                .map(ColValue::Double),
+            // keep the original text to avoid losing precision
+            CastType::Decimal => match value.to_number() {
+                Some(_) => col_value
+                    .to_option_string()
+                    .map(|v| ColValue::Decimal(v.trim().to_string())),
+                None => None,
+            },
+            CastType::Bool => match &value {
+                ConditionValue::String(v) if v.eq_ignore_ascii_case("true") => {
+                    Some(ColValue::Bool(true))
+                }
+                ConditionValue::String(v) if v.eq_ignore_ascii_case("false") => {
+                    Some(ColValue::Bool(false))
+                }
+                _ => value
+                    .to_number()
+                    .and_then(|v| v.to_bool())
+                    .map(ColValue::Bool),
+            },
+            CastType::Date => value
+                .to_datetime()
+                .map(|v| ColValue::Date(v.format(DATE_FORMAT).to_string())),
+            CastType::DateTime => value
+                .to_datetime()
+                .map(|v| ColValue::DateTime(v.format(DATETIME_FORMAT).to_string())),
+        };
+
+        match result {
+            Some(v) => Ok(v),
+            None => bail! {Error::PipelineError(format!(
+                "can not cast value: {} to {:?}",
+                col_value, cast_type
+            ))},
+        }
+    }
+
+    fn condition_value_to_col_value(value: ConditionValue) -> ColValue {
+        match value {
+            ConditionValue::Null => ColValue::None,
+            ConditionValue::Bool(v) => ColValue::Bool(v),
+            ConditionValue::Int(v) => match i64::try_from(v) {
+                Ok(v) => ColValue::LongLong(v),
+                Err(_) => ColValue::Decimal(v.to_string()),
+            },
+            ConditionValue::Float(v) => ColValue::Double(v),
+            ConditionValue::String(v) => ColValue::String(v),
+            ConditionValue::DateTime(v) => {
+                ColValue::DateTime(v.format(DATETIME_FORMAT).to_string())
+            }
+        }
+    }
+
+    fn json_to_col_value(value: serde_json::Value) -> ColValue {
+        match value {
+            serde_json::Value::Null => ColValue::None,
+            serde_json::Value::Bool(v) => ColValue::Bool(v),
+            serde_json::Value::Number(v) => {
+                if let Some(v) = v.as_i64() {
+                    ColValue::LongLong(v)
+                } else if let Some(v) = v.as_u64() {
+                    ColValue::UnsignedLongLong(v)
+                } else {
+                    ColValue::Double(v.as_f64().unwrap_or_default())
+                }
+            }
+            serde_json::Value::String(v) => ColValue::String(v),
+            v => ColValue::Json2(v.to_string()),
+        }
+    }
+}
+
+struct ColValuesResolver<'a> {
+    col_values: &'a HashMap<String, ColValue>,
+}
+
+impl ColumnResolver for ColValuesResolver<'_> {
+    fn resolve(&self, col: &str) -> ConditionValue {
+        match self.col_values.get(col) {
+            Some(col_value) => ConditionValue::from_col_value(col_value),
+            None => ConditionValue::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::{
+        config::{config_enums::DbType, transform_config::TransformConfig},
+        meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+    };
+
+    use super::TransformProcessor;
+
+    fn build_processor(rules: &str) -> TransformProcessor {
+        let config = TransformConfig {
+            rules: format!("json:{}", rules),
+            hmac_key: "secret".into(),
+        };
+        TransformProcessor::new(&config, &DbType::Mysql).unwrap()
+    }
+
+    fn build_row_data(row_type: RowType) -> RowData {
+        let mut col_values = HashMap::new();
+        col_values.insert("id".to_string(), ColValue::Long(1));
+        col_values.insert(
+            "email".to_string(),
+            ColValue::String("alice@example.com".into()),
+        );
+        col_values.insert(
+            "phone".to_string(),
+            ColValue::String("138-1234-5678".into()),
+        );
+        col_values.insert("first_name".to_string(), ColValue::String("Alice".into()));
+        col_values.insert("last_name".to_string(), ColValue::String("Liddell".into()));
+        col_values.insert("score".to_string(), ColValue::Decimal("98.50".into()));
+        col_values.insert("remark".to_string(), ColValue::None);
+        match row_type {
+            RowType::Insert => RowData::new(
+                "app".into(),
+                "users".into(),
+                row_type,
+                None,
+                Some(col_values),
+            ),
+            RowType::Delete => RowData::new(
+                "app".into(),
+                "users".into(),
+                row_type,
+                Some(col_values),
+                None,
+            ),
+            RowType::Update => RowData::new(
+                "app".into(),
+                "users".into(),
+                row_type,
+                Some(col_values.clone()),
+                Some(col_values),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_mask_and_hash() {
+        let processor = build_processor(
+            r#"[{"db":"app","tb":"users","col":"phone","type":"mask","keep_prefix":3,"keep_suffix":4},
+                {"db":"app","tb":"*","col":"email","type":"hmac"},
+                {"db":"*","tb":"*","col":"id","type":"hash"},
+                {"db":"app","tb":"users","col":"remark","type":"hash"},
+                {"db":"app","tb":"orders","col":"first_name","type":"null"}]"#,
+        );
+        let row_data = processor
+            .process(vec![build_row_data(RowType::Update)])
+            .unwrap()
+            .remove(0);
+        let before = row_data.before.unwrap();
+        let after = row_data.after.unwrap();
+        // before and after are transformed the same way, so keys are still matched
+        assert_eq!(before, after);
+
+        assert_eq!(after["phone"], ColValue::String("138-****-5678".into()));
+        assert_eq!(
+            after["id"],
+            ColValue::String(
+                "6b86b273ff34fce19d6b804eff5a3f5747ada4eaa22f1d49c01e52ddb7875b4b".into()
+            )
+        );
+        match &after["email"] {
+            ColValue::String(v) => assert_eq!(v.len(), 64),
+            v => panic!("unexpected value: {:?}", v),
+        }
+        // NULL is kept
+        assert_eq!(after["remark"], ColValue::None);
+        // rules of other tables are not applied
+        assert_eq!(after["first_name"], ColValue::String("Alice".into()));
+    }
+
+    #[test]
+    fn test_replace_and_cast() {
+        let processor = build_processor(
+            r#"[{"db":"app","tb":"users","col":"email","type":"regex_replace","pattern":"^[^@]+","replacement":"user"},
+                {"db":"app","tb":"users","col":"last_name","type":"null"},
+                {"db":"app","tb":"users","col":"first_name","type":"constant","value":"anonymous"},
+                {"db":"app","tb":"users","col":"not_exist","type":"constant","value":"x"},
+                {"db":"app","tb":"users","col":"id","type":"cast","to":"string"}]"#,
+        );
+        let row_data = processor
+            .process(vec![build_row_data(RowType::Insert)])
+            .unwrap()
+            .remove(0);
+        let after = row_data.after.unwrap();
+        assert_eq!(after["email"], ColValue::String("user@example.com".into()));
+        assert_eq!(after["last_name"], ColValue::None);
+        assert_eq!(after["first_name"], ColValue::String("anonymous".into()));
+        assert!(!after.contains_key("not_exist"));
+        assert_eq!(after["id"], ColValue::String("1".into()));
+
+        // 98.50 can not be cast to int
+        let processor = build_processor(
+            r#"[{"db":"app","tb":"users","col":"score","type":"cast","to":"int"}]"#,
+        );
+        assert!(processor
+            .process(vec![build_row_data(RowType::Insert)])
+            .is_err());
+        let processor = build_processor(
+            r#"[{"db":"app","tb":"users","col":"score","type":"cast","to":"float"}]"#,
+        );
+        let after = processor
+            .process(vec![build_row_data(RowType::Insert)])
+            .unwrap()
+            .remove(0)
+            .after
+            .unwrap();
+        assert_eq!(after["score"], ColValue::Double(98.5));
+    }
+
+    #[test]
+    fn test_compute_add_drop() {
+        let processor = build_processor(
+            r#"[{"db":"app","tb":"users","col":"full_name","type":"compute","expr":"CONCAT(first_name, ' ', last_name)"},
+                {"db":"app","tb":"users","col":"bonus","type":"compute","expr":"score * 2"},
+                {"db":"app","tb":"users","col":"source","type":"add","value":"prod"},
+                {"db":"app","tb":"users","col":"first_name","type":"drop"},
+                {"db":"app","tb":"users","col":"last_name","type":"drop"}]"#,
+        );
+        let row_data = processor
+            .process(vec![build_row_data(RowType::Delete)])
+            .unwrap()
+            .remove(0);
+        let before = row_data.before.unwrap();
+        assert_eq!(
+            before["full_name"],
+            ColValue::String("Alice Liddell".into())
+        );
+        assert_eq!(before["bonus"], ColValue::Double(197.0));
+        assert_eq!(before["source"], ColValue::String("prod".into()));
+        assert!(!before.contains_key("first_name"));
+        assert!(!before.contains_key("last_name"));
+
+        // source columns are missing in the row image
+        let mut row_data = build_row_data(RowType::Delete);
+        row_data.before.as_mut().unwrap().remove("last_name");
+        let before = processor
+            .process(vec![row_data])
+            .unwrap()
+            .remove(0)
+            .before
+            .unwrap();
+        assert!(!before.contains_key("full_name"));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = TransformConfig {
+            rules: r#"json:[{"db":"app","tb":"users","col":"email","type":"hmac"}]"#.into(),
+            hmac_key: String::new(),
+        };
+        assert!(TransformProcessor::new(&config, &DbType::Mysql).is_err());
+
+        let config = TransformConfig {
+            rules: r#"json:[{"db":"app","tb":"users","col":"email","type":"encrypt"}]"#.into(),
+            hmac_key: String::new(),
+        };
+        assert!(TransformProcessor::new(&config, &DbType::Mysql).is_err());
+
+        let config = TransformConfig {
+            rules: r#"json:[{"db":"app","tb":"users","col":"total","type":"compute","expr":"md5(id)"}]"#
+                .into(),
+            hmac_key: String::new(),
+        };
+        assert!(TransformProcessor::new(&config, &DbType::Mysql).is_err());
+    }
+}
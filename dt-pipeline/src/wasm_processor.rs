@@ -0,0 +1,613 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::bail;
+use dt_common::error::Error;
+use dt_common::meta::col_value::ColValue;
+use dt_common::meta::ddl_meta::ddl_data::DdlData;
+use dt_common::meta::ddl_meta::ddl_parser::DdlParser;
+use dt_common::meta::row_data::RowData;
+use dt_common::meta::row_type::RowType;
+use serde_json::{json, Map, Number, Value};
+use wasmtime::{
+    Config, Engine, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::WasiCtxBuilder;
+
+const MEMORY: &str = "memory";
+const WASI_INITIALIZE: &str = "_initialize";
+const ALLOC: &str = "dts_alloc";
+const DEALLOC: &str = "dts_dealloc";
+const INIT: &str = "dts_init";
+const PROCESS_ROWS: &str = "dts_process_rows";
+const PROCESS_DDLS: &str = "dts_process_ddls";
+
+struct WasmState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+/// runs a WASI / WASM plugin for each batch of rows and each ddl.
+///
+/// Module exports:
+/// - memory
+/// - dts_alloc(len: i32) -> i32, allocates the input buffer in module memory
+/// - dts_dealloc(ptr: i32, len: i32), optional, frees the input / output buffers
+/// - dts_init(), optional, called once after the module is instantiated
+/// - dts_process_rows(ptr: i32, len: i32) -> i64, optional
+/// - dts_process_ddls(ptr: i32, len: i32) -> i64, optional
+///
+/// Input and output are utf8 json arrays, the returned i64 is (output_ptr << 32 | output_len),
+/// a negative value means the plugin failed. Each call is limited by fuel, and the module memory
+/// is limited by max_memory_mb.
+pub struct WasmProcessor {
+    store: Store<WasmState>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    dealloc: Option<TypedFunc<(i32, i32), ()>>,
+    process_rows: Option<TypedFunc<(i32, i32), i64>>,
+    process_ddls: Option<TypedFunc<(i32, i32), i64>>,
+    fuel: u64,
+}
+
+impl WasmProcessor {
+    pub fn new(module_file: &str, fuel: u64, max_memory_mb: u64) -> anyhow::Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::from_file(&engine, module_file)?;
+
+        let mut linker = Linker::new(&engine);
+        preview1::add_to_linker_sync(&mut linker, |state: &mut WasmState| &mut state.wasi)?;
+
+        let state = WasmState {
+            wasi: WasiCtxBuilder::new()
+                .inherit_stdout()
+                .inherit_stderr()
+                .build_p1(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size((max_memory_mb * 1024 * 1024) as usize)
+                .build(),
+        };
+        let mut store = Store::new(&engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(fuel)?;
+
+        let instance = linker.instantiate(&mut store, &module)?;
+        let memory = match instance.get_memory(&mut store, MEMORY) {
+            Some(memory) => memory,
+            None => bail! {Error::ConfigError(format!(
+                "wasm module should export memory, file: {}",
+                module_file
+            ))},
+        };
+        let alloc = instance.get_typed_func(&mut store, ALLOC)?;
+        let dealloc = instance.get_typed_func(&mut store, DEALLOC).ok();
+        let process_rows = instance.get_typed_func(&mut store, PROCESS_ROWS).ok();
+        let process_ddls = instance.get_typed_func(&mut store, PROCESS_DDLS).ok();
+
+        // reactor modules built for WASI should be initialized before other exports are called
+        for name in [WASI_INITIALIZE, INIT] {
+            if let Ok(func) = instance.get_typed_func::<(), ()>(&mut store, name) {
+                func.call(&mut store, ())
+                    .map_err(|e| Error::PipelineError(format!("wasm {} failed: {}", name, e)))?;
+            }
+        }
+
+        Ok(Self {
+            store,
+            memory,
+            alloc,
+            dealloc,
+            process_rows,
+            process_ddls,
+            fuel,
+        })
+    }
+
+    pub fn process(&mut self, data: Vec<RowData>) -> anyhow::Result<Vec<RowData>> {
+        let process_rows = match &self.process_rows {
+            Some(process_rows) => process_rows.clone(),
+            None => return Ok(data),
+        };
+
+        let col_kinds = Self::get_col_kinds(&data);
+        let input: Vec<Value> = data.iter().map(Self::row_data_to_json).collect();
+        let output = self.call(&process_rows, PROCESS_ROWS, &Value::Array(input))?;
+        let mut new_data = Vec::new();
+        for row in Self::get_json_array(output, PROCESS_ROWS)? {
+            if let Some(row_data) = Self::json_to_row_data(row, &col_kinds)? {
+                new_data.push(row_data);
+            }
+        }
+        Ok(new_data)
+    }
+
+    pub fn process_ddl(&mut self, data: Vec<DdlData>) -> anyhow::Result<Vec<DdlData>> {
+        let process_ddls = match &self.process_ddls {
+            Some(process_ddls) => process_ddls.clone(),
+            None => return Ok(data),
+        };
+
+        // called for each ddl, so the rewritten ddls can be matched to the origin one
+        let mut new_data = Vec::new();
+        for ddl_data in data {
+            let input = json!([Self::ddl_data_to_json(&ddl_data)]);
+            let output = self.call(&process_ddls, PROCESS_DDLS, &input)?;
+            for ddl in Self::get_json_array(output, PROCESS_DDLS)? {
+                new_data.push(Self::json_to_ddl_data(ddl, &ddl_data)?);
+            }
+        }
+        Ok(new_data)
+    }
+
+    fn call(
+        &mut self,
+        func: &TypedFunc<(i32, i32), i64>,
+        name: &str,
+        input: &Value,
+    ) -> anyhow::Result<Value> {
+        // fuel is reset for each call, a plugin running out of fuel traps instead of hanging
+        self.store.set_fuel(self.fuel)?;
+
+        let input = serde_json::to_vec(input)?;
+        let input_len = input.len() as i32;
+        let input_ptr = self
+            .alloc
+            .call(&mut self.store, input_len)
+            .map_err(|e| Error::PipelineError(format!("wasm {} failed: {}", ALLOC, e)))?;
+        self.memory
+            .write(&mut self.store, input_ptr as u32 as usize, &input)?;
+
+        let result = func
+            .call(&mut self.store, (input_ptr, input_len))
+            .map_err(|e| Error::PipelineError(format!("wasm {} failed: {}", name, e)))?;
+        self.free(input_ptr, input_len)?;
+        if result < 0 {
+            bail! {Error::PipelineError(format!(
+                "wasm {} returned error code: {}",
+                name, result
+            ))}
+        }
+
+        let output_ptr = (result >> 32) as u32;
+        let output_len = result as u32;
+        let mut output = vec![0; output_len as usize];
+        self.memory
+            .read(&self.store, output_ptr as usize, &mut output)?;
+        self.free(output_ptr as i32, output_len as i32)?;
+
+        match serde_json::from_slice(&output) {
+            Ok(output) => Ok(output),
+            Err(e) => bail! {Error::PipelineError(format!(
+                "wasm {} returned invalid json: {}",
+                name, e
+            ))},
+        }
+    }
+
+    fn free(&mut self, ptr: i32, len: i32) -> anyhow::Result<()> {
+        if let Some(dealloc) = self.dealloc.clone() {
+            dealloc
+                .call(&mut self.store, (ptr, len))
+                .map_err(|e| Error::PipelineError(format!("wasm {} failed: {}", DEALLOC, e)))?;
+        }
+        Ok(())
+    }
+
+    fn get_json_array(output: Value, name: &str) -> anyhow::Result<Vec<Value>> {
+        match output {
+            Value::Array(values) => Ok(values),
+            _ => bail! {Error::PipelineError(format!(
+                "wasm {} should return a json array",
+                name
+            ))},
+        }
+    }
+
+    fn row_data_to_json(row_data: &RowData) -> Value {
+        json!({
+            "schema": row_data.schema,
+            "tb": row_data.tb,
+            "row_type": row_data.row_type.to_string(),
+            "before": Self::col_values_to_json(&row_data.before),
+            "after": Self::col_values_to_json(&row_data.after),
+        })
+    }
+
+    fn json_to_row_data(
+        row: Value,
+        col_kinds: &HashMap<(String, String), HashMap<String, ColValue>>,
+    ) -> anyhow::Result<Option<RowData>> {
+        let mut row = match row {
+            Value::Object(row) => row,
+            Value::Null => return Ok(None),
+            _ => bail! {Error::PipelineError(
+                "wasm row should be a json object".into()
+            )},
+        };
+
+        let get_str = |row: &Map<String, Value>, key: &str| match row.get(key) {
+            Some(Value::String(v)) => Ok(v.clone()),
+            _ => Err(Error::PipelineError(format!(
+                "wasm row should contain string: {}",
+                key
+            ))),
+        };
+        let schema = get_str(&row, "schema")?;
+        let tb = get_str(&row, "tb")?;
+        let row_type = get_str(&row, "row_type")?;
+        // row filtered
+        if row_type.is_empty() {
+            return Ok(None);
+        }
+
+        let empty_kinds = HashMap::new();
+        let tb_col_kinds = col_kinds
+            .get(&(schema.clone(), tb.clone()))
+            .unwrap_or(&empty_kinds);
+        let before = Self::json_to_col_values(row.remove("before"), tb_col_kinds)?;
+        let after = Self::json_to_col_values(row.remove("after"), tb_col_kinds)?;
+        Ok(Some(RowData::new(
+            schema,
+            tb,
+            RowType::from_str(&row_type)?,
+            before,
+            after,
+        )))
+    }
+
+    fn ddl_data_to_json(ddl_data: &DdlData) -> Value {
+        let (schema, tb) = ddl_data.get_schema_tb();
+        json!({
+            "db_type": ddl_data.db_type.to_string(),
+            "ddl_type": ddl_data.ddl_type.to_string(),
+            "default_schema": ddl_data.default_schema,
+            "schema": schema,
+            "tb": tb,
+            "query": ddl_data.query,
+        })
+    }
+
+    // only query and default_schema can be rewritten, a rewritten query is parsed again
+    fn json_to_ddl_data(ddl: Value, origin: &DdlData) -> anyhow::Result<DdlData> {
+        let query = match ddl.get("query") {
+            Some(Value::String(query)) => query.clone(),
+            _ => bail! {Error::PipelineError(
+                "wasm ddl should contain string: query".into()
+            )},
+        };
+        let default_schema = match ddl.get("default_schema") {
+            Some(Value::String(default_schema)) => default_schema.clone(),
+            _ => origin.default_schema.clone(),
+        };
+
+        if query == origin.query {
+            let mut ddl_data = origin.clone();
+            ddl_data.default_schema = default_schema;
+            return Ok(ddl_data);
+        }
+
+        let mut ddl_data = DdlParser::new(origin.db_type.clone()).parse(&query)?;
+        ddl_data.default_schema = default_schema;
+        ddl_data.query = query;
+        Ok(ddl_data)
+    }
+
+    fn col_values_to_json(col_values: &Option<HashMap<String, ColValue>>) -> Value {
+        match col_values {
+            Some(col_values) => Value::Object(
+                col_values
+                    .iter()
+                    .map(|(col, col_value)| (col.clone(), Self::col_value_to_json(col_value)))
+                    .collect(),
+            ),
+            None => Value::Null,
+        }
+    }
+
+    fn json_to_col_values(
+        value: Option<Value>,
+        col_kinds: &HashMap<String, ColValue>,
+    ) -> anyhow::Result<Option<HashMap<String, ColValue>>> {
+        let map = match value {
+            Some(Value::Object(map)) => map,
+            Some(Value::Null) | None => return Ok(None),
+            _ => bail! {Error::PipelineError(
+                "wasm row before / after should be a json object or null".into()
+            )},
+        };
+
+        let mut col_values = HashMap::new();
+        for (col, value) in map {
+            let col_value = Self::json_to_col_value(value, col_kinds.get(&col))?;
+            col_values.insert(col, col_value);
+        }
+        Ok(Some(col_values))
+    }
+
+    fn col_value_to_json(col_value: &ColValue) -> Value {
+        match col_value {
+            ColValue::Bool(v) => json!(v),
+            ColValue::Tiny(v) => json!(v),
+            ColValue::UnsignedTiny(v) => json!(v),
+            ColValue::Short(v) => json!(v),
+            ColValue::UnsignedShort(v) => json!(v),
+            ColValue::Long(v) => json!(v),
+            ColValue::UnsignedLong(v) => json!(v),
+            ColValue::LongLong(v) => json!(v),
+            ColValue::UnsignedLongLong(v) => json!(v),
+            ColValue::Year(v) => json!(v),
+            ColValue::Bit(v) => json!(v),
+            ColValue::Set(v) => json!(v),
+            ColValue::Enum(v) => json!(v),
+            ColValue::Float(v) => json!(v),
+            ColValue::Double(v) => json!(v),
+
+            ColValue::Decimal(v)
+            | ColValue::Time(v)
+            | ColValue::Date(v)
+            | ColValue::DateTime(v)
+            | ColValue::Timestamp(v)
+            | ColValue::String(v)
+            | ColValue::Set2(v)
+            | ColValue::Enum2(v)
+            | ColValue::Json2(v) => json!(v),
+
+            // binary values are hex encoded
+            ColValue::RawString(v) | ColValue::Blob(v) | ColValue::Json(v) => json!(hex::encode(v)),
+            ColValue::Json3(v) => v.clone(),
+
+            // mongo documents can not be edited in plugins
+            ColValue::MongoDoc(_) | ColValue::None => Value::Null,
+        }
+    }
+
+    // kind is the original value of the column, used to restore the original type,
+    // values not fitting the original type (e.g. changed by plugins) are converted by json types
+    fn json_to_col_value(value: Value, kind: Option<&ColValue>) -> anyhow::Result<ColValue> {
+        let col_value = match (value, kind) {
+            (Value::String(v), Some(ColValue::Blob(_)))
+            | (Value::String(v), Some(ColValue::RawString(_)))
+            | (Value::String(v), Some(ColValue::Json(_))) => {
+                let bytes = match hex::decode(&v) {
+                    Ok(bytes) => bytes,
+                    Err(e) => bail! {Error::PipelineError(format!(
+                        "wasm binary value should be hex encoded, value: {}, error: {}",
+                        v, e
+                    ))},
+                };
+                match kind {
+                    Some(ColValue::Blob(_)) => ColValue::Blob(bytes),
+                    Some(ColValue::RawString(_)) => ColValue::RawString(bytes),
+                    _ => ColValue::Json(bytes),
+                }
+            }
+            // json values are passed to plugins as they are
+            (v, Some(ColValue::Json3(_))) if !v.is_null() => ColValue::Json3(v),
+            (Value::String(v), Some(kind)) => match kind {
+                ColValue::Decimal(_) => ColValue::Decimal(v),
+                ColValue::Time(_) => ColValue::Time(v),
+                ColValue::Date(_) => ColValue::Date(v),
+                ColValue::DateTime(_) => ColValue::DateTime(v),
+                ColValue::Timestamp(_) => ColValue::Timestamp(v),
+                ColValue::Set2(_) => ColValue::Set2(v),
+                ColValue::Enum2(_) => ColValue::Enum2(v),
+                ColValue::Json2(_) => ColValue::Json2(v),
+                _ => ColValue::String(v),
+            },
+
+            (Value::Null, _) => ColValue::None,
+            (Value::Bool(v), _) => ColValue::Bool(v),
+            (Value::Number(v), kind) => {
+                if let Some(col_value) = kind.and_then(|kind| Self::json_number_to_kind(&v, kind)) {
+                    col_value
+                } else if let Some(v) = v.as_i64() {
+                    ColValue::LongLong(v)
+                } else if let Some(v) = v.as_u64() {
+                    ColValue::UnsignedLongLong(v)
+                } else {
+                    ColValue::Double(v.as_f64().unwrap_or_default())
+                }
+            }
+            (Value::String(v), _) => ColValue::String(v),
+            (v, _) => ColValue::Json2(v.to_string()),
+        };
+        Ok(col_value)
+    }
+
+    // None if the kind is not numeric or the number is out of its range
+    fn json_number_to_kind(v: &Number, kind: &ColValue) -> Option<ColValue> {
+        let col_value = match kind {
+            ColValue::Tiny(_) => ColValue::Tiny(v.as_i64()?.try_into().ok()?),
+            ColValue::UnsignedTiny(_) => ColValue::UnsignedTiny(v.as_u64()?.try_into().ok()?),
+            ColValue::Short(_) => ColValue::Short(v.as_i64()?.try_into().ok()?),
+            ColValue::UnsignedShort(_) => ColValue::UnsignedShort(v.as_u64()?.try_into().ok()?),
+            ColValue::Long(_) => ColValue::Long(v.as_i64()?.try_into().ok()?),
+            ColValue::UnsignedLong(_) => ColValue::UnsignedLong(v.as_u64()?.try_into().ok()?),
+            ColValue::LongLong(_) => ColValue::LongLong(v.as_i64()?),
+            ColValue::UnsignedLongLong(_) => ColValue::UnsignedLongLong(v.as_u64()?),
+            ColValue::Year(_) => ColValue::Year(v.as_u64()?.try_into().ok()?),
+            ColValue::Bit(_) => ColValue::Bit(v.as_u64()?),
+            ColValue::Set(_) => ColValue::Set(v.as_u64()?),
+            ColValue::Enum(_) => ColValue::Enum(v.as_u64()?.try_into().ok()?),
+            ColValue::Float(_) => ColValue::Float(v.as_f64()? as f32),
+            ColValue::Double(_) => ColValue::Double(v.as_f64()?),
+            _ => return None,
+        };
+        Some(col_value)
+    }
+
+    // the original type of each column of each table, values are not kept since only kinds are needed
+    fn get_col_kinds(data: &[RowData]) -> HashMap<(String, String), HashMap<String, ColValue>> {
+        let mut col_kinds: HashMap<(String, String), HashMap<String, ColValue>> = HashMap::new();
+        for row_data in data {
+            for col_values in [&row_data.before, &row_data.after].into_iter().flatten() {
+                for (col, col_value) in col_values {
+                    let kind = match col_value {
+                        ColValue::Bool(_) => ColValue::Bool(false),
+                        ColValue::Tiny(_) => ColValue::Tiny(0),
+                        ColValue::UnsignedTiny(_) => ColValue::UnsignedTiny(0),
+                        ColValue::Short(_) => ColValue::Short(0),
+                        ColValue::UnsignedShort(_) => ColValue::UnsignedShort(0),
+                        ColValue::Long(_) => ColValue::Long(0),
+                        ColValue::UnsignedLong(_) => ColValue::UnsignedLong(0),
+                        ColValue::LongLong(_) => ColValue::LongLong(0),
+                        ColValue::UnsignedLongLong(_) => ColValue::UnsignedLongLong(0),
+                        ColValue::Float(_) => ColValue::Float(0.0),
+                        ColValue::Double(_) => ColValue::Double(0.0),
+                        ColValue::Decimal(_) => ColValue::Decimal(String::new()),
+                        ColValue::Time(_) => ColValue::Time(String::new()),
+                        ColValue::Date(_) => ColValue::Date(String::new()),
+                        ColValue::DateTime(_) => ColValue::DateTime(String::new()),
+                        ColValue::Timestamp(_) => ColValue::Timestamp(String::new()),
+                        ColValue::Year(_) => ColValue::Year(0),
+                        ColValue::String(_) => ColValue::String(String::new()),
+                        ColValue::RawString(_) => ColValue::RawString(Vec::new()),
+                        ColValue::Blob(_) => ColValue::Blob(Vec::new()),
+                        ColValue::Bit(_) => ColValue::Bit(0),
+                        ColValue::Set(_) => ColValue::Set(0),
+                        ColValue::Enum(_) => ColValue::Enum(0),
+                        ColValue::Set2(_) => ColValue::Set2(String::new()),
+                        ColValue::Enum2(_) => ColValue::Enum2(String::new()),
+                        ColValue::Json(_) => ColValue::Json(Vec::new()),
+                        ColValue::Json2(_) => ColValue::Json2(String::new()),
+                        ColValue::Json3(_) => ColValue::Json3(Value::Null),
+                        ColValue::MongoDoc(_) | ColValue::None => continue,
+                    };
+                    col_kinds
+                        .entry((row_data.schema.clone(), row_data.tb.clone()))
+                        .or_default()
+                        .insert(col.clone(), kind);
+                }
+            }
+        }
+        col_kinds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{col_value::ColValue, row_data::RowData, row_type::RowType};
+
+    use super::WasmProcessor;
+
+    // bump allocator, dts_process_rows returns the output json at OUTPUT_PTR if defined,
+    // otherwise returns the input
+    fn build_processor(name: &str, output: &str, body: &str, max_memory_mb: u64) -> WasmProcessor {
+        let process_rows = if body.is_empty() {
+            r#"local.get $ptr i64.extend_i32_u i64.const 32 i64.shl
+            local.get $len i64.extend_i32_u i64.or"#
+                .to_string()
+        } else {
+            body.to_string()
+        };
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (global $heap (mut i32) (i32.const 4096))
+                (data (i32.const 16) "{}")
+                (func (export "dts_alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    global.get $heap
+                    local.set $ptr
+                    global.get $heap local.get $len i32.add global.set $heap
+                    local.get $ptr)
+                (func (export "dts_init")
+                    i32.const 32 memory.grow i32.const -1 i32.eq
+                    if unreachable end)
+                (func (export "dts_process_rows") (param $ptr i32) (param $len i32) (result i64)
+                    {})
+            )"#,
+            output.replace('\\', "\\\\").replace('"', "\\\""),
+            process_rows
+        );
+        let file = std::env::temp_dir().join(format!("ape_dts_wasm_test_{}.wat", name));
+        std::fs::write(&file, wat).unwrap();
+        WasmProcessor::new(file.to_str().unwrap(), 1_000_000, max_memory_mb).unwrap()
+    }
+
+    fn build_row_data() -> RowData {
+        let mut after = HashMap::new();
+        after.insert("id".to_string(), ColValue::Long(1));
+        after.insert("name".to_string(), ColValue::String("a".into()));
+        after.insert("data".to_string(), ColValue::Blob(vec![0xab, 0xcd]));
+        after.insert("flag".to_string(), ColValue::Tiny(-1));
+        after.insert("score".to_string(), ColValue::Float(1.5));
+        after.insert("price".to_string(), ColValue::Decimal("1.20".into()));
+        after.insert(
+            "created".to_string(),
+            ColValue::DateTime("2024-01-01 00:00:00".into()),
+        );
+        after.insert("tags".to_string(), ColValue::Set2("a,b".into()));
+        after.insert("note".to_string(), ColValue::None);
+        after.insert(
+            "doc".to_string(),
+            ColValue::Json3(serde_json::json!({"k": [1, 2]})),
+        );
+        RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        )
+    }
+
+    #[test]
+    fn test_passthrough() {
+        let mut processor = build_processor("passthrough", "", "", 16);
+        let row_data = build_row_data();
+        let result = processor.process(vec![row_data.clone()]).unwrap();
+        assert_eq!(result.len(), 1);
+
+        // column types are kept for unmodified rows
+        let after = result[0].after.as_ref().unwrap();
+        assert_eq!(after["id"], ColValue::Long(1));
+        assert_eq!(after["data"], ColValue::Blob(vec![0xab, 0xcd]));
+        assert_eq!(after, row_data.after.as_ref().unwrap());
+        assert_eq!(result[0].row_type, RowType::Insert);
+    }
+
+    #[test]
+    fn test_filter_and_fan_out() {
+        let output = r#"[
+            {"schema":"db_1","tb":"tb_1","row_type":"insert","after":{"id":1,"data":"ff"}},
+            {"schema":"db_1","tb":"tb_1","row_type":"","after":{"id":2}},
+            {"schema":"db_1","tb":"tb_1_audit","row_type":"insert","after":{"id":1,"op":"insert"}}
+        ]"#
+        .lines()
+        .map(str::trim)
+        .collect::<String>();
+        let body = format!("i64.const 68719476736 i64.const {} i64.or", output.len());
+        let mut processor = build_processor("fan_out", &output, &body, 16);
+        let result = processor.process(vec![build_row_data()]).unwrap();
+        assert_eq!(result.len(), 2);
+
+        assert_eq!(
+            result[0].after.as_ref().unwrap()["data"],
+            ColValue::Blob(vec![0xff])
+        );
+        assert_eq!(result[1].tb, "tb_1_audit");
+        assert_eq!(
+            result[1].after.as_ref().unwrap()["op"],
+            ColValue::String("insert".into())
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        // runs out of fuel
+        let mut processor = build_processor("fuel", "", "(loop br 0) i64.const 0", 16);
+        let err = processor.process(vec![build_row_data()]).unwrap_err();
+        assert!(err.to_string().contains("dts_process_rows"));
+
+        // dts_init grows memory by 2MB
+        build_processor("memory", "", "", 16);
+        let file = std::env::temp_dir().join("ape_dts_wasm_test_memory.wat");
+        assert!(WasmProcessor::new(file.to_str().unwrap(), 1_000_000, 1).is_err());
+    }
+}
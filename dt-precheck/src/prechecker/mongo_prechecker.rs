@@ -72,7 +72,7 @@ impl Prechecker for MongoPrechecker {
             ));
         }
 
-        // 1. replSet used
+        // 1. replSet used, or the specify url is a mongos of a sharded cluster
         // 2. the specify url is the master
         // let random_db = self.fetcher.get_random_db()?;
         let rs_status = self.fetcher.execute_for_db("hello").await?;
@@ -85,10 +85,13 @@ impl Prechecker for MongoPrechecker {
                 .unwrap_or(""),
             rs_status.get("me").and_then(Bson::as_str).unwrap_or(""),
         );
+        let is_mongos = rs_status.get("msg").and_then(Bson::as_str) == Some("isdbgrid");
 
         let mut err_msg = "";
         if !ok {
             err_msg = "fetching mongodb instance status with 'db.hello()' failed.";
+        } else if is_mongos {
+            // cdc of sharded clusters by source=change_stream or source=sharded_op_log
         } else if primary.is_empty() || me.is_empty() {
             err_msg = "mongodb is not a replicaSet architecture.";
         } else if primary != me {
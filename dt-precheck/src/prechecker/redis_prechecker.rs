@@ -89,6 +89,7 @@ impl Prechecker for RedisPrechecker {
             monitor: ExtractorMonitor::new(monitor),
             data_marker: None,
             time_filter: TimeFilter::default(),
+            shard_ddl_coordinator: None,
         };
 
         let mut psyncer = RedisPsyncExtractor {
@@ -1,13 +1,15 @@
 use std::{
     cmp,
+    collections::HashMap,
     str::FromStr,
     sync::{atomic::AtomicBool, Arc, Mutex},
 };
 
 use dt_common::{
     config::{
-        config_enums::{DbType, ExtractType},
+        config_enums::{DbType, ExtractType, OnlineDdlPolicyEnum, ShardDdlModeEnum},
         extractor_config::ExtractorConfig,
+        router_config::RouterConfig,
         task_config::TaskConfig,
     },
     meta::{
@@ -16,12 +18,15 @@ use dt_common::{
     },
     monitor::monitor::Monitor,
     rdb_filter::RdbFilter,
+    row_filter::RowFilter,
     time_filter::TimeFilter,
 };
 use dt_common::{
     meta::{
-        avro::avro_converter::AvroConverter, mongo::mongo_cdc_source::MongoCdcSource,
-        pg::pg_meta_manager::PgMetaManager, redis::redis_statistic_type::RedisStatisticType,
+        avro::avro_converter::AvroConverter,
+        mongo::{mongo_cdc_source::MongoCdcSource, mongo_row_converter::MongoRowConverter},
+        pg::pg_meta_manager::PgMetaManager,
+        redis::redis_statistic_type::RedisStatisticType,
         syncer::Syncer,
     },
     utils::redis_util::RedisUtil,
@@ -36,18 +41,21 @@ use dt_connector::{
         mongo::{
             mongo_cdc_extractor::MongoCdcExtractor, mongo_check_extractor::MongoCheckExtractor,
             mongo_snapshot_extractor::MongoSnapshotExtractor,
+            mongo_struct_extractor::MongoStructExtractor,
         },
         mysql::{
             mysql_cdc_extractor::MysqlCdcExtractor, mysql_check_extractor::MysqlCheckExtractor,
             mysql_snapshot_extractor::MysqlSnapshotExtractor,
-            mysql_struct_extractor::MysqlStructExtractor,
+            mysql_struct_extractor::MysqlStructExtractor, online_ddl_util::OnlineDdlTracker,
         },
         pg::{
             pg_cdc_extractor::PgCdcExtractor, pg_check_extractor::PgCheckExtractor,
             pg_snapshot_extractor::PgSnapshotExtractor, pg_struct_extractor::PgStructExtractor,
         },
         redis::{
-            redis_client::RedisClient, redis_psync_extractor::RedisPsyncExtractor,
+            redis_check_extractor::RedisCheckExtractor, redis_client::RedisClient,
+            redis_cluster_psync_extractor::RedisClusterPsyncExtractor,
+            redis_psync_extractor::RedisPsyncExtractor,
             redis_reshard_extractor::RedisReshardExtractor,
             redis_scan_extractor::RedisScanExtractor,
             redis_snapshot_file_extractor::RedisSnapshotFileExtractor,
@@ -55,6 +63,7 @@ use dt_connector::{
         resumer::{cdc_resumer::CdcResumer, snapshot_resumer::SnapshotResumer},
     },
     rdb_router::RdbRouter,
+    shard_ddl_coordinator::ShardDdlCoordinator,
     Extractor,
 };
 
@@ -82,6 +91,7 @@ impl ExtractorUtil {
             monitor: ExtractorMonitor::new(monitor),
             data_marker,
             time_filter: TimeFilter::default(),
+            shard_ddl_coordinator: None,
         };
 
         let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&config.runtime.log_level);
@@ -160,6 +170,7 @@ impl ExtractorUtil {
                 heartbeat_tb,
                 start_time_utc,
                 end_time_utc,
+                online_ddl_policy,
             } => {
                 let conn_pool = TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let meta_manager = TaskUtil::create_mysql_meta_manager(
@@ -170,8 +181,24 @@ impl ExtractorUtil {
                 )
                 .await?;
                 base_extractor.time_filter = TimeFilter::new(&start_time_utc, &end_time_utc)?;
+                base_extractor.shard_ddl_coordinator = Self::create_shard_ddl_coordinator(
+                    config,
+                    &url,
+                    &base_extractor.router,
+                    &cdc_resumer,
+                    syncer.clone(),
+                )
+                .await?;
+                let online_ddl_tracker = Self::create_online_ddl_tracker(
+                    online_ddl_policy,
+                    &url,
+                    &cdc_resumer,
+                    syncer.clone(),
+                )
+                .await?;
                 let extractor = MysqlCdcExtractor {
                     meta_manager,
+                    row_filter: RowFilter::from_rdb_filter(&filter)?,
                     filter,
                     conn_pool,
                     url,
@@ -187,6 +214,7 @@ impl ExtractorUtil {
                     resumer: cdc_resumer,
                     gtid_enabled,
                     gtid_set,
+                    online_ddl_tracker,
                 };
                 Box::new(extractor)
             }
@@ -248,8 +276,17 @@ impl ExtractorUtil {
                 let conn_pool = TaskUtil::create_pg_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
                 base_extractor.time_filter = TimeFilter::new(&start_time_utc, &end_time_utc)?;
+                base_extractor.shard_ddl_coordinator = Self::create_shard_ddl_coordinator(
+                    config,
+                    &url,
+                    &base_extractor.router,
+                    &cdc_resumer,
+                    syncer.clone(),
+                )
+                .await?;
                 let extractor = PgCdcExtractor {
                     meta_manager,
+                    row_filter: RowFilter::from_rdb_filter(&filter)?,
                     filter,
                     url,
                     conn_pool,
@@ -273,12 +310,16 @@ impl ExtractorUtil {
                 app_name,
                 db,
                 tb,
+                parallel_size,
+                batch_size,
             } => {
                 let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
                 let extractor = MongoSnapshotExtractor {
                     resumer: snapshot_resumer,
                     db,
                     tb,
+                    parallel_size,
+                    batch_size,
                     mongo_client,
                     base_extractor,
                 };
@@ -296,6 +337,8 @@ impl ExtractorUtil {
             } => {
                 let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
                 let extractor = MongoCdcExtractor {
+                    url,
+                    row_filter: RowFilter::from_rdb_filter(&filter)?,
                     filter,
                     resume_token,
                     start_timestamp,
@@ -307,6 +350,7 @@ impl ExtractorUtil {
                     heartbeat_tb,
                     syncer,
                     resumer: cdc_resumer,
+                    full_doc_update: config.mongo_mapping.is_some(),
                 };
                 Box::new(extractor)
             }
@@ -351,7 +395,49 @@ impl ExtractorUtil {
                 Box::new(extractor)
             }
 
-            ExtractorConfig::RedisSnapshot { url, repl_port } => {
+            ExtractorConfig::MongoStruct { url, app_name, db } => {
+                let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
+                let (row_converter, sample_size) = match &config.mongo_mapping {
+                    Some(mongo_mapping_config) => (
+                        Some(MongoRowConverter::new(mongo_mapping_config)?),
+                        mongo_mapping_config.sample_size,
+                    ),
+                    None => (None, 0),
+                };
+                let extractor = MongoStructExtractor {
+                    mongo_client,
+                    db,
+                    filter,
+                    base_extractor,
+                    row_converter,
+                    sinker_db_type: config.sinker_basic.db_type.clone(),
+                    sample_size,
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::RedisSnapshot {
+                url,
+                repl_port,
+                is_cluster,
+            } => {
+                if is_cluster {
+                    let extractor = RedisClusterPsyncExtractor {
+                        url,
+                        syncer,
+                        repl_port,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::Snapshot,
+                        keepalive_interval_secs: 0,
+                        heartbeat_interval_secs: 0,
+                        heartbeat_key: String::new(),
+                        shard_tasks: HashMap::new(),
+                    };
+                    return Ok(Box::new(extractor));
+                }
+
                 let extractor = RedisPsyncExtractor {
                     conn: RedisClient::new(&url).await?,
                     syncer,
@@ -366,6 +452,7 @@ impl ExtractorUtil {
                     keepalive_interval_secs: 0,
                     heartbeat_interval_secs: 0,
                     heartbeat_key: String::new(),
+                    cluster_shard: None,
                 };
                 Box::new(extractor)
             }
@@ -385,7 +472,12 @@ impl ExtractorUtil {
                 statistic_type,
             } => {
                 let conn = RedisUtil::create_redis_conn(&url).await?;
-                let statistic_type = RedisStatisticType::from_str(&statistic_type)?;
+                // without statistic_type, scan extractor dumps the whole entry of each key
+                let statistic_type = if statistic_type.is_empty() {
+                    None
+                } else {
+                    Some(RedisStatisticType::from_str(&statistic_type)?)
+                };
                 let extractor = RedisScanExtractor {
                     conn,
                     statistic_type,
@@ -405,7 +497,26 @@ impl ExtractorUtil {
                 keepalive_interval_secs,
                 heartbeat_interval_secs,
                 heartbeat_key,
+                is_cluster,
             } => {
+                if is_cluster {
+                    // positions of all shards are resumed from RedisCluster position
+                    let extractor = RedisClusterPsyncExtractor {
+                        url,
+                        syncer,
+                        repl_port,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::Cdc,
+                        keepalive_interval_secs,
+                        heartbeat_interval_secs,
+                        heartbeat_key,
+                        shard_tasks: HashMap::new(),
+                    };
+                    return Ok(Box::new(extractor));
+                }
+
                 let extractor = RedisPsyncExtractor {
                     conn: RedisClient::new(&url).await?,
                     repl_id,
@@ -420,6 +531,7 @@ impl ExtractorUtil {
                     resumer: cdc_resumer,
                     base_extractor,
                     extract_type: ExtractType::Cdc,
+                    cluster_shard: None,
                 };
                 Box::new(extractor)
             }
@@ -431,7 +543,25 @@ impl ExtractorUtil {
                 keepalive_interval_secs,
                 heartbeat_interval_secs,
                 heartbeat_key,
+                is_cluster,
             } => {
+                if is_cluster {
+                    let extractor = RedisClusterPsyncExtractor {
+                        url,
+                        syncer,
+                        repl_port,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::SnapshotAndCdc,
+                        keepalive_interval_secs,
+                        heartbeat_interval_secs,
+                        heartbeat_key,
+                        shard_tasks: HashMap::new(),
+                    };
+                    return Ok(Box::new(extractor));
+                }
+
                 let extractor = RedisPsyncExtractor {
                     conn: RedisClient::new(&url).await?,
                     syncer,
@@ -446,6 +576,23 @@ impl ExtractorUtil {
                     keepalive_interval_secs,
                     heartbeat_interval_secs,
                     heartbeat_key,
+                    cluster_shard: None,
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::RedisCheck {
+                url,
+                check_log_dir,
+                batch_size,
+            } => {
+                let conn = RedisUtil::create_redis_conn(&url).await?;
+                let extractor = RedisCheckExtractor {
+                    conn,
+                    filter,
+                    check_log_dir,
+                    batch_size,
+                    base_extractor,
                 };
                 Box::new(extractor)
             }
@@ -525,4 +672,60 @@ impl ExtractorUtil {
         };
         Ok(meta_manager)
     }
+
+    async fn create_online_ddl_tracker(
+        policy: OnlineDdlPolicyEnum,
+        url: &str,
+        cdc_resumer: &CdcResumer,
+        syncer: Arc<Mutex<Syncer>>,
+    ) -> anyhow::Result<OnlineDdlTracker> {
+        // the state is logged with every checkpoint until it changes
+        syncer.lock().unwrap().online_ddl_state = cdc_resumer.online_ddl_state.clone();
+        // without persisted state, shadow tables of online ddls in progress are found in source
+        let mut src_tbs = Vec::new();
+        if policy != OnlineDdlPolicyEnum::None && cdc_resumer.online_ddl_state.is_empty() {
+            for schema in TaskUtil::list_schemas(url, &DbType::Mysql).await? {
+                for tb in TaskUtil::list_tbs(url, &schema, &DbType::Mysql).await? {
+                    src_tbs.push((schema.clone(), tb));
+                }
+            }
+        }
+        OnlineDdlTracker::new(policy, &cdc_resumer.online_ddl_state, &src_tbs)
+    }
+
+    async fn create_shard_ddl_coordinator(
+        config: &TaskConfig,
+        url: &str,
+        router: &RdbRouter,
+        cdc_resumer: &CdcResumer,
+        syncer: Arc<Mutex<Syncer>>,
+    ) -> anyhow::Result<Option<ShardDdlCoordinator>> {
+        let RouterConfig::Rdb { shard_ddl_mode, .. } = &config.router;
+        if *shard_ddl_mode == ShardDdlModeEnum::None {
+            return Ok(None);
+        }
+
+        // shards of each target are the source tables matched by filter
+        let db_type = &config.extractor_basic.db_type;
+        let mut filter = RdbFilter::from_config(&config.filter, db_type)?;
+        let mut src_tbs = Vec::new();
+        for schema in TaskUtil::list_schemas(url, db_type).await? {
+            if filter.filter_schema(&schema) {
+                continue;
+            }
+            for tb in TaskUtil::list_tbs(url, &schema, db_type).await? {
+                if !filter.filter_tb(&schema, &tb) {
+                    src_tbs.push((schema.clone(), tb));
+                }
+            }
+        }
+
+        ShardDdlCoordinator::new(
+            &config.router,
+            router,
+            &src_tbs,
+            &cdc_resumer.shard_ddl_state,
+            syncer,
+        )
+    }
 }
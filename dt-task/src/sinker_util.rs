@@ -3,13 +3,14 @@ use std::{
     sync::{Arc, Mutex, RwLock},
 };
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use dt_common::{
     config::{
         config_enums::DbType, extractor_config::ExtractorConfig, sinker_config::SinkerConfig,
         task_config::TaskConfig,
     },
-    meta::redis::command::key_parser::KeyParser,
+    error::Error,
+    meta::{rdb_meta_manager::RdbMetaManager, redis::command::key_parser::KeyParser},
     monitor::monitor::Monitor,
     rdb_filter::RdbFilter,
 };
@@ -18,7 +19,11 @@ use dt_common::{
         avro::avro_converter::AvroConverter,
         mysql::mysql_meta_manager::MysqlMetaManager,
         pg::pg_meta_manager::PgMetaManager,
-        redis::{redis_statistic_type::RedisStatisticType, redis_write_method::RedisWriteMethod},
+        redis::{
+            redis_memory_analyzer::{MemoryReportFormat, RedisMemoryAnalyzer},
+            redis_statistic_type::RedisStatisticType,
+            redis_write_method::RedisWriteMethod,
+        },
     },
     utils::redis_util::RedisUtil,
 };
@@ -37,13 +42,20 @@ use dt_connector::{
             orc_sequencer::OrcSequencer,
         },
         kafka::kafka_sinker::KafkaSinker,
-        mongo::{mongo_checker::MongoChecker, mongo_sinker::MongoSinker},
+        mongo::{
+            mongo_checker::MongoChecker, mongo_sinker::MongoSinker,
+            mongo_struct_sinker::MongoStructSinker,
+        },
         mysql::{
             mysql_checker::MysqlChecker, mysql_sinker::MysqlSinker,
             mysql_struct_sinker::MysqlStructSinker,
         },
         pg::{pg_checker::PgChecker, pg_sinker::PgSinker, pg_struct_sinker::PgStructSinker},
-        redis::{redis_sinker::RedisSinker, redis_statistic_sinker::RedisStatisticSinker},
+        redis::{
+            rdb_writer::RdbWriter, redis_checker::RedisChecker, redis_sinker::RedisSinker,
+            redis_snapshot_file_sinker::RedisSnapshotFileSinker,
+            redis_statistic_sinker::RedisStatisticSinker,
+        },
         sql_sinker::SqlSinker,
         starrocks::{
             starrocks_sinker::StarRocksSinker, starrocks_struct_sinker::StarrocksStructSinker,
@@ -88,6 +100,7 @@ impl SinkerUtil {
                 url,
                 batch_size,
                 replace,
+                partial_upsert,
             } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Mysql)?;
                 let conn_pool =
@@ -107,6 +120,7 @@ impl SinkerUtil {
                         monitor: monitor.clone(),
                         data_marker: data_marker.clone(),
                         replace,
+                        partial_upsert,
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -146,6 +160,8 @@ impl SinkerUtil {
                 url,
                 batch_size,
                 replace,
+                partial_upsert,
+                ddl_conflict_policy,
             } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Pg)?;
                 let conn_pool =
@@ -162,6 +178,8 @@ impl SinkerUtil {
                         monitor: monitor.clone(),
                         data_marker: data_marker.clone(),
                         replace,
+                        partial_upsert,
+                        ddl_conflict_policy: ddl_conflict_policy.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -200,6 +218,7 @@ impl SinkerUtil {
                 url,
                 app_name,
                 batch_size,
+                ddl_conflict_policy,
             } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Mongo)?;
                 for _ in 0..parallel_size {
@@ -209,6 +228,7 @@ impl SinkerUtil {
                         router: router.clone(),
                         mongo_client,
                         monitor: monitor.clone(),
+                        ddl_conflict_policy: ddl_conflict_policy.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -222,6 +242,7 @@ impl SinkerUtil {
             } => {
                 let reverse_router =
                     RdbRouter::from_config(&task_config.router, &DbType::Mongo)?.reverse();
+                let filter = RdbFilter::from_config(&task_config.filter, &DbType::Mongo)?;
                 for _ in 0..parallel_size {
                     let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
                     let sinker = MongoChecker {
@@ -229,6 +250,7 @@ impl SinkerUtil {
                         reverse_router: reverse_router.clone(),
                         mongo_client,
                         monitor: monitor.clone(),
+                        filter: filter.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -313,11 +335,28 @@ impl SinkerUtil {
                 sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
             }
 
+            SinkerConfig::MongoStruct {
+                url,
+                app_name,
+                conflict_policy,
+            } => {
+                let filter = RdbFilter::from_config(&task_config.filter, &DbType::Mongo)?;
+                let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
+                let sinker = MongoStructSinker {
+                    mongo_client,
+                    conflict_policy: conflict_policy.clone(),
+                    filter,
+                };
+                sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+            }
+
             SinkerConfig::Redis {
                 url,
                 batch_size,
                 method,
                 is_cluster,
+                chunk_bytes,
+                chunk_size,
             } => {
                 // redis sinker may need meta data from RDB extractor
                 let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
@@ -349,6 +388,8 @@ impl SinkerUtil {
                             monitor: monitor.clone(),
                             data_marker: data_marker.clone(),
                             key_parser: KeyParser::new(),
+                            chunk_bytes,
+                            chunk_size,
                         };
                         sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                     }
@@ -366,24 +407,112 @@ impl SinkerUtil {
                             monitor: monitor.clone(),
                             data_marker: data_marker.clone(),
                             key_parser: KeyParser::new(),
+                            chunk_bytes,
+                            chunk_size,
+                        };
+                        sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                    }
+                }
+            }
+
+            SinkerConfig::RedisCheck {
+                url,
+                batch_size,
+                is_cluster,
+                ttl_tolerance_secs,
+                ..
+            } => {
+                let mut conn = RedisUtil::create_redis_conn(&url).await?;
+                if is_cluster {
+                    let url_info = Url::parse(&url)?;
+                    let username = url_info.username();
+                    let password = url_info.password().unwrap_or("").to_string();
+
+                    let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
+                    for node in nodes.iter() {
+                        if !node.is_master {
+                            continue;
+                        }
+
+                        let new_url = format!("redis://{}:{}@{}", username, password, node.address);
+                        let conn = RedisUtil::create_redis_conn(&new_url).await?;
+                        let sinker = RedisChecker {
+                            cluster_node: Some(node.clone()),
+                            conn,
+                            batch_size,
+                            now_db_id: -1,
+                            ttl_tolerance_secs,
+                            monitor: monitor.clone(),
+                        };
+                        sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                    }
+                } else {
+                    for _ in 0..parallel_size {
+                        let conn = RedisUtil::create_redis_conn(&url).await?;
+                        let sinker = RedisChecker {
+                            cluster_node: None,
+                            conn,
+                            batch_size,
+                            now_db_id: -1,
+                            ttl_tolerance_secs,
+                            monitor: monitor.clone(),
                         };
                         sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                     }
                 }
             }
 
+            SinkerConfig::RedisSnapshotFile {
+                file_path,
+                rdb_version,
+            } => {
+                // all entries are written into one file
+                let sinker = RedisSnapshotFileSinker {
+                    file_path,
+                    rdb_writer: RdbWriter::new(rdb_version)?,
+                    file: None,
+                    skipped_count: 0,
+                    monitor: monitor.clone(),
+                };
+                sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+            }
+
             SinkerConfig::RedisStatistic {
                 statistic_type,
                 data_size_threshold,
                 freq_threshold,
+                key_prefix_delimiters,
+                key_prefix_depth,
+                top_n,
+                report_format,
                 ..
             } => {
                 let statistic_type = RedisStatisticType::from_str(&statistic_type)?;
-                for _ in 0..parallel_size {
+                let report_format = if report_format.is_empty() {
+                    MemoryReportFormat::default()
+                } else {
+                    MemoryReportFormat::from_str(&report_format)?
+                };
+                // memory analysis aggregates all keys in one sinker
+                let sinker_count = match statistic_type {
+                    RedisStatisticType::Memory => 1,
+                    _ => parallel_size,
+                };
+                for _ in 0..sinker_count {
+                    let memory_analyzer = match statistic_type {
+                        RedisStatisticType::Memory => Some(RedisMemoryAnalyzer::new(
+                            &key_prefix_delimiters,
+                            key_prefix_depth,
+                            top_n,
+                        )),
+                        _ => None,
+                    };
                     let sinker = RedisStatisticSinker {
                         statistic_type: statistic_type.clone(),
                         data_size_threshold,
                         freq_threshold,
+                        memory_analyzer,
+                        report_format: report_format.clone(),
                         monitor: monitor.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
@@ -394,13 +523,38 @@ impl SinkerUtil {
                 url,
                 batch_size,
                 stream_load_url,
+                auto_evolve,
+                ddl_conflict_policy,
                 ..
             }
             | SinkerConfig::Doris {
                 url,
                 batch_size,
                 stream_load_url,
+                auto_evolve,
+                ddl_conflict_policy,
             } => {
+                let struct_sinker = if auto_evolve {
+                    let conn_pool =
+                        TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log).await?;
+                    let db_type = &task_config.extractor_basic.db_type;
+                    let filter = RdbFilter::from_config(&task_config.filter, db_type)?;
+                    let router = RdbRouter::from_config(&task_config.router, db_type)?;
+                    let extractor_meta_manager =
+                        Self::get_auto_evolve_meta_manager(task_config).await?;
+                    Some(StarrocksStructSinker {
+                        db_type: task_config.sinker_basic.db_type.clone(),
+                        conn_pool,
+                        conflict_policy: ddl_conflict_policy,
+                        filter,
+                        router,
+                        extractor_meta_manager,
+                        backend_count: 0,
+                    })
+                } else {
+                    None
+                };
+
                 for _ in 0..parallel_size {
                     let url_info = Url::parse(&stream_load_url)?;
                     let host = url_info.host_str().unwrap().to_string();
@@ -433,6 +587,7 @@ impl SinkerUtil {
                         monitor: monitor.clone(),
                         sync_timestamp: Utc::now().timestamp_millis(),
                         hard_delete: false,
+                        struct_sinker: struct_sinker.clone(),
                     };
                     if let SinkerConfig::StarRocks { hard_delete, .. } = task_config.sinker {
                         sinker.hard_delete = hard_delete;
@@ -453,9 +608,10 @@ impl SinkerUtil {
                 let conn_pool = TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let filter = RdbFilter::from_config(&task_config.filter, &DbType::Mysql)?;
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Mysql)?;
+                // no meta manager for mongo sources, tables are created by the sampled column types
                 let extractor_meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
                     .await?
-                    .unwrap();
+                    .unwrap_or_default();
                 let sinker = StarrocksStructSinker {
                     db_type: task_config.sinker_basic.db_type.clone(),
                     conn_pool,
@@ -468,7 +624,37 @@ impl SinkerUtil {
                 sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
             }
 
-            SinkerConfig::ClickHouse { url, batch_size } => {
+            SinkerConfig::ClickHouse {
+                url,
+                batch_size,
+                auto_evolve,
+                ddl_conflict_policy,
+            } => {
+                let struct_sinker = if auto_evolve {
+                    let url_info = Url::parse(&url)?;
+                    let host = url_info.host_str().unwrap().to_string();
+                    let port = format!("{}", url_info.port().unwrap());
+                    let client = clickhouse::Client::default()
+                        .with_url(format!("http://{}:{}", host, port))
+                        .with_user(url_info.username())
+                        .with_password(url_info.password().unwrap_or(""));
+                    let db_type = &task_config.extractor_basic.db_type;
+                    let filter = RdbFilter::from_config(&task_config.filter, db_type)?;
+                    let router = RdbRouter::from_config(&task_config.router, db_type)?;
+                    let extractor_meta_manager =
+                        Self::get_auto_evolve_meta_manager(task_config).await?;
+                    Some(ClickhouseStructSinker {
+                        client,
+                        conflict_policy: ddl_conflict_policy,
+                        engine: String::new(),
+                        filter,
+                        router,
+                        extractor_meta_manager,
+                    })
+                } else {
+                    None
+                };
+
                 for _ in 0..parallel_size {
                     let url_info = Url::parse(&url)?;
                     let host = url_info.host_str().unwrap().to_string();
@@ -489,6 +675,7 @@ impl SinkerUtil {
                         batch_size,
                         monitor: monitor.clone(),
                         sync_timestamp: Utc::now().timestamp_millis(),
+                        struct_sinker: struct_sinker.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -508,9 +695,10 @@ impl SinkerUtil {
                     .with_password(url_info.password().unwrap_or(""));
                 let filter = RdbFilter::from_config(&task_config.filter, &DbType::Mysql)?;
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Mysql)?;
+                // no meta manager for mongo sources, tables are created by the sampled column types
                 let extractor_meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
                     .await?
-                    .unwrap();
+                    .unwrap_or_default();
                 let sinker = ClickhouseStructSinker {
                     client,
                     conflict_policy,
@@ -693,4 +881,16 @@ impl SinkerUtil {
         };
         Ok(sub_sinkers)
     }
+
+    async fn get_auto_evolve_meta_manager(
+        task_config: &TaskConfig,
+    ) -> anyhow::Result<RdbMetaManager> {
+        match ExtractorUtil::get_extractor_meta_manager(task_config).await? {
+            Some(meta_manager) => Ok(meta_manager),
+            None => bail! {Error::ConfigError(format!(
+                "auto_evolve is not supported for source: {}",
+                task_config.extractor_basic.db_type
+            ))},
+        }
+    }
 }
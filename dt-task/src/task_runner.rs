@@ -21,7 +21,11 @@ use dt_common::{
     },
     error::Error,
     log_finished, log_info,
-    meta::{avro::avro_converter::AvroConverter, dt_queue::DtQueue},
+    meta::{
+        avro::avro_converter::AvroConverter, dt_queue::DtQueue,
+        mongo::mongo_row_converter::MongoRowConverter,
+        redis::command::row_converter::RedisRowConverter,
+    },
     monitor::{group_monitor::GroupMonitor, monitor::Monitor, FlushableMonitor},
     rdb_filter::RdbFilter,
     utils::{sql_util::SqlUtil, time_util::TimeUtil},
@@ -38,7 +42,8 @@ use dt_connector::{
 };
 use dt_pipeline::{
     base_pipeline::BasePipeline, http_server_pipeline::HttpServerPipeline,
-    lua_processor::LuaProcessor, Pipeline,
+    lua_processor::LuaProcessor, transform_processor::TransformProcessor,
+    wasm_processor::WasmProcessor, Pipeline,
 };
 
 use log4rs::config::RawConfig;
@@ -90,12 +95,22 @@ impl TaskRunner {
 
         let db_type = &self.config.extractor_basic.db_type;
         let router = RdbRouter::from_config(&self.config.router, db_type)?;
+        // checkers route rows back to source tables by the reversed router
+        if matches!(
+            self.config.sinker,
+            SinkerConfig::MysqlCheck { .. }
+                | SinkerConfig::PgCheck { .. }
+                | SinkerConfig::MongoCheck { .. }
+        ) {
+            router.check_reversible()?;
+        }
         let snapshot_resumer = SnapshotResumer::from_config(&self.config)?;
         let cdc_resumer = CdcResumer::from_config(&self.config)?;
 
         match &self.config.extractor {
             ExtractorConfig::MysqlStruct { url, .. }
             | ExtractorConfig::PgStruct { url, .. }
+            | ExtractorConfig::MongoStruct { url, .. }
             | ExtractorConfig::MysqlSnapshot { url, .. }
             | ExtractorConfig::PgSnapshot { url, .. }
             | ExtractorConfig::MongoSnapshot { url, .. }
@@ -150,6 +165,14 @@ impl TaskRunner {
                     schema: schema.clone(),
                 }),
 
+                ExtractorConfig::MongoStruct { url, app_name, .. } => {
+                    Some(ExtractorConfig::MongoStruct {
+                        url: url.clone(),
+                        app_name: app_name.clone(),
+                        db: schema.clone(),
+                    })
+                }
+
                 _ => None,
             };
 
@@ -286,14 +309,20 @@ impl TaskRunner {
                 batch_size: *batch_size,
             },
 
-            ExtractorConfig::MongoSnapshot { url, app_name, .. } => {
-                ExtractorConfig::MongoSnapshot {
-                    url: url.clone(),
-                    app_name: app_name.clone(),
-                    db: schema.into(),
-                    tb: tb.into(),
-                }
-            }
+            ExtractorConfig::MongoSnapshot {
+                url,
+                app_name,
+                parallel_size,
+                batch_size,
+                ..
+            } => ExtractorConfig::MongoSnapshot {
+                url: url.clone(),
+                app_name: app_name.clone(),
+                db: schema.into(),
+                tb: tb.into(),
+                parallel_size: *parallel_size,
+                batch_size: *batch_size,
+            },
 
             ExtractorConfig::FoxlakeS3 {
                 url,
@@ -351,6 +380,7 @@ impl TaskRunner {
         let syncer = Arc::new(Mutex::new(Syncer {
             received_position: Position::None,
             committed_position: Position::None,
+            ..Default::default()
         }));
 
         let (extractor_data_marker, sinker_data_marker) = if let Some(data_marker_config) =
@@ -530,13 +560,62 @@ impl TaskRunner {
                     None
                 };
 
-                let lua_processor =
-                    self.config
-                        .processor
-                        .as_ref()
-                        .map(|processor_config| LuaProcessor {
-                            lua_code: processor_config.lua_code.clone(),
-                        });
+                let lua_processor = match &self.config.processor {
+                    Some(processor_config) if !processor_config.lua_code.is_empty() => {
+                        Some(LuaProcessor::new(
+                            &processor_config.lua_code,
+                            processor_config.lua_mode.clone(),
+                            &processor_config.lua_lookup_files,
+                        )?)
+                    }
+                    _ => None,
+                };
+
+                let wasm_processor = match &self.config.processor {
+                    Some(processor_config) if !processor_config.wasm_module_file.is_empty() => {
+                        Some(WasmProcessor::new(
+                            &processor_config.wasm_module_file,
+                            processor_config.wasm_fuel,
+                            processor_config.wasm_max_memory_mb,
+                        )?)
+                    }
+                    _ => None,
+                };
+
+                let transform_processor = match &self.config.transform {
+                    Some(transform_config) => Some(TransformProcessor::new(
+                        transform_config,
+                        &self.config.extractor_basic.db_type,
+                    )?),
+                    None => None,
+                };
+
+                let redis_row_converter = match &self.config.redis_mapping {
+                    Some(redis_mapping_config) => {
+                        Some(RedisRowConverter::new(redis_mapping_config)?)
+                    }
+                    None => None,
+                };
+
+                let mongo_row_converter = match &self.config.mongo_mapping {
+                    Some(mongo_mapping_config) => {
+                        let converter = MongoRowConverter::new(mongo_mapping_config)?;
+                        // rows of child tables are deleted by _id in cdc, which is only supported by rdb sinkers
+                        if converter.has_child_tbs()
+                            && matches!(self.config.extractor, ExtractorConfig::MongoCdc { .. })
+                            && !matches!(
+                                self.config.sinker,
+                                SinkerConfig::Mysql { .. } | SinkerConfig::Pg { .. }
+                            )
+                        {
+                            bail! {Error::ConfigError(
+                                "child_tb_map in [mongo_mapping] is only supported for mongo cdc tasks to mysql / pg".into()
+                            )}
+                        }
+                        Some(converter)
+                    }
+                    None => None,
+                };
 
                 let parallelizer = ParallelizerUtil::create_parallelizer(
                     &self.config,
@@ -556,7 +635,11 @@ impl TaskRunner {
                     syncer,
                     monitor,
                     data_marker,
+                    transform_processor,
                     lua_processor,
+                    wasm_processor,
+                    redis_row_converter,
+                    mongo_row_converter,
                 };
                 Ok(Box::new(pipeline))
             }
@@ -591,7 +674,8 @@ impl TaskRunner {
 
         match &self.config.sinker {
             SinkerConfig::MysqlCheck { check_log_dir, .. }
-            | SinkerConfig::PgCheck { check_log_dir, .. } => {
+            | SinkerConfig::PgCheck { check_log_dir, .. }
+            | SinkerConfig::RedisCheck { check_log_dir, .. } => {
                 if !check_log_dir.is_empty() {
                     config_str = config_str.replace(CHECK_LOG_DIR_PLACEHODLER, check_log_dir);
                 }
@@ -1,5 +1,6 @@
 mod log_reader;
 mod mongo_to_mongo;
+mod mongo_to_mysql;
 mod mysql_to_clickhouse;
 mod mysql_to_doris;
 mod mysql_to_foxlake;
@@ -7,6 +8,7 @@ mod mysql_to_kafka_to_mysql;
 mod mysql_to_mysql;
 mod mysql_to_mysql_case_sensitive;
 mod mysql_to_mysql_lua;
+mod mysql_to_pg;
 mod mysql_to_redis;
 mod mysql_to_starrocks;
 mod mysql_to_tidb;
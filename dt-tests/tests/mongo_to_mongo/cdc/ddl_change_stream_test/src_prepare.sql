@@ -0,0 +1,13 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
+db.createCollection("tb_2");
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "name": 1 }, "name": "name_1" }] });
+
+use test_db_2
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
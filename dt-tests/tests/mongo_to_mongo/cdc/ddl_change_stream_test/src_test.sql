@@ -0,0 +1,13 @@
+use test_db_1
+
+db.tb_1.insertOne({ "name": "a", "age": "1" });
+db.runCommand({ "renameCollection": "test_db_1.tb_1", "to": "test_db_1.tb_1_new" });
+db.tb_1_new.insertOne({ "name": "b", "age": "2" });
+
+db.tb_2.insertOne({ "name": "a", "age": "1" });
+db.tb_2.drop();
+
+use test_db_2
+
+db.tb_1.insertOne({ "name": "a", "age": "1" });
+db.dropDatabase();
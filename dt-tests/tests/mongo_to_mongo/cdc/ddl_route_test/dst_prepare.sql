@@ -0,0 +1,5 @@
+use dst_test_db_1
+
+db.dropDatabase();
+
+db.createCollection("dst_tb_1");
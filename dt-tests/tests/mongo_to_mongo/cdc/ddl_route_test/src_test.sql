@@ -0,0 +1,11 @@
+use test_db_1
+
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "name": 1 }, "name": "name_1" }] });
+db.tb_1.insertOne({ "name": "a", "age": "1" });
+
+db.runCommand({ "create": "tb_2", "capped": true, "size": 1048576 });
+db.tb_2.insertOne({ "name": "a", "age": "1" });
+
+db.runCommand({ "create": "tb_3" });
+db.tb_3.insertOne({ "name": "a", "age": "1" });
+db.runCommand({ "renameCollection": "test_db_1.tb_3", "to": "test_db_1.tb_3_new" });
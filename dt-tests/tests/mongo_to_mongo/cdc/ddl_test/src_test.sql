@@ -0,0 +1,32 @@
+use test_db_1
+
+-- create collection with options
+db.runCommand({ "create": "tb_2", "capped": true, "size": 1048576, "max": 1000 });
+db.tb_2.insertOne({ "name": "a", "age": "1" });
+
+-- create index on empty collection
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "name": 1 }, "name": "name_unique", "unique": true }] });
+db.tb_1.insertOne({ "name": "a", "age": "1" });
+db.tb_1.insertOne({ "name": "b", "age": "2" });
+
+-- create index on non-empty collection, two-phase index build
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "age": -1 }, "name": "age_partial", "partialFilterExpression": { "age": { "$gt": "1" } } }] });
+
+-- rename collection
+db.runCommand({ "create": "tb_3" });
+db.tb_3.insertOne({ "name": "a", "age": "1" });
+db.runCommand({ "renameCollection": "test_db_1.tb_3", "to": "test_db_1.tb_3_new" });
+
+-- drop index
+db.runCommand({ "createIndexes": "tb_3_new", "indexes": [{ "key": { "age": 1 }, "name": "age_1" }, { "key": { "name": 1 }, "name": "name_1" }] });
+db.runCommand({ "dropIndexes": "tb_3_new", "index": "age_1" });
+
+-- drop collection
+db.runCommand({ "create": "tb_4" });
+db.tb_4.insertOne({ "name": "a", "age": "1" });
+db.tb_4.drop();
+
+use test_db_2
+
+db.tb_1.insertOne({ "name": "a", "age": "1" });
+db.dropDatabase();
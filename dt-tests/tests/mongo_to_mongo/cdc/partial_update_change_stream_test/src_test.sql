@@ -0,0 +1,29 @@
+use test_db_1
+
+db.tb_1.insertOne({ "_id": 1, "name": "a", "info": { "city": "x", "zip": "1", "geo": { "lat": 1, "lng": 2 } }, "tags": ["a", "b", "c", "d", "e"] });
+db.tb_1.insertOne({ "_id": 2, "name": "b", "info": { "city": "x", "zip": "2" }, "tags": ["a", "b", "c"] });
+db.tb_1.insertOne({ "_id": 3, "name": "c", "age": 1 });
+
+-- multiple fields
+db.tb_1.updateOne({ "_id": 1 }, { "$set": { "name": "a_1", "age": 10 } });
+
+-- nested fields
+db.tb_1.updateOne({ "_id": 1 }, { "$set": { "info.city": "y", "info.geo.lat": 3 } });
+db.tb_1.updateOne({ "_id": 1 }, { "$unset": { "info.zip": "" } });
+
+-- array elements
+db.tb_1.updateOne({ "_id": 1 }, { "$set": { "tags.1": "b_1" } });
+db.tb_1.updateOne({ "_id": 1 }, { "$push": { "tags": "f" } });
+
+-- array truncated
+db.tb_1.updateOne({ "_id": 1 }, { "$pop": { "tags": 1 } });
+db.tb_1.updateOne({ "_id": 2 }, { "$push": { "tags": { "$each": [], "$slice": 1 } } });
+
+-- array truncated and updated, fallback to replacement
+db.tb_1.updateOne({ "_id": 1 }, { "$pull": { "tags": "b_1" } });
+
+-- set and unset
+db.tb_1.updateOne({ "_id": 2 }, { "$set": { "info.city": "z" }, "$unset": { "info.zip": "" } });
+
+-- replacement
+db.tb_1.replaceOne({ "_id": 3 }, { "name": "c_1", "info": { "city": "x" } });
@@ -0,0 +1,6 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "_id": "hashed" }, "name": "_id_hashed" }] });
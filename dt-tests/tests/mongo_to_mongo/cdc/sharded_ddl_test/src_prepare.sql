@@ -0,0 +1,6 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.runCommand({ "enableSharding": "test_db_1" });
+db.runCommand({ "shardCollection": "test_db_1.tb_1", "key": { "_id": "hashed" } });
@@ -0,0 +1,22 @@
+use test_db_1
+
+-- chunks of tb_1 are distributed to all shards, ddls of tb_1 are written to the oplog of each shard
+db.tb_1.insertOne({ "_id": 1, "name": "a", "age": "1" });
+db.tb_1.insertOne({ "_id": 2, "name": "b", "age": "2" });
+db.tb_1.insertOne({ "_id": 3, "name": "c", "age": "3" });
+db.tb_1.insertOne({ "_id": 4, "name": "d", "age": "4" });
+
+-- create index
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "name": 1 }, "name": "name_1" }, { "key": { "age": 1 }, "name": "age_1" }] });
+db.tb_1.insertOne({ "_id": 5, "name": "e", "age": "5" });
+
+-- drop index
+db.runCommand({ "dropIndexes": "tb_1", "index": "age_1" });
+db.tb_1.insertOne({ "_id": 6, "name": "f", "age": "6" });
+
+-- create and drop an unsharded collection, which only exists in the primary shard
+db.runCommand({ "create": "tb_2" });
+db.tb_2.insertOne({ "name": "a", "age": "1" });
+db.runCommand({ "create": "tb_3" });
+db.tb_3.insertOne({ "name": "a", "age": "1" });
+db.tb_3.drop();
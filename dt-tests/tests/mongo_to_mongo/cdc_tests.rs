@@ -16,6 +16,23 @@ mod test {
         TestBase::run_mongo_cdc_test("mongo_to_mongo/cdc/change_stream_test", 3000, 3000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_partial_update_test() {
+        TestBase::run_mongo_cdc_test("mongo_to_mongo/cdc/partial_update_test", 3000, 3000).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_partial_update_change_stream_test() {
+        TestBase::run_mongo_cdc_test(
+            "mongo_to_mongo/cdc/partial_update_change_stream_test",
+            3000,
+            3000,
+        )
+        .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_resume_test() {
@@ -45,4 +62,29 @@ mod test {
     async fn cdc_heartbeat_test() {
         TestBase::run_mongo_heartbeat_test("mongo_to_mongo/cdc/heartbeat_test", 3000, 3000).await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_ddl_test() {
+        TestBase::run_mongo_ddl_test("mongo_to_mongo/cdc/ddl_test", 3000, 5000).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_ddl_change_stream_test() {
+        TestBase::run_mongo_ddl_test("mongo_to_mongo/cdc/ddl_change_stream_test", 3000, 5000).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_ddl_route_test() {
+        TestBase::run_mongo_ddl_test("mongo_to_mongo/cdc/ddl_route_test", 3000, 5000).await;
+    }
+
+    /// requires a sharded cluster, mongo_sharded_extractor_url should be a mongos
+    #[tokio::test]
+    #[serial]
+    async fn cdc_sharded_ddl_test() {
+        TestBase::run_mongo_ddl_test("mongo_to_mongo/cdc/sharded_ddl_test", 3000, 15000).await;
+    }
 }
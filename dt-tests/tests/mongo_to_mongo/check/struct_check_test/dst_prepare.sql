@@ -0,0 +1,8 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "email": 1 }, "name": "email_unique", "unique": true }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "name": 1 }, "name": "name_1", "sparse": true }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "extra": 1 }, "name": "extra_1" }] });
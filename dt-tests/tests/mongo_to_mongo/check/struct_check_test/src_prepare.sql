@@ -0,0 +1,9 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "email": 1 }, "name": "email_unique", "unique": true }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "age": 1 }, "name": "age_1" }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "name": 1 }, "name": "name_1" }] });
+db.runCommand({ "create": "tb_2", "capped": true, "size": 1048576 });
@@ -15,4 +15,10 @@ mod test {
     async fn check_route_test() {
         TestBase::run_mongo_check_test("mongo_to_mongo/check/route_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn check_struct_test() {
+        TestBase::run_mongo_check_test("mongo_to_mongo/check/struct_check_test").await;
+    }
 }
@@ -4,3 +4,4 @@ mod precheck_tests;
 mod review_tests;
 mod revise_tests;
 mod snapshot_tests;
+mod struct_tests;
@@ -0,0 +1,67 @@
+use test_db_1
+
+-- _id of mixed types
+db.tb_1.insertOne({ "_id" : "1", "name" : "a", "age" : "1" });
+db.tb_1.insertOne({ "_id" : "2", "name" : "a", "age" : "2" });
+db.tb_1.insertOne({ "_id" : "3", "name" : "a", "age" : "3" });
+db.tb_1.insertOne({ "_id" : "4", "name" : "a", "age" : "4" });
+db.tb_1.insertOne({ "_id" : "5", "name" : "a", "age" : "5" });
+db.tb_1.insertOne({ "_id" : "6", "name" : "a", "age" : "6" });
+db.tb_1.insertOne({ "_id" : "7", "name" : "a", "age" : "7" });
+db.tb_1.insertOne({ "_id" : "8", "name" : "a", "age" : "8" });
+db.tb_1.insertOne({ "_id" : "9", "name" : "a", "age" : "9" });
+db.tb_1.insertOne({ "_id" : "10", "name" : "a", "age" : "10" });
+db.tb_1.insertOne({ "_id" : 1, "name" : "b", "age" : "1" });
+db.tb_1.insertOne({ "_id" : 2, "name" : "b", "age" : "2" });
+db.tb_1.insertOne({ "_id" : 3, "name" : "b", "age" : "3" });
+db.tb_1.insertOne({ "_id" : 4, "name" : "b", "age" : "4" });
+db.tb_1.insertOne({ "_id" : 5, "name" : "b", "age" : "5" });
+db.tb_1.insertOne({ "_id" : 6, "name" : "b", "age" : "6" });
+db.tb_1.insertOne({ "_id" : 7, "name" : "b", "age" : "7" });
+db.tb_1.insertOne({ "_id" : 8, "name" : "b", "age" : "8" });
+db.tb_1.insertOne({ "_id" : 9, "name" : "b", "age" : "9" });
+db.tb_1.insertOne({ "_id" : 10, "name" : "b", "age" : "10" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1001" }, "name" : "c", "age" : "1" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1002" }, "name" : "c", "age" : "2" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1003" }, "name" : "c", "age" : "3" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1004" }, "name" : "c", "age" : "4" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1005" }, "name" : "c", "age" : "5" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1006" }, "name" : "c", "age" : "6" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1007" }, "name" : "c", "age" : "7" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1008" }, "name" : "c", "age" : "8" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1009" }, "name" : "c", "age" : "9" });
+db.tb_1.insertOne({ "_id" : { "$numberLong" : "1010" }, "name" : "c", "age" : "10" });
+db.tb_1.insertOne({ "name" : "d", "age" : "1" });
+db.tb_1.insertOne({ "name" : "d", "age" : "2" });
+db.tb_1.insertOne({ "name" : "d", "age" : "3" });
+db.tb_1.insertOne({ "name" : "d", "age" : "4" });
+db.tb_1.insertOne({ "name" : "d", "age" : "5" });
+db.tb_1.insertOne({ "name" : "d", "age" : "6" });
+db.tb_1.insertOne({ "name" : "d", "age" : "7" });
+db.tb_1.insertOne({ "name" : "d", "age" : "8" });
+db.tb_1.insertOne({ "name" : "d", "age" : "9" });
+db.tb_1.insertOne({ "name" : "d", "age" : "10" });
+db.tb_1.insertOne({ "_id" : { "$date" : { "$numberLong" : "1700000000000" } }, "name" : "e", "age" : "1" });
+db.tb_1.insertOne({ "_id" : { "$timestamp" : { "t" : 1700000000, "i" : 1 } }, "name" : "f", "age" : "1" });
+
+-- _id of ObjectId
+db.tb_2.insertOne({ "name" : "a", "age" : "1" });
+db.tb_2.insertOne({ "name" : "a", "age" : "2" });
+db.tb_2.insertOne({ "name" : "a", "age" : "3" });
+db.tb_2.insertOne({ "name" : "a", "age" : "4" });
+db.tb_2.insertOne({ "name" : "a", "age" : "5" });
+db.tb_2.insertOne({ "name" : "a", "age" : "6" });
+db.tb_2.insertOne({ "name" : "a", "age" : "7" });
+db.tb_2.insertOne({ "name" : "a", "age" : "8" });
+db.tb_2.insertOne({ "name" : "a", "age" : "9" });
+db.tb_2.insertOne({ "name" : "a", "age" : "10" });
+db.tb_2.insertOne({ "name" : "a", "age" : "11" });
+db.tb_2.insertOne({ "name" : "a", "age" : "12" });
+db.tb_2.insertOne({ "name" : "a", "age" : "13" });
+db.tb_2.insertOne({ "name" : "a", "age" : "14" });
+db.tb_2.insertOne({ "name" : "a", "age" : "15" });
+db.tb_2.insertOne({ "name" : "a", "age" : "16" });
+db.tb_2.insertOne({ "name" : "a", "age" : "17" });
+db.tb_2.insertOne({ "name" : "a", "age" : "18" });
+db.tb_2.insertOne({ "name" : "a", "age" : "19" });
+db.tb_2.insertOne({ "name" : "a", "age" : "20" });
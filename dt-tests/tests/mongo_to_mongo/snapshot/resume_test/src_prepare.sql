@@ -2,5 +2,7 @@ use test_db_1
 
 db.dropDatabase();
 
-db.createCollection("tb_1")
-db.createCollection("tb_2")
\ No newline at end of file
+db.createCollection("tb_1");
+db.createCollection("tb_2");
+db.createCollection("tb_3");
+db.createCollection("tb_4");
\ No newline at end of file
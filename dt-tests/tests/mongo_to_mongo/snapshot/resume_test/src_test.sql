@@ -11,3 +11,15 @@ db.tb_2.insertOne({ "_id" : "648195af9aa9cadd41a9dcb2", "name" : "a", "age" : "2
 db.tb_2.insertOne({ "_id" : "648195af9aa9cadd41a9dcb3", "name" : "a", "age" : "3" })
 db.tb_2.insertOne({ "_id" : "648195af9aa9cadd41a9dcb4", "name" : "a", "age" : "4" })
 db.tb_2.insertOne({ "_id" : "648195af9aa9cadd41a9dcb5", "name" : "a", "age" : "5" })
+
+db.tb_3.insertOne({ "_id" : 1, "name" : "a", "age" : "1" })
+db.tb_3.insertOne({ "_id" : 2, "name" : "a", "age" : "2" })
+db.tb_3.insertOne({ "_id" : 3, "name" : "a", "age" : "3" })
+db.tb_3.insertOne({ "_id" : 4, "name" : "a", "age" : "4" })
+db.tb_3.insertOne({ "_id" : 5, "name" : "a", "age" : "5" })
+
+db.tb_4.insertOne({ "_id" : { "$oid" : "648195af9aa9cadd41a9dcb1" }, "name" : "a", "age" : "1" })
+db.tb_4.insertOne({ "_id" : { "$oid" : "648195af9aa9cadd41a9dcb2" }, "name" : "a", "age" : "2" })
+db.tb_4.insertOne({ "_id" : { "$oid" : "648195af9aa9cadd41a9dcb3" }, "name" : "a", "age" : "3" })
+db.tb_4.insertOne({ "_id" : { "$oid" : "648195af9aa9cadd41a9dcb4" }, "name" : "a", "age" : "4" })
+db.tb_4.insertOne({ "_id" : { "$oid" : "648195af9aa9cadd41a9dcb5" }, "name" : "a", "age" : "5" })
@@ -18,12 +18,20 @@ mod test {
         TestBase::run_mongo_snapshot_test("mongo_to_mongo/snapshot/route_test").await;
     }
 
-    // #[tokio::test]
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_parallel_test() {
+        TestBase::run_mongo_snapshot_test("mongo_to_mongo/snapshot/parallel_test").await;
+    }
+
+    #[tokio::test]
     #[serial]
     async fn snapshot_resume_test() {
         let mut dst_expected_counts = HashMap::new();
         dst_expected_counts.insert(("test_db_1", "tb_1"), 2);
         dst_expected_counts.insert(("test_db_1", "tb_2"), 5);
+        dst_expected_counts.insert(("test_db_1", "tb_3"), 2);
+        dst_expected_counts.insert(("test_db_1", "tb_4"), 2);
 
         TestBase::run_mongo_snapshot_test_and_check_dst_count(
             "mongo_to_mongo/snapshot/resume_test",
@@ -0,0 +1,7 @@
+use test_db_1
+
+db.dropDatabase();
+
+use test_db_2
+
+db.dropDatabase();
@@ -0,0 +1,28 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
+db.runCommand({ "create": "capped_tb", "capped": true, "size": 1048576, "max": 1000 });
+db.runCommand({ "create": "validator_tb", "validator": { "$jsonSchema": { "bsonType": "object", "required": ["name"], "properties": { "name": { "bsonType": "string" } } } }, "validationLevel": "moderate", "validationAction": "warn" });
+db.runCommand({ "create": "collation_tb", "collation": { "locale": "fr", "strength": 2 } });
+db.runCommand({ "create": "timeseries_tb", "timeseries": { "timeField": "ts", "metaField": "meta", "granularity": "minutes" }, "expireAfterSeconds": 86400 });
+db.runCommand({ "create": "clustered_tb", "clusteredIndex": { "key": { "_id": 1 }, "unique": true }, "expireAfterSeconds": 3600 });
+db.runCommand({ "create": "view_1", "viewOn": "tb_1", "pipeline": [{ "$match": { "status": "A" } }] });
+
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "email": 1 }, "name": "email_unique", "unique": true }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "age": -1, "name": 1 }, "name": "age_name_partial", "partialFilterExpression": { "age": { "$gt": 18 } } }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "created_at": 1 }, "name": "created_at_ttl", "expireAfterSeconds": 600 }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "content": "text", "title": "text" }, "name": "content_text", "weights": { "title": 10 }, "default_language": "english" }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "loc": "2dsphere" }, "name": "loc_2dsphere" }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "$**": 1 }, "name": "attrs_wildcard", "wildcardProjection": { "attrs": 1 } }] });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "code": 1 }, "name": "code_sparse_hidden", "sparse": true, "hidden": true }] });
+db.runCommand({ "createIndexes": "collation_tb", "indexes": [{ "key": { "name": 1 }, "name": "name_1" }] });
+db.runCommand({ "createIndexes": "timeseries_tb", "indexes": [{ "key": { "meta.sensor": 1, "ts": -1 }, "name": "sensor_ts" }] });
+
+use test_db_2
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "a": 1, "b": -1 }, "name": "a_b", "unique": true }] });
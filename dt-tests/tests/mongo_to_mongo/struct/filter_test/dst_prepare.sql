@@ -0,0 +1,3 @@
+use test_db_1
+
+db.dropDatabase();
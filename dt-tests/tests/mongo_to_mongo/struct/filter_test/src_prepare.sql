@@ -0,0 +1,10 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.runCommand({ "create": "tb_1", "validator": { "age": { "$gte": 0 } } });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "email": 1 }, "name": "email_unique", "unique": true }] });
+db.runCommand({ "create": "tb_2", "capped": true, "size": 1048576 });
+db.runCommand({ "createIndexes": "tb_2", "indexes": [{ "key": { "loc": "2dsphere" }, "name": "loc_2dsphere" }] });
+db.createCollection("tb_3");
+db.runCommand({ "createIndexes": "tb_3", "indexes": [{ "key": { "a": 1 }, "name": "a_1" }] });
@@ -0,0 +1,11 @@
+use dst_test_db_1
+
+db.dropDatabase();
+
+use dst_test_db_2
+
+db.dropDatabase();
+
+use test_db_2
+
+db.dropDatabase();
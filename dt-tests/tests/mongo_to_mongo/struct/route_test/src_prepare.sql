@@ -0,0 +1,15 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.runCommand({ "create": "tb_1", "collation": { "locale": "en", "strength": 1 } });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "email": 1 }, "name": "email_unique", "unique": true }] });
+
+use test_db_2
+
+db.dropDatabase();
+
+db.runCommand({ "create": "tb_1", "capped": true, "size": 1048576 });
+db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "a": 1, "b": -1 }, "name": "a_b" }] });
+db.createCollection("tb_2");
+db.runCommand({ "createIndexes": "tb_2", "indexes": [{ "key": { "created_at": 1 }, "name": "created_at_ttl", "expireAfterSeconds": 600 }] });
@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod test {
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn struct_basic_test() {
+        TestBase::run_mongo_struct_test("mongo_to_mongo/struct/basic_test").await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn struct_route_test() {
+        TestBase::run_mongo_struct_test("mongo_to_mongo/struct/route_test").await;
+    }
+
+    /// do_structures=table
+    #[tokio::test]
+    #[serial]
+    async fn struct_filter_test() {
+        TestBase::run_mongo_struct_test("mongo_to_mongo/struct/filter_test").await;
+    }
+}
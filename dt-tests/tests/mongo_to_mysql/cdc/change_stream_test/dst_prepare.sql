@@ -0,0 +1,7 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.tb_1(`_id` varchar(255), `name` longtext, `age` int, `profile_nick` longtext, `city` longtext, PRIMARY KEY(`_id`));
+
+CREATE TABLE test_db_1.tb_1_tags(`_id` varchar(255), `_idx` bigint, `value` longtext, PRIMARY KEY(`_id`, `_idx`));
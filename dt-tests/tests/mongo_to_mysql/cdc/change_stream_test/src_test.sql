@@ -0,0 +1,25 @@
+use test_db_1
+
+db.tb_1.insertOne({ "_id": "1", "name": "a", "age": 1, "profile": { "nick": "n_1", "address": { "city": "c_1" } }, "tags": ["t_1", "t_2"] });
+db.tb_1.insertOne({ "_id": "2", "name": "b", "age": 2, "tags": ["t_3"] });
+db.tb_1.insertOne({ "_id": "3", "name": "c", "profile": { "nick": "n_3" } });
+db.tb_1.insertOne({ "_id": "4", "name": "d", "tags": ["t_4", "t_5", "t_6"] });
+
+-- fields
+db.tb_1.updateOne({ "_id": "1" }, { "$set": { "name": "a_1", "age": 10 } });
+db.tb_1.updateOne({ "_id": "2" }, { "$unset": { "age": "" } });
+
+-- nested fields, including the col_map path
+db.tb_1.updateOne({ "_id": "1" }, { "$set": { "profile.nick": "n_11", "profile.address.city": "c_11" } });
+db.tb_1.updateOne({ "_id": "3" }, { "$set": { "profile.address": { "city": "c_3" } } });
+
+-- arrays of child tables
+db.tb_1.updateOne({ "_id": "1" }, { "$set": { "tags.1": "t_22" } });
+db.tb_1.updateOne({ "_id": "2" }, { "$push": { "tags": "t_33" } });
+db.tb_1.updateOne({ "_id": "4" }, { "$pop": { "tags": 1 } });
+
+-- replacement
+db.tb_1.replaceOne({ "_id": "3" }, { "name": "c_1", "tags": ["t_7"] });
+
+db.tb_1.deleteOne({ "_id": "2" });
+db.tb_1.deleteOne({ "_id": "4" });
@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod test {
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_op_log_test() {
+        TestBase::run_mongo_rdb_cdc_test("mongo_to_mysql/cdc/op_log_test", 3000, 3000).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_change_stream_test() {
+        TestBase::run_mongo_rdb_cdc_test("mongo_to_mysql/cdc/change_stream_test", 3000, 3000).await;
+    }
+}
@@ -0,0 +1,6 @@
+use test_db_1
+
+db.tb_1.insertOne({ "_id": "1", "name": "a", "age": 1, "profile": { "nick": "n_1", "address": { "city": "c_1" } }, "tags": ["t_1", "t_2"] });
+db.tb_1.insertOne({ "_id": "2", "name": "b", "age": 2, "tags": ["t_3"] });
+db.tb_1.insertOne({ "_id": "3", "name": "c", "profile": { "nick": "n_3" } });
+db.tb_1.insertOne({ "_id": "4" });
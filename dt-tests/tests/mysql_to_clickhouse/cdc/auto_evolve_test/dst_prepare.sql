@@ -0,0 +1 @@
+DROP DATABASE IF EXISTS test_db_1;
\ No newline at end of file
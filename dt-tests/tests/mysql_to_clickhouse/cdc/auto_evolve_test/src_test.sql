@@ -0,0 +1,19 @@
+INSERT INTO test_db_1.tb_1 VALUES (1, 1, 'a', 1), (2, 2, 'b', 2);
+
+ALTER TABLE test_db_1.tb_1 ADD COLUMN f_4 bigint DEFAULT NULL;
+INSERT INTO test_db_1.tb_1 VALUES (3, 3, 'c', 3, 3);
+UPDATE test_db_1.tb_1 SET f_4 = 10 WHERE id = 1;
+
+ALTER TABLE test_db_1.tb_1 MODIFY COLUMN f_2 varchar(255) DEFAULT NULL;
+INSERT INTO test_db_1.tb_1 VALUES (4, 4, 'dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd', 4, 4);
+
+-- narrowing column type is skipped
+ALTER TABLE test_db_1.tb_1 MODIFY COLUMN f_4 int DEFAULT NULL;
+INSERT INTO test_db_1.tb_1 VALUES (5, 5, 'e', 5, 5);
+
+ALTER TABLE test_db_1.tb_1 RENAME COLUMN f_3 TO f_3_new;
+UPDATE test_db_1.tb_1 SET f_3_new = 30 WHERE id = 3;
+
+ALTER TABLE test_db_1.tb_1 DROP COLUMN f_1;
+DELETE FROM test_db_1.tb_1 WHERE id = 2;
+INSERT INTO test_db_1.tb_1 VALUES (6, 'f', 6, 6);
@@ -4,7 +4,7 @@ mod test {
     use serial_test::serial;
 
     use crate::{
-        mysql_to_clickhouse::table_schemas::MysqlBasicTable,
+        mysql_to_clickhouse::table_schemas::{MysqlAutoEvolveTable, MysqlBasicTable},
         test_runner::rdb_clickhouse_test_runner::RdbClickHouseTestRunner,
     };
 
@@ -20,4 +20,17 @@ mod test {
             .unwrap();
         runner.close().await.unwrap();
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_auto_evolve_test() {
+        let runner = RdbClickHouseTestRunner::new("mysql_to_clickhouse/cdc/auto_evolve_test")
+            .await
+            .unwrap();
+        runner
+            .run_ddl_test::<MysqlAutoEvolveTable>(3000, 10000)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
 }
@@ -50,3 +50,11 @@ pub(super) struct MysqlRouteTable {
     pk: i8,
     tinyint_col: Option<i8>,
 }
+
+#[derive(Row, Deserialize, Serialize)]
+pub(super) struct MysqlAutoEvolveTable {
+    id: i32,
+    f_2: Option<String>,
+    f_3_new: Option<i32>,
+    f_4: Option<i64>,
+}
@@ -22,4 +22,10 @@ mod test {
     async fn cdc_json_to_string_test() {
         TestBase::run_cdc_test("mysql_to_doris/cdc/2_1_0/json_to_string_test", 3000, 5000).await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_auto_evolve_test() {
+        TestBase::run_ddl_test("mysql_to_doris/cdc/2_1_0/auto_evolve_test", 3000, 10000).await;
+    }
 }
@@ -0,0 +1,5 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.generated_col_test(f_0 INT, f_1 INT, f_2 VARCHAR(64), f_3 INT GENERATED ALWAYS AS (f_1 + 1) VIRTUAL, f_4 VARCHAR(128) GENERATED ALWAYS AS (CONCAT(f_2, '_', f_1)) STORED, PRIMARY KEY(f_0));
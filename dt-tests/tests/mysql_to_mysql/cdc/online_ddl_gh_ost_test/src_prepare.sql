@@ -0,0 +1,5 @@
+DROP DATABASE IF EXISTS test_db_1;
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.tb_1 ( f_0 int, f_1 int DEFAULT NULL, PRIMARY KEY (f_0) );
+INSERT INTO test_db_1.tb_1 VALUES (1, 1), (2, 2);
@@ -0,0 +1,24 @@
+-- gh-ost: create the changelog table and the ghost table, alter the ghost table
+create /* gh-ost */ table `test_db_1`.`_tb_1_ghc` ( id bigint auto_increment, last_update timestamp not null DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP, hint varchar(64) charset ascii not null, value varchar(4096) charset ascii not null, primary key(id), unique key hint_uidx(hint) ) auto_increment=256;
+create /* gh-ost */ table `test_db_1`.`_tb_1_gho` like `test_db_1`.`tb_1`;
+alter /* gh-ost */ table `test_db_1`.`_tb_1_gho` ADD COLUMN f_2 int DEFAULT NULL;
+insert /* gh-ost */ into `test_db_1`.`_tb_1_ghc` (id, hint, value) values (2, 'state', 'GhostTableMigrated');
+
+-- gh-ost: copy rows and apply binlog of the original table to the ghost table
+INSERT INTO test_db_1.tb_1 VALUES (3, 3);
+insert /* gh-ost */ ignore into `test_db_1`.`_tb_1_gho` (`f_0`, `f_1`) (select `f_0`, `f_1` from `test_db_1`.`tb_1` force index (`PRIMARY`));
+UPDATE test_db_1.tb_1 SET f_1 = 20 WHERE f_0 = 2;
+replace /* gh-ost */ into `test_db_1`.`_tb_1_gho` (`f_0`, `f_1`) values (2, 20);
+DELETE FROM test_db_1.tb_1 WHERE f_0 = 1;
+delete /* gh-ost */ from `test_db_1`.`_tb_1_gho` where ((`f_0` = 1));
+
+-- gh-ost: cut-over
+create /* gh-ost */ table `test_db_1`.`_tb_1_del` ( id int auto_increment primary key ) engine=InnoDB comment='ghost-cut-over-sentry';
+drop /* gh-ost */ table if exists `test_db_1`.`_tb_1_del`;
+rename /* gh-ost */ table `test_db_1`.`tb_1` to `test_db_1`.`_tb_1_del`, `test_db_1`.`_tb_1_gho` to `test_db_1`.`tb_1`;
+drop /* gh-ost */ table if exists `test_db_1`.`_tb_1_ghc`;
+drop /* gh-ost */ table if exists `test_db_1`.`_tb_1_del`;
+
+-- the original table has the new column
+INSERT INTO test_db_1.tb_1 VALUES (4, 4, 4);
+UPDATE test_db_1.tb_1 SET f_2 = 3 WHERE f_0 = 3;
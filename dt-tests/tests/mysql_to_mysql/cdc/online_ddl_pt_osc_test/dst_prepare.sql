@@ -0,0 +1,5 @@
+DROP DATABASE IF EXISTS dst_test_db_1;
+CREATE DATABASE dst_test_db_1;
+
+CREATE TABLE dst_test_db_1.tb_1 ( f_0 int, f_1 int DEFAULT NULL, PRIMARY KEY (f_0) );
+INSERT INTO dst_test_db_1.tb_1 VALUES (1, 1), (2, 2);
@@ -0,0 +1,25 @@
+-- pt-osc: create the ghost table, alter the ghost table
+CREATE TABLE `test_db_1`.`_tb_1_new` LIKE `test_db_1`.`tb_1`;
+ALTER TABLE `test_db_1`.`_tb_1_new` ADD COLUMN f_2 int DEFAULT NULL;
+
+-- pt-osc: triggers apply changes of the original table to the ghost table
+CREATE TRIGGER `test_db_1`.`pt_osc_test_db_1_tb_1_ins` AFTER INSERT ON `test_db_1`.`tb_1` FOR EACH ROW REPLACE INTO `test_db_1`.`_tb_1_new` (`f_0`, `f_1`) VALUES (NEW.`f_0`, NEW.`f_1`);
+CREATE TRIGGER `test_db_1`.`pt_osc_test_db_1_tb_1_upd` AFTER UPDATE ON `test_db_1`.`tb_1` FOR EACH ROW REPLACE INTO `test_db_1`.`_tb_1_new` (`f_0`, `f_1`) VALUES (NEW.`f_0`, NEW.`f_1`);
+CREATE TRIGGER `test_db_1`.`pt_osc_test_db_1_tb_1_del` AFTER DELETE ON `test_db_1`.`tb_1` FOR EACH ROW DELETE IGNORE FROM `test_db_1`.`_tb_1_new` WHERE `test_db_1`.`_tb_1_new`.`f_0` <=> OLD.`f_0`;
+
+-- pt-osc: copy rows
+INSERT LOW_PRIORITY IGNORE INTO `test_db_1`.`_tb_1_new` (`f_0`, `f_1`) SELECT `f_0`, `f_1` FROM `test_db_1`.`tb_1` LOCK IN SHARE MODE;
+INSERT INTO test_db_1.tb_1 VALUES (3, 3);
+UPDATE test_db_1.tb_1 SET f_1 = 20 WHERE f_0 = 2;
+DELETE FROM test_db_1.tb_1 WHERE f_0 = 1;
+
+-- pt-osc: cut-over
+RENAME TABLE `test_db_1`.`tb_1` TO `test_db_1`.`_tb_1_old`, `test_db_1`.`_tb_1_new` TO `test_db_1`.`tb_1`;
+DROP TABLE IF EXISTS `test_db_1`.`_tb_1_old`;
+DROP TRIGGER IF EXISTS `test_db_1`.`pt_osc_test_db_1_tb_1_del`;
+DROP TRIGGER IF EXISTS `test_db_1`.`pt_osc_test_db_1_tb_1_upd`;
+DROP TRIGGER IF EXISTS `test_db_1`.`pt_osc_test_db_1_tb_1_ins`;
+
+-- the original table has the new column
+INSERT INTO test_db_1.tb_1 VALUES (4, 4, 4);
+UPDATE test_db_1.tb_1 SET f_2 = 3 WHERE f_0 = 3;
@@ -0,0 +1,6 @@
+DROP DATABASE IF EXISTS test_db_1;
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.where_condition_1 ( f_0 int, f_1 int, PRIMARY KEY (f_0) );
+CREATE TABLE test_db_1.where_condition_2 ( f_0 int, f_1 int, PRIMARY KEY (f_0) );
+CREATE TABLE test_db_1.where_condition_3 ( f_0 int, f_1 int, PRIMARY KEY (f_0) );
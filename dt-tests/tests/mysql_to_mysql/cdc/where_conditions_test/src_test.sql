@@ -0,0 +1,12 @@
+INSERT INTO test_db_1.where_condition_1 VALUES(1, 1),(2, 2),(3, 3),(4, 4),(5, 5),(6, 6),(7, 7),(8, 8),(9, 9),(10, 10);
+INSERT INTO test_db_1.where_condition_2 VALUES(1, 1),(2, 2),(3, 3),(4, 4),(5, 5),(6, 6),(7, 7),(8, 8),(9, 9),(10, 10);
+INSERT INTO test_db_1.where_condition_3 VALUES(1, 1),(2, 2),(3, 3),(4, 4),(5, 5);
+
+-- moved out of / into the subset
+UPDATE test_db_1.where_condition_1 SET f_1 = 10 WHERE f_0 = 2;
+UPDATE test_db_1.where_condition_1 SET f_1 = 1 WHERE f_0 = 9;
+UPDATE test_db_1.where_condition_1 SET f_1 = 0 WHERE f_0 = 3;
+UPDATE test_db_1.where_condition_2 SET f_1 = 0 WHERE f_0 = 1;
+
+DELETE FROM test_db_1.where_condition_1 WHERE f_0 IN (1, 4);
+DELETE FROM test_db_1.where_condition_2 WHERE f_0 IN (1, 4);
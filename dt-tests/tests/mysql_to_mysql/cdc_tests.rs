@@ -41,6 +41,24 @@ mod test {
         TestBase::run_ddl_test("mysql_to_mysql/cdc/ddl_route_test", 3000, 5000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_online_ddl_gh_ost_test() {
+        TestBase::run_ddl_test("mysql_to_mysql/cdc/online_ddl_gh_ost_test", 3000, 5000).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_online_ddl_pt_osc_test() {
+        TestBase::run_ddl_test("mysql_to_mysql/cdc/online_ddl_pt_osc_test", 3000, 5000).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_generated_col_test() {
+        TestBase::run_cdc_test("mysql_to_mysql/cdc/generated_col_test", 3000, 2000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_ddl_meta_center_test() {
@@ -166,4 +184,10 @@ mod test {
     async fn cdc_gtid_test() {
         TestBase::run_cdc_test("mysql_to_mysql/cdc/gtid_test", 3000, 2000).await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_where_conditions_test() {
+        TestBase::run_cdc_test("mysql_to_mysql/cdc/where_conditions_test", 3000, 2000).await;
+    }
 }
@@ -0,0 +1 @@
+INSERT INTO test_db_1.generated_col_test(f_0, f_1, f_2) VALUES (1, 1, 'a'), (2, 2, 'b'), (3, NULL, 'c'), (4, 4, NULL);
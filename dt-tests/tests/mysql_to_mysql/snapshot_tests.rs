@@ -73,6 +73,12 @@ mod test {
         TestBase::run_snapshot_test("mysql_to_mysql/snapshot/json_test").await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_generated_col_test() {
+        TestBase::run_snapshot_test("mysql_to_mysql/snapshot/generated_col_test").await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_route_test() {
@@ -10,4 +10,15 @@ CREATE TABLE `expression_defaults` (
   `d` date DEFAULT ((curdate() + interval 1 year)),
   `p` point DEFAULT (point(0,0)),
   `j` json DEFAULT (json_array())
-) ENGINE=InnoDB DEFAULT CHARSET=utf8mb3
\ No newline at end of file
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb3
+
+struct_it_mysql2mysql_1.generated_invisible_cols
+CREATE TABLE `generated_invisible_cols` (
+  `id` int NOT NULL,
+  `f_1` int DEFAULT NULL,
+  `f_2` int GENERATED ALWAYS AS ((`f_1` + 1)) VIRTUAL,
+  `f_3` int GENERATED ALWAYS AS ((`f_1` * 2)) STORED,
+  `f_4` int DEFAULT NULL /*!80023 INVISIBLE */,
+  `f_5` int GENERATED ALWAYS AS ((`f_1` + 2)) VIRTUAL /*!80023 INVISIBLE */,
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb3
@@ -18,3 +18,16 @@ CREATE TABLE struct_it_mysql2mysql_1.expression_defaults (
   j JSON        DEFAULT (JSON_ARRAY())
 );
 ```
+
+-- generated and invisible columns, invisible columns are surpported in mysql 8.0.23+
+```
+CREATE TABLE struct_it_mysql2mysql_1.generated_invisible_cols (
+  id INT NOT NULL,
+  f_1 INT,
+  f_2 INT GENERATED ALWAYS AS (f_1 + 1) VIRTUAL,
+  f_3 INT GENERATED ALWAYS AS (f_1 * 2) STORED,
+  f_4 INT INVISIBLE,
+  f_5 INT GENERATED ALWAYS AS (f_1 + 2) VIRTUAL INVISIBLE,
+  PRIMARY KEY (id)
+);
+```
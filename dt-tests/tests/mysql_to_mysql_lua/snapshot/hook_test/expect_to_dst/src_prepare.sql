@@ -0,0 +1,8 @@
+DROP DATABASE IF EXISTS lua_hook_test_expect;
+
+CREATE DATABASE lua_hook_test_expect;
+
+CREATE TABLE `lua_hook_test_expect`.`orders` ( `id` int(11) NOT NULL, `country_code` varchar(8) DEFAULT NULL, `country_name` varchar(64) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8mb4;
+CREATE TABLE `lua_hook_test_expect`.`orders_audit` ( `id` int(11) NOT NULL, `order_id` int(11) DEFAULT NULL, `seq` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8mb4;
+CREATE TABLE `lua_hook_test_expect`.`json_test` ( `id` int(11) NOT NULL, `f_1` json DEFAULT NULL, `f_2` blob DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8mb4;
+CREATE TABLE `lua_hook_test_expect`.`filter_row_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8mb4;
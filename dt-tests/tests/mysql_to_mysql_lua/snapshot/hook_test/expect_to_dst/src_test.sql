@@ -0,0 +1,7 @@
+INSERT INTO lua_hook_test_expect.orders VALUES(1, 'CN', 'China'), (2, 'US', 'United States'), (3, 'XX', 'unknown');
+INSERT INTO lua_hook_test_expect.orders_audit VALUES(100, 1, 1), (200, 2, 2), (300, 3, 3);
+
+INSERT INTO lua_hook_test_expect.json_test VALUES(1, '{"k": "v", "n": [1, 2]}', x'ABCDEF0123456789');
+INSERT INTO lua_hook_test_expect.json_test VALUES(2, '{"k": "v2"}', x'ABCDEF012345678901');
+
+INSERT INTO lua_hook_test_expect.filter_row_test VALUES(2, 2);
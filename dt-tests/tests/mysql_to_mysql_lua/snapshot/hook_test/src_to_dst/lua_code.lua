@@ -0,0 +1,43 @@
+-- executed once when the task starts
+function init()
+    audit_seq = 0
+end
+
+function process_row(row)
+    if row.schema ~= "lua_hook_test" then
+        return row
+    end
+
+    -- fill column by lookup file
+    if row.tb == "orders" then
+        local name = lookup("countries", row.after.country_code)
+        if name ~= nil then
+            row.after.country_name = name
+        else
+            row.after.country_name = "unknown"
+        end
+
+        -- fan-out: emit an extra audit row for each order, global state is kept between rows
+        audit_seq = audit_seq + 1
+        local audit = {
+            schema = row.schema,
+            tb = "orders_audit",
+            row_type = "insert",
+            after = { id = row.after.id * 100, order_id = row.after.id, seq = audit_seq }
+        }
+        return { row, audit }
+    end
+
+    -- json and blob values are passed as strings
+    if row.tb == "json_test" and row.after.id == 2 then
+        row.after.f_1 = '{"k": "v2"}'
+        row.after.f_2 = row.after.f_2 .. "\x01"
+    end
+
+    -- drop row
+    if row.tb == "filter_row_test" and row.after.id == 1 then
+        return nil
+    end
+
+    return row
+end
@@ -0,0 +1,6 @@
+INSERT INTO lua_hook_test.orders VALUES(1, 'CN', NULL), (2, 'US', NULL), (3, 'XX', NULL);
+
+INSERT INTO lua_hook_test.json_test VALUES(1, '{"k": "v", "n": [1, 2]}', x'ABCDEF0123456789');
+INSERT INTO lua_hook_test.json_test VALUES(2, '{"k": "v"}', x'ABCDEF0123456789');
+
+INSERT INTO lua_hook_test.filter_row_test VALUES(1, 1), (2, 2);
@@ -16,4 +16,10 @@ mod test {
     async fn snapshot_basic_test() {
         TestBase::run_snapshot_lua_test("mysql_to_mysql_lua/snapshot/basic_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_hook_test() {
+        TestBase::run_snapshot_lua_test("mysql_to_mysql_lua/snapshot/hook_test").await;
+    }
 }
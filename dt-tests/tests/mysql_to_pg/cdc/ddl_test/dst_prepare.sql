@@ -0,0 +1,7 @@
+DROP SCHEMA IF EXISTS test_db_1 CASCADE;
+CREATE SCHEMA test_db_1;
+
+CREATE TABLE test_db_1.tb_1 ( f_0 smallint, f_1 smallint DEFAULT NULL, PRIMARY KEY (f_0) );
+
+CREATE TABLE test_db_1.index_tb_1 ( f_0 smallint, f_1 smallint DEFAULT NULL, f_2 smallint DEFAULT NULL, PRIMARY KEY (f_0) );
+CREATE INDEX idx_f_2 ON test_db_1.index_tb_1 (f_2);
@@ -0,0 +1,8 @@
+DROP DATABASE IF EXISTS test_db_1;
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.tb_1 ( f_0 tinyint, f_1 smallint DEFAULT NULL, PRIMARY KEY (f_0) );
+
+CREATE TABLE test_db_1.index_tb_1 ( f_0 tinyint, f_1 smallint DEFAULT NULL, f_2 smallint DEFAULT NULL, PRIMARY KEY (f_0) );
+-- index not created by cdc keeps its original name in target
+CREATE INDEX idx_f_2 ON test_db_1.index_tb_1 (f_2);
@@ -0,0 +1,36 @@
+INSERT INTO test_db_1.tb_1 VALUES (1,1);
+
+-- add column
+ALTER TABLE test_db_1.tb_1 ADD COLUMN f_2 smallint DEFAULT NULL;
+-- column position is ignored by target
+ALTER TABLE test_db_1.tb_1 ADD COLUMN f_3 varchar(64) DEFAULT NULL AFTER f_1;
+
+INSERT INTO test_db_1.tb_1 VALUES (2,2,'2',2);
+
+-- modify column
+ALTER TABLE test_db_1.tb_1 MODIFY COLUMN f_3 varchar(128) DEFAULT NULL;
+
+-- drop column
+ALTER TABLE test_db_1.tb_1 DROP COLUMN f_2;
+
+INSERT INTO test_db_1.tb_1 VALUES (3,3,'3');
+
+-- create table
+CREATE TABLE test_db_1.tb_2 ( f_0 tinyint, f_1 smallint DEFAULT NULL, f_2 smallint DEFAULT NULL, PRIMARY KEY (f_0) ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+INSERT INTO test_db_1.tb_2 VALUES (1,1,1);
+
+-- add index, created as tb_2_idx_f_1 / tb_2_idx_f_2 in target
+ALTER TABLE test_db_1.tb_2 ADD INDEX idx_f_1 (f_1);
+CREATE INDEX idx_f_2 ON test_db_1.tb_2 (f_2);
+
+-- rename / drop index created by cdc
+ALTER TABLE test_db_1.tb_2 RENAME INDEX idx_f_1 TO idx_f_1_new;
+ALTER TABLE test_db_1.tb_2 DROP INDEX idx_f_1_new;
+DROP INDEX idx_f_2 ON test_db_1.tb_2;
+
+-- drop index not created by cdc
+DROP INDEX idx_f_2 ON test_db_1.index_tb_1;
+
+INSERT INTO test_db_1.tb_2 VALUES (2,2,2);
+INSERT INTO test_db_1.index_tb_1 VALUES (1,1,1);
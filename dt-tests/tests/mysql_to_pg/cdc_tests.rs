@@ -0,0 +1,11 @@
+#[cfg(test)]
+mod test {
+    use crate::test_runner::test_base::TestBase;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_ddl_test() {
+        TestBase::run_ddl_test("mysql_to_pg/cdc/ddl_test", 3000, 5000).await;
+    }
+}
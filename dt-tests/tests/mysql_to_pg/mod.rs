@@ -0,0 +1 @@
+mod cdc_tests;
@@ -0,0 +1,13 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+```
+CREATE TABLE test_db_1.tb_1 (
+    id int NOT NULL,
+    f_1 int DEFAULT NULL,
+    f_2 varchar(64) DEFAULT NULL,
+    f_3 int DEFAULT NULL,
+    PRIMARY KEY (id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+```
@@ -33,4 +33,15 @@ mod test {
         )
         .await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_auto_evolve_test() {
+        TestBase::run_ddl_test(
+            "mysql_to_starrocks/cdc/3_2_11/auto_evolve_test",
+            3000,
+            10000,
+        )
+        .await;
+    }
 }
@@ -0,0 +1,6 @@
+INSERT INTO test_db_1.generated_col_test(f_0, f_1, f_2) VALUES (1, 1, 'a'), (2, 2, 'b'), (3, NULL, 'c'), (4, 4, NULL);
+
+UPDATE test_db_1.generated_col_test SET f_1 = 10, f_2 = 'aa' WHERE f_0 = 1;
+UPDATE test_db_1.generated_col_test SET f_1 = 3 WHERE f_0 = 3;
+
+DELETE FROM test_db_1.generated_col_test WHERE f_0 = 2;
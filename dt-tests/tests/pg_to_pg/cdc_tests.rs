@@ -59,6 +59,12 @@ mod test {
         TestBase::run_cdc_test("pg_to_pg/cdc/route_test", 3000, 4000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_generated_col_test() {
+        TestBase::run_cdc_test("pg_to_pg/cdc/generated_col_test", 3000, 4000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_foreign_key_test() {
@@ -92,4 +92,10 @@ mod test {
     async fn snapshot_route_test() {
         TestBase::run_snapshot_test("pg_to_pg/snapshot/route_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_generated_col_test() {
+        TestBase::run_snapshot_test("pg_to_pg/snapshot/generated_col_test").await;
+    }
 }
@@ -0,0 +1,40 @@
+-------------------- keys hashed to different slots, they are spread across all masters
+-- string entries
+SET set_key_1 val_1
+SET set_key_2_中文 val_2_中文
+SET "set_key_3_  😀" "val_2_  😀"
+SET set_key_4 val_4
+SET set_key_5 val_5
+SET set_key_6 val_6
+APPEND set_key_1 append_val_1
+INCRBY incr_key_1 10
+DEL set_key_6
+
+-- hash entries
+HSET hset_key_1 field_1 val_1 field_2_中文 val_2_中文 "field_3_  😀" "val_3_  😀"
+HSET hset_key_2 field_1 val_1 field_2 val_2
+HDEL hset_key_2 field_2
+
+-- list entries
+RPUSH list_key_1 val_1 val_2_中文 "val_3_  😀"
+LPUSH list_key_2 val_1 val_2
+LPOP list_key_2
+
+-- sets entries
+SADD sets_key_1 val_1 val_2_中文 "val_3_  😀" val_5
+SREM sets_key_1 val_5
+
+-- zset entries
+ZADD zset_key_1 1 val_1 2 val_2_中文 3 "val_3_  😀"
+ZINCRBY zset_key_1 5 val_1
+
+-- stream entries
+XADD stream_key_1 * field_1 val_1 field_2_中文 val_2_中文 "field_3_  😀" "val_3_  😀"
+
+-- multi-key commands in the same slot
+MSET {tag_1}key_1 val_1 {tag_1}key_2 val_2 {tag_1}key_3 val_3
+RENAME {tag_1}key_3 {tag_1}key_4
+
+-- expire
+SET expire_key_1 val_1 EX 100000
+EXPIRE set_key_2_中文 100000
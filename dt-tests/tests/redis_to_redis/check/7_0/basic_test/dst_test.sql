@@ -0,0 +1,19 @@
+-------------------- string entries
+SET string_key_1 val_1
+SET string_key_2 val_2000
+HSET type_key_1 field_1 val_1
+
+-------------------- hash entries
+HSET hash_key_1 field_1 val_1 field_2 val_2000 field_3 val_3
+
+-------------------- list entries
+RPUSH list_key_1 val_1 val_3 val_2
+
+-------------------- sets entries
+SADD set_key_1 val_1 val_3
+
+-------------------- zset entries
+ZADD zset_key_1 1 val_1 20 val_2
+
+-------------------- stream entries
+XADD stream_key_1 1-1 field_1 val_1000
@@ -0,0 +1 @@
+flushall
\ No newline at end of file
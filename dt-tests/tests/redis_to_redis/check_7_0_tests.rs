@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod test {
+
+    use crate::test_runner::test_base::TestBase;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn check_basic_test() {
+        TestBase::run_redis_check_test("redis_to_redis/check/7_0/basic_test").await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn revise_basic_test() {
+        TestBase::run_redis_revise_test("redis_to_redis/check/7_0/revise_test").await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn review_basic_test() {
+        TestBase::run_redis_review_test("redis_to_redis/check/7_0/review_test").await;
+    }
+}
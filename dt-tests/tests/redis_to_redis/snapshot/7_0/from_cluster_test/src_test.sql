@@ -0,0 +1,36 @@
+-------------------- keys hashed to different slots, they are spread across all masters
+-- string entries
+SET set_key_1 val_1
+SET set_key_2_中文 val_2_中文
+SET "set_key_3_  😀" "val_2_  😀"
+SET set_key_4 val_4
+SET set_key_5 val_5
+SET set_key_6 val_6
+
+-- hash entries
+HSET hset_key_1 field_1 val_1 field_2_中文 val_2_中文 "field_3_  😀" "val_3_  😀"
+HSET hset_key_2 field_1 val_1
+HSET hset_key_3 field_1 val_1
+
+-- list entries
+RPUSH list_key_1 val_1 val_2_中文 "val_3_  😀"
+RPUSH list_key_2 val_1
+RPUSH list_key_3 val_1
+
+-- sets entries
+SADD sets_key_1 val_1 val_2_中文 "val_3_  😀"
+SADD sets_key_2 val_1
+
+-- zset entries
+ZADD zset_key_1 1 val_1 2 val_2_中文 3 "val_3_  😀"
+ZADD zset_key_2 1 val_1
+
+-- stream entries
+XADD stream_key_1 * field_1 val_1 field_2_中文 val_2_中文 "field_3_  😀" "val_3_  😀"
+XADD stream_key_2 * field_1 val_1
+
+-- keys with hash tag are in the same slot
+MSET {tag_1}key_1 val_1 {tag_1}key_2 val_2 {tag_1}key_3 val_3
+
+-- expire
+SET expire_key_1 val_1 EX 100000
@@ -106,9 +106,22 @@ mod test {
             .await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_statistic_memory_rdb_test() {
+        TestBase::run_redis_statistic_test("redis_to_redis/snapshot/7_0/statistic_memory_rdb_test")
+            .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_to_cluster_test() {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/to_cluster_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_from_cluster_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/from_cluster_test").await;
+    }
 }
@@ -8,4 +8,10 @@ mod test {
     async fn snapshot_cmds_test() {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/rebloom/cmds_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_rewrite_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/rebloom/rewrite_test").await;
+    }
 }
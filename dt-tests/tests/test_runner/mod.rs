@@ -1,6 +1,7 @@
 pub mod base_test_runner;
 pub mod check_util;
 pub mod mongo_check_test_runner;
+pub mod mongo_rdb_test_runner;
 pub mod mongo_test_runner;
 pub mod precheck_test_runner;
 pub mod rdb_check_test_runner;
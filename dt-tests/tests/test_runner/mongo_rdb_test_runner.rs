@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use dt_common::{
+    config::{config_enums::DbType, sinker_config::SinkerConfig, task_config::TaskConfig},
+    error::Error,
+    meta::{
+        col_value::ColValue,
+        mongo::{mongo_constant::MongoConstants, mongo_row_converter::MongoRowConverter},
+        row_data::RowData,
+        row_type::RowType,
+    },
+    utils::time_util::TimeUtil,
+};
+use dt_task::task_util::TaskUtil;
+use sqlx::{MySql, Pool};
+
+use super::{
+    mongo_test_runner::{MongoTestRunner, SRC},
+    rdb_test_runner::RdbTestRunner,
+    rdb_util::RdbUtil,
+};
+
+/// mongo -> mysql tasks with [mongo_mapping], src documents are converted by MongoRowConverter
+/// and compared with rows of the tables created in dst_prepare.sql
+pub struct MongoRdbTestRunner {
+    mongo_runner: MongoTestRunner,
+    dst_conn_pool: Pool<MySql>,
+    row_converter: MongoRowConverter,
+}
+
+impl MongoRdbTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let mongo_runner = MongoTestRunner::new(relative_test_dir).await?;
+        let config = TaskConfig::new(&mongo_runner.base.task_config_file)?;
+
+        let dst_conn_pool = match &config.sinker {
+            SinkerConfig::Mysql { url, .. } => {
+                TaskUtil::create_mysql_conn_pool(url, 1, false).await?
+            }
+            _ => bail! {Error::ConfigError("unsupported sinker config".into())},
+        };
+        let row_converter = MongoRowConverter::new(config.mongo_mapping.as_ref().unwrap())?;
+
+        Ok(Self {
+            mongo_runner,
+            dst_conn_pool,
+            row_converter,
+        })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.dst_conn_pool.close().await;
+        Ok(())
+    }
+
+    pub async fn run_snapshot_test(&self) -> anyhow::Result<()> {
+        self.execute_prepare_sqls().await?;
+        self.mongo_runner.execute_test_sqls().await?;
+
+        self.mongo_runner.base.start_task().await?;
+        self.compare_data().await
+    }
+
+    pub async fn run_cdc_test(&self, start_millis: u64, parse_millis: u64) -> anyhow::Result<()> {
+        self.execute_prepare_sqls().await?;
+
+        let task = self.mongo_runner.base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        let src_sqls = MongoTestRunner::slice_sqls_by_db(&self.mongo_runner.base.src_test_sqls);
+        let src_mongo_client = self.mongo_runner.src_mongo_client.as_ref().unwrap();
+        for (db, sqls) in src_sqls.iter() {
+            let (src_insert_sqls, src_update_sqls, src_delete_sqls) =
+                MongoTestRunner::slice_sqls_by_type(sqls);
+            for sqls in [src_insert_sqls, src_update_sqls, src_delete_sqls] {
+                self.mongo_runner
+                    .execute_dmls(src_mongo_client, db, &sqls)
+                    .await?;
+                TimeUtil::sleep_millis(parse_millis).await;
+                self.compare_data().await?;
+            }
+        }
+        self.mongo_runner.base.abort_task(&task).await
+    }
+
+    async fn execute_prepare_sqls(&self) -> anyhow::Result<()> {
+        let src_mongo_client = self.mongo_runner.src_mongo_client.as_ref().unwrap();
+        let src_sqls = MongoTestRunner::slice_sqls_by_db(&self.mongo_runner.base.src_prepare_sqls);
+        for (db, sqls) in src_sqls.iter() {
+            self.mongo_runner
+                .execute_ddls(src_mongo_client, db, sqls)
+                .await?;
+            self.mongo_runner
+                .execute_dmls(src_mongo_client, db, sqls)
+                .await?;
+        }
+        RdbUtil::execute_sqls_mysql(
+            &self.dst_conn_pool,
+            &self.mongo_runner.base.dst_prepare_sqls,
+        )
+        .await
+    }
+
+    async fn compare_data(&self) -> anyhow::Result<()> {
+        let dst_db_tbs = RdbTestRunner::get_compare_db_tbs_from_sqls(
+            &DbType::Mysql,
+            &self.mongo_runner.base.dst_prepare_sqls,
+        )?;
+
+        // HashMap<(db, tb), rows>, rows converted from all documents of src
+        let mut expected_rows: HashMap<(String, String), Vec<RowData>> = HashMap::new();
+        let src_dbs: Vec<String> =
+            MongoTestRunner::slice_sqls_by_db(&self.mongo_runner.base.src_prepare_sqls)
+                .into_keys()
+                .collect();
+        for db in src_dbs.iter() {
+            for tb in self.mongo_runner.list_tb(db, SRC).await {
+                for (_, doc) in self.mongo_runner.fetch_data(db, &tb, SRC).await {
+                    let after =
+                        HashMap::from([(MongoConstants::DOC.to_string(), ColValue::MongoDoc(doc))]);
+                    let row_data =
+                        RowData::new(db.clone(), tb.clone(), RowType::Insert, None, Some(after));
+                    for row_data in self.row_converter.convert(row_data)? {
+                        expected_rows
+                            .entry((row_data.schema.clone(), row_data.tb.clone()))
+                            .or_default()
+                            .push(row_data);
+                    }
+                }
+            }
+        }
+
+        for db_tb in dst_db_tbs.iter() {
+            let tb_meta = RdbUtil::get_tb_meta_mysql(&self.dst_conn_pool, db_tb).await?;
+            let dst_rows = RdbUtil::fetch_data_mysql(&self.dst_conn_pool, None, db_tb, "").await?;
+            let src_rows = expected_rows.remove(db_tb).unwrap_or_default();
+            println!(
+                "compare tb data, db: {}, tb: {}, src rows: {}, dst rows: {}",
+                db_tb.0,
+                db_tb.1,
+                src_rows.len(),
+                dst_rows.len()
+            );
+            assert_eq!(src_rows.len(), dst_rows.len());
+
+            let get_key = |row_data: &RowData| {
+                let after = row_data.after.as_ref().unwrap();
+                tb_meta
+                    .basic
+                    .id_cols
+                    .iter()
+                    .map(|col| after.get(col).and_then(|v| v.to_option_string()))
+                    .collect::<Vec<Option<String>>>()
+            };
+            let src_rows: HashMap<_, _> = src_rows.iter().map(|i| (get_key(i), i)).collect();
+            for dst_row in dst_rows.iter() {
+                let src_row = src_rows.get(&get_key(dst_row)).unwrap();
+                let src_after = src_row.after.as_ref().unwrap();
+                // fields missing in the document are NULL in target
+                for (col, dst_value) in dst_row.after.as_ref().unwrap() {
+                    let src_value = src_after.get(col).and_then(|v| v.to_option_string());
+                    assert_eq!(src_value, dst_value.to_option_string(), "col: {}", col);
+                }
+            }
+        }
+        Ok(())
+    }
+}
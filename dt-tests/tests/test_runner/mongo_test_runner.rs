@@ -3,12 +3,15 @@ use std::collections::HashMap;
 use dt_common::meta::mongo::{mongo_constant::MongoConstants, mongo_key::MongoKey};
 use dt_common::{
     config::{
-        config_enums::DbType, extractor_config::ExtractorConfig, sinker_config::SinkerConfig,
-        task_config::TaskConfig,
+        config_enums::DbType, extractor_config::ExtractorConfig, filter_config::FilterConfig,
+        sinker_config::SinkerConfig, task_config::TaskConfig,
     },
+    rdb_filter::RdbFilter,
     utils::time_util::TimeUtil,
 };
-use dt_connector::rdb_router::RdbRouter;
+use dt_connector::{
+    meta_fetcher::mongo::mongo_struct_fetcher::MongoStructFetcher, rdb_router::RdbRouter,
+};
 use dt_task::task_util::TaskUtil;
 use mongodb::{
     bson::{doc, Document},
@@ -24,7 +27,7 @@ use super::base_test_runner::BaseTestRunner;
 
 pub struct MongoTestRunner {
     pub base: BaseTestRunner,
-    src_mongo_client: Option<Client>,
+    pub src_mongo_client: Option<Client>,
     dst_mongo_client: Option<Client>,
     router: RdbRouter,
 }
@@ -44,7 +47,8 @@ impl MongoTestRunner {
         match config.extractor {
             ExtractorConfig::MongoSnapshot { url, app_name, .. }
             | ExtractorConfig::MongoCdc { url, app_name, .. }
-            | ExtractorConfig::MongoCheck { url, app_name, .. } => {
+            | ExtractorConfig::MongoCheck { url, app_name, .. }
+            | ExtractorConfig::MongoStruct { url, app_name, .. } => {
                 src_mongo_client = Some(
                     TaskUtil::create_mongo_client(&url, &app_name)
                         .await
@@ -56,7 +60,8 @@ impl MongoTestRunner {
 
         match config.sinker {
             SinkerConfig::Mongo { url, app_name, .. }
-            | SinkerConfig::MongoCheck { url, app_name, .. } => {
+            | SinkerConfig::MongoCheck { url, app_name, .. }
+            | SinkerConfig::MongoStruct { url, app_name, .. } => {
                 dst_mongo_client = Some(
                     TaskUtil::create_mongo_client(&url, &app_name)
                         .await
@@ -188,6 +193,78 @@ impl MongoTestRunner {
         Ok(())
     }
 
+    /// compare collection options and index definitions of src and dst,
+    /// dst should contain exactly the structures not filtered by do_structures
+    pub async fn run_struct_test(&self) -> anyhow::Result<()> {
+        self.execute_prepare_sqls().await?;
+        self.base.start_task().await?;
+
+        let config = TaskConfig::new(&self.base.task_config_file).unwrap();
+        let filter = RdbFilter::from_config(&config.filter, &DbType::Mongo)?;
+        let all_filter = Self::all_structures_filter()?;
+
+        let src_sqls = Self::slice_sqls_by_db(&self.base.src_prepare_sqls);
+        for (db, _) in src_sqls.iter() {
+            let mut src_fetcher = MongoStructFetcher {
+                mongo_client: self.src_mongo_client.clone().unwrap(),
+                db: db.clone(),
+                filter: Some(filter.clone()),
+            };
+
+            for mut src_statement in src_fetcher.get_create_collection_statements("").await? {
+                let (dst_db, dst_tb) = self.router.get_tb_map(db, &src_statement.tb);
+                src_statement.route(&dst_db, &dst_tb);
+
+                let mut dst_fetcher = MongoStructFetcher {
+                    mongo_client: self.dst_mongo_client.clone().unwrap(),
+                    db: dst_db,
+                    filter: None,
+                };
+                let dst_statements = dst_fetcher
+                    .get_create_collection_statements(&dst_tb)
+                    .await?;
+                assert_eq!(dst_statements.len(), 1);
+
+                let expect_sqls: HashMap<String, String> =
+                    src_statement.to_sqls(&filter)?.into_iter().collect();
+                let dst_sqls: HashMap<String, String> = dst_statements[0]
+                    .to_sqls(&all_filter)?
+                    .into_iter()
+                    .collect();
+                println!("expect_sqls: {:?}\n", expect_sqls);
+                println!("dst_sqls: {:?}\n", dst_sqls);
+                assert_eq!(expect_sqls, dst_sqls);
+            }
+        }
+        Ok(())
+    }
+
+    /// execute src_test.sqls one by one with ddls mixed in, then compare collections,
+    /// indexes and data of src and dst
+    pub async fn run_ddl_test(&self, start_millis: u64, parse_millis: u64) -> anyhow::Result<()> {
+        self.execute_prepare_sqls().await?;
+
+        let task = self.base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        let src_mongo_client = self.src_mongo_client.as_ref().unwrap();
+        let src_sqls = Self::slice_sqls_by_db(&self.base.src_test_sqls);
+        for (db, sqls) in src_sqls.iter() {
+            for sql in sqls.iter() {
+                let sql = vec![sql.clone()];
+                self.execute_ddls(src_mongo_client, db, &sql).await?;
+                self.execute_dmls(src_mongo_client, db, &sql).await?;
+            }
+        }
+        TimeUtil::sleep_millis(parse_millis).await;
+
+        for (db, _) in src_sqls.iter() {
+            self.compare_db_struct(db).await?;
+            self.compare_db_data(db).await;
+        }
+        self.base.abort_task(&task).await
+    }
+
     pub async fn run_heartbeat_test(
         &self,
         start_millis: u64,
@@ -251,9 +328,16 @@ impl MongoTestRunner {
         Ok(())
     }
 
-    async fn execute_ddls(&self, client: &Client, db: &str, sqls: &[String]) -> anyhow::Result<()> {
+    pub async fn execute_ddls(
+        &self,
+        client: &Client,
+        db: &str,
+        sqls: &[String],
+    ) -> anyhow::Result<()> {
         for sql in sqls.iter() {
-            if sql.contains("dropDatabase") {
+            if sql.contains("runCommand") {
+                self.execute_command(client, db, sql).await.unwrap();
+            } else if sql.contains("dropDatabase") {
                 self.execute_drop_database(client, db).await.unwrap();
             } else if sql.contains("drop") {
                 self.execute_drop(client, db, sql).await.unwrap();
@@ -264,14 +348,25 @@ impl MongoTestRunner {
         Ok(())
     }
 
-    async fn execute_dmls(&self, client: &Client, db: &str, sqls: &[String]) -> anyhow::Result<()> {
+    pub async fn execute_dmls(
+        &self,
+        client: &Client,
+        db: &str,
+        sqls: &[String],
+    ) -> anyhow::Result<()> {
         for sql in sqls.iter() {
+            if sql.contains("runCommand") {
+                continue;
+            }
             if sql.contains("insert") {
                 self.execute_insert(client, db, sql).await?;
             }
             if sql.contains("update") {
                 self.execute_update(client, db, sql).await?;
             }
+            if sql.contains("replaceOne") {
+                self.execute_replace(client, db, sql).await?;
+            }
             if sql.contains("delete") {
                 self.execute_delete(client, db, sql).await?;
             }
@@ -317,6 +412,29 @@ impl MongoTestRunner {
         Ok(())
     }
 
+    async fn execute_command(&self, client: &Client, db: &str, sql: &str) -> anyhow::Result<()> {
+        // example: db.runCommand({ "createIndexes": "tb_1", "indexes": [{ "key": { "a": 1 }, "name": "a_1" }] })
+        let re = Regex::new(r"db.runCommand\(([\w\W]+)\)").unwrap();
+        let cap = re.captures(sql).unwrap();
+        let command: Document = serde_json::from_str(cap.get(1).unwrap().as_str()).unwrap();
+        // renameCollection and sharding commands can only be executed in admin
+        let db = if ["renameCollection", "enableSharding", "shardCollection"]
+            .iter()
+            .any(|i| command.contains_key(i))
+        {
+            "admin"
+        } else {
+            db
+        };
+
+        client
+            .database(db)
+            .run_command(command, None)
+            .await
+            .unwrap();
+        Ok(())
+    }
+
     async fn execute_insert(&self, client: &Client, db: &str, sql: &str) -> anyhow::Result<()> {
         // example: db.tb_2.insertOne({ "name": "a", "age": "1" })
         let re = Regex::new(r"db.(\w+).insert(One|Many)\(([\w\W]+)\)").unwrap();
@@ -352,14 +470,11 @@ impl MongoTestRunner {
     }
 
     async fn execute_update(&self, client: &Client, db: &str, sql: &str) -> anyhow::Result<()> {
-        let re = Regex::new(r"db.(\w+).update(One|Many)\(([\w\W]+),([\w\W]+)\)").unwrap();
+        let re = Regex::new(r"db.(\w+).update(One|Many)\(([\w\W]+)\)").unwrap();
         let cap = re.captures(sql).unwrap();
         let tb = cap.get(1).unwrap().as_str();
-        let query_doc = cap.get(3).unwrap().as_str();
-        let update_doc = cap.get(4).unwrap().as_str();
+        let (query_doc, update_doc) = Self::split_docs(cap.get(3).unwrap().as_str());
 
-        let query_doc: Document = serde_json::from_str(query_doc).unwrap();
-        let update_doc: Document = serde_json::from_str(update_doc).unwrap();
         let coll = client.database(db).collection::<Document>(tb);
         if sql.contains("updateOne") {
             coll.update_one(query_doc, update_doc, None).await.unwrap();
@@ -369,6 +484,78 @@ impl MongoTestRunner {
         Ok(())
     }
 
+    async fn execute_replace(&self, client: &Client, db: &str, sql: &str) -> anyhow::Result<()> {
+        // example: db.tb_1.replaceOne({ "_id": 1 }, { "name": "a", "age": "1" })
+        let re = Regex::new(r"db.(\w+).replaceOne\(([\w\W]+)\)").unwrap();
+        let cap = re.captures(sql).unwrap();
+        let tb = cap.get(1).unwrap().as_str();
+        let (query_doc, replacement) = Self::split_docs(cap.get(2).unwrap().as_str());
+
+        let coll = client.database(db).collection::<Document>(tb);
+        coll.replace_one(query_doc, replacement, None)
+            .await
+            .unwrap();
+        Ok(())
+    }
+
+    /// split "{ ... }, { ... }" into 2 documents, both of them may contain ","
+    fn split_docs(content: &str) -> (Document, Document) {
+        for (i, _) in content.match_indices(',') {
+            if let (Ok(first), Ok(second)) = (
+                serde_json::from_str::<Document>(&content[..i]),
+                serde_json::from_str::<Document>(&content[i + 1..]),
+            ) {
+                return (first, second);
+            }
+        }
+        panic!("invalid documents: {}", content)
+    }
+
+    async fn compare_db_struct(&self, db: &str) -> anyhow::Result<()> {
+        let all_filter = Self::all_structures_filter()?;
+
+        let mut src_fetcher = MongoStructFetcher {
+            mongo_client: self.src_mongo_client.clone().unwrap(),
+            db: db.into(),
+            filter: None,
+        };
+        let mut dst_fetcher = MongoStructFetcher {
+            mongo_client: self.dst_mongo_client.clone().unwrap(),
+            db: self.router.get_schema_map(db).into(),
+            filter: None,
+        };
+        let src_statements = src_fetcher.get_create_collection_statements("").await?;
+        let dst_statements = dst_fetcher.get_create_collection_statements("").await?;
+        assert_eq!(src_statements.len(), dst_statements.len());
+
+        let mut dst_sqls = HashMap::new();
+        for dst_statement in dst_statements.iter() {
+            dst_sqls.extend(dst_statement.to_sqls(&all_filter)?);
+        }
+
+        let mut expect_sqls = HashMap::new();
+        for mut src_statement in src_statements {
+            let (dst_db, dst_tb) = self.router.get_tb_map(db, &src_statement.tb);
+            src_statement.route(&dst_db, &dst_tb);
+            expect_sqls.extend(src_statement.to_sqls(&all_filter)?);
+        }
+        println!(
+            "compare db struct, db: {}, expect_sqls: {:?}",
+            db, expect_sqls
+        );
+        println!("compare db struct, db: {}, dst_sqls: {:?}", db, dst_sqls);
+        assert_eq!(expect_sqls, dst_sqls);
+        Ok(())
+    }
+
+    fn all_structures_filter() -> anyhow::Result<RdbFilter> {
+        let config = FilterConfig {
+            do_structures: "*".into(),
+            ..Default::default()
+        };
+        RdbFilter::from_config(&config, &DbType::Mongo)
+    }
+
     async fn compare_db_data(&self, db: &str) {
         let tbs = self.list_tb(db, SRC).await;
         for tb in tbs.iter() {
@@ -381,7 +568,7 @@ impl MongoTestRunner {
         let src_data = self.fetch_data(db, tb, SRC).await;
 
         let (dst_db, dst_tb) = self.router.get_tb_map(db, tb);
-        let dst_data = self.fetch_data(dst_db, dst_tb, DST).await;
+        let dst_data = self.fetch_data(&dst_db, &dst_tb, DST).await;
 
         assert_eq!(src_data.len(), dst_data.len());
         for id in src_data.keys() {
@@ -430,7 +617,7 @@ impl MongoTestRunner {
         results
     }
 
-    fn slice_sqls_by_db(sqls: &[String]) -> HashMap<String, Vec<String>> {
+    pub fn slice_sqls_by_db(sqls: &[String]) -> HashMap<String, Vec<String>> {
         let mut db = String::new();
         let mut sliced_sqls: HashMap<String, Vec<String>> = HashMap::new();
         for sql in sqls.iter() {
@@ -448,7 +635,7 @@ impl MongoTestRunner {
         sliced_sqls
     }
 
-    fn slice_sqls_by_type(sqls: &[String]) -> (Vec<String>, Vec<String>, Vec<String>) {
+    pub fn slice_sqls_by_type(sqls: &[String]) -> (Vec<String>, Vec<String>, Vec<String>) {
         let mut insert_sqls = Vec::new();
         let mut update_sqls = Vec::new();
         let mut delete_sqls = Vec::new();
@@ -456,7 +643,7 @@ impl MongoTestRunner {
             if sql.contains("insert") {
                 insert_sqls.push(sql.clone());
             }
-            if sql.contains("update") {
+            if sql.contains("update") || sql.contains("replaceOne") {
                 update_sqls.push(sql.clone());
             }
             if sql.contains("delete") {
@@ -113,6 +113,23 @@ impl RdbClickHouseTestRunner {
         basic.wait_task_finish(&task).await
     }
 
+    /// src_test.sql may contain ddls, all sqls are executed in order before comparing
+    pub async fn run_ddl_test<'a, T: Row + Serialize + for<'b> Deserialize<'b>>(
+        &self,
+        start_millis: u64,
+        parse_millis: u64,
+    ) -> anyhow::Result<()> {
+        let runner = &self.rdb_test_runner;
+        self.prepare_task().await?;
+
+        let task = runner.spawn_cdc_task(start_millis, parse_millis).await?;
+        runner.execute_src_sqls(&runner.base.src_test_sqls).await?;
+        TimeUtil::sleep_millis(parse_millis).await;
+        self.compare_data_for_tbs::<T>().await?;
+
+        runner.base.wait_task_finish(&task).await
+    }
+
     async fn prepare_task(&self) -> anyhow::Result<()> {
         let basic = &self.rdb_test_runner.base;
         self.rdb_test_runner
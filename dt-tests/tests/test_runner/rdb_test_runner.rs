@@ -680,7 +680,7 @@ impl RdbTestRunner {
         let mut dst_db_tbs = vec![];
         for (db, tb) in src_db_tbs.iter() {
             let (dst_db, dst_tb) = self.router.get_tb_map(db, tb);
-            dst_db_tbs.push((dst_db.into(), dst_tb.into()));
+            dst_db_tbs.push((dst_db, dst_tb));
         }
 
         Ok((src_db_tbs, dst_db_tbs))
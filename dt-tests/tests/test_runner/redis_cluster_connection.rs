@@ -50,6 +50,13 @@ impl RedisClusterConnection {
         &mut self.default_conn
     }
 
+    pub fn get_all_node_conns(&mut self) -> Vec<&mut Connection> {
+        if self.node_conn_map.is_empty() {
+            return vec![self.get_default_conn()];
+        }
+        self.node_conn_map.values_mut().collect()
+    }
+
     pub fn get_node_conn_by_key(&mut self, key: &str) -> &mut Connection {
         if self.slot_node_map.is_empty() {
             return self.get_default_conn();
@@ -7,13 +7,13 @@ use futures::executor::block_on;
 use crate::test_runner::rdb_test_runner::DST;
 
 use super::{
-    mongo_check_test_runner::MongoCheckTestRunner, mongo_test_runner::MongoTestRunner,
-    precheck_test_runner::PrecheckTestRunner, rdb_check_test_runner::RdbCheckTestRunner,
-    rdb_kafka_rdb_test_runner::RdbKafkaRdbTestRunner, rdb_lua_test_runner::RdbLuaTestRunner,
-    rdb_redis_test_runner::RdbRedisTestRunner, rdb_sql_test_runner::RdbSqlTestRunner,
-    rdb_starrocks_test_runner::RdbStarRocksTestRunner, rdb_struct_test_runner::RdbStructTestRunner,
-    rdb_test_runner::RdbTestRunner, redis_statistic_runner::RedisStatisticTestRunner,
-    redis_test_runner::RedisTestRunner,
+    mongo_check_test_runner::MongoCheckTestRunner, mongo_rdb_test_runner::MongoRdbTestRunner,
+    mongo_test_runner::MongoTestRunner, precheck_test_runner::PrecheckTestRunner,
+    rdb_check_test_runner::RdbCheckTestRunner, rdb_kafka_rdb_test_runner::RdbKafkaRdbTestRunner,
+    rdb_lua_test_runner::RdbLuaTestRunner, rdb_redis_test_runner::RdbRedisTestRunner,
+    rdb_sql_test_runner::RdbSqlTestRunner, rdb_starrocks_test_runner::RdbStarRocksTestRunner,
+    rdb_struct_test_runner::RdbStructTestRunner, rdb_test_runner::RdbTestRunner,
+    redis_statistic_runner::RedisStatisticTestRunner, redis_test_runner::RedisTestRunner,
 };
 
 pub struct TestBase {}
@@ -138,6 +138,11 @@ impl TestBase {
         runner.run_snapshot_test(true).await.unwrap();
     }
 
+    pub async fn run_mongo_struct_test(test_dir: &str) {
+        let runner = MongoTestRunner::new(test_dir).await.unwrap();
+        runner.run_struct_test().await.unwrap();
+    }
+
     pub async fn run_mongo_snapshot_test_and_check_dst_count(
         test_dir: &str,
         dst_expected_counts: HashMap<(&str, &str), usize>,
@@ -155,6 +160,21 @@ impl TestBase {
         }
     }
 
+    pub async fn run_mongo_rdb_snapshot_test(test_dir: &str) {
+        let runner = MongoRdbTestRunner::new(test_dir).await.unwrap();
+        runner.run_snapshot_test().await.unwrap();
+        runner.close().await.unwrap();
+    }
+
+    pub async fn run_mongo_rdb_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let runner = MongoRdbTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
     pub async fn run_mongo_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
         let runner = MongoTestRunner::new(test_dir).await.unwrap();
         runner
@@ -163,6 +183,14 @@ impl TestBase {
             .unwrap();
     }
 
+    pub async fn run_mongo_ddl_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let runner = MongoTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_ddl_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+    }
+
     pub async fn run_mongo_cdc_resume_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
         let runner = MongoTestRunner::new(test_dir).await.unwrap();
         runner
@@ -213,6 +241,21 @@ impl TestBase {
         runner.run_snapshot_test().await.unwrap();
     }
 
+    pub async fn run_redis_check_test(test_dir: &str) {
+        let mut runner = RedisTestRunner::new_default(test_dir).await.unwrap();
+        runner.run_check_test().await.unwrap();
+    }
+
+    pub async fn run_redis_revise_test(test_dir: &str) {
+        let mut runner = RedisTestRunner::new_default(test_dir).await.unwrap();
+        runner.run_revise_test().await.unwrap();
+    }
+
+    pub async fn run_redis_review_test(test_dir: &str) {
+        let mut runner = RedisTestRunner::new_default(test_dir).await.unwrap();
+        runner.run_check_test().await.unwrap();
+    }
+
     pub async fn run_redis_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
         let mut runner = RedisTestRunner::new_default(test_dir).await.unwrap();
         runner